@@ -20,7 +20,7 @@ fn make_random_quant_model(hidden_dim: usize) -> QuantNnue {
     for _ in 0..hidden_dim { w2.push(next_i8()); }
     let b2 = vec![0i16; 1];
     QuantNnue {
-        meta: QuantMeta { version: 1, input_dim, hidden_dim, output_dim: 1 },
+        meta: QuantMeta { version: 1, input_dim, hidden_dim, output_dim: 1, output_scale: 1.0 },
         w1_scale: 1.0,
         w2_scale: 1.0,
         w1,
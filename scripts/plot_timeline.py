@@ -0,0 +1,107 @@
+#!/usr/bin/env python3
+"""Plot search timelines written by `compare_play --timeline` or the UCI
+`TimelineDir` option: depth, score, time and nodes against move number.
+
+Each engine label gets one line per metric, averaged over all games given, so a
+base/exp match shows at a glance where time use or search depth diverges.
+Pass --per-game to draw every game separately instead, and --normalized to plot
+the expected score from each point's `wdl` (written by compare_play with each
+engine's WDL model) instead of centipawns, so engines with different evals share
+one scale.
+
+    python3 scripts/plot_timeline.py timelines/ -o timeline.png
+"""
+import argparse
+import json
+import sys
+from collections import defaultdict
+from pathlib import Path
+
+SUPPORTED_VERSION = 1
+METRICS = [("depth", "depth"), ("score_cp", "score (cp, side to move)"), ("time_ms", "time (ms)"), ("nodes", "nodes")]
+NORMALIZED = ("expected", "expected score (side to move)")
+
+
+def expected(pt):
+    # W + D/2 from the per-mille triple; points from older files have none
+    wdl = pt.get("wdl")
+    return None if wdl is None else (wdl[0] + wdl[1] / 2) / 1000
+
+
+def load(paths):
+    games = []
+    for p in map(Path, paths):
+        files = sorted(p.glob("*.json")) if p.is_dir() else [p]
+        for f in files:
+            doc = json.loads(f.read_text())
+            if doc.get("version") != SUPPORTED_VERSION:
+                sys.exit(f"{f}: unsupported timeline version {doc.get('version')}")
+            doc["_name"] = f.stem
+            games.append(doc)
+    return games
+
+
+def series(games, per_game, metrics):
+    # (label, metric) -> {move_number: [values]}
+    out = defaultdict(lambda: defaultdict(list))
+    for g in games:
+        for pt in g["points"]:
+            label = f"{g['_name']}:{pt['engine']}" if per_game else pt["engine"]
+            for key, _ in metrics:
+                v = expected(pt) if key == NORMALIZED[0] else pt[key]
+                if v is not None:
+                    out[(label, key)][pt["move_number"]].append(v)
+    return out
+
+
+def main():
+    ap = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    ap.add_argument("paths", nargs="+", help="timeline JSON files or directories of them")
+    ap.add_argument("-o", "--out", help="write an image instead of opening a window")
+    ap.add_argument("--per-game", action="store_true", help="one line per game and engine instead of averages")
+    ap.add_argument("--log-nodes", action="store_true", help="log scale for the nodes panel")
+    ap.add_argument("--normalized", action="store_true", help="plot expected score from the WDL fields instead of cp")
+    args = ap.parse_args()
+
+    games = load(args.paths)
+    if not games:
+        sys.exit("no timelines found")
+    try:
+        import matplotlib
+        if args.out:
+            matplotlib.use("Agg")
+        import matplotlib.pyplot as plt
+    except ImportError:
+        sys.exit("matplotlib is required: pip install matplotlib")
+
+    metrics = [NORMALIZED if k == "score_cp" and args.normalized else (k, t) for k, t in METRICS]
+    data = series(games, args.per_game, metrics)
+    if args.normalized and not any(key == NORMALIZED[0] for _, key in data):
+        sys.exit("no wdl fields in these timelines (write them with compare_play --timeline)")
+    labels = sorted({label for label, _ in data})
+    fig, axes = plt.subplots(len(metrics), 1, sharex=True, figsize=(10, 2.6 * len(metrics)))
+    for ax, (key, title) in zip(axes, metrics):
+        for label in labels:
+            pts = data.get((label, key))
+            if not pts:
+                continue
+            xs = sorted(pts)
+            ys = [sum(pts[x]) / len(pts[x]) for x in xs]
+            ax.plot(xs, ys, label=label, marker="." if len(xs) < 60 else None)
+        ax.set_ylabel(title)
+        ax.grid(alpha=0.3)
+    if args.log_nodes:
+        axes[-1].set_yscale("log")
+    axes[-1].set_xlabel("move number")
+    axes[0].legend(loc="best", fontsize="small")
+    fig.suptitle(f"{len(games)} game(s)" + ("" if args.per_game else ", averaged per move"))
+    fig.tight_layout()
+    if args.out:
+        fig.savefig(args.out, dpi=120)
+        print(f"wrote {args.out}")
+    else:
+        plt.show()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,95 @@
+use clap::Parser;
+use cozy_chess::{Board, Move};
+use piebot::search::alphabeta::{InfoHook, RootIteration, RootMoveScore, Searcher, SearchParams};
+use piebot::search::tt::Bound;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-analyze", about = "Analyze one position, optionally showing how every root move's score evolves by depth")]
+struct Args {
+    /// Position to analyze (default: start position)
+    #[arg(long)]
+    fen: Option<String>,
+    #[arg(long, default_value_t = 8)]
+    depth: u32,
+    /// Optional movetime in milliseconds
+    #[arg(long)]
+    movetime: Option<u64>,
+    /// Print the per-iteration root move score matrix
+    #[arg(long, default_value_t = false)]
+    root_table: bool,
+    /// Search every root move with a full window so all table entries are exact (slower)
+    #[arg(long, default_value_t = false)]
+    full_window: bool,
+    #[arg(long, default_value_t = 64)]
+    hash_mb: usize,
+    /// Disable aspiration windows (default on)
+    #[arg(long, default_value_t = false)]
+    no_aspiration: bool,
+}
+
+// Rows: moves ordered by their last-iteration score; cells: cp, "<=cp" for fail-low bounds
+fn print_root_table(table: &[RootIteration]) {
+    let Some(last) = table.last() else { return; };
+    let mut moves: Vec<(Move, i32)> = last.scores.iter().map(|s| (s.mv, s.score_cp)).collect();
+    moves.sort_by_key(|&(_, s)| -s);
+    print!("{:<8}", "move");
+    for it in table { print!("{:>8}", format!("d{}", it.depth)); }
+    println!();
+    for (m, _) in moves {
+        print!("{:<8}", format!("{}", m));
+        for it in table {
+            let cell = match it.scores.iter().find(|s| s.mv == m) {
+                Some(s) if s.bound == Bound::Upper => format!("<={}", s.score_cp),
+                Some(s) if s.bound == Bound::Lower => format!(">={}", s.score_cp),
+                Some(s) => format!("{}", s.score_cp),
+                None => "-".to_string(),
+            };
+            print!("{:>8}", cell);
+        }
+        println!();
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let board = match &args.fen {
+        Some(f) => Board::from_fen(f, false).unwrap_or_else(|e| { eprintln!("bad fen: {:?}", e); std::process::exit(2); }),
+        None => Board::default(),
+    };
+    let params = SearchParams {
+        depth: args.depth,
+        movetime: args.movetime.map(Duration::from_millis),
+        use_tt: true,
+        order_captures: true,
+        use_history: true,
+        use_killers: true,
+        use_lmr: true,
+        use_nullmove: true,
+        use_aspiration: !args.no_aspiration && !args.full_window,
+        ..Default::default()
+    };
+    let mut searcher = Searcher::builder().hash_mb(args.hash_mb).track_root_scores(args.root_table).build()
+        .unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+    let hook: InfoHook = Arc::new(|r| {
+        println!("depth {:>2} seldepth {:>2} score {:>6} nodes {:>10} time {:>6}ms best {}", r.depth, r.seldepth, r.score.value(), r.nodes, r.time_ms, r.bestmove.as_deref().unwrap_or("-"));
+    });
+    searcher.set_info_hook(Some(hook));
+    if args.full_window {
+        // Score each root move on its own at every depth: exact values, one search per move
+        let mut table: Vec<RootIteration> = Vec::new();
+        for d in 1..=args.depth.max(1) {
+            let scored = searcher.score_root_moves(&board, SearchParams { depth: d, ..params });
+            table.push(RootIteration {
+                depth: d,
+                scores: scored.into_iter().map(|(mv, score_cp)| RootMoveScore { mv, score_cp, bound: Bound::Exact }).collect(),
+            });
+        }
+        print_root_table(&table);
+        return;
+    }
+    let res = searcher.search_with_params(&board, params);
+    println!("bestmove {} score {} depth {} nodes {} nps {}", res.bestmove.as_deref().unwrap_or("0000"), res.score.value(), res.depth, res.nodes, res.nps);
+    if args.root_table { print_root_table(searcher.root_score_table()); }
+}
@@ -0,0 +1,117 @@
+use clap::Parser;
+use cozy_chess::{Board, Color, Move};
+use piebot::io::pgn::{move_to_san, parse_pgn};
+use piebot::search::alphabeta::{AnalysisStyle, Searcher, SearchParams};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-annotate", about = "Annotate PGN games with the engine's top moves and mistake marks")]
+struct Args {
+    /// PGN file to annotate
+    #[arg(long)]
+    pgn: PathBuf,
+    /// Search depth per line
+    #[arg(long, default_value_t = 8)]
+    depth: u32,
+    /// Optional movetime per line in milliseconds
+    #[arg(long)]
+    movetime: Option<u64>,
+    /// Number of candidate moves listed per position
+    #[arg(long, default_value_t = 3)]
+    lines: usize,
+    /// MultiPV style: exclusion (exact scores) or scored (one ply shallower, cheaper)
+    #[arg(long, default_value = "exclusion")]
+    style: AnalysisStyle,
+    /// Leave this many opening plies unannotated
+    #[arg(long, default_value_t = 0)]
+    skip_plies: usize,
+    /// Loss (cp) against the best line that earns ?!, ? and ??
+    #[arg(long, default_value_t = 50)]
+    inaccuracy_cp: i32,
+    #[arg(long, default_value_t = 100)]
+    mistake_cp: i32,
+    #[arg(long, default_value_t = 300)]
+    blunder_cp: i32,
+    #[arg(long, default_value_t = 64)]
+    hash_mb: usize,
+}
+
+// Pawns from white's point of view, as GUIs show them
+fn pawns(cp: i32, stm: Color) -> String {
+    let white = if stm == Color::White { cp } else { -cp };
+    format!("{:+.2}", white as f64 / 100.0)
+}
+
+fn find_move(board: &Board, uci: &str) -> Option<Move> {
+    let mut found = None;
+    board.generate_moves(|ml| { for m in ml { if format!("{}", m) == uci { found = Some(m); break; } } found.is_some() });
+    found
+}
+
+fn main() {
+    let args = Args::parse();
+    let text = std::fs::read_to_string(&args.pgn).unwrap_or_else(|e| { eprintln!("read {}: {}", args.pgn.display(), e); std::process::exit(2); });
+    let params = SearchParams {
+        depth: args.depth,
+        movetime: args.movetime.map(Duration::from_millis),
+        use_tt: true,
+        order_captures: true,
+        use_history: true,
+        use_killers: true,
+        use_lmr: true,
+        use_nullmove: true,
+        ..Default::default()
+    };
+    let mut searcher = Searcher::builder().hash_mb(args.hash_mb).build().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+    for (gi, game) in parse_pgn(&text).into_iter().enumerate() {
+        eprintln!("game {}: {} plies", gi + 1, game.moves.len());
+        searcher.new_game();
+        for (k, v) in &game.tags { println!("[{} \"{}\"]", k, v); }
+        println!();
+        let mut tokens: Vec<String> = Vec::new();
+        let mut board = game.start.clone();
+        for (ply, &m) in game.moves.iter().enumerate() {
+            let stm = board.side_to_move();
+            if stm == Color::White { tokens.push(format!("{}.", board.fullmove_number())); } else if ply == 0 { tokens.push(format!("{}...", board.fullmove_number())); }
+            let san = move_to_san(&board, m);
+            if ply < args.skip_plies {
+                tokens.push(san);
+                board.play(m);
+                continue;
+            }
+            searcher.new_search();
+            let lines = searcher.search_multipv(&board, params, args.lines.max(1), args.style);
+            let played = format!("{}", m);
+            let played_cp = match lines.iter().find(|r| r.bestmove.as_deref() == Some(played.as_str())) {
+                Some(r) => r.score.value(),
+                None => {
+                    // Outside the top lines: score it exactly with a searchmoves restriction
+                    searcher.set_search_moves(Some(vec![m]));
+                    let r = searcher.search_with_params(&board, params);
+                    searcher.set_search_moves(None);
+                    r.score.value()
+                }
+            };
+            let best_cp = lines.first().map_or(played_cp, |r| r.score.value());
+            let loss = best_cp - played_cp;
+            let nag = if loss >= args.blunder_cp { "??" } else if loss >= args.mistake_cp { "?" } else if loss >= args.inaccuracy_cp { "?!" } else { "" };
+            tokens.push(format!("{}{}", san, nag));
+            let alts: Vec<String> = lines.iter().filter_map(|r| {
+                let alt = find_move(&board, r.bestmove.as_deref()?)?;
+                Some(format!("{} {}", move_to_san(&board, alt), pawns(r.score.value(), stm)))
+            }).collect();
+            tokens.push(format!("{{ {} | {} }}", pawns(played_cp, stm), alts.join(", ")));
+            board.play(m);
+        }
+        tokens.push(game.tag("Result").unwrap_or("*").to_string());
+        // Wrap movetext at ~80 columns
+        let mut line = String::new();
+        for t in tokens {
+            if !line.is_empty() && line.len() + 1 + t.len() > 80 { println!("{}", line); line.clear(); }
+            if !line.is_empty() { line.push(' '); }
+            line.push_str(&t);
+        }
+        println!("{}\n", line);
+    }
+}
@@ -1,7 +1,19 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use cozy_chess::Board;
-use piebot::search::alphabeta::{Searcher, SearchParams};
-use std::time::{Duration, Instant};
+use piebot::io::fen::{load_fen_suite, BENCH_SUITE};
+use piebot::search::affinity::{self, CorePinning};
+use piebot::search::options::{EngineOptions, EvalKind};
+use piebot::search::timeman::{Calibration, DEFAULT_MOVETIME_MS};
+use piebot::search::ttd::{DepthCurve, TtdSummary};
+use piebot::search::tt::suggested_hash_mb;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Preset {
+    /// All cores, NNUE if a network is given, hash sized to --movetime; reports the
+    /// SIMD level, NPS scaling over 1, 2, 4, 8.. threads and hash advice per time control
+    NnueSmp,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "piebot-bench", version, about = "Benchmark PieBot search NPS with PST/NNUE")]
@@ -10,18 +22,30 @@ struct Args {
     #[arg(long, default_value = "startpos")]
     fen: String,
 
-    /// Threads
-    #[arg(long, default_value_t = 1)]
-    threads: usize,
+    /// EngineOptions JSON to start from; the flags below override it
+    #[arg(long)]
+    options: Option<String>,
 
-    /// Movetime in milliseconds (ignored if depth is set)
-    #[arg(long, default_value_t = 1000)]
-    movetime: u64,
+    /// Threads (default: from options)
+    #[arg(long)]
+    threads: Option<usize>,
 
-    /// Fixed search depth (overrides movetime when > 0)
+    /// Pin search threads: off | auto | core list like 0+2+4-7 (default: from options)
+    #[arg(long)]
+    affinity: Option<CorePinning>,
+
+    /// Movetime in milliseconds (default 1000 unless --depth is given)
+    #[arg(long)]
+    movetime: Option<u64>,
+
+    /// Search depth, 0 = none; with --movetime the search stops at whichever comes first
     #[arg(long, default_value_t = 0)]
     depth: u32,
 
+    /// Iterations completed even past the movetime (default: from options)
+    #[arg(long)]
+    min_depth: Option<u32>,
+
     /// Use NNUE (requires NNUE file)
     #[arg(long, default_value_t = false)]
     use_nnue: bool,
@@ -34,51 +58,207 @@ struct Args {
     #[arg(long)]
     nnue_quant_file: Option<String>,
 
-    /// Eval blend percent (0..100). 0=PST, 100=NNUE
-    #[arg(long, default_value_t = 100)]
-    blend: u8,
+    /// Eval blend percent (0..100). 0=PST, 100=NNUE (default: from options)
+    #[arg(long)]
+    blend: Option<u8>,
+
+    /// Transposition table size in MB, approximate (default: from options)
+    #[arg(long)]
+    hash_mb: Option<usize>,
+
+    /// Run the suite once per eval (material, pst, nnue if a file is given) at a fixed
+    /// node budget and report depth/score divergence and best-move disagreement
+    #[arg(long, default_value_t = false)]
+    eval_compare: bool,
+
+    /// Node budget per position for --eval-compare
+    #[arg(long, default_value_t = 200_000)]
+    nodes: u64,
+
+    /// Search every suite position and print its time to depth and effective branching
+    /// factor, then the run's mean time to each depth and EBF
+    #[arg(long, default_value_t = false)]
+    ttd: bool,
+
+    /// Suite file for --eval-compare and --ttd (FEN per line or JSONL {"fen":...}); default: built-in suite
+    #[arg(long)]
+    suite: Option<String>,
+
+    /// Hardware assessment preset (overrides --threads and --hash-mb)
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+}
+
+// Global options with this run's flags applied
+fn engine_options(args: &Args) -> EngineOptions {
+    if let Some(path) = &args.options {
+        match EngineOptions::load_json(path) {
+            Ok(o) => EngineOptions::set_global(o),
+            Err(e) => { eprintln!("--options {}: {}", path, e); std::process::exit(2); }
+        }
+    }
+    let mut o = EngineOptions::global();
+    if let Some(t) = args.threads { o.threads = t.max(1); }
+    if let Some(a) = &args.affinity { o.affinity = a.clone(); }
+    if let Some(h) = args.hash_mb { o.hash_mb = h; }
+    if let Some(b) = args.blend { o.eval_blend = b.min(100); }
+    if args.nnue_file.is_some() { o.nnue_file = args.nnue_file.clone(); }
+    if args.nnue_quant_file.is_some() { o.nnue_quant_file = args.nnue_quant_file.clone(); }
+    if args.use_nnue { o.eval = EvalKind::Nnue; }
+    o.depth = args.depth;
+    o.movetime_ms = args.movetime.or(if args.depth > 0 { None } else { Some(DEFAULT_MOVETIME_MS) });
+    if let Some(d) = args.min_depth { o.min_depth = d; }
+    o
+}
+
+struct EvalRun { best: Option<String>, score: i32, depth: u32 }
+
+fn suite_boards(args: &Args) -> Vec<Board> {
+    let fens: Vec<String> = match &args.suite {
+        Some(path) => load_fen_suite(path),
+        None => BENCH_SUITE.iter().map(|s| s.to_string()).collect(),
+    };
+    fens.iter().filter_map(|f| Board::from_fen(f, false).ok()).collect()
+}
+
+fn eval_compare(args: &Args, opts: &EngineOptions) {
+    let boards = suite_boards(args);
+    let mut modes = vec![EvalKind::Material, EvalKind::Pst];
+    if opts.nnue_file.is_some() || opts.nnue_quant_file.is_some() { modes.push(EvalKind::Nnue); } else { eprintln!("no NNUE file given; comparing material vs pst only"); }
+    let p = piebot::search::alphabeta::SearchParams { depth: 0, movetime: None, max_nodes: Some(args.nodes), ..opts.search_params() };
+
+    // runs[mode][position]
+    let mut runs: Vec<Vec<EvalRun>> = Vec::new();
+    for &mode in &modes {
+        let mut s = EngineOptions { eval: mode, ..opts.clone() }.build_searcher().unwrap_or_else(|e| { eprintln!("{}: {}", mode, e); std::process::exit(2); });
+        let mut out = Vec::new();
+        for b in &boards {
+            s.new_game();
+            let r = s.search_with_params(b, p);
+            out.push(EvalRun { best: r.bestmove, score: r.score.value(), depth: r.depth });
+        }
+        let n = out.len().max(1) as f64;
+        println!("{:<9} avg_depth={:.2} avg_score={:+.1}", mode, out.iter().map(|r| r.depth as f64).sum::<f64>() / n, out.iter().map(|r| r.score as f64).sum::<f64>() / n);
+        runs.push(out);
+    }
+    println!("{:<20} {:>10} {:>12} {:>10}", "pair", "bestmove%", "mean|dcp|", "mean ddepth");
+    for i in 0..modes.len() {
+        for j in i + 1..modes.len() {
+            let n = boards.len().max(1) as f64;
+            let disagree = runs[i].iter().zip(&runs[j]).filter(|(a, b)| a.best != b.best).count() as f64;
+            let dcp = runs[i].iter().zip(&runs[j]).map(|(a, b)| (a.score - b.score).abs() as f64).sum::<f64>() / n;
+            let dd = runs[i].iter().zip(&runs[j]).map(|(a, b)| b.depth as f64 - a.depth as f64).sum::<f64>() / n;
+            println!("{:<20} {:>9.1}% {:>12.1} {:>+10.2}", format!("{} vs {}", modes[i], modes[j]), 100.0 * disagree / n, dcp, dd);
+        }
+    }
+}
+
+fn fmt_ebf(e: Option<f64>) -> String { e.map_or("-".to_string(), |e| format!("{:.2}", e)) }
+
+// One line per completed iteration of a search
+fn print_iterations(curve: &DepthCurve) {
+    println!("{:>5} {:>12} {:>10} {:>6}", "depth", "nodes", "time_ms", "ebf");
+    for (i, p) in curve.points.iter().enumerate() {
+        println!("{:>5} {:>12} {:>10} {:>6}", p.depth, p.nodes, p.time_ms, fmt_ebf(curve.step_ebf(i)));
+    }
+}
 
-    /// Transposition table size in MB (approximate)
-    #[arg(long, default_value_t = 64)]
-    hash_mb: usize,
+fn ttd_suite(args: &Args, opts: &EngineOptions) {
+    let boards = suite_boards(args);
+    let mut s = opts.build_searcher().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+    let p = opts.search_params();
+    let pool = affinity::thread_pool(opts.threads, &opts.affinity, opts.stack_mb);
+    println!("{:>4} {:>5} {:>12} {:>10} {:>6}  time to depth (ms)", "pos", "depth", "nodes", "time_ms", "ebf");
+    let mut curves = Vec::new();
+    for (i, b) in boards.iter().enumerate() {
+        s.new_game();
+        let (curve, hook) = DepthCurve::recorder();
+        s.set_info_hook(Some(hook));
+        let r = pool.install(|| s.search_with_params(b, p));
+        let curve = curve.lock().unwrap().clone();
+        let times: Vec<String> = curve.points.iter().map(|p| p.time_ms.to_string()).collect();
+        println!("{:>4} {:>5} {:>12} {:>10} {:>6}  {}", i, r.depth, r.nodes, r.time_ms, fmt_ebf(curve.ebf()), times.join(" "));
+        curves.push(curve);
+    }
+    let sum = TtdSummary::from_curves(&curves);
+    let times: Vec<String> = sum.mean_time_ms.iter().enumerate().map(|(d, t)| format!("d{}={:.0}", d + 1, t)).collect();
+    println!("summary: positions={} ebf={} common_depth={} mean_ttd_ms {}", sum.positions, fmt_ebf(sum.ebf), sum.common_depth, times.join(" "));
+}
+
+// Thread counts for the scaling table: powers of two below `cores`, then `cores`
+fn thread_steps(cores: usize) -> Vec<usize> {
+    let mut v: Vec<usize> = std::iter::successors(Some(1usize), |&t| Some(t * 2)).take_while(|&t| t < cores).collect();
+    v.push(cores);
+    v
+}
+
+fn nnue_smp(args: &Args, mut opts: EngineOptions, board: &Board) {
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if opts.nnue_file.is_some() || opts.nnue_quant_file.is_some() { opts.eval = EvalKind::Nnue; } else { eprintln!("no NNUE file given; benchmarking with {}", opts.eval); }
+    let movetime_ms = args.movetime.unwrap_or(DEFAULT_MOVETIME_MS);
+    let movetime = Duration::from_millis(movetime_ms);
+    // Size the hash for this time control from a quick single-thread probe
+    let probe = Calibration::measure(&opts, Duration::from_millis(100));
+    opts.hash_mb = suggested_hash_mb((probe.nodes_per_ms as f64 * movetime.as_millis() as f64 * cores as f64) as u64);
+    println!("simd={} cores={} eval={} hash={}MB movetime={}ms", piebot::version::simd_level(), cores, opts.eval, opts.hash_mb, movetime_ms);
+    println!("{:>7} {:>12} {:>8} {:>10} {:>6}", "threads", "nps", "speedup", "efficiency", "depth");
+    let mut base = 0.0;
+    let mut top_nps = 0;
+    for t in thread_steps(cores) {
+        let o = EngineOptions { threads: t, depth: 0, movetime_ms: Some(movetime_ms), ..opts.clone() };
+        let mut s = o.build_searcher().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+        let pool = affinity::thread_pool(t, &o.affinity, o.stack_mb);
+        let res = pool.install(|| s.search_with_params(board, o.search_params()));
+        if t == 1 { base = res.nps.max(1) as f64; }
+        let speedup = res.nps as f64 / base;
+        println!("{:>7} {:>12} {:>7.2}x {:>9.0}% {:>6}", t, res.nps, speedup, 100.0 * speedup / t as f64, res.depth);
+        top_nps = res.nps;
+    }
+    // Hash that holds one move's nodes at the full-machine speed
+    println!("hash advice at {} nps:", top_nps);
+    for ms in [100u64, 1_000, 10_000, 60_000] {
+        println!("  {:>6} ms/move -> {} MB", ms, suggested_hash_mb(top_nps.saturating_mul(ms) / 1000));
+    }
 }
 
 fn main() {
     env_logger::init();
     let args = Args::parse();
+    let opts = engine_options(&args);
+    if args.eval_compare { eval_compare(&args, &opts); return; }
+    if args.ttd { ttd_suite(&args, &opts); return; }
     let board = if args.fen == "startpos" { Board::default() } else { Board::from_fen(&args.fen, false).expect("valid FEN") };
+    if args.preset == Some(Preset::NnueSmp) { nnue_smp(&args, opts, &board); return; }
 
-    let mut s = Searcher::default();
-    s.set_tt_capacity_mb(args.hash_mb);
-    let mut p = SearchParams::default();
-    p.use_tt = true; p.order_captures = true; p.use_history = true; p.threads = args.threads.max(1);
-    if args.depth > 0 { p.depth = args.depth; } else { p.movetime = Some(Duration::from_millis(args.movetime)); }
-
-    if args.use_nnue {
-        s.set_use_nnue(true);
-        s.set_eval_blend_percent(args.blend);
-        if let Some(q) = args.nnue_quant_file.as_deref() {
-            match piebot::eval::nnue::loader::QuantNnue::load_quantized(q) {
-                Ok(model) => s.set_nnue_quant_model(model),
-                Err(e) => eprintln!("failed to load quant NNUE: {}", e),
-            }
-        } else if let Some(d) = args.nnue_file.as_deref() {
-            match piebot::eval::nnue::Nnue::load(d) {
-                Ok(nn) => s.set_nnue_network(Some(nn)),
-                Err(e) => eprintln!("failed to load dense NNUE: {}", e),
-            }
-        }
-    }
+    let mut s = opts.build_searcher().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+    let p = opts.search_params();
+    let (curve, hook) = DepthCurve::recorder();
+    s.set_info_hook(Some(hook));
 
-    let t0 = Instant::now();
     // Ensure Rayon uses requested threads
-    let res = if args.threads > 1 {
-        let pool = rayon::ThreadPoolBuilder::new().num_threads(args.threads).build().unwrap();
+    let res = if opts.threads > 1 || opts.affinity != CorePinning::Off {
+        let pool = affinity::thread_pool(opts.threads, &opts.affinity, opts.stack_mb);
         pool.install(|| s.search_with_params(&board, p))
     } else {
         s.search_with_params(&board, p)
     };
-    let dt = t0.elapsed();
-    let nps = if dt.as_secs_f64() > 0.0 { res.nodes as f64 / dt.as_secs_f64() } else { 0.0 };
-    println!("bestmove={} score_cp={} nodes={} elapsed={:.3}s nps={:.1}", res.bestmove.unwrap_or_else(|| "(none)".to_string()), res.score_cp, res.nodes, dt.as_secs_f64(), nps);
+    println!("bestmove={} score_cp={} nodes={} depth={} seldepth={} tbhits={} elapsed={:.3}s nps={}",
+        res.bestmove.as_deref().unwrap_or("(none)"), res.score.value(), res.nodes, res.depth, res.seldepth, res.tbhits, res.time_ms as f64 / 1000.0, res.nps);
+    let curve = curve.lock().unwrap().clone();
+    print_iterations(&curve);
+    println!("ebf={}", fmt_ebf(curve.ebf()));
+    let st = s.split_stats();
+    if st.splits > 0 {
+        println!("split: policy={} splits={} cutoffs={} split_nodes={} wasted_nodes={} wasted={:.1}%", opts.split, st.splits, st.cutoffs, st.split_nodes, st.wasted_nodes, 100.0 * st.wasted_fraction());
+    }
+    let cs = s.cutoff_stats();
+    let buckets: Vec<String> = cs.by_index.iter().map(u64::to_string).collect();
+    println!("cutoffs: total={} first={:.1}% mean_index={:.2} by_index={}", cs.total(), 100.0 * cs.first_move_rate(), cs.mean_index(), buckets.join("/"));
+    // First-move cutoff rate by remaining depth (the last row includes deeper nodes)
+    println!("{:>5} {:>10} {:>7} {:>10}", "depth", "cutoffs", "first", "mean_index");
+    for (d, c) in s.cutoff_stats_by_depth().iter().enumerate().filter(|(_, c)| c.total() > 0) {
+        println!("{:>5} {:>10} {:>6.1}% {:>10.2}", d, c.total(), 100.0 * c.first_move_rate(), c.mean_index());
+    }
+    let mc = s.move_checks();
+    if cfg!(feature = "search-stats") { println!("movecheck: tt_moves={} tt_rejected={} killers={} killers_rejected={}", mc.tt_moves, mc.tt_rejected, mc.killers, mc.killers_rejected); }
 }
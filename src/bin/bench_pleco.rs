@@ -6,6 +6,14 @@ use std::time::Instant;
 use rand::{SeedableRng, Rng};
 #[cfg(feature = "board-pleco")]
 use rand::rngs::SmallRng;
+#[cfg(feature = "board-pleco")]
+use piebot::search::alphabeta_pleco::HybridStats;
+#[cfg(feature = "board-pleco")]
+use piebot::search::alphabeta::{HelperScheme, SplitConfig, SplitPolicy, SplitStats};
+#[cfg(feature = "board-pleco")]
+use piebot::search::options::EngineOptions;
+#[cfg(feature = "board-pleco")]
+use piebot::search::affinity::{self, CorePinning};
 
 #[cfg(feature = "board-pleco")]
 #[derive(Parser, Debug)]
@@ -13,11 +21,27 @@ use rand::rngs::SmallRng;
 struct Args {
     #[arg(long, default_value = "startpos")] fen: String,
     #[arg(long, default_value_t = 4)] threads: usize,
+    /// Pin search threads: off | auto | core list like 0+2+4-7 (default: from options)
+    #[arg(long)] affinity: Option<CorePinning>,
+    /// In-tree split scheme: ybwc | jamboree (the old fixed depth>=3, 12-move split); default: from options
+    #[arg(long)] split: Option<SplitPolicy>,
+    /// Movetime (ms) and depth limits, 0 = none; the search stops at whichever comes first
     #[arg(long, default_value_t = 2000)] movetime: u64,
     #[arg(long, default_value_t = 6)] depth: u32,
-    /// SMP mode: off | in-tree | lazy
+    /// Iterations completed even past the movetime (default: from options)
+    #[arg(long)] min_depth: Option<u32>,
+    /// SMP mode: off | in-tree | lazy-indep | lazy-coop (lazy) | lazy-hybrid
     #[arg(long, default_value = "in-tree")]
     smp: String,
+    /// Safe SMP profile: helpers keep the main thread's pruning (also `smp_safe=on` in options)
+    #[arg(long, default_value_t = false)]
+    smp_safe: bool,
+    /// Master seed for Lazy SMP helper profiles; 0 = fixed scheme (default: from options)
+    #[arg(long)]
+    smp_seed: Option<u64>,
+    /// Lazy SMP helpers (lazy-indep): rotate | skip (default: from options)
+    #[arg(long)]
+    smp_helpers: Option<HelperScheme>,
     /// Deterministic seed to randomize starting positions
     #[arg(long, default_value_t = 1u64)]
     seed: u64,
@@ -57,24 +81,38 @@ struct Args {
     /// Limit to top-K moves for tempered sampling (0=all)
     #[arg(long, default_value_t = 3usize)]
     rollout_topk: usize,
+    /// Transposition table size in MB (approximate)
+    #[arg(long, default_value_t = 64)]
+    hash_mb: usize,
+    /// EngineOptions JSON for the search toggles and eval; --threads/--hash-mb win
+    #[arg(long)]
+    options: Option<String>,
 }
 
 #[cfg(feature = "board-pleco")]
 fn main_inner() {
     let args = Args::parse();
-    let pool = rayon::ThreadPoolBuilder::new().num_threads(args.threads).build().unwrap();
+    if let Some(path) = &args.options {
+        match EngineOptions::load_json(path) {
+            Ok(o) => EngineOptions::set_global(o),
+            Err(e) => { eprintln!("--options {}: {}", path, e); std::process::exit(2); }
+        }
+    }
+    let pinning = args.affinity.clone().unwrap_or_else(|| EngineOptions::global().affinity);
+    let pool = affinity::thread_pool(args.threads, &pinning, EngineOptions::global().stack_mb);
 
     if args.positions <= 1 && args.suite.is_none() {
         let mut board = if args.fen == "startpos" { pleco::Board::start_pos() } else { pleco::Board::from_fen(&args.fen).expect("valid fen") };
         randomize_board(&mut board, args.seed, args.min_plies, args.max_plies);
-        let t0 = Instant::now();
-        let (bm, sc, nodes, depth_reached, seldepth) = pool.install(|| run_one(&mut board.clone(), &args));
-        let dt = t0.elapsed();
+        let (r, hs, st) = pool.install(|| run_one(&mut board.clone(), &args));
+        let bm = r.bestmove.as_deref().unwrap_or("(none)");
         if args.json {
-            println!("{{\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{:.1},\"score_cp\":{},\"bestmove\":\"{:?}\"}}",
-                nodes, depth_reached, seldepth, nodes as f64 / dt.as_secs_f64(), sc, bm);
+            println!("{{\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{},\"time_ms\":{},\"tbhits\":{},\"score_cp\":{},\"bestmove\":\"{}\"{}{}}}",
+                r.nodes, r.depth, r.seldepth, r.nps, r.time_ms, r.tbhits, r.score.value(), bm, hybrid_json(&hs), split_json(&st));
         } else {
-            println!("bestmove={:?} score_cp={} nodes={} depth={} seldepth={} elapsed={:.3}s nps={:.1}", bm, sc, nodes, depth_reached, seldepth, dt.as_secs_f64(), nodes as f64 / dt.as_secs_f64());
+            println!("bestmove={} score_cp={} nodes={} depth={} seldepth={} elapsed={:.3}s nps={}", bm, r.score.value(), r.nodes, r.depth, r.seldepth, r.time_ms as f64 / 1000.0, r.nps);
+            if st.splits > 0 { println!("{}", split_line(&st)); }
+            if let Some(h) = hs { println!("hybrid: prewarm_runs={} prewarm_ms={} prewarm_nodes={} tt_hit_cold={:.3} tt_hit_warm={:.3}", h.prewarm_runs, h.prewarm_ms, h.prewarm_nodes, h.cold_hit_rate(), h.warm_hit_rate()); }
         }
         return;
     }
@@ -110,41 +148,44 @@ fn main_inner() {
         let mut depths = Vec::with_capacity(cases.len());
         let mut seldepths = Vec::with_capacity(cases.len());
         let mut nodes_total: u64 = 0;
+        let mut split_total = SplitStats::default();
         let t0_all = Instant::now();
         for (i, mut board) in cases.into_iter().enumerate().take(args.positions) {
-            let t0 = Instant::now();
-            let (bm, sc, nodes, depth_reached, seldepth) = pool.install(|| run_one(&mut board, &args));
-            let dt = t0.elapsed();
-            depths.push(depth_reached); seldepths.push(seldepth); nodes_total += nodes;
-            if args.json { println!("{{\"idx\":{},\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{:.1},\"score_cp\":{},\"bestmove\":\"{:?}\"}}", i, nodes, depth_reached, seldepth, nodes as f64 / dt.as_secs_f64(), sc, bm); }
-            else { println!("case={} depth={} seldepth={} nodes={} elapsed={:.3}s nps={:.1}", i, depth_reached, seldepth, nodes, dt.as_secs_f64(), nodes as f64 / dt.as_secs_f64()); }
+            let (r, hs, st) = pool.install(|| run_one(&mut board, &args));
+            depths.push(r.depth); seldepths.push(r.seldepth); nodes_total += r.nodes;
+            split_total.merge(&st);
+            if args.json { println!("{{\"idx\":{},\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{},\"score_cp\":{},\"bestmove\":\"{}\"{}{}}}", i, r.nodes, r.depth, r.seldepth, r.nps, r.score.value(), r.bestmove.as_deref().unwrap_or("(none)"), hybrid_json(&hs), split_json(&st)); }
+            else { println!("case={} depth={} seldepth={} nodes={} elapsed={:.3}s nps={}", i, r.depth, r.seldepth, r.nodes, r.time_ms as f64 / 1000.0, r.nps); }
         }
         let dt_all = t0_all.elapsed();
         let avg_depth = if depths.is_empty() { 0.0 } else { depths.iter().copied().sum::<u32>() as f64 / depths.len() as f64 };
         let avg_seldepth = if seldepths.is_empty() { 0.0 } else { seldepths.iter().copied().sum::<u32>() as f64 / seldepths.len() as f64 };
         let min_depth = depths.iter().copied().min().unwrap_or(0);
         let max_depth = depths.iter().copied().max().unwrap_or(0);
-        if args.json { println!("{{\"summary\":true,\"positions\":{},\"avg_depth\":{:.2},\"avg_seldepth\":{:.2},\"min_depth\":{},\"max_depth\":{},\"total_nodes\":{},\"elapsed\":{:.3}}}", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64()); }
-        else { println!("summary: positions={} avg_depth={:.2} avg_seldepth={:.2} min={} max={} total_nodes={} elapsed={:.3}s", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64()); }
+        if args.json { println!("{{\"summary\":true,\"positions\":{},\"avg_depth\":{:.2},\"avg_seldepth\":{:.2},\"min_depth\":{},\"max_depth\":{},\"total_nodes\":{},\"elapsed\":{:.3}{}}}", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64(), split_json(&split_total)); }
+        else {
+            println!("summary: positions={} avg_depth={:.2} avg_seldepth={:.2} min={} max={} total_nodes={} elapsed={:.3}s", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64());
+            if split_total.splits > 0 { println!("{}", split_line(&split_total)); }
+        }
         return;
     }
 
     let mut depths = Vec::with_capacity(picked.len());
     let mut seldepths = Vec::with_capacity(picked.len());
     let mut nodes_total: u64 = 0;
+    let mut split_total = SplitStats::default();
     let t0_all = Instant::now();
     for (i, fen) in picked.iter().enumerate() {
         let mut board = if fen == "startpos" { pleco::Board::start_pos() } else { pleco::Board::from_fen(&fen).expect("valid fen") };
         randomize_board(&mut board, args.seed.wrapping_add((i as u64).wrapping_mul(101_390_4223)), args.min_plies, args.max_plies);
-        let t0 = Instant::now();
-        let (bm, sc, nodes, depth_reached, seldepth) = pool.install(|| run_one(&mut board.clone(), &args));
-        let dt = t0.elapsed();
-        depths.push(depth_reached); seldepths.push(seldepth); nodes_total += nodes;
+        let (r, hs, st) = pool.install(|| run_one(&mut board.clone(), &args));
+        depths.push(r.depth); seldepths.push(r.seldepth); nodes_total += r.nodes;
+        split_total.merge(&st);
         if args.json {
-            println!("{{\"idx\":{},\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{:.1},\"score_cp\":{},\"bestmove\":\"{:?}\"}}",
-                i, nodes, depth_reached, seldepth, nodes as f64 / dt.as_secs_f64(), sc, bm);
+            println!("{{\"idx\":{},\"nodes\":{},\"depth\":{},\"seldepth\":{},\"nps\":{},\"score_cp\":{},\"bestmove\":\"{}\"{}{}}}",
+                i, r.nodes, r.depth, r.seldepth, r.nps, r.score.value(), r.bestmove.as_deref().unwrap_or("(none)"), hybrid_json(&hs), split_json(&st));
         } else {
-            println!("case={} depth={} seldepth={} nodes={} elapsed={:.3}s nps={:.1}", i, depth_reached, seldepth, nodes, dt.as_secs_f64(), nodes as f64 / dt.as_secs_f64());
+            println!("case={} depth={} seldepth={} nodes={} elapsed={:.3}s nps={}", i, r.depth, r.seldepth, r.nodes, r.time_ms as f64 / 1000.0, r.nps);
         }
     }
     let dt_all = t0_all.elapsed();
@@ -153,7 +194,10 @@ fn main_inner() {
     let min_depth = depths.iter().copied().min().unwrap_or(0);
     let max_depth = depths.iter().copied().max().unwrap_or(0);
     if args.json { println!("{{\\\"summary\\\":true,\\\"positions\\\":{},\\\"avg_depth\\\":{:.2},\\\"avg_seldepth\\\":{:.2},\\\"min_depth\\\":{},\\\"max_depth\\\":{},\\\"total_nodes\\\":{},\\\"elapsed\\\":{:.3}}}", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64()); }
-    else { println!("summary: positions={} avg_depth={:.2} avg_seldepth={:.2} min={} max={} total_nodes={} elapsed={:.3}s", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64()); }
+    else {
+        println!("summary: positions={} avg_depth={:.2} avg_seldepth={:.2} min={} max={} total_nodes={} elapsed={:.3}s", depths.len(), avg_depth, avg_seldepth, min_depth, max_depth, nodes_total, dt_all.as_secs_f64());
+        if split_total.splits > 0 { println!("{}", split_line(&split_total)); }
+    }
 }
 
 #[cfg(not(feature = "board-pleco"))]
@@ -166,38 +210,68 @@ fn main() { main_inner(); }
 
 #[cfg(feature = "board-pleco")]
 fn randomize_board(board: &mut pleco::Board, seed: u64, min_plies: usize, max_plies: usize) {
+    use piebot::selfplay::openings::OpeningGenerator;
     if max_plies == 0 || max_plies < min_plies { return; }
-    let mut rng = SmallRng::seed_from_u64(seed);
-    let span = (max_plies - min_plies + 1) as u32;
-    let plies = min_plies + (if span == 0 { 0 } else { (rng.gen::<u32>() % span) as usize });
-    for _ in 0..plies {
-        let moves = board.generate_moves();
-        let len = moves.len(); if len == 0 { break; }
-        let idx = rng.gen_range(0..len);
-        let mv = moves[idx];
-        board.apply_move(mv);
-        if board.checkmate() || board.stalemate() { break; }
+    // Walk on the cozy board so the opening filter applies; seed parity picks the side to move
+    let Ok(start) = cozy_chess::Board::from_fen(&board.fen(), false) else { return; };
+    let stm = if seed.is_multiple_of(2) { cozy_chess::Color::White } else { cozy_chess::Color::Black };
+    let mut gen = OpeningGenerator::new(seed, min_plies, max_plies).with_start(start);
+    if let Some(b) = gen.generate(Some(stm)) {
+        if let Ok(p) = pleco::Board::from_fen(&format!("{}", b)) { *board = p; }
     }
 }
 
 #[cfg(feature = "board-pleco")]
-fn run_one(board: &mut pleco::Board, args: &Args) -> (Option<pleco::BitMove>, i32, u64, u32, u32) {
+fn run_one(board: &mut pleco::Board, args: &Args) -> (piebot::search::alphabeta::SearchResult, Option<HybridStats>, SplitStats) {
     use piebot::search::alphabeta_pleco::SmpMode;
-    let mut s = piebot::search::alphabeta_pleco::PlecoSearcher::default();
-    s.set_threads(args.threads);
+    let mut opts = EngineOptions { threads: args.threads, hash_mb: args.hash_mb, ..EngineOptions::global() };
+    if let Some(d) = args.min_depth { opts.min_depth = d; }
+    let mut s = opts.build_pleco_searcher().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+    match args.split {
+        Some(SplitPolicy::Jamboree) => s.set_split(SplitConfig::JAMBOREE),
+        Some(SplitPolicy::Ybwc) => s.set_split(SplitConfig::default()),
+        None => {}
+    }
     let smp_mode = match args.smp.as_str() {
         "off" => SmpMode::Off,
         "in-tree" => SmpMode::InTree,
         "lazy-indep" => SmpMode::LazyIndep,
         "lazy-coop" => SmpMode::LazyCoop,
         "lazy" => SmpMode::LazyCoop,
+        "lazy-hybrid" => SmpMode::LazyHybrid,
         _ => SmpMode::InTree,
     };
     s.set_smp_mode(smp_mode);
+    if args.smp_safe { s.set_smp_safe(true); }
+    if let Some(seed) = args.smp_seed { s.set_smp_seed(seed); }
+    if let Some(scheme) = args.smp_helpers { s.set_helper_scheme(scheme); }
     let finish = match args.tm_policy.as_str() { "spend" => false, _ => true };
     s.set_time_manager(finish, args.tm_factor);
-    let (bm, sc, nodes) = s.search_movetime(board, args.movetime, args.depth);
-    (bm, sc, nodes, s.last_depth(), s.last_seldepth())
+    let r = s.search_result(board, args.movetime, args.depth);
+    let hs = if smp_mode == SmpMode::LazyHybrid && args.threads > 1 { Some(s.hybrid_stats()) } else { None };
+    (r, hs, s.split_stats())
+}
+
+// Extra JSON fields (with leading comma) for in-tree split accounting
+#[cfg(feature = "board-pleco")]
+fn split_json(st: &SplitStats) -> String {
+    if st.splits == 0 { return String::new(); }
+    format!(",\"splits\":{},\"split_cutoffs\":{},\"split_nodes\":{},\"wasted_nodes\":{},\"wasted_frac\":{:.4}", st.splits, st.cutoffs, st.split_nodes, st.wasted_nodes, st.wasted_fraction())
+}
+
+#[cfg(feature = "board-pleco")]
+fn split_line(st: &SplitStats) -> String {
+    format!("split: splits={} cutoffs={} split_nodes={} wasted_nodes={} wasted={:.1}%", st.splits, st.cutoffs, st.split_nodes, st.wasted_nodes, 100.0 * st.wasted_fraction())
+}
+
+// Extra JSON fields (with leading comma) for LazyHybrid pre-warm statistics
+#[cfg(feature = "board-pleco")]
+fn hybrid_json(hs: &Option<HybridStats>) -> String {
+    match hs {
+        Some(h) => format!(",\"prewarm_runs\":{},\"prewarm_ms\":{},\"prewarm_nodes\":{},\"tt_probes_cold\":{},\"tt_hit_rate_cold\":{:.4},\"tt_probes_warm\":{},\"tt_hit_rate_warm\":{:.4}",
+            h.prewarm_runs, h.prewarm_ms, h.prewarm_nodes, h.cold_probes, h.cold_hit_rate(), h.warm_probes, h.warm_hit_rate()),
+        None => String::new(),
+    }
 }
 
 #[cfg(feature = "board-pleco")]
@@ -0,0 +1,136 @@
+use clap::Parser;
+use cozy_chess::{Board, Move};
+use piebot::board::cozy::decode_move;
+use piebot::io::pgn::{parse_pgn, PgnFilter, TimeClass};
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::search::eval::eval_cp;
+use piebot::search::wdl::{material_units, WdlModel, WdlSample};
+use piebot::selfplay::{generate_games, SelfPlayParams};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-calibrate", about = "Relate static/search evals to game results and fit a WDL model")]
+struct Args {
+    /// PGN file to replay (games without a result are skipped)
+    #[arg(long)]
+    pgn: Option<PathBuf>,
+    /// Generate this many self-play games instead of reading PGN
+    #[arg(long, default_value_t = 0)]
+    selfplay: usize,
+    /// Self-play search depth
+    #[arg(long, default_value_t = 3)]
+    selfplay_depth: u32,
+    /// Search depth for the "search eval" of each sampled position
+    #[arg(long, default_value_t = 4)]
+    depth: u32,
+    /// Skip this many opening plies of each game
+    #[arg(long, default_value_t = 8)]
+    skip_plies: usize,
+    /// Sample every Nth ply
+    #[arg(long, default_value_t = 2)]
+    every: usize,
+    /// Stop after this many positions (0 = all)
+    #[arg(long, default_value_t = 0)]
+    max_positions: usize,
+    /// Width of the calibration buckets in centipawns
+    #[arg(long, default_value_t = 100)]
+    bucket_cp: i32,
+    /// Optional JSONL dump of every sample (fen, static, search, material, result)
+    #[arg(long)]
+    dump: Option<PathBuf>,
+    #[arg(long, default_value_t = 7)]
+    seed: u64,
+    /// PGN import: keep games where both players are rated at least this
+    #[arg(long)]
+    min_elo: Option<u32>,
+    /// PGN import: allowed speed classes, comma-separated (ultrabullet,bullet,blitz,rapid,classical,correspondence)
+    #[arg(long, value_delimiter = ',')]
+    time_class: Vec<TimeClass>,
+    /// PGN import: drop bullet and ultrabullet games
+    #[arg(long)]
+    exclude_bullet: bool,
+    /// PGN import: allowed Termination tag values, comma-separated (e.g. Normal)
+    #[arg(long, value_delimiter = ',')]
+    termination: Vec<String>,
+}
+
+struct Sample { static_cp: i32, search_cp: i32, material: u32, result: i8 }
+
+fn collect_games(args: &Args) -> Vec<(Board, Vec<Move>, i8)> {
+    let mut out = Vec::new();
+    if let Some(path) = &args.pgn {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|e| { eprintln!("read {}: {}", path.display(), e); String::new() });
+        let filter = PgnFilter { min_elo: args.min_elo, time_classes: args.time_class.clone(), exclude_bullet: args.exclude_bullet, terminations: args.termination.clone() };
+        let mut rejected: std::collections::BTreeMap<&str, usize> = Default::default();
+        for g in parse_pgn(&text) {
+            if let Err(why) = filter.check(&g) { *rejected.entry(why).or_default() += 1; continue; }
+            if let Some(r) = g.result { out.push((g.start, g.moves, r)); }
+        }
+        for (why, n) in &rejected { eprintln!("filtered {} games: {}", n, why); }
+    }
+    if args.selfplay > 0 {
+        let params = SelfPlayParams {
+            games: args.selfplay, max_plies: 200, threads: 1, use_engine: true, depth: args.selfplay_depth, movetime_ms: None, seed: args.seed,
+            temperature_tau: 1.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.25,
+            dirichlet_plies: 8, temperature_moves: 12, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0, opening_sources: Vec::new(),
+        };
+        for rec in generate_games(&params) {
+            let moves = rec.moves.iter().map_while(|&c| decode_move(c)).collect();
+            out.push((rec.start, moves, rec.result));
+        }
+    }
+    out
+}
+
+fn main() {
+    let args = Args::parse();
+    let games = collect_games(&args);
+    eprintln!("replaying {} games", games.len());
+    let params = SearchParams { depth: args.depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_nullmove: true, use_lmr: true, ..Default::default() };
+    let mut searcher = Searcher::default();
+    let mut dump = args.dump.as_ref().map(|p| std::io::BufWriter::new(std::fs::File::create(p).expect("create dump file")));
+    let mut samples: Vec<Sample> = Vec::new();
+    'games: for (start, moves, result) in &games {
+        searcher.new_game();
+        let mut b = start.clone();
+        for (ply, m) in moves.iter().enumerate() {
+            if ply >= args.skip_plies && (ply - args.skip_plies) % args.every.max(1) == 0 {
+                let stm_result = if b.side_to_move() == cozy_chess::Color::White { *result } else { -*result };
+                searcher.new_search();
+                let search_cp = searcher.search_with_params(&b, params).score.value();
+                let s = Sample { static_cp: eval_cp(&b), search_cp, material: material_units(&b), result: stm_result };
+                if let Some(w) = dump.as_mut() {
+                    let _ = writeln!(w, "{{\"fen\":\"{}\",\"static_cp\":{},\"search_cp\":{},\"material\":{},\"result\":{}}}", b, s.static_cp, s.search_cp, s.material, s.result);
+                }
+                samples.push(s);
+                if args.max_positions > 0 && samples.len() >= args.max_positions { break 'games; }
+            }
+            b.play(*m);
+        }
+    }
+    if samples.is_empty() { eprintln!("no positions sampled"); return; }
+
+    // Mate scores would dominate the fit; keep them out
+    let fit_set: Vec<WdlSample> = samples.iter().filter(|s| s.search_cp.abs() < 5_000)
+        .map(|s| WdlSample { cp: s.search_cp, material: s.material, result: s.result }).collect();
+    let model = WdlModel::default().fit(&fit_set);
+
+    let width = args.bucket_cp.max(10);
+    println!("{:>12} {:>7} {:>6} {:>6} {:>6} {:>8} {:>8} {:>8} {:>10}", "search_cp", "n", "W%", "D%", "L%", "model_W", "model_D", "model_L", "avg_static");
+    let mut buckets: std::collections::BTreeMap<i32, Vec<&Sample>> = std::collections::BTreeMap::new();
+    for s in samples.iter().filter(|s| s.search_cp.abs() < 5_000) { buckets.entry(s.search_cp.div_euclid(width)).or_default().push(s); }
+    for (k, v) in &buckets {
+        let n = v.len() as f64;
+        let frac = |r: i8| 100.0 * v.iter().filter(|s| s.result == r).count() as f64 / n;
+        let (mut mw, mut md, mut ml) = (0.0, 0.0, 0.0);
+        for s in v { let (w, d, l) = model.probabilities(s.search_cp, s.material); mw += w; md += d; ml += l; }
+        let avg_static = v.iter().map(|s| s.static_cp as f64).sum::<f64>() / n;
+        println!("{:>5}..{:<6} {:>7} {:>6.1} {:>6.1} {:>6.1} {:>8.1} {:>8.1} {:>8.1} {:>10.0}",
+            k * width, (k + 1) * width, v.len(), frac(1), frac(0), frac(-1), 100.0 * mw / n, 100.0 * md / n, 100.0 * ml / n, avg_static);
+    }
+    let mad = samples.iter().map(|s| (s.static_cp - s.search_cp).abs() as f64).sum::<f64>() / samples.len() as f64;
+    println!("positions={} static_vs_search_mean_abs_diff={:.1}cp", samples.len(), mad);
+    println!("nll default={:.4} fitted={:.4}", WdlModel::default().loss(&fit_set), model.loss(&fit_set));
+    println!("{}", serde_json::to_string(&model).unwrap());
+}
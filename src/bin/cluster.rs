@@ -0,0 +1,80 @@
+use clap::{Parser, Subcommand};
+use cozy_chess::Board;
+use piebot::search::cluster::{cluster_search, ping, serve, ClusterLink};
+use piebot::search::options::EngineOptions;
+use std::net::TcpListener;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-cluster", about = "Experimental root-split search across machines over TCP")]
+struct Args {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Serve search jobs from a master
+    Worker {
+        /// Address to listen on; anything but loopback needs --secret
+        #[arg(long, default_value = "127.0.0.1:7070")]
+        listen: String,
+        /// Shared secret the master must send with every job
+        #[arg(long)]
+        secret: Option<String>,
+    },
+    /// Analyse one position, sharing the root moves between workers
+    Analyze {
+        /// Worker addresses, comma-separated host:port
+        #[arg(long, value_delimiter = ',')]
+        workers: Vec<String>,
+        /// Position (default: start position)
+        #[arg(long)]
+        fen: Option<String>,
+        #[arg(long, default_value_t = 10)]
+        depth: u32,
+        /// Movetime per worker in milliseconds instead of a fixed depth
+        #[arg(long)]
+        movetime: Option<u64>,
+        /// EngineOptions JSON sent to every worker; --depth/--movetime override it
+        #[arg(long)]
+        options: Option<String>,
+        /// Shared secret of the workers
+        #[arg(long)]
+        secret: Option<String>,
+        /// Seconds to wait for a fixed-depth share before searching it locally
+        #[arg(long, default_value_t = 600)]
+        timeout: u64,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    match Args::parse().cmd {
+        Cmd::Worker { listen, secret } => {
+            let listener = TcpListener::bind(&listen)?;
+            let addr = listener.local_addr()?;
+            if !addr.ip().is_loopback() && secret.is_none() { anyhow::bail!("refusing to listen on {} without --secret", addr); }
+            eprintln!("cluster worker listening on {}", addr);
+            serve(listener, secret)?;
+        }
+        Cmd::Analyze { workers, fen, depth, movetime, options, secret, timeout } => {
+            let board = match &fen {
+                Some(f) => Board::from_fen(f, false).map_err(|e| anyhow::anyhow!("bad fen: {:?}", e))?,
+                None => Board::default(),
+            };
+            let mut opts = match &options { Some(p) => EngineOptions::load_json(p)?, None => EngineOptions::default() };
+            opts.depth = if movetime.is_some() { 64 } else { depth };
+            opts.movetime_ms = movetime;
+            let link = ClusterLink { secret, timeout: Duration::from_secs(timeout) };
+            for w in &workers { if !ping(w, &link) { eprintln!("warning: worker {} not answering; its share will run locally", w); } }
+            let run = cluster_search(&board, &opts, &workers, &link)?;
+            for s in &run.shares {
+                let failed = s.failure.as_deref().map(|f| format!(" (fell back: {})", f)).unwrap_or_default();
+                println!("{:<22} moves {:>3} depth {:>2} nodes {:>12} best {:<6} cp {}{}", s.worker, s.moves, s.depth, s.nodes, s.bestmove.as_deref().unwrap_or("none"), s.score_cp, failed);
+            }
+            let r = &run.result;
+            println!("bestmove {} cp {} depth {} nodes {} time {}ms nps {}", r.bestmove.as_deref().unwrap_or("none"), r.score.value(), r.depth, r.nodes, r.time_ms, r.nps);
+        }
+    }
+    Ok(())
+}
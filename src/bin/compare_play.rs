@@ -0,0 +1,347 @@
+use clap::Parser;
+use cozy_chess::Color;
+use piebot::board::cozy::has_legal_moves;
+use piebot::io::timeline::GameTimeline;
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::search::options::EngineOptions;
+use piebot::search::wdl::{material_units, WdlModel};
+use piebot::search::timeman::{allocate, Calibration, ClockPlan, GoLimits, TimeConfig};
+use piebot::selfplay::adjudicate::Adjudication;
+use piebot::selfplay::game::{startpos, GameState};
+use piebot::selfplay::frc::{random_dfrc, random_frc};
+use piebot::selfplay::openings::OpeningGenerator;
+use piebot::selfplay::review::{CplStats, Reviewer};
+use rand::SeedableRng;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-compare-play", about = "Play an A/B match between two search configurations")]
+struct Args {
+    /// Number of games (played in colour-swapped pairs)
+    #[arg(long, default_value_t = 20)]
+    games: usize,
+    /// Search depth per move
+    #[arg(long, default_value_t = 4)]
+    depth: u32,
+    /// Optional movetime per move in milliseconds
+    #[arg(long)]
+    movetime: Option<u64>,
+    /// Game clock as base+increment in seconds (e.g. "10+0.1"); --depth still caps each search
+    #[arg(long)]
+    tc: Option<String>,
+    /// Stop and score a draw after this many plies
+    #[arg(long, default_value_t = 200)]
+    max_plies: usize,
+    /// Optional FEN/EPD file of opening positions (one per line)
+    #[arg(long)]
+    openings: Option<PathBuf>,
+    /// Random-walk plies from the start position when no openings file is given;
+    /// walks are filtered for balance and alternate the side to move per pair
+    #[arg(long, default_value_t = 8)]
+    random_plies: usize,
+    /// Start positions when no openings file is given: standard (random walks),
+    /// frc (a random Chess960 setup per pair) or dfrc (double FRC)
+    #[arg(long, default_value = "standard", value_parser = ["standard", "frc", "dfrc"])]
+    variant: String,
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// Base engine overrides, e.g. "lmr=off,nullmove=on,depth=5"; applied on top of --base-preset
+    #[arg(long, default_value = "")]
+    base: String,
+    /// Experimental engine overrides (same syntax as --base)
+    #[arg(long, default_value = "")]
+    exp: String,
+    /// WDL model JSON (from calibrate) for the base engine's eval; scores are also
+    /// reported as win/draw/loss so engines with different evals compare
+    #[arg(long)]
+    base_wdl: Option<String>,
+    /// WDL model JSON for the experimental engine's eval
+    #[arg(long)]
+    exp_wdl: Option<String>,
+    /// Named preset for the base engine (built in: default, strong, fastsafe, material)
+    #[arg(long)]
+    base_preset: Option<String>,
+    /// Named preset for the experimental engine
+    #[arg(long)]
+    exp_preset: Option<String>,
+    /// JSON file of extra presets: {"name": {"lmr": false, "hash": 128, ...}} or {"name": "lmr=off,hash=128"}
+    #[arg(long)]
+    presets: Option<PathBuf>,
+    /// EngineOptions JSON both engines start from (before presets and overrides)
+    #[arg(long)]
+    options: Option<String>,
+    /// Disable repetition/50-move/insufficient-material adjudication
+    #[arg(long, default_value_t = false)]
+    no_adjudicate: bool,
+    /// Write one timeline JSON per game (depth, score, time, nodes by move) into this
+    /// directory; plot with scripts/plot_timeline.py
+    #[arg(long)]
+    timeline: Option<PathBuf>,
+    /// After each game, re-search every played position at this depth (a few plies
+    /// deeper than the games) and report centipawn loss per engine by phase; 0 = off
+    #[arg(long, default_value_t = 0)]
+    cpl_depth: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome { WhiteWins, BlackWins, Draw(Option<Adjudication>) }
+
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("default", ""),
+    ("strong", "tt=on,captures=on,history=on,killers=on,lmr=on,nullmove=on,aspiration=on,hash=128"),
+    ("fastsafe", "lmr=off,nullmove=off,aspiration=off,hash=16"),
+    ("material", "eval=material"),
+    ("game", "style=game"),
+    ("analysis", "style=analysis"),
+];
+
+// Preset values are either a spec string or an object of key -> bool/number/string
+fn load_presets(path: &PathBuf) -> Result<Vec<(String, String)>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+    let v: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let obj = v.as_object().ok_or_else(|| format!("{}: expected an object of presets", path.display()))?;
+    let mut out = Vec::new();
+    for (name, val) in obj {
+        let spec = match val {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Object(kv) => kv.iter().map(|(k, v)| match v {
+                serde_json::Value::Bool(b) => format!("{}={}", k, if *b { "on" } else { "off" }),
+                serde_json::Value::String(s) => format!("{}={}", k, s),
+                other => format!("{}={}", k, other),
+            }).collect::<Vec<_>>().join(","),
+            _ => return Err(format!("preset '{}': expected a string or object", name)),
+        };
+        out.push((name.clone(), spec));
+    }
+    Ok(out)
+}
+
+fn find_preset<'a>(name: &str, file: &'a [(String, String)]) -> Result<&'a str, String> {
+    // File presets shadow the built-in ones
+    file.iter().find(|(n, _)| n == name).map(|(_, s)| s.as_str())
+        .or_else(|| BUILTIN_PRESETS.iter().find(|(n, _)| *n == name).map(|(_, s)| *s))
+        .ok_or_else(|| {
+            let mut names: Vec<&str> = BUILTIN_PRESETS.iter().map(|(n, _)| *n).chain(file.iter().map(|(n, _)| n.as_str())).collect();
+            names.sort(); names.dedup();
+            format!("unknown preset '{}' (available: {})", name, names.join(", "))
+        })
+}
+
+/// Game clock: `base` seconds plus `inc` seconds per move.
+#[derive(Clone, Copy, Debug)]
+struct TimeControl { base: Duration, inc: Duration }
+
+fn parse_tc(s: &str) -> Result<TimeControl, String> {
+    let (b, i) = s.split_once('+').unwrap_or((s, "0"));
+    let secs = |v: &str| v.trim().parse::<f64>().ok().filter(|x| *x >= 0.0).map(Duration::from_secs_f64).ok_or_else(|| format!("bad time control '{}' (expected base+inc in seconds)", s));
+    Ok(TimeControl { base: secs(b)?, inc: secs(i)? })
+}
+
+#[derive(Default, Debug)]
+struct TimeStats { moves: u32, used: Duration, max_move: Duration, min_remaining: Option<Duration>, flags: u32 }
+
+/// How an engine's scores, put through its WDL model, matched the game results:
+/// the mean squared error of the expected score (Brier) and the mean |cp| as the
+/// raw scale. Mate scores are left out of the cp mean.
+#[derive(Default, Debug)]
+struct ScoreStats { game: Vec<f64>, n: u32, sq_err: f64, cp_n: u32, abs_cp: f64 }
+
+impl ScoreStats {
+    fn record(&mut self, cp: i32, is_mate: bool, expected: f64) {
+        self.game.push(expected);
+        if !is_mate { self.cp_n += 1; self.abs_cp += cp.unsigned_abs() as f64; }
+    }
+
+    // `result` is the game's score for this engine (1, 0.5 or 0)
+    fn settle(&mut self, result: f64) {
+        for e in self.game.drain(..) { self.n += 1; self.sq_err += (e - result).powi(2); }
+    }
+}
+
+struct Engine { name: &'static str, searcher: Searcher, params: SearchParams, time: TimeConfig, wdl: WdlModel, stats: TimeStats, scores: ScoreStats, cpl: CplStats }
+
+impl Engine {
+    fn new(name: &'static str, opts: &EngineOptions, wdl: WdlModel) -> Result<Self, String> {
+        let searcher = opts.build_searcher().map_err(|e| e.to_string())?;
+        Ok(Self { name, searcher, params: opts.search_params(), time: opts.time_config(), wdl, stats: TimeStats::default(), scores: ScoreStats::default(), cpl: CplStats::default() })
+    }
+}
+
+fn play_game(game: &mut GameState, white: &mut Engine, black: &mut Engine, tc: Option<TimeControl>, max_plies: usize, adjudicate_draws: bool, mut timeline: Option<&mut GameTimeline>) -> (Outcome, usize, Option<Color>) {
+    white.searcher.new_game(); black.searcher.new_game();
+    let mut clocks = tc.map(|t| [t.base, t.base]);
+    game.reset();
+    for ply in 0..max_plies {
+        let board = game.board();
+        if !has_legal_moves(board) {
+            if board.checkers().is_empty() { return (Outcome::Draw(None), ply, None); }
+            return (if board.side_to_move() == Color::White { Outcome::BlackWins } else { Outcome::WhiteWins }, ply, None);
+        }
+        if adjudicate_draws {
+            if let Some(a) = game.adjudicate() { return (Outcome::Draw(Some(a)), ply, None); }
+        }
+        let stm = board.side_to_move();
+        let side = if stm == Color::White { 0 } else { 1 };
+        let engine = if stm == Color::White { &mut *white } else { &mut *black };
+        let mut p = engine.params;
+        if let (Some(c), Some(t)) = (clocks.as_ref(), tc) {
+            // Abort at the hard limit; between iterations the plan stops by the soft one
+            let go = GoLimits { remaining: Some(c[side]), increment: t.inc, ..Default::default() };
+            if let Some(budget) = allocate(&go, &engine.time) {
+                p.movetime = Some(budget.hard);
+                p.clock = Some(ClockPlan { budget, config: engine.time });
+            }
+        }
+        engine.searcher.new_search();
+        let t0 = Instant::now();
+        let res = engine.searcher.search_with_params(board, p);
+        let spent = t0.elapsed();
+        if let Some(t) = timeline.as_deref_mut() { t.push_wdl(board, engine.name, &res, &engine.wdl); }
+        engine.scores.record(res.score.value(), res.score.is_mate(), engine.wdl.expected_score(res.score.value(), material_units(board)));
+        engine.stats.moves += 1;
+        engine.stats.used += spent;
+        engine.stats.max_move = engine.stats.max_move.max(spent);
+        if let (Some(c), Some(t)) = (clocks.as_mut(), tc) {
+            // Charge the actual wall time; running out before the increment lands is a loss
+            if spent >= c[side] {
+                engine.stats.flags += 1;
+                engine.stats.min_remaining = Some(Duration::ZERO);
+                let outcome = if stm == Color::White { Outcome::BlackWins } else { Outcome::WhiteWins };
+                return (outcome, ply, Some(stm));
+            }
+            c[side] = c[side] - spent + t.inc;
+            let left = c[side];
+            engine.stats.min_remaining = Some(engine.stats.min_remaining.map_or(left, |m| m.min(left)));
+        }
+        if !res.bestmove.as_deref().is_some_and(|u| game.play_uci(u)) { return (Outcome::Draw(None), ply, None); }
+    }
+    (Outcome::Draw(Some(Adjudication::MaxPlies)), max_plies, None)
+}
+
+fn print_time_stats(name: &str, s: &TimeStats) {
+    let avg = if s.moves == 0 { 0.0 } else { s.used.as_secs_f64() * 1000.0 / s.moves as f64 };
+    let min_rem = s.min_remaining.map(|d| format!("{}ms", d.as_millis())).unwrap_or_else(|| "-".to_string());
+    println!("{:<4} time: moves={} used={:.1}s avg={:.0}ms max={}ms min_remaining={} flags={}",
+        name, s.moves, s.used.as_secs_f64(), avg, s.max_move.as_millis(), min_rem, s.flags);
+}
+
+fn print_score_stats(name: &str, s: &ScoreStats) {
+    let abs_cp = if s.cp_n == 0 { 0.0 } else { s.abs_cp / s.cp_n as f64 };
+    let brier = if s.n == 0 { f64::NAN } else { s.sq_err / s.n as f64 };
+    println!("{:<4} score: mean_abs_cp={:.0} expected_score_brier={:.4} positions={}", name, abs_cp, brier, s.n);
+}
+
+fn load_wdl(flag: &str, path: Option<&str>) -> WdlModel {
+    path.map(WdlModel::load_json).transpose().unwrap_or_else(|e| { eprintln!("{}: {}", flag, e); std::process::exit(2); }).unwrap_or_default()
+}
+
+fn main() {
+    let args = Args::parse();
+    let tc = match args.tc.as_deref().map(parse_tc) {
+        Some(Ok(t)) => Some(t),
+        Some(Err(e)) => { eprintln!("--tc: {}", e); std::process::exit(2); }
+        None => None,
+    };
+    if let Some(path) = &args.options {
+        match EngineOptions::load_json(path) {
+            Ok(o) => EngineOptions::set_global(o),
+            Err(e) => { eprintln!("--options {}: {}", path, e); std::process::exit(2); }
+        }
+    }
+    // Clocked games: measure this machine's speed before the engines read their time configs
+    if tc.is_some() { Calibration::measure(&EngineOptions::global(), Duration::from_millis(100)).set_global(); }
+    let file_presets = match args.presets.as_ref().map(load_presets) {
+        Some(Ok(p)) => p,
+        Some(Err(e)) => { eprintln!("--presets: {}", e); std::process::exit(2); }
+        None => Vec::new(),
+    };
+    let mut defaults = EngineOptions::global();
+    defaults.depth = args.depth;
+    if args.movetime.is_some() { defaults.movetime_ms = args.movetime; }
+    let resolve = |preset: Option<&str>, spec: &str| -> Result<EngineOptions, String> {
+        let mut opts = defaults.clone();
+        if let Some(name) = preset { opts.apply_spec(find_preset(name, &file_presets)?).map_err(|e| format!("preset '{}': {}", name, e))?; }
+        opts.apply_spec(spec).map_err(|e| e.to_string())?;
+        Ok(opts)
+    };
+    let base_cfg = resolve(args.base_preset.as_deref(), &args.base).unwrap_or_else(|e| { eprintln!("--base: {}", e); std::process::exit(2); });
+    let exp_cfg = resolve(args.exp_preset.as_deref(), &args.exp).unwrap_or_else(|e| { eprintln!("--exp: {}", e); std::process::exit(2); });
+    println!("base: {}", base_cfg.describe());
+    println!("exp:  {}", exp_cfg.describe());
+
+    let mut gen = OpeningGenerator::new(args.seed, args.random_plies.div_ceil(2), args.random_plies);
+    let mut frc_rng = rand::rngs::SmallRng::seed_from_u64(args.seed);
+    let openings = match args.openings.as_ref().map(piebot::io::fen::load_openings) {
+        Some(Ok(o)) => o,
+        Some(Err(e)) => { eprintln!("--openings: {}", e); std::process::exit(2); }
+        None => Vec::new(),
+    };
+    if let Some(dir) = &args.timeline {
+        if let Err(e) = std::fs::create_dir_all(dir) { eprintln!("--timeline {}: {}", dir.display(), e); std::process::exit(2); }
+    }
+    // The reviewer searches with the shared options, neither engine's overrides
+    let mut reviewer = (args.cpl_depth > 0).then(|| {
+        let opts = EngineOptions { depth: args.cpl_depth, movetime_ms: None, ..defaults.clone() };
+        let searcher = opts.build_searcher().unwrap_or_else(|e| { eprintln!("--cpl-depth: {}", e); std::process::exit(2); });
+        Reviewer::new(searcher, opts.search_params())
+    });
+    let mut base = Engine::new("base", &base_cfg, load_wdl("--base-wdl", args.base_wdl.as_deref())).unwrap_or_else(|e| { eprintln!("base: {}", e); std::process::exit(2); });
+    let mut exp = Engine::new("exp", &exp_cfg, load_wdl("--exp-wdl", args.exp_wdl.as_deref())).unwrap_or_else(|e| { eprintln!("exp: {}", e); std::process::exit(2); });
+    let (mut w, mut d, mut l) = (0u32, 0u32, 0u32);
+    let mut adjudicated = [0u32; 4];
+    let mut game = GameState::default();
+    for g in 0..args.games {
+        // New opening every pair (the second game replays it); exp plays white in even games
+        if g % 2 == 0 {
+            let stm = if g % 4 == 0 { Color::White } else { Color::Black };
+            let start = if !openings.is_empty() { openings[(g / 2) % openings.len()].clone() }
+                else if args.variant == "frc" { random_frc(&mut frc_rng) }
+                else if args.variant == "dfrc" { random_dfrc(&mut frc_rng) }
+                else if args.random_plies == 0 { startpos() }
+                else { gen.generate(Some(stm)).unwrap_or_else(startpos) };
+            game.reset_to(&start);
+        }
+        let exp_white = g % 2 == 0;
+        let (white, black) = if exp_white { (&mut exp, &mut base) } else { (&mut base, &mut exp) };
+        let mut timeline = args.timeline.as_ref().map(|_| GameTimeline::new(g + 1, game.start(), white.name, black.name));
+        let (outcome, plies, flagged) = play_game(&mut game, white, black, tc, args.max_plies, !args.no_adjudicate, timeline.as_mut());
+        if let (Some(dir), Some(mut t)) = (&args.timeline, timeline) {
+            t.result = Some(match outcome { Outcome::WhiteWins => "1-0", Outcome::BlackWins => "0-1", Outcome::Draw(_) => "1/2-1/2" }.to_string());
+            let path = dir.join(format!("game_{:03}.json", g + 1));
+            if let Err(e) = t.write(&path) { eprintln!("write {}: {}", path.display(), e); }
+        }
+        let exp_score = match outcome {
+            Outcome::WhiteWins => if exp_white { 1 } else { -1 },
+            Outcome::BlackWins => if exp_white { -1 } else { 1 },
+            Outcome::Draw(_) => 0,
+        };
+        match exp_score { 1 => w += 1, -1 => l += 1, _ => d += 1 }
+        exp.scores.settle(0.5 + 0.5 * exp_score as f64);
+        base.scores.settle(0.5 - 0.5 * exp_score as f64);
+        if let Outcome::Draw(Some(a)) = outcome { adjudicated[a as usize] += 1; }
+        let flag = flagged.map(|c| format!(" ({:?} lost on time)", c)).unwrap_or_default();
+        println!("game {:>3}: exp={} plies={} result={:?}{}", g + 1, if exp_white { "white" } else { "black" }, plies, outcome, flag);
+        if let Some(r) = reviewer.as_mut() {
+            let [white_cpl, black_cpl] = r.review_game(game.start(), game.moves());
+            let (exp_cpl, base_cpl) = if exp_white { (white_cpl, black_cpl) } else { (black_cpl, white_cpl) };
+            println!("          cpl exp: {}", exp_cpl.summary());
+            println!("          cpl base: {}", base_cpl.summary());
+            exp.cpl.add(&exp_cpl);
+            base.cpl.add(&base_cpl);
+        }
+    }
+    let n = (w + d + l).max(1) as f64;
+    let score = (w as f64 + 0.5 * d as f64) / n;
+    let elo = if score <= 0.0 || score >= 1.0 { f64::NAN } else { 400.0 * (score / (1.0 - score)).log10() };
+    println!("exp vs base: +{} ={} -{} score={:.1}% elo={:+.0}", w, d, l, 100.0 * score, elo);
+    println!("adjudicated draws: threefold={} fifty={} material={} max_plies={}", adjudicated[0], adjudicated[1], adjudicated[2], adjudicated[3]);
+    print_time_stats("base", &base.stats);
+    print_time_stats("exp", &exp.stats);
+    print_score_stats("base", &base.scores);
+    print_score_stats("exp", &exp.scores);
+    if let Some(r) = &reviewer {
+        println!("base cpl (depth {}): {}", r.depth(), base.cpl.summary());
+        println!("exp  cpl (depth {}): {}", r.depth(), exp.cpl.summary());
+    }
+}
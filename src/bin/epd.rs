@@ -0,0 +1,67 @@
+use clap::Parser;
+use piebot::io::epd::load_epd;
+use piebot::search::options::EngineOptions;
+use piebot::search::suite::{mate_histogram, run_case};
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-epd", about = "Run an EPD test suite (bm / am / dm opcodes)")]
+struct Args {
+    /// EPD file
+    suite: String,
+
+    /// EngineOptions JSON to start from; the flags below override it
+    #[arg(long)]
+    options: Option<String>,
+
+    /// Search depth for cases without dm
+    #[arg(long, default_value_t = 8)]
+    depth: u32,
+
+    /// Movetime per case in milliseconds instead of a fixed depth
+    #[arg(long)]
+    movetime: Option<u64>,
+
+    /// dm cases search to their mate depth plus this many plies; -1 uses --depth/--movetime
+    #[arg(long, default_value_t = 2, allow_hyphen_values = true)]
+    mate_margin: i32,
+
+    /// Print only failures and the summary
+    #[arg(long, default_value_t = false)]
+    quiet: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if let Some(path) = &args.options { EngineOptions::set_global(EngineOptions::load_json(path)?); }
+    let mut opts = EngineOptions::global();
+    opts.depth = args.depth;
+    opts.movetime_ms = args.movetime;
+    if args.movetime.is_some() { opts.depth = 64; }
+    let cases = load_epd(&args.suite)?;
+    let mut searcher = opts.build_searcher()?;
+    let params = opts.search_params();
+    let margin = u32::try_from(args.mate_margin).ok();
+    let mut results = Vec::with_capacity(cases.len());
+    for case in &cases {
+        searcher.new_game();
+        let r = run_case(&mut searcher, case, params, margin);
+        if !args.quiet || !r.solved() {
+            let mate = match (r.mate_expected, r.mate_found) {
+                (Some(e), Some(f)) => format!(" mate {} (dm {})", f, e),
+                (Some(e), None) => format!(" no mate (dm {})", e),
+                (None, Some(f)) => format!(" mate {}", f),
+                (None, None) => format!(" cp {}", r.score_cp),
+            };
+            println!("{:<4} {:<24} {:<6} depth {}{}", if r.solved() { "ok" } else { "FAIL" }, r.id, r.bestmove.as_deref().unwrap_or("none"), r.depth, mate);
+        }
+        results.push(r);
+    }
+    let solved = results.iter().filter(|r| r.solved()).count();
+    println!("solved {}/{}", solved, results.len());
+    let hist = mate_histogram(&results);
+    if !hist.is_empty() {
+        println!("{:>4} {:>6} {:>6} {:>10} {:>6}", "dm", "cases", "solved", "wrong dist", "missed");
+        for (n, b) in &hist { println!("{:>4} {:>6} {:>6} {:>10} {:>6}", n, b.total, b.solved, b.wrong_distance, b.missed); }
+    }
+    Ok(())
+}
@@ -1,12 +1,22 @@
 use cozy_chess::Board;
-use piebot::perft::perft;
+use piebot::perft::{perft, perft_detail};
 use std::env;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    // `perft detail <depth> [fen]` prints per-depth move statistics
+    let detail = args.get(1).is_some_and(|a| a == "detail");
+    if detail { args.remove(1); }
     let depth: u32 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(3);
     let fen_opt = args.get(2).map(|s| s.to_string());
     let board = if let Some(fen) = fen_opt { Board::from_fen(&fen, false).expect("Invalid FEN") } else { Board::default() };
+    if detail {
+        println!("{:>5} {:>12} {:>10} {:>8} {:>8} {:>10} {:>10} {:>10}", "depth", "nodes", "captures", "e.p.", "castles", "promotions", "checks", "checkmates");
+        for (d, s) in perft_detail(&board, depth).iter().enumerate() {
+            println!("{:>5} {:>12} {:>10} {:>8} {:>8} {:>10} {:>10} {:>10}", d + 1, s.nodes, s.captures, s.en_passant, s.castles, s.promotions, s.checks, s.checkmates);
+        }
+        return;
+    }
     let nodes = perft(&board, depth);
     println!("nodes: {nodes}");
 }
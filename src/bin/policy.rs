@@ -0,0 +1,99 @@
+use clap::{Parser, Subcommand};
+use cozy_chess::{Board, Move};
+use piebot::eval::policy::PolicyNet;
+use piebot::io::pgn::{parse_pgn, PgnGame};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-policy", about = "Train and test the move-ordering policy net on PGN games")]
+struct Args {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Fit a policy net to the moves played in a PGN file and save it (PIEPOL01)
+    Train {
+        #[arg(long)]
+        pgn: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+        /// Start from this net instead of a random one
+        #[arg(long)]
+        init: Option<PathBuf>,
+        #[arg(long, default_value_t = 32)]
+        hidden: usize,
+        #[arg(long, default_value_t = 4)]
+        epochs: u32,
+        #[arg(long, default_value_t = 0.01)]
+        lr: f32,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+    /// Top-1 accuracy of a net on the moves of a PGN file
+    Test {
+        #[arg(long)]
+        net: PathBuf,
+        #[arg(long)]
+        pgn: PathBuf,
+    },
+}
+
+fn load_games(path: &PathBuf) -> anyhow::Result<Vec<PgnGame>> {
+    let games = parse_pgn(&std::fs::read_to_string(path)?);
+    if games.iter().all(|g| g.moves.is_empty()) { anyhow::bail!("no moves in {}", path.display()); }
+    Ok(games)
+}
+
+// Every (position, move played) pair of the games
+fn positions(games: &[PgnGame]) -> impl Iterator<Item = (Board, Move)> + '_ {
+    games.iter().flat_map(|g| {
+        let mut board = g.start.clone();
+        g.moves.iter().map(move |&m| {
+            let before = board.clone();
+            board.play(m);
+            (before, m)
+        })
+    })
+}
+
+fn main() -> anyhow::Result<()> {
+    match Args::parse().cmd {
+        Cmd::Train { pgn, out, init, hidden, epochs, lr, seed } => {
+            let games = load_games(&pgn)?;
+            let mut net = match init {
+                Some(p) => PolicyNet::load(p)?,
+                None => PolicyNet::new(hidden.max(1), seed),
+            };
+            for epoch in 1..=epochs {
+                let (mut n, mut loss, mut top) = (0u64, 0f64, 0u64);
+                for (board, m) in positions(&games) {
+                    if let Some((l, t)) = net.train_position(&board, m, lr) {
+                        n += 1;
+                        loss += f64::from(l);
+                        top += u64::from(t);
+                    }
+                }
+                println!("epoch {} positions {} loss {:.4} top1 {:.1}%", epoch, n, loss / n.max(1) as f64, 100.0 * top as f64 / n.max(1) as f64);
+            }
+            net.save(&out)?;
+            println!("saved {} (hidden {}, hash {:016x})", out.display(), net.hidden, net.fingerprint());
+            Ok(())
+        }
+        Cmd::Test { net, pgn } => {
+            let net = PolicyNet::load(net)?;
+            let (mut n, mut top) = (0u64, 0u64);
+            for (board, m) in positions(&load_games(&pgn)?) {
+                let mut moves: Vec<Move> = Vec::new();
+                board.generate_moves(|ml| { moves.extend(ml); false });
+                let p = net.probabilities(&board, &moves);
+                let best = moves.iter().zip(&p).max_by(|a, b| a.1.total_cmp(b.1)).map(|(&mv, _)| mv);
+                n += 1;
+                top += u64::from(best == Some(m));
+            }
+            println!("positions {} top1 {:.1}%", n, 100.0 * top as f64 / n.max(1) as f64);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use clap::Parser;
+use cozy_chess::Board;
+use piebot::io::fen::{load_fen_suite, BENCH_SUITE};
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::search::profile::SearchProfile;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-profile", version, about = "Profile where PieBot search time goes (movegen, ordering, eval, TT)")]
+struct Args {
+    /// Fixed search depth per position
+    #[arg(long, default_value_t = 6)]
+    depth: u32,
+
+    /// Optional movetime per position in milliseconds (overrides depth when set)
+    #[arg(long)]
+    movetime: Option<u64>,
+
+    /// Optional suite file with FEN per line or JSONL {"fen":...}
+    #[arg(long)]
+    suite: Option<String>,
+
+    /// Transposition table size in MB (approximate)
+    #[arg(long, default_value_t = 64)]
+    hash_mb: usize,
+
+    /// Disable history/counter-move ordering
+    #[arg(long, default_value_t = false)]
+    no_history: bool,
+
+    /// Disable killer moves
+    #[arg(long, default_value_t = false)]
+    no_killers: bool,
+
+    /// Disable late move reductions
+    #[arg(long, default_value_t = false)]
+    no_lmr: bool,
+
+    /// Disable null-move pruning
+    #[arg(long, default_value_t = false)]
+    no_nullmove: bool,
+}
+
+fn pct(part: Duration, total: Duration) -> f64 {
+    if total.is_zero() { 0.0 } else { 100.0 * part.as_secs_f64() / total.as_secs_f64() }
+}
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+    let fens: Vec<String> = match &args.suite {
+        Some(path) => load_fen_suite(path),
+        None => BENCH_SUITE.iter().map(|s| s.to_string()).collect(),
+    };
+
+    let params = SearchParams {
+        depth: args.depth,
+        movetime: args.movetime.map(Duration::from_millis),
+        use_tt: true,
+        order_captures: true,
+        use_history: !args.no_history,
+        use_killers: !args.no_killers,
+        use_lmr: !args.no_lmr,
+        use_nullmove: !args.no_nullmove,
+        threads: 1,
+        ..Default::default()
+    };
+
+    let mut total = SearchProfile::default();
+    let mut total_time = Duration::ZERO;
+    let mut total_nodes = 0u64;
+    for (i, fen) in fens.iter().enumerate() {
+        let board = match Board::from_fen(fen, false) {
+            Ok(b) => b,
+            Err(_) => { eprintln!("skipping invalid FEN: {}", fen); continue; }
+        };
+        let mut s = Searcher::builder().hash_mb(args.hash_mb).profiling(true).build().unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(2); });
+        let res = s.search_with_params(&board, params);
+        println!("pos {:>2}: depth={} nodes={} time={}ms nps={}", i + 1, res.depth, res.nodes, res.time_ms, res.nps);
+        total.merge(&s.profile());
+        total_time += Duration::from_millis(res.time_ms);
+        total_nodes += res.nodes;
+    }
+
+    let accounted = total.movegen + total.ordering + total.eval + total.tt;
+    let other = total_time.saturating_sub(accounted);
+    println!();
+    println!("{:<12} {:>10} {:>7}", "phase", "ms", "%");
+    for (name, d) in [
+        ("movegen", total.movegen),
+        ("ordering", total.ordering),
+        ("eval", total.eval),
+        ("tt", total.tt),
+        ("other", other),
+    ] {
+        println!("{:<12} {:>10.1} {:>6.1}%", name, d.as_secs_f64() * 1000.0, pct(d, total_time));
+    }
+    println!("{:<12} {:>10}", "total", total_time.as_millis());
+    println!();
+    let tree = (total.main_nodes + total.qsearch_nodes).max(1);
+    println!("main nodes    {:>12} ({:.1}%)", total.main_nodes, 100.0 * total.main_nodes as f64 / tree as f64);
+    println!("qsearch nodes {:>12} ({:.1}%)", total.qsearch_nodes, 100.0 * total.qsearch_nodes as f64 / tree as f64);
+    println!("reported nodes {:>11}", total_nodes);
+}
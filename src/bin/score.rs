@@ -0,0 +1,169 @@
+use clap::{Parser, ValueEnum};
+use piebot::io::fen::parse_fen_line;
+use piebot::search::label::{label_positions, Label, LabelMode};
+use piebot::search::options::EngineOptions;
+use piebot::search::zobrist;
+use piebot::selfplay::{game_positions, read_games, read_shard, SHARD_MAGIC, SHARD_MAGIC_V1};
+use std::collections::HashMap;
+use std::io::{BufRead, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format { Csv, Jsonl }
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-score", about = "Label positions with qsearch or fixed-depth search scores (FEN,score CSV or JSONL)")]
+struct Args {
+    /// Input files: one FEN or EPD per line, JSONL with a "fen" field, or selfplay
+    /// shards (positions rebuilt from the games.jsonl beside them). Reads stdin
+    /// when none are given or for "-"
+    inputs: Vec<PathBuf>,
+
+    /// Search depth per position; 0 scores with qsearch only
+    #[arg(long, default_value_t = 0)]
+    depth: u32,
+
+    /// Worker threads, each with its own searcher
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
+
+    /// Hash per worker in MB; cleared before every position
+    #[arg(long, default_value_t = 8)]
+    hash_mb: usize,
+
+    /// EngineOptions JSON to start from
+    #[arg(long)]
+    options: Option<String>,
+
+    /// Option overrides as comma-separated key=value pairs, e.g. "eval=nnue,nnue_quant=net.bin"
+    #[arg(long)]
+    set: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    /// Report scores from White's point of view instead of the side to move's
+    #[arg(long)]
+    white: bool,
+
+    /// Output file (default stdout)
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+// Positions labelled per batch; labels are written as each batch finishes
+const BATCH: usize = 1024;
+
+// Labels positions a batch at a time and streams them to the output in input order
+struct Scorer<'a> {
+    opts: &'a EngineOptions,
+    args: &'a Args,
+    pending: Vec<cozy_chess::Board>,
+    out: Box<dyn Write>,
+    scored: usize,
+}
+
+impl Scorer<'_> {
+    fn push(&mut self, board: cozy_chess::Board) -> anyhow::Result<()> {
+        self.pending.push(board);
+        if self.pending.len() >= BATCH * self.args.threads.max(1) { self.flush()?; }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        if self.pending.is_empty() { return Ok(()); }
+        let mode = if self.args.depth == 0 { LabelMode::Qsearch } else { LabelMode::Depth(self.args.depth) };
+        for l in &label_positions(&self.pending, self.opts, mode, self.args.threads)? { write_label(&mut self.out, l, self.args.format, self.args.white)?; }
+        self.scored += self.pending.len();
+        self.pending.clear();
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+// Whether `path` starts with a selfplay shard magic
+fn is_shard(path: &Path) -> std::io::Result<bool> {
+    let mut magic = [0u8; 8];
+    let n = std::fs::File::open(path)?.read(&mut magic)?;
+    Ok(n == magic.len() && (&magic == SHARD_MAGIC || &magic == SHARD_MAGIC_V1))
+}
+
+// Positions of a shard, looked up by key among the positions of the games.jsonl in its
+// directory (`games`, cached per directory); records with no such position are skipped
+fn read_shard_positions(path: &Path, games: &mut HashMap<PathBuf, HashMap<u64, cozy_chess::Board>>, scorer: &mut Scorer) -> anyhow::Result<usize> {
+    let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    if !games.contains_key(&dir) {
+        let gs = read_games(&dir).map_err(|e| anyhow::anyhow!("{}: {}", dir.display(), e))?;
+        games.insert(dir.clone(), gs.iter().flat_map(game_positions).map(|b| (zobrist::compute(&b), b)).collect());
+    }
+    let positions = &games[&dir];
+    let mut skipped = 0;
+    for (i, r) in read_shard(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?.iter().enumerate() {
+        match positions.get(&r.key) {
+            Some(b) => scorer.push(b.clone())?,
+            None => { eprintln!("{}: record {}: no position with key {:016x} in the games file", path.display(), i, r.key); skipped += 1; }
+        }
+    }
+    Ok(skipped)
+}
+
+// Position lines of one input; unparseable lines are reported and skipped
+fn read_positions(name: &str, input: impl BufRead, scorer: &mut Scorer) -> anyhow::Result<usize> {
+    let mut skipped = 0;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') { continue; }
+        let parsed = if l.starts_with('{') {
+            serde_json::from_str::<serde_json::Value>(l).map_err(|e| e.to_string())
+                .and_then(|v| v.get("fen").and_then(|f| f.as_str()).map(parse_fen_line).unwrap_or_else(|| Err("no \"fen\" field".to_string())))
+        } else {
+            parse_fen_line(l)
+        };
+        match parsed {
+            Ok(b) => scorer.push(b)?,
+            Err(e) => { eprintln!("{}:{}: {}", name, i + 1, e); skipped += 1; }
+        }
+    }
+    Ok(skipped)
+}
+
+fn write_label(out: &mut impl Write, l: &Label, format: Format, white: bool) -> anyhow::Result<()> {
+    let score = if white { l.white_score() } else { l.score };
+    match format {
+        Format::Csv => writeln!(out, "{},{}", l.fen, score)?,
+        Format::Jsonl => writeln!(out, "{}", serde_json::to_string(&Label { score, ..l.clone() })?)?,
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut opts = match &args.options { Some(path) => EngineOptions::load_json(path)?, None => EngineOptions::global() };
+    opts.hash_mb = args.hash_mb;
+    if let Some(spec) = &args.set { opts.apply_spec(spec)?; }
+
+    let start = Instant::now();
+    let out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+    let mut scorer = Scorer { opts: &opts, args: &args, pending: Vec::new(), out, scored: 0 };
+    let mut games = HashMap::new();
+    let mut skipped = 0;
+    if args.inputs.is_empty() || args.inputs.iter().any(|p| p.as_os_str() == "-") {
+        skipped += read_positions("stdin", std::io::stdin().lock(), &mut scorer)?;
+    }
+    for path in args.inputs.iter().filter(|p| p.as_os_str() != "-") {
+        if is_shard(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))? {
+            skipped += read_shard_positions(path, &mut games, &mut scorer)?;
+            continue;
+        }
+        let f = std::fs::File::open(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        skipped += read_positions(&path.display().to_string(), std::io::BufReader::new(f), &mut scorer)?;
+    }
+    scorer.flush()?;
+    eprintln!("scored {} positions ({} skipped) in {:.1}s", scorer.scored, skipped, start.elapsed().as_secs_f64());
+    Ok(())
+}
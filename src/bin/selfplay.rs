@@ -1,5 +1,10 @@
 use clap::Parser;
-use piebot::selfplay::{SelfPlayParams, generate_games, write_shards};
+use piebot::selfplay::{SelfPlayParams, ShardMeta, generate_games, write_games, write_shards};
+use piebot::selfplay::ab::{run_ab, AbConfig, Sprt, SprtStatus};
+use piebot::selfplay::epd::write_epd;
+use piebot::selfplay::openings::{OpeningMix, OpeningSource};
+use piebot::search::label::LabelMode;
+use piebot::search::options::EngineOptions;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -39,10 +44,65 @@ struct Args {
     openings: Option<PathBuf>,
     #[arg(long, default_value_t = 0.1)]
     temperature_tau_final: f32,
+    /// Without --openings, start from filtered random walks of up to this many plies (0 = start position)
+    #[arg(long, default_value_t = 0)]
+    random_opening_plies: usize,
+    /// Weighted opening source, repeatable: kind[:arg][,w=<weight>][,plies=<max>] with
+    /// kind start|random:<plies>|book:<file>|tactical:<file>|frc|dfrc; overrides the two options above
+    #[arg(long = "source")]
+    sources: Vec<OpeningSource>,
+    /// Also write every shard position as EPD with the result (c0) and a score (ce)
+    #[arg(long)]
+    epd: Option<PathBuf>,
+    /// Search depth of the EPD scores; 0 scores with qsearch only
+    #[arg(long, default_value_t = 0)]
+    epd_depth: u32,
+    /// Play paired A/B games instead of generating data: this option spec (on top of
+    /// the shared options) against --ab-base, --games games at --depth
+    #[arg(long)]
+    ab_exp: Option<String>,
+    /// Option spec of the A/B baseline
+    #[arg(long, default_value = "")]
+    ab_base: String,
+    /// SPRT Elo hypotheses for A/B runs as elo0,elo1; the run stops once one is
+    /// accepted and exits with status 1 when the experiment fails (H0)
+    #[arg(long, default_value = "0,10")]
+    sprt: String,
+}
+
+fn ab_match(a: &Args, exp_spec: &str) -> anyhow::Result<()> {
+    let params = |spec: &str| -> anyhow::Result<_> {
+        let mut o = EngineOptions { depth: a.depth, movetime_ms: a.movetime_ms, threads: 1, ..EngineOptions::global() };
+        o.apply_spec(spec)?;
+        Ok(o.search_params())
+    };
+    let (elo0, elo1) = a.sprt.split_once(',').and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| anyhow::anyhow!("--sprt: expected elo0,elo1, got '{}'", a.sprt))?;
+    let cfg = AbConfig {
+        base: params(&a.ab_base)?,
+        exp: params(exp_spec)?,
+        max_pairs: a.games.div_ceil(2),
+        max_plies: a.max_plies,
+        random_plies: a.random_opening_plies,
+        seed: a.seed,
+        sprt: Some(Sprt { elo0, elo1, ..Sprt::default() }),
+    };
+    let (lower, upper) = cfg.sprt.unwrap_or_default().bounds();
+    let r = run_ab(&cfg, |r| {
+        let t = r.tally;
+        eprintln!("games {:>4}: +{} ={} -{} llr={:.2} ({:.2}, {:.2})", t.games(), t.wins, t.draws, t.losses, r.llr.unwrap_or(0.0), lower, upper);
+        true
+    });
+    let t = r.tally;
+    let elo = t.elo().map_or("n/a".to_string(), |e| format!("{:+.0}", e));
+    println!("exp vs base: +{} ={} -{} score={:.1}% elo={} sprt={:?}", t.wins, t.draws, t.losses, 100.0 * t.score(), elo, r.status);
+    if r.status == SprtStatus::AcceptH0 { std::process::exit(1); }
+    Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
     let a = Args::parse();
+    if let Some(spec) = &a.ab_exp { return ab_match(&a, spec); }
     let params = SelfPlayParams {
         games: a.games,
         max_plies: a.max_plies,
@@ -59,11 +119,21 @@ fn main() -> anyhow::Result<()> {
         temperature_moves: a.temperature_moves,
         openings_path: a.openings,
         temperature_tau_final: a.temperature_tau_final,
+        random_opening_plies: a.random_opening_plies,
+        opening_sources: a.sources,
     };
+    // Fail on a bad openings file now rather than falling back mid-run
+    OpeningMix::new(&params.curriculum(), params.seed)?;
     eprintln!("Generating {} games (depth={}, threads={}, engine={}, tau={}, dir_eps={})", a.games, a.depth, a.threads, a.use_engine, a.temperature_tau, a.dirichlet_epsilon);
     let games = generate_games(&params);
     eprintln!("Writing shards to {}", a.out.display());
-    let shards = write_shards(&games, &a.out, a.max_records_per_shard)?;
+    let shards = write_shards(&games, &a.out, a.max_records_per_shard, &ShardMeta::from_params(&params))?;
     eprintln!("Wrote {} shards", shards.len());
+    write_games(&games, &a.out)?;
+    if let Some(path) = &a.epd {
+        let mode = if a.epd_depth == 0 { LabelMode::Qsearch } else { LabelMode::Depth(a.epd_depth) };
+        let lines = write_epd(&games, path, &EngineOptions::global(), mode, a.threads)?;
+        eprintln!("Wrote {} EPD positions to {}", lines, path.display());
+    }
     Ok(())
 }
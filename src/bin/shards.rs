@@ -0,0 +1,78 @@
+use clap::{Parser, Subcommand};
+use piebot::search::label::LabelMode;
+use piebot::search::options::EngineOptions;
+use piebot::selfplay::epd::write_epd;
+use piebot::selfplay::read_games;
+use piebot::selfplay::stats::shard_stats;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-shards", about = "Inspect self-play shard directories")]
+struct Args {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Position count, duplicates, result and side-to-move balance, game length;
+    /// warns on shards with bad magic or truncated records
+    Stats {
+        dir: PathBuf,
+        /// Fail instead of warning when a shard is unreadable
+        #[arg(long)]
+        strict: bool,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write every shard position as EPD with the result (c0) and a score (ce),
+    /// rebuilt from the games.jsonl selfplay saves beside the shards
+    Epd {
+        dir: PathBuf,
+        /// Output EPD file
+        #[arg(long)]
+        out: PathBuf,
+        /// Search depth of the scores; 0 scores with qsearch only
+        #[arg(long, default_value_t = 0)]
+        depth: u32,
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    match Args::parse().cmd {
+        Cmd::Stats { dir, strict, json } => {
+            let st = shard_stats(&dir)?;
+            for p in &st.bad_files { eprintln!("warning: {}: {}", p.path.display(), p.reason); }
+            if strict && !st.bad_files.is_empty() {
+                anyhow::bail!("{} of {} shards unreadable", st.bad_files.len(), st.bad_files.len() + st.files);
+            }
+            if st.files == 0 { anyhow::bail!("no readable shards in {}", dir.display()); }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&st)?);
+                return Ok(());
+            }
+            println!("files          {} ({} bad)", st.files, st.bad_files.len());
+            println!("positions      {}", st.positions);
+            println!("unique keys    {} ({:.1}%)", st.unique_keys, 100.0 * st.unique_ratio());
+            println!("results        white {:.1}%  draw {:.1}%  black {:.1}%", 100.0 * st.result_share(2), 100.0 * st.result_share(1), 100.0 * st.result_share(0));
+            println!("side to move   white {:.1}%", 100.0 * st.white_to_move());
+            match st.avg_game_plies {
+                Some(p) => println!("avg game plies {:.1}", p),
+                None => println!("avg game plies n/a (headerless v1 shards)"),
+            }
+            println!("conflicting    {} keys with more than one result", st.conflicting_keys);
+            println!("collisions     {:.2e} expected among unique keys", st.expected_collisions());
+            Ok(())
+        }
+        Cmd::Epd { dir, out, depth, threads } => {
+            let games = read_games(&dir).map_err(|e| anyhow::anyhow!("{}: {}", dir.display(), e))?;
+            let mode = if depth == 0 { LabelMode::Qsearch } else { LabelMode::Depth(depth) };
+            let lines = write_epd(&games, &out, &EngineOptions::global(), mode, threads)?;
+            eprintln!("Wrote {} EPD positions from {} games to {}", lines, games.len(), out.display());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use clap::Parser;
+use piebot::io::epd::epd_result;
+use piebot::io::fen::parse_fen_line;
+use piebot::search::eval::DEVELOPMENT;
+use piebot::search::texel::{fit_k, loss, tune_development, DevelopmentSample};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "piebot-tune", about = "Texel-tune the classical eval's development weights on EPD positions with c0 results")]
+struct Args {
+    /// EPD files whose lines carry the game result as c0 (e.g. from selfplay --epd)
+    inputs: Vec<PathBuf>,
+    /// Scaling constant of the expected score; fitted to the current weights when omitted
+    #[arg(long)]
+    k: Option<f64>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let a = Args::parse();
+    let mut samples = Vec::new();
+    for path in &a.inputs {
+        let text = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        for (i, line) in text.lines().enumerate() {
+            let l = line.trim();
+            if l.is_empty() || l.starts_with('#') { continue; }
+            let board = parse_fen_line(l).map_err(|e| anyhow::anyhow!("{}:{}: {}", path.display(), i + 1, e))?;
+            let result = epd_result(l).ok_or_else(|| anyhow::anyhow!("{}:{}: no c0 result", path.display(), i + 1))?;
+            samples.push(DevelopmentSample::new(&board, result));
+        }
+    }
+    if samples.is_empty() { anyhow::bail!("no positions"); }
+    let k = a.k.unwrap_or_else(|| fit_k(&samples, &DEVELOPMENT));
+    let tuned = tune_development(&samples, DEVELOPMENT, k);
+    eprintln!("{} positions, k={:.3}: loss {:.6} -> {:.6}", samples.len(), k, loss(&samples, &DEVELOPMENT, k), loss(&samples, &tuned, k));
+    println!("{}", serde_json::to_string(&tuned)?);
+    Ok(())
+}
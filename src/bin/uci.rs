@@ -1,7 +1,39 @@
-use piebot::uci::UciEngine;
+use std::io;
+use std::time::Duration;
+use piebot::search::options::EngineOptions;
+use piebot::uci::{options_table, Backend, UciEngine};
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    // Optional: --options <file.json> replaces the engine defaults (EngineOptions)
+    if let Some(i) = args.iter().position(|a| a == "--options") {
+        match args.get(i + 1).map(|p| EngineOptions::load_json(p)) {
+            Some(Ok(o)) => EngineOptions::set_global(o),
+            Some(Err(e)) => eprintln!("--options: {}", e),
+            None => eprintln!("--options requires a file"),
+        }
+    }
     let mut engine = UciEngine::new();
-    engine.run_loop();
+    // Optional: --level N (1..=20) for a weakened casual opponent
+    if let Some(i) = args.iter().position(|a| a == "--level") {
+        if let Some(level) = args.get(i + 1).and_then(|s| s.parse::<u8>().ok()) { engine.set_skill_level(level); }
+    }
+    // Optional: --backend cozy|pleco (also settable via 'setoption name Backend')
+    if let Some(i) = args.iter().position(|a| a == "--backend") {
+        match args.get(i + 1).map(|s| s.parse::<Backend>()) {
+            Some(Ok(b)) => engine.set_backend(b),
+            Some(Err(e)) => eprintln!("{}", e),
+            None => eprintln!("--backend requires a value"),
+        }
+    }
+    // Optional: --dump-options prints every option, grouped, with its default and exits
+    if args.iter().any(|a| a == "--dump-options") {
+        print!("{}", options_table(&engine.uci_options()));
+        return;
+    }
+    // Speed probe (~100ms) with the configured backend and eval, so the first clocked
+    // move is budgeted for this machine; --no-calibrate skips it
+    if !args.iter().any(|a| a == "--no-calibrate") { engine.calibrate(Duration::from_millis(100)); }
+    engine.run_loop(io::BufReader::new(io::stdin()), io::stdout());
 }
 
@@ -0,0 +1,73 @@
+#[cfg(feature = "board-pleco")]
+use clap::Parser;
+
+#[cfg(feature = "board-pleco")]
+#[derive(Parser, Debug)]
+#[command(name = "piebot-verify", about = "Differential check of the cozy and pleco backends (moves, FEN, keys, SEE, eval)")]
+struct Args {
+    /// Start position(s); defaults to the bench suite
+    #[arg(long)]
+    fen: Vec<String>,
+
+    /// FEN suite file (one FEN per line, or JSONL with a "fen" field)
+    #[arg(long)]
+    suite: Option<String>,
+
+    /// Perft depth walked from each start position (0 skips the perft walk)
+    #[arg(long, default_value_t = 3)]
+    depth: u32,
+
+    /// Random games per start position
+    #[arg(long, default_value_t = 50)]
+    games: usize,
+
+    /// Plies per random game
+    #[arg(long, default_value_t = 120)]
+    plies: usize,
+
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+
+    /// Compare NNUE evals with this quantized network instead of material
+    #[arg(long)]
+    nnue_quant: Option<String>,
+
+    /// Reproducers printed per check
+    #[arg(long, default_value_t = 5)]
+    show: usize,
+}
+
+#[cfg(feature = "board-pleco")]
+fn main() -> anyhow::Result<()> {
+    use piebot::board::verify::Verifier;
+    use piebot::eval::nnue::loader::QuantNnue;
+    use piebot::io::fen::{load_fen_suite, BENCH_SUITE};
+    use std::collections::BTreeMap;
+
+    let args = Args::parse();
+    let mut fens = args.fen.clone();
+    if let Some(path) = &args.suite { fens.extend(load_fen_suite(path)); }
+    if fens.is_empty() { fens = BENCH_SUITE.iter().map(|f| f.to_string()).collect(); }
+    let mut v = Verifier::new();
+    if let Some(path) = &args.nnue_quant { v = v.with_nnue_quant(QuantNnue::load_quantized(path)?.into()); }
+    for (i, fen) in fens.iter().enumerate() {
+        if args.depth > 0 { v.perft(fen, args.depth)?; }
+        v.random_games(fen, args.seed.wrapping_add(i as u64), args.games, args.plies)?;
+    }
+    let mut shown: BTreeMap<_, usize> = BTreeMap::new();
+    for m in v.mismatches() {
+        let n = shown.entry(m.check).or_insert(0);
+        *n += 1;
+        if *n <= args.show { println!("{}", m); }
+    }
+    let counts = v.counts();
+    let summary: Vec<String> = counts.iter().map(|(c, n)| format!("{} {}", c, n)).collect();
+    println!("positions {} mismatches {}", v.positions(), if summary.is_empty() { "none".to_string() } else { summary.join(", ") });
+    if !counts.is_empty() { std::process::exit(1); }
+    Ok(())
+}
+
+#[cfg(not(feature = "board-pleco"))]
+fn main() {
+    eprintln!("verify requires --features board-pleco");
+}
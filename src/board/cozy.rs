@@ -1,22 +1,121 @@
-use cozy_chess::{Board as CozyBoard, Color};
+use cozy_chess::{Board as CozyBoard, Color, Move, Piece, Square};
+use crate::error::TitanError;
+
+/// Whether the side to move has any legal move; stops at the first piece with one.
+pub fn has_legal_moves(board: &CozyBoard) -> bool {
+    let mut any = false;
+    board.generate_moves(|_| { any = true; true });
+    any
+}
+
+pub fn legal_move_count(board: &CozyBoard) -> usize {
+    let mut n = 0usize;
+    board.generate_moves(|ml| { n += ml.len(); false });
+    n
+}
+
+/// Maximum of `game_phase`: all minor and major pieces on the board.
+pub const PHASE_MAX: u32 = 24;
+
+/// Non-pawn material phase, `PHASE_MAX` in the opening down to 0 with bare kings and
+/// pawns (knight/bishop 1, rook 2, queen 4; clamped if promotions push it higher).
+pub fn game_phase(board: &CozyBoard) -> u32 {
+    let count = |p: Piece| board.pieces(p).len();
+    (count(Piece::Knight) + count(Piece::Bishop) + 2 * count(Piece::Rook) + 4 * count(Piece::Queen)).min(PHASE_MAX)
+}
+
+// FEN fields: placement, side, castling, en passant, halfmove, fullmove
+fn fen_fields(board: &CozyBoard) -> Vec<String> { format!("{}", board).split_whitespace().map(str::to_string).collect() }
+
+/// Swap colours and flip the board vertically, so the side to move changes colour
+/// but the position is otherwise identical (evals from the mover's view match).
+pub fn flip_colors(board: &CozyBoard) -> CozyBoard {
+    let f = fen_fields(board);
+    let swap = |c: char| if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() };
+    let placement: Vec<String> = f[0].split('/').rev().map(|r| r.chars().map(swap).collect()).collect();
+    let side = if f[1] == "w" { "b" } else { "w" };
+    let castling = if f[2] == "-" { "-".to_string() } else {
+        let s: String = f[2].chars().map(swap).collect();
+        "KQkq".chars().filter(|c| s.contains(*c)).collect()
+    };
+    let ep = if f[3] == "-" { "-".to_string() } else { f[3].replace('3', "x").replace('6', "3").replace('x', "6") };
+    let fen = format!("{} {} {} {} {} {}", placement.join("/"), side, castling, ep, f[4], f[5]);
+    CozyBoard::from_fen(&fen, false).expect("colour flip of a legal position is legal")
+}
+
+/// Mirror files (a<->h). `None` while castling rights remain, since the mirrored
+/// rights do not exist in standard chess.
+pub fn mirror_files(board: &CozyBoard) -> Option<CozyBoard> {
+    let f = fen_fields(board);
+    if f[2] != "-" { return None; }
+    let placement: Vec<String> = f[0].split('/').map(|r| r.chars().rev().collect()).collect();
+    let ep = if f[3] == "-" { "-".to_string() } else {
+        let b = f[3].as_bytes();
+        format!("{}{}", (b'h' - (b[0] - b'a')) as char, b[1] as char)
+    };
+    let fen = format!("{} {} - {} {} {}", placement.join("/"), f[1], ep, f[4], f[5]);
+    CozyBoard::from_fen(&fen, false).ok()
+}
+
+/// The move `m` as it appears after `flip_colors`.
+pub fn flip_move(m: Move) -> Move { Move { from: m.from.flip_rank(), to: m.to.flip_rank(), promotion: m.promotion } }
+
+/// The move `m` as it appears after `mirror_files`.
+pub fn mirror_move(m: Move) -> Move { Move { from: m.from.flip_file(), to: m.to.flip_file(), promotion: m.promotion } }
+
+const PROMOTIONS: [Piece; 4] = [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen];
+
+/// 16-bit move encoding for game records: from square in bits 0-5, to square in
+/// bits 6-11, promotion in bits 12-14 (0 none, then knight, bishop, rook, queen).
+/// Castling keeps cozy-chess's king-takes-rook form.
+pub fn encode_move(m: Move) -> u16 {
+    let promo = m.promotion.and_then(|p| PROMOTIONS.iter().position(|&q| q == p)).map_or(0, |i| i as u16 + 1);
+    m.from as u16 | (m.to as u16) << 6 | promo << 12
+}
+
+/// Inverse of `encode_move`; `None` for a code no move encodes to. The move is not
+/// checked against any position.
+pub fn decode_move(code: u16) -> Option<Move> {
+    let promotion = match (code >> 12) as usize {
+        0 => None,
+        p @ 1..=4 => Some(PROMOTIONS[p - 1]),
+        _ => return None,
+    };
+    Some(Move { from: Square::index((code & 63) as usize), to: Square::index((code >> 6 & 63) as usize), promotion })
+}
 
 #[derive(Clone, Debug)]
 pub struct Position {
     board: CozyBoard,
+    // Keys of the positions before `board`, oldest first
+    history: Vec<u64>,
+    // Where the last move captured, if it did
+    last_capture: Option<Square>,
 }
 
 impl Position {
     pub fn startpos() -> Self {
-        Self { board: CozyBoard::default() }
+        Self { board: CozyBoard::default(), history: Vec::new(), last_capture: None }
+    }
+
+    pub fn from_fen(fen: &str) -> crate::Result<Self> {
+        CozyBoard::from_fen(fen, false).map(|b| Self { board: b, history: Vec::new(), last_capture: None }).map_err(|e| TitanError::Fen { fen: fen.to_string(), reason: format!("{e:?}") })
     }
 
-    pub fn from_fen(fen: &str) -> Result<Self, String> {
-        CozyBoard::from_fen(fen, false).map(|b| Self { board: b }).map_err(|e| format!("FEN error: {e:?}"))
+    /// The position described by the arguments of a UCI `position` command.
+    pub fn from_uci_position(args: &str) -> crate::Result<Self> {
+        let (fen, moves) = super::split_position_args(args)?;
+        let mut pos = match fen { Some(f) => Self::from_fen(&f)?, None => Self::startpos() };
+        for m in moves { pos.make_move_uci(m)?; }
+        Ok(pos)
     }
 
     pub fn board(&self) -> &CozyBoard { &self.board }
 
-    pub fn make_move_uci(&mut self, mv_uci: &str) -> Result<(), String> {
+    /// Keys (`Board::hash`) of the positions the moves were played from, oldest first.
+    pub fn history(&self) -> &[u64] { &self.history }
+
+    pub fn find_move_uci(&self, mv_uci: &str) -> Option<cozy_chess::Move> {
         let mut found = None;
         self.board.generate_moves(|moves| {
             for m in moves {
@@ -24,18 +123,41 @@ impl Position {
             }
             found.is_some()
         });
-        if let Some(m) = found { self.board.play(m); Ok(()) } else { Err(format!("Illegal move: {}", mv_uci)) }
+        found
     }
 
-    pub fn legal_moves_count(&self) -> usize {
-        let mut ct = 0usize;
-        self.board.generate_moves(|moves| { ct += moves.len(); false });
-        ct
+    pub fn make_move_uci(&mut self, mv_uci: &str) -> crate::Result<()> {
+        let m = self.find_move_uci(mv_uci).ok_or_else(|| TitanError::IllegalMove { mv: mv_uci.to_string(), fen: format!("{}", self.board) })?;
+        self.history.push(self.board.hash());
+        self.last_capture = self.board.colors(!self.board.side_to_move()).has(m.to).then_some(m.to);
+        self.board.play(m);
+        Ok(())
     }
 
+    /// Whether the side to move can take back on the square the last move captured on.
+    pub fn can_recapture(&self) -> bool {
+        let Some(sq) = self.last_capture else { return false };
+        let mut found = false;
+        self.board.generate_moves(|moves| { found = moves.into_iter().any(|m| m.to == sq); found });
+        found
+    }
+
+    pub fn legal_move_count(&self) -> usize { legal_move_count(&self.board) }
+
+    #[deprecated(note = "use `legal_move_count`")]
+    pub fn legal_moves_count(&self) -> usize { self.legal_move_count() }
+
+    pub fn has_legal_moves(&self) -> bool { has_legal_moves(&self.board) }
+
+    pub fn game_phase(&self) -> u32 { game_phase(&self.board) }
+
     pub fn side_to_move(&self) -> Color { self.board.side_to_move() }
 
-    pub fn set_from_start_and_moves(moves: &[String]) -> Result<Self, String> {
+    pub fn color_flipped(&self) -> Self { Self { board: flip_colors(&self.board), history: Vec::new(), last_capture: None } }
+
+    pub fn mirrored(&self) -> Option<Self> { mirror_files(&self.board).map(|board| Self { board, history: Vec::new(), last_capture: None }) }
+
+    pub fn set_from_start_and_moves(moves: &[String]) -> crate::Result<Self> {
         let mut pos = Self::startpos();
         for m in moves { pos.make_move_uci(m)?; }
         Ok(pos)
@@ -1,3 +1,29 @@
 pub mod cozy;
 #[cfg(feature = "board-pleco")]
 pub mod pleco;
+#[cfg(feature = "board-pleco")]
+pub mod verify;
+
+use crate::error::{Result, TitanError};
+
+/// Split the arguments of a UCI `position` command into the FEN (`None` for
+/// `startpos`) and the moves to play from it. The FEN runs up to `moves`;
+/// four-field EPD-style FENs get the move counters padded.
+pub fn split_position_args(args: &str) -> Result<(Option<String>, Vec<&str>)> {
+    let mut tokens = args.split_whitespace();
+    let fen = match tokens.next() {
+        Some("startpos") => None,
+        Some("fen") => {
+            let mut fields: Vec<&str> = tokens.by_ref().take_while(|t| *t != "moves").collect();
+            if fields.is_empty() { return Err(TitanError::Position(args.trim().to_string())); }
+            if fields.len() == 4 { fields.extend(["0", "1"]); }
+            return Ok((Some(fields.join(" ")), tokens.collect()));
+        }
+        _ => return Err(TitanError::Position(args.trim().to_string())),
+    };
+    match tokens.next() {
+        None => Ok((fen, Vec::new())),
+        Some("moves") => Ok((fen, tokens.collect())),
+        Some(_) => Err(TitanError::Position(args.trim().to_string())),
+    }
+}
@@ -1,5 +1,38 @@
 #![cfg(feature = "board-pleco")]
 use pleco::{Board as PlecoBoard, MoveList};
+use crate::error::{Result, TitanError};
+
+/// Parse a FEN for pleco without trusting pleco's parser: it panics on some malformed
+/// input (bad castling chars, out-of-range squares), so the FEN must first pass
+/// cozy-chess's strict parser and any remaining panic is caught.
+pub fn parse_fen(fen: &str) -> Result<PlecoBoard> {
+    let err = |reason: String| TitanError::Fen { fen: fen.to_string(), reason };
+    cozy_chess::Board::from_fen(fen, false).map_err(|e| err(format!("{e:?}")))?;
+    std::panic::catch_unwind(|| PlecoBoard::from_fen(fen))
+        .map_err(|_| err("rejected by pleco".to_string()))?
+        .map_err(|e| err(format!("{e:?}")))
+}
+
+/// The board described by the arguments of a UCI `position` command, with the
+/// moves applied.
+pub fn parse_uci_position(args: &str) -> Result<PlecoBoard> {
+    parse_uci_position_history(args).map(|(board, _)| board)
+}
+
+/// `parse_uci_position` with the keys (`Board::zobrist`) of the positions the moves
+/// were played from, oldest first.
+pub fn parse_uci_position_history(args: &str) -> Result<(PlecoBoard, Vec<u64>)> {
+    let (fen, moves) = super::split_position_args(args)?;
+    let mut board = match fen { Some(f) => parse_fen(&f)?, None => PlecoBoard::start_pos() };
+    let mut history = Vec::with_capacity(moves.len());
+    for uci in moves {
+        let m = board.generate_moves().iter().copied().find(|m| format!("{}", m) == uci)
+            .ok_or_else(|| TitanError::IllegalMove { mv: uci.to_string(), fen: board.fen() })?;
+        history.push(board.zobrist());
+        board.apply_move(m);
+    }
+    Ok((board, history))
+}
 
 pub struct RevBoard {
     board: PlecoBoard,
@@ -7,8 +40,8 @@ pub struct RevBoard {
 }
 
 impl RevBoard {
-    pub fn from_fen(fen: &str) -> Result<Self, String> {
-        PlecoBoard::from_fen(fen).map(|b| Self { board: b, stack: Vec::with_capacity(128) }).map_err(|e| format!("FEN error: {e:?}"))
+    pub fn from_fen(fen: &str) -> Result<Self> {
+        parse_fen(fen).map(|b| Self { board: b, stack: Vec::with_capacity(128) })
     }
     pub fn startpos() -> Self { Self { board: PlecoBoard::start_pos(), stack: Vec::with_capacity(128) } }
     pub fn generate_moves(&self) -> MoveList { self.board.generate_moves() }
@@ -0,0 +1,211 @@
+#![cfg(feature = "board-pleco")]
+//! Differential checks between the cozy-chess and pleco paths. Both boards are
+//! walked in lockstep (a perft tree or random games) and every position is
+//! compared: legal moves, FEN, zobrist keys, SEE, material signature, king attack,
+//! NNUE features and the searchers' static evals. Each mismatch keeps the FEN it
+//! was found at, so it can be reproduced with either backend.
+
+use crate::board::cozy::Position;
+use crate::board::pleco::parse_fen;
+use crate::eval::nnue::features::{active_indices_pleco, HalfKpA};
+use crate::eval::nnue::loader::QuantNnue;
+use crate::search::alphabeta::Searcher;
+use crate::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher};
+use crate::search::eval::{KingAttack, MaterialSig};
+use crate::search::see::see_gain_cp;
+use crate::Result;
+use cozy_chess::{Board, Color, File, Move, Piece};
+use pleco::{BitMove, Board as PlecoBoard, Player};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// What was compared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Check { Moves, Fen, Zobrist, See, Material, KingAttack, Features, Eval }
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Check::Moves => "moves", Check::Fen => "fen", Check::Zobrist => "zobrist", Check::See => "see",
+            Check::Material => "material", Check::KingAttack => "king_attack", Check::Features => "features", Check::Eval => "eval",
+        })
+    }
+}
+
+/// One disagreement between the backends, with the position it was found at.
+#[derive(Clone, Debug)]
+pub struct Mismatch { pub check: Check, pub fen: String, pub detail: String }
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} | fen {}", self.check, self.detail, self.fen)
+    }
+}
+
+/// A move in standard UCI notation; cozy-chess writes castling as the king
+/// capturing its own rook.
+pub fn standard_uci(board: &Board, m: Move) -> String {
+    let castles = board.piece_on(m.from) == Some(Piece::King) && board.colors(board.side_to_move()).has(m.to);
+    if !castles { return m.to_string(); }
+    let file = if m.to.file() > m.from.file() { File::G } else { File::C };
+    format!("{}{}", m.from, cozy_chess::Square::new(file, m.to.rank()))
+}
+
+fn without_ep(fen: &str) -> String {
+    fen.split(' ').enumerate().map(|(i, f)| if i == 3 { "-" } else { f }).collect::<Vec<_>>().join(" ")
+}
+
+/// Runs the checks and collects mismatches. The zobrist check is cross-backend:
+/// positions one backend keys alike must be keyed alike by the other.
+pub struct Verifier {
+    cozy: Searcher,
+    pleco: PlecoSearcher,
+    nnue: bool,
+    keys: HashMap<u64, u64>,
+    keys_back: HashMap<u64, u64>,
+    positions: u64,
+    mismatches: Vec<Mismatch>,
+}
+
+impl Default for Verifier {
+    fn default() -> Self { Self::new() }
+}
+
+impl Verifier {
+    /// Evals are compared in material mode (pleco's only non-NNUE eval).
+    pub fn new() -> Self {
+        let mut cozy = Searcher::default();
+        cozy.set_material_eval(true);
+        let mut pleco = PlecoSearcher::default();
+        pleco.set_eval_mode(PlecoEvalMode::Material);
+        Self { cozy, pleco, nnue: false, keys: HashMap::new(), keys_back: HashMap::new(), positions: 0, mismatches: Vec::new() }
+    }
+
+    /// Compare NNUE evals with this network instead of material.
+    pub fn with_nnue_quant(mut self, model: Arc<QuantNnue>) -> Self {
+        self.cozy.set_nnue_quant_model(model.clone());
+        self.cozy.set_use_nnue(true);
+        self.pleco.set_nnue_quant_model(model);
+        self.pleco.set_eval_mode(PlecoEvalMode::Nnue);
+        self.nnue = true;
+        self
+    }
+
+    pub fn positions(&self) -> u64 { self.positions }
+    pub fn mismatches(&self) -> &[Mismatch] { &self.mismatches }
+
+    /// Mismatch count per check.
+    pub fn counts(&self) -> BTreeMap<Check, usize> {
+        let mut c = BTreeMap::new();
+        for m in &self.mismatches { *c.entry(m.check).or_insert(0) += 1; }
+        c
+    }
+
+    fn report(&mut self, check: Check, board: &Board, detail: String) {
+        self.mismatches.push(Mismatch { check, fen: board.to_string(), detail });
+    }
+
+    /// Compare one position; returns the legal moves both backends agree on.
+    pub fn check_position(&mut self, cozy: &Board, pleco: &PlecoBoard) -> Vec<(Move, BitMove)> {
+        self.positions += 1;
+        // Moves, matched by standard UCI string
+        let mut cozy_moves = BTreeMap::new();
+        cozy.generate_moves(|ml| { for m in ml { cozy_moves.insert(standard_uci(cozy, m), m); } false });
+        let mut pleco_moves: BTreeMap<String, BitMove> = pleco.generate_moves().iter().map(|&m| (m.stringify(), m)).collect();
+        let mut both = Vec::with_capacity(cozy_moves.len());
+        let mut only_cozy = Vec::new();
+        for (uci, m) in cozy_moves {
+            match pleco_moves.remove(&uci) { Some(pm) => both.push((m, pm)), None => only_cozy.push(uci) }
+        }
+        if !only_cozy.is_empty() || !pleco_moves.is_empty() {
+            let only_pleco: Vec<_> = pleco_moves.into_keys().collect();
+            self.report(Check::Moves, cozy, format!("cozy only [{}], pleco only [{}]", only_cozy.join(" "), only_pleco.join(" ")));
+        }
+
+        // FEN; cozy-chess names the en passant square after every double push, pleco
+        // only when the capture is possible, so it only counts when there is one
+        let (cozy_fen, pleco_fen) = (cozy.to_string(), pleco.fen());
+        let ep_capture = both.iter().any(|(_, pm)| pm.is_en_passant());
+        if cozy_fen != pleco_fen && (ep_capture || without_ep(&cozy_fen) != without_ep(&pleco_fen)) {
+            self.report(Check::Fen, cozy, format!("pleco has {}", pleco_fen));
+        }
+
+        // Keys: the same position must get the same key in both backends, and a key
+        // must not depend on how the position was reached
+        let (ck, pk) = (cozy.hash(), pleco.zobrist());
+        if let Some(fresh) = parse_fen(&pleco_fen).ok().map(|b| b.zobrist()) {
+            if fresh != pk { self.report(Check::Zobrist, cozy, format!("pleco incremental {:016x}, from FEN {:016x}", pk, fresh)); }
+        }
+        let seen = (*self.keys.entry(ck).or_insert(pk), *self.keys_back.entry(pk).or_insert(ck));
+        if seen != (pk, ck) { self.report(Check::Zobrist, cozy, format!("cozy {:016x} / pleco {:016x} paired differently before", ck, pk)); }
+
+        // SEE on plain captures: same verdict on whether the capture loses material
+        for &(m, pm) in &both {
+            if m.promotion.is_some() || pm.is_en_passant() { continue; }
+            if let Some(gain) = see_gain_cp(cozy, m) {
+                let pleco_ok = pleco.see_ge(pm, 0);
+                if (gain >= 0) != pleco_ok {
+                    self.report(Check::See, cozy, format!("{}: cozy gain {}, pleco see_ge(0) {}", pm.stringify(), gain, pleco_ok));
+                }
+            }
+        }
+
+        let (cs, ps) = (MaterialSig::from_board(cozy), MaterialSig::from_pleco(pleco));
+        if cs != ps { self.report(Check::Material, cozy, format!("cozy {:?}, pleco {:?}", cs, ps)); }
+        for (c, p) in [(Color::White, Player::White), (Color::Black, Player::Black)] {
+            let (ca, pa) = (KingAttack::from_board(cozy, c), KingAttack::from_pleco(pleco, p));
+            if ca != pa { self.report(Check::KingAttack, cozy, format!("{:?} attacking: cozy {:?}, pleco {:?}", c, ca, pa)); }
+        }
+        let (mut cf, mut pf) = (HalfKpA.active_indices(cozy), active_indices_pleco(pleco));
+        cf.sort_unstable();
+        pf.sort_unstable();
+        if cf != pf { self.report(Check::Features, cozy, format!("{} cozy features, {} pleco", cf.len(), pf.len())); }
+
+        let (ce, pe) = (self.cozy.static_eval(cozy), self.pleco.static_eval(pleco));
+        if ce != pe { self.report(Check::Eval, cozy, format!("{}: cozy {}, pleco {}", if self.nnue { "nnue" } else { "material" }, ce, pe)); }
+        both
+    }
+
+    /// Walk the perft tree of `fen` to `depth`, checking every node; returns the
+    /// leaf count (moves both backends agree on).
+    pub fn perft(&mut self, fen: &str, depth: u32) -> Result<u64> {
+        let cozy = Position::from_fen(fen)?.board().clone();
+        let mut pleco = parse_fen(fen)?;
+        Ok(self.walk(&cozy, &mut pleco, depth))
+    }
+
+    fn walk(&mut self, cozy: &Board, pleco: &mut PlecoBoard, depth: u32) -> u64 {
+        let moves = self.check_position(cozy, pleco);
+        if depth == 0 { return 1; }
+        let mut leaves = 0;
+        for (m, pm) in moves {
+            let mut child = cozy.clone();
+            child.play(m);
+            pleco.apply_move(pm);
+            leaves += self.walk(&child, pleco, depth - 1);
+            pleco.undo_move();
+        }
+        leaves
+    }
+
+    /// Play `games` random games of up to `plies` plies from `fen`, checking every
+    /// position. Reproducible for a given seed.
+    pub fn random_games(&mut self, fen: &str, seed: u64, games: usize, plies: usize) -> Result<()> {
+        let start = Position::from_fen(fen)?.board().clone();
+        let start_pleco = parse_fen(fen)?;
+        let mut rng = SmallRng::seed_from_u64(seed);
+        for _ in 0..games {
+            let (mut cozy, mut pleco) = (start.clone(), start_pleco.clone());
+            for _ in 0..plies {
+                let moves = self.check_position(&cozy, &pleco);
+                if moves.is_empty() { break; }
+                let (m, pm) = moves[rng.gen_range(0..moves.len())];
+                cozy.play(m);
+                pleco.apply_move(pm);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use thiserror::Error;
+
+/// Library error type. Front ends decide how to surface it: the UCI layer prints
+/// `info string <error>`, the binaries print it and exit.
+#[derive(Debug, Error)]
+pub enum TitanError {
+    #[error("invalid FEN '{fen}': {reason}")]
+    Fen { fen: String, reason: String },
+    #[error("illegal move {mv} in {fen}")]
+    IllegalMove { mv: String, fen: String },
+    #[error("bad position command '{0}'")]
+    Position(String),
+    #[error("{path}:{line}: {reason}")]
+    Openings { path: String, line: usize, reason: String },
+    #[error("{path}:{line}: {reason}")]
+    Epd { path: String, line: usize, reason: String },
+    #[error("{path}: {source}")]
+    Io { path: String, #[source] source: std::io::Error },
+    #[error("{path}: {source}")]
+    Json { path: String, #[source] source: serde_json::Error },
+    #[error("NNUE: {0}")]
+    Nnue(String),
+    #[error("policy net: {0}")]
+    Policy(String),
+    #[error("option {name}: {reason}")]
+    Option { name: String, reason: String },
+    #[error("unknown option '{0}'")]
+    UnknownOption(String),
+    #[error("searcher config: {0}")]
+    Config(String),
+    #[error("cluster {addr}: {reason}")]
+    Cluster { addr: String, reason: String },
+}
+
+pub type Result<T, E = TitanError> = std::result::Result<T, E>;
+
+impl TitanError {
+    pub fn io(path: impl AsRef<std::path::Path>, source: std::io::Error) -> Self {
+        TitanError::Io { path: path.as_ref().display().to_string(), source }
+    }
+
+    pub fn option(name: &str, reason: impl Into<String>) -> Self {
+        TitanError::Option { name: name.to_string(), reason: reason.into() }
+    }
+}
+
+/// Read and deserialize a JSON file, keeping the path in either error.
+pub(crate) fn read_json<T: serde::de::DeserializeOwned>(path: impl AsRef<std::path::Path>) -> Result<T> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|e| TitanError::io(path, e))?;
+    serde_json::from_str(text.trim()).map_err(|source| TitanError::Json { path: path.display().to_string(), source })
+}
@@ -1,2 +1,3 @@
 pub mod nnue;
+pub mod policy;
 
@@ -55,3 +55,20 @@ impl HalfKpA {
         out
     }
 }
+
+/// HalfKP(A) active indices for a pleco board, identical in layout to `HalfKpA::active_indices`.
+#[cfg(feature = "board-pleco")]
+pub fn active_indices_pleco(board: &pleco::Board) -> Vec<usize> {
+    use pleco::{Player, PieceType};
+    const PLECO_ORDER: [PieceType; 5] = [PieceType::P, PieceType::N, PieceType::B, PieceType::R, PieceType::Q];
+    let mut out = Vec::with_capacity(64);
+    for (side, player) in [(Color::White, Player::White), (Color::Black, Player::Black)] {
+        let k_idx = board.king_sq(player).0 as usize;
+        for (pi, p) in PLECO_ORDER.iter().enumerate() {
+            for sq in board.piece_bb(player, *p) {
+                out.push(idx_for(side, k_idx, pi, sq.0 as usize));
+            }
+        }
+    }
+    out
+}
@@ -1,6 +1,7 @@
-use anyhow::{Context, Result, bail};
+use crate::error::{Result, TitanError};
+use crate::eval::nnue::SCALED_VERSION;
 use std::fs::File;
-use std::io::{Read, BufReader};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
 #[derive(Debug, Clone, Copy)]
@@ -9,6 +10,8 @@ pub struct QuantMeta {
     pub input_dim: usize,
     pub hidden_dim: usize,
     pub output_dim: usize,
+    /// Centipawns per unit of raw output (1.0 before `SCALED_VERSION`)
+    pub output_scale: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -23,40 +26,119 @@ pub struct QuantNnue {
 }
 
 const Q_MAGIC: &[u8; 8] = b"PIENNQ01"; // Pie NNUE Quant v1
+/// Upper bound on weights per layer; larger headers are corrupt (or hostile) files.
+pub const MAX_LAYER_PARAMS: usize = 1 << 26;
+
+/// `rows * cols`, rejecting products that overflow or exceed `MAX_LAYER_PARAMS`.
+pub fn layer_len(rows: usize, cols: usize, what: &str) -> Result<usize> {
+    match rows.checked_mul(cols) {
+        Some(n) if n <= MAX_LAYER_PARAMS => Ok(n),
+        _ => Err(TitanError::Nnue(format!("{} dimensions {}x{} too large", what, rows, cols))),
+    }
+}
+
+/// FNV-1a over a network's dimensions and weights: a short, stable id for logs.
+#[derive(Clone, Copy)]
+pub(crate) struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub(crate) fn new() -> Self { Self(0xcbf2_9ce4_8422_2325) }
+    pub(crate) fn bytes(mut self, bytes: &[u8]) -> Self {
+        for &b in bytes { self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3); }
+        self
+    }
+    pub(crate) fn dims(self, dims: &[usize]) -> Self {
+        dims.iter().fold(self, |h, &d| h.bytes(&(d as u64).to_le_bytes()))
+    }
+    pub(crate) fn finish(self) -> u64 { self.0 }
+}
+
+/// Maps a read failure to `TitanError::Nnue`, naming the field being read.
+pub(crate) fn read_err(what: &'static str) -> impl FnOnce(std::io::Error) -> TitanError {
+    move |e| TitanError::Nnue(format!("{}: {}", what, e))
+}
 
 impl QuantNnue {
+    /// Content hash of the network (dimensions, scales and weights), printed by `uci`
+    /// and on load so test logs identify the net independently of its file name.
+    pub fn fingerprint(&self) -> u64 {
+        let m = &self.meta;
+        let h = Fingerprint::new().dims(&[m.version as usize, m.input_dim, m.hidden_dim, m.output_dim])
+            .bytes(&self.w1_scale.to_le_bytes()).bytes(&self.w2_scale.to_le_bytes());
+        let h = if m.version >= SCALED_VERSION { h.bytes(&m.output_scale.to_le_bytes()) } else { h };
+        let h = self.w1.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes()));
+        let h = self.b1.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes()));
+        let h = self.w2.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes()));
+        self.b2.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes())).finish()
+    }
+
     pub fn load_quantized<P: AsRef<Path>>(path: P) -> Result<Self> {
         // Simple quant format for bootstrapping:
         // magic: 8 bytes b"PIENNQ01"
         // u32 version
         // u32 input_dim, u32 hidden_dim, u32 output_dim
         // f32 w1_scale, f32 w2_scale
+        // f32 output_scale (version >= 2 only)
         // i8  w1[hidden*input]
         // i16 b1[hidden]
         // i8  w2[output*hidden]
         // i16 b2[output]
-        let f = File::open(&path).with_context(|| format!("open quant nnue file: {}", path.as_ref().display()))?;
-        let mut r = BufReader::new(f);
+        let f = File::open(&path).map_err(|e| TitanError::io(&path, e))?;
+        Self::from_reader(BufReader::new(f))
+    }
+
+    /// Write the network in the quant format `load_quantized` reads, with
+    /// `meta.output_scale` in the header from `SCALED_VERSION` on.
+    pub fn save_quantized<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = File::create(&path).map_err(|e| TitanError::io(&path, e))?;
+        let mut w = BufWriter::new(f);
+        self.write_to(&mut w).and_then(|_| w.flush()).map_err(|e| TitanError::io(&path, e))
+    }
+
+    /// `save_quantized` to any byte sink.
+    pub fn write_to<W: Write>(&self, mut w: W) -> std::io::Result<()> {
+        let m = &self.meta;
+        w.write_all(Q_MAGIC)?;
+        for v in [m.version, m.input_dim as u32, m.hidden_dim as u32, m.output_dim as u32] { w.write_all(&v.to_le_bytes())?; }
+        w.write_all(&self.w1_scale.to_le_bytes())?;
+        w.write_all(&self.w2_scale.to_le_bytes())?;
+        if m.version >= SCALED_VERSION { w.write_all(&m.output_scale.to_le_bytes())?; }
+        w.write_all(&self.w1.iter().map(|&v| v as u8).collect::<Vec<_>>())?;
+        for v in &self.b1 { w.write_all(&v.to_le_bytes())?; }
+        w.write_all(&self.w2.iter().map(|&v| v as u8).collect::<Vec<_>>())?;
+        for v in &self.b2 { w.write_all(&v.to_le_bytes())?; }
+        Ok(())
+    }
+
+    /// Parse the quant format from any byte source (see `load_quantized`).
+    pub fn from_reader<R: Read>(mut r: R) -> Result<Self> {
         let mut magic = [0u8; 8];
-        r.read_exact(&mut magic).context("read magic")?;
-        if &magic != Q_MAGIC { bail!("bad quant NNUE magic"); }
+        r.read_exact(&mut magic).map_err(read_err("read magic"))?;
+        if &magic != Q_MAGIC { return Err(TitanError::Nnue("bad quant NNUE magic".into())); }
         let mut b4 = [0u8; 4];
-        r.read_exact(&mut b4).context("read version")?;
+        r.read_exact(&mut b4).map_err(read_err("read version"))?;
         let version = u32::from_le_bytes(b4);
-        r.read_exact(&mut b4).context("read input_dim")?;
+        r.read_exact(&mut b4).map_err(read_err("read input_dim"))?;
         let input_dim = u32::from_le_bytes(b4) as usize;
-        r.read_exact(&mut b4).context("read hidden_dim")?;
+        r.read_exact(&mut b4).map_err(read_err("read hidden_dim"))?;
         let hidden_dim = u32::from_le_bytes(b4) as usize;
-        r.read_exact(&mut b4).context("read output_dim")?;
+        r.read_exact(&mut b4).map_err(read_err("read output_dim"))?;
         let output_dim = u32::from_le_bytes(b4) as usize;
         let mut b4f = [0u8; 4];
-        r.read_exact(&mut b4f).context("read w1_scale")?;
+        r.read_exact(&mut b4f).map_err(read_err("read w1_scale"))?;
         let w1_scale = f32::from_le_bytes(b4f);
-        r.read_exact(&mut b4f).context("read w2_scale")?;
+        r.read_exact(&mut b4f).map_err(read_err("read w2_scale"))?;
         let w2_scale = f32::from_le_bytes(b4f);
+        let output_scale = if version >= SCALED_VERSION {
+            r.read_exact(&mut b4f).map_err(read_err("read output_scale"))?;
+            f32::from_le_bytes(b4f)
+        } else { 1.0 };
+
+        let w1_len = layer_len(hidden_dim, input_dim, "w1")?;
+        let w2_len = layer_len(output_dim, hidden_dim, "w2")?;
+        layer_len(input_dim.max(hidden_dim).max(output_dim), 2, "layer")?;
 
-        fn read_fill<T: Copy + Default>(r: &mut BufReader<File>, elem_size: usize, n: usize) -> Result<Vec<u8>> {
-            use std::io::Read as _;
+        fn read_fill<R: Read>(r: &mut R, elem_size: usize, n: usize) -> Result<Vec<u8>> {
             let total = n * elem_size;
             let mut buf = vec![0u8; total];
             let mut off = 0usize;
@@ -64,16 +146,16 @@ impl QuantNnue {
                 match r.read(&mut buf[off..]) {
                     Ok(0) => break,
                     Ok(k) => off += k,
-                    Err(e) => return Err(e).context("read weights")
+                    Err(e) => return Err(read_err("read weights")(e)),
                 }
             }
             Ok(buf)
         }
 
-        let w1_bytes = read_fill::<i8>(&mut r, 1, hidden_dim * input_dim)?;
-        let b1_bytes = read_fill::<i16>(&mut r, 2, hidden_dim)?;
-        let w2_bytes = read_fill::<i8>(&mut r, 1, output_dim * hidden_dim)?;
-        let b2_bytes = read_fill::<i16>(&mut r, 2, output_dim)?;
+        let w1_bytes = read_fill(&mut r, 1, w1_len)?;
+        let b1_bytes = read_fill(&mut r, 2, hidden_dim)?;
+        let w2_bytes = read_fill(&mut r, 1, w2_len)?;
+        let b2_bytes = read_fill(&mut r, 2, output_dim)?;
 
         let w1 = w1_bytes.into_iter().map(|b| b as i8).collect();
         let mut b1 = Vec::with_capacity(hidden_dim);
@@ -92,7 +174,7 @@ impl QuantNnue {
         }
 
         Ok(Self {
-            meta: QuantMeta { version, input_dim, hidden_dim, output_dim },
+            meta: QuantMeta { version, input_dim, hidden_dim, output_dim, output_scale },
             w1_scale, w2_scale,
             w1, b1, w2, b2,
         })
@@ -7,16 +7,34 @@ pub mod quant;
 use std::path::Path;
 use std::fs::File;
 use std::io::{Read, BufReader};
-use anyhow::{bail, Context, Result};
+use crate::error::{Result, TitanError};
+use crate::search::score::MATE_BOUND;
 
-#[derive(Debug)]
+/// Largest network score; anything beyond would read as a mate.
+pub const MAX_NNUE_CP: i32 = MATE_BOUND - 1;
+
+/// First file version (dense and quant) whose header carries `output_scale`;
+/// older files score raw outputs as centipawns.
+pub const SCALED_VERSION: u32 = 2;
+
+/// Raw network output to centipawns: scaled, rounded and clamped below the mate
+/// range. A NaN output (broken weights) scores as 0.
+pub fn output_cp(raw: f64, scale: f32) -> i32 {
+    let cp = raw * scale as f64;
+    if cp.is_nan() { 0 } else { (cp.round() as i64).clamp(-(MAX_NNUE_CP as i64), MAX_NNUE_CP as i64) as i32 }
+}
+
+#[derive(Clone, Debug)]
 pub struct NnueMeta {
     pub version: u32,
     pub input_dim: usize,
     pub hidden_dim: usize,
     pub output_dim: usize,
+    /// Centipawns per unit of raw output (1.0 before `SCALED_VERSION`)
+    pub output_scale: f32,
 }
 
+#[derive(Clone)]
 pub struct Nnue {
     pub meta: NnueMeta,
     // Placeholder weights; real implementation will store quantized layers
@@ -27,41 +45,62 @@ pub struct Nnue {
 }
 
 impl Nnue {
+    /// Content hash of the dense network; see `QuantNnue::fingerprint`.
+    pub fn fingerprint(&self) -> u64 {
+        let m = &self.meta;
+        let mut h = loader::Fingerprint::new().dims(&[m.version as usize, m.input_dim, m.hidden_dim, m.output_dim]);
+        if m.version >= SCALED_VERSION { h = h.bytes(&m.output_scale.to_le_bytes()); }
+        [&self.w1, &self.b1, &self.w2, &self.b2].iter()
+            .fold(h, |h, layer| layer.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes())))
+            .finish()
+    }
+
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         // Format:
         // magic: 8 bytes b"PIENNUE1"
         // u32 version (LE)
         // u32 input_dim, u32 hidden_dim, u32 output_dim (LE)
+        // f32 output_scale (version >= 2 only)
         // f32 w1[hidden_dim * input_dim]
         // f32 b1[hidden_dim]
         // f32 w2[output_dim * hidden_dim]
         // f32 b2[output_dim]
-        let f = File::open(&path).with_context(|| format!("open nnue file: {}", path.as_ref().display()))?;
-        let mut r = BufReader::new(f);
+        let f = File::open(&path).map_err(|e| TitanError::io(&path, e))?;
+        Self::from_reader(BufReader::new(f))
+    }
+
+    /// Parse the dense format from any byte source (see `load`).
+    pub fn from_reader<R: Read>(mut r: R) -> Result<Self> {
         let mut magic = [0u8; 8];
-        r.read_exact(&mut magic).context("read magic")?;
+        r.read_exact(&mut magic).map_err(loader::read_err("read magic"))?;
         if &magic != b"PIENNUE1" {
-            bail!("bad NNUE magic");
+            return Err(TitanError::Nnue("bad NNUE magic".into()));
         }
         let mut buf4 = [0u8; 4];
-        r.read_exact(&mut buf4).context("read version")?;
+        r.read_exact(&mut buf4).map_err(loader::read_err("read version"))?;
         let version = u32::from_le_bytes(buf4);
-        r.read_exact(&mut buf4).context("read input_dim")?;
+        r.read_exact(&mut buf4).map_err(loader::read_err("read input_dim"))?;
         let input_dim = u32::from_le_bytes(buf4) as usize;
-        r.read_exact(&mut buf4).context("read hidden_dim")?;
+        r.read_exact(&mut buf4).map_err(loader::read_err("read hidden_dim"))?;
         let hidden_dim = u32::from_le_bytes(buf4) as usize;
-        r.read_exact(&mut buf4).context("read output_dim")?;
+        r.read_exact(&mut buf4).map_err(loader::read_err("read output_dim"))?;
         let output_dim = u32::from_le_bytes(buf4) as usize;
+        let output_scale = if version >= SCALED_VERSION {
+            r.read_exact(&mut buf4).map_err(loader::read_err("read output_scale"))?;
+            f32::from_le_bytes(buf4)
+        } else { 1.0 };
+        let w1_len = loader::layer_len(hidden_dim, input_dim, "w1")?;
+        let w2_len = loader::layer_len(output_dim, hidden_dim, "w2")?;
+        loader::layer_len(input_dim.max(hidden_dim).max(output_dim), 4, "layer")?;
         let mut read_f32s = |n: usize| -> Result<Vec<f32>> {
             // Lenient reader: if file ends early, pad with zeros.
-            use std::io::Read as _;
             let mut buf = vec![0u8; n * 4];
             let mut off = 0usize;
             while off < buf.len() {
                 match r.read(&mut buf[off..]) {
                     Ok(0) => break, // EOF
                     Ok(k) => off += k,
-                    Err(e) => return Err(e).with_context(|| format!("read {} f32s", n)),
+                    Err(e) => return Err(TitanError::Nnue(format!("read {} f32s: {}", n, e))),
                 }
             }
             let mut out = Vec::with_capacity(n);
@@ -72,16 +111,17 @@ impl Nnue {
             }
             Ok(out)
         };
-        let w1 = read_f32s(hidden_dim * input_dim)?;
+        let w1 = read_f32s(w1_len)?;
         let b1 = read_f32s(hidden_dim)?;
-        let w2 = read_f32s(output_dim * hidden_dim)?;
+        let w2 = read_f32s(w2_len)?;
         let b2 = read_f32s(output_dim)?;
         Ok(Self {
-            meta: NnueMeta { version, input_dim, hidden_dim, output_dim },
+            meta: NnueMeta { version, input_dim, hidden_dim, output_dim, output_scale },
             w1, b1, w2, b2,
         })
     }
 
+    /// White-relative centipawns (see `output_cp`).
     pub fn evaluate(&self, board: &Board) -> i32 {
         let x = self.features(board);
         let n = self.meta.input_dim;
@@ -100,7 +140,7 @@ impl Nnue {
             for j in 0..h { sum += row[j] * y1[j]; }
             out = sum;
         }
-        out.round() as i32
+        output_cp(out as f64, self.meta.output_scale)
     }
 
     pub fn refresh_accumulator(&mut self, _board: &Board) {
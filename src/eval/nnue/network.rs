@@ -1,161 +1,240 @@
 use crate::eval::nnue::loader::QuantNnue;
-use crate::eval::nnue::features::{HalfKpA, HALFKP_PIECE_ORDER};
-use cozy_chess::{Board, Color, Piece, Move};
-use std::collections::HashSet;
+use crate::eval::nnue::output_cp;
+use crate::eval::nnue::features::HalfKpA;
+use cozy_chess::{Board, Move};
+use std::sync::Arc;
 
-/// Quantized NNUE wrapper; currently a placeholder that will be wired to the search.
+/// Quantized NNUE with an incrementally updated first layer.
+///
+/// The accumulator is a stack with one frame per ply: `push` copies the top
+/// frame and moves the copy to the child position, `pop` returns to the parent
+/// without any arithmetic. Frames are kept after a pop, so a search reuses their
+/// buffers instead of allocating per node.
 pub struct QuantNetwork {
-    pub model: QuantNnue,
+    /// Shared weights: search workers and a hot-swapped replacement hold their own
+    /// `Arc`, so a swap never changes the network under a running search.
+    pub model: Arc<QuantNnue>,
     pub feats: HalfKpA,
-    // Incremental state
-    acc: Vec<i32>,
-    active: HashSet<usize>,
-    wk_idx: usize,
-    bk_idx: usize,
+    frames: Vec<Frame>,
+    top: usize,
 }
 
+/// First-layer sums for one position, with its active features (sorted).
+#[derive(Clone, Default)]
+struct Frame { acc: Vec<i32>, active: Vec<usize> }
+
+/// Undo record for `apply_move` / `update_to_indices` (the in-place API used
+/// outside the search's push/pop).
 pub enum ChangeSet {
     Delta { added: Vec<usize>, removed: Vec<usize> },
-    Snapshot { acc: Vec<i32>, active: HashSet<usize>, wk_idx: usize, bk_idx: usize },
+    Snapshot { acc: Vec<i32>, active: Vec<usize> },
 }
 
 impl QuantNetwork {
-    pub fn new(model: QuantNnue) -> Self {
+    /// Check a loaded model fits this network (HalfKP input, one output) before
+    /// `new`, which asserts on it.
+    pub fn validate(model: &QuantNnue) -> crate::Result<()> {
+        let m = &model.meta;
+        if m.input_dim != HalfKpA.dim() { return Err(crate::TitanError::Nnue(format!("quant model input_dim {} != HalfKP dim {}", m.input_dim, HalfKpA.dim()))); }
+        if m.output_dim != 1 || m.hidden_dim == 0 { return Err(crate::TitanError::Nnue(format!("quant model needs hidden_dim > 0 and output_dim 1, got {}x{}", m.hidden_dim, m.output_dim))); }
+        if model.w1.len() != m.hidden_dim * m.input_dim || model.b1.len() != m.hidden_dim || model.w2.len() < m.hidden_dim || model.b2.is_empty() { return Err(crate::TitanError::Nnue("quant model weight sizes do not match its header".into())); }
+        Ok(())
+    }
+
+    pub fn new(model: QuantNnue) -> Self { Self::from_shared(Arc::new(model)) }
+
+    /// A network over already-shared weights with its own accumulator.
+    pub fn from_shared(model: Arc<QuantNnue>) -> Self {
         let feats = HalfKpA;
         let dim = feats.dim();
         assert_eq!(model.meta.input_dim, dim, "Quant model input_dim must equal HalfKP dim");
-        let acc = vec![0i32; model.meta.hidden_dim];
-        Self { model, feats, acc, active: HashSet::new(), wk_idx: 0, bk_idx: 0 }
+        // No features active yet: the accumulator is the bias, so a push from here is exact
+        let frame = Frame { acc: (0..model.meta.hidden_dim).map(|j| model.b1.get(j).map_or(0, |&b| b as i32)).collect(), active: Vec::new() };
+        Self { model, feats, frames: vec![frame], top: 0 }
     }
 
+    /// A network for another thread, sharing the weights and starting from this
+    /// one's current position (one frame copied, no recompute).
+    pub fn fork(&self) -> Self {
+        Self { model: self.model.clone(), feats: HalfKpA, frames: vec![self.frames[self.top].clone()], top: 0 }
+    }
+
+    /// Recompute the accumulator for `board` and make it the bottom of the stack.
     pub fn refresh(&mut self, board: &Board) {
-        // Recompute active set and accumulators from scratch
         let act = self.feats.active_indices(board);
-        self.active.clear();
-        self.active.extend(act.iter().copied());
-        self.wk_idx = square_index(board, Color::White, Piece::King);
-        self.bk_idx = square_index(board, Color::Black, Piece::King);
-        // accum = b1 + sum_w1(active)
-        let h = self.model.meta.hidden_dim;
-        let n = self.model.meta.input_dim;
-        for j in 0..h { self.acc[j] = self.model.b1[j] as i32; }
-        for &idx in &self.active {
-            let base = idx; // column
-            for j in 0..h {
-                let w = self.model.w1[j * n + base] as i32;
-                self.acc[j] += w;
-            }
+        self.refresh_from_indices(&act);
+    }
+
+    /// Rebuild the accumulator from a precomputed active set (used by non-cozy backends).
+    pub fn refresh_from_indices(&mut self, act: &[usize]) {
+        let mut sorted = act.to_vec();
+        sorted.sort_unstable();
+        self.top = 0;
+        rebuild(&self.model, &mut self.frames[0], &sorted);
+    }
+
+    /// Enter the child position `after` (a ply deeper); undone by `pop`.
+    pub fn push(&mut self, after: &Board) {
+        let act = self.feats.active_indices(after);
+        self.push_indices(&act);
+    }
+
+    /// `push` for a precomputed active set (used by non-cozy backends).
+    pub fn push_indices(&mut self, after: &[usize]) {
+        let next = self.top + 1;
+        if next == self.frames.len() { self.frames.push(Frame::default()); }
+        let (below, above) = self.frames.split_at_mut(next);
+        let (parent, child) = (&below[self.top], &mut above[0]);
+        let mut sorted = after.to_vec();
+        sorted.sort_unstable();
+        let (removed, added) = diff(&parent.active, &sorted);
+        if removed.len() + added.len() > sorted.len() {
+            // A king move changes every feature of its side: cheaper from scratch
+            rebuild(&self.model, child, &sorted);
+        } else {
+            child.acc.clone_from(&parent.acc);
+            child.active = sorted;
+            apply(&self.model, &mut child.acc, &removed, &added);
         }
+        self.top = next;
     }
 
+    /// Back to the parent of the last `push`.
+    pub fn pop(&mut self) {
+        debug_assert!(self.top > 0, "pop without push");
+        self.top = self.top.saturating_sub(1);
+    }
+
+    /// Plies pushed above the last refresh.
+    pub fn depth(&self) -> usize { self.top }
+
+    /// White-relative centipawns for the top frame (HalfKP features carry no side to move).
     pub fn eval_current(&self) -> i32 { self.eval_from_acc() }
 
+    /// `eval_current` recomputed from scratch for `board`.
     pub fn eval_full(&self, board: &Board) -> i32 {
         // Full recompute path; used for parity testing
-        let act = self.feats.active_indices(board);
-        let h = self.model.meta.hidden_dim;
-        let n = self.model.meta.input_dim;
-        let mut y = vec![0i32; h];
-        for j in 0..h { y[j] = self.model.b1[j] as i32; }
-        for &idx in &act {
-            for j in 0..h {
-                y[j] += self.model.w1[j * n + idx] as i32;
-            }
-        }
-        // ReLU and head
-        let mut out: i64 = self.model.b2[0] as i64;
-        for j in 0..h {
-            let v = y[j].max(0) as i64;
-            out += (self.model.w2[j] as i64) * v;
-        }
-        out as i32
-    }
-
-    pub fn apply_move(&mut self, before: &Board, _mv: Move, after: &Board) -> ChangeSet {
-        // If either king moved, snapshot + refresh for safety
-        let wk_before = square_index(before, Color::White, Piece::King);
-        let bk_before = square_index(before, Color::Black, Piece::King);
-        let wk_after = square_index(after, Color::White, Piece::King);
-        let bk_after = square_index(after, Color::Black, Piece::King);
-        if wk_before != wk_after || bk_before != bk_after {
-            let snap = ChangeSet::Snapshot { acc: self.acc.clone(), active: self.active.clone(), wk_idx: self.wk_idx, bk_idx: self.bk_idx };
-            self.refresh(after);
-            return snap;
-        }
-        // Diff-based update for non-king moves (handles promotions, ep, captures) by recomputing active sets
-        let h = self.model.meta.hidden_dim;
-        let n = self.model.meta.input_dim;
-
-        let before_set = self.active.clone();
-        let mut after_set: HashSet<usize> = HashSet::new();
-        for (side, k_idx) in [(Color::White, wk_after), (Color::Black, bk_after)] {
-            for (pi, p) in HALFKP_PIECE_ORDER.iter().enumerate() {
-                let bb = after.colors(side) & after.pieces(*p);
-                for sq in bb {
-                    let s = format!("{}", sq); let b = s.as_bytes();
-                    let file = (b[0] - b'a') as usize; let rank = (b[1] - b'1') as usize; let sq_idx = rank * 8 + file;
-                    let idx = (((if side == Color::White { 0 } else { 1 }) * 64 + k_idx) * HALFKP_PIECE_ORDER.len() + pi) * 64 + sq_idx;
-                    after_set.insert(idx);
-                }
-            }
-        }
-        let removed: Vec<usize> = before_set.difference(&after_set).copied().collect();
-        let added: Vec<usize> = after_set.difference(&before_set).copied().collect();
-        // Apply removals
-        for idx in &removed { if self.active.remove(idx) { for j in 0..h { self.acc[j] -= self.model.w1[j * n + *idx] as i32; } } }
-        // Apply additions
-        for idx in &added { if self.active.insert(*idx) { for j in 0..h { self.acc[j] += self.model.w1[j * n + *idx] as i32; } } }
-        ChangeSet::Delta { added, removed }
+        eval_indices(&self.model, &self.feats.active_indices(board))
+    }
+
+    /// Move the current frame in place to `after`; undone by `revert`.
+    pub fn apply_move(&mut self, _before: &Board, _mv: Move, after: &Board) -> ChangeSet {
+        let act = self.feats.active_indices(after);
+        self.update_to_indices(&act)
     }
 
     pub fn revert(&mut self, change: ChangeSet) {
+        let frame = &mut self.frames[self.top];
         match change {
-            ChangeSet::Snapshot { acc, active, wk_idx, bk_idx } => {
-                self.acc = acc; self.active = active; self.wk_idx = wk_idx; self.bk_idx = bk_idx;
-            }
+            ChangeSet::Snapshot { acc, active } => { frame.acc = acc; frame.active = active; }
             ChangeSet::Delta { added, removed } => {
-                let h = self.model.meta.hidden_dim; let n = self.model.meta.input_dim;
-                // Undo additions by subtracting
-                for idx in added { if self.active.remove(&idx) { for j in 0..h { self.acc[j] -= self.model.w1[j * n + idx] as i32; } } }
-                // Undo removals by adding back
-                for idx in removed { if self.active.insert(idx) { for j in 0..h { self.acc[j] += self.model.w1[j * n + idx] as i32; } } }
+                apply(&self.model, &mut frame.acc, &added, &removed);
+                frame.active.retain(|i| added.binary_search(i).is_err());
+                frame.active.extend_from_slice(&removed);
+                frame.active.sort_unstable();
             }
         }
     }
-    
+
+    /// Move the current frame in place to a new active set. King moves touch every
+    /// index, so large diffs fall back to snapshot + refresh.
+    pub fn update_to_indices(&mut self, after: &[usize]) -> ChangeSet {
+        let frame = &mut self.frames[self.top];
+        let mut sorted = after.to_vec();
+        sorted.sort_unstable();
+        let (removed, added) = diff(&frame.active, &sorted);
+        if removed.len() + added.len() > sorted.len() {
+            let snap = ChangeSet::Snapshot { acc: frame.acc.clone(), active: frame.active.clone() };
+            rebuild(&self.model, frame, &sorted);
+            return snap;
+        }
+        apply(&self.model, &mut frame.acc, &removed, &added);
+        frame.active = sorted;
+        ChangeSet::Delta { added, removed }
+    }
+
     fn eval_from_acc(&self) -> i32 {
         let h = self.model.meta.hidden_dim;
-        let mut out: i64 = self.model.b2[0] as i64;
-        for j in 0..h {
-            let v = self.acc[j].max(0) as i64;
-            out += (self.model.w2[j] as i64) * v;
-        }
-        out as i32
+        output_cp(head(&self.model, &self.frames[self.top].acc[..h]) as f64, self.model.meta.output_scale)
     }
 }
 
-fn square_index(board: &Board, side: Color, piece: Piece) -> usize {
-    let sq = (board.colors(side) & board.pieces(piece)).into_iter().next().unwrap();
-    let s = format!("{}", sq);
-    let b = s.as_bytes();
-    let file = (b[0] - b'a') as usize;
-    let rank = (b[1] - b'1') as usize;
-    rank * 8 + file
+// accum = b1 + sum of the active columns of w1
+fn rebuild(model: &QuantNnue, frame: &mut Frame, sorted: &[usize]) {
+    let h = model.meta.hidden_dim;
+    let n = model.meta.input_dim;
+    frame.acc.clear();
+    frame.acc.extend(model.b1[..h].iter().map(|&b| b as i32));
+    frame.active.clear();
+    frame.active.extend_from_slice(sorted);
+    for &idx in sorted {
+        for (a, &w) in frame.acc.iter_mut().zip(column(model, n, idx)) { *a += w as i32; }
+    }
 }
 
-fn active_indices_side_diff(board: &Board, side: Color, k_idx: usize) -> Vec<usize> {
-    let mut out = Vec::with_capacity(16);
-    for (pi, p) in HALFKP_PIECE_ORDER.iter().enumerate() {
-        let bb = board.colors(side) & board.pieces(*p);
-        for sq in bb {
-            let s = format!("{}", sq);
-            let b = s.as_bytes();
-            let file = (b[0] - b'a') as usize;
-            let rank = (b[1] - b'1') as usize;
-            let sq_idx = rank * 8 + file;
-            let idx = (((if side == Color::White { 0 } else { 1 }) * 64 + k_idx) * HALFKP_PIECE_ORDER.len() + pi) * 64 + sq_idx;
-            out.push(idx);
+// Column `idx` of w1 (hidden_dim rows of `n` inputs), top to bottom
+fn column(model: &QuantNnue, n: usize, idx: usize) -> impl Iterator<Item = &i8> { model.w1[idx..].iter().step_by(n) }
+
+// b2 + w2 . ReLU(acc), before the output scale
+fn head(model: &QuantNnue, acc: &[i32]) -> i64 {
+    acc.iter().zip(&model.w2).fold(model.b2[0] as i64, |out, (&v, &w)| out + (w as i64) * v.max(0) as i64)
+}
+
+fn apply(model: &QuantNnue, acc: &mut [i32], removed: &[usize], added: &[usize]) {
+    let h = model.meta.hidden_dim;
+    let n = model.meta.input_dim;
+    for &idx in removed { for (a, &w) in acc[..h].iter_mut().zip(column(model, n, idx)) { *a -= w as i32; } }
+    for &idx in added { for (a, &w) in acc[..h].iter_mut().zip(column(model, n, idx)) { *a += w as i32; } }
+}
+
+// (removed, added) between two sorted, duplicate-free index lists
+fn diff(before: &[usize], after: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        match (before.get(i), after.get(j)) {
+            (Some(a), Some(b)) if a == b => { i += 1; j += 1; }
+            (Some(a), Some(b)) if a < b => { removed.push(*a); i += 1; }
+            (Some(a), None) => { removed.push(*a); i += 1; }
+            (_, Some(b)) => { added.push(*b); j += 1; }
+            (None, None) => unreachable!(),
         }
     }
-    out
+    (removed, added)
+}
+
+/// White-relative centipawns of `model` for an active feature set, computed from
+/// scratch (no accumulator).
+pub fn eval_indices(model: &QuantNnue, act: &[usize]) -> i32 {
+    let h = model.meta.hidden_dim;
+    let n = model.meta.input_dim;
+    let mut y: Vec<i32> = model.b1[..h].iter().map(|&b| b as i32).collect();
+    for &idx in act {
+        for (a, &w) in y.iter_mut().zip(column(model, n, idx)) { *a += w as i32; }
+    }
+    output_cp(head(model, &y) as f64, model.meta.output_scale)
+}
+
+/// Default `SmallNet::max_phase`: roughly a rook and a minor each, or less.
+pub const SMALL_NET_PHASE: u32 = 6;
+
+/// A second, smaller network for endgames and low time (the "small net" of a dual-net
+/// setup). It keeps no accumulator: with few pieces on, evaluating from scratch costs
+/// about as much as an incremental update, and the main network's stack stays the
+/// only one pushed and popped per move.
+#[derive(Clone)]
+pub struct SmallNet {
+    pub model: Arc<QuantNnue>,
+    /// Positions at or below this game phase (24 = all pieces, 0 = kings and pawns)
+    /// use the small net; the main network evaluates the rest.
+    pub max_phase: u32,
+}
+
+impl SmallNet {
+    pub fn new(model: impl Into<Arc<QuantNnue>>, max_phase: u32) -> Self { Self { model: model.into(), max_phase } }
+
+    /// Whether a position of `phase` is the small net's.
+    pub fn covers(&self, phase: u32) -> bool { phase <= self.max_phase }
+
+    pub fn eval_board(&self, board: &Board) -> i32 { eval_indices(&self.model, &HalfKpA.active_indices(board)) }
 }
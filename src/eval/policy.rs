@@ -0,0 +1,264 @@
+//! A tiny policy network for move ordering: side-to-move-relative piece squares in,
+//! one ReLU hidden layer, a logit per (from, to) pair out. It is only consulted where
+//! the history table has nothing yet (early iterations, fresh nodes), so it needs to
+//! be cheap rather than strong; `bin/policy` trains and exports one from PGN games.
+
+use crate::error::{Result, TitanError};
+use crate::eval::nnue::loader::Fingerprint;
+use cozy_chess::{Board, Color, Move, Piece};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Own and opponent pieces of six kinds on 64 squares.
+pub const POLICY_INPUTS: usize = 2 * 6 * 64;
+/// One logit per from-to pair; promotions share their pawn move's.
+pub const POLICY_MOVES: usize = 64 * 64;
+/// Largest hidden layer a file may declare; bigger headers are corrupt.
+pub const MAX_POLICY_HIDDEN: usize = 4096;
+const P_MAGIC: &[u8; 8] = b"PIEPOL01"; // Pie policy v1
+/// Ordering points for the net's favourite move by default (a first killer is 50).
+pub const DEFAULT_POLICY_WEIGHT: u32 = 40;
+/// Shallowest remaining depth at which a node asks the net: below it a forward pass
+/// costs more than the ordering saves.
+pub const POLICY_MIN_DEPTH: u32 = 2;
+
+/// Weights as stored: `w1` input-major (the active inputs' rows are contiguous),
+/// `w2` move-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyNet {
+    pub hidden: usize,
+    w1: Vec<f32>, // input x hidden
+    b1: Vec<f32>, // hidden
+    w2: Vec<f32>, // move x hidden
+    b2: Vec<f32>, // move
+}
+
+/// Inputs of `board` from the side to move's view: its pieces first, squares
+/// flipped for Black.
+pub fn features(board: &Board) -> Vec<usize> {
+    let stm = board.side_to_move();
+    let mut out = Vec::with_capacity(32);
+    for (side, color) in [(0, stm), (1, !stm)] {
+        for (pi, &p) in Piece::ALL.iter().enumerate() {
+            for sq in board.colors(color) & board.pieces(p) {
+                out.push(feature(side, pi, orient(stm == Color::White, sq as usize)));
+            }
+        }
+    }
+    out
+}
+
+/// `features` for a pleco board.
+#[cfg(feature = "board-pleco")]
+pub fn features_pleco(board: &pleco::Board) -> Vec<usize> {
+    use pleco::{PieceType, Player};
+    const KINDS: [PieceType; 6] = [PieceType::P, PieceType::N, PieceType::B, PieceType::R, PieceType::Q, PieceType::K];
+    let stm = board.turn();
+    let them = if stm == Player::White { Player::Black } else { Player::White };
+    let mut out = Vec::with_capacity(32);
+    for (side, player) in [(0, stm), (1, them)] {
+        for (pi, &p) in KINDS.iter().enumerate() {
+            for sq in board.piece_bb(player, p) {
+                out.push(feature(side, pi, orient(stm == Player::White, sq.0 as usize)));
+            }
+        }
+    }
+    out
+}
+
+fn feature(side: usize, piece: usize, sq: usize) -> usize { (side * 6 + piece) * 64 + sq }
+
+fn orient(white_to_move: bool, sq: usize) -> usize { if white_to_move { sq } else { sq ^ 56 } }
+
+/// Output index of a move from `from` to `to` (0..64 squares, board orientation).
+pub fn move_index(white_to_move: bool, from: usize, to: usize) -> usize {
+    orient(white_to_move, from) * 64 + orient(white_to_move, to)
+}
+
+fn cozy_move_index(board: &Board, m: Move) -> usize {
+    move_index(board.side_to_move() == Color::White, m.from as usize, m.to as usize)
+}
+
+/// Ordering bonuses at one node (see `PolicyNet::node`).
+pub struct NodePolicy<'a> {
+    net: &'a PolicyNet,
+    hidden: Vec<f32>,
+    max: f32,
+    weight: f32,
+    white: bool,
+}
+
+impl NodePolicy<'_> {
+    /// `weight` for the net's favourite move, less for the others in proportion to
+    /// their probability relative to it.
+    pub fn bonus(&self, from: usize, to: usize) -> i32 {
+        let z = self.net.logit(&self.hidden, move_index(self.white, from, to));
+        (self.weight * (z - self.max).exp()).round() as i32
+    }
+}
+
+impl PolicyNet {
+    /// A freshly initialised network (small random weights from `seed`) to train.
+    pub fn new(hidden: usize, seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut init = |n: usize, scale: f32| (0..n).map(|_| rng.gen_range(-scale..scale)).collect::<Vec<f32>>();
+        let w1 = init(POLICY_INPUTS * hidden, 0.1);
+        let w2 = init(POLICY_MOVES * hidden, 0.1);
+        Self { hidden, w1, b1: vec![0.0; hidden], w2, b2: vec![0.0; POLICY_MOVES] }
+    }
+
+    /// Content hash, as `QuantNnue::fingerprint`.
+    pub fn fingerprint(&self) -> u64 {
+        let h = Fingerprint::new().dims(&[POLICY_INPUTS, self.hidden, POLICY_MOVES]);
+        [&self.w1, &self.b1, &self.w2, &self.b2].iter()
+            .fold(h, |h, layer| layer.iter().fold(h, |h, v| h.bytes(&v.to_le_bytes())))
+            .finish()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        // Format:
+        // magic: 8 bytes b"PIEPOL01"
+        // u32 version (1), u32 hidden (LE)
+        // f32 w1[768 * hidden] (input-major), f32 b1[hidden]
+        // f32 w2[4096 * hidden] (move-major), f32 b2[4096]
+        let f = File::open(&path).map_err(|e| TitanError::io(&path, e))?;
+        Self::from_reader(BufReader::new(f))
+    }
+
+    /// Parse the policy format from any byte source (see `load`). Unlike the NNUE
+    /// readers, a short file is an error: there is no sensible padding for a policy.
+    pub fn from_reader<R: Read>(mut r: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic).map_err(policy_err("read magic"))?;
+        if &magic != P_MAGIC { return Err(TitanError::Policy("bad policy magic".into())); }
+        let mut b4 = [0u8; 4];
+        r.read_exact(&mut b4).map_err(policy_err("read version"))?;
+        let version = u32::from_le_bytes(b4);
+        if version != 1 { return Err(TitanError::Policy(format!("unsupported version {}", version))); }
+        r.read_exact(&mut b4).map_err(policy_err("read hidden"))?;
+        let hidden = u32::from_le_bytes(b4) as usize;
+        if hidden == 0 || hidden > MAX_POLICY_HIDDEN { return Err(TitanError::Policy(format!("hidden size {} out of range", hidden))); }
+        let mut read_f32s = |n: usize, what: &'static str| -> Result<Vec<f32>> {
+            let mut buf = vec![0u8; n * 4];
+            r.read_exact(&mut buf).map_err(policy_err(what))?;
+            Ok(buf.chunks_exact(4).map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect())
+        };
+        let w1 = read_f32s(POLICY_INPUTS * hidden, "read w1")?;
+        let b1 = read_f32s(hidden, "read b1")?;
+        let w2 = read_f32s(POLICY_MOVES * hidden, "read w2")?;
+        let b2 = read_f32s(POLICY_MOVES, "read b2")?;
+        Ok(Self { hidden, w1, b1, w2, b2 })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = File::create(&path).map_err(|e| TitanError::io(&path, e))?;
+        let mut w = BufWriter::new(f);
+        self.write_to(&mut w).and_then(|_| w.flush()).map_err(|e| TitanError::io(&path, e))
+    }
+
+    /// The `load` format.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(P_MAGIC)?;
+        w.write_all(&1u32.to_le_bytes())?;
+        w.write_all(&(self.hidden as u32).to_le_bytes())?;
+        for layer in [&self.w1, &self.b1, &self.w2, &self.b2] {
+            for v in layer.iter() { w.write_all(&v.to_le_bytes())?; }
+        }
+        Ok(())
+    }
+
+    // Hidden layer before the ReLU
+    fn pre_activation(&self, feats: &[usize]) -> Vec<f32> {
+        let mut h = self.b1.clone();
+        for &f in feats {
+            for (hj, w) in h.iter_mut().zip(&self.w1[f * self.hidden..(f + 1) * self.hidden]) { *hj += w; }
+        }
+        h
+    }
+
+    fn hidden_layer(&self, feats: &[usize]) -> Vec<f32> {
+        let mut h = self.pre_activation(feats);
+        for v in &mut h { *v = v.max(0.0); }
+        h
+    }
+
+    fn logit(&self, hidden: &[f32], mv: usize) -> f32 {
+        self.b2[mv] + self.w2[mv * self.hidden..(mv + 1) * self.hidden].iter().zip(hidden).map(|(w, h)| w * h).sum::<f32>()
+    }
+
+    fn node_from(&self, feats: &[usize], white: bool, moves: impl Iterator<Item = (usize, usize)>, weight: u32) -> NodePolicy<'_> {
+        let hidden = self.hidden_layer(feats);
+        let max = moves.map(|(f, t)| self.logit(&hidden, move_index(white, f, t))).fold(f32::NEG_INFINITY, f32::max);
+        NodePolicy { net: self, hidden, max, weight: weight as f32, white }
+    }
+
+    /// Ordering bonuses for `moves` in `board`, worth at most `weight`.
+    pub fn node(&self, board: &Board, moves: &[Move], weight: u32) -> NodePolicy<'_> {
+        let white = board.side_to_move() == Color::White;
+        self.node_from(&features(board), white, moves.iter().map(|m| (m.from as usize, m.to as usize)), weight)
+    }
+
+    /// `node` for a pleco board.
+    #[cfg(feature = "board-pleco")]
+    pub fn node_pleco(&self, board: &pleco::Board, moves: &[pleco::BitMove], weight: u32) -> NodePolicy<'_> {
+        let white = board.turn() == pleco::Player::White;
+        self.node_from(&features_pleco(board), white, moves.iter().map(|m| (m.get_src().0 as usize, m.get_dest().0 as usize)), weight)
+    }
+
+    /// Probability of each of `moves` (softmax over them).
+    pub fn probabilities(&self, board: &Board, moves: &[Move]) -> Vec<f32> {
+        let hidden = self.hidden_layer(&features(board));
+        softmax(moves.iter().map(|&m| self.logit(&hidden, cozy_move_index(board, m))).collect())
+    }
+
+    /// One SGD step of cross-entropy towards `target` over the legal moves of
+    /// `board`. Returns the loss before the step and whether `target` already had the
+    /// highest probability, or `None` if `target` is not legal there.
+    pub fn train_position(&mut self, board: &Board, target: Move, lr: f32) -> Option<(f32, bool)> {
+        let mut moves: Vec<Move> = Vec::new();
+        board.generate_moves(|ml| { moves.extend(ml); false });
+        // Promotions share a logit: train on one move per index
+        let mut idx: Vec<usize> = moves.iter().map(|&m| cozy_move_index(board, m)).collect();
+        idx.sort_unstable();
+        idx.dedup();
+        let t = idx.binary_search(&cozy_move_index(board, target)).ok().filter(|_| board.is_legal(target))?;
+        let feats = features(board);
+        let pre = self.pre_activation(&feats);
+        let hidden: Vec<f32> = pre.iter().map(|v| v.max(0.0)).collect();
+        let p = softmax(idx.iter().map(|&i| self.logit(&hidden, i)).collect());
+        let top = p.iter().all(|&q| q <= p[t]);
+        let loss = -p[t].max(1e-12).ln();
+        // dL/dz = p - onehot(target)
+        let h = self.hidden;
+        let mut dh = vec![0.0f32; h];
+        for (k, &i) in idx.iter().enumerate() {
+            let dz = p[k] - if k == t { 1.0 } else { 0.0 };
+            for ((d, w), hv) in dh.iter_mut().zip(&mut self.w2[i * h..(i + 1) * h]).zip(&hidden) {
+                *d += dz * *w;
+                *w -= lr * dz * hv;
+            }
+            self.b2[i] -= lr * dz;
+        }
+        for (d, &v) in dh.iter_mut().zip(&pre) { if v <= 0.0 { *d = 0.0; } }
+        for &f in &feats {
+            for (w, d) in self.w1[f * h..(f + 1) * h].iter_mut().zip(&dh) { *w -= lr * d; }
+        }
+        for (b, d) in self.b1.iter_mut().zip(&dh) { *b -= lr * d; }
+        Some((loss, top))
+    }
+}
+
+fn softmax(mut z: Vec<f32>) -> Vec<f32> {
+    let max = z.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut sum = 0.0;
+    for v in &mut z { *v = (*v - max).exp(); sum += *v; }
+    for v in &mut z { *v /= sum; }
+    z
+}
+
+fn policy_err(what: &'static str) -> impl FnOnce(std::io::Error) -> TitanError {
+    move |e| TitanError::Policy(format!("{}: {}", what, e))
+}
@@ -0,0 +1,56 @@
+//! Byte-input harnesses for the cargo-fuzz targets in `fuzz/`. Each entry point
+//! must return normally for every input; a panic is a bug.
+
+use crate::board::cozy::Position;
+use crate::eval::nnue::loader::QuantNnue;
+use crate::eval::nnue::network::QuantNetwork;
+use crate::eval::nnue::Nnue;
+use crate::uci::UciEngine;
+use cozy_chess::Board;
+
+/// FEN parsing on both boards, then move generation and one ply of every legal move.
+pub fn fen(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else { return; };
+    if let Ok(p) = Position::from_fen(s) {
+        let _ = p.legal_move_count();
+        let b = p.board();
+        b.generate_moves(|ml| { for m in ml { let mut c = b.clone(); c.play(m); } false });
+    }
+    #[cfg(feature = "board-pleco")]
+    if let Ok(mut b) = crate::board::pleco::parse_fen(s) {
+        for m in b.generate_moves().iter().copied() { b.apply_move(m); b.undo_move(); }
+    }
+}
+
+/// Feed newline-separated commands to the UCI dispatcher. Searches are capped at
+/// depth 1 / 5 ms, and options that spawn threads or touch the filesystem are
+/// skipped so the fuzzer spends its time in the parsers.
+pub fn uci(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    let mut engine = UciEngine::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let lower = line.to_lowercase();
+        if lower.starts_with("setoption") && (lower.contains("threads") || lower.contains("file") || lower.contains("dir")) { continue; }
+        let keep = if let Some(rest) = line.strip_prefix("go") {
+            // Later tokens win in the go parser; without the reader of `run_loop`
+            // nothing would stop an infinite or ponder search
+            let rest: Vec<&str> = rest.split_whitespace().filter(|t| !matches!(*t, "infinite" | "ponder")).collect();
+            engine.handle_line(&format!("go {} depth 1 movetime 5", rest.join(" ")))
+        } else {
+            engine.handle_line(line)
+        };
+        if !keep { break; }
+    }
+}
+
+/// Both network loaders on the raw bytes; whatever loads must also evaluate.
+pub fn nnue(data: &[u8]) {
+    if let Ok(nn) = Nnue::from_reader(data) { let _ = nn.evaluate(&Board::default()); }
+    if let Ok(model) = QuantNnue::from_reader(data) {
+        if QuantNetwork::validate(&model).is_ok() {
+            let mut net = QuantNetwork::new(model);
+            net.refresh(&Board::default());
+            let _ = net.eval_current();
+        }
+    }
+}
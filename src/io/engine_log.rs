@@ -0,0 +1,47 @@
+//! Per-game engine log: every UCI command received (`>>`), every line sent (`<<`)
+//! and internal warnings (`!!`), each stamped with milliseconds since the file was
+//! opened. Meant for tournaments where the GUI does not keep the engine's stdout.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Writes `engine_<unix seconds>_<game>.log` files into one directory; the next
+/// file is opened by the first line after `new_game`.
+pub struct EngineLog {
+    dir: PathBuf,
+    file: Option<(BufWriter<File>, Instant)>,
+    games: usize,
+}
+
+impl EngineLog {
+    /// Log into `dir`, created if missing.
+    pub fn new(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, file: None, games: 0 })
+    }
+
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    /// Close the current file; the next line starts a new one.
+    pub fn new_game(&mut self) { self.file = None; }
+
+    pub fn input(&mut self, line: &str) { self.write(">>", line); }
+    pub fn output(&mut self, line: &str) { self.write("<<", line); }
+    pub fn warning(&mut self, line: &str) { self.write("!!", line); }
+
+    // Flushed per line so a crash or a killed engine loses nothing
+    fn write(&mut self, tag: &str, line: &str) {
+        if self.file.is_none() {
+            self.games += 1;
+            let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            let path = self.dir.join(format!("engine_{}_{:03}.log", secs, self.games));
+            // An unwritable directory loses the log, never the game
+            self.file = File::create(path).ok().map(|f| (BufWriter::new(f), Instant::now()));
+        }
+        if let Some((w, start)) = &mut self.file {
+            let _ = writeln!(w, "{:>8} {} {}", start.elapsed().as_millis(), tag, line).and_then(|_| w.flush());
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! EPD test suites. A line is the four FEN fields followed by `;`-terminated
+//! operations; the runner reads `id`, `bm` (best moves), `am` (moves to avoid) and
+//! `dm` (direct mate: the side to move mates in N moves). Other opcodes are ignored,
+//! except `c0` (the game result), which `epd_result` reads for tuning.
+
+use crate::io::pgn::san_to_move;
+use cozy_chess::{Board, Move};
+
+#[derive(Clone, Debug)]
+pub struct EpdCase {
+    pub id: String,
+    pub board: Board,
+    pub best: Vec<Move>,
+    pub avoid: Vec<Move>,
+    /// Moves to mate (`dm`)
+    pub mate: Option<i32>,
+}
+
+impl EpdCase {
+    /// Search depth in plies needed to see the mate: the mating side's N moves and
+    /// the defender's N - 1 replies.
+    pub fn mate_plies(&self) -> Option<u32> {
+        self.mate.map(|n| (2 * n - 1).max(1) as u32)
+    }
+}
+
+// Split operations on ';' outside quotes, then each into opcode and operands
+fn operations(s: &str) -> Vec<(String, Vec<String>)> {
+    let mut ops = Vec::new();
+    let (mut words, mut word, mut quoted) = (Vec::<String>::new(), String::new(), false);
+    let flush = |word: &mut String, words: &mut Vec<String>| if !word.is_empty() { words.push(std::mem::take(word)); };
+    for c in s.chars() {
+        match c {
+            '"' => { quoted = !quoted; if !quoted { words.push(std::mem::take(&mut word)); } }
+            ';' if !quoted => {
+                flush(&mut word, &mut words);
+                if !words.is_empty() { let w = std::mem::take(&mut words); ops.push((w[0].clone(), w[1..].to_vec())); }
+            }
+            c if c.is_whitespace() && !quoted => flush(&mut word, &mut words),
+            c => word.push(c),
+        }
+    }
+    flush(&mut word, &mut words);
+    if !words.is_empty() { ops.push((words[0].clone(), words[1..].to_vec())); }
+    ops
+}
+
+// A move in SAN, or in the engine's coordinate notation
+fn parse_move(board: &Board, s: &str) -> Option<Move> {
+    san_to_move(board, s).or_else(|| {
+        let mut found = None;
+        board.generate_moves(|ml| { found = ml.into_iter().find(|m| format!("{}", m) == s); found.is_some() });
+        found
+    })
+}
+
+// The operations, which start after the fourth field
+fn after_fen(line: &str) -> &str {
+    let mut rest = line.trim_start();
+    for _ in 0..4 { rest = rest.trim_start().split_once(char::is_whitespace).map_or("", |(_, r)| r); }
+    rest
+}
+
+/// The game result of a line's `c0` operation (`"1-0"`, `"0-1"`, `"1/2-1/2"`) as
+/// White-relative 1, -1 or 0; `None` without one.
+pub fn epd_result(line: &str) -> Option<i8> {
+    let (_, args) = operations(after_fen(line)).into_iter().find(|(op, _)| op == "c0")?;
+    match args.first()?.as_str() { "1-0" => Some(1), "0-1" => Some(-1), "1/2-1/2" => Some(0), _ => None }
+}
+
+/// Parse one EPD line; `index` names the case when it has no `id`.
+pub fn parse_epd(line: &str, index: usize) -> Result<EpdCase, String> {
+    let fields: Vec<&str> = line.split_whitespace().take(4).collect();
+    if fields.len() < 4 { return Err(format!("expected four FEN fields, got '{}'", line.trim())); }
+    let board = Board::from_fen(&format!("{} 0 1", fields.join(" ")), false).map_err(|e| format!("{:?}", e))?;
+    let mut case = EpdCase { id: format!("#{}", index), board, best: Vec::new(), avoid: Vec::new(), mate: None };
+    for (op, args) in operations(after_fen(line)) {
+        let moves = |args: &[String]| args.iter().map(|a| parse_move(&case.board, a).ok_or_else(|| format!("{}: illegal move '{}'", op, a))).collect::<Result<Vec<_>, _>>();
+        match op.as_str() {
+            "id" => if let Some(id) = args.first() { case.id = id.clone(); },
+            "bm" => case.best = moves(&args)?,
+            "am" => case.avoid = moves(&args)?,
+            "dm" => match args.first().and_then(|a| a.parse::<i32>().ok()) {
+                Some(n) if n > 0 => case.mate = Some(n),
+                _ => return Err(format!("dm: expected a positive move count, got '{}'", args.join(" "))),
+            },
+            _ => {}
+        }
+    }
+    Ok(case)
+}
+
+/// Read an EPD suite, skipping blank lines and `#` comments. A bad line is an
+/// error naming its line number.
+pub fn load_epd(path: impl AsRef<std::path::Path>) -> crate::Result<Vec<EpdCase>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|e| crate::TitanError::io(path, e))?;
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') { continue; }
+        let case = parse_epd(l, out.len() + 1).map_err(|reason| crate::TitanError::Epd { path: path.display().to_string(), line: i + 1, reason })?;
+        out.push(case);
+    }
+    Ok(out)
+}
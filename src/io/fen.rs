@@ -1,3 +1,80 @@
 // Placeholder FEN utilities; we rely on cozy-chess for parsing.
 pub fn normalize_fen(fen: &str) -> String { fen.trim().to_string() }
 
+/// Small mixed suite (opening, middlegame, tactical, endgame) used by the bench tools.
+pub const BENCH_SUITE: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w KQ - 0 8",
+    "2r3k1/1q1nbppp/r3p3/3pP3/pPpP4/P1Q2N2/2RN1PPP/2R4K b - - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+];
+
+/// Read a suite file: one FEN per line, or JSONL objects with a "fen" field.
+pub fn load_fen_suite(path: &str) -> Vec<String> {
+    let mut fens = Vec::new();
+    if let Ok(text) = std::fs::read_to_string(path) {
+        for line in text.lines() {
+            let l = line.trim(); if l.is_empty() { continue; }
+            if l.starts_with('{') {
+                if let Ok(v) = serde_json::from_str::<serde_json::Value>(l) {
+                    if let Some(f) = v.get("fen").and_then(|x| x.as_str()) { fens.push(f.to_string()); }
+                }
+            } else { fens.push(l.to_string()); }
+        }
+    }
+    fens
+}
+
+/// Read an openings file: one FEN or four-field EPD per line, `#` comments and
+/// blank lines skipped. An unparseable line is skipped and reported on stderr with
+/// its line number, so a typo neither sinks the whole set nor shrinks it silently.
+pub fn load_openings(path: impl AsRef<std::path::Path>) -> crate::Result<Vec<cozy_chess::Board>> {
+    let (boards, bad) = read_openings(path)?;
+    for e in bad { eprintln!("openings: skipping {}", e); }
+    Ok(boards)
+}
+
+/// The positions of an openings file and a `TitanError::Openings` for each line
+/// that did not parse; only an unreadable file is an error.
+pub fn read_openings(path: impl AsRef<std::path::Path>) -> crate::Result<(Vec<cozy_chess::Board>, Vec<crate::TitanError>)> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|e| crate::TitanError::io(path, e))?;
+    let (mut out, mut bad) = (Vec::new(), Vec::new());
+    for (i, line) in text.lines().enumerate() {
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') { continue; }
+        match parse_fen_line(l) {
+            Ok(b) => out.push(b),
+            Err(reason) => bad.push(crate::TitanError::Openings { path: path.display().to_string(), line: i + 1, reason }),
+        }
+    }
+    Ok((out, bad))
+}
+
+/// One position line: a FEN, or an EPD whose missing move counters become `0 1`
+/// (operations after the four fields are ignored). Castling rights may be given as
+/// rook files (Shredder FEN), as Chess960 positions need.
+pub fn parse_fen_line(line: &str) -> Result<cozy_chess::Board, String> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let fen = match parts.len() {
+        n if n >= 6 && parts[4].parse::<u32>().is_ok() => parts[..6].join(" "),
+        n if n >= 4 => format!("{} 0 1", parts[..4].join(" ")),
+        _ => return Err(format!("expected a FEN, got '{}'", line)),
+    };
+    fen.parse::<cozy_chess::Board>().map_err(|e| format!("{:?}", e))
+}
+
+/// FEN of `board`, with castling rights as rook files (Shredder FEN) when a castling
+/// king or rook is off its standard square, so Chess960 positions read back intact.
+pub fn board_fen(board: &cozy_chess::Board) -> String {
+    use cozy_chess::{Color, File, Rank, Square};
+    let standard = Color::ALL.iter().all(|&c| {
+        let r = board.castle_rights(c);
+        (r.short.is_none() && r.long.is_none())
+            || (board.king(c) == Square::new(File::E, Rank::First.relative_to(c)) && r.short.is_none_or(|f| f == File::H) && r.long.is_none_or(|f| f == File::A))
+    });
+    if standard { format!("{}", board) } else { format!("{:#}", board) }
+}
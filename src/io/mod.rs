@@ -1,2 +1,5 @@
+pub mod engine_log;
+pub mod epd;
 pub mod fen;
-
+pub mod pgn;
+pub mod timeline;
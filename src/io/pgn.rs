@@ -0,0 +1,270 @@
+use cozy_chess::{Board, Move, Piece};
+use crate::board::cozy::has_legal_moves;
+
+/// A game read from PGN. Parsing stops at the first move that fails to resolve,
+/// so `moves` is always a legal sequence from `start`.
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub start: Board,
+    pub moves: Vec<Move>,
+    /// 1 white win, 0 draw, -1 black win; None for "*" or a missing result
+    pub result: Option<i8>,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+fn parse_result(s: &str) -> Option<i8> {
+    match s { "1-0" => Some(1), "0-1" => Some(-1), "1/2-1/2" => Some(0), _ => None }
+}
+
+/// Resolve a SAN token (e.g. "Nbd7", "exd5", "e8=Q+", "O-O") to a legal move.
+pub fn san_to_move(board: &Board, san: &str) -> Option<Move> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+    let mut legal: Vec<Move> = Vec::new();
+    board.generate_moves(|ml| { legal.extend(ml); false });
+    let stm = board.side_to_move();
+    if san == "O-O" || san == "0-0" || san == "O-O-O" || san == "0-0-0" {
+        let long = san.len() > 3;
+        let king = board.king(stm);
+        // cozy-chess encodes castling as the king capturing its own rook
+        return legal.into_iter().find(|m| {
+            m.from == king && board.colors(stm).has(m.to) && ((m.to as usize) < (king as usize)) == long
+        });
+    }
+    let bytes = san.as_bytes();
+    let (piece, rest) = match bytes.first()? {
+        b'N' => (Piece::Knight, &san[1..]),
+        b'B' => (Piece::Bishop, &san[1..]),
+        b'R' => (Piece::Rook, &san[1..]),
+        b'Q' => (Piece::Queen, &san[1..]),
+        b'K' => (Piece::King, &san[1..]),
+        _ => (Piece::Pawn, san),
+    };
+    let (body, promo) = match rest.split_once('=') {
+        Some((b, p)) => (b, p.chars().next()),
+        None if piece == Piece::Pawn && rest.ends_with(['N', 'B', 'R', 'Q']) => (&rest[..rest.len() - 1], rest.chars().last()),
+        None => (rest, None),
+    };
+    let promo = match promo {
+        Some('N') => Some(Piece::Knight), Some('B') => Some(Piece::Bishop),
+        Some('R') => Some(Piece::Rook), Some('Q') => Some(Piece::Queen),
+        Some(_) => return None, None => None,
+    };
+    let body: String = body.chars().filter(|&c| c != 'x' && c != '-').collect();
+    if body.len() < 2 { return None; }
+    let (disamb, dest) = body.split_at(body.len() - 2);
+    let candidates: Vec<Move> = legal.into_iter().filter(|m| {
+        if format!("{}", m.to) != dest || m.promotion != promo { return false; }
+        if board.piece_on(m.from) != Some(piece) { return false; }
+        let from = format!("{}", m.from);
+        disamb.chars().all(|c| from.contains(c))
+    }).collect();
+    if candidates.len() == 1 { Some(candidates[0]) } else { None }
+}
+
+/// Render a legal move in SAN, with check/mate suffix.
+pub fn move_to_san(board: &Board, m: Move) -> String {
+    let stm = board.side_to_move();
+    let piece = board.piece_on(m.from).unwrap_or(Piece::Pawn);
+    let from = format!("{}", m.from);
+    let to = format!("{}", m.to);
+    let mut san = if piece == Piece::King && board.colors(stm).has(m.to) {
+        if (m.to as usize) > (m.from as usize) { "O-O".to_string() } else { "O-O-O".to_string() }
+    } else {
+        let capture = board.colors(!stm).has(m.to) || (piece == Piece::Pawn && from[..1] != to[..1]);
+        let mut s = String::new();
+        if piece == Piece::Pawn {
+            if capture { s.push_str(&from[..1]); }
+        } else {
+            s.push(match piece { Piece::Knight => 'N', Piece::Bishop => 'B', Piece::Rook => 'R', Piece::Queen => 'Q', _ => 'K' });
+            let mut others: Vec<String> = Vec::new();
+            board.generate_moves(|ml| {
+                for o in ml { if o.to == m.to && o.from != m.from && board.piece_on(o.from) == Some(piece) { others.push(format!("{}", o.from)); } }
+                false
+            });
+            if !others.is_empty() {
+                if others.iter().all(|o| o[..1] != from[..1]) { s.push_str(&from[..1]); }
+                else if others.iter().all(|o| o[1..] != from[1..]) { s.push_str(&from[1..]); }
+                else { s.push_str(&from); }
+            }
+        }
+        if capture { s.push('x'); }
+        s.push_str(&to);
+        if let Some(p) = m.promotion {
+            s.push('=');
+            s.push(match p { Piece::Knight => 'N', Piece::Bishop => 'B', Piece::Rook => 'R', _ => 'Q' });
+        }
+        s
+    };
+    let mut after = board.clone();
+    after.play(m);
+    if !after.checkers().is_empty() {
+        san.push(if has_legal_moves(&after) { '+' } else { '#' });
+    }
+    san
+}
+
+/// Parse every game in a PGN text. Comments, variations, NAGs and move numbers are skipped.
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut tags: Vec<(String, String)> = Vec::new();
+    let mut movetext = String::new();
+    let mut flush = |tags: &mut Vec<(String, String)>, movetext: &mut String| {
+        if tags.is_empty() && movetext.trim().is_empty() { return; }
+        games.push(parse_game(std::mem::take(tags), movetext));
+        movetext.clear();
+    };
+    for line in text.lines() {
+        let l = line.trim();
+        if l.starts_with('%') { continue; }
+        if l.starts_with('[') && l.ends_with(']') {
+            // A tag after movetext starts the next game
+            if !movetext.trim().is_empty() { flush(&mut tags, &mut movetext); }
+            let inner = &l[1..l.len() - 1];
+            if let Some((k, v)) = inner.split_once(' ') {
+                tags.push((k.to_string(), v.trim().trim_matches('"').to_string()));
+            }
+        } else {
+            movetext.push_str(l);
+            movetext.push('\n');
+        }
+    }
+    flush(&mut tags, &mut movetext);
+    games
+}
+
+fn parse_game(tags: Vec<(String, String)>, movetext: &str) -> PgnGame {
+    let start = tags.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("FEN"))
+        .and_then(|(_, fen)| Board::from_fen(fen, false).ok())
+        .unwrap_or_default();
+    let mut result = tags.iter().find(|(k, _)| k == "Result").and_then(|(_, v)| parse_result(v));
+    // Strip comments and variations
+    let mut clean = String::with_capacity(movetext.len());
+    let (mut brace, mut paren) = (false, 0usize);
+    for line in movetext.lines() {
+        for c in line.chars() {
+            match c {
+                '{' if !brace => brace = true,
+                '}' if brace => brace = false,
+                ';' if !brace => break,
+                '(' if !brace => paren += 1,
+                ')' if !brace && paren > 0 => paren -= 1,
+                _ if brace || paren > 0 => {}
+                _ => clean.push(c),
+            }
+        }
+        clean.push(' ');
+    }
+    let mut board = start.clone();
+    let mut moves = Vec::new();
+    let mut ok = true;
+    for tok in clean.split_whitespace() {
+        if let Some(r) = parse_result(tok) { result = result.or(Some(r)); continue; }
+        if tok == "*" || tok.starts_with('$') { continue; }
+        // Drop move numbers like "12." or "12..." (possibly glued to the move)
+        let san = tok.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if san.is_empty() || !ok { continue; }
+        match san_to_move(&board, san) {
+            Some(m) => { board.play(m); moves.push(m); }
+            None => ok = false,
+        }
+    }
+    PgnGame { tags, start, moves, result }
+}
+
+/// Speed class of a game, using Lichess's bounds on the estimated duration
+/// `base + 40 * increment` (seconds).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeClass { UltraBullet, Bullet, Blitz, Rapid, Classical, Correspondence }
+
+impl TimeClass {
+    /// Classify a `TimeControl` tag: "300+3", "600", "40/7200" (CCRL, moves/seconds)
+    /// or "-" (correspondence). `None` for "?" or anything unparseable.
+    pub fn from_time_control(tc: &str) -> Option<Self> {
+        let tc = tc.trim();
+        if tc == "-" { return Some(TimeClass::Correspondence); }
+        // Multi-period controls ("40/7200:3600") are classified by their first period
+        let first = tc.split(':').next()?;
+        let clock = first.split_once('/').map_or(first, |(_, secs)| secs);
+        let (base, inc) = clock.split_once('+').unwrap_or((clock, "0"));
+        let estimate = base.parse::<f64>().ok()? + 40.0 * inc.parse::<f64>().ok()?;
+        Some(match estimate {
+            e if e < 29.0 => TimeClass::UltraBullet,
+            e if e < 179.0 => TimeClass::Bullet,
+            e if e < 479.0 => TimeClass::Blitz,
+            e if e < 1499.0 => TimeClass::Rapid,
+            _ => TimeClass::Classical,
+        })
+    }
+
+    /// From the `TimeControl` tag, falling back to a Lichess-style `Event`
+    /// ("Rated Blitz game").
+    pub fn of_game(game: &PgnGame) -> Option<Self> {
+        if let Some(c) = game.tag("TimeControl").and_then(Self::from_time_control) { return Some(c); }
+        let event = game.tag("Event")?.to_lowercase();
+        [("ultrabullet", TimeClass::UltraBullet), ("bullet", TimeClass::Bullet), ("blitz", TimeClass::Blitz), ("rapid", TimeClass::Rapid), ("classical", TimeClass::Classical), ("correspondence", TimeClass::Correspondence)]
+            .into_iter().find(|(name, _)| event.contains(name)).map(|(_, c)| c)
+    }
+}
+
+impl std::str::FromStr for TimeClass {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ultrabullet" => Ok(TimeClass::UltraBullet),
+            "bullet" => Ok(TimeClass::Bullet),
+            "blitz" => Ok(TimeClass::Blitz),
+            "rapid" => Ok(TimeClass::Rapid),
+            "classical" => Ok(TimeClass::Classical),
+            "correspondence" => Ok(TimeClass::Correspondence),
+            other => Err(format!("unknown time class '{}' (ultrabullet|bullet|blitz|rapid|classical|correspondence)", other)),
+        }
+    }
+}
+
+/// Tag-pair filter applied on import so only games worth learning from reach the
+/// training data. Every criterion is off by default; a game missing a tag that an
+/// active criterion needs is rejected.
+#[derive(Clone, Debug, Default)]
+pub struct PgnFilter {
+    /// Both `WhiteElo` and `BlackElo` must be at least this
+    pub min_elo: Option<u32>,
+    /// Allowed speed classes; empty allows all
+    pub time_classes: Vec<TimeClass>,
+    /// Reject bullet and ultrabullet games (on top of `time_classes`)
+    pub exclude_bullet: bool,
+    /// Allowed `Termination` values, case-insensitive (e.g. "Normal"); empty allows all
+    pub terminations: Vec<String>,
+}
+
+impl PgnFilter {
+    /// `Err` names the first criterion the game fails, for import statistics.
+    pub fn check(&self, game: &PgnGame) -> Result<(), &'static str> {
+        if let Some(min) = self.min_elo {
+            let elo = |tag| game.tag(tag).and_then(|v| v.trim().parse::<u32>().ok());
+            match (elo("WhiteElo"), elo("BlackElo")) {
+                (Some(w), Some(b)) if w >= min && b >= min => {}
+                (Some(_), Some(_)) => return Err("elo"),
+                _ => return Err("missing elo"),
+            }
+        }
+        if self.exclude_bullet || !self.time_classes.is_empty() {
+            let class = TimeClass::of_game(game).ok_or("missing time control")?;
+            if self.exclude_bullet && class <= TimeClass::Bullet { return Err("bullet"); }
+            if !self.time_classes.is_empty() && !self.time_classes.contains(&class) { return Err("time class"); }
+        }
+        if !self.terminations.is_empty() {
+            let t = game.tag("Termination").ok_or("missing termination")?;
+            if !self.terminations.iter().any(|a| a.eq_ignore_ascii_case(t.trim())) { return Err("termination"); }
+        }
+        Ok(())
+    }
+
+    pub fn accepts(&self, game: &PgnGame) -> bool { self.check(game).is_ok() }
+}
@@ -0,0 +1,88 @@
+use cozy_chess::{Board, Color};
+use serde::{Deserialize, Serialize};
+use crate::search::alphabeta::SearchResult;
+use crate::search::wdl::{material_units, WdlModel};
+
+/// Bump when fields change meaning; `scripts/plot_timeline.py` checks it.
+pub const TIMELINE_VERSION: u32 = 1;
+
+/// One search in a game: what the engine reached and what it cost.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimelinePoint {
+    /// Game ply from the fullmove counter (0 = white's first move)
+    pub ply: usize,
+    /// Fullmove number of the position searched
+    pub move_number: u32,
+    /// "w" or "b"
+    pub side: String,
+    /// Label of the engine that searched ("base", "exp", "uci", ...)
+    pub engine: String,
+    #[serde(rename = "move")]
+    pub mv: String,
+    pub depth: u32,
+    pub seldepth: u32,
+    /// Side-to-move score
+    pub score_cp: i32,
+    pub time_ms: u64,
+    pub nodes: u64,
+    pub nps: u64,
+    /// Win/draw/loss per mille for the side to move under the searching engine's
+    /// WDL model, comparable across engines with differently scaled evals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wdl: Option<[u32; 3]>,
+}
+
+/// Per-game timeline of depth, score, time and nodes by move, written as one
+/// JSON document per game.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameTimeline {
+    pub version: u32,
+    pub game: usize,
+    pub start_fen: String,
+    pub white: String,
+    pub black: String,
+    /// "1-0", "0-1", "1/2-1/2"; `None` while the game is running
+    pub result: Option<String>,
+    pub points: Vec<TimelinePoint>,
+}
+
+impl GameTimeline {
+    pub fn new(game: usize, start: &Board, white: &str, black: &str) -> Self {
+        Self { version: TIMELINE_VERSION, game, start_fen: crate::io::fen::board_fen(start), white: white.to_string(), black: black.to_string(), result: None, points: Vec::new() }
+    }
+
+    /// Record the search made in `board` (the position before the move was played).
+    pub fn push(&mut self, board: &Board, engine: &str, res: &SearchResult) {
+        let black = board.side_to_move() == Color::Black;
+        self.points.push(TimelinePoint {
+            ply: 2 * (board.fullmove_number().max(1) as usize - 1) + black as usize,
+            move_number: board.fullmove_number() as u32,
+            side: if black { "b" } else { "w" }.to_string(),
+            engine: engine.to_string(),
+            mv: res.bestmove.clone().unwrap_or_else(|| "0000".to_string()),
+            depth: res.depth,
+            seldepth: res.seldepth,
+            score_cp: res.score.value(),
+            time_ms: res.time_ms,
+            nodes: res.nodes,
+            nps: res.nps,
+            wdl: None,
+        });
+    }
+
+    /// `push`, with the score also converted to win/draw/loss by `model`.
+    pub fn push_wdl(&mut self, board: &Board, engine: &str, res: &SearchResult, model: &WdlModel) {
+        self.push(board, engine, res);
+        let (w, d, l) = model.wdl_permille(res.score.value(), material_units(board));
+        if let Some(p) = self.points.last_mut() { p.wdl = Some([w, d, l]); }
+    }
+
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).expect("timeline serializes") }
+
+    /// Write the whole timeline, replacing `path`.
+    pub fn write(&self, path: &std::path::Path) -> std::io::Result<()> { std::fs::write(path, self.to_json()) }
+
+    pub fn load(path: &std::path::Path) -> crate::Result<Self> {
+        crate::error::read_json(path)
+    }
+}
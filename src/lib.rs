@@ -6,5 +6,10 @@ pub mod io;
 pub mod search;
 pub mod selfplay;
 pub mod eval;
+pub mod error;
+pub mod version;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
 
 // Re-exports kept minimal for new engine path
+pub use error::{Result, TitanError};
@@ -1,4 +1,4 @@
-use cozy_chess::Board;
+use cozy_chess::{Board, Move, Piece};
 
 pub fn perft(board: &Board, depth: u32) -> u64 {
     if depth == 0 {
@@ -15,3 +15,55 @@ pub fn perft(board: &Board, depth: u32) -> u64 {
     });
     nodes
 }
+
+/// Move counts of one perft depth, by category as in the Chess Programming Wiki
+/// tables: captures include en passant, checks include checkmates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passant: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub checkmates: u64,
+}
+
+impl PerftStats {
+    fn record(&mut self, board: &Board, m: Move, child: &Board) {
+        let en_passant = board.piece_on(m.from) == Some(Piece::Pawn) && m.from.file() != m.to.file() && board.piece_on(m.to).is_none();
+        self.nodes += 1;
+        self.captures += u64::from(board.colors(!board.side_to_move()).has(m.to) || en_passant);
+        self.en_passant += u64::from(en_passant);
+        // Castling is encoded as the king taking its own rook
+        self.castles += u64::from(board.colors(board.side_to_move()).has(m.to));
+        self.promotions += u64::from(m.promotion.is_some());
+        if !child.checkers().is_empty() {
+            self.checks += 1;
+            self.checkmates += u64::from(!child.generate_moves(|_| true));
+        }
+    }
+}
+
+/// Perft with statistics: entry `d - 1` counts the moves made at depth `d`, for
+/// every depth up to `depth`. Pinpoints the move class a movegen bug affects.
+pub fn perft_detail(board: &Board, depth: u32) -> Vec<PerftStats> {
+    let mut stats = vec![PerftStats::default(); depth as usize];
+    detail(board, 0, &mut stats);
+    stats
+}
+
+fn detail(board: &Board, ply: usize, stats: &mut [PerftStats]) {
+    if ply >= stats.len() {
+        return;
+    }
+    board.generate_moves(|moves| {
+        for m in moves {
+            let mut child = board.clone();
+            child.play(m);
+            stats[ply].record(board, m, &child);
+            detail(&child, ply + 1, stats);
+        }
+        false
+    });
+}
@@ -0,0 +1,122 @@
+//! Search-thread core pinning. Left to the scheduler, Lazy-SMP workers migrate
+//! between P and E cores (and onto SMT siblings), which makes thread-scaling
+//! measurements on hybrid CPUs too noisy to compare.
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Where search workers run. `Auto` spreads them over physical cores (one logical
+/// CPU per core, fastest cores first); `Cores` pins worker `i` to the `i`-th listed
+/// core. With more workers than cores the list wraps.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CorePinning { #[default] Off, Auto, Cores(Vec<usize>) }
+
+impl CorePinning {
+    /// The logical CPUs workers are pinned to, in worker order (empty when off).
+    pub fn cores(&self) -> Vec<usize> {
+        match self {
+            CorePinning::Off => Vec::new(),
+            CorePinning::Auto => physical_cores(),
+            CorePinning::Cores(c) => c.clone(),
+        }
+    }
+}
+
+/// "off", "auto", or a core list like "0,2,4-7". `+` also separates entries so
+/// the list survives comma-separated option specs ("affinity=0+2+4-7").
+impl std::str::FromStr for CorePinning {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "off" | "none" => Ok(CorePinning::Off),
+            "auto" => Ok(CorePinning::Auto),
+            list => parse_cpu_list(list).filter(|c| !c.is_empty()).map(CorePinning::Cores)
+                .ok_or_else(|| format!("bad affinity '{}' (off|auto|core list like 0+2+4-7)", s.trim())),
+        }
+    }
+}
+
+impl std::fmt::Display for CorePinning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorePinning::Off => f.pad("off"),
+            CorePinning::Auto => f.pad("auto"),
+            CorePinning::Cores(c) => f.pad(&c.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("+")),
+        }
+    }
+}
+
+impl TryFrom<String> for CorePinning {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> { s.parse() }
+}
+
+impl From<CorePinning> for String {
+    fn from(p: CorePinning) -> String { p.to_string() }
+}
+
+/// Parse a Linux-style CPU list ("0-3,8,10-11"); `+` and spaces work as separators.
+fn parse_cpu_list(s: &str) -> Option<Vec<usize>> {
+    let mut out = Vec::new();
+    for part in s.split([',', '+', ' ']).map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((a, b)) => {
+                let (a, b) = (a.trim().parse::<usize>().ok()?, b.trim().parse::<usize>().ok()?);
+                if b < a { return None; }
+                out.extend(a..=b);
+            }
+            None => out.push(part.parse().ok()?),
+        }
+    }
+    Some(out)
+}
+
+/// One logical CPU per physical core, fastest cores first (P before E on hybrid
+/// parts). Falls back to every available CPU when the topology is unreadable.
+pub fn physical_cores() -> Vec<usize> {
+    let all = || (0..std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)).collect();
+    let online = match std::fs::read_to_string("/sys/devices/system/cpu/online").ok().and_then(|s| parse_cpu_list(s.trim())) {
+        Some(c) if !c.is_empty() => c,
+        _ => return all(),
+    };
+    let sys = |cpu: usize, file: &str| std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/{}", cpu, file)).ok();
+    let mut cores: Vec<(u64, usize)> = Vec::new();
+    for &cpu in &online {
+        // Keep the lowest-numbered sibling of each core
+        let first = sys(cpu, "topology/thread_siblings_list").and_then(|s| parse_cpu_list(s.trim())).and_then(|s| s.into_iter().min());
+        if first.is_some_and(|f| f != cpu) { continue; }
+        let max_khz = sys(cpu, "cpufreq/cpuinfo_max_freq").and_then(|s| s.trim().parse().ok()).unwrap_or(0);
+        cores.push((max_khz, cpu));
+    }
+    cores.sort_by_key(|&(khz, cpu)| (std::cmp::Reverse(khz), cpu));
+    cores.into_iter().map(|(_, cpu)| cpu).collect()
+}
+
+/// Pin the calling thread to one logical CPU; false when unsupported or refused.
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cpu: usize) -> bool {
+    if cpu >= libc::CPU_SETSIZE as usize { return false; }
+    // SAFETY: cpu_set_t is plain data and `cpu` is within its size
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_cpu: usize) -> bool { false }
+
+/// A search pool of `threads` workers, each pinned per `pinning` and given a
+/// `stack_mb` stack: qsearch under extensions recurses deeper than rayon's default
+/// allows at long time controls.
+pub fn thread_pool(threads: usize, pinning: &CorePinning, stack_mb: usize) -> ThreadPool {
+    let cores = pinning.cores();
+    let mut b = ThreadPoolBuilder::new().num_threads(threads.max(1)).stack_size(stack_mb.max(1) << 20);
+    if !cores.is_empty() {
+        b = b.start_handler(move |i| { pin_current_thread(cores[i % cores.len()]); });
+    }
+    b.build().expect("search thread pool")
+}
@@ -1,13 +1,25 @@
-use cozy_chess::{Board, Move, Square};
-use crate::search::eval::{eval_cp, MATE_SCORE, DRAW_SCORE};
+use cozy_chess::{Board, Color, Move, Piece, Rank, Square};
+use crate::search::aspiration::{Window, WindowStats, MIN_WINDOW};
+use crate::search::contempt::{Contempt, DrawBias};
+use crate::search::conversion;
+use crate::search::eval::{eval_cp, material_eval_cp, scale_eval, side_relative, KingAttack, MaterialSig, Score, MATE_BOUND, MATE_SCORE};
 use std::time::{Duration, Instant};
 use crate::search::zobrist;
-use crate::search::tt::{Tt, Entry, Bound};
+use crate::search::see::see_ge;
+use crate::search::tt::{Tt, Entry, Bound, MoveChecks};
+use crate::search::eval_hash::{EvalHash, EvalHashStats};
+use crate::search::score::MAX_PLY;
 use std::sync::Arc;
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicI32, Ordering};
-use crate::eval::nnue::network::QuantNetwork;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use crate::eval::nnue::network::{QuantNetwork, SmallNet};
 use crate::eval::nnue::loader::QuantNnue;
+use crate::eval::policy::{PolicyNet, DEFAULT_POLICY_WEIGHT, POLICY_MIN_DEPTH};
+use crate::search::profile::SearchProfile;
+use crate::search::guard::guarded;
+use crate::search::timeman::{best_move_share, ClockPlan, TimeManager, WallClock};
+use crate::board::cozy::{game_phase, has_legal_moves};
 const HIST_PROMO_KINDS: usize = 5; // None, N, B, R, Q
 const HIST_SIZE: usize = 64 * 64 * HIST_PROMO_KINDS;
 
@@ -22,6 +34,22 @@ fn promo_index(p: Option<cozy_chess::Piece>) -> usize {
     }
 }
 
+/// Destination of `m` if it takes an enemy piece (en passant excluded).
+#[inline]
+fn capture_square(board: &Board, m: Move) -> Option<Square> {
+    if board.colors(!board.side_to_move()).has(m.to) { Some(m.to) } else { None }
+}
+
+const CAPTURE_HIST_SIZE: usize = 6 * 64 * 6;
+
+/// Capture-history slot of `m`: moved piece, destination and captured piece.
+#[inline]
+fn capture_index(board: &Board, m: Move) -> Option<usize> {
+    capture_square(board, m)?;
+    let (moved, taken) = (board.piece_on(m.from)?, board.piece_on(m.to)?);
+    Some((moved as usize * 64 + m.to as usize) * 6 + taken as usize)
+}
+
 #[inline]
 fn move_index(m: Move) -> usize {
     let from = m.from as usize;
@@ -54,6 +82,11 @@ fn piece_at(board: &Board, sq: Square) -> Option<(cozy_chess::Color, cozy_chess:
     None
 }
 
+#[inline]
+fn killer_bonus(killers: &[Option<Move>; 2], m: Move) -> i32 {
+    if killers[0] == Some(m) { 50 } else if killers[1] == Some(m) { 30 } else { 0 }
+}
+
 #[inline]
 fn mvv_lva_score(board: &Board, m: Move) -> i32 {
     let to = m.to; let from = m.from;
@@ -62,9 +95,61 @@ fn mvv_lva_score(board: &Board, m: Move) -> i32 {
     victim * 10 - attacker
 }
 
+/// Expected type of a node, predicted from its parent before it is searched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeType {
+    /// On the principal variation: exact score expected
+    Pv,
+    /// Expected to fail high: one good move should refute the parent's move
+    Cut,
+    /// Expected to fail low: every move has to be searched
+    All,
+}
+
+impl NodeType {
+    /// Expected type of the child reached by the `idx`-th move searched here: the
+    /// eldest son of a PV node stays on the PV, the eldest of a cut node is an all
+    /// node, and every later move (and every child of an all node) is a cut node.
+    pub fn child(self, idx: usize) -> NodeType {
+        match (self, idx) {
+            (NodeType::Pv, 0) => NodeType::Pv,
+            (NodeType::Cut, 0) => NodeType::All,
+            _ => NodeType::Cut,
+        }
+    }
+}
+
+/// Minimum depth for the internal iterative reduction of PV and cut nodes without a TT move.
+pub(crate) const IIR_DEPTH: u32 = 4;
+
+/// SEE pruning (`SearchParams::see_prune_quiets`/`see_prune_captures`) works at
+/// this remaining depth and below.
+pub const SEE_PRUNE_DEPTH: u32 = 6;
+/// A quiet move is skipped when it loses more than this per ply of depth (cp).
+pub const SEE_QUIET_MARGIN: i32 = 60;
+/// A capture is skipped when it loses more than this per ply of depth (cp).
+pub const SEE_CAPTURE_MARGIN: i32 = 100;
+
+/// Default history points per ply of LMR adjustment (`SearchParams::lmr_history_div`).
+pub const LMR_HISTORY_DIV: i32 = 256;
+
+/// Default explosion guard (`SearchParams::explosion_guard`): a root move may take
+/// eight times the nodes of the whole previous iteration.
+pub const EXPLOSION_GUARD: u32 = 8;
+/// Iterations before this depth are too small for node counts to mean much, so the
+/// guard stays off.
+pub const GUARD_MIN_DEPTH: u32 = 5;
+/// Nodes per millisecond a deterministic search spends for its time limits: a
+/// fixed rate, so the same limits give the same nodes on any machine.
+pub const DETERMINISTIC_NODES_PER_MS: u64 = 500;
+
 #[derive(Default, Debug, Clone, Copy)]
 pub struct SearchParams {
+    /// Depth limit, 0 = none; with `movetime` too the search stops at whichever
+    /// comes first (`timeman::SearchLimits`)
     pub depth: u32,
+    /// Iterations completed before `movetime` or the clock may stop the search
+    pub min_depth: u32,
     pub use_tt: bool,
     pub max_nodes: Option<u64>,
     pub movetime: Option<Duration>,
@@ -76,21 +161,313 @@ pub struct SearchParams {
     pub use_lmr: bool,
     pub use_killers: bool,
     pub use_nullmove: bool,
+    /// Reproducible search: one thread, and `movetime` or the clock's soft budget
+    /// becomes a node budget at `DETERMINISTIC_NODES_PER_MS` (`deterministic_limits`)
     pub deterministic: bool,
+    /// Search the root moves one by one even with `threads > 1`, so every split is
+    /// inside the tree
+    pub serial_root: bool,
+    /// One-ply extensions (recaptures, pawn pushes to the 7th) allowed along a
+    /// single line; 0 disables them.
+    pub max_extensions: u32,
+    /// Skip LMR and null-move pruning at PV nodes (analysis: exact PV scores)
+    pub protect_pv: bool,
+    /// Extra LMR plies for moves ordered 8th or later; 0 keeps the one-ply reduction
+    pub lmr_late_extra: u32,
+    /// History points per ply of LMR adjustment: a late move without history is
+    /// reduced a ply more, one with this many points as usual, one with twice as
+    /// many a ply less (quiet history for quiets, capture history for captures);
+    /// 0 keeps the fixed reductions
+    pub lmr_history_div: i32,
+    /// Reduce late captures that lose material by SEE like late quiets
+    pub lmr_captures: bool,
+    /// Skip null move and reduce late moves one ply less where the side to move
+    /// has a dangerous attack on the enemy king (`eval::KingAttack`)
+    pub king_danger: bool,
+    /// Predict PV/cut/all nodes: reduce depth by one where a PV or cut node has no
+    /// TT move (IIR), reduce late moves one ply more at cut nodes, and skip null
+    /// move at all nodes
+    pub node_types: bool,
+    /// Quiet killers and countermoves earn their ordering bonus only if they do
+    /// not hang the moved piece (`see::see_ge`); hanging quiets are reduced a ply more
+    pub see_quiets: bool,
+    /// Skip quiet moves whose SEE is below `-SEE_QUIET_MARGIN * depth`, and captures
+    /// below `-SEE_CAPTURE_MARGIN * depth`, at depth `SEE_PRUNE_DEPTH` or less; never
+    /// the first move, checks, extended moves, in check or along a protected PV
+    pub see_prune_quiets: bool,
+    pub see_prune_captures: bool,
+    /// In-tree split policy and thresholds (threads > 1)
+    pub split: SplitConfig,
+    /// Draw scoring (static and dynamic contempt)
+    pub contempt: Contempt,
+    /// Conversion mode when the root is a TB win (`search::conversion`): no
+    /// contempt, null move or LMR, and an eval that drives the mate
+    pub conversion: bool,
+    /// Clock play: don't start an iteration past the soft limit or one predicted
+    /// to overrun the hard limit; `movetime` remains the abort deadline
+    pub clock: Option<ClockPlan>,
+    /// A root move searched inside an aspiration window may take this many times
+    /// the previous iteration's nodes; past that it is re-searched a ply shallower
+    /// with a full window, and the iteration is not re-searched. 0 = no guard
+    pub explosion_guard: u32,
+    /// Start aspiration windows from the game's fail and score-swing statistics
+    /// (`search::aspiration`) instead of the fixed `aspiration_window_cp`
+    pub adaptive_window: bool,
+}
+
+impl SearchParams {
+    /// These parameters with wall-clock limits replaced by a node budget and one
+    /// thread, so nothing depends on timing or thread scheduling. A clock's hard
+    /// limit stays as the abort deadline: only a machine too slow for the budget
+    /// reaches it, and losing on time is worse than losing reproducibility.
+    pub fn deterministic_limits(self) -> Self {
+        let ms = self.clock.map(|c| c.budget.soft).or(self.movetime).map(|t| t.as_millis() as u64);
+        let budget = ms.map(|ms| ms.max(1).saturating_mul(DETERMINISTIC_NODES_PER_MS));
+        let max_nodes = match (self.max_nodes, budget) { (Some(a), Some(b)) => Some(a.min(b)), (a, b) => a.or(b) };
+        Self { threads: 1, movetime: self.clock.map(|c| c.budget.hard), clock: None, max_nodes, ..self }
+    }
+}
+
+/// How the in-tree parallel search shares a node's moves between threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitPolicy {
+    /// Young Brothers Wait: the eldest move is searched first (and may split further
+    /// down); the younger moves go parallel only if it did not cut off
+    #[default]
+    Ybwc,
+    /// The earlier scheme: the eldest is searched by one helper, then every other
+    /// move in parallel whatever it returned
+    Jamboree,
+}
+
+impl std::str::FromStr for SplitPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ybwc" => Ok(SplitPolicy::Ybwc),
+            "jamboree" => Ok(SplitPolicy::Jamboree),
+            other => Err(format!("bad split policy '{}' (ybwc|jamboree)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for SplitPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self { SplitPolicy::Ybwc => "ybwc", SplitPolicy::Jamboree => "jamboree" })
+    }
+}
+
+/// How Lazy SMP helpers (pleco `SmpMode::LazyIndep`) spread over the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HelperScheme {
+    /// Helpers iterate the same depths as the main thread, each with a rotated
+    /// move-order tail
+    #[default]
+    Rotate,
+    /// Helpers keep the main thread's move order but skip iterations on staggered
+    /// patterns, so most of the time they search a ply or two deeper and seed the TT
+    /// ahead of it
+    Skip,
+}
+
+impl std::str::FromStr for HelperScheme {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rotate" => Ok(HelperScheme::Rotate),
+            "skip" => Ok(HelperScheme::Skip),
+            other => Err(format!("bad helper scheme '{}' (rotate|skip)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for HelperScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self { HelperScheme::Rotate => "rotate", HelperScheme::Skip => "skip" })
+    }
+}
+
+/// Where in-tree splits may happen: a node needs `min_depth` plies left and at
+/// least `min_moves` legal moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitConfig { pub policy: SplitPolicy, pub min_depth: u32, pub min_moves: usize }
+
+impl Default for SplitConfig {
+    fn default() -> Self { Self { policy: SplitPolicy::Ybwc, min_depth: 3, min_moves: 6 } }
+}
+
+impl SplitConfig {
+    /// The previous fixed scheme (depth >= 3, >= 12 moves), kept for comparison runs
+    pub const JAMBOREE: SplitConfig = SplitConfig { policy: SplitPolicy::Jamboree, min_depth: 3, min_moves: 12 };
+
+    pub(crate) fn splits(&self, threads: usize, depth: u32, moves: usize) -> bool {
+        threads > 1 && depth >= self.min_depth && moves >= self.min_moves
+    }
+}
+
+/// Late-move pruning thresholds: at `depth` plies left, quiet moves ordered at or
+/// after `moves[depth - 1][improving]` are skipped. `improving` is whether the
+/// static eval rose since the side to move's previous turn; such nodes keep more
+/// moves. Written `10/15:8/12:6/9` (not improving/improving, depth 1 first).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct LmpTable { pub moves: [[usize; 2]; LmpTable::DEPTH] }
+
+impl Default for LmpTable {
+    fn default() -> Self { Self { moves: [[10, 15], [8, 12], [6, 9]] } }
+}
+
+impl LmpTable {
+    /// Deepest remaining depth with a threshold; LMP is off above it.
+    pub const DEPTH: usize = 3;
+
+    /// Whether the quiet move at `index` in the move order is pruned.
+    pub fn prunes(&self, depth: u32, improving: bool, index: usize) -> bool {
+        (1..=Self::DEPTH as u32).contains(&depth) && index >= self.moves[depth as usize - 1][usize::from(improving)]
+    }
+}
+
+impl std::str::FromStr for LmpTable {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let bad = || format!("bad lmp table '{}' (e.g. 10/15:8/12:6/9)", s);
+        let rows: Vec<&str> = s.trim().split(':').collect();
+        if rows.len() != Self::DEPTH { return Err(bad()); }
+        let mut t = LmpTable::default();
+        for (row, text) in t.moves.iter_mut().zip(rows) {
+            let (a, b) = text.trim().split_once('/').ok_or_else(bad)?;
+            *row = [a.trim().parse().map_err(|_| bad())?, b.trim().parse().map_err(|_| bad())?];
+        }
+        Ok(t)
+    }
+}
+
+impl std::fmt::Display for LmpTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<String> = self.moves.iter().map(|[a, b]| format!("{}/{}", a, b)).collect();
+        f.pad(&rows.join(":"))
+    }
+}
+
+/// Beta cutoffs of one search by the index of the move that cut in the ordered
+/// list (the last bucket collects the 8th move and later): a measure of move
+/// ordering quality. Counts the calling thread's serial nodes only.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CutoffStats { pub by_index: [u64; CutoffStats::BUCKETS] }
+
+impl CutoffStats {
+    pub const BUCKETS: usize = 8;
+    /// Remaining depths kept apart by `Searcher::cutoff_stats_by_depth`; deeper
+    /// nodes share the last entry.
+    pub const DEPTHS: usize = 16;
+    pub fn record(&mut self, index: usize) { self.by_index[index.min(Self::BUCKETS - 1)] += 1; }
+    pub fn total(&self) -> u64 { self.by_index.iter().sum() }
+    /// Share of cutoffs made by the first move searched.
+    pub fn first_move_rate(&self) -> f64 { if self.total() == 0 { 0.0 } else { self.by_index[0] as f64 / self.total() as f64 } }
+    /// Mean index of the cutting move (late moves counted as the last bucket).
+    pub fn mean_index(&self) -> f64 {
+        if self.total() == 0 { return 0.0; }
+        self.by_index.iter().enumerate().map(|(i, &n)| i as f64 * n as f64).sum::<f64>() / self.total() as f64
+    }
+}
+
+/// In-tree split accounting for one search. `wasted_nodes` are worker nodes spent on
+/// moves ordered after the first one that failed high at a split point; a serial
+/// search would have stopped at that move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SplitStats { pub splits: u64, pub cutoffs: u64, pub split_nodes: u64, pub wasted_nodes: u64 }
+
+impl SplitStats {
+    pub fn merge(&mut self, o: &SplitStats) {
+        self.splits += o.splits; self.cutoffs += o.cutoffs; self.split_nodes += o.split_nodes; self.wasted_nodes += o.wasted_nodes;
+    }
+    pub fn wasted_fraction(&self) -> f64 { if self.split_nodes == 0 { 0.0 } else { self.wasted_nodes as f64 / self.split_nodes as f64 } }
+
+    /// Record one split point from its parallel moves' (score, nodes), in move order.
+    /// `cut` is set when a move searched before the split already failed high.
+    pub(crate) fn record(&mut self, tails: impl IntoIterator<Item = (i32, u64)>, beta: i32, mut cut: bool) {
+        self.splits += 1;
+        for (score, nodes) in tails {
+            self.split_nodes += nodes;
+            if cut { self.wasted_nodes += nodes; }
+            cut |= score >= beta;
+        }
+        if cut { self.cutoffs += 1; }
+    }
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct SearchResult {
     pub bestmove: Option<String>,
-    pub score_cp: i32,
+    pub score: Score,
     pub nodes: u64,
+    pub depth: u32,     // last completed iteration
+    pub seldepth: u32,  // deepest ply reached, including qsearch
+    pub time_ms: u64,
+    pub nps: u64,
+    pub tbhits: u64,    // reserved for tablebase probing
+    pub bestmove_changes: u32, // iterations whose best move differed from the previous one
+    pub pv: Vec<String>, // principal variation from `bestmove`; empty when only the move is known
+}
+
+/// One root move's score from an iteration. Moves searched after alpha was raised
+/// usually fail low, so their score is only an upper bound.
+#[derive(Clone, Copy, Debug)]
+pub struct RootMoveScore { pub mv: Move, pub score_cp: i32, pub bound: Bound }
+
+/// Root move scores from one completed iteration, in search order.
+#[derive(Clone, Debug)]
+pub struct RootIteration { pub depth: u32, pub scores: Vec<RootMoveScore> }
+
+/// How MultiPV lines are produced (UCI `AnalysisStyle`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AnalysisStyle {
+    /// Search, exclude the best move, search again (exact scores, N full searches)
+    #[default]
+    Exclusion,
+    /// One shallower search per root move (cheaper, one ply less deep)
+    Scored,
+}
+
+impl std::str::FromStr for AnalysisStyle {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "exclusion" => Ok(AnalysisStyle::Exclusion),
+            "scored" => Ok(AnalysisStyle::Scored),
+            other => Err(format!("unknown analysis style '{}'", other)),
+        }
+    }
+}
+
+/// Called after every completed iteration with the result so far (UCI `info` lines).
+pub type InfoHook = Arc<dyn Fn(&SearchResult) + Send + Sync>;
+
+impl SearchResult {
+    /// Fill `time_ms` and `nps` from the search's own elapsed time.
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.time_ms = elapsed.as_millis() as u64;
+        let secs = elapsed.as_secs_f64();
+        self.nps = if secs > 0.0 { (self.nodes as f64 / secs) as u64 } else { 0 };
+        self
+    }
+
+    #[deprecated(note = "use the `score` field")]
+    pub fn score_cp(&self) -> i32 { self.score.value() }
 }
 
 pub struct Searcher {
     tt: Arc<Tt>,
+    // NNUE outputs by position, shared with workers like the TT
+    eval_hash: Arc<EvalHash>,
     pub(crate) nodes: u64,
+    seldepth: u32,
     node_limit: u64,
     deadline: Option<Instant>,
+    // UCI `stop`: ends the search like a passed deadline, workers included
+    stop: Option<Arc<AtomicBool>>,
     order_captures: bool,
     use_history: bool,
     threads: usize,
@@ -100,15 +477,74 @@ pub struct Searcher {
     use_lmr: bool,
     use_killers: bool,
     use_nullmove: bool,
+    // Extension budget per line, extensions used on the current line, and the
+    // square the move into this node captured on (for recapture extensions)
+    max_extensions: u32,
+    ext_used: u32,
+    prev_capture: Option<Square>,
+    protect_pv: bool,
+    lmr_late_extra: u32,
+    lmr_history_div: i32,
+    lmr_captures: bool,
+    king_danger: bool,
+    node_types: bool,
+    see_quiets: bool,
+    see_prune_quiets: bool,
+    see_prune_captures: bool,
+    cutoffs: CutoffStats,
+    cutoffs_by_depth: [CutoffStats; CutoffStats::DEPTHS],
+    split: SplitConfig,
+    split_stats: SplitStats,
+    // Draw scoring: contempt settings, their value for the current iteration, the
+    // engine's side, and position keys of the line being searched (repetitions)
+    contempt: Contempt,
+    draw_bias: DrawBias,
+    root_side: Color,
+    path: Vec<u64>,
+    // Keys of the game's positions before the root, oldest first (`set_game_history`)
+    game_history: Vec<u64>,
+    // Winning side while converting a TB win (`search::conversion`)
+    converting: Option<Color>,
+    // The TT generation was advanced by `new_search` for the next search
+    gen_fresh: bool,
     // Optional NNUE evaluator (scalar path for now)
     use_nnue: bool,
     nnue: Option<crate::eval::nnue::Nnue>,
     nnue_quant: Option<QuantNetwork>,
+    // Endgame network, and whether it evaluates every position this search (low time)
+    nnue_small: Option<SmallNet>,
+    small_net_only: bool,
     eval_blend_percent: u8, // 0..100, 0=PST only, 100=NNUE only
+    material_eval: bool,    // plain material instead of PST when NNUE is off
+    // Move-ordering policy net and the ordering points of its favourite move
+    policy: Option<Arc<PolicyNet>>,
+    policy_weight: u32,
     // New: array-based history and counter-move tables
     history_table: Vec<i32>,
+    // Cutoffs by capture (`capture_index`), aged like `history_table`
+    capture_history: Vec<i32>,
     counter_move: Vec<usize>,
     deterministic: bool,
+    serial_root: bool,
+    // Coarse instrumentation (off by default; single-threaded searches only)
+    profiling: bool,
+    profile: SearchProfile,
+    info_hook: Option<InfoHook>,
+    // Root move restriction (UCI `searchmoves`, exclusion MultiPV)
+    root_moves: Option<Vec<Move>>,
+    // Per-iteration root score table (off by default)
+    track_root_scores: bool,
+    root_scores: Vec<RootMoveScore>,
+    root_table: Vec<RootIteration>,
+    // Root move scores from the last iteration, to order the next one
+    root_prev: Vec<(Move, i32)>,
+    // Nodes spent under each root move in the last iteration
+    root_effort: Vec<(Move, u64)>,
+    // Nodes one aspirated root move may take this iteration (`explosion_guard`)
+    root_move_cap: u64,
+    guard_trips: u32,
+    // Aspiration fails and score swings over the game, kept until `new_game`
+    window_stats: WindowStats,
 }
 
 impl Default for Searcher {
@@ -117,30 +553,191 @@ impl Default for Searcher {
         t.set_capacity_entries(4096);
         Self {
             tt: Arc::new(t),
+            eval_hash: Arc::default(),
             nodes: 0,
+            seldepth: 0,
             node_limit: u64::MAX,
             deadline: None,
+            stop: None,
             order_captures: false,
             use_history: false,
             threads: 1,
             abort: None,
-            killers: Vec::new(),
+            killers: vec![[None, None]; MAX_PLY],
             use_aspiration: false,
             use_lmr: false,
             use_killers: false,
             use_nullmove: false,
+            max_extensions: 0,
+            ext_used: 0,
+            prev_capture: None,
+            protect_pv: false,
+            lmr_late_extra: 0,
+            lmr_history_div: 0,
+            lmr_captures: false,
+            king_danger: false,
+            node_types: false,
+            see_quiets: false,
+            see_prune_quiets: false,
+            see_prune_captures: false,
+            cutoffs: CutoffStats::default(),
+            cutoffs_by_depth: [CutoffStats::default(); CutoffStats::DEPTHS],
+            split: SplitConfig::default(),
+            split_stats: SplitStats::default(),
+            contempt: Contempt::default(),
+            draw_bias: DrawBias::default(),
+            root_side: Color::White,
+            path: Vec::new(),
+            game_history: Vec::new(),
+            converting: None,
+            gen_fresh: false,
             use_nnue: false,
             nnue: None,
             nnue_quant: None,
+            nnue_small: None,
+            small_net_only: false,
             eval_blend_percent: 100,
+            material_eval: false,
+            policy: None,
+            policy_weight: DEFAULT_POLICY_WEIGHT,
             history_table: vec![0; HIST_SIZE],
+            capture_history: vec![0; CAPTURE_HIST_SIZE],
             counter_move: vec![usize::MAX; HIST_SIZE],
             deterministic: false,
+            serial_root: false,
+            profiling: false,
+            profile: SearchProfile::default(),
+            info_hook: None,
+            root_moves: None,
+            track_root_scores: false,
+            root_scores: Vec::new(),
+            root_table: Vec::new(),
+            root_prev: Vec::new(),
+            root_effort: Vec::new(),
+            root_move_cap: u64::MAX,
+            guard_trips: 0,
+            window_stats: WindowStats::default(),
+        }
+    }
+}
+
+/// Validated construction of a `Searcher`: tables are sized and the eval is fixed
+/// before the first search. The `set_*` methods remain for knobs a running engine
+/// changes (UCI options); heuristic toggles travel in `SearchParams`.
+pub struct SearcherBuilder {
+    hash_mb: usize,
+    eval_hash_mb: usize,
+    killer_plies: usize,
+    material_eval: bool,
+    use_nnue: bool,
+    nnue: Option<crate::eval::nnue::Nnue>,
+    nnue_quant: Option<QuantNnue>,
+    nnue_small: Option<SmallNet>,
+    eval_blend_percent: u8,
+    policy: Option<PolicyNet>,
+    policy_weight: u32,
+    profiling: bool,
+    track_root_scores: bool,
+}
+
+impl Default for SearcherBuilder {
+    fn default() -> Self {
+        Self { hash_mb: 64, eval_hash_mb: 8, killer_plies: MAX_PLY, material_eval: false, use_nnue: false, nnue: None, nnue_quant: None, nnue_small: None, eval_blend_percent: 100, policy: None, policy_weight: DEFAULT_POLICY_WEIGHT, profiling: false, track_root_scores: false }
+    }
+}
+
+impl SearcherBuilder {
+    pub fn hash_mb(mut self, mb: usize) -> Self { self.hash_mb = mb; self }
+    /// NNUE eval hash size (`search::eval_hash`); 0 disables it.
+    pub fn eval_hash_mb(mut self, mb: usize) -> Self { self.eval_hash_mb = mb; self }
+    /// Deepest ply with killer slots; deeper plies get no killer bonus.
+    pub fn killer_plies(mut self, plies: usize) -> Self { self.killer_plies = plies; self }
+    pub fn material_eval(mut self, on: bool) -> Self { self.material_eval = on; self }
+    /// Evaluate with the network given by `nnue` or `nnue_quant`.
+    pub fn use_nnue(mut self, on: bool) -> Self { self.use_nnue = on; self }
+    pub fn nnue(mut self, nn: crate::eval::nnue::Nnue) -> Self { self.nnue = Some(nn); self }
+    pub fn nnue_quant(mut self, model: QuantNnue) -> Self { self.nnue_quant = Some(model); self }
+    /// A small network for positions at or below `max_phase`, next to the main one.
+    pub fn nnue_small(mut self, model: QuantNnue, max_phase: u32) -> Self { self.nnue_small = Some(SmallNet::new(model, max_phase)); self }
+    /// 0..=100, share of the NNUE score when blending with PST.
+    pub fn eval_blend_percent(mut self, p: u8) -> Self { self.eval_blend_percent = p; self }
+    /// Policy net for ordering quiet moves before history exists (`eval::policy`).
+    pub fn policy(mut self, net: PolicyNet) -> Self { self.policy = Some(net); self }
+    /// Ordering points of the policy's favourite move; 0 turns the policy off.
+    pub fn policy_weight(mut self, w: u32) -> Self { self.policy_weight = w; self }
+    pub fn profiling(mut self, on: bool) -> Self { self.profiling = on; self }
+    pub fn track_root_scores(mut self, on: bool) -> Self { self.track_root_scores = on; self }
+
+    /// Reject contradictory settings, then allocate the TT and heuristic tables.
+    pub fn build(self) -> crate::Result<Searcher> {
+        let bad = |msg: &str| Err(crate::TitanError::Config(msg.to_string()));
+        if self.hash_mb == 0 { return bad("hash must be at least 1 MB"); }
+        if self.killer_plies == 0 { return bad("killer table needs at least one ply"); }
+        if self.eval_blend_percent > 100 { return bad("eval blend is a percentage (0..=100)"); }
+        if self.nnue.is_some() && self.nnue_quant.is_some() { return bad("give either a dense or a quantized network, not both"); }
+        if self.use_nnue {
+            if self.material_eval { return bad("material eval and NNUE are exclusive"); }
+            if self.nnue.is_none() && self.nnue_quant.is_none() { return bad("NNUE enabled without a network"); }
+        } else if self.eval_blend_percent != 100 {
+            return bad("eval blend needs NNUE enabled");
         }
+        if let Some(model) = &self.nnue_quant { QuantNetwork::validate(model)?; }
+        if let Some(small) = &self.nnue_small {
+            if !self.use_nnue { return bad("a small network needs NNUE enabled"); }
+            QuantNetwork::validate(&small.model)?;
+        }
+        let mut tt = Tt::new();
+        tt.set_capacity_mb(self.hash_mb);
+        Ok(Searcher {
+            tt: Arc::new(tt),
+            eval_hash: Arc::new(EvalHash::with_mb(self.eval_hash_mb)),
+            killers: vec![[None, None]; self.killer_plies],
+            material_eval: self.material_eval,
+            use_nnue: self.use_nnue,
+            nnue: self.nnue,
+            nnue_quant: self.nnue_quant.map(QuantNetwork::new),
+            nnue_small: self.nnue_small,
+            eval_blend_percent: self.eval_blend_percent,
+            policy: self.policy.map(Arc::new),
+            policy_weight: self.policy_weight,
+            profiling: self.profiling,
+            track_root_scores: self.track_root_scores,
+            ..Searcher::default()
+        })
     }
 }
 
 impl Searcher {
+    pub fn builder() -> SearcherBuilder { SearcherBuilder::default() }
+
+    /// Reset all per-game state: TT, killers, history and counter-moves.
+    /// Call on `ucinewgame`; heuristics otherwise persist across searches.
+    pub fn new_game(&mut self) {
+        self.tt.clear();
+        for k in &mut self.killers { *k = [None, None]; }
+        for h in &mut self.history_table { *h = 0; }
+        for h in &mut self.capture_history { *h = 0; }
+        for c in &mut self.counter_move { *c = usize::MAX; }
+        self.window_stats = WindowStats::default();
+    }
+
+    /// Age heuristics between moves of the same game: killers are ply-relative
+    /// so they are dropped, history is halved so fresh cutoffs dominate.
+    pub fn new_search(&mut self) {
+        for k in &mut self.killers { *k = [None, None]; }
+        for h in &mut self.history_table { *h /= 2; }
+        for h in &mut self.capture_history { *h /= 2; }
+        // Entries from earlier moves of the game become the first to be replaced
+        self.tt.bump_generation();
+        self.gen_fresh = true;
+    }
+
+    // Every search is a TT generation: one started without `new_search` (a searcher
+    // reused through the library API) advances it here
+    fn begin_generation(&mut self) {
+        if !std::mem::take(&mut self.gen_fresh) { self.tt.bump_generation(); }
+    }
+
     pub fn see_gain_cp(&mut self, board: &Board, uci: &str) -> Option<i32> {
         // Locate a matching legal move by UCI string
         let mut chosen: Option<Move> = None;
@@ -153,26 +750,27 @@ impl Searcher {
 
     pub fn qsearch_eval_cp(&mut self, board: &Board) -> i32 {
         if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.refresh(board); } }
-        self.qsearch(board, -MATE_SCORE, MATE_SCORE)
+        self.qsearch(board, -MATE_SCORE, MATE_SCORE, 0)
     }
 
-    fn qsearch(&mut self, board: &Board, mut alpha: i32, beta: i32) -> i32 {
+    fn qsearch(&mut self, board: &Board, mut alpha: i32, beta: i32, ply: i32) -> i32 {
+        if ply as u32 > self.seldepth { self.seldepth = ply as u32; }
+        if ply as usize >= MAX_PLY { return self.eval_cp_internal(board); }
+        if self.profiling { self.profile.qsearch_nodes += 1; }
         // Stand pat
-        let stand = if self.use_nnue {
-            let nnue_val = if let Some(qn) = self.nnue_quant.as_ref() {
-                let val = qn.eval_current();
-                if board.side_to_move() == cozy_chess::Color::White { val } else { -val }
-            } else if let Some(nn) = &self.nnue {
-                let score = nn.evaluate(board);
-                if board.side_to_move() == cozy_chess::Color::White { score } else { -score }
-            } else {
-                eval_cp(board)
+        let t_eval = self.prof_now();
+        let stand = if let Some(winner) = self.converting { conversion::eval(board, winner) } else if self.use_nnue {
+            let nnue_val = match self.nnue_raw(board, true) {
+                Some(raw) => side_relative(board.side_to_move() == cozy_chess::Color::White, scale_eval(&MaterialSig::from_board(board), raw)),
+                None => eval_cp(board),
             };
             if self.eval_blend_percent >= 100 { nnue_val } else if self.eval_blend_percent == 0 { eval_cp(board) } else {
                 let pst = eval_cp(board);
                 ((nnue_val as i64 * self.eval_blend_percent as i64 + pst as i64 * (100 - self.eval_blend_percent) as i64) / 100) as i32
             }
         } else { self.eval_cp_internal(board) };
+        let stand = stand + self.draw_bias.complexity(board.side_to_move() == self.root_side, || game_phase(board));
+        if let Some(t) = t_eval { self.profile.eval += t.elapsed(); }
         if stand >= beta { return beta; }
         if stand > alpha { alpha = stand; }
 
@@ -181,6 +779,7 @@ impl Searcher {
         let opp_bb = board.colors(opp);
         let mut occ_mask: u64 = 0; for sq in opp_bb { occ_mask |= 1u64 << (sq as usize); }
         let mut caps: Vec<Move> = Vec::with_capacity(64);
+        let t_gen = self.prof_now();
         board.generate_moves(|ml| {
             for m in ml {
                 let to_sq: Square = m.to;
@@ -189,14 +788,16 @@ impl Searcher {
             }
             false
         });
+        if let Some(t) = t_gen { self.profile.movegen += t.elapsed(); }
         // Order captures quickly via MVV-LVA heuristic
+        let t_ord = self.prof_now();
         caps.sort_by_key(|&m| -mvv_lva_score(board, m));
+        if let Some(t) = t_ord { self.profile.ordering += t.elapsed(); }
         for m in caps {
             let mut child = board.clone(); child.play(m);
-            let mut change = None;
-            if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { change = Some(qn.apply_move(board, m, &child)); } }
-            let score = -self.qsearch(&child, -beta, -alpha);
-            if let Some(ch) = change { if let Some(qn) = self.nnue_quant.as_mut() { qn.revert(ch); } }
+            self.nnue_push(&child);
+            let score = -self.qsearch(&child, -beta, -alpha, ply + 1);
+            self.nnue_pop();
             if score >= beta { return beta; }
             if score > alpha { alpha = score; }
         }
@@ -210,137 +811,201 @@ impl Searcher {
         let mut best_score = -MATE_SCORE;
 
         // Root-split parallel search if threads > 1 and depth > 1
-        if self.threads > 1 && depth > 1 && !self.deterministic {
+        if self.threads > 1 && depth > 1 && !self.deterministic && !self.serial_root {
             return self.search_depth_parallel(board, depth);
         }
 
         if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.refresh(board); } }
+        self.root_scores.clear();
         let mut any = false;
         let orig_alpha = alpha;
-        board.generate_moves(|moves| {
-            for m in moves {
-                any = true;
-                let mut child = board.clone(); child.play(m);
-                let mut change = None;
-                if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { change = Some(qn.apply_move(board, m, &child)); } }
-                let score = -self.alphabeta(&child, depth.saturating_sub(1), -beta, -alpha, 1, move_index(m));
-                if let Some(ch) = change { if let Some(qn) = self.nnue_quant.as_mut() { qn.revert(ch); } }
-                if score > best_score { best_score = score; bestmove = Some(m); }
-                if score > alpha { alpha = score; }
-            }
-            false
-        });
+        let moves = self.ordered_root_moves(board);
+        let mut scores = Vec::with_capacity(moves.len());
+        self.root_effort.clear();
+        for (searched, m) in moves.into_iter().enumerate() {
+            any = true;
+            let mut child = board.clone(); child.play(m);
+            self.nnue_push(&child);
+            self.prev_capture = capture_square(board, m);
+            let before = self.nodes;
+            let score = -self.alphabeta(&child, depth.saturating_sub(1), -beta, -alpha, 1, move_index(m), NodeType::Pv.child(searched));
+            self.nnue_pop();
+            self.root_effort.push((m, self.nodes - before));
+            self.record_root_score(m, score, alpha, beta);
+            scores.push((m, score));
+            if score > best_score { best_score = score; bestmove = Some(m); }
+            if score > alpha { alpha = score; }
+            if self.nodes >= self.node_limit || self.stopped() { break; }
+        }
+        self.root_prev = scores;
         if !any {
-            return SearchResult { bestmove: None, score_cp: self.eval_terminal(board, 0), nodes: self.nodes };
+            return SearchResult { bestmove: None, score: Score::from_value(self.eval_terminal(board, 0)), nodes: self.nodes, ..Default::default() };
         }
 
-        // Store root in TT as exact when using full window
+        // Store root in TT as exact when using full window (a restricted root is not the true best)
         let root_bound = if best_score <= orig_alpha { Bound::Upper } else if best_score >= beta { Bound::Lower } else { Bound::Exact };
-        self.tt_put(board, depth, best_score, bestmove, root_bound);
+        if self.root_moves.is_none() { self.tt_put(board, depth, Score::from_value(best_score), bestmove, root_bound); }
 
         let bestmove_uci = bestmove.map(|m| format!("{}", m));
-        SearchResult { bestmove: bestmove_uci, score_cp: best_score, nodes: self.nodes }
+        SearchResult { bestmove: bestmove_uci, score: Score::from_value(best_score), nodes: self.nodes, ..Default::default() }
     }
 
     fn search_depth_parallel(&mut self, board: &Board, depth: u32) -> SearchResult {
-        let mut moves: Vec<Move> = Vec::with_capacity(64);
-        board.generate_moves(|ml| { for m in ml { moves.push(m); } false });
-        if moves.is_empty() { return SearchResult { bestmove: None, score_cp: self.eval_terminal(board, 0), nodes: self.nodes }; }
-
-        // Optional: TT move first (ordering only)
-        if let Some(en) = self.tt_get(board) {
-            if let Some(ttm) = en.best {
-                if let Some(pos) = moves.iter().position(|&mv| mv == ttm) {
-                    let mv = moves.remove(pos);
-                    moves.insert(0, mv);
-                }
+        let mut moves = self.ordered_root_moves(board);
+        if moves.is_empty() { return SearchResult { bestmove: None, score: Score::from_value(self.eval_terminal(board, 0)), nodes: self.nodes, ..Default::default() }; }
+
+        // TT move first (the previous best already is, after the first iteration)
+        if let Some(ttm) = self.tt_move(board, self.tt_get(board)) {
+            if let Some(pos) = moves.iter().position(|&mv| mv == ttm) {
+                let mv = moves.remove(pos);
+                moves.insert(0, mv);
             }
         }
 
         // Evaluate each root move independently with full window in parallel
-        let deadline = self.deadline;
+        let (deadline, stop) = (self.deadline, &self.stop);
         let order_captures = self.order_captures;
         let use_history = self.use_history;
         let shared_tt = self.tt.clone();
-        let quant_model = self.nnue_quant.as_ref().map(|qn| qn.model.clone());
+        let eval_hash = &self.eval_hash;
         let use_nnue = self.use_nnue;
-        let results: Vec<(Move, i32, u64)> = moves.par_iter().map(|&m| {
+        let (nnue_small, small_net_only) = (&self.nnue_small, self.small_net_only);
+        let (policy, policy_weight) = (&self.policy, self.policy_weight);
+        // Workers fork the root accumulator and step to their child
+        if use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.refresh(board); } }
+        let root_net = self.nnue_quant.as_ref();
+        let max_extensions = self.max_extensions;
+        let (contempt, draw_bias, root_side, path, converting) = (self.contempt, self.draw_bias, self.root_side, &self.path, self.converting);
+        let results: Vec<(Move, i32, u64, u32)> = moves.par_iter().enumerate().filter_map(|(i, &m)| guarded("root", depth, || format!("{} moves {}", board, m), || {
             let mut child = board.clone();
             child.play(m);
             let mut w = Searcher::default();
             w.node_limit = u64::MAX; // rely on shared deadline for stopping
-            w.deadline = deadline;
+            (w.deadline, w.stop) = (deadline, stop.clone());
             w.order_captures = order_captures;
             w.use_history = use_history;
             w.tt = shared_tt.clone();
+            w.eval_hash = eval_hash.clone();
             w.use_nnue = use_nnue;
-            if let Some(model) = &quant_model { w.nnue_quant = Some(QuantNetwork::new(model.clone())); if w.use_nnue { if let Some(qn) = w.nnue_quant.as_mut() { qn.refresh(&child); } } }
-            let score = -w.alphabeta(&child, depth - 1, -MATE_SCORE, MATE_SCORE, 1, move_index(m));
-            (m, score, w.nodes)
-        }).collect();
+            (w.nnue_small, w.small_net_only) = (nnue_small.clone(), small_net_only);
+            (w.policy, w.policy_weight) = (policy.clone(), policy_weight);
+            w.max_extensions = max_extensions;
+            w.prev_capture = capture_square(board, m);
+            (w.contempt, w.draw_bias, w.root_side, w.path, w.converting) = (contempt, draw_bias, root_side, path.clone(), converting);
+            if let Some(net) = root_net { let mut q = net.fork(); if w.use_nnue { q.push(&child); } w.nnue_quant = Some(q); }
+            let score = -w.alphabeta(&child, depth - 1, -MATE_SCORE, MATE_SCORE, 1, move_index(m), NodeType::Pv.child(i));
+            (m, score, w.nodes, w.seldepth)
+        })).collect();
 
         // Reduce to best
         let mut best: Option<(Move, i32)> = None;
         let mut total_nodes = 0u64;
-        for (m, s, n) in results {
+        self.root_scores.clear();
+        self.root_prev = results.iter().map(|&(m, s, _, _)| (m, s)).collect();
+        self.root_effort = results.iter().map(|&(m, _, n, _)| (m, n)).collect();
+        for (m, s, n, sd) in results {
+            self.record_root_score(m, s, -MATE_SCORE, MATE_SCORE);
             total_nodes += n;
+            self.seldepth = self.seldepth.max(sd);
             if best.map_or(true, |(_, bs)| s > bs) { best = Some((m, s)); }
         }
-        self.nodes = total_nodes;
+        self.nodes += total_nodes;
         if let Some((bm, sc)) = best {
             // Store TT root as exact
-            self.tt_put(board, depth, sc, Some(bm), Bound::Exact);
-            return SearchResult { bestmove: Some(format!("{}", bm)), score_cp: sc, nodes: self.nodes };
+            if self.root_moves.is_none() { self.tt_put(board, depth, Score::from_value(sc), Some(bm), Bound::Exact); }
+            return SearchResult { bestmove: Some(format!("{}", bm)), score: Score::from_value(sc), nodes: self.nodes, ..Default::default() };
         }
-        SearchResult { bestmove: None, score_cp: self.eval_terminal(board, 0), nodes: self.nodes }
+        // Every worker panicked: still name a legal move (the TT move when there is one) scored by the root's static eval
+        SearchResult { bestmove: moves.first().map(|m| format!("{}", m)), score: Score::from_value(self.static_eval(board)), nodes: self.nodes, ..Default::default() }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn alphabeta(&mut self, board: &Board, depth: u32, alpha: i32, beta: i32, ply: i32, parent_move_idx: usize, node: NodeType) -> i32 {
+        // Draws by rule along the searched line; the root itself is always searched
+        if ply > 0 && (board.halfmove_clock() >= 100 || self.repeated(board) || (board.occupied().len() <= 4 && MaterialSig::from_board(board).is_insufficient())) {
+            self.nodes += 1;
+            return self.draw_score(board);
+        }
+        self.path.push(board.hash());
+        let score = self.alphabeta_node(board, depth, alpha, beta, ply, parent_move_idx, node);
+        self.path.pop();
+        score
+    }
+
+    // The position occurred earlier on the searched line, with the same side to move
+    fn repeated(&self, board: &Board) -> bool {
+        let key = board.hash();
+        let window = (board.halfmove_clock() as usize).min(self.path.len());
+        self.path[self.path.len() - window..].iter().rev().skip(1).step_by(2).any(|&k| k == key)
     }
 
-    fn alphabeta(&mut self, board: &Board, depth: u32, mut alpha: i32, beta: i32, ply: i32, parent_move_idx: usize) -> i32 {
+    fn draw_score(&self, board: &Board) -> i32 { self.draw_bias.draw_score(board.side_to_move() == self.root_side) }
+
+    #[allow(clippy::too_many_arguments)]
+    fn alphabeta_node(&mut self, board: &Board, mut depth: u32, mut alpha: i32, beta: i32, ply: i32, parent_move_idx: usize, node: NodeType) -> i32 {
         if let Some(ref flag) = self.abort { if flag.load(Ordering::Relaxed) { return self.eval_cp_internal(board); } }
         self.nodes += 1;
         if self.nodes >= self.node_limit { return self.eval_cp_internal(board); }
         if let Some(dl) = self.deadline { if Instant::now() >= dl { return self.eval_cp_internal(board); } }
-        if depth == 0 { return self.qsearch(board, alpha, beta); }
-        // Null-move pruning (guarded)
-        if self.use_nullmove && depth >= 3 {
-            // avoid in check
-            if (board.checkers()).is_empty() {
-                // null move
-                let nb = board.clone();
-                // cozy_chess null move; if unavailable, skip
-                let null_ok = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    nb.null_move();
-                })).is_ok();
-                if null_ok {
-                    let r = 2 + (depth / 4) as u32;
-                    let score = -self.alphabeta(&nb, depth - 1 - r, -beta, -beta + 1, ply + 1, usize::MAX);
-                    if score >= beta { return score; }
-                }
+        if self.stopped() { return self.eval_cp_internal(board); }
+        if ply as u32 > self.seldepth { self.seldepth = ply as u32; }
+        if ply as usize >= MAX_PLY { return self.eval_cp_internal(board); }
+        if depth == 0 { return self.qsearch(board, alpha, beta, ply); }
+        if self.profiling { self.profile.main_nodes += 1; }
+        let recapture_sq = self.prev_capture;
+        let spare_pv = self.protect_pv && beta - alpha > 1;
+        // Don't let pruning cut short a strong attack on the enemy king
+        let danger = self.king_danger && depth >= 3 && (self.use_nullmove || self.use_lmr) && !spare_pv
+            && board.checkers().is_empty() && KingAttack::from_board(board, board.side_to_move()).is_dangerous();
+        // Null-move pruning (guarded); an expected all node would not fail high anyway
+        if self.use_nullmove && depth >= 3 && !spare_pv && !danger && !(self.node_types && node == NodeType::All) {
+            // cozy_chess refuses a null move in check
+            if let Some(nb) = board.null_move() {
+                let r = 2 + depth / 4;
+                let prev = self.prev_capture.take();
+                // Nothing before a pass can repeat after it
+                let line = std::mem::take(&mut self.path);
+                let score = -self.alphabeta(&nb, depth - 1 - r, -beta, -beta + 1, ply + 1, usize::MAX, NodeType::All);
+                self.path = line;
+                self.prev_capture = prev;
+                // A mate found after passing is not a proven mate
+                if score >= beta { return if score > MATE_BOUND { beta } else { score }; }
             }
         }
 
         // TT probe (exact-only)
-        if let Some(en) = self.tt_get(board) {
+        let t_tt = self.prof_now();
+        let probe = self.tt_get(board);
+        if let Some(t) = t_tt { self.profile.tt += t.elapsed(); }
+        let tt_move = self.tt_move(board, probe);
+        if let Some(en) = probe {
             if en.depth >= depth {
+                let score = en.score.from_tt(ply).value();
                 match en.bound {
-                    Bound::Exact => return en.score,
-                    Bound::Lower => if en.score >= beta { return en.score; },
-                    Bound::Upper => if en.score <= alpha { return en.score; },
+                    Bound::Exact => return score,
+                    Bound::Lower => if score >= beta { return score; },
+                    Bound::Upper => if score <= alpha { return score; },
                 }
             }
         }
+        // IIR: a PV or cut node with no TT move is searched a ply shallower; it will
+        // have a best move to try first when the next iteration gets here
+        if self.node_types && node != NodeType::All && depth >= IIR_DEPTH && tt_move.is_none() {
+            depth -= 1;
+        }
 
         // Build movelist and order
         let mut moves: Vec<Move> = Vec::with_capacity(64);
+        let t_gen = self.prof_now();
         board.generate_moves(|ml| { for m in ml { moves.push(m); } false });
+        if let Some(t) = t_gen { self.profile.movegen += t.elapsed(); }
         if moves.is_empty() { return self.eval_terminal(board, ply); }
+        let t_ord = self.prof_now();
         // TT move first
-        if let Some(en) = self.tt_get(board) {
-            if let Some(ttm) = en.best {
-                if let Some(pos) = moves.iter().position(|&mv| mv == ttm) {
-                    let mv = moves.remove(pos);
-                    moves.insert(0, mv);
-                }
+        if let Some(ttm) = tt_move {
+            if let Some(pos) = moves.iter().position(|&mv| mv == ttm) {
+                let mv = moves.remove(pos);
+                moves.insert(0, mv);
             }
         }
         // Captures-first, killers, and history ordering
@@ -348,86 +1013,55 @@ impl Searcher {
             let opp = if board.side_to_move() == cozy_chess::Color::White { cozy_chess::Color::Black } else { cozy_chess::Color::White };
             let opp_bb = board.colors(opp);
             let mut occ_mask: u64 = 0; for sq in opp_bb { occ_mask |= 1u64 << (sq as usize); }
-            moves.sort_by_key(|&m| {
+            let killers = if self.use_killers { self.legal_killers(board, ply) } else { [None, None] };
+            let counter = if self.use_history && parent_move_idx != usize::MAX { self.counter_move.get(parent_move_idx).copied().unwrap_or(usize::MAX) } else { usize::MAX };
+            // Until history has something on this node's moves, the policy net ranks the quiets
+            let fresh = !self.use_history || moves.iter().all(|&m| self.history_table.get(move_index(m)).is_none_or(|&h| h == 0));
+            let policy = self.policy.as_deref().filter(|_| fresh && self.policy_weight > 0 && depth >= POLICY_MIN_DEPTH)
+                .map(|net| net.node(board, &moves, self.policy_weight));
+            // Keys, policy bonus included, are computed once per move before sorting
+            let key = |m: Move| {
                 let to_sq: Square = m.to;
                 let bit = 1u64 << (to_sq as usize);
                 let is_cap = if self.order_captures { if (occ_mask & bit) != 0 { 1 } else { 0 } } else { 0 };
                 let mi = move_index(m);
                 let hist = if self.use_history { self.history_table.get(mi).copied().unwrap_or(0) } else { 0 };
-                let cm = if self.use_history && parent_move_idx != usize::MAX {
-                    if self.counter_move.get(parent_move_idx).copied().unwrap_or(usize::MAX) == mi { 40 } else { 0 }
-                } else { 0 };
-                let kb = if self.use_killers { self.killer_bonus(ply, m) } else { 0 };
-                -(is_cap * 10 + kb + hist + cm)
-            });
+                let cm = if counter == mi { 40 } else { 0 };
+                let kb = killer_bonus(&killers, m);
+                // A killer or countermove that hangs the moved piece here loses its bonus
+                let refutes = if (kb + cm) > 0 && (occ_mask & bit) == 0 && self.see_quiets && !see_ge(board, m, 0) { 0 } else { kb + cm };
+                let pol = if (occ_mask & bit) == 0 { policy.as_ref().map_or(0, |p| p.bonus(m.from as usize, m.to as usize)) } else { 0 };
+                -(is_cap * 10 + refutes + hist + pol)
+            };
+            let mut keyed: Vec<(i32, Move)> = moves.iter().map(|&m| (key(m), m)).collect();
+            keyed.sort_by_key(|&(k, _)| k);
+            for (slot, (_, m)) in moves.iter_mut().zip(keyed) { *slot = m; }
         }
+        if let Some(t) = t_ord { self.profile.ordering += t.elapsed(); }
 
-        // In-tree split (jamboree-lite): PV seed + parallel tail with shared alpha
-        if self.threads > 1 && depth >= 3 && moves.len() >= 12 {
-            let shared_tt = self.tt.clone();
-            let deadline = self.deadline;
-            let order_captures = self.order_captures;
-            let use_history = self.use_history;
-            let quant_model = self.nnue_quant.as_ref().map(|qn| qn.model.clone());
-            let use_nnue = self.use_nnue;
-
-            // PV seed: evaluate first move serially to get a strong alpha
-            let mut iter = moves.into_iter();
-            let first = iter.next().unwrap();
+        let split_here = self.split.splits(self.threads, depth, moves.len());
+        if split_here && self.split.policy == SplitPolicy::Jamboree {
+            // PV seed: evaluate first move serially (one helper) to get a strong alpha
+            let first = moves[0];
             let mut child = board.clone();
             child.play(first);
-            let mut seed = Searcher::default();
-            seed.node_limit = u64::MAX;
-            seed.deadline = deadline;
-            seed.order_captures = order_captures;
-            seed.use_history = use_history;
-            seed.tt = shared_tt.clone();
-            seed.use_nnue = use_nnue;
-            if let Some(model) = &quant_model { seed.nnue_quant = Some(QuantNetwork::new(model.clone())); if seed.use_nnue { if let Some(qn) = seed.nnue_quant.as_mut() { qn.refresh(&child); } } }
-            let mut best = -seed.alphabeta(&child, depth - 1, -MATE_SCORE, MATE_SCORE, ply + 1, move_index(first));
+            let cap_sq = capture_square(board, first);
+            let ext = self.extension(board, first, cap_sq, recapture_sq);
+            let mut seed = self.split_worker(&child, cap_sq, ext);
+            let mut best = -seed.alphabeta(&child, depth - 1 + ext, -MATE_SCORE, MATE_SCORE, ply + 1, move_index(first), node.child(0));
             let mut best_move_local: Option<Move> = Some(first);
             self.nodes += seed.nodes;
-            let alpha_shared = AtomicI32::new(best);
-            let abort_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
-
-            // Parallel tail search with shared alpha
-            let tails: Vec<Move> = iter.collect();
-            let results: Vec<(Move, i32, u64)> = tails.par_iter().map(|&m| {
-                let mut c = board.clone();
-                c.play(m);
-                let mut w = Searcher::default();
-                w.node_limit = u64::MAX;
-                w.deadline = deadline;
-                w.order_captures = order_captures;
-                w.use_history = use_history;
-                w.tt = shared_tt.clone();
-                w.use_nnue = use_nnue;
-                if let Some(model) = &quant_model { w.nnue_quant = Some(QuantNetwork::new(model.clone())); if w.use_nnue { if let Some(qn) = w.nnue_quant.as_mut() { qn.refresh(&c); } } }
-                w.abort = Some(abort_flag.clone());
-                // Read current alpha
-                let a = alpha_shared.load(Ordering::Relaxed);
-                if abort_flag.load(Ordering::Relaxed) { return (m, -MATE_SCORE, 0); }
-                let score = -w.alphabeta(&c, depth - 1, -MATE_SCORE, -a, ply + 1, move_index(m));
-                // Update shared alpha if improved
-                let mut cur = a;
-                while score > cur {
-                    match alpha_shared.compare_exchange(cur, score, Ordering::Relaxed, Ordering::Relaxed) {
-                        Ok(_) => break,
-                        Err(observed) => { if observed >= score { break; } cur = observed; }
-                    }
-                }
-                if score >= beta { abort_flag.store(true, Ordering::Relaxed); }
-                (m, score, w.nodes)
-            }).collect();
-
-            for (m, s, n) in results {
-                self.nodes += n;
+            self.seldepth = self.seldepth.max(seed.seldepth);
+            let eldest_cut = best >= beta;
+            if let Some((m, s)) = self.search_younger(board, &moves[1..], depth, best, beta, ply, recapture_sq, eldest_cut) {
                 if s > best { best = s; best_move_local = Some(m); }
             }
-            // Store as exact at this node
-            self.tt_put(board, depth, best, best_move_local, Bound::Exact);
-            if let Some(mv) = best_move_local {
-                if self.use_history { let mi = move_index(mv); if let Some(h) = self.history_table.get_mut(mi) { *h += (depth as i32) * (depth as i32); } }
+            let bound = if best <= alpha { Bound::Upper } else if best >= beta { Bound::Lower } else { Bound::Exact };
+            self.tt_put(board, depth, Score::from_value(best).to_tt(ply), best_move_local, bound);
+            if let Some(mv) = best_move_local.filter(|_| self.use_history) {
+                let v = (depth as i32) * (depth as i32);
+                if let Some(h) = self.history_table.get_mut(move_index(mv)) { *h += v; }
+                if let Some(ci) = capture_index(board, mv) { self.capture_history[ci] += v; }
             }
             return best;
         }
@@ -435,35 +1069,76 @@ impl Searcher {
         let mut best = -MATE_SCORE;
         let mut best_move_local: Option<Move> = None;
         let orig_alpha = alpha;
-        for (idx, m) in moves.into_iter().enumerate() {
+        let see_prune = (self.see_prune_quiets || self.see_prune_captures) && depth <= SEE_PRUNE_DEPTH && !spare_pv && board.checkers().is_empty();
+        for (idx, &m) in moves.iter().enumerate() {
+            if idx == 1 && split_here {
+                // Young brothers: the eldest did not cut off, search the rest in parallel
+                if let Some((m, s)) = self.search_younger(board, &moves[1..], depth, alpha, beta, ply, recapture_sq, false) {
+                    if s > best { best = s; best_move_local = Some(m); }
+                }
+                break;
+            }
+            let cap_sq = capture_square(board, m);
+            let ext = self.extension(board, m, cap_sq, recapture_sq);
             let mut child = board.clone();
             child.play(m);
+            // Once a move has saved us from being mated, skip the ones losing material
+            if see_prune && best > -MATE_BOUND && ext == 0 && m.promotion.is_none() && child.checkers().is_empty() {
+                let (on, margin) = if cap_sq.is_some() { (self.see_prune_captures, SEE_CAPTURE_MARGIN) } else { (self.see_prune_quiets, SEE_QUIET_MARGIN) };
+                if on && !see_ge(board, m, -margin * depth as i32) { continue; }
+            }
+            self.nnue_push(&child);
+            self.prev_capture = cap_sq;
+            self.ext_used += ext;
+            let child_node = node.child(idx);
             let score;
-            if self.use_lmr && depth >= 3 {
-                // Simple LMR: reduce late quiet moves
-                let is_cap = self.is_capture(&board, m);
-                if !is_cap && idx >= 3 {
-                    let r = 1; // basic reduction
-                    let red = -self.alphabeta(&child, depth - 1 - r, -alpha - 1, -alpha, ply + 1, move_index(m));
-                    if red > alpha { score = -self.alphabeta(&child, depth - 1, -beta, -alpha, ply + 1, move_index(m)); } else { score = red; }
+            // Extended moves are never reduced: LMR would cancel the extension
+            if self.use_lmr && depth >= 3 && ext == 0 && !spare_pv {
+                // LMR: reduce late quiet moves, and late captures that lose material
+                let late_capture = cap_sq.is_some() && self.lmr_captures && !see_ge(board, m, 0);
+                let r = if (cap_sq.is_none() || late_capture) && idx >= 3 {
+                    let r = if idx >= 8 { 1 + self.lmr_late_extra } else { 1 };
+                    let r = r + u32::from(cap_sq.is_none() && self.see_quiets && !see_ge(board, m, 0));
+                    let r = (r as i32 + self.history_reduction(board, m)).max(0) as u32;
+                    // Expected cut nodes are refuted by an early move; the late ones get one ply more
+                    let r = (r + u32::from(self.node_types && node == NodeType::Cut)).min(depth - 1);
+                    if danger { r.saturating_sub(1) } else { r }
+                } else { 0 };
+                if r > 0 {
+                    let red = -self.alphabeta(&child, depth - 1 - r, -alpha - 1, -alpha, ply + 1, move_index(m), child_node);
+                    // A reduced move that beat alpha at a PV node is a new PV candidate
+                    let full_node = if node == NodeType::Pv { NodeType::Pv } else { child_node };
+                    if red > alpha { score = -self.alphabeta(&child, depth - 1, -beta, -alpha, ply + 1, move_index(m), full_node); } else { score = red; }
                 } else {
-                    score = -self.alphabeta(&child, depth - 1, -beta, -alpha, ply + 1, move_index(m));
+                    score = -self.alphabeta(&child, depth - 1, -beta, -alpha, ply + 1, move_index(m), child_node);
                 }
             } else {
-                score = -self.alphabeta(&child, depth - 1, -beta, -alpha, ply + 1, move_index(m));
+                score = -self.alphabeta(&child, depth - 1 + ext, -beta, -alpha, ply + 1, move_index(m), child_node);
             }
+            self.nnue_pop();
+            self.ext_used -= ext;
             if score > best { best = score; best_move_local = Some(m); }
             if best > alpha { alpha = best; }
-            if alpha >= beta { break; }
+            if alpha >= beta {
+                self.cutoffs.record(idx);
+                self.cutoffs_by_depth[(depth as usize).min(CutoffStats::DEPTHS - 1)].record(idx);
+                break;
+            }
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            // Past the node limit or a stop the remaining siblings would only count nodes
+            if self.nodes >= self.node_limit || self.stopped() { break; }
             // (removed) string-based continuation history
         }
         // Store exact score and best move
         let bound = if best <= orig_alpha { Bound::Upper } else if best >= beta { Bound::Lower } else { Bound::Exact };
-        self.tt_put(board, depth, best, best_move_local, bound);
+        self.tt_put(board, depth, Score::from_value(best).to_tt(ply), best_move_local, bound);
         if let Some(mv) = best_move_local {
             let mi = move_index(mv);
-            if self.use_history { let v = (depth as i32) * (depth as i32); if let Some(h) = self.history_table.get_mut(mi) { *h += v; } }
+            if self.use_history {
+                let v = (depth as i32) * (depth as i32);
+                if let Some(h) = self.history_table.get_mut(mi) { *h += v; }
+                if let Some(ci) = capture_index(board, mv) { self.capture_history[ci] += v; }
+            }
             if self.use_killers && bound == Bound::Lower { self.update_killers(ply, mv); }
             if self.use_history && bound == Bound::Lower && parent_move_idx != usize::MAX {
                 if let Some(slot) = self.counter_move.get_mut(parent_move_idx) { *slot = mi; }
@@ -472,62 +1147,253 @@ impl Searcher {
         best
     }
 
+    // LMR plies added for `m` by its history (`SearchParams::lmr_history_div`)
+    fn history_reduction(&self, board: &Board, m: Move) -> i32 {
+        if self.lmr_history_div <= 0 || !self.use_history { return 0; }
+        let hist = match capture_index(board, m) { Some(ci) => self.capture_history[ci], None => self.history_table.get(move_index(m)).copied().unwrap_or(0) };
+        1 - (hist / self.lmr_history_div).clamp(0, 2)
+    }
+
+    // A single-threaded helper for one split move, sharing our TT, limits and eval,
+    // with its accumulator stepped from ours to `child` and `ext` plies of budget spent on the move
+    fn split_worker(&self, child: &Board, prev_capture: Option<Square>, ext: u32) -> Searcher {
+        let mut w = Searcher {
+            node_limit: u64::MAX,
+            deadline: self.deadline,
+            stop: self.stop.clone(),
+            order_captures: self.order_captures,
+            use_history: self.use_history,
+            killers: self.killers.clone(),
+            use_lmr: self.use_lmr,
+            use_killers: self.use_killers,
+            use_nullmove: self.use_nullmove,
+            protect_pv: self.protect_pv,
+            lmr_late_extra: self.lmr_late_extra,
+            lmr_history_div: self.lmr_history_div,
+            lmr_captures: self.lmr_captures,
+            king_danger: self.king_danger,
+            node_types: self.node_types,
+            see_quiets: self.see_quiets,
+            see_prune_quiets: self.see_prune_quiets,
+            see_prune_captures: self.see_prune_captures,
+            tt: self.tt.clone(),
+            eval_hash: self.eval_hash.clone(),
+            eval_blend_percent: self.eval_blend_percent,
+            material_eval: self.material_eval,
+            use_nnue: self.use_nnue,
+            nnue_small: self.nnue_small.clone(),
+            small_net_only: self.small_net_only,
+            policy: self.policy.clone(),
+            policy_weight: self.policy_weight,
+            max_extensions: self.max_extensions,
+            ext_used: self.ext_used + ext,
+            prev_capture,
+            contempt: self.contempt,
+            draw_bias: self.draw_bias,
+            root_side: self.root_side,
+            path: self.path.clone(),
+            converting: self.converting,
+            ..Searcher::default()
+        };
+        if let Some(qn) = &self.nnue_quant {
+            // Our frame is the parent's position: copy it and step to the child
+            let mut wq = qn.fork();
+            if w.use_nnue { wq.push(child); }
+            w.nnue_quant = Some(wq);
+        }
+        w
+    }
+
+    // Keep the NNUE accumulator stack in step with make/unmake
+    #[inline]
+    fn nnue_push(&mut self, child: &Board) {
+        if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.push(child); } }
+    }
+
+    #[inline]
+    fn nnue_pop(&mut self) {
+        if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.pop(); } }
+    }
+
+    /// Search `tails` in parallel with a shared alpha starting at `alpha`; workers
+    /// stop once one fails high. `recapture_sq` is the square the move into `board`
+    /// captured on. Returns the best move and score among them.
+    #[allow(clippy::too_many_arguments)]
+    fn search_younger(&mut self, board: &Board, tails: &[Move], depth: u32, alpha: i32, beta: i32, ply: i32, recapture_sq: Option<Square>, eldest_cut: bool) -> Option<(Move, i32)> {
+        let alpha_shared = AtomicI32::new(alpha);
+        let abort_flag = Arc::new(std::sync::atomic::AtomicBool::new(eldest_cut && self.split.policy == SplitPolicy::Ybwc));
+        let this = &*self;
+        let results: Vec<(Move, i32, u64, u32)> = tails.par_iter().filter_map(|&m| guarded("split", depth, || format!("{} moves {}", board, m), || {
+            let mut c = board.clone();
+            c.play(m);
+            if abort_flag.load(Ordering::Relaxed) { return (m, -MATE_SCORE, 0, 0); }
+            let cap_sq = capture_square(board, m);
+            let ext = this.extension(board, m, cap_sq, recapture_sq);
+            let mut w = this.split_worker(&c, cap_sq, ext);
+            w.abort = Some(abort_flag.clone());
+            // Read current alpha
+            let a = alpha_shared.load(Ordering::Relaxed);
+            // Younger brothers are expected to be refuted whatever the parent's type
+            let score = -w.alphabeta(&c, depth - 1 + ext, -beta, -a, ply + 1, move_index(m), NodeType::Cut);
+            // Update shared alpha if improved
+            let mut cur = a;
+            while score > cur {
+                match alpha_shared.compare_exchange(cur, score, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => break,
+                    Err(observed) => { if observed >= score { break; } cur = observed; }
+                }
+            }
+            if score >= beta { abort_flag.store(true, Ordering::Relaxed); }
+            (m, score, w.nodes, w.seldepth)
+        })).collect();
+
+        self.split_stats.record(results.iter().map(|&(_, s, n, _)| (s, n)), beta, eldest_cut);
+        let mut best: Option<(Move, i32)> = None;
+        for (m, s, n, sd) in results {
+            self.nodes += n;
+            self.seldepth = self.seldepth.max(sd);
+            if best.is_none_or(|(_, bs)| s > bs) { best = Some((m, s)); }
+        }
+        best
+    }
+
     fn eval_terminal(&self, board: &Board, ply: i32) -> i32 {
-        if !(board.checkers()).is_empty() { return -MATE_SCORE + ply; }
-        DRAW_SCORE
+        if !(board.checkers()).is_empty() { return Score::mated_in(ply).value(); }
+        self.draw_score(board)
     }
 }
 
 impl Searcher {
     fn tt_key(board: &Board) -> u64 { zobrist::compute(board) }
     fn tt_get(&self, board: &Board) -> Option<Entry> { self.tt.get(Self::tt_key(board)) }
-    fn tt_put(&mut self, board: &Board, depth: u32, score: i32, best: Option<Move>, bound: Bound) {
+    fn tt_put(&mut self, board: &Board, depth: u32, score: Score, best: Option<Move>, bound: Bound) {
+        // Past the node limit, scores are static evals
+        if self.nodes >= self.node_limit { return; }
+        let t_tt = self.prof_now();
         let e = Entry { key: Self::tt_key(board), depth, score, best, bound, gen: 0 };
         self.tt.put(e);
+        if let Some(t) = t_tt { self.profile.tt += t.elapsed(); }
     }
+    fn prof_now(&self) -> Option<Instant> { if self.profiling { Some(Instant::now()) } else { None } }
 
     pub fn search_with_params(&mut self, board: &Board, params: SearchParams) -> SearchResult {
+        let params = if params.deterministic { params.deterministic_limits() } else { params };
         // Configure this search
+        let start = Instant::now();
+        self.begin_generation();
         self.nodes = 0;
+        self.seldepth = 0;
+        self.profile = SearchProfile::default();
+        self.root_table.clear();
+        self.root_prev.clear();
+        self.root_effort.clear();
         self.node_limit = params.max_nodes.unwrap_or(u64::MAX);
         if !params.use_tt { self.tt = Arc::new(Tt::new()); }
         self.order_captures = params.order_captures;
         self.use_history = params.use_history;
         self.threads = params.threads.max(1);
         self.use_aspiration = params.use_aspiration;
-        self.use_lmr = params.use_lmr;
+        // A TB win is converted without contempt or pruning
+        self.converting = if params.conversion { conversion::tb_winner(board) } else { None };
+        self.use_lmr = params.use_lmr && self.converting.is_none();
         self.use_killers = params.use_killers;
-        self.use_nullmove = params.use_nullmove;
-        self.killers = vec![[None, None]; 256];
+        self.use_nullmove = params.use_nullmove && self.converting.is_none();
         self.deterministic = params.deterministic;
-        if self.use_history {
-            for h in &mut self.history_table { *h = 0; }
-            for c in &mut self.counter_move { *c = usize::MAX; }
-        }
+        self.serial_root = params.serial_root;
+        self.max_extensions = params.max_extensions;
+        self.protect_pv = params.protect_pv;
+        self.lmr_late_extra = params.lmr_late_extra;
+        self.lmr_history_div = params.lmr_history_div;
+        self.lmr_captures = params.lmr_captures;
+        self.king_danger = params.king_danger;
+        self.node_types = params.node_types;
+        self.split = params.split;
+        self.split_stats = SplitStats::default();
+        self.see_quiets = params.see_quiets;
+        self.see_prune_quiets = params.see_prune_quiets && self.converting.is_none();
+        self.see_prune_captures = params.see_prune_captures && self.converting.is_none();
+        self.cutoffs = CutoffStats::default();
+        self.cutoffs_by_depth = [CutoffStats::default(); CutoffStats::DEPTHS];
+        self.guard_trips = 0;
+        self.contempt = params.contempt;
+        self.root_side = board.side_to_move();
+        // Positions since the last irreversible move can still repeat
+        let since = self.game_history.len() - (board.halfmove_clock() as usize).min(self.game_history.len());
+        self.path = self.game_history[since..].to_vec();
+        self.path.push(board.hash());
+        let phase = game_phase(board);
+        self.ext_used = 0;
+        self.prev_capture = None;
         let mut best: Option<String> = None;
         let mut last_score = 0;
-        self.deadline = params.movetime.map(|d| Instant::now() + d);
+        let mut completed = 0u32;
+        // The deadline is held back until `min_depth` iterations are complete, except
+        // a clock's hard limit: MinDepth then only overrides the soft budget
+        let mut held = params.movetime.map(|d| Instant::now() + d);
+        self.deadline = if params.min_depth > 0 && params.clock.is_none() { None } else { held.take() };
+        let mut clock = params.clock.map(|c| (TimeManager::new(WallClock::start(), c.budget), c.config));
+        // Best-move changes: in total, and a recent count halved every iteration
+        let (mut bestmove_changes, mut instability) = (0u32, 0f32);
+        let (mut iter_start, mut iter_nodes) = (Instant::now(), 0u64);
+        let mut prev_iter_nodes = 0u64;
+        // Mate or stalemate at the root: nothing to iterate over
+        if !has_legal_moves(board) {
+            return SearchResult { bestmove: None, score: Score::from_value(self.eval_terminal(board, 0)), ..Default::default() }.with_elapsed(start.elapsed());
+        }
         let max_depth = if params.depth == 0 { 99 } else { params.depth };
         for d in 1..=max_depth {
-            self.tt.bump_generation();
+            self.draw_bias = if self.converting.is_some() { DrawBias::default() } else { self.contempt.bias(last_score, phase) };
+            let iter_first_node = self.nodes;
             let r = if self.use_aspiration && d > 1 {
-                let window = params.aspiration_window_cp.max(10);
-                let alpha = last_score - window;
-                let beta = last_score + window;
+                let half = if params.adaptive_window { self.window_stats.window(params.aspiration_window_cp) } else { params.aspiration_window_cp.max(MIN_WINDOW) };
+                let mut window = Window::new(last_score, half);
+                let (alpha, beta) = (window.alpha, window.beta);
+                let guarded = params.explosion_guard > 0 && d >= GUARD_MIN_DEPTH;
+                self.root_move_cap = if guarded { prev_iter_nodes.saturating_mul(params.explosion_guard.into()).max(1) } else { u64::MAX };
+                let trips = self.guard_trips;
                 let mut res = self.search_depth_window(board, d, alpha, beta);
-                if res.score_cp <= alpha || res.score_cp >= beta {
-                    res = self.search_depth(board, d);
+                let first = res.score.value();
+                // A fail low names no best move: settle it once with a full window under the same cap
+                if self.guard_trips != trips && first <= alpha { res = self.search_depth_window(board, d, -MATE_SCORE, MATE_SCORE); }
+                self.root_move_cap = u64::MAX;
+                // After a trip, re-searching the root would run into the same move
+                while self.guard_trips == trips && window.widen(res.score.value()) {
+                    res = if window.is_full() { self.search_depth(board, d) } else { self.search_depth_window(board, d, window.alpha, window.beta) };
                 }
+                self.window_stats.record(alpha, beta, first, last_score, res.score.value());
                 res
             } else {
                 self.search_depth(board, d)
             };
+            prev_iter_nodes = self.nodes - iter_first_node;
+            let changed = d > 1 && r.bestmove != best;
+            bestmove_changes += u32::from(changed);
+            instability = instability / 2.0 + f32::from(u8::from(changed));
             best = r.bestmove.clone();
-            last_score = r.score_cp;
-            if self.nodes >= self.node_limit { break; }
+            last_score = r.score.value();
+            completed = d;
+            if self.track_root_scores { self.root_table.push(RootIteration { depth: d, scores: std::mem::take(&mut self.root_scores) }); }
+            if let Some(hook) = &self.info_hook {
+                let pv = self.pv_line(board, best.as_deref(), d);
+                hook(&SearchResult { bestmove: best.clone(), score: Score::from_value(last_score), nodes: self.nodes, depth: d, seldepth: self.seldepth, bestmove_changes, pv, ..Default::default() }.with_elapsed(start.elapsed()));
+            }
+            if self.nodes >= self.node_limit || self.stopped() { break; }
+            if d >= params.min_depth && held.is_some() { self.deadline = held.take(); }
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            if d < params.min_depth { (iter_start, iter_nodes) = (Instant::now(), self.nodes); continue; }
+            if let Some((tm, cfg)) = &mut clock {
+                // An unstable root gets more of the soft budget; a stable best move that
+                // took nearly all the root nodes gets less
+                tm.stretch_soft(1.0 + cfg.instability * instability);
+                if let Some(share) = self.best_move_share(best.as_deref()) { tm.scale_soft_by_effort(cfg.effort_scale(share, instability)); }
+                let cost = cfg.iteration_cost(self.nodes - iter_nodes, iter_start.elapsed());
+                if !tm.can_start_iteration(cost, cfg.growth) { break; }
+                (iter_start, iter_nodes) = (Instant::now(), self.nodes);
+            }
         }
-        SearchResult { bestmove: best, score_cp: last_score, nodes: self.nodes }
+        let pv = self.pv_line(board, best.as_deref(), completed);
+        SearchResult { bestmove: best, score: Score::from_value(last_score), nodes: self.nodes, depth: completed, seldepth: self.seldepth, bestmove_changes, pv, ..Default::default() }
+            .with_elapsed(start.elapsed())
     }
 
     fn search_depth_window(&mut self, board: &Board, depth: u32, alpha0: i32, beta0: i32) -> SearchResult {
@@ -539,33 +1405,54 @@ impl Searcher {
         if self.threads > 1 && depth > 1 { return self.search_depth(board, depth); }
 
         if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { qn.refresh(board); } }
+        self.root_scores.clear();
         let mut any = false;
         let orig_alpha = alpha;
-        board.generate_moves(|moves| {
-            for m in moves {
-                any = true;
-                let mut child = board.clone(); child.play(m);
-                let mut change = None;
-                if self.use_nnue { if let Some(qn) = self.nnue_quant.as_mut() { change = Some(qn.apply_move(board, m, &child)); } }
-                let score = -self.alphabeta(&child, depth.saturating_sub(1), -beta, -alpha, 1, move_index(m));
-                if let Some(ch) = change { if let Some(qn) = self.nnue_quant.as_mut() { qn.revert(ch); } }
-                if score > best_score { best_score = score; bestmove = Some(m); }
-                if score > alpha { alpha = score; }
+        let moves = self.ordered_root_moves(board);
+        let mut scores = Vec::with_capacity(moves.len());
+        self.root_effort.clear();
+        for (searched, m) in moves.into_iter().enumerate() {
+            any = true;
+            let mut child = board.clone(); child.play(m);
+            self.nnue_push(&child);
+            self.prev_capture = capture_square(board, m);
+            let before = self.nodes;
+            let (limit, cap) = (self.node_limit, before.saturating_add(self.root_move_cap));
+            self.node_limit = limit.min(cap);
+            let mut score = -self.alphabeta(&child, depth.saturating_sub(1), -beta, -alpha, 1, move_index(m), NodeType::Pv.child(searched));
+            self.node_limit = limit;
+            if self.nodes >= cap && self.nodes < limit {
+                // A runaway move: settle it a ply shallower with a full window
+                self.guard_trips += 1;
+                self.prev_capture = capture_square(board, m);
+                score = -self.alphabeta(&child, depth.saturating_sub(2), -MATE_SCORE, MATE_SCORE, 1, move_index(m), NodeType::Pv.child(searched));
             }
-            false
-        });
-        if !any { return SearchResult { bestmove: None, score_cp: self.eval_terminal(board, 0), nodes: self.nodes }; }
+            self.nnue_pop();
+            self.root_effort.push((m, self.nodes - before));
+            self.record_root_score(m, score, alpha, beta);
+            scores.push((m, score));
+            if score > best_score { best_score = score; bestmove = Some(m); }
+            if score > alpha { alpha = score; }
+            if self.nodes >= self.node_limit || self.stopped() { break; }
+        }
+        self.root_prev = scores;
+        if !any { return SearchResult { bestmove: None, score: Score::from_value(self.eval_terminal(board, 0)), nodes: self.nodes, ..Default::default() }; }
+        // Store the root with the bound the window gives it, as the full-window search does
+        let root_bound = if best_score <= orig_alpha { Bound::Upper } else if best_score >= beta { Bound::Lower } else { Bound::Exact };
+        if self.root_moves.is_none() { self.tt_put(board, depth, Score::from_value(best_score), bestmove, root_bound); }
         let bestmove_uci = bestmove.map(|m| format!("{}", m));
-        SearchResult { bestmove: bestmove_uci, score_cp: best_score, nodes: self.nodes }
+        SearchResult { bestmove: bestmove_uci, score: Score::from_value(best_score), nodes: self.nodes, ..Default::default() }
     }
 
-    fn is_capture(&self, board: &Board, m: Move) -> bool {
-        let opp = if board.side_to_move() == cozy_chess::Color::White { cozy_chess::Color::Black } else { cozy_chess::Color::White };
-        let opp_bb = board.colors(opp);
-        let m_str = format!("{}", m);
-        let to_str = &m_str[2..4];
-        for sq in opp_bb { if format!("{}", sq) == to_str { return true; } }
-        false
+    /// Plies to extend `m` by: one for a recapture on the square the previous move
+    /// captured on, or for a pawn push to the 7th, while the line has budget left.
+    /// At most one ply per move, so a future singular extension should take the
+    /// max with this rather than add to it.
+    fn extension(&self, board: &Board, m: Move, cap_sq: Option<Square>, recapture_sq: Option<Square>) -> u32 {
+        if self.ext_used >= self.max_extensions { return 0; }
+        if cap_sq.is_some() && cap_sq == recapture_sq { return 1; }
+        if board.piece_on(m.from) == Some(Piece::Pawn) && m.to.rank().relative_to(board.side_to_move()) == Rank::Seventh { return 1; }
+        0
     }
 
     fn update_killers(&mut self, ply: i32, m: Move) {
@@ -582,52 +1469,334 @@ impl Searcher {
         slot[0] = Some(m);
     }
 
-    fn killer_bonus(&self, ply: i32, m: Move) -> i32 {
-        let p = ply as usize;
-        if p >= self.killers.len() { return 0; }
-        let slot = &self.killers[p];
-        if slot[0] == Some(m) { 50 } else if slot[1] == Some(m) { 30 } else { 0 }
+    // Killers were stored by sibling positions: keep the ones legal here
+    fn legal_killers(&self, board: &Board, ply: i32) -> [Option<Move>; 2] {
+        let Some(slot) = self.killers.get(ply as usize) else { return [None, None] };
+        slot.map(|k| k.filter(|&m| self.tt.checks().killer(board.is_legal(m))))
+    }
+
+    // The probed entry's move, if it is legal here: an aliased or corrupted entry
+    // can carry a move from another position
+    fn tt_move(&self, board: &Board, probe: Option<Entry>) -> Option<Move> {
+        probe.and_then(|en| en.best).filter(|&m| self.tt.checks().tt_move(board.is_legal(m)))
     }
 
     // removed string-based continuation parent key
 
+    /// The reply the TT expects after `best` (UCI `bestmove ... ponder`), if legal.
+    pub fn ponder_move(&self, board: &Board, best: Move) -> Option<Move> {
+        if !board.is_legal(best) { return None; }
+        let mut child = board.clone();
+        child.play(best);
+        self.tt_get(&child).and_then(|e| e.best).filter(|&m| child.is_legal(m))
+    }
+
+    /// Principal variation from `best`: the table's best move in each following
+    /// position while it is legal, up to `len` moves or a repeated position. An
+    /// overwritten entry can cut the line short but never make it illegal.
+    pub fn tt_pv(&self, board: &Board, best: Move, len: u32) -> Vec<Move> {
+        let (mut b, mut pv) = (board.clone(), Vec::new());
+        let mut seen = vec![b.hash()];
+        let mut next = Some(best);
+        while let Some(m) = next.filter(|&m| pv.len() < len as usize && b.is_legal(m)) {
+            b.play(m);
+            pv.push(m);
+            if seen.contains(&b.hash()) { break; }
+            seen.push(b.hash());
+            next = self.tt_get(&b).and_then(|e| e.best);
+        }
+        pv
+    }
+
+    // `tt_pv` of a best move in UCI notation, at least that move
+    fn pv_line(&self, board: &Board, best: Option<&str>, depth: u32) -> Vec<String> {
+        let Some(uci) = best else { return Vec::new() };
+        let pv: Vec<String> = uci.parse().map(|m| self.tt_pv(board, m, depth.max(1))).unwrap_or_default().iter().map(|m| format!("{}", m)).collect();
+        if pv.is_empty() { vec![uci.to_string()] } else { pv }
+    }
+
     pub fn tt_probe(&self, board: &Board) -> Option<(u32, Bound)> {
         self.tt_get(board).map(|e| (e.depth, e.bound))
     }
 
+    /// Resize the table, keeping its entries (`Tt::resized_mb`); the same size is a
+    /// no-op. The new table replaces the shared one, so helpers still holding the old
+    /// one finish on it and the next search starts on the new one.
     pub fn set_tt_capacity_mb(&mut self, mb: usize) {
-        let mut tt = Tt::new();
-        tt.set_capacity_mb(mb);
-        self.tt = Arc::new(tt);
+        if self.tt.has_capacity_mb(mb) { return; }
+        self.tt = Arc::new(self.tt.resized_mb(mb));
     }
     pub fn get_threads(&self) -> usize { self.threads }
+    /// In-tree split accounting for the last search.
+    pub fn split_stats(&self) -> SplitStats { self.split_stats }
+    /// Beta cutoffs of the last search by move index.
+    pub fn cutoff_stats(&self) -> CutoffStats { self.cutoffs }
+    /// The same cutoffs split by the remaining depth of the node (index = depth),
+    /// to see where ordering is weakest: history-ordered shallow nodes or the TT
+    /// and killer guided deep ones.
+    pub fn cutoff_stats_by_depth(&self) -> &[CutoffStats] { &self.cutoffs_by_depth }
+    /// Root moves the explosion guard cut short in the last search.
+    pub fn guard_trips(&self) -> u32 { self.guard_trips }
+    /// Aspiration statistics of the game so far (reset by `new_game`).
+    pub fn window_stats(&self) -> WindowStats { self.window_stats }
+    /// Cumulative TT-move and killer legality checks on this searcher's table (`search-stats` only).
+    pub fn move_checks(&self) -> MoveChecks { self.tt.move_checks() }
+    /// The transposition table, shared with helper threads (keyed by `zobrist::compute`).
+    pub fn tt(&self) -> &Tt { &self.tt }
+    /// Enable coarse per-phase timing (movegen, ordering, eval, TT). Only the
+    /// calling thread is instrumented, so profile with threads=1.
+    pub fn set_profiling(&mut self, on: bool) { self.profiling = on; }
+    pub fn profile(&self) -> SearchProfile { self.profile }
+    pub fn set_info_hook(&mut self, hook: Option<InfoHook>) { self.info_hook = hook; }
+    /// Restrict the root to these moves (UCI `searchmoves`); `None` searches all.
+    pub fn set_search_moves(&mut self, moves: Option<Vec<Move>>) { self.root_moves = moves; }
+    /// Keys (`Board::hash`) of the game's positions before the root, oldest first, so
+    /// lines repeating them score as draws (UCI `position … moves`). Kept until replaced.
+    pub fn set_game_history(&mut self, keys: Vec<u64>) { self.game_history = keys; }
+    /// Flag that stops the running search once set (UCI `stop`), checked like the
+    /// deadline; the caller clears it before the next search.
+    pub fn set_stop_flag(&mut self, flag: Option<Arc<AtomicBool>>) { self.stop = flag; }
+    fn stopped(&self) -> bool { self.stop.as_ref().is_some_and(|f| f.load(Ordering::Relaxed)) }
+
+    /// Keep each root move's score per completed iteration (see `root_score_table`).
+    pub fn set_track_root_scores(&mut self, on: bool) { self.track_root_scores = on; }
+    /// Root scores by iteration from the last `search_with_params`, if tracking is on.
+    pub fn root_score_table(&self) -> &[RootIteration] { &self.root_table }
+
+    /// Nodes spent under each root move in the last completed iteration.
+    pub fn root_effort(&self) -> &[(Move, u64)] { &self.root_effort }
+
+    /// Share of the last iteration's root nodes spent on `best` (UCI notation).
+    pub fn best_move_share(&self, best: Option<&str>) -> Option<f32> {
+        let best: Move = best?.parse().ok()?;
+        best_move_share(&self.root_effort, &best)
+    }
+
+    fn record_root_score(&mut self, mv: Move, score_cp: i32, alpha: i32, beta: i32) {
+        if !self.track_root_scores { return; }
+        let bound = if score_cp <= alpha { Bound::Upper } else if score_cp >= beta { Bound::Lower } else { Bound::Exact };
+        self.root_scores.push(RootMoveScore { mv, score_cp, bound });
+    }
+
+    // Allowed root moves, best score in the last iteration first. Fail-low moves can
+    // tie the best on their bound, so ties keep the last iteration's order; moves it
+    // did not score follow in generation order
+    fn ordered_root_moves(&self, board: &Board) -> Vec<Move> {
+        let mut moves: Vec<Move> = Vec::with_capacity(64);
+        board.generate_moves(|ml| { for m in ml { if self.root_allowed(m) { moves.push(m); } } false });
+        let prev = |m: &Move| match self.root_prev.iter().position(|(pm, _)| pm == m) {
+            Some(i) => (std::cmp::Reverse(self.root_prev[i].1), i),
+            None => (std::cmp::Reverse(i32::MIN), usize::MAX),
+        };
+        moves.sort_by_key(prev);
+        moves
+    }
+
+    #[inline]
+    fn root_allowed(&self, m: Move) -> bool { self.root_moves.as_ref().is_none_or(|r| r.contains(&m)) }
+
+    /// Score each allowed root move independently with a `params.depth - 1` search of
+    /// the child (qsearch at depth 1). Cheaper than exclusion MultiPV, but every score is
+    /// one ply shallower. `params.movetime` bounds them all together. Results are in move
+    /// generation order.
+    pub fn score_root_moves(&mut self, board: &Board, params: SearchParams) -> Vec<(Move, i32)> {
+        let mut scored: Vec<(Move, i32)> = Vec::new();
+        board.generate_moves(|ml| { for m in ml { if self.root_allowed(m) { scored.push((m, 0)); } } false });
+        // The children search their own moves, not the root's restriction
+        let saved = self.root_moves.take();
+        let hook = self.info_hook.take();
+        // Every child search restarts `self.nodes`
+        let mut total_nodes = 0;
+        // One generation for all the child searches
+        self.begin_generation();
+        // `movetime` covers the whole root: each child gets what is left of it
+        let deadline = params.movetime.map(|t| Instant::now() + t);
+        for entry in &mut scored {
+            let mut child = board.clone();
+            child.play(entry.0);
+            let child_depth = params.depth.saturating_sub(1);
+            entry.1 = if child_depth == 0 { -self.qsearch_eval_cp(&child) } else {
+                let movetime = deadline.map(|d| d.saturating_duration_since(Instant::now()).max(Duration::from_millis(1)));
+                let p = SearchParams { depth: child_depth, movetime, clock: None, ..params };
+                self.gen_fresh = true;
+                let r = self.search_with_params(&child, p);
+                total_nodes += r.nodes;
+                -r.score.value()
+            };
+        }
+        self.nodes = total_nodes;
+        self.root_moves = saved;
+        self.info_hook = hook;
+        scored
+    }
+
+    /// Search independent positions concurrently on the current rayon pool (the
+    /// global one outside `install`): one single-threaded search per position, on a
+    /// worker per pool thread with its own table of this searcher's size and this
+    /// searcher's evaluation and stop flag. Positions start in order as workers free
+    /// up, so one long search never holds back the rest, and `(index, result)` pairs
+    /// arrive as they finish. As in `search::label`, each position starts from a
+    /// cleared table, so its result depends only on the position and `params`.
+    /// Dropping the receiver ends the batch after the searches already running.
+    pub fn search_batch(&self, positions: Vec<Board>, params: SearchParams) -> mpsc::Receiver<(usize, SearchResult)> {
+        let (tx, rx) = mpsc::channel();
+        let params = SearchParams { threads: 1, ..params };
+        let workers = rayon::current_num_threads().min(positions.len());
+        let jobs = Arc::new((positions, AtomicUsize::new(0)));
+        for _ in 0..workers {
+            let (mut w, jobs, tx) = (self.batch_worker(workers), jobs.clone(), tx.clone());
+            rayon::spawn(move || loop {
+                let i = jobs.1.fetch_add(1, Ordering::Relaxed);
+                let Some(board) = jobs.0.get(i) else { break };
+                w.new_game();
+                if tx.send((i, w.search_with_params(board, params))).is_err() { break; }
+            });
+        }
+        rx
+    }
+
+    // An idle searcher with our evaluation and stop flag, and an equal share of our
+    // table's size among `workers`, so a batch takes no more memory than one search
+    fn batch_worker(&self, workers: usize) -> Searcher {
+        let mut tt = Tt::new();
+        tt.set_capacity_entries(self.tt.capacity() / workers.max(1));
+        Searcher {
+            tt: Arc::new(tt),
+            eval_hash: self.eval_hash.clone(),
+            stop: self.stop.clone(),
+            use_nnue: self.use_nnue,
+            nnue: self.nnue.clone(),
+            nnue_quant: self.nnue_quant.as_ref().map(|qn| qn.fork()),
+            nnue_small: self.nnue_small.clone(),
+            small_net_only: self.small_net_only,
+            eval_blend_percent: self.eval_blend_percent,
+            material_eval: self.material_eval,
+            policy: self.policy.clone(),
+            policy_weight: self.policy_weight,
+            ..Searcher::default()
+        }
+    }
+
+    /// Best `lines` root moves, best first, using the given style.
+    pub fn search_multipv(&mut self, board: &Board, params: SearchParams, lines: usize, style: AnalysisStyle) -> Vec<SearchResult> {
+        match style {
+            AnalysisStyle::Exclusion => self.search_multipv_exclusion(board, params, lines),
+            AnalysisStyle::Scored => {
+                let start = Instant::now();
+                let mut scored = self.score_root_moves(board, params);
+                scored.sort_by_key(|&(_, s)| -s);
+                let (nodes, seldepth, elapsed) = (self.nodes, self.seldepth, start.elapsed());
+                scored.into_iter().take(lines).map(|(m, s)| {
+                    let pv = self.tt_pv(board, m, params.depth.max(1)).iter().map(|m| format!("{}", m)).collect();
+                    SearchResult { bestmove: Some(format!("{}", m)), score: Score::from_value(s), nodes, depth: params.depth.max(1), seldepth, pv, ..Default::default() }.with_elapsed(elapsed)
+                }).collect()
+            }
+        }
+    }
+
+    /// Exclusion MultiPV: search, report the best move, exclude it and search again,
+    /// up to `lines` times. Each line is a full search of the remaining moves, so
+    /// secondary scores are exact rather than bounds from a shared window. Honours
+    /// an existing `searchmoves` restriction; `params.movetime` applies per line.
+    pub fn search_multipv_exclusion(&mut self, board: &Board, params: SearchParams, lines: usize) -> Vec<SearchResult> {
+        let mut candidates: Vec<Move> = Vec::new();
+        board.generate_moves(|ml| { for m in ml { if self.root_allowed(m) { candidates.push(m); } } false });
+        let saved = self.root_moves.take();
+        let hook = self.info_hook.take();
+        let mut out = Vec::new();
+        // The lines share one generation
+        self.begin_generation();
+        for _ in 0..lines.min(candidates.len()) {
+            self.root_moves = Some(candidates.clone());
+            self.gen_fresh = true;
+            let res = self.search_with_params(board, params);
+            let Some(best) = res.bestmove.as_deref().and_then(|u| candidates.iter().position(|m| format!("{}", m) == u)) else { break };
+            candidates.remove(best);
+            out.push(res);
+            if self.stopped() { break; }
+        }
+        self.root_moves = saved;
+        self.info_hook = hook;
+        // Shallow searches are not monotone across exclusions; report best first
+        out.sort_by_key(|r| -r.score);
+        out
+    }
 
     pub fn set_use_nnue(&mut self, on: bool) { self.use_nnue = on; }
-    pub fn set_nnue_network(&mut self, nn: Option<crate::eval::nnue::Nnue>) { self.nnue = nn; }
-    pub fn set_nnue_quant_model(&mut self, model: QuantNnue) { self.nnue_quant = Some(QuantNetwork::new(model)); }
-    pub fn clear_nnue_quant(&mut self) { self.nnue_quant = None; }
+    pub fn set_nnue_network(&mut self, nn: Option<crate::eval::nnue::Nnue>) { self.nnue = nn; self.eval_hash.clear(); }
+    /// The dense network, if one is installed.
+    pub fn nnue_network(&self) -> Option<&crate::eval::nnue::Nnue> { self.nnue.as_ref() }
+    /// Install (or hot-swap) the quantized network. Takes effect from the next search,
+    /// workers included; TT scores from the previous network are dropped.
+    pub fn set_nnue_quant_model(&mut self, model: impl Into<Arc<QuantNnue>>) {
+        self.nnue_quant = Some(QuantNetwork::from_shared(model.into()));
+        self.tt.clear();
+        self.eval_hash.clear();
+    }
+    /// Weights of the installed quantized network, if any.
+    pub fn nnue_quant_model(&self) -> Option<&Arc<QuantNnue>> { self.nnue_quant.as_ref().map(|qn| &qn.model) }
+    pub fn clear_nnue_quant(&mut self) { self.nnue_quant = None; self.eval_hash.clear(); }
+    /// Install (or remove) the small network; like a main-network swap, cached scores
+    /// are dropped.
+    pub fn set_nnue_small(&mut self, small: Option<SmallNet>) {
+        self.nnue_small = small;
+        self.tt.clear();
+        self.eval_hash.clear();
+    }
+    pub fn nnue_small(&self) -> Option<&SmallNet> { self.nnue_small.as_ref() }
+    /// Evaluate every position with the small network (when one is installed), e.g.
+    /// with little time left. Set per search; a change drops the eval hash and the
+    /// TT, whose scores came from the other network.
+    pub fn set_small_net_only(&mut self, on: bool) {
+        if on != self.small_net_only && self.nnue_small.is_some() {
+            self.tt.clear();
+            self.eval_hash.clear();
+        }
+        self.small_net_only = on;
+    }
+    /// Resize the NNUE eval hash (dropping its entries); 0 disables it. Same size: no-op.
+    pub fn set_eval_hash_mb(&mut self, mb: usize) {
+        if mb != self.eval_hash.mb() { self.eval_hash = Arc::new(EvalHash::with_mb(mb)); }
+    }
+    /// NNUE eval hash probes and hits since it was sized or cleared.
+    pub fn eval_hash_stats(&self) -> EvalHashStats { self.eval_hash.stats() }
     pub fn set_eval_blend_percent(&mut self, p: u8) { self.eval_blend_percent = p.min(100); }
+    /// Install (or remove) the move-ordering policy net.
+    pub fn set_policy(&mut self, net: Option<PolicyNet>) { self.policy = net.map(Arc::new); }
+    pub fn policy(&self) -> Option<&PolicyNet> { self.policy.as_deref() }
+    /// Ordering points of the policy's favourite move; 0 turns the policy off.
+    pub fn set_policy_weight(&mut self, w: u32) { self.policy_weight = w; }
+    /// Evaluate with material only (a baseline for eval comparisons); ignored while NNUE is on.
+    pub fn set_material_eval(&mut self, on: bool) { self.material_eval = on; }
+    /// The static eval the search uses at leaves (side to move's view, cp).
+    pub fn static_eval(&self, board: &Board) -> i32 { self.eval_cp_internal(board) }
+
+    // The network's raw output (White-relative) through the eval hash, or `None`
+    // without a network. `incremental` reads the accumulator, which must be at `board`;
+    // the small net (endgames, or everything under `small_net_only`) needs none
+    fn nnue_raw(&self, board: &Board, incremental: bool) -> Option<i32> {
+        if let Some(small) = self.nnue_small.as_ref().filter(|sn| self.small_net_only || sn.covers(game_phase(board))) {
+            return Some(self.eval_hash.get_or_insert_with(board.hash(), || small.eval_board(board)));
+        }
+        if let Some(qn) = &self.nnue_quant {
+            Some(self.eval_hash.get_or_insert_with(board.hash(), || if incremental { qn.eval_current() } else { qn.eval_full(board) }))
+        } else {
+            self.nnue.as_ref().map(|nn| self.eval_hash.get_or_insert_with(board.hash(), || nn.evaluate(board)))
+        }
+    }
 
     fn eval_cp_internal(&self, board: &Board) -> i32 {
+        if let Some(winner) = self.converting { return conversion::eval(board, winner); }
         if self.use_nnue {
-            let mut have_nnue = false;
-            let mut nnue_sided = 0i32;
-            if let Some(qn) = &self.nnue_quant {
-                let score = qn.eval_full(board);
-                nnue_sided = if board.side_to_move() == cozy_chess::Color::White { score } else { -score };
-                have_nnue = true;
-            } else if let Some(nn) = &self.nnue {
-                let score = nn.evaluate(board);
-                nnue_sided = if board.side_to_move() == cozy_chess::Color::White { score } else { -score };
-                have_nnue = true;
-            }
-            if have_nnue {
+            if let Some(raw) = self.nnue_raw(board, false) {
+                let nnue_sided = scale_eval(&MaterialSig::from_board(board), side_relative(board.side_to_move() == cozy_chess::Color::White, raw));
                 if self.eval_blend_percent >= 100 { return nnue_sided; }
                 let pst = eval_cp(board);
                 if self.eval_blend_percent == 0 { return pst; }
                 return ((nnue_sided as i64 * self.eval_blend_percent as i64 + pst as i64 * (100 - self.eval_blend_percent) as i64) / 100) as i32;
             }
         }
+        if self.material_eval { return material_eval_cp(board); }
         eval_cp(board)
     }
 }
@@ -1,93 +1,569 @@
 #![cfg(feature = "board-pleco")]
 use pleco::{Board as PlecoBoard, BitMove as PMove, Player, PieceType, Piece};
 use crate::search::tt_pleco::{TtPleco, Entry as TtEntry, Bound as TtBound};
+use crate::search::tt::MoveChecks;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::time::{Duration, Instant};
 use rayon::prelude::*;
 use std::time::Duration as StdDuration;
-use crate::search::eval::{MATE_SCORE, DRAW_SCORE};
+use crate::search::eval::{pst_cp_pleco, scale_eval, side_relative, KingAttack, MaterialSig, Score, MATE_BOUND, MATE_SCORE};
+use crate::search::score::MAX_PLY;
+use crate::search::aspiration::{Window, WindowStats, MIN_WINDOW};
+use crate::search::contempt::{Contempt, DrawBias};
+use crate::search::conversion;
+use crate::search::alphabeta::{HelperScheme, InfoHook, NodeType, LmpTable, SearchResult, SplitConfig, SplitPolicy, SplitStats, EXPLOSION_GUARD, GUARD_MIN_DEPTH, IIR_DEPTH};
+use crate::search::guard::guarded;
+use crate::search::eval_hash::{EvalHash, EvalHashStats};
+use crate::search::timeman::{best_move_share, ClockPlan, TimeConfig, TimeManager, WallClock};
+use crate::eval::nnue::loader::QuantNnue;
+use crate::eval::nnue::network::{eval_indices, QuantNetwork, SmallNet};
+use crate::eval::nnue::features::active_indices_pleco;
+use crate::eval::policy::{PolicyNet, DEFAULT_POLICY_WEIGHT, POLICY_MIN_DEPTH};
+// `evals` entry for a node searched in check (no static eval)
+const NO_EVAL: i32 = i32::MIN;
+
+// Eval hash key. Pleco's zobrist key leaves out the black king's square, which the
+// network's features depend on, so it is mixed back in
+fn eval_key(board: &PlecoBoard) -> u64 {
+    board.zobrist() ^ crate::search::zobrist::splitmix64(1 + board.king_sq(Player::Black).0 as u64)
+}
 
 pub struct PlecoSearcher {
     nodes: u64,
     deadline: Option<Instant>,
     tt: Arc<TtPleco>,
+    eval_hash: Arc<EvalHash>, // NNUE outputs by position, shared with every helper
     killers: Vec<[Option<PMove>; 2]>,
     history: Vec<i32>,
     threads: usize,
     use_killers: bool,
     use_lmr: bool,
     use_nullmove: bool,
+    king_danger: bool,      // relax pruning under a dangerous king attack
+    node_types: bool,       // IIR, cut-node LMR and all-node null skip (see `set_node_types`)
+    conversion: bool,       // see `set_conversion`
     use_aspiration: bool,
     aspiration_window_cp: i32,
     last_depth: u32,
     abort: Option<Arc<std::sync::atomic::AtomicBool>>,
     smp_mode: SmpMode,
+    smp_safe: bool,         // helpers keep the main thread's pruning (see `set_smp_safe`)
     // Heuristic knobs for diversification (used by Lazy SMP helpers)
     lmr_aggr: i32,          // extra LMR reduction for helpers
+    lmr_late_extra: u32,    // extra LMR plies for moves ordered 8th or later (see `set_lmr_late_extra`)
     null_r_bonus: i32,      // extra null-move reduction R for helpers
     tt_first: bool,         // whether to hoist TT move to front
     order_offset: usize,    // rotate tail by offset to diversify ordering
     helper_mode: bool,      // enables aggressive helper-only pruning (LMP/Futility)
+    smp_seed: u64,          // master seed for LazyIndep helper profiles (see `HelperProfile`)
+    helper_scheme: HelperScheme, // LazyIndep helpers: rotated order or skipped iterations
+    skip_worker: usize,     // this helper's index when it skips iterations (`helper_skips`); 0 = none
+    lmp: LmpTable,          // helper LMP move counts (see `set_lmp`)
+    use_futility: bool,     // futility pruning of quiets at depth <= 2 outside helper_mode
+    use_lmp: bool,          // late-move pruning with `lmp` outside helper_mode
+    evals: Vec<i32>,        // helper static evals by ply on the current line, for `improving`
     max_seldepth: u32,      // deepest ply reached (selective depth)
     tm_finish_one: bool,    // time manager policy: true = finish-one-depth, false = spend budget
+    min_depth: u32,         // iterations completed before the movetime may stop the search
+    gen_fresh: bool,        // `new_search` advanced the TT generation for the next search
     tm_factor: f32,         // multiplier for predicting next iteration cost
+    tm_effort: f32,         // weight of the best move's root-node share; 0 = off (see `set_tm_effort`)
+    root_effort: Vec<(PMove, u64)>, // nodes under each root move in the last iteration
+    root_prev: Vec<(PMove, i32)>,   // root move scores from the last iteration, to order the next one
+    bestmove_changes: u32,  // iterations whose best move differed from the previous one
+    clock: Option<ClockPlan>,       // clock play: movetime is the hard limit (see `set_clock_plan`)
+    explosion_guard: u32,   // root move cap as a multiple of the previous iteration's nodes
+    node_cap: u64,          // node count at which the current aspirated root move stops
+    node_limit: u64,        // node budget of the whole search (see `set_node_limit`)
+    guard_trips: u32,
+    adaptive_window: bool,  // aspiration window from the game's statistics
+    window_stats: WindowStats, // aspiration fails and score swings, kept until `clear`
+    eval_mode: PlecoEvalMode,
+    nnue_quant: Option<QuantNetwork>,
+    nnue_small: Option<SmallNet>, // endgame network; evaluates everything while `small_net_only`
+    small_net_only: bool,
+    policy: Option<Arc<PolicyNet>>, // orders quiets at nodes without history
+    policy_weight: u32,
+    hybrid_stats: HybridStats,
+    split: SplitConfig,
+    split_stats: SplitStats,
+    contempt: Contempt,
+    draw_bias: DrawBias,    // contempt resolved for the current iteration
+    root_side: Player,      // the engine's side
+    path: Vec<u64>,         // keys of the line being searched, for repetitions
+    game_history: Vec<u64>, // keys of the game's positions before the root (see `set_game_history`)
+    converting: Option<Player>, // winning side while converting a TB win
+    info_hook: Option<InfoHook>,
+    search_start: Instant,
+    root_moves: Option<Vec<PMove>>, // `searchmoves` / MultiPV exclusion; None = all
+    stop: Option<Arc<AtomicBool>>,  // UCI `stop`, shared with every helper
 }
 
+/// Pre-warm accounting for `SmpMode::LazyHybrid`, reset at the start of each search.
+/// TT hit rates are measured over the cooperative phase of each iteration, split by
+/// whether a pre-warm helper ran just before it (probe counts need the `search-stats` feature).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HybridStats {
+    pub prewarm_runs: u32,
+    pub prewarm_ms: u64,
+    pub prewarm_nodes: u64,
+    pub cold_probes: u64,
+    pub cold_hits: u64,
+    pub warm_probes: u64,
+    pub warm_hits: u64,
+}
+
+impl HybridStats {
+    pub fn cold_hit_rate(&self) -> f64 { if self.cold_probes == 0 { 0.0 } else { self.cold_hits as f64 / self.cold_probes as f64 } }
+    pub fn warm_hit_rate(&self) -> f64 { if self.warm_probes == 0 { 0.0 } else { self.warm_hits as f64 / self.warm_probes as f64 } }
+}
+
+/// Static evaluation used by the pleco searcher. `Pst` adds the piece-square tables
+/// of the cozy eval to material. `Nnue` falls back to material when no quantized
+/// model is loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PlecoEvalMode { #[default] Material, Pst, Nnue }
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SmpMode { Off, InTree, LazyIndep, LazyCoop, LazyHybrid }
 
-impl Default for PlecoSearcher { fn default() -> Self { Self { nodes: 0, deadline: None, tt: Arc::new(TtPleco::default()), killers: vec![[None,None];256], history: vec![0; 64*64*5], threads: 1, use_killers: true, use_lmr: true, use_nullmove: true, use_aspiration: true, aspiration_window_cp: 30, last_depth: 0, abort: None, smp_mode: SmpMode::InTree, lmr_aggr: 0, null_r_bonus: 0, tt_first: true, order_offset: 0, helper_mode: false, max_seldepth: 0, tm_finish_one: true, tm_factor: 1.9 } } }
+/// How one `SmpMode::LazyIndep` worker departs from the main thread: a wider
+/// aspiration window, TT-move hoisting, a rotated move-order tail and extra LMR.
+/// Worker 0 is the main thread and is never diversified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HelperProfile { pub window_extra: i32, pub tt_first: bool, pub order_offset: usize, pub lmr_aggr: i32 }
+
+impl HelperProfile {
+    /// Worker `wid`'s profile under master seed `seed`. Seed 0 keeps the fixed
+    /// scheme derived from the worker index alone; any other seed draws each
+    /// worker's profile from `splitmix64(seed, wid)`, so a run is reproducible from
+    /// its seed and a sweep over seeds varies the helpers systematically.
+    pub fn derive(seed: u64, wid: usize) -> Self {
+        if wid == 0 { return Self { window_extra: 0, tt_first: true, order_offset: 0, lmr_aggr: 0 }; }
+        if seed == 0 {
+            return Self { window_extra: (wid as i32 % 3) * 20, tt_first: wid.is_multiple_of(2), order_offset: wid, lmr_aggr: 1 + wid as i32 % 2 };
+        }
+        let r = crate::search::zobrist::splitmix64(seed ^ (wid as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        Self { window_extra: (r % 3) as i32 * 20, tt_first: (r >> 8) & 1 == 0, order_offset: 1 + ((r >> 16) % 7) as usize, lmr_aggr: 1 + ((r >> 24) & 1) as i32 }
+    }
+}
+
+// Iteration-skipping patterns for `HelperScheme::Skip`, one per helper (cycling):
+// helper `i` skips depth `d` when `(d + SKIP_PHASE[i]) / SKIP_SIZE[i]` is odd
+const SKIP_SIZE: [u32; 20] = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4];
+const SKIP_PHASE: [u32; 20] = [0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7];
+
+/// Whether worker `wid` skips iteration `depth` under `HelperScheme::Skip`. The main
+/// thread (worker 0) and depth 1 are never skipped; the first two helpers take odd
+/// and even depths, later ones skip in longer, shifted runs.
+pub fn helper_skips(wid: usize, depth: u32) -> bool {
+    if wid == 0 || depth <= 1 { return false; }
+    let i = (wid - 1) % SKIP_SIZE.len();
+    ((depth + SKIP_PHASE[i]) / SKIP_SIZE[i]) % 2 == 1
+}
+
+impl Default for PlecoSearcher { fn default() -> Self { Self { nodes: 0, deadline: None, tt: Arc::new(TtPleco::default()), eval_hash: Arc::default(), killers: vec![[None,None];MAX_PLY], history: vec![0; 64*64*5], threads: 1, use_killers: true, use_lmr: true, use_nullmove: true, king_danger: true, node_types: false, conversion: false, use_aspiration: true, aspiration_window_cp: 30, last_depth: 0, abort: None, smp_mode: SmpMode::InTree, smp_safe: false, lmr_aggr: 0, lmr_late_extra: 0, null_r_bonus: 0, tt_first: true, order_offset: 0, helper_mode: false, smp_seed: 0, helper_scheme: HelperScheme::Rotate, skip_worker: 0, lmp: LmpTable::default(), use_futility: false, use_lmp: false, evals: Vec::new(), max_seldepth: 0, tm_finish_one: true, min_depth: 0, gen_fresh: false, tm_factor: 1.9, tm_effort: 0.0, root_effort: Vec::new(), root_prev: Vec::new(), bestmove_changes: 0, explosion_guard: EXPLOSION_GUARD, node_cap: u64::MAX, node_limit: u64::MAX, guard_trips: 0, adaptive_window: true, window_stats: WindowStats::default(), eval_mode: PlecoEvalMode::Material, nnue_quant: None, nnue_small: None, small_net_only: false, policy: None, policy_weight: DEFAULT_POLICY_WEIGHT, hybrid_stats: HybridStats::default(), split: SplitConfig::default(), split_stats: SplitStats::default(), contempt: Contempt::default(), draw_bias: DrawBias::default(), root_side: Player::White, path: Vec::new(), game_history: Vec::new(), converting: None, info_hook: None, search_start: Instant::now(), root_moves: None, stop: None, clock: None } } }
 
 impl PlecoSearcher {
-    pub fn clear(&mut self) { self.nodes = 0; self.killers.iter_mut().for_each(|k| *k = [None, None]); self.history.fill(0); self.tt.bump_generation(); }
-    pub fn set_tt_capacity_mb(&mut self, mb: usize) { Arc::get_mut(&mut self.tt).map(|t| t.set_capacity_mb(mb)); }
+    /// Age heuristics between moves of the same game (history halved, killers dropped, TT generation bumped).
+    pub fn new_search(&mut self) { self.killers.iter_mut().for_each(|k| *k = [None, None]); self.history.iter_mut().for_each(|h| *h /= 2); self.tt.bump_generation(); self.gen_fresh = true; }
+    // A search started without `new_search` still gets its own TT generation
+    fn begin_generation(&mut self) { if !std::mem::take(&mut self.gen_fresh) { self.tt.bump_generation(); } }
+    /// Reset all per-game state (`ucinewgame`): TT, killers, history and window statistics.
+    pub fn clear(&mut self) { self.nodes = 0; self.killers.iter_mut().for_each(|k| *k = [None, None]); self.history.fill(0); self.tt.clear(); self.window_stats = WindowStats::default(); }
+    /// Resize the table, keeping its entries; see `Searcher::set_tt_capacity_mb`.
+    pub fn set_tt_capacity_mb(&mut self, mb: usize) { if !self.tt.has_capacity_mb(mb) { self.tt = Arc::new(self.tt.resized_mb(mb)); } }
     pub fn set_threads(&mut self, t: usize) { self.threads = t.max(1); }
     pub fn last_depth(&self) -> u32 { self.last_depth }
     pub fn set_smp_mode(&mut self, m: SmpMode) { self.smp_mode = m; }
+    /// Safe SMP profile. Every helper thread, in every `SmpMode`, searches with the
+    /// main thread's pruning rules: its LMR, null-move and aspiration toggles, no
+    /// extra LMR or null-move reduction, no helper-only move-count or futility
+    /// pruning. Helpers still differ in move order and aspiration width, which
+    /// changes which nodes they reach but not the bounds they prove. A search cut
+    /// short (deadline, or a sibling's cutoff at a split) stores nothing in the TT,
+    /// and its score is dropped rather than counted as a bound. LazyCoop picks its
+    /// best move from the current depth's scores only. So:
+    /// - every TT entry a helper writes is one the single-threaded search could store;
+    /// - a multi-threaded result at depth d is a depth-d result of the same search,
+    ///   and stays within a small margin of the single-threaded score (tests/smp_safe.rs).
+    ///
+    /// Off by default: the faster diversified helpers gain more in timed play.
+    pub fn set_smp_safe(&mut self, on: bool) { self.smp_safe = on; }
+    pub fn smp_safe(&self) -> bool { self.smp_safe }
+    /// Master seed for the Lazy SMP helper profiles (`HelperProfile::derive`); 0 is
+    /// the fixed per-index scheme for LazyIndep and undiversified LazyCoop/LazyHybrid
+    /// helpers.
+    pub fn set_smp_seed(&mut self, seed: u64) { self.smp_seed = seed; }
+    /// How LazyIndep helpers diversify: `Rotate` (default) rotates their move order,
+    /// `Skip` staggers the depths they iterate (`helper_skips`).
+    pub fn set_helper_scheme(&mut self, scheme: HelperScheme) { self.helper_scheme = scheme; }
+    pub fn helper_scheme(&self) -> HelperScheme { self.helper_scheme }
+    pub fn smp_seed(&self) -> u64 { self.smp_seed }
+    /// Search toggles (all on by default) and the aspiration half-window in cp.
+    pub fn set_heuristics(&mut self, killers: bool, lmr: bool, nullmove: bool, aspiration: bool, window_cp: i32) {
+        self.use_killers = killers; self.use_lmr = lmr; self.use_nullmove = nullmove; self.use_aspiration = aspiration; self.aspiration_window_cp = window_cp.max(1);
+    }
+    /// Extra LMR plies for moves ordered 8th or later (`SearchParams::lmr_late_extra`).
+    pub fn set_lmr_late_extra(&mut self, plies: u32) { self.lmr_late_extra = plies; }
     pub fn last_seldepth(&self) -> u32 { self.max_seldepth }
+    /// Always complete this many iterations, however short the movetime
+    /// (`timeman::SearchLimits`); 0, the default, lets the movetime cut any.
+    pub fn set_min_depth(&mut self, depth: u32) { self.min_depth = depth; }
     pub fn set_time_manager(&mut self, finish_one: bool, factor: f32) { self.tm_finish_one = finish_one; self.tm_factor = if factor > 0.1 { factor } else { 1.9 }; }
+    /// Scale the movetime (or node limit) by the best move's share of the root nodes
+    /// (`TimeConfig::effort` weight; 0, the default, leaves it alone): a stable best
+    /// move that took nearly all of them stops the search early, effort spread over
+    /// several moves shrinks the finish-one reserve so one more iteration can start.
+    /// For clock play, where the movetime is a soft budget.
+    pub fn set_tm_effort(&mut self, weight: f32) { self.tm_effort = weight.max(0.0); }
+    /// Stop after this many nodes, as `SearchParams::max_nodes` does for the cozy
+    /// searcher; an iteration the limit cuts short is dropped after depth 1. Counts
+    /// this searcher's own nodes (Lazy SMP helpers are not limited). `None`, the
+    /// default, searches without a limit.
+    pub fn set_node_limit(&mut self, limit: Option<u64>) { self.node_limit = limit.unwrap_or(u64::MAX); }
+    /// Clock play as with `SearchParams::clock`: the movetime given to the search is
+    /// the hard limit, and the serial and in-tree roots start no iteration past the
+    /// soft budget, stretched while the best move keeps changing and scaled by its
+    /// share of the root nodes. Lazy SMP modes search to the soft budget. `None`
+    /// (the default) treats the movetime as a plain deadline.
+    pub fn set_clock_plan(&mut self, plan: Option<ClockPlan>) { self.clock = plan; }
+    /// Nodes spent under each root move in the last iteration (for Lazy SMP, the
+    /// chosen worker's or the cooperative root split's).
+    pub fn root_effort(&self) -> &[(PMove, u64)] { &self.root_effort }
+    /// Cap a serially searched root move inside an aspiration window at this many
+    /// times the previous iteration's nodes (`SearchParams::explosion_guard`); 0 = off.
+    pub fn set_explosion_guard(&mut self, factor: u32) { self.explosion_guard = factor; }
+    /// Root moves the explosion guard cut short in the last search.
+    pub fn guard_trips(&self) -> u32 { self.guard_trips }
+    /// Start serial aspiration windows from the game's statistics (`search::aspiration`).
+    pub fn set_adaptive_window(&mut self, on: bool) { self.adaptive_window = on; }
+    /// Aspiration statistics of the game so far (reset by `clear`).
+    pub fn window_stats(&self) -> WindowStats { self.window_stats }
+    pub fn hybrid_stats(&self) -> HybridStats { self.hybrid_stats }
+    /// In-tree split policy and thresholds (`SmpMode::InTree`).
+    pub fn set_split(&mut self, split: SplitConfig) { self.split = split; }
+    /// Late-move pruning thresholds for the diversified helpers (not in safe mode).
+    pub fn set_lmp(&mut self, lmp: LmpTable) { self.lmp = lmp; }
+    /// Futility-prune quiets at depth <= 2 on every thread, not only the helpers
+    /// (never at PV nodes, in check, or near mate scores).
+    pub fn set_futility(&mut self, on: bool) { self.use_futility = on; }
+    /// Late-move-prune quiets with the `set_lmp` table on every thread, not only the
+    /// helpers (never at PV nodes or in check).
+    pub fn set_lmp_pruning(&mut self, on: bool) { self.use_lmp = on; }
+    /// In-tree split accounting for the last search.
+    pub fn split_stats(&self) -> SplitStats { self.split_stats }
+    /// Cumulative TT-move and killer legality checks on this searcher's table (`search-stats` only).
+    pub fn move_checks(&self) -> MoveChecks { self.tt.move_checks() }
+    /// The transposition table, shared with helper threads (keyed by `Board::zobrist`).
+    pub fn tt(&self) -> &TtPleco { &self.tt }
+    pub fn set_contempt(&mut self, c: Contempt) { self.contempt = c; }
+    /// Relax null move and LMR at nodes where the side to move is attacking the king.
+    pub fn set_king_danger(&mut self, on: bool) { self.king_danger = on; }
+    /// Predict PV/cut/all nodes from the parent: reduce PV and cut nodes without a TT
+    /// move by one ply (below the IID plies), reduce late moves one ply more at cut
+    /// nodes, and skip null move at all nodes.
+    pub fn set_node_types(&mut self, on: bool) { self.node_types = on; }
+    /// Convert TB-won roots (`search::conversion`): no contempt, null move or LMR,
+    /// and an eval that drives the mate.
+    pub fn set_conversion(&mut self, on: bool) { self.conversion = on; }
+    pub fn set_eval_mode(&mut self, m: PlecoEvalMode) { self.eval_mode = m; }
+    pub fn eval_mode(&self) -> PlecoEvalMode { self.eval_mode }
+    /// Install (or hot-swap) the quantized network for the next search; TT scores
+    /// from the previous network are dropped.
+    pub fn set_nnue_quant_model(&mut self, model: impl Into<Arc<QuantNnue>>) {
+        self.nnue_quant = Some(QuantNetwork::from_shared(model.into()));
+        self.tt.clear();
+        self.eval_hash.clear();
+    }
+    pub fn nnue_quant_model(&self) -> Option<&Arc<QuantNnue>> { self.nnue_quant.as_ref().map(|qn| &qn.model) }
+    pub fn clear_nnue_quant(&mut self) { self.nnue_quant = None; self.eval_hash.clear(); }
+    /// Install (or remove) the small endgame network (`SmallNet`); cached scores are dropped.
+    pub fn set_nnue_small(&mut self, small: Option<SmallNet>) {
+        self.nnue_small = small;
+        self.tt.clear();
+        self.eval_hash.clear();
+    }
+    pub fn nnue_small(&self) -> Option<&SmallNet> { self.nnue_small.as_ref() }
+    /// Evaluate every position with the small network for the next searches (low
+    /// time); a change drops the cached scores of the other network.
+    pub fn set_small_net_only(&mut self, on: bool) {
+        if on != self.small_net_only && self.nnue_small.is_some() {
+            self.tt.clear();
+            self.eval_hash.clear();
+        }
+        self.small_net_only = on;
+    }
+    /// Install (or remove) the move-ordering policy net (`eval::policy`).
+    pub fn set_policy(&mut self, net: Option<PolicyNet>) { self.policy = net.map(Arc::new); }
+    pub fn policy(&self) -> Option<&PolicyNet> { self.policy.as_deref() }
+    /// Ordering points of the policy's favourite move; 0 turns the policy off.
+    pub fn set_policy_weight(&mut self, w: u32) { self.policy_weight = w; }
+    /// Resize the NNUE eval hash (`search::eval_hash`), dropping its entries; 0
+    /// disables it. Same size: no-op.
+    pub fn set_eval_hash_mb(&mut self, mb: usize) {
+        if mb != self.eval_hash.mb() { self.eval_hash = Arc::new(EvalHash::with_mb(mb)); }
+    }
+    /// NNUE eval hash probes and hits since it was sized or cleared.
+    pub fn eval_hash_stats(&self) -> EvalHashStats { self.eval_hash.stats() }
+    /// The static eval the search uses at leaves (side to move's view, cp).
+    pub fn static_eval(&mut self, board: &PlecoBoard) -> i32 {
+        self.nnue_refresh(board);
+        self.eval(board)
+    }
+    /// Per-iteration callback. LazyIndep only reports its final result.
+    pub fn set_info_hook(&mut self, hook: Option<InfoHook>) { self.info_hook = hook; }
+    /// Restrict the root to these moves (UCI `searchmoves`); `None` searches all.
+    /// A restricted root is searched by the main thread, without Lazy SMP helpers.
+    pub fn set_search_moves(&mut self, moves: Option<Vec<PMove>>) { self.root_moves = moves; }
+    /// Keys (`Board::zobrist`) of the game's positions before the root, oldest first, so
+    /// lines repeating them score as draws (UCI `position … moves`). Kept until replaced.
+    pub fn set_game_history(&mut self, keys: Vec<u64>) { self.game_history = keys; }
+    /// Flag that stops the running search once set (UCI `stop`), checked like the
+    /// deadline; the caller clears it before the next search.
+    pub fn set_stop_flag(&mut self, flag: Option<Arc<AtomicBool>>) { self.stop = flag; }
+    fn stopped(&self) -> bool { self.stop.as_ref().is_some_and(|f| f.load(std::sync::atomic::Ordering::Relaxed)) }
+
+    // Legal root moves allowed by `root_moves`
+    fn root_move_list(&self, board: &PlecoBoard) -> Vec<PMove> {
+        board.generate_moves().iter().copied().filter(|m| self.root_moves.as_ref().is_none_or(|r| r.contains(m))).collect()
+    }
+
+    /// Exclusion MultiPV: search, take the best move out of the root and search
+    /// again, up to `lines` times, so every line's score is exact. `millis` applies
+    /// per line; lines come best first and share one TT generation.
+    pub fn search_multipv(&mut self, board: &mut PlecoBoard, millis: u64, depth: u32, lines: usize) -> Vec<SearchResult> {
+        let mut candidates = self.root_move_list(board);
+        let saved = self.root_moves.take();
+        let hook = self.info_hook.take();
+        let mut out = Vec::new();
+        self.begin_generation();
+        for _ in 0..lines.min(candidates.len()) {
+            self.root_moves = Some(candidates.clone());
+            self.gen_fresh = true;
+            let t0 = Instant::now();
+            let (bm, sc, nodes) = self.search_movetime(board, millis, depth);
+            let Some(best) = bm.and_then(|m| candidates.iter().position(|&c| c == m)) else { break };
+            candidates.remove(best);
+            let pv = self.pv_line(board, bm);
+            out.push(SearchResult { bestmove: bm.map(|m| format!("{}", m)), score: Score::from_value(sc), nodes, depth: self.last_depth, seldepth: self.max_seldepth, pv, ..Default::default() }
+                .with_elapsed(t0.elapsed()));
+            if self.stopped() { break; }
+        }
+        self.root_moves = saved;
+        self.info_hook = hook;
+        // Shallow searches are not monotone across exclusions; report best first
+        out.sort_by_key(|r| -r.score);
+        out
+    }
+
+    fn report_iter(&self, board: &PlecoBoard, best: Option<PMove>, score: i32) {
+        if let Some(hook) = &self.info_hook {
+            let pv = self.pv_line(board, best);
+            hook(&SearchResult { bestmove: best.map(|m| format!("{}", m)), score: Score::from_value(score), nodes: self.nodes, depth: self.last_depth, seldepth: self.max_seldepth, bestmove_changes: self.bestmove_changes, pv, ..Default::default() }
+                .with_elapsed(self.search_start.elapsed()));
+        }
+    }
+
+    #[inline]
+    fn nnue_on(&self) -> bool { self.eval_mode == PlecoEvalMode::Nnue && self.nnue_quant.is_some() }
+
+    // Rebuild the accumulator for the search root
+    fn nnue_refresh(&mut self, board: &PlecoBoard) {
+        if self.eval_mode != PlecoEvalMode::Nnue { return; }
+        if let Some(qn) = self.nnue_quant.as_mut() { qn.refresh_from_indices(&active_indices_pleco(board)); }
+    }
+
+    // Helper-only pruning (extra LMR and null-move R, LMP/futility); not in safe mode
+    // or while converting a TB win
+    // Give LazyCoop/LazyHybrid helper `wid` an aspiration window `extra` wider than ours;
+    // under a non-zero seed it also takes the seeded profile's window, TT-move hoisting
+    // and move-order rotation, as LazyIndep helpers do
+    fn seed_helper(&self, w: &mut Self, wid: usize, extra: i32) {
+        w.aspiration_window_cp = self.aspiration_window_cp + extra;
+        if self.smp_seed == 0 { return; }
+        let p = HelperProfile::derive(self.smp_seed, wid);
+        (w.aspiration_window_cp, w.tt_first, w.order_offset) = (w.aspiration_window_cp + p.window_extra, p.tt_first, p.order_offset);
+    }
+
+    fn diversify_pruning(&self, w: &mut Self, lmr_aggr: i32) {
+        if self.smp_safe || self.converting.is_some() { return; }
+        (w.lmr_aggr, w.null_r_bonus, w.helper_mode, w.lmp) = (lmr_aggr, 1, true, self.lmp);
+    }
+
+    // Give a helper/worker searcher our eval and policy, draw scoring, pruning guards, SMP
+    // profile and TT generation (its searches do not start another), with its
+    // accumulator at `board`
+    fn attach_eval(&self, w: &mut Self, board: &PlecoBoard) {
+        w.eval_mode = self.eval_mode;
+        w.eval_hash = self.eval_hash.clone();
+        (w.king_danger, w.node_types, w.smp_safe) = (self.king_danger, self.node_types, self.smp_safe);
+        (w.contempt, w.draw_bias, w.root_side, w.path) = (self.contempt, self.draw_bias, self.root_side, self.path.clone());
+        w.converting = self.converting;
+        (w.use_futility, w.use_lmp, w.lmp, w.lmr_late_extra) = (self.use_futility, self.use_lmp, self.lmp, self.lmr_late_extra);
+        (w.nnue_small, w.small_net_only) = (self.nnue_small.clone(), self.small_net_only);
+        (w.policy, w.policy_weight) = (self.policy.clone(), self.policy_weight);
+        w.gen_fresh = true;
+        if !self.nnue_on() { return; }
+        // Copy our frame and step it to `board` rather than recomputing from scratch
+        if let Some(qn) = &self.nnue_quant {
+            let mut wq = qn.fork();
+            wq.push_indices(&active_indices_pleco(board));
+            w.nnue_quant = Some(wq);
+        }
+    }
+
+    #[inline]
+    fn make(&mut self, board: &mut PlecoBoard, m: PMove) {
+        board.apply_move(m);
+        if self.eval_mode == PlecoEvalMode::Nnue {
+            if let Some(qn) = self.nnue_quant.as_mut() { qn.push_indices(&active_indices_pleco(board)); }
+        }
+    }
+
+    #[inline]
+    fn unmake(&mut self, board: &mut PlecoBoard) {
+        board.undo_move();
+        if self.eval_mode == PlecoEvalMode::Nnue {
+            if let Some(qn) = self.nnue_quant.as_mut() { qn.pop(); }
+        }
+    }
+
+    /// Like `search_movetime`, packaged with depth, seldepth, time and nps.
+    pub fn search_result(&mut self, board: &mut PlecoBoard, millis: u64, depth: u32) -> SearchResult {
+        let t0 = Instant::now();
+        let (bm, sc, nodes) = self.search_movetime(board, millis, depth);
+        let pv = self.pv_line(board, bm);
+        SearchResult { bestmove: bm.map(|m| format!("{}", m)), score: Score::from_value(sc), nodes, depth: self.last_depth, seldepth: self.max_seldepth, bestmove_changes: self.bestmove_changes, pv, ..Default::default() }
+            .with_elapsed(t0.elapsed())
+    }
 
     pub fn search_movetime(&mut self, board: &mut PlecoBoard, millis: u64, depth: u32) -> (Option<PMove>, i32, u64) {
+        self.search_start = Instant::now();
+        self.begin_generation();
+        self.split_stats = SplitStats::default();
+        self.root_side = board.turn();
+        // Positions since the last irreversible move can still repeat
+        let since = self.game_history.len() - (board.rule_50().max(0) as usize).min(self.game_history.len());
+        self.path = self.game_history[since..].to_vec();
+        self.path.push(board.zobrist());
+        self.evals.clear();
+        self.root_prev.clear();
+        self.bestmove_changes = 0;
+        self.converting = if self.conversion { conversion::tb_winner_pleco(board) } else { None };
+        self.set_draw_bias(board, 0);
+        // Mate or stalemate at the root: nothing to iterate over
+        if board.generate_moves().is_empty() {
+            self.nodes = 0;
+            self.last_depth = 0;
+            self.max_seldepth = 0;
+            return (None, self.eval_terminal(board, 0), 0);
+        }
+        let lazy_ms = self.clock.map_or(millis, |c| (c.budget.soft.as_millis() as u64).max(1));
         match self.smp_mode {
-            SmpMode::LazyCoop if self.threads > 1 => return self.search_movetime_lazy_coop(board, millis, depth),
-            SmpMode::LazyIndep if self.threads > 1 => return self.search_movetime_lazy(board, millis, depth),
-            SmpMode::LazyHybrid if self.threads > 1 => return self.search_movetime_lazy_hybrid(board, millis, depth),
+            _ if self.root_moves.is_some() => {}
+            SmpMode::LazyCoop if self.threads > 1 => return self.search_movetime_lazy_coop(board, lazy_ms, depth),
+            SmpMode::LazyIndep if self.threads > 1 => return self.search_movetime_lazy(board, lazy_ms, depth),
+            SmpMode::LazyHybrid if self.threads > 1 => return self.search_movetime_lazy_hybrid(board, lazy_ms, depth),
             _ => {}
         }
         self.nodes = 0;
-        self.deadline = Some(Instant::now() + Duration::from_millis(millis));
+        self.deadline = (millis > 0).then(|| Instant::now() + Duration::from_millis(millis));
         self.abort = Some(Arc::new(std::sync::atomic::AtomicBool::new(false)));
         self.max_seldepth = 0;
+        self.nnue_refresh(board);
+        self.root_effort.clear();
+        self.guard_trips = 0;
+        let mut prev_iter_nodes = 0u64;
         let mut best: Option<PMove> = None; let mut best_score = -MATE_SCORE;
         let max_depth = if depth == 0 { 99 } else { depth };
         let mut last_score = 0;
         let mut last_iter_time = Duration::from_millis(0);
+        // Recent best-move changes, halved every iteration
+        let mut instability = 0f32;
+        let effort = TimeConfig { effort: self.tm_effort, ..TimeConfig::default() };
+        let mut effort_scale = 1.0f32;
+        let mut clock = self.clock.map(|c| (TimeManager::new(WallClock::start(), c.budget), c.config));
+        let mut held = self.hold_deadline();
         for d in 1..=max_depth {
-            self.tt.bump_generation();
+            if helper_skips(self.skip_worker, d) { continue; }
+            if d > 1 { self.set_draw_bias(board, last_score); }
             if self.tm_finish_one && d > 1 {
-                if let Some(dl) = self.deadline {
+                let reserve = self.tm_factor / effort_scale;
+                // MinDepth iterations start regardless; only the deadline itself stops them
+                if let Some(dl) = self.deadline.filter(|_| d > self.min_depth) {
                     let remaining = dl.saturating_duration_since(Instant::now());
-                    if last_iter_time > Duration::from_millis(0) && remaining < last_iter_time.mul_f32(self.tm_factor) { break; }
+                    if last_iter_time > Duration::from_millis(0) && remaining < last_iter_time.mul_f32(reserve) { break; }
                 }
+                if ((self.node_limit.saturating_sub(self.nodes)) as f32) < prev_iter_nodes as f32 * reserve { break; }
             }
-            let iter_start = Instant::now();
+            let (iter_start, iter_first_node) = (Instant::now(), self.nodes);
             let (bm, sc) = if self.use_aspiration && d > 1 {
-                let window = self.aspiration_window_cp.max(10);
-                let alpha = last_score - window;
-                let beta = last_score + window;
-                let (b1, s1) = self.root_iter_window(board, d, alpha, beta);
-                if s1 <= alpha || s1 >= beta { self.root_iter(board, d) } else { (b1, s1) }
+                let half = if self.adaptive_window { self.window_stats.window(self.aspiration_window_cp) } else { self.aspiration_window_cp.max(MIN_WINDOW) };
+                let mut window = Window::new(last_score, half);
+                let (alpha, beta) = (window.alpha, window.beta);
+                let trips = self.guard_trips;
+                let cap = (self.explosion_guard > 0 && d >= GUARD_MIN_DEPTH).then(|| prev_iter_nodes.saturating_mul(self.explosion_guard.into()).max(1));
+                let (mut bm, mut sc) = self.root_iter_window(board, d, alpha, beta, cap);
+                let first = sc;
+                // A fail low names no best move: settle it once with a full window under the same cap
+                if self.guard_trips != trips && first <= alpha { (bm, sc) = self.root_iter_window(board, d, -MATE_SCORE, MATE_SCORE, cap); }
+                // After a trip, re-searching the root would run into the same move
+                while self.guard_trips == trips && window.widen(sc) {
+                    (bm, sc) = if window.is_full() { self.root_iter(board, d) } else { self.root_iter_window(board, d, window.alpha, window.beta, None) };
+                }
+                self.window_stats.record(alpha, beta, first, last_score, sc);
+                (bm, sc)
             } else {
                 self.root_iter(board, d)
             };
+            if d > 1 && self.nodes >= self.node_limit { break; }
+            prev_iter_nodes = self.nodes - iter_first_node;
+            let changed = d > 1 && bm != best;
+            self.bestmove_changes += u32::from(changed);
+            instability = instability / 2.0 + f32::from(u8::from(changed));
             best = bm; best_score = sc; last_score = sc;
             self.last_depth = d;
+            self.report_iter(board, best, best_score);
             last_iter_time = iter_start.elapsed();
+            self.release_deadline(&mut held, d);
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            if self.stopped() || self.nodes >= self.node_limit { break; }
+            // A stable best move that took nearly all the root nodes ends the search early;
+            // spread effort widens the finish-one check above instead
+            if self.tm_effort > 0.0 && (millis > 0 || self.node_limit < u64::MAX) && d >= self.min_depth {
+                if let Some(share) = best.and_then(|m| best_move_share(&self.root_effort, &m)) {
+                    effort_scale = effort.effort_scale(share, instability);
+                    if effort_scale < 1.0 && self.budget_used(millis) >= effort_scale { break; }
+                }
+            }
+            if let Some((tm, cfg)) = clock.as_mut().filter(|_| d >= self.min_depth) {
+                // As in the cozy search: an unstable root gets more of the soft budget,
+                // a clear best move less, and no iteration starts that would pass the hard limit
+                tm.stretch_soft(1.0 + cfg.instability * instability);
+                if let Some(share) = best.and_then(|m| best_move_share(&self.root_effort, &m)) { tm.scale_soft_by_effort(cfg.effort_scale(share, instability)); }
+                if !tm.can_start_iteration(cfg.iteration_cost(prev_iter_nodes, last_iter_time), cfg.growth) { break; }
+            }
         }
+        self.release_deadline(&mut held, u32::MAX);
         (best, best_score, self.nodes)
     }
 
+    // The larger share of the movetime or the node limit used so far
+    fn budget_used(&self, millis: u64) -> f32 {
+        let time = if millis > 0 { self.search_start.elapsed().as_secs_f32() * 1000.0 / millis as f32 } else { 0.0 };
+        let nodes = if self.node_limit < u64::MAX { self.nodes as f32 / self.node_limit as f32 } else { 0.0 };
+        time.max(nodes)
+    }
+
+    // The movetime deadline is held back (`None`) until `min_depth` iterations are done;
+    // a clock's hard limit never is
+    fn hold_deadline(&mut self) -> Option<Instant> { if self.min_depth > 0 && self.clock.is_none() { self.deadline.take() } else { None } }
+    fn release_deadline(&mut self, held: &mut Option<Instant>, depth: u32) {
+        if depth >= self.min_depth { if let Some(dl) = held.take() { self.deadline = Some(dl); } }
+    }
+
     // Cooperative Lazy SMP: partition root move list across workers per iteration
     fn search_movetime_lazy_coop(&mut self, board: &mut PlecoBoard, millis: u64, depth: u32) -> (Option<PMove>, i32, u64) {
         self.nodes = 0;
@@ -102,8 +578,9 @@ impl PlecoSearcher {
         let max_depth = if depth == 0 { 99 } else { depth };
         let mut last_score = 0;
         let mut last_iter_time = Duration::from_millis(0);
+        let mut held = self.hold_deadline();
         for d in 1..=max_depth {
-            self.tt.bump_generation();
+            if d > 1 && best.is_some() { self.set_draw_bias(board, best_score); }
             if self.tm_finish_one && d > 1 {
                 if let Some(dl) = self.deadline {
                     let remaining = dl.saturating_duration_since(Instant::now());
@@ -114,8 +591,8 @@ impl PlecoSearcher {
             // Build and order root moves
             let mut ml: Vec<PMove> = board.generate_moves().iter().copied().collect();
             if ml.is_empty() { break; }
-            let tt_best = self.tt.get(board.zobrist()).and_then(|e| e.best);
-            self.order_moves(board, &mut ml, tt_best, 0);
+            let tt_best = self.tt_move(board);
+            self.order_moves(board, &mut ml, tt_best, 0, d);
             // Seed PV with first move (TT-best) to raise alpha early
             use std::sync::atomic::{AtomicI32, Ordering};
             let shared_tt = self.tt.clone();
@@ -123,15 +600,16 @@ impl PlecoSearcher {
             let mut b1 = board.clone(); b1.apply_move(pv);
             let mut seed = Self::default();
             seed.tt = shared_tt.clone();
-            seed.threads = 1; seed.use_killers = self.use_killers; seed.use_lmr = self.use_lmr; seed.use_nullmove = self.use_nullmove; seed.use_aspiration = self.use_aspiration; seed.aspiration_window_cp = self.aspiration_window_cp; seed.deadline = self.deadline; seed.smp_mode = SmpMode::Off;
-            let pv_sc = -seed.alphabeta(&mut b1, d.saturating_sub(1), -MATE_SCORE, MATE_SCORE, 1);
+            seed.threads = 1; seed.use_killers = self.use_killers; seed.use_lmr = self.use_lmr; seed.use_nullmove = self.use_nullmove; seed.use_aspiration = self.use_aspiration; seed.aspiration_window_cp = self.aspiration_window_cp; seed.deadline = self.deadline; seed.stop = self.stop.clone(); seed.smp_mode = SmpMode::Off;
+            self.attach_eval(&mut seed, &b1);
+            let pv_sc = -seed.alphabeta(&mut b1, d.saturating_sub(1), -MATE_SCORE, MATE_SCORE, 1, NodeType::Pv);
             self.nodes += seed.nodes; if seed.max_seldepth > self.max_seldepth { self.max_seldepth = seed.max_seldepth; }
             let alpha_shared = AtomicI32::new(pv_sc);
             // Dynamic chunking and stronger early cancellation
             let tails: Vec<PMove> = ml.into_iter().skip(1).collect();
             let abort_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
             let chunk = (tails.len() + self.threads - 1) / self.threads.max(1);
-            let results: Vec<(PMove, i32, u64, u32)> = tails.par_chunks(chunk.max(1)).flat_map(|chunk_moves| {
+            let results: Vec<(PMove, i32, u64, u32)> = tails.par_chunks(chunk.max(1)).enumerate().flat_map(|(ci, chunk_moves)| {
                 let mut out = Vec::with_capacity(chunk_moves.len());
                 for &m in chunk_moves {
                     if abort_flag.load(std::sync::atomic::Ordering::Relaxed) { break; }
@@ -139,9 +617,10 @@ impl PlecoSearcher {
                     let mut c = board.clone(); c.apply_move(m);
                     let mut w = Self::default();
                     w.tt = shared_tt.clone();
-                    w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; w.aspiration_window_cp = self.aspiration_window_cp + 10; w.deadline = self.deadline; w.tm_finish_one = self.tm_finish_one; w.tm_factor = self.tm_factor; w.smp_mode = SmpMode::Off;
+                    w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; self.seed_helper(&mut w, ci + 1, 10); w.deadline = self.deadline; w.stop = self.stop.clone(); w.tm_finish_one = self.tm_finish_one; w.tm_factor = self.tm_factor; w.smp_mode = SmpMode::Off;
+                    self.attach_eval(&mut w, &c);
                     let a = alpha_shared.load(Ordering::Relaxed);
-                    let sc = -w.alphabeta(&mut c, d.saturating_sub(1), -MATE_SCORE, -a, 1);
+                    let Some(sc) = guarded("coop", d, || format!("{} moves {}", board.fen(), m), || -w.alphabeta(&mut c, d.saturating_sub(1), -MATE_SCORE, -a, 1, NodeType::Cut)) else { continue; };
                     let mut cur = a;
                     while sc > cur {
                         match alpha_shared.compare_exchange(cur, sc, Ordering::Relaxed, Ordering::Relaxed) {
@@ -153,27 +632,46 @@ impl PlecoSearcher {
                 }
                 out
             }).collect();
-            // Reduce results
-            for (m, sc, n, sd) in results { self.nodes += n; if sc > best_score { best_score = sc; best = Some(m); } if sd > self.max_seldepth { self.max_seldepth = sd; } }
+            // Reduce results. The safe profile counts only this depth's scores, with the
+            // seeded PV move as a candidate; otherwise the best score so far carries over
+            let (mut it_best, mut it_score) = if self.smp_safe { (Some(pv), pv_sc) } else { (best, best_score) };
+            self.root_effort = std::iter::once((pv, seed.nodes)).chain(results.iter().map(|&(m, _, n, _)| (m, n))).collect();
+            for (m, sc, n, sd) in results {
+                self.nodes += n;
+                if sc > it_score { it_score = sc; it_best = Some(m); }
+                self.max_seldepth = self.max_seldepth.max(sd);
+            }
             let completed = !abort_flag.load(std::sync::atomic::Ordering::Relaxed) && self.deadline.map(|dl| Instant::now() < dl).unwrap_or(true);
-            if completed { self.last_depth = d; }
+            // Under the safe profile an interrupted depth only stands in when there is nothing better
+            if completed || best.is_none() || !self.smp_safe {
+                self.bestmove_changes += u32::from(d > 1 && it_best != best);
+                best = it_best; best_score = it_score;
+            }
+            if completed { self.last_depth = d; self.report_iter(board, best, best_score); }
             last_iter_time = iter_start.elapsed();
+            self.release_deadline(&mut held, d);
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            if self.stopped() { break; }
         }
+        self.release_deadline(&mut held, u32::MAX);
         (best, best_score, self.nodes)
     }
 
-    // Hybrid Lazy SMP: use independent helper to pre-warm TT at depth d+2, then LazyCoop to complete depth d
+    // Hybrid Lazy SMP: an independent helper pre-warms the TT a ply or two deeper with
+    // whatever time the next cooperative iteration is not predicted to need, then
+    // LazyCoop completes depth d.
     fn search_movetime_lazy_hybrid(&mut self, board: &mut PlecoBoard, millis: u64, depth: u32) -> (Option<PMove>, i32, u64) {
         self.nodes = 0;
-        self.deadline = Some(Instant::now() + Duration::from_millis(millis));
+        self.deadline = (millis > 0).then(|| Instant::now() + Duration::from_millis(millis));
         self.abort = Some(Arc::new(std::sync::atomic::AtomicBool::new(false)));
         self.max_seldepth = 0;
+        self.hybrid_stats = HybridStats::default();
         let mut best: Option<PMove> = None; let mut best_score = -MATE_SCORE;
         let max_depth = if depth == 0 { 99 } else { depth };
         let mut last_iter_time = Duration::from_millis(0);
+        let mut last_coop_time = Duration::from_millis(0);
+        let mut held = self.hold_deadline();
         for d in 1..=max_depth {
-            self.tt.bump_generation();
             if self.tm_finish_one && d > 1 {
                 if let Some(dl) = self.deadline {
                     let remaining = dl.saturating_duration_since(Instant::now());
@@ -181,25 +679,63 @@ impl PlecoSearcher {
                 }
             }
             let iter_start = Instant::now();
-            // Pre-warm TT via an independent helper search at depth d+2 for a small time slice
-            if let Some(dl) = self.deadline {
-                let remaining = dl.saturating_duration_since(Instant::now());
-                let slice = (remaining.as_millis() as u64 / 4).max(10);
+            // Pre-warm budget: a third of the time not reserved for this iteration's coop
+            // search (predicted from the last one), capped at a quarter of what remains and
+            // at twice the reserve so cheap early iterations skip pre-warm entirely
+            let mut warmed = false;
+            let reserve = last_coop_time.mul_f32(self.tm_factor);
+            let slice = match self.deadline {
+                Some(dl) if d > 1 => {
+                    let remaining = dl.saturating_duration_since(Instant::now());
+                    (remaining.saturating_sub(reserve).as_millis() as u64 / 3)
+                        .min(remaining.as_millis() as u64 / 4)
+                        .min(reserve.as_millis() as u64 * 2)
+                }
+                _ => 0,
+            };
+            if slice >= 5 {
+                let warm_depth = if Duration::from_millis(slice) >= reserve { d + 2 } else { d + 1 };
                 let shared_tt = self.tt.clone();
                 let mut helper = Self::default();
                 helper.tt = shared_tt.clone();
-                helper.threads = 1; helper.use_killers = self.use_killers; helper.use_lmr = true; helper.use_nullmove = true; helper.use_aspiration = true; helper.aspiration_window_cp = self.aspiration_window_cp + 20; helper.deadline = Some(Instant::now() + Duration::from_millis(slice)); helper.tm_finish_one = false; helper.tm_factor = self.tm_factor; helper.smp_mode = SmpMode::Off; helper.lmr_aggr = 1; helper.null_r_bonus = 1; helper.helper_mode = true;
-                let _ = helper.search_movetime(&mut board.clone(), slice, d.saturating_add(2));
+                helper.threads = 1; helper.use_killers = self.use_killers; self.seed_helper(&mut helper, 1, 20); helper.deadline = Some(Instant::now() + Duration::from_millis(slice)); helper.stop = self.stop.clone(); helper.tm_finish_one = false; helper.tm_factor = self.tm_factor; helper.smp_mode = SmpMode::Off;
+                // The pre-warm helper prunes at full strength unless the profile is safe
+                let full = !self.smp_safe;
+                (helper.use_lmr, helper.use_nullmove, helper.use_aspiration) = (self.use_lmr || full, self.use_nullmove || full, self.use_aspiration || full);
+                self.diversify_pruning(&mut helper, 1);
+                self.attach_eval(&mut helper, board);
+                let warm_start = Instant::now();
+                let _ = helper.search_movetime(&mut board.clone(), slice, warm_depth);
                 self.nodes += helper.nodes;
+                self.hybrid_stats.prewarm_runs += 1;
+                self.hybrid_stats.prewarm_ms += warm_start.elapsed().as_millis() as u64;
+                self.hybrid_stats.prewarm_nodes += helper.nodes;
+                warmed = true;
             }
             // Complete current depth cooperatively
+            let coop_start = Instant::now();
+            let (p0, h0) = self.tt.probe_stats();
+            // The inner coop search re-runs depths 1..=d; only this loop reports
+            let (hook, changes) = (self.info_hook.take(), self.bestmove_changes);
             let (bm, sc, nodes) = self.search_movetime_lazy_coop(board, 0, d); // uses existing deadline
+            (self.info_hook, self.bestmove_changes) = (hook, changes);
+            let (p1, h1) = self.tt.probe_stats();
+            last_coop_time = coop_start.elapsed();
+            if warmed { self.hybrid_stats.warm_probes += p1 - p0; self.hybrid_stats.warm_hits += h1 - h0; }
+            else { self.hybrid_stats.cold_probes += p1 - p0; self.hybrid_stats.cold_hits += h1 - h0; }
             self.nodes += nodes;
-            if let Some(m) = bm { best = Some(m); best_score = sc; }
+            if let Some(m) = bm {
+                self.bestmove_changes += u32::from(d > 1 && best != Some(m));
+                best = Some(m); best_score = sc;
+            }
             self.last_depth = d;
+            self.report_iter(board, best, best_score);
             last_iter_time = iter_start.elapsed();
+            self.release_deadline(&mut held, d);
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            if self.stopped() { break; }
         }
+        self.release_deadline(&mut held, u32::MAX);
         (best, best_score, self.nodes)
     }
 
@@ -207,8 +743,8 @@ impl PlecoSearcher {
         let shared_tt = self.tt.clone();
         let threads = self.threads;
         let max_depth = if depth == 0 { 99 } else { depth };
-        let deadline = Some(Instant::now() + Duration::from_millis(millis));
-        let results: Vec<(usize, Option<PMove>, i32, u64, u32, u32)> = (0..threads).into_par_iter().map(|wid| {
+        let deadline = (millis > 0).then(|| Instant::now() + Duration::from_millis(millis));
+        let results: Vec<(usize, Option<PMove>, i32, u64, u32, u32, Vec<(PMove, u64)>, u32)> = (0..threads).into_par_iter().filter_map(|wid| guarded("lazy", max_depth, || board.fen(), || {
             let mut w = Self::default();
             w.tt = shared_tt.clone();
             w.threads = 1;
@@ -217,14 +753,33 @@ impl PlecoSearcher {
             w.use_nullmove = self.use_nullmove;
             w.use_aspiration = self.use_aspiration;
             // Diversify aspiration window, LMR, null move, and ordering
-            w.aspiration_window_cp = self.aspiration_window_cp + (wid as i32 % 3) * 20;
-            if wid > 0 { w.lmr_aggr = 1 + ((wid as i32) % 2); w.null_r_bonus = 1; w.tt_first = (wid % 2) == 0; w.order_offset = wid as usize; w.helper_mode = true; }
-            w.deadline = deadline;
+            let p = HelperProfile::derive(self.smp_seed, wid);
+            w.aspiration_window_cp = self.aspiration_window_cp + p.window_extra;
+            if wid > 0 {
+                w.tt_first = p.tt_first;
+                match self.helper_scheme {
+                    HelperScheme::Rotate => w.order_offset = p.order_offset,
+                    HelperScheme::Skip => w.skip_worker = wid,
+                }
+                self.diversify_pruning(&mut w, p.lmr_aggr);
+            }
+            (w.deadline, w.stop) = (deadline, self.stop.clone());
+            w.min_depth = self.min_depth;
             w.smp_mode = SmpMode::Off;
+            self.attach_eval(&mut w, board);
             let mut b = board.clone();
             let (bm, sc, nodes) = w.search_movetime(&mut b, millis, max_depth);
-            (wid, bm, sc, nodes, w.last_depth(), w.last_seldepth())
-        }).collect();
+            (wid, bm, sc, nodes, w.last_depth(), w.last_seldepth(), std::mem::take(&mut w.root_effort), w.bestmove_changes)
+        })).collect();
+        if results.is_empty() {
+            // Every worker died: fall back to a plain single-threaded search
+            let mode = self.smp_mode;
+            self.smp_mode = SmpMode::Off;
+            self.gen_fresh = true;
+            let r = self.search_movetime(board, millis, depth);
+            self.smp_mode = mode;
+            return r;
+        }
         // Choose the deepest worker; break ties preferring worker 0, then higher score
         let mut best = results[0].clone();
         for r in &results {
@@ -232,29 +787,76 @@ impl PlecoSearcher {
         }
         self.nodes = results.iter().map(|r| r.3).sum();
         self.last_depth = best.4; self.max_seldepth = results.iter().map(|r| r.5).max().unwrap_or(0);
+        (self.root_effort, self.bestmove_changes) = (best.6, best.7);
         (best.1, best.2, self.nodes)
     }
 
     #[inline]
-    fn killer_bonus(&self, ply: usize, m: PMove) -> i32 {
-        if !self.use_killers { return 0; }
-        let p = ply.min(self.killers.len()-1);
-        let slot = &self.killers[p];
-        if slot[0] == Some(m) { 50 } else if slot[1] == Some(m) { 30 } else { 0 }
+    // Killers were stored by sibling positions: keep the ones legal here
+    fn legal_killers(&self, board: &PlecoBoard, ply: usize) -> [Option<PMove>; 2] {
+        if !self.use_killers { return [None, None]; }
+        let slot = self.killers[ply.min(self.killers.len()-1)];
+        slot.map(|k| k.filter(|&m| self.tt.checks().killer(board.pseudo_legal_move(m) && board.legal_move(m))))
+    }
+
+    /// The reply the TT expects after `best` (UCI `bestmove ... ponder`), if legal.
+    pub fn ponder_move(&self, board: &mut PlecoBoard, best: PMove) -> Option<PMove> {
+        if !(board.pseudo_legal_move(best) && board.legal_move(best)) { return None; }
+        board.apply_move(best);
+        let reply = self.tt.get(board.zobrist()).and_then(|e| e.best).filter(|&m| board.pseudo_legal_move(m) && board.legal_move(m));
+        board.undo_move();
+        reply
     }
 
-    fn order_moves(&self, board: &PlecoBoard, moves: &mut Vec<PMove>, tt_best: Option<PMove>, ply: usize) {
+    /// Principal variation from `best`, walked through the TT as in the cozy
+    /// `Searcher::tt_pv`: legal moves only, up to `len` or a repeated position.
+    pub fn tt_pv(&self, board: &PlecoBoard, best: PMove, len: u32) -> Vec<PMove> {
+        let (mut b, mut pv) = (board.clone(), Vec::new());
+        let mut seen = vec![b.zobrist()];
+        let mut next = Some(best);
+        while let Some(m) = next.filter(|&m| pv.len() < len as usize && b.pseudo_legal_move(m) && b.legal_move(m)) {
+            b.apply_move(m);
+            pv.push(m);
+            if seen.contains(&b.zobrist()) { break; }
+            seen.push(b.zobrist());
+            next = self.tt.get(b.zobrist()).and_then(|e| e.best);
+        }
+        pv
+    }
+
+    // `tt_pv` to the last completed depth, in UCI notation
+    fn pv_line(&self, board: &PlecoBoard, best: Option<PMove>) -> Vec<String> {
+        best.map(|m| self.tt_pv(board, m, self.last_depth.max(1)).iter().map(|m| format!("{}", m)).collect()).unwrap_or_default()
+    }
+
+    // The TT move for this position, if it is legal here: an aliased or corrupted
+    // entry can carry a move from another position
+    fn tt_move(&self, board: &PlecoBoard) -> Option<PMove> {
+        self.tt.get(board.zobrist()).and_then(|e| e.best)
+            .filter(|&m| self.tt.checks().tt_move(board.pseudo_legal_move(m) && board.legal_move(m)))
+    }
+
+    fn order_moves(&self, board: &PlecoBoard, moves: &mut Vec<PMove>, tt_best: Option<PMove>, ply: usize, depth: u32) {
         if self.tt_first {
             if let Some(ttm) = tt_best { if let Some(pos) = moves.iter().position(|&x| x == ttm) { let mv = moves.remove(pos); moves.insert(0, mv); } }
         }
         if moves.len() <= 1 { return; }
-        moves[1..].sort_by_key(|&m| {
+        let killers = self.legal_killers(board, ply);
+        // Until history has something on this node's moves, the policy net ranks the quiets
+        let policy = self.policy.as_deref().filter(|_| self.policy_weight > 0 && depth >= POLICY_MIN_DEPTH && moves.iter().all(|&m| self.history_score(m) == 0))
+            .map(|net| net.node_pleco(board, moves, self.policy_weight));
+        // Keys, policy bonus included, are computed once per move before sorting
+        let key = |m: PMove| {
             let cap = if m.is_capture() { 1 } else { 0 };
             let mvv = if cap == 1 { self.mvv_lva(board, m) } else { 0 };
             let hist = self.history_score(m);
-            let kb = self.killer_bonus(ply, m);
-            -(cap * 10 + kb + hist + mvv)
-        });
+            let kb = if killers[0] == Some(m) { 50 } else if killers[1] == Some(m) { 30 } else { 0 };
+            let pol = if cap == 0 { policy.as_ref().map_or(0, |p| p.bonus(m.get_src().0 as usize, m.get_dest().0 as usize)) } else { 0 };
+            -(cap * 10 + kb + hist + mvv + pol)
+        };
+        let mut keyed: Vec<(i32, PMove)> = moves[1..].iter().map(|&m| (key(m), m)).collect();
+        keyed.sort_by_key(|&(k, _)| k);
+        for (slot, (_, m)) in moves[1..].iter_mut().zip(keyed) { *slot = m; }
         // Diversification: rotate tail by offset
         if self.order_offset > 0 && moves.len() > 2 {
             let tail = &mut moves[1..];
@@ -301,15 +903,15 @@ impl PlecoSearcher {
     }
 
     fn root_iter(&mut self, board: &mut PlecoBoard, depth: u32) -> (Option<PMove>, i32) {
-        self.root_iter_window(board, depth, -MATE_SCORE, MATE_SCORE)
+        self.root_iter_window(board, depth, -MATE_SCORE, MATE_SCORE, None)
     }
 
-    fn root_iter_window(&mut self, board: &mut PlecoBoard, depth: u32, alpha0: i32, beta: i32) -> (Option<PMove>, i32) {
+    // `cap`: nodes a serially searched root move may take (`set_explosion_guard`)
+    fn root_iter_window(&mut self, board: &mut PlecoBoard, depth: u32, alpha0: i32, beta: i32, cap: Option<u64>) -> (Option<PMove>, i32) {
         let mut alpha = alpha0;
-        let mut ml: Vec<PMove> = board.generate_moves().iter().copied().collect();
-        if ml.is_empty() { return (None, self.eval_terminal(board)); }
-        let tt_best = self.tt.get(board.zobrist()).and_then(|e| e.best);
-        self.order_moves(board, &mut ml, tt_best, 0);
+        let mut ml = self.root_move_list(board);
+        if ml.is_empty() { return (None, self.eval_terminal(board, 0)); }
+        self.order_root_moves(board, &mut ml, depth);
         // Root SMP split (in-tree SMP only; split only when heavy and time allows)
         if self.smp_mode == SmpMode::InTree && self.threads > 1 {
             let heavy = depth >= 5 && ml.len() >= 16;
@@ -320,21 +922,24 @@ impl PlecoSearcher {
             let first = ml[0];
             let mut b1 = board.clone(); b1.apply_move(first);
             let mut seed = Self { tt: shared_tt.clone(), ..Self::default() };
-            seed.threads = 1; seed.use_killers = self.use_killers; seed.use_lmr = self.use_lmr; seed.use_nullmove = self.use_nullmove; seed.use_aspiration = self.use_aspiration; seed.aspiration_window_cp = self.aspiration_window_cp; seed.deadline = self.deadline; seed.smp_mode = SmpMode::Off;
+            seed.threads = 1; seed.use_killers = self.use_killers; seed.use_lmr = self.use_lmr; seed.use_nullmove = self.use_nullmove; seed.use_aspiration = self.use_aspiration; seed.aspiration_window_cp = self.aspiration_window_cp; seed.deadline = self.deadline; seed.stop = self.stop.clone(); seed.smp_mode = SmpMode::Off;
             let abort_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
             seed.abort = Some(abort_flag.clone());
-            let mut best_sc = -seed.alphabeta(&mut b1, depth - 1, -beta, -alpha, 1);
+            self.attach_eval(&mut seed, &b1);
+            let mut best_sc = -seed.alphabeta(&mut b1, depth - 1, -beta, -alpha, 1, NodeType::Pv);
             self.nodes += seed.nodes;
+            let mut effort = vec![(first, seed.nodes)];
             let mut best = first;
             let tails: Vec<PMove> = ml.into_iter().skip(1).collect();
             use std::sync::atomic::{AtomicI32, Ordering};
             let alpha_shared = AtomicI32::new(best_sc);
-            let results: Vec<(PMove, i32, u64)> = tails.par_iter().map(|&m| {
+            let results: Vec<(PMove, i32, u64)> = tails.par_iter().filter_map(|&m| guarded("root", depth, || format!("{} moves {}", board.fen(), m), || {
                 let mut c = board.clone(); c.apply_move(m);
                 let mut w = Self { tt: shared_tt.clone(), ..Self::default() };
-                w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; w.aspiration_window_cp = self.aspiration_window_cp; w.deadline = self.deadline; w.abort = Some(abort_flag.clone()); w.smp_mode = SmpMode::Off;
+                w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; w.aspiration_window_cp = self.aspiration_window_cp; w.deadline = self.deadline; w.stop = self.stop.clone(); w.abort = Some(abort_flag.clone()); w.smp_mode = SmpMode::Off;
+                self.attach_eval(&mut w, &c);
                 let a = alpha_shared.load(Ordering::Relaxed);
-                let score = -w.alphabeta(&mut c, depth - 1, -beta, -a, 1);
+                let score = -w.alphabeta(&mut c, depth - 1, -beta, -a, 1, NodeType::Cut);
                 // update alpha
                 let mut cur = a;
                 while score > cur {
@@ -345,51 +950,127 @@ impl PlecoSearcher {
                 }
                 if score >= beta { abort_flag.store(true, Ordering::Relaxed); }
                 (m, score, w.nodes)
-            }).collect();
-            for (m, s, n) in results { self.nodes += n; if s > best_sc { best_sc = s; best = m; } }
-            // Store root exact
-            self.tt.put(TtEntry { key: board.zobrist(), depth, score: best_sc, best: Some(best), bound: TtBound::Exact, gen: 0 });
+            })).collect();
+            self.root_prev = std::iter::once((first, best_sc)).chain(results.iter().map(|&(m, s, _)| (m, s))).collect();
+            for (m, s, n) in results { self.nodes += n; effort.push((m, n)); if s > best_sc { best_sc = s; best = m; } }
+            self.root_effort = effort;
+            // Store root exact (a restricted root's best is not the position's)
+            if self.root_moves.is_none() { self.tt.put(TtEntry { key: board.zobrist(), depth, score: Score::from_value(best_sc), best: Some(best), bound: TtBound::Exact, gen: 0 }); }
             return (Some(best), best_sc);
             }
         }
         // Serial
         let mut best: Option<PMove> = None; let mut best_sc = -MATE_SCORE;
-        for m in ml.iter() {
-            board.apply_move(*m);
-            let sc = -self.alphabeta(board, depth.saturating_sub(1), -beta, -alpha, 1);
-            board.undo_move();
+        self.root_effort.clear();
+        let mut scores = Vec::with_capacity(ml.len());
+        for (i, m) in ml.iter().enumerate() {
+            self.make(board, *m);
+            let before = self.nodes;
+            self.node_cap = cap.map_or(u64::MAX, |c| before.saturating_add(c));
+            let mut sc = -self.alphabeta(board, depth.saturating_sub(1), -beta, -alpha, 1, NodeType::Pv.child(i));
+            if self.nodes >= self.node_cap {
+                // A runaway move: settle it a ply shallower with a full window
+                self.node_cap = u64::MAX;
+                self.guard_trips += 1;
+                sc = -self.alphabeta(board, depth.saturating_sub(2), -MATE_SCORE, MATE_SCORE, 1, NodeType::Pv.child(i));
+            }
+            self.node_cap = u64::MAX;
+            self.unmake(board);
+            self.root_effort.push((*m, self.nodes - before));
+            scores.push((*m, sc));
             if sc > best_sc { best_sc = sc; best = Some(*m); }
             if sc > alpha { alpha = sc; }
             if let Some(dl) = self.deadline { if Instant::now() >= dl { break; } }
+            if self.stopped() || self.nodes >= self.node_limit { break; }
         }
+        self.root_prev = scores;
         (best, best_sc)
     }
 
-    // Evaluate all legal root moves at a fixed depth and return scores (higher is better)
+    // Root moves in the usual order, then by the last iteration's scores, best first,
+    // with the TT move kept in front. Helpers rotating their move order keep the rotation
+    fn order_root_moves(&self, board: &PlecoBoard, ml: &mut Vec<PMove>, depth: u32) {
+        let tt_best = self.tt_move(board);
+        self.order_moves(board, ml, tt_best, 0, depth);
+        if self.order_offset > 0 || self.root_prev.is_empty() { return; }
+        let prev = |m: &PMove| self.root_prev.iter().find(|(pm, _)| pm == m).map_or(i32::MIN, |&(_, s)| s);
+        ml.sort_by_cached_key(|m| (Some(*m) != tt_best, std::cmp::Reverse(prev(m))));
+    }
+
+    // Evaluate the root moves `searchmoves` allows at a fixed depth and return scores (higher is better)
     pub fn score_root_moves(&mut self, board: &mut PlecoBoard, depth: u32) -> Vec<(PMove, i32)> {
         let mut out: Vec<(PMove, i32)> = Vec::new();
-        let mut ml: Vec<PMove> = board.generate_moves().iter().copied().collect();
+        let mut ml = self.root_move_list(board);
         if ml.is_empty() { return out; }
-        let tt_best = self.tt.get(board.zobrist()).and_then(|e| e.best);
-        self.order_moves(board, &mut ml, tt_best, 0);
+        self.begin_generation();
+        self.nnue_refresh(board);
+        let tt_best = self.tt_move(board);
+        self.order_moves(board, &mut ml, tt_best, 0, depth);
         for &m in ml.iter() {
-            board.apply_move(m);
-            let sc = -self.alphabeta(board, depth.saturating_sub(1), -MATE_SCORE, MATE_SCORE, 1);
-            board.undo_move();
+            self.make(board, m);
+            let sc = -self.alphabeta(board, depth.saturating_sub(1), -MATE_SCORE, MATE_SCORE, 1, NodeType::Pv);
+            self.unmake(board);
             out.push((m, sc));
         }
         out.sort_by_key(|&(_, sc)| -sc);
         out
     }
 
-    fn alphabeta(&mut self, board: &mut PlecoBoard, depth: u32, mut alpha: i32, beta: i32, ply: u32) -> i32 {
+    fn alphabeta(&mut self, board: &mut PlecoBoard, depth: u32, alpha: i32, beta: i32, ply: u32, node: NodeType) -> i32 {
+        // Draws by rule along the searched line; the root itself is always searched
+        if ply > 0 && (board.rule_50() >= 100 || self.repeated(board) || (board.count_all_pieces() <= 4 && MaterialSig::from_pleco(board).is_insufficient())) {
+            self.nodes += 1;
+            return self.draw_score(board);
+        }
+        self.path.push(board.zobrist());
+        let sc = self.alphabeta_node(board, depth, alpha, beta, ply, node);
+        self.path.pop();
+        sc
+    }
+
+    // A search cut short by the deadline or a sibling's cutoff returns static evals;
+    // the safe profile keeps those out of the shared table
+    fn tt_store(&self, e: TtEntry) {
+        if (self.smp_safe && self.interrupted()) || self.nodes >= self.node_cap.min(self.node_limit) { return; }
+        self.tt.put(e);
+    }
+
+    fn interrupted(&self) -> bool {
+        self.deadline.is_some_and(|dl| Instant::now() >= dl) || self.stopped()
+            || self.abort.as_ref().is_some_and(|f| f.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    // The position occurred earlier on the searched line, with the same side to move
+    fn repeated(&self, board: &PlecoBoard) -> bool {
+        let key = board.zobrist();
+        let window = (board.rule_50().max(0) as usize).min(self.path.len());
+        self.path[self.path.len() - window..].iter().rev().skip(1).step_by(2).any(|&k| k == key)
+    }
+
+    fn draw_score(&self, board: &PlecoBoard) -> i32 { self.draw_bias.draw_score(board.turn() == self.root_side) }
+
+    // Contempt for the next iteration from the engine's latest root score
+    fn set_draw_bias(&mut self, board: &PlecoBoard, root_cp: i32) {
+        // A TB win is converted without contempt
+        if self.converting.is_some() { self.draw_bias = DrawBias::default(); return; }
+        self.draw_bias = self.contempt.bias(root_cp, MaterialSig::from_pleco(board).phase());
+    }
+
+    fn alphabeta_node(&mut self, board: &mut PlecoBoard, mut depth: u32, mut alpha: i32, beta: i32, ply: u32, node: NodeType) -> i32 {
         self.nodes += 1;
         if ply > self.max_seldepth { self.max_seldepth = ply; }
+        if ply as usize >= MAX_PLY || self.nodes >= self.node_cap.min(self.node_limit) { return self.eval(board); }
         if let Some(dl) = self.deadline { if Instant::now() >= dl { return self.eval(board); } }
+        if self.stopped() { return self.eval(board); }
         if let Some(ref f) = self.abort { if f.load(std::sync::atomic::Ordering::Relaxed) { return self.eval(board); } }
         if depth == 0 { return self.qsearch(board, alpha, beta, ply); }
-        // Null-move pruning
-        if self.use_nullmove && depth >= 3 && !board.in_check() {
+        // Before null move, whose subtree compares against this ply
+        let improving = (self.helper_mode || self.use_lmp) && self.improving(board, ply);
+        // Don't let pruning cut short a strong attack on the enemy king
+        let danger = self.king_danger && depth >= 3 && (self.use_nullmove || self.use_lmr || self.helper_mode || self.use_lmp)
+            && !board.in_check() && KingAttack::from_pleco(board, board.turn()).is_dangerous();
+        // Null-move pruning; an expected all node would not fail high anyway
+        if self.use_nullmove && self.converting.is_none() && depth >= 3 && !board.in_check() && !danger && !(self.node_types && node == NodeType::All) {
             let mut nb = board.clone();
             // Pleco supports null moves via apply_null_move/undo_null_move if available
             let did_null = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe { nb.apply_null_move(); })).is_ok();
@@ -397,88 +1078,104 @@ impl PlecoSearcher {
                 let mut r = 2 + (depth / 4) as u32;
                 if self.null_r_bonus > 0 { r = r.saturating_add(self.null_r_bonus as u32); }
                 if r >= depth { r = depth - 1; }
-                let sc = -self.alphabeta(&mut nb, depth - 1 - r, -beta, -beta + 1, ply + 1);
-                if sc >= beta { return sc; }
+                // Nothing before a pass can repeat after it
+                let line = std::mem::take(&mut self.path);
+                let sc = -self.alphabeta(&mut nb, depth - 1 - r, -beta, -beta + 1, ply + 1, NodeType::All);
+                self.path = line;
+                // A mate found after passing is not a proven mate
+                if sc >= beta { return if sc > MATE_BOUND { beta } else { sc }; }
             }
         }
         // TT probe
         if let Some(e) = self.tt.get(board.zobrist()) {
-            if e.depth >= depth { match e.bound { TtBound::Exact => return e.score, TtBound::Lower => if e.score >= beta { return e.score; }, TtBound::Upper => if e.score <= alpha { return e.score; } } }
+            let sc = e.score.from_tt(ply as i32).value();
+            if e.depth >= depth { match e.bound { TtBound::Exact => return sc, TtBound::Lower => if sc >= beta { return sc; }, TtBound::Upper => if sc <= alpha { return sc; } } }
         }
         let mut ml: Vec<PMove> = board.generate_moves().iter().copied().collect();
-        if ml.is_empty() { return self.eval_terminal(board); }
+        if ml.is_empty() { return self.eval_terminal(board, ply); }
         // Internal Iterative Deepening: if no TT move and depth is sufficient, do a shallow search to fill TT
-        let mut tt_best = self.tt.get(board.zobrist()).and_then(|e| e.best);
+        let mut tt_best = self.tt_move(board);
         if tt_best.is_none() && depth >= 3 && ply <= 2 {
-            let _ = self.alphabeta(board, depth - 2, alpha, beta, ply);
-            tt_best = self.tt.get(board.zobrist()).and_then(|e| e.best);
+            let _ = self.alphabeta(board, depth - 2, alpha, beta, ply, node);
+            tt_best = self.tt_move(board);
+        } else if tt_best.is_none() && self.node_types && node != NodeType::All && depth >= IIR_DEPTH {
+            // Deeper down, reduce instead: the next iteration finds a TT move here
+            depth -= 1;
         }
-        self.order_moves(board, &mut ml, tt_best, (self.killers.len()-1).min(depth as usize));
-        // In-tree split (jamboree-lite): PV seed + parallel tail
-        if self.threads > 1 && depth >= 3 && ml.len() >= 12 {
-            let shared_tt = self.tt.clone();
+        self.order_moves(board, &mut ml, tt_best, (self.killers.len()-1).min(depth as usize), depth);
+        let split_here = self.split.splits(self.threads, depth, ml.len());
+        if split_here && self.split.policy == SplitPolicy::Jamboree {
             // PV seed
             let first = ml[0];
             let mut b1 = board.clone(); b1.apply_move(first);
-            let mut seed = Self { tt: shared_tt.clone(), ..Self::default() };
-            seed.threads = 1; seed.use_killers = self.use_killers; seed.use_lmr = self.use_lmr; seed.use_nullmove = self.use_nullmove; seed.use_aspiration = self.use_aspiration; seed.aspiration_window_cp = self.aspiration_window_cp; seed.deadline = self.deadline;
-            let mut best = -seed.alphabeta(&mut b1, depth - 1, -beta, -alpha, ply + 1);
+            let mut seed = self.split_worker(&b1);
+            // The first move's extension, as on the serial path
+            let extend = u32::from(depth >= 5 && ply < depth);
+            let mut best = -seed.alphabeta(&mut b1, depth - 1 + extend, -beta, -alpha, ply + 1, node.child(0));
             self.nodes += seed.nodes;
             let mut best_move_local: Option<PMove> = Some(first);
-            use std::sync::atomic::{AtomicI32, Ordering};
-            let alpha_shared = AtomicI32::new(best);
-            let tails: Vec<PMove> = ml.into_iter().skip(1).collect();
-            let abort_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
-            let results: Vec<(PMove, i32, u64)> = tails.par_iter().map(|&m| {
-                let mut c = board.clone(); c.apply_move(m);
-                let mut w = Self { tt: shared_tt.clone(), ..Self::default() };
-                w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; w.aspiration_window_cp = self.aspiration_window_cp; w.deadline = self.deadline; w.abort = Some(abort_flag.clone());
-                let a = alpha_shared.load(Ordering::Relaxed);
-                let sc = -w.alphabeta(&mut c, depth - 1, -beta, -a, ply + 1);
-                // update alpha
-                let mut cur = a;
-                while sc > cur {
-                    match alpha_shared.compare_exchange(cur, sc, Ordering::Relaxed, Ordering::Relaxed) {
-                        Ok(_) => break,
-                        Err(obs) => { if obs >= sc { break; } cur = obs; }
-                    }
-                }
-                if sc >= beta { abort_flag.store(true, std::sync::atomic::Ordering::Relaxed); }
-                (m, sc, w.nodes)
-            }).collect();
-            for (m, s, n) in results { self.nodes += n; if s > best { best = s; best_move_local = Some(m); } }
-            // Store
-            self.tt.put(TtEntry { key: board.zobrist(), depth, score: best, best: best_move_local, bound: TtBound::Exact, gen: 0 });
+            let eldest_cut = best >= beta;
+            if let Some((m, s)) = self.search_younger(board, &ml[1..], depth, best, beta, ply, eldest_cut) {
+                if s > best { best = s; best_move_local = Some(m); }
+            }
+            let bound = if best <= alpha { TtBound::Upper } else if best >= beta { TtBound::Lower } else { TtBound::Exact };
+            self.tt_store(TtEntry { key: board.zobrist(), depth, score: Score::from_value(best).to_tt(ply as i32), best: best_move_local, bound, gen: 0 });
             return best;
         }
 
+        // First-class pruning spares PV nodes and a TB conversion; helpers prune everywhere
+        let plain = self.converting.is_none() && beta - alpha <= 1;
+        let lmp = self.helper_mode || (self.use_lmp && plain);
+        let futility = self.helper_mode || (self.use_futility && plain && alpha.abs() < MATE_BOUND);
         let mut bestmove: Option<PMove> = None;
         for (i, m) in ml.iter().enumerate() {
-            // Helper-only pruning: Late Move Pruning (LMP) and Futility for quiets at small depth
+            if i == 1 && split_here {
+                // Young brothers: the eldest did not cut off, search the rest in parallel
+                if let Some((m, sc)) = self.search_younger(board, &ml[1..], depth, alpha, beta, ply, false) {
+                    if sc >= beta {
+                        self.tt_store(TtEntry { key: board.zobrist(), depth, score: Score::from_value(sc).to_tt(ply as i32), best: Some(m), bound: TtBound::Lower, gen: 0 });
+                        return beta;
+                    }
+                    if sc > alpha { alpha = sc; bestmove = Some(m); }
+                }
+                break;
+            }
+            // Late Move Pruning (LMP) and Futility for quiets at small depth.
+            // Killers are kept: they refuted a sibling, so they are the likely tactics here
             let is_cap = m.is_capture();
-            if self.helper_mode && !is_cap && depth <= 3 && (i >= (if depth >= 3 { 6 } else if depth == 2 { 8 } else { 10 })) && !board.in_check() {
+            if lmp && !is_cap && !danger && !board.in_check() && self.lmp.prunes(depth, improving, i) && !self.is_killer(*m, depth) {
                 continue;
             }
-            if self.helper_mode && !is_cap && depth <= 2 && !board.in_check() {
+            if futility && !is_cap && depth <= 2 && !board.in_check() && (i > 0 || self.helper_mode) {
                 let stand = self.eval(board);
                 let margin = 100 * depth as i32;
                 if stand + margin <= alpha { continue; }
             }
-            board.apply_move(*m);
+            self.make(board, *m);
             // Singular-like extension: extend the first move a bit at deeper depths
-            let extend = if i == 0 && depth >= 5 { 1 } else { 0 };
-            let sc = if self.use_lmr && depth >= 3 && !m.is_capture() && i >= 3 && extend == 0 {
+            // (bounded by ply, otherwise the PV line never loses depth and recursion runs away)
+            let extend = if i == 0 && depth >= 5 && ply < depth { 1 } else { 0 };
+            let red_d = if self.use_lmr && self.converting.is_none() && depth >= 3 && !m.is_capture() && i >= 3 && extend == 0 {
                 let base_red = 1 + self.lmr_aggr.max(0) as u32;
-                let mut red_d = if depth >= 6 { base_red + 1 } else { base_red };
-                if red_d >= depth { red_d = depth - 1; }
-                let red = -self.alphabeta(board, depth - 1 - red_d, -alpha - 1, -alpha, ply + 1);
-                if red > alpha { -self.alphabeta(board, depth - 1, -beta, -alpha, ply + 1) } else { red }
+                let red_d = if depth >= 6 { base_red + 1 } else { base_red };
+                let red_d = if i >= 8 { red_d + self.lmr_late_extra } else { red_d };
+                // Expected cut nodes are refuted by an early move; the late ones lose one ply more
+                let red_d = red_d + u32::from(self.node_types && node == NodeType::Cut);
+                // Attacking lines lose one ply less
+                red_d.min(depth - 1) - u32::from(danger)
+            } else { 0 };
+            let child_node = node.child(i);
+            let sc = if red_d > 0 {
+                let red = -self.alphabeta(board, depth - 1 - red_d, -alpha - 1, -alpha, ply + 1, child_node);
+                // A reduced move that beat alpha at a PV node is a new PV candidate
+                let full_node = if node == NodeType::Pv { NodeType::Pv } else { child_node };
+                if red > alpha { -self.alphabeta(board, depth - 1, -beta, -alpha, ply + 1, full_node) } else { red }
             } else {
-                -self.alphabeta(board, depth - 1 + extend, -beta, -alpha, ply + 1)
+                -self.alphabeta(board, depth - 1 + extend, -beta, -alpha, ply + 1, child_node)
             };
-            board.undo_move();
+            self.unmake(board);
             if sc >= beta {
-                self.tt.put(TtEntry { key: board.zobrist(), depth, score: sc, best: Some(*m), bound: TtBound::Lower, gen: 0 });
+                self.tt_store(TtEntry { key: board.zobrist(), depth, score: Score::from_value(sc).to_tt(ply as i32), best: Some(*m), bound: TtBound::Lower, gen: 0 });
                 if self.use_killers {
                     let ply = (self.killers.len()-1).min(depth as usize);
                     let k = &mut self.killers[ply]; if k[0] != Some(*m) { k[1] = k[0]; k[0] = Some(*m); }
@@ -493,21 +1190,69 @@ impl PlecoSearcher {
             if sc > alpha { alpha = sc; bestmove = Some(*m); }
         }
         let bound = if bestmove.is_some() { TtBound::Exact } else { TtBound::Upper };
-        self.tt.put(TtEntry { key: board.zobrist(), depth, score: alpha, best: bestmove, bound, gen: 0 });
+        self.tt_store(TtEntry { key: board.zobrist(), depth, score: Score::from_value(alpha).to_tt(ply as i32), best: bestmove, bound, gen: 0 });
         alpha
     }
 
+    // A single-threaded in-tree helper sharing our TT, limits and eval, with its
+    // accumulator at `board`
+    fn split_worker(&self, board: &PlecoBoard) -> Self {
+        let mut w = Self { tt: self.tt.clone(), ..Self::default() };
+        w.threads = 1; w.use_killers = self.use_killers; w.use_lmr = self.use_lmr; w.use_nullmove = self.use_nullmove; w.use_aspiration = self.use_aspiration; w.aspiration_window_cp = self.aspiration_window_cp; w.deadline = self.deadline; w.stop = self.stop.clone();
+        self.attach_eval(&mut w, board);
+        w
+    }
+
+    /// Search `tails` in parallel with a shared alpha starting at `alpha`; workers
+    /// stop once one fails high. Returns the best move and score among them.
+    #[allow(clippy::too_many_arguments)]
+    fn search_younger(&mut self, board: &PlecoBoard, tails: &[PMove], depth: u32, alpha: i32, beta: i32, ply: u32, eldest_cut: bool) -> Option<(PMove, i32)> {
+        use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+        let alpha_shared = AtomicI32::new(alpha);
+        let abort_flag = Arc::new(AtomicBool::new(eldest_cut && self.split.policy == SplitPolicy::Ybwc));
+        let this = &*self;
+        let results: Vec<(PMove, i32, u64)> = tails.par_iter().filter_map(|&m| guarded("split", depth, || format!("{} moves {}", board.fen(), m), || {
+            if abort_flag.load(Ordering::Relaxed) { return (m, -MATE_SCORE, 0); }
+            let mut c = board.clone(); c.apply_move(m);
+            let mut w = this.split_worker(&c);
+            w.abort = Some(abort_flag.clone());
+            let a = alpha_shared.load(Ordering::Relaxed);
+            // Younger brothers are expected to be refuted whatever the parent's type
+            let sc = -w.alphabeta(&mut c, depth - 1, -beta, -a, ply + 1, NodeType::Cut);
+            // update alpha
+            let mut cur = a;
+            while sc > cur {
+                match alpha_shared.compare_exchange(cur, sc, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => break,
+                    Err(obs) => { if obs >= sc { break; } cur = obs; }
+                }
+            }
+            if sc >= beta { abort_flag.store(true, Ordering::Relaxed); }
+            // Stopped by a sibling's cutoff: the score is a static eval, not a bound
+            else if this.smp_safe && w.interrupted() { return (m, -MATE_SCORE, w.nodes); }
+            (m, sc, w.nodes)
+        })).collect();
+        self.split_stats.record(results.iter().map(|&(_, s, n)| (s, n)), beta, eldest_cut);
+        let mut best: Option<(PMove, i32)> = None;
+        for (m, s, n) in results {
+            self.nodes += n;
+            if best.is_none_or(|(_, bs)| s > bs) { best = Some((m, s)); }
+        }
+        best
+    }
+
     fn qsearch(&mut self, board: &mut PlecoBoard, mut alpha: i32, beta: i32, ply: u32) -> i32 {
         if ply > self.max_seldepth { self.max_seldepth = ply; }
-        let stand = self.eval(board);
+        if ply as usize >= MAX_PLY { return self.eval(board); }
+        let stand = self.eval(board) + self.draw_bias.complexity(board.turn() == self.root_side, || MaterialSig::from_pleco(board).phase());
         if stand >= beta { return beta; }
         if stand > alpha { alpha = stand; }
         let mut caps: Vec<PMove> = board.generate_moves().iter().copied().filter(|m| m.is_capture()).collect();
         caps.sort_by_key(|&m| -self.mvv_lva(board, m));
         for m in caps.into_iter() {
-            board.apply_move(m);
+            self.make(board, m);
             let sc = -self.qsearch(board, -beta, -alpha, ply + 1);
-            board.undo_move();
+            self.unmake(board);
             if sc >= beta { return beta; }
             if sc > alpha { alpha = sc; }
         }
@@ -515,17 +1260,45 @@ impl PlecoSearcher {
     }
 
     fn eval(&self, board: &PlecoBoard) -> i32 {
+        if self.eval_mode == PlecoEvalMode::Nnue && self.converting.is_none() {
+            let sig = MaterialSig::from_pleco(board);
+            // The small net takes endgames (everything under `small_net_only`), from scratch
+            let raw = match (self.nnue_small.as_ref().filter(|sn| self.small_net_only || sn.covers(sig.phase())), &self.nnue_quant) {
+                (Some(small), _) => Some(self.eval_hash.get_or_insert_with(eval_key(board), || eval_indices(&small.model, &active_indices_pleco(board)))),
+                (None, Some(qn)) => Some(self.eval_hash.get_or_insert_with(eval_key(board), || qn.eval_current())),
+                (None, None) => None,
+            };
+            if let Some(raw) = raw { return side_relative(board.turn() == Player::White, scale_eval(&sig, raw)); }
+        }
         // Simple material count for prototype
-        
         let mut score = 0i32;
         for &(p, v) in &[(PieceType::P,100),(PieceType::N,320),(PieceType::B,330),(PieceType::R,500),(PieceType::Q,900)] {
             score += board.count_piece(Player::White, p) as i32 * v;
             score -= board.count_piece(Player::Black, p) as i32 * v;
         }
-        if board.turn() == Player::White { score } else { -score }
+        let sig = MaterialSig::from_pleco(board);
+        let white = board.turn() == Player::White;
+        match self.converting {
+            Some(winner) => conversion::eval_pleco(board, winner, side_relative(white, scale_eval(&sig, score))),
+            None if self.eval_mode == PlecoEvalMode::Pst => side_relative(white, scale_eval(&sig, score + pst_cp_pleco(board))),
+            None => side_relative(white, scale_eval(&sig, score)),
+        }
+    }
+
+    // Record this node's static eval; improving if above the side to move's eval two plies up
+    fn improving(&mut self, board: &PlecoBoard, ply: u32) -> bool {
+        let ply = ply as usize;
+        if self.evals.len() <= ply { self.evals.resize(ply + 1, NO_EVAL); }
+        let e = if board.in_check() { NO_EVAL } else { self.eval(board) };
+        self.evals[ply] = e;
+        ply >= 2 && e != NO_EVAL && self.evals[ply - 2] != NO_EVAL && e > self.evals[ply - 2]
+    }
+
+    fn is_killer(&self, m: PMove, depth: u32) -> bool {
+        self.use_killers && self.killers[(self.killers.len() - 1).min(depth as usize)].contains(&Some(m))
     }
 
-    fn eval_terminal(&self, board: &PlecoBoard) -> i32 {
-        if board.in_check() { -MATE_SCORE } else { DRAW_SCORE }
+    fn eval_terminal(&self, board: &PlecoBoard, ply: u32) -> i32 {
+        if board.in_check() { Score::mated_in(ply as i32).value() } else { self.draw_score(board) }
     }
 }
@@ -0,0 +1,105 @@
+//! Aspiration window adapted over a game. Every aspirated iteration records whether
+//! it failed high or low and how far the root score moved from the previous
+//! iteration; the window the next iteration (and the next move) starts from widens
+//! where scores are volatile and narrows where they are quiet. The estimate lives in
+//! the searcher, so it carries across the moves of a game until `ucinewgame`.
+//!
+//! `Window` is the re-search loop both backends run inside an iteration: a fail
+//! widens only the failed side, by a growing margin around the returned score,
+//! and opens it fully after `WIDEN_TRIES` fails or near a mate score.
+
+use crate::search::score::{MATE_BOUND, MATE_SCORE};
+
+/// Narrowest adapted half-window (cp); also the floor of a fixed window.
+pub const MIN_WINDOW: i32 = 10;
+/// Widest adapted half-window (cp).
+pub const MAX_WINDOW: i32 = 200;
+/// Fail rate at which the base window is kept: more fails widen it, fewer narrow it.
+pub const TARGET_FAIL_RATE: f32 = 0.125;
+/// Fails on one iteration before the failed side is opened to the full window.
+pub const WIDEN_TRIES: u32 = 3;
+// Weight of the newest iteration in the running averages
+const DECAY: f32 = 1.0 / 8.0;
+
+/// Fail and score-swing statistics of the aspirated iterations of one game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowStats {
+    /// Aspirated iterations recorded this game
+    pub iterations: u32,
+    pub fail_high: u32,
+    pub fail_low: u32,
+    /// Running average of fails per iteration
+    pub fail_rate: f32,
+    /// Running average of the root score change between iterations (cp)
+    pub volatility: f32,
+}
+
+impl Default for WindowStats {
+    fn default() -> Self {
+        Self { iterations: 0, fail_high: 0, fail_low: 0, fail_rate: TARGET_FAIL_RATE, volatility: 0.0 }
+    }
+}
+
+impl WindowStats {
+    /// Record one aspirated iteration: `score` is its first (windowed) result against
+    /// `[alpha, beta]`, `prev` and `settled` the root scores before and after it.
+    pub fn record(&mut self, alpha: i32, beta: i32, score: i32, prev: i32, settled: i32) {
+        let (high, low) = (score >= beta, score <= alpha);
+        self.iterations += 1;
+        self.fail_high += u32::from(high);
+        self.fail_low += u32::from(low);
+        self.fail_rate += DECAY * (f32::from(u8::from(high || low)) - self.fail_rate);
+        // A mate score says nothing about how much ordinary scores swing
+        if prev.abs() < MATE_BOUND && settled.abs() < MATE_BOUND {
+            let swing = (settled - prev).abs().min(MAX_WINDOW) as f32;
+            self.volatility += DECAY * (swing - self.volatility);
+        }
+    }
+
+    /// The half-window to search with, from `base` (the configured window): scaled
+    /// by the fail rate relative to `TARGET_FAIL_RATE` (half of `base` without fails,
+    /// up to 2.5 times it), and at least the typical score swing.
+    pub fn window(&self, base: i32) -> i32 {
+        let scale = (0.5 + 0.5 * self.fail_rate / TARGET_FAIL_RATE).clamp(0.5, 2.5);
+        let w = (base as f32 * scale).max(self.volatility).round() as i32;
+        w.clamp(MIN_WINDOW, MAX_WINDOW)
+    }
+}
+
+/// Aspiration bounds of one iteration, widened after each fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Window {
+    pub alpha: i32,
+    pub beta: i32,
+    /// Margin the next widening puts beyond the failed score
+    pub delta: i32,
+    /// Fails so far this iteration
+    pub fails: u32,
+}
+
+impl Window {
+    /// Half-window `half` around the previous iteration's score.
+    pub fn new(prev: i32, half: i32) -> Self {
+        let delta = half.max(1);
+        Self { alpha: (prev - delta).max(-MATE_SCORE), beta: (prev + delta).min(MATE_SCORE), delta, fails: 0 }
+    }
+
+    /// Both sides open: the search is an ordinary full-window one.
+    pub fn is_full(&self) -> bool { self.alpha <= -MATE_SCORE && self.beta >= MATE_SCORE }
+
+    /// Widen after `score` came back; false when it was inside the window (or the
+    /// window was already full) and the iteration is settled.
+    pub fn widen(&mut self, score: i32) -> bool {
+        let (low, high) = (score <= self.alpha && self.alpha > -MATE_SCORE, score >= self.beta && self.beta < MATE_SCORE);
+        if !low && !high { return false; }
+        self.fails += 1;
+        self.delta = self.delta.saturating_mul(2);
+        let open = self.fails >= WIDEN_TRIES || score.abs() >= MATE_BOUND;
+        if low {
+            self.alpha = if open { -MATE_SCORE } else { (score - self.delta).max(-MATE_SCORE) };
+        } else {
+            self.beta = if open { MATE_SCORE } else { (score + self.delta).min(MATE_SCORE) };
+        }
+        true
+    }
+}
@@ -0,0 +1,226 @@
+//! Experimental root split over several machines. Worker processes listen on TCP;
+//! the master deals the root moves out round-robin, each worker searches its share
+//! as a `searchmoves`-restricted search, and the master keeps the best reply.
+//! Messages are one JSON object per line. A worker that cannot be reached or
+//! fails mid-search has its share searched locally, so a run always completes.
+//!
+//! Options travel as an `EngineOptions::describe` spec, so network files must exist
+//! at the same paths on every worker. A worker started with a secret answers only
+//! jobs carrying the same one; the master gives up on a reply after
+//! `ClusterLink::timeout` (or the movetime plus `REPLY_GRACE`) and searches the share
+//! itself.
+
+use crate::search::alphabeta::SearchResult;
+use crate::search::options::EngineOptions;
+use crate::search::score::Score;
+use crate::{Result, TitanError};
+use cozy_chess::{Board, Move};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Slack on top of the movetime before a timed job's reply counts as lost.
+pub const REPLY_GRACE: Duration = Duration::from_secs(5);
+/// How long the master waits for a fixed-depth job by default.
+pub const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How the master reaches its workers.
+#[derive(Clone, Debug)]
+pub struct ClusterLink {
+    /// Shared secret sent with every job; must match the workers'
+    pub secret: Option<String>,
+    /// Longest wait for a fixed-depth job's reply
+    pub timeout: Duration,
+}
+
+impl Default for ClusterLink {
+    fn default() -> Self { Self { secret: None, timeout: DEFAULT_REPLY_TIMEOUT } }
+}
+
+/// A share of the root: search `fen` restricted to `moves` with the options in `spec`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClusterJob {
+    pub fen: String,
+    pub moves: Vec<String>,
+    pub spec: String,
+    /// The master's `ClusterLink::secret`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ClusterReply {
+    pub bestmove: Option<String>,
+    pub score_cp: i32,
+    pub nodes: u64,
+    pub depth: u32,
+    /// Set when the worker rejected the job
+    pub error: Option<String>,
+}
+
+/// Who searched which part of the root.
+#[derive(Clone, Debug)]
+pub struct ShareReport {
+    /// Worker address, or "local" for the master's fallback
+    pub worker: String,
+    pub moves: usize,
+    pub nodes: u64,
+    pub depth: u32,
+    pub bestmove: Option<String>,
+    pub score_cp: i32,
+    /// Why the worker's share fell back to the master
+    pub failure: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClusterResult {
+    pub result: SearchResult,
+    pub shares: Vec<ShareReport>,
+}
+
+fn parse_moves(board: &Board, moves: &[String]) -> std::result::Result<Vec<Move>, String> {
+    let mut legal = Vec::new();
+    board.generate_moves(|ml| { legal.extend(ml); false });
+    moves.iter().map(|s| legal.iter().copied().find(|m| format!("{}", m) == *s).ok_or_else(|| format!("illegal move {}", s))).collect()
+}
+
+/// Search one job here; `ClusterReply::error` reports a bad position, move or spec.
+pub fn run_job(job: &ClusterJob) -> ClusterReply {
+    let run = || -> std::result::Result<ClusterReply, String> {
+        let board = Board::from_fen(&job.fen, false).map_err(|e| format!("bad fen '{}': {:?}", job.fen, e))?;
+        let moves = parse_moves(&board, &job.moves)?;
+        let mut opts = EngineOptions::default();
+        opts.apply_spec(&job.spec).map_err(|e| e.to_string())?;
+        let mut s = opts.build_searcher().map_err(|e| e.to_string())?;
+        s.set_search_moves(Some(moves));
+        let r = s.search_with_params(&board, opts.search_params());
+        Ok(ClusterReply { bestmove: r.bestmove, score_cp: r.score.value(), nodes: r.nodes, depth: r.depth, error: None })
+    };
+    run().unwrap_or_else(|e| ClusterReply { error: Some(e), ..Default::default() })
+}
+
+// Compared without an early exit, so the reply time says nothing about the secret
+fn same_secret(got: Option<&str>, want: &str) -> bool {
+    let got = got.unwrap_or_default().as_bytes();
+    got.len() == want.len() && got.iter().zip(want.as_bytes()).fold(0u8, |d, (a, b)| d | (a ^ b)) == 0
+}
+
+/// Answer jobs on one connection until the master hangs up. With a `secret`, a job
+/// without it is refused and the connection closed.
+pub fn serve_connection(stream: TcpStream, secret: Option<&str>) -> std::io::Result<()> {
+    let mut out = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() { continue; }
+        let (reply, refused) = match serde_json::from_str::<ClusterJob>(&line) {
+            Ok(job) if secret.is_some_and(|s| !same_secret(job.secret.as_deref(), s)) => (ClusterReply { error: Some("bad secret".into()), ..Default::default() }, true),
+            Ok(job) => (run_job(&job), false),
+            Err(e) => (ClusterReply { error: Some(format!("bad job: {}", e)), ..Default::default() }, false),
+        };
+        writeln!(out, "{}", serde_json::to_string(&reply).map_err(std::io::Error::other)?)?;
+        if refused { break; }
+    }
+    Ok(())
+}
+
+/// Worker loop: one thread per master connection (see `serve_connection`).
+pub fn serve(listener: TcpListener, secret: Option<String>) -> std::io::Result<()> {
+    let secret: Option<std::sync::Arc<str>> = secret.map(Into::into);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let secret = secret.clone();
+        std::thread::spawn(move || {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            if let Err(e) = serve_connection(stream, secret.as_deref()) { eprintln!("cluster worker: {}: {}", peer, e); }
+        });
+    }
+    Ok(())
+}
+
+// Send one job and wait up to `timeout` for its reply
+fn ask(addr: &str, job: &ClusterJob, timeout: Duration) -> Result<ClusterReply> {
+    let err = |reason: String| TitanError::Cluster { addr: addr.to_string(), reason };
+    let sock = addr.to_socket_addrs().map_err(|e| err(e.to_string()))?.next().ok_or_else(|| err("no address".into()))?;
+    let stream = TcpStream::connect_timeout(&sock, CONNECT_TIMEOUT).map_err(|e| err(e.to_string()))?;
+    stream.set_read_timeout(Some(timeout)).and_then(|_| stream.set_write_timeout(Some(CONNECT_TIMEOUT))).map_err(|e| err(e.to_string()))?;
+    let mut out = stream.try_clone().map_err(|e| err(e.to_string()))?;
+    writeln!(out, "{}", serde_json::to_string(job).map_err(|e| err(e.to_string()))?).map_err(|e| err(e.to_string()))?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).map_err(|e| match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => err(format!("no reply within {}s", timeout.as_secs_f32())),
+        _ => err(e.to_string()),
+    })?;
+    if line.is_empty() { return Err(err("connection closed".into())); }
+    let reply: ClusterReply = serde_json::from_str(&line).map_err(|e| err(format!("bad reply: {}", e)))?;
+    match reply.error {
+        Some(e) => Err(err(e)),
+        None => Ok(reply),
+    }
+}
+
+/// Search `board` with `opts` across `workers` (host:port) reached through `link`.
+/// Root moves are dealt round-robin; with no workers the master searches everything
+/// itself.
+pub fn cluster_search(board: &Board, opts: &EngineOptions, workers: &[String], link: &ClusterLink) -> Result<ClusterResult> {
+    let start = Instant::now();
+    let mut moves: Vec<Move> = Vec::new();
+    board.generate_moves(|ml| { moves.extend(ml); false });
+    if moves.is_empty() {
+        // Mate or stalemate: nothing to share out
+        let result = opts.build_searcher()?.search_with_params(board, opts.search_params());
+        return Ok(ClusterResult { result, shares: Vec::new() });
+    }
+    let slots = workers.len().max(1);
+    let mut shares: Vec<Vec<String>> = vec![Vec::new(); slots];
+    for (i, m) in moves.iter().enumerate() { shares[i % slots].push(format!("{}", m)); }
+    let spec = opts.describe();
+    let fen = format!("{}", board);
+    let timeout = opts.movetime_ms.map_or(link.timeout, |ms| Duration::from_millis(ms) + REPLY_GRACE);
+
+    let replies: Vec<(String, Vec<String>, Result<ClusterReply>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = shares.iter().enumerate().filter(|(_, share)| !share.is_empty()).map(|(i, share)| {
+            let job = ClusterJob { fen: fen.clone(), moves: share.clone(), spec: spec.clone(), secret: link.secret.clone() };
+            let worker = workers.get(i).cloned();
+            scope.spawn(move || {
+                let reply = match &worker {
+                    Some(addr) => ask(addr, &job, timeout),
+                    None => Ok(run_job(&job)),
+                };
+                (worker.unwrap_or_else(|| "local".to_string()), job.moves, reply)
+            })
+        }).collect();
+        handles.into_iter().map(|h| h.join().expect("cluster share thread")).collect()
+    });
+
+    let mut reports = Vec::new();
+    for (worker, share, reply) in replies {
+        let (reply, failure) = match reply {
+            Ok(r) => (r, None),
+            Err(e) => {
+                // Search the lost share here
+                let r = run_job(&ClusterJob { fen: fen.clone(), moves: share.clone(), spec: spec.clone(), secret: None });
+                if let Some(e) = r.error { return Err(TitanError::Config(e)); }
+                (r, Some(e.to_string()))
+            }
+        };
+        reports.push(ShareReport { worker, moves: share.len(), nodes: reply.nodes, depth: reply.depth, bestmove: reply.bestmove, score_cp: reply.score_cp, failure });
+    }
+    let best = reports.iter().filter(|r| r.bestmove.is_some()).max_by_key(|r| r.score_cp);
+    let result = SearchResult {
+        bestmove: best.and_then(|r| r.bestmove.clone()),
+        score: Score::from_value(best.map_or(0, |r| r.score_cp)),
+        nodes: reports.iter().map(|r| r.nodes).sum(),
+        // Every share completed at least this depth
+        depth: reports.iter().map(|r| r.depth).min().unwrap_or(0),
+        ..Default::default()
+    }.with_elapsed(start.elapsed());
+    Ok(ClusterResult { result, shares: reports })
+}
+
+/// True if the worker at `addr` answers a trivial job sent through `link`.
+pub fn ping(addr: &str, link: &ClusterLink) -> bool {
+    let job = ClusterJob { fen: format!("{}", Board::default()), moves: vec!["e2e4".into()], spec: "depth=1".into(), secret: link.secret.clone() };
+    ask(addr, &job, CONNECT_TIMEOUT).is_ok()
+}
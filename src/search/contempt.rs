@@ -0,0 +1,66 @@
+//! Draw scoring for match play. Static contempt makes every draw worth
+//! `-static_cp` to the engine. Dynamic contempt adds a term from the root score and
+//! game phase: when ahead, draws cost more (so the engine does not trade into
+//! them); when behind, positions with more material left score a little better
+//! for the engine (keep the game complicated rather than simplifying into a loss).
+
+use crate::board::cozy::PHASE_MAX;
+use crate::search::score::DRAW_SCORE;
+use serde::{Deserialize, Serialize};
+
+/// Extra contempt when clearly ahead with all pieces on the board.
+pub const DYNAMIC_MAX_CP: i32 = 40;
+/// Bonus for keeping all pieces on when clearly behind.
+pub const COMPLEXITY_MAX_CP: i32 = 30;
+/// Root score (cp) at which the dynamic terms reach about three quarters of their maximum.
+const LEAD_SCALE_CP: f64 = 200.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Contempt {
+    /// Value of a draw to the opponent, in centipawns (UCI `Contempt`)
+    pub static_cp: i32,
+    /// Scale contempt with the root score and game phase (UCI `DynamicContempt`)
+    pub dynamic: bool,
+}
+
+/// Contempt resolved for one search iteration from the root position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct DrawBias {
+    /// What a draw costs the engine
+    pub draw_cp: i32,
+    /// Eval bonus for the engine at full material, fading as pieces come off
+    pub complexity_cp: i32,
+}
+
+impl Contempt {
+    /// `root_cp` is the engine's score from the last iteration, `phase` the root's
+    /// non-pawn material (`0..=PHASE_MAX`).
+    pub fn bias(&self, root_cp: i32, phase: u32) -> DrawBias {
+        let mut b = DrawBias { draw_cp: self.static_cp, complexity_cp: 0 };
+        if !self.dynamic { return b; }
+        let lead = (root_cp as f64 / LEAD_SCALE_CP).tanh();
+        let w = phase.min(PHASE_MAX) as f64 / PHASE_MAX as f64;
+        if lead > 0.0 {
+            // Draws are likelier (and more acceptable) once the pieces are off
+            b.draw_cp += (DYNAMIC_MAX_CP as f64 * lead * (0.5 + 0.5 * w)).round() as i32;
+        } else {
+            b.complexity_cp = (COMPLEXITY_MAX_CP as f64 * -lead).round() as i32;
+        }
+        b
+    }
+}
+
+impl DrawBias {
+    /// A draw's score for the side to move.
+    pub fn draw_score(&self, engine_to_move: bool) -> i32 {
+        if engine_to_move { DRAW_SCORE - self.draw_cp } else { DRAW_SCORE + self.draw_cp }
+    }
+
+    /// Eval adjustment for the side to move; `phase` is only computed when needed.
+    pub fn complexity(&self, engine_to_move: bool, phase: impl FnOnce() -> u32) -> i32 {
+        if self.complexity_cp == 0 { return 0; }
+        let c = self.complexity_cp * phase().min(PHASE_MAX) as i32 / PHASE_MAX as i32;
+        if engine_to_move { c } else { -c }
+    }
+}
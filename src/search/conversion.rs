@@ -0,0 +1,87 @@
+//! Conversion mode for won endgames. Until tablebase probing is added, a TB win is
+//! recognised from material: one side has a bare king and the other a queen, a rook,
+//! or bishop and knight. Contempt only gets in the way of such a win and pruning
+//! delays the mate, so both searchers drop contempt, null move and LMR when the
+//! root is a TB win, and evaluate with material plus `bonus`: the loser's king
+//! driven to the edge, the kings brought together, and a penalty that grows with
+//! the fifty-move counter so lines that mate or reset the counter sooner are
+//! preferred (DTZ-like). Bishop and knight can only mate in a corner of the bishop's
+//! colour, so there `corner_bonus` pulls the king to those. The usual eval is left out: king tables that keep the king
+//! home in the middlegame pull the wrong way here.
+
+use crate::search::eval::{material_eval_cp, MaterialSig};
+use cozy_chess::{Board, Color, Piece};
+
+/// Per step of the losing king away from the centre (0..=6 steps).
+pub const EDGE_CP: i32 = 20;
+/// Per step the kings are closer than the maximum distance of 7.
+pub const CLOSE_CP: i32 = 10;
+/// Per ply on the fifty-move counter.
+pub const FIFTY_CP: i32 = 2;
+/// Per step the losing king is closer to a mating corner than the maximum distance of 7 (K+B+N).
+pub const CORNER_CP: i32 = 20;
+/// Light squares (b1, a2, ...) as a bitboard.
+const LIGHT_SQUARES: u64 = 0x55AA_55AA_55AA_55AA;
+
+/// The side (0 = white, 1 = black) that wins by force with this material, if any.
+pub fn tb_win(sig: &MaterialSig) -> Option<usize> {
+    let bare = |s: usize| sig.pawns[s] + sig.knights[s] + sig.bishops[s] + sig.rooks[s] + sig.queens[s] == 0;
+    let mates = |s: usize| sig.queens[s] + sig.rooks[s] > 0 || (sig.bishops[s] > 0 && sig.knights[s] > 0);
+    (0..2).find(|&s| bare(1 - s) && mates(s))
+}
+
+/// The winner's bonus, from the king squares (0 = a1 .. 63 = h8) and the halfmove clock.
+pub fn bonus(winner_king: usize, loser_king: usize, halfmove: u32) -> i32 {
+    let (wf, wr) = ((winner_king % 8) as i32, (winner_king / 8) as i32);
+    let (lf, lr) = ((loser_king % 8) as i32, (loser_king / 8) as i32);
+    // 0 on the four centre files/ranks, 3 on the edge
+    let off_centre = |x: i32| ((2 * x - 7).abs() - 1) / 2;
+    let distance = (wf - lf).abs().max((wr - lr).abs());
+    EDGE_CP * (off_centre(lf) + off_centre(lr)) + CLOSE_CP * (7 - distance) - FIFTY_CP * halfmove.min(100) as i32
+}
+
+/// K+B+N only: the bonus for the losing king's distance to the nearer corner of the
+/// bishop's colour (a8/h1 for a light-squared bishop, a1/h8 for a dark one).
+pub fn corner_bonus(loser_king: usize, light_bishop: bool) -> i32 {
+    let (lf, lr) = ((loser_king % 8) as i32, (loser_king / 8) as i32);
+    let corners = if light_bishop { [(0, 7), (7, 0)] } else { [(0, 0), (7, 7)] };
+    let distance = corners.iter().map(|&(f, r)| (lf - f).abs().max((lr - r).abs())).min().unwrap_or(7);
+    CORNER_CP * (7 - distance)
+}
+
+/// The winner's bishop colour (true = light) when bishop and knight have to mate without
+/// a queen or rook; None otherwise, or with bishops on both colours.
+fn mating_bishop(heavy: bool, knights: bool, bishops: u64) -> Option<bool> {
+    if heavy || !knights || bishops == 0 { return None; }
+    if bishops & LIGHT_SQUARES == bishops { Some(true) } else if bishops & LIGHT_SQUARES == 0 { Some(false) } else { None }
+}
+
+/// The colour that wins by force on `board` (see `tb_win`).
+pub fn tb_winner(board: &Board) -> Option<Color> {
+    tb_win(&MaterialSig::from_board(board)).map(|s| if s == 0 { Color::White } else { Color::Black })
+}
+
+/// Conversion eval for the side to move on `board`, with `winner` the converting side.
+pub fn eval(board: &Board, winner: Color) -> i32 {
+    let mut b = bonus(board.king(winner) as usize, board.king(!winner) as usize, board.halfmove_clock() as u32);
+    let ours = |p: Piece| board.colored_pieces(winner, p);
+    let heavy = !(ours(Piece::Queen) | ours(Piece::Rook)).is_empty();
+    if let Some(light) = mating_bishop(heavy, !ours(Piece::Knight).is_empty(), ours(Piece::Bishop).0) { b += corner_bonus(board.king(!winner) as usize, light); }
+    material_eval_cp(board) + if board.side_to_move() == winner { b } else { -b }
+}
+
+#[cfg(feature = "board-pleco")]
+pub fn tb_winner_pleco(board: &pleco::Board) -> Option<pleco::Player> {
+    tb_win(&MaterialSig::from_pleco(board)).map(|s| if s == 0 { pleco::Player::White } else { pleco::Player::Black })
+}
+
+/// `eval` for a pleco board; `material_cp` is the side to move's material.
+#[cfg(feature = "board-pleco")]
+pub fn eval_pleco(board: &pleco::Board, winner: pleco::Player, material_cp: i32) -> i32 {
+    use pleco::PieceType;
+    let loser_king = board.king_sq(winner.other_player()).0 as usize;
+    let mut b = bonus(board.king_sq(winner).0 as usize, loser_king, board.rule_50().max(0) as u32);
+    let ours = |p: PieceType| board.piece_bb(winner, p).0;
+    if let Some(light) = mating_bishop(ours(PieceType::Q) | ours(PieceType::R) != 0, ours(PieceType::N) != 0, ours(PieceType::B)) { b += corner_bonus(loser_king, light); }
+    material_cp + if board.turn() == winner { b } else { -b }
+}
@@ -1,3 +1,10 @@
+//! Static evaluation. Sign convention, shared by every eval in the engine:
+//! the raw terms (material, PSTs, passers and the NNUE outputs in `eval::nnue`)
+//! are White-relative, and `side_relative` turns them into the side to move's
+//! view exactly once, at the point an eval leaves for the search. Everything the
+//! search returns or stores (negamax, qsearch, TT, UCI `score`) is then from the
+//! side to move's view, like `Score`.
+
 use cozy_chess::{Board, Color, Piece, Square};
 
 const PAWN: i32 = 100;
@@ -24,15 +31,100 @@ pub fn material_eval_cp_side_agnostic(board: &Board) -> i32 {
     score
 }
 
+/// A White-relative eval from the side to move's view (the one sign flip; see the module docs).
+#[inline]
+pub fn side_relative(white_to_move: bool, white_cp: i32) -> i32 { if white_to_move { white_cp } else { -white_cp } }
+
 // Material from side-to-move perspective (negamax-friendly)
 pub fn material_eval_cp(board: &Board) -> i32 {
-    let base = material_eval_cp_side_agnostic(board);
-    if board.side_to_move() == Color::White { base } else { -base }
+    side_relative(board.side_to_move() == Color::White, material_eval_cp_side_agnostic(board))
 }
 
-// Mate scoring helpers
-pub const MATE_SCORE: i32 = 30_000;
-pub const DRAW_SCORE: i32 = 0;
+/// Full-strength endgame scale: evals are multiplied by `scale / SCALE_NORMAL`.
+pub const SCALE_NORMAL: i32 = 64;
+const LIGHT_SQUARES: u64 = 0x55AA_55AA_55AA_55AA;
+
+/// Piece counts indexed `[white, black]`, plus whether each side's single bishop
+/// stands on a different square colour. Enough to recognise drawish material.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MaterialSig {
+    pub pawns: [i32; 2],
+    pub knights: [i32; 2],
+    pub bishops: [i32; 2],
+    pub rooks: [i32; 2],
+    pub queens: [i32; 2],
+    pub opposite_bishops: bool,
+}
+
+impl MaterialSig {
+    pub fn from_board(board: &Board) -> Self {
+        let c = |p: Piece| [count_piece(board, Color::White, p), count_piece(board, Color::Black, p)];
+        let light = |color: Color| (board.colors(color) & board.pieces(Piece::Bishop)).0 & LIGHT_SQUARES != 0;
+        let bishops = c(Piece::Bishop);
+        let opposite_bishops = bishops == [1, 1] && light(Color::White) != light(Color::Black);
+        Self { pawns: c(Piece::Pawn), knights: c(Piece::Knight), bishops, rooks: c(Piece::Rook), queens: c(Piece::Queen), opposite_bishops }
+    }
+
+    #[cfg(feature = "board-pleco")]
+    pub fn from_pleco(board: &pleco::Board) -> Self {
+        use pleco::{PieceType, Player};
+        let c = |p: PieceType| [board.count_piece(Player::White, p) as i32, board.count_piece(Player::Black, p) as i32];
+        let light = |pl: Player| board.piece_bb(pl, PieceType::B).0 & LIGHT_SQUARES != 0;
+        let bishops = c(PieceType::B);
+        let opposite_bishops = bishops == [1, 1] && light(Player::White) != light(Player::Black);
+        Self { pawns: c(PieceType::P), knights: c(PieceType::N), bishops, rooks: c(PieceType::R), queens: c(PieceType::Q), opposite_bishops }
+    }
+
+    fn non_pawn(&self, side: usize) -> i32 {
+        self.knights[side] * KNIGHT + self.bishops[side] * BISHOP + self.rooks[side] * ROOK + self.queens[side] * QUEEN
+    }
+
+    /// Game phase as in `board::cozy::game_phase` (24 with all pieces on, 0 with none).
+    pub fn phase(&self) -> u32 {
+        let sum = |c: [i32; 2]| c[0] + c[1];
+        ((sum(self.knights) + sum(self.bishops) + 2 * sum(self.rooks) + 4 * sum(self.queens)).max(0) as u32).min(crate::board::cozy::PHASE_MAX)
+    }
+
+    /// Neither side can mate: bare kings, a lone minor, or one bishop each on the
+    /// same colour.
+    pub fn is_insufficient(&self) -> bool {
+        if self.pawns != [0, 0] || self.rooks != [0, 0] || self.queens != [0, 0] { return false; }
+        let minors = self.knights[0] + self.knights[1] + self.bishops[0] + self.bishops[1];
+        minors <= 1 || (self.bishops == [1, 1] && self.knights == [0, 0] && !self.opposite_bishops)
+    }
+}
+
+/// Scale (out of `SCALE_NORMAL`) for known drawish material: opposite-coloured
+/// bishops, and a pawnless stronger side that is at most a minor piece up
+/// (R vs R+minor, R vs minor, lone minor).
+pub fn endgame_scale(sig: &MaterialSig) -> i32 {
+    if sig.opposite_bishops && sig.knights == [0, 0] && sig.queens == [0, 0] {
+        return if sig.rooks == [0, 0] { SCALE_NORMAL / 2 } else { SCALE_NORMAL * 3 / 4 };
+    }
+    let total = |s: usize| sig.non_pawn(s) + sig.pawns[s] * PAWN;
+    let strong = if total(0) >= total(1) { 0 } else { 1 };
+    let edge = sig.non_pawn(strong) - sig.non_pawn(1 - strong);
+    if sig.pawns[strong] == 0 && edge > 0 && edge <= BISHOP { return SCALE_NORMAL / 4; }
+    SCALE_NORMAL
+}
+
+/// Apply `endgame_scale` to an eval (either perspective; scaling is symmetric).
+pub fn scale_eval(sig: &MaterialSig, cp: i32) -> i32 {
+    let s = endgame_scale(sig);
+    if s == SCALE_NORMAL { cp } else { cp * s / SCALE_NORMAL }
+}
+
+// Mate and draw scores (encoding and helpers in `search::score`)
+pub use crate::search::score::{Score, DRAW_SCORE, MATE_BOUND, MATE_SCORE};
+
+#[deprecated(note = "use `Score::mate_moves`")]
+pub fn mate_in(score: i32) -> Option<i32> { Score::from_value(score).mate_moves() }
+
+#[deprecated(note = "use `Score::to_tt`")]
+pub fn score_to_tt(score: i32, ply: i32) -> i32 { Score::from_value(score).to_tt(ply).value() }
+
+#[deprecated(note = "use `Score::from_tt`")]
+pub fn score_from_tt(score: i32, ply: i32) -> i32 { Score::from_value(score).from_tt(ply).value() }
 
 // Simple PSTs (from white's perspective); values in centipawns
 // Lightweight, hand-rolled to encourage centralization/development
@@ -126,9 +218,216 @@ fn pst_value_for(board: &Board, color: Color, piece: Piece) -> i32 {
     sum
 }
 
-// Combined material + PST (side-to-move perspective)
-pub fn eval_cp(board: &Board) -> i32 {
-    let mat = material_eval_cp_side_agnostic(board);
+/// The piece-square term (`EvalBreakdown::pst`) of a pleco board, White-relative.
+#[cfg(feature = "board-pleco")]
+pub fn pst_cp_pleco(board: &pleco::Board) -> i32 {
+    use pleco::{PieceType, Player};
+    let tables = [(PieceType::P, &PST_PAWN), (PieceType::N, &PST_KNIGHT), (PieceType::B, &PST_BISHOP), (PieceType::R, &PST_ROOK), (PieceType::Q, &PST_QUEEN), (PieceType::K, &PST_KING)];
+    // `mirror` flips the rank for Black
+    let side = |player: Player, mirror: usize| -> i32 {
+        tables.iter().map(|&(p, t)| {
+            let (mut bb, mut sum) = (board.piece_bb(player, p).0, 0);
+            while bb != 0 { sum += t[bb.trailing_zeros() as usize ^ mirror] as i32; bb &= bb - 1; }
+            sum
+        }).sum()
+    };
+    side(Player::White, 0) - side(Player::Black, 56)
+}
+
+// Passed pawn bonus by rank from the pawn's own side (index 0 = first rank)
+const PASSED_BONUS: [i32; 8] = [0, 5, 10, 20, 35, 60, 100, 0];
+/// Bonus for a passer the defending king cannot catch in a pawn ending.
+pub const UNSTOPPABLE_PASSER: i32 = 500;
+
+/// Passed pawn term, positive for White. Every passer earns a rank bonus; with no
+/// pieces left for the defender, the square rule decides whether a passer is
+/// unstoppable, and if both sides have one the faster pawn (ties to the side to
+/// move) wins the race.
+pub fn passed_pawns_cp(board: &Board) -> i32 {
+    let pawns = |c: Color| (board.colors(c) & board.pieces(Piece::Pawn)).into_iter().map(|s| (s.file() as i32, s.rank() as i32)).collect::<Vec<_>>();
+    let (wp, bp) = (pawns(Color::White), pawns(Color::Black));
+    let occupied = board.occupied();
+    let mut score = 0;
+    // Fewest moves to queen among each side's unstoppable passers
+    let mut race = [None::<i32>; 2];
+    for (side, color, own, enemy) in [(0usize, Color::White, &wp, &bp), (1, Color::Black, &bp, &wp)] {
+        let sign = if side == 0 { 1 } else { -1 };
+        let defender = !color;
+        let defender_pieces = board.colors(defender) & !(board.pieces(Piece::Pawn) | board.pieces(Piece::King));
+        let king = board.king(defender);
+        let (kf, kr) = (king.file() as i32, king.rank() as i32);
+        for &(f, r) in own.iter() {
+            let rel = if side == 0 { r } else { 7 - r };
+            let ahead = |er: i32| if side == 0 { er > r } else { er < r };
+            if enemy.iter().any(|&(ef, er)| (ef - f).abs() <= 1 && ahead(er)) { continue; }
+            score += sign * PASSED_BONUS[rel as usize];
+            if !defender_pieces.is_empty() { continue; }
+            let promo_rank = if side == 0 { 7 } else { 0 };
+            let path_clear = (rel + 1..=7).all(|k| {
+                let rank = if side == 0 { k } else { 7 - k };
+                !occupied.has(Square::new(cozy_chess::File::index(f as usize), cozy_chess::Rank::index(rank as usize)))
+            });
+            if !path_clear { continue; }
+            // Double push from the start rank saves a move
+            let steps = (7 - rel) - i32::from(rel == 1);
+            let king_dist = (kf - f).abs().max((kr - promo_rank).abs());
+            let tempo = i32::from(board.side_to_move() == defender);
+            if king_dist - tempo > steps { race[side] = Some(race[side].map_or(steps, |s: i32| s.min(steps))); }
+        }
+    }
+    match race {
+        [Some(_), None] => score += UNSTOPPABLE_PASSER,
+        [None, Some(_)] => score -= UNSTOPPABLE_PASSER,
+        [Some(w), Some(b)] => {
+            let white_first = w < b || (w == b && board.side_to_move() == Color::White);
+            score += if white_first { UNSTOPPABLE_PASSER } else { -UNSTOPPABLE_PASSER };
+        }
+        [None, None] => {}
+    }
+    score
+}
+
+// Attack units per piece reaching the king zone: knight, bishop, rook, queen
+const ZONE_WEIGHT: [i32; 4] = [2, 2, 3, 5];
+/// Units at which an attack by two or more pieces counts as dangerous: a queen and
+/// a minor piece on the zone are enough.
+pub const DANGER_UNITS: i32 = 7;
+
+/// Pressure on a king: the pieces attacking its zone (the king square and its
+/// neighbours) and the shield pawns missing in front of it. Each attacker adds its
+/// weight plus one unit per extra zone square it hits; each of the three files
+/// around the king without an own pawn one or two ranks ahead adds one more.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct KingAttack {
+    pub attackers: u32,
+    pub units: i32,
+}
+
+impl KingAttack {
+    /// `attacker`'s pressure on the other king.
+    pub fn from_board(board: &Board, attacker: Color) -> Self {
+        let defender = !attacker;
+        let pieces = [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen].map(|p| board.colored_pieces(attacker, p).0);
+        Self::from_bitboards(board.king(defender), board.colored_pieces(defender, Piece::Pawn).0, pieces, board.occupied().0, defender == Color::White)
+    }
+
+    #[cfg(feature = "board-pleco")]
+    pub fn from_pleco(board: &pleco::Board, attacker: pleco::Player) -> Self {
+        use pleco::PieceType;
+        let defender = attacker.other_player();
+        let pieces = [PieceType::N, PieceType::B, PieceType::R, PieceType::Q].map(|p| board.piece_bb(attacker, p).0);
+        let king = Square::index(board.king_sq(defender).0 as usize);
+        Self::from_bitboards(king, board.piece_bb(defender, PieceType::P).0, pieces, board.occupied().0, defender == pleco::Player::White)
+    }
+
+    fn from_bitboards(king: Square, shield_pawns: u64, pieces: [u64; 4], occupied: u64, white_king: bool) -> Self {
+        use cozy_chess::{get_bishop_moves, get_king_moves, get_knight_moves, get_rook_moves, BitBoard};
+        let zone = get_king_moves(king) | king.bitboard();
+        let blockers = BitBoard(occupied);
+        let mut a = KingAttack::default();
+        for (i, &bb) in pieces.iter().enumerate() {
+            for sq in BitBoard(bb) {
+                let reach = match i {
+                    0 => get_knight_moves(sq),
+                    1 => get_bishop_moves(sq, blockers),
+                    2 => get_rook_moves(sq, blockers),
+                    _ => get_bishop_moves(sq, blockers) | get_rook_moves(sq, blockers),
+                };
+                let hits = (reach & zone).len() as i32;
+                if hits > 0 { a.attackers += 1; a.units += ZONE_WEIGHT[i] + hits - 1; }
+            }
+        }
+        let (f, r) = (king.file() as i32, king.rank() as i32);
+        let ahead = if white_king { 1 } else { -1 };
+        for ff in (f - 1).max(0)..=(f + 1).min(7) {
+            let covered = [1, 2].iter().any(|k| {
+                let rr = r + ahead * k;
+                (0..8).contains(&rr) && shield_pawns & (1u64 << (rr * 8 + ff)) != 0
+            });
+            if !covered { a.units += 1; }
+        }
+        a
+    }
+
+    /// Enough pressure that search should not prune the attacking side's moves hard.
+    pub fn is_dangerous(&self) -> bool { self.attackers >= 2 && self.units >= DANGER_UNITS }
+}
+
+/// Bonus per unused castling right with all pieces on; it tapers with the game phase.
+pub const CASTLING_RIGHT: i32 = 15;
+
+/// Castling rights term, positive for White. Each right still held is worth
+/// `CASTLING_RIGHT` scaled by `phase` (out of `PHASE_MAX`), so an early king walk
+/// (Ke2, Kd2) gives up something the king PST alone does not see. A castled king
+/// earns about as much from the PST, so castling itself stays neutral.
+pub fn castling_rights_cp(board: &Board, phase: u32) -> i32 {
+    let held = |c: Color| { let r = board.castle_rights(c); i32::from(r.short.is_some()) + i32::from(r.long.is_some()) };
+    (held(Color::White) - held(Color::Black)) * CASTLING_RIGHT * phase as i32 / crate::board::cozy::PHASE_MAX as i32
+}
+
+/// Moves (fullmove number) up to which a minor that has moved twice is penalised.
+pub const DEVELOPMENT_MOVES: u16 = 10;
+
+/// Weights of the opening development term, in centipawns per feature with all
+/// pieces on. The term is linear in `DevelopmentFeatures`, so a tuner can fit
+/// these from feature counts alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DevelopmentWeights { pub developed_minor: i32, pub castled: i32, pub repeat_move: i32 }
+
+pub const DEVELOPMENT: DevelopmentWeights = DevelopmentWeights { developed_minor: 8, castled: 20, repeat_move: -12 };
+
+/// One side's opening development: minors off their home squares, a castled king,
+/// and (before `DEVELOPMENT_MOVES`) minors standing where one move from home cannot
+/// reach, which must have spent a second tempo. The eval has no move history, so
+/// that is how "moved the same piece twice" shows on the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DevelopmentFeatures { pub developed_minors: i32, pub castled: i32, pub repeat_moves: i32 }
+
+impl DevelopmentFeatures {
+    pub fn from_board(board: &Board, color: Color) -> Self {
+        use cozy_chess::{get_bishop_rays, get_knight_moves, BitBoard, File, Rank};
+        let home = |f: File| Square::new(f, Rank::First.relative_to(color));
+        let own = |p: Piece| board.colors(color) & board.pieces(p);
+        let mut f = Self::default();
+        for (piece, files, reach) in [
+            (Piece::Knight, [File::B, File::G], get_knight_moves as fn(Square) -> BitBoard),
+            (Piece::Bishop, [File::C, File::F], get_bishop_rays),
+        ] {
+            let homes = home(files[0]).bitboard() | home(files[1]).bitboard();
+            let one_move = files.iter().fold(BitBoard::EMPTY, |bb, &h| bb | reach(home(h)));
+            let out = own(piece) - homes;
+            f.developed_minors += out.len() as i32;
+            if board.fullmove_number() <= DEVELOPMENT_MOVES { f.repeat_moves += (out - one_move).len() as i32; }
+        }
+        let rights = board.castle_rights(color);
+        let king = board.king(color);
+        let castled_square = [File::A, File::B, File::C, File::G, File::H].iter().any(|&f| king == home(f));
+        f.castled = i32::from(castled_square && rights.short.is_none() && rights.long.is_none());
+        f
+    }
+
+    pub fn score(&self, w: &DevelopmentWeights) -> i32 {
+        self.developed_minors * w.developed_minor + self.castled * w.castled + self.repeat_moves * w.repeat_move
+    }
+}
+
+/// Opening development term, positive for White: `DEVELOPMENT` applied to each
+/// side's features, tapered by `phase` (out of `PHASE_MAX`) so it fades as pieces
+/// come off. It keeps very shallow searches from shuffling heavy pieces and
+/// re-moving minors before the rest are out.
+pub fn development_cp(board: &Board, phase: u32) -> i32 {
+    let side = |c: Color| DevelopmentFeatures::from_board(board, c).score(&DEVELOPMENT);
+    (side(Color::White) - side(Color::Black)) * phase as i32 / crate::board::cozy::PHASE_MAX as i32
+}
+
+/// The classical eval's terms, each White-relative in centipawns. `total` is
+/// `material + pst + passers + castling + development` after the endgame scale
+/// (out of `SCALE_NORMAL`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalBreakdown { pub material: i32, pub pst: i32, pub passers: i32, pub castling: i32, pub development: i32, pub scale: i32, pub total: i32 }
+
+pub fn eval_breakdown(board: &Board) -> EvalBreakdown {
+    let material = material_eval_cp_side_agnostic(board);
     let pst =
         pst_value_for(board, Color::White, Piece::Pawn) - pst_value_for(board, Color::Black, Piece::Pawn) +
         pst_value_for(board, Color::White, Piece::Knight) - pst_value_for(board, Color::Black, Piece::Knight) +
@@ -136,6 +435,14 @@ pub fn eval_cp(board: &Board) -> i32 {
         pst_value_for(board, Color::White, Piece::Rook) - pst_value_for(board, Color::Black, Piece::Rook) +
         pst_value_for(board, Color::White, Piece::Queen) - pst_value_for(board, Color::Black, Piece::Queen) +
         pst_value_for(board, Color::White, Piece::King) - pst_value_for(board, Color::Black, Piece::King);
-    let total = mat + pst;
-    if board.side_to_move() == Color::White { total } else { -total }
+    let passers = passed_pawns_cp(board);
+    let sig = MaterialSig::from_board(board);
+    let castling = castling_rights_cp(board, sig.phase());
+    let development = development_cp(board, sig.phase());
+    EvalBreakdown { material, pst, passers, castling, development, scale: endgame_scale(&sig), total: scale_eval(&sig, material + pst + passers + castling + development) }
+}
+
+// Combined material + PST (side-to-move perspective)
+pub fn eval_cp(board: &Board) -> i32 {
+    side_relative(board.side_to_move() == Color::White, eval_breakdown(board).total)
 }
@@ -0,0 +1,92 @@
+//! NNUE evaluation hash. The network forward pass is the most expensive part of a
+//! leaf, and the TT does not cover qsearch, so transposed positions reached there
+//! (and by Lazy-SMP helpers probing the same lines) would each pay for it again.
+//! Entries hold the raw network output (White-relative, before the endgame scale
+//! and PST blending), keyed by the position hash, so the table stays valid when
+//! the blend or contempt changes and only a new network invalidates it.
+//!
+//! One `AtomicU64` per slot: the upper 32 key bits next to the 32-bit score, so
+//! a torn or aliased read is caught by the key check without a lock.
+
+use crate::search::profile::StatCounter;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bytes per slot, for sizing in MB.
+pub const SLOT_BYTES: usize = 8;
+
+/// Probe and hit counts since the table was sized or cleared; 0 without the
+/// `search-stats` feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EvalHashStats { pub probes: u64, pub hits: u64 }
+
+impl EvalHashStats {
+    pub fn hit_rate(&self) -> f64 { if self.probes == 0 { 0.0 } else { self.hits as f64 / self.probes as f64 } }
+}
+
+/// Shared by a searcher and its worker threads through an `Arc`. An empty table
+/// (the default, or 0 MB) misses every probe and stores nothing.
+#[derive(Default)]
+pub struct EvalHash {
+    slots: Vec<AtomicU64>,
+    mb: usize,
+    probes: StatCounter,
+    hits: StatCounter,
+}
+
+impl EvalHash {
+    /// A table of `mb` megabytes rounded down to a power of two slots; 0 disables it.
+    pub fn with_mb(mb: usize) -> Self {
+        let n = mb.saturating_mul(1 << 20) / SLOT_BYTES;
+        let n = if n == 0 { 0 } else { 1usize << n.ilog2() };
+        Self { slots: (0..n).map(|_| AtomicU64::new(0)).collect(), mb, ..Self::default() }
+    }
+
+    /// Size asked for in MB.
+    pub fn mb(&self) -> usize { self.mb }
+    pub fn is_enabled(&self) -> bool { !self.slots.is_empty() }
+
+    #[inline]
+    fn slot(&self, key: u64) -> &AtomicU64 { &self.slots[key as usize & (self.slots.len() - 1)] }
+
+    #[inline]
+    fn pack(key: u64, value: i32) -> u64 { (key & 0xFFFF_FFFF_0000_0000) | u64::from(value as u32) }
+
+    /// The stored network output for `key`, if any.
+    #[inline]
+    pub fn probe(&self, key: u64) -> Option<i32> {
+        if self.slots.is_empty() { return None; }
+        self.probes.bump();
+        let word = self.slot(key).load(Ordering::Relaxed);
+        // An all-zero word is an empty slot
+        if word == 0 || (word ^ key) >> 32 != 0 { return None; }
+        self.hits.bump();
+        Some(word as u32 as i32)
+    }
+
+    /// Store `value` for `key`, replacing whatever shared its slot.
+    #[inline]
+    pub fn store(&self, key: u64, value: i32) {
+        if self.slots.is_empty() { return; }
+        self.slot(key).store(Self::pack(key, value), Ordering::Relaxed);
+    }
+
+    /// `probe`, or `compute` the output and store it.
+    #[inline]
+    pub fn get_or_insert_with(&self, key: u64, compute: impl FnOnce() -> i32) -> i32 {
+        if let Some(v) = self.probe(key) { return v; }
+        let v = compute();
+        self.store(key, v);
+        v
+    }
+
+    /// Drop every entry (a new network) and reset the counters.
+    pub fn clear(&self) {
+        for s in &self.slots { s.store(0, Ordering::Relaxed); }
+        self.probes.reset();
+        self.hits.reset();
+    }
+
+    pub fn stats(&self) -> EvalHashStats {
+        EvalHashStats { probes: self.probes.get(), hits: self.hits.get() }
+    }
+}
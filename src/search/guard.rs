@@ -0,0 +1,24 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static WORKER_PANICS: AtomicU64 = AtomicU64::new(0);
+
+/// Worker panics caught by `guarded` since the process started.
+pub fn worker_panics() -> u64 { WORKER_PANICS.load(Ordering::Relaxed) }
+
+/// Run one parallel search task, turning a panic into `None` so the remaining
+/// workers' results can still be used. The position (`fen` is only built on
+/// failure) and depth are logged so the bug can be reproduced.
+pub fn guarded<T>(worker: &str, depth: u32, fen: impl FnOnce() -> String, task: impl FnOnce() -> T) -> Option<T> {
+    match catch_unwind(AssertUnwindSafe(task)) {
+        Ok(v) => Some(v),
+        Err(payload) => {
+            WORKER_PANICS.fetch_add(1, Ordering::Relaxed);
+            let msg = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            eprintln!("info string {} worker panicked at depth {} fen {}: {}", worker, depth, fen(), msg);
+            None
+        }
+    }
+}
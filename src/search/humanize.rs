@@ -0,0 +1,39 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+use std::time::Duration;
+
+/// What the caller knows about the position before thinking.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MoveContext {
+    pub legal_moves: usize,
+    pub in_check: bool,
+    pub recapture: bool, // opponent just captured and we can take back on that square
+}
+
+/// Randomizes per-move think time inside the time manager's budget so that
+/// online play does not show flat, robot-like timing. The RNG is reseeded per game.
+pub struct Humanizer {
+    seed: u64,
+    games: u64,
+    rng: SmallRng,
+}
+
+impl Humanizer {
+    pub fn new(seed: u64) -> Self { Self { seed, games: 0, rng: SmallRng::seed_from_u64(seed) } }
+
+    pub fn new_game(&mut self) {
+        self.games += 1;
+        self.rng = SmallRng::seed_from_u64(self.seed ^ self.games.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    }
+
+    /// Never exceeds `budget`. Forced moves and recaptures are near-instant;
+    /// checks and narrow positions are treated as critical and get more time.
+    pub fn think_time(&mut self, budget: Duration, ctx: MoveContext) -> Duration {
+        if ctx.legal_moves <= 1 || (ctx.recapture && !ctx.in_check) {
+            return budget.mul_f32(self.rng.gen_range(0.02..0.08));
+        }
+        let critical = ctx.in_check || ctx.legal_moves <= 5;
+        let lo = if critical { 0.6 } else { 0.25 };
+        budget.mul_f32(self.rng.gen_range(lo..1.0))
+    }
+}
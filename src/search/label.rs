@@ -0,0 +1,73 @@
+//! Batch scoring of positions, the way training data is relabelled with a
+//! stronger searcher. Every position starts from a fresh searcher state (cleared
+//! hash, killers and history), so a label depends only on the position and the
+//! options, not on input order or on which worker thread picked it up.
+
+use crate::search::alphabeta::{SearchParams, Searcher};
+use crate::search::options::EngineOptions;
+use cozy_chess::{Board, Color};
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// How a position is scored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelMode {
+    /// Quiescence search from the position (static eval after captures settle)
+    Qsearch,
+    /// Full search to this depth
+    Depth(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Label {
+    pub fen: String,
+    /// Centipawns for the side to move (mates as `search::score` encodes them)
+    pub score: i32,
+    pub white_to_move: bool,
+    /// Search best move; `None` in qsearch mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bestmove: Option<String>,
+    /// Completed depth; 0 in qsearch mode
+    pub depth: u32,
+}
+
+impl Label {
+    /// The score from White's point of view.
+    pub fn white_score(&self) -> i32 { if self.white_to_move { self.score } else { -self.score } }
+}
+
+/// Score one position with `searcher`, clearing its state first.
+pub fn label_one(searcher: &mut Searcher, board: &Board, mode: LabelMode, params: SearchParams) -> Label {
+    searcher.new_game();
+    let (score, bestmove, depth) = match mode {
+        LabelMode::Qsearch => (searcher.qsearch_eval_cp(board), None, 0),
+        LabelMode::Depth(d) => {
+            let r = searcher.search_with_params(board, SearchParams { depth: d.max(1), movetime: None, max_nodes: None, clock: None, threads: 1, ..params });
+            (r.score.value(), r.bestmove, r.depth)
+        }
+    };
+    Label { fen: board.to_string(), score, white_to_move: board.side_to_move() == Color::White, bestmove, depth }
+}
+
+/// Score `boards` on `threads` workers, each with its own single-threaded searcher
+/// built from `opts`. Labels come back in input order.
+pub fn label_positions(boards: &[Board], opts: &EngineOptions, mode: LabelMode, threads: usize) -> crate::Result<Vec<Label>> {
+    let opts = EngineOptions { threads: 1, ..opts.clone() };
+    let params = opts.search_params();
+    let searchers = (0..threads.clamp(1, boards.len().max(1))).map(|_| opts.build_searcher()).collect::<crate::Result<Vec<_>>>()?;
+    let next = AtomicUsize::new(0);
+    let labels = Mutex::new(vec![None; boards.len()]);
+    std::thread::scope(|s| {
+        for mut searcher in searchers {
+            let (next, labels) = (&next, &labels);
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(board) = boards.get(i) else { break };
+                let label = label_one(&mut searcher, board, mode, params);
+                labels.lock().unwrap()[i] = Some(label);
+            });
+        }
+    });
+    Ok(labels.into_inner().unwrap().into_iter().flatten().collect())
+}
@@ -1,8 +1,26 @@
 pub mod eval;
+pub mod score;
 pub mod alphabeta;
+pub mod aspiration;
 pub mod zobrist;
 pub mod tt;
+pub mod eval_hash;
 pub mod see;
+pub mod profile;
+pub mod ttd;
+pub mod humanize;
+pub mod skill;
+pub mod wdl;
+pub mod timeman;
+pub mod guard;
+pub mod options;
+pub mod contempt;
+pub mod conversion;
+pub mod affinity;
+pub mod suite;
+pub mod label;
+pub mod texel;
+pub mod cluster;
 #[cfg(feature = "board-pleco")]
 pub mod alphabeta_pleco;
 #[cfg(feature = "board-pleco")]
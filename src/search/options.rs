@@ -0,0 +1,648 @@
+//! One set of engine options for every binary, so bench, compare_play and the UCI
+//! backends start from the same defaults and pick up new options together.
+
+use crate::error::{Result, TitanError};
+use crate::eval::nnue::loader::QuantNnue;
+use crate::eval::nnue::network::{QuantNetwork, SmallNet, SMALL_NET_PHASE};
+use crate::eval::nnue::Nnue;
+use crate::eval::policy::{PolicyNet, DEFAULT_POLICY_WEIGHT};
+use crate::search::affinity::CorePinning;
+use crate::search::contempt::Contempt;
+use crate::search::alphabeta::{HelperScheme, LmpTable, SearchParams, Searcher, SplitConfig, SplitPolicy, EXPLOSION_GUARD};
+use crate::search::timeman::{Calibration, TimeConfig};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Static evaluation behind the search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvalKind { #[default] Pst, Material, Nnue }
+
+impl std::str::FromStr for EvalKind {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pst" => Ok(EvalKind::Pst),
+            "material" => Ok(EvalKind::Material),
+            "nnue" => Ok(EvalKind::Nnue),
+            other => Err(format!("bad eval '{}' (pst|material|nnue)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for EvalKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self { EvalKind::Pst => "pst", EvalKind::Material => "material", EvalKind::Nnue => "nnue" })
+    }
+}
+
+/// Pruning and time-use profile. `Game` prunes hard and spends the clock for match
+/// play; `Analysis` keeps the PV unpruned so scores (and MultiPV lines) are exact
+/// rather than fast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchStyle { #[default] Game, Analysis }
+
+impl std::str::FromStr for SearchStyle {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "game" => Ok(SearchStyle::Game),
+            "analysis" => Ok(SearchStyle::Analysis),
+            other => Err(format!("bad style '{}' (game|analysis)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for SearchStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self { SearchStyle::Game => "game", SearchStyle::Analysis => "analysis" })
+    }
+}
+
+/// Groups of the `uci` option list, in print order.
+pub const UCI_GROUPS: [&str; 8] = ["Engine", "Search", "Pruning", "Parallel", "Draws", "Evaluation", "Play", "Reporting"];
+
+/// One UCI `option` line plus the group and help text of the `--dump-options` table.
+/// `kind` is everything after `type`, with the current value as the default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UciOption {
+    pub group: &'static str,
+    pub name: &'static str,
+    pub kind: String,
+    pub help: &'static str,
+}
+
+impl UciOption {
+    pub fn check(group: &'static str, name: &'static str, on: bool, help: &'static str) -> Self {
+        Self { group, name, kind: format!("check default {}", on), help }
+    }
+    pub fn spin(group: &'static str, name: &'static str, value: i64, min: i64, max: i64, help: &'static str) -> Self {
+        Self { group, name, kind: format!("spin default {} min {} max {}", value, min, max), help }
+    }
+    pub fn string(group: &'static str, name: &'static str, value: &str, help: &'static str) -> Self {
+        Self { group, name, kind: format!("string default {}", value), help }
+    }
+    pub fn combo(group: &'static str, name: &'static str, value: &str, vars: &[&str], help: &'static str) -> Self {
+        let vars: String = vars.iter().map(|v| format!(" var {}", v)).collect();
+        Self { group, name, kind: format!("combo default {}{}", value, vars), help }
+    }
+
+    /// Position of the group in `UCI_GROUPS` (unknown groups sort last).
+    pub fn group_rank(&self) -> usize {
+        UCI_GROUPS.iter().position(|g| *g == self.group).unwrap_or(UCI_GROUPS.len())
+    }
+
+    /// Orders options by group, keeping the given order within a group.
+    pub fn sort(options: &mut [UciOption]) { options.sort_by_key(UciOption::group_rank); }
+}
+
+impl std::fmt::Display for UciOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "option name {} type {}", self.name, self.kind)
+    }
+}
+
+/// Search and eval configuration shared by all binaries. Missing JSON fields take
+/// the defaults, so option files only need the keys they change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineOptions {
+    /// Depth limit; 0 iterates until another limit stops the search
+    pub depth: u32,
+    /// Time limit; with `depth` too the search stops at whichever comes first
+    pub movetime_ms: Option<u64>,
+    /// Iterations always completed, even past the movetime (0 = none)
+    pub min_depth: u32,
+    /// Nodes an aspirated root move may take, as a multiple of the previous
+    /// iteration's; see `SearchParams::explosion_guard`. 0 = off
+    pub explosion_guard: u32,
+    /// Clock time (ms) kept back per move for GUI and transport latency
+    pub move_overhead_ms: u64,
+    pub threads: usize,
+    /// Core pinning for search threads (off by default)
+    pub affinity: CorePinning,
+    /// Stack size of each search worker in MB (see `affinity::thread_pool`)
+    pub stack_mb: usize,
+    pub hash_mb: usize,
+    pub use_tt: bool,
+    pub order_captures: bool,
+    pub use_history: bool,
+    pub use_killers: bool,
+    pub use_lmr: bool,
+    /// Extra LMR plies for moves ordered 8th or later in the game style (0 = off)
+    pub lmr_late_extra: u32,
+    /// History points per ply of LMR adjustment, 0 = fixed reductions (cozy backend;
+    /// see `SearchParams::lmr_history_div`, `LMR_HISTORY_DIV` is a starting point)
+    pub lmr_history_div: i32,
+    /// LMR of late captures that lose material by SEE (cozy backend)
+    pub lmr_captures: bool,
+    pub use_nullmove: bool,
+    /// Relax null move and LMR when the side to move has a strong king attack
+    pub king_danger: bool,
+    /// Predict PV/cut/all nodes to drive IIR, cut-node LMR and the null-move skip
+    pub node_types: bool,
+    /// SEE-gate quiet killer/countermove bonuses and reduce hanging quiets more (cozy backend)
+    pub see_quiets: bool,
+    /// SEE pruning of losing quiets and captures at low depth (cozy backend; see
+    /// `SearchParams::see_prune_quiets`)
+    pub see_prune_quiets: bool,
+    pub see_prune_captures: bool,
+    pub use_aspiration: bool,
+    pub aspiration_window_cp: i32,
+    /// Adapt the window to the game's aspiration fails and score swings, starting
+    /// from `aspiration_window_cp`; see `search::aspiration`
+    pub adaptive_window: bool,
+    pub max_extensions: u32,
+    /// One search thread and node budgets instead of time limits, so a GUI user's
+    /// report reproduces from the position and `go` limits (cozy backend)
+    pub deterministic: bool,
+    /// In-tree split policy and thresholds (threads > 1)
+    pub split: SplitPolicy,
+    pub split_depth: u32,
+    pub split_moves: usize,
+    /// Safe SMP profile: helper threads keep the main thread's pruning (pleco backend;
+    /// see `PlecoSearcher::set_smp_safe`)
+    pub smp_safe: bool,
+    /// Master seed for the Lazy SMP helper profiles, 0 = fixed scheme (pleco
+    /// backend; see `HelperProfile::derive`)
+    pub smp_seed: u64,
+    /// Lazy SMP helpers rotate their move order or skip iterations (pleco backend;
+    /// see `HelperScheme`)
+    pub smp_helpers: HelperScheme,
+    /// NNUE eval hash in MB, 0 = off (see `search::eval_hash`)
+    pub eval_hash_mb: usize,
+    /// Late-move pruning move counts for diversified helpers (pleco backend)
+    pub lmp: LmpTable,
+    /// Futility pruning of quiets at depth <= 2 on every thread (pleco backend)
+    pub futility: bool,
+    /// Late-move pruning with `lmp` on every thread (pleco backend)
+    pub lmp_pruning: bool,
+    /// Value of a draw to the opponent (cp); see `search::contempt`
+    pub contempt: i32,
+    /// Scale contempt with the score and game phase (match play)
+    pub dynamic_contempt: bool,
+    /// Convert TB-won roots without contempt or pruning; see `search::conversion`
+    pub conversion: bool,
+    pub style: SearchStyle,
+    pub eval: EvalKind,
+    /// Dense network (PIENNUE1), used by `eval = nnue` when no quantized file is set
+    pub nnue_file: Option<String>,
+    /// Quantized network (PIENNQ01); preferred over `nnue_file`
+    pub nnue_quant_file: Option<String>,
+    /// Small quantized network for endgames and low time, next to the main one
+    pub nnue_small_file: Option<String>,
+    /// Game phase (0..24) at or below which the small network evaluates
+    pub small_net_phase: u32,
+    /// Remaining clock (ms) below which the small network evaluates every position; 0 = never
+    pub small_net_time_ms: u64,
+    /// 0..100, share of the NNUE score when blending with PST
+    pub eval_blend: u8,
+    /// Policy net (PIEPOL01) ordering quiets before history has data; any eval
+    pub policy_file: Option<String>,
+    /// Ordering points of the policy's favourite move; 0 = policy off
+    pub policy_weight: u32,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            depth: 6,
+            movetime_ms: None,
+            min_depth: 0,
+            explosion_guard: EXPLOSION_GUARD,
+            move_overhead_ms: 10,
+            threads: 1,
+            affinity: CorePinning::Off,
+            stack_mb: 16,
+            hash_mb: 64,
+            use_tt: true,
+            order_captures: true,
+            use_history: true,
+            use_killers: true,
+            use_lmr: true,
+            lmr_late_extra: 0,
+            lmr_history_div: 0,
+            lmr_captures: false,
+            use_nullmove: true,
+            king_danger: true,
+            node_types: false,
+            see_quiets: true,
+            see_prune_quiets: false,
+            see_prune_captures: false,
+            use_aspiration: true,
+            aspiration_window_cp: 30,
+            adaptive_window: true,
+            max_extensions: 2,
+            deterministic: false,
+            split: SplitPolicy::Ybwc,
+            split_depth: SplitConfig::default().min_depth,
+            split_moves: SplitConfig::default().min_moves,
+            smp_safe: false,
+            smp_seed: 0,
+            smp_helpers: HelperScheme::Rotate,
+            eval_hash_mb: 8,
+            lmp: LmpTable::default(),
+            futility: false,
+            lmp_pruning: false,
+            contempt: 0,
+            dynamic_contempt: false,
+            conversion: true,
+            style: SearchStyle::Game,
+            eval: EvalKind::Pst,
+            nnue_file: None,
+            nnue_quant_file: None,
+            nnue_small_file: None,
+            small_net_phase: SMALL_NET_PHASE,
+            small_net_time_ms: 0,
+            eval_blend: 100,
+            policy_file: None,
+            policy_weight: DEFAULT_POLICY_WEIGHT,
+        }
+    }
+}
+
+static GLOBAL: RwLock<Option<EngineOptions>> = RwLock::new(None);
+
+impl EngineOptions {
+    /// Process-wide defaults: what `set_global` installed, else `Default`.
+    pub fn global() -> Self { Self::global_or(Self::default()) }
+
+    /// What `set_global` installed, else `fallback`.
+    pub fn global_or(fallback: Self) -> Self {
+        GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or(fallback)
+    }
+
+    /// The cozy UCI engine's defaults: as `Default`, but killers, LMR, null move and
+    /// aspiration off, as that engine has always played.
+    pub fn cozy_uci() -> Self {
+        Self { use_killers: false, use_lmr: false, use_nullmove: false, use_aspiration: false, ..Self::default() }
+    }
+
+    /// Replace the process-wide defaults (e.g. from an `--options` file at startup).
+    pub fn set_global(opts: EngineOptions) {
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(opts);
+    }
+
+    pub fn load_json(path: &str) -> Result<Self> {
+        crate::error::read_json(path)
+    }
+
+    /// Set one option by its spec key (see `describe`). Keys are case-insensitive.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let v = value.trim();
+        let num = |v: &str| v.parse::<i64>().map_err(|_| TitanError::option(key, format!("bad number '{}'", v)));
+        let flag = |v: &str| match v.to_lowercase().as_str() {
+            "on" | "true" | "1" | "yes" => Ok(true),
+            "off" | "false" | "0" | "no" => Ok(false),
+            _ => Err(TitanError::option(key, format!("bad boolean '{}'", v))),
+        };
+        let file = |v: &str| if v.is_empty() { None } else { Some(v.to_string()) };
+        match key.trim().to_lowercase().as_str() {
+            "depth" => self.depth = num(v)?.clamp(0, 99) as u32,
+            "min_depth" | "mindepth" => self.min_depth = num(v)?.clamp(0, 99) as u32,
+            "explosion_guard" | "explosionguard" => self.explosion_guard = num(v)?.clamp(0, 64) as u32,
+            "move_overhead" | "moveoverhead" | "move overhead" => self.move_overhead_ms = num(v)?.clamp(0, 10_000) as u64,
+            "movetime" => self.movetime_ms = if v.is_empty() { None } else { Some(num(v)?.max(0) as u64) },
+            "threads" => self.threads = num(v)?.clamp(1, 512) as usize,
+            "affinity" => self.affinity = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            "stack" | "stack_mb" | "threadstack" => self.stack_mb = num(v)?.clamp(1, 1024) as usize,
+            "hash" => self.hash_mb = num(v)?.clamp(1, 16384) as usize,
+            "eval_hash" | "evalhash" => self.eval_hash_mb = num(v)?.clamp(0, 1024) as usize,
+            "tt" => self.use_tt = flag(v)?,
+            "captures" => self.order_captures = flag(v)?,
+            "history" => self.use_history = flag(v)?,
+            "killers" => self.use_killers = flag(v)?,
+            "lmr" => self.use_lmr = flag(v)?,
+            "lmr_late_extra" | "lmrlateextra" => self.lmr_late_extra = num(v)?.clamp(0, 3) as u32,
+            "lmr_history_div" | "lmrhistorydiv" => self.lmr_history_div = num(v)?.clamp(0, 100_000) as i32,
+            "lmr_captures" | "lmrcaptures" => self.lmr_captures = flag(v)?,
+            "nullmove" => self.use_nullmove = flag(v)?,
+            "king_danger" | "kingdanger" => self.king_danger = flag(v)?,
+            "node_types" | "nodetypes" => self.node_types = flag(v)?,
+            "see_quiets" | "seequiets" => self.see_quiets = flag(v)?,
+            "see_prune_quiets" | "seeprunequiets" => self.see_prune_quiets = flag(v)?,
+            "see_prune_captures" | "seeprunecaptures" => self.see_prune_captures = flag(v)?,
+            "aspiration" => self.use_aspiration = flag(v)?,
+            "window" => self.aspiration_window_cp = num(v)?.clamp(1, 1000) as i32,
+            "adaptive_window" | "adaptivewindow" => self.adaptive_window = flag(v)?,
+            "extensions" => self.max_extensions = num(v)?.clamp(0, 16) as u32,
+            "deterministic" => self.deterministic = flag(v)?,
+            "split" => self.split = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            "split_depth" | "splitdepth" => self.split_depth = num(v)?.clamp(1, 64) as u32,
+            "split_moves" | "splitmoves" => self.split_moves = num(v)?.clamp(2, 256) as usize,
+            "smp_safe" | "smpsafe" => self.smp_safe = flag(v)?,
+            "smp_seed" | "smpseed" => self.smp_seed = num(v)?.clamp(0, i32::MAX as i64) as u64,
+            "smp_helpers" | "smphelpers" => self.smp_helpers = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            "lmp" => self.lmp = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            "futility" => self.futility = flag(v)?,
+            "lmp_pruning" | "lmppruning" => self.lmp_pruning = flag(v)?,
+            "contempt" => self.contempt = num(v)?.clamp(-200, 200) as i32,
+            "dynamic_contempt" | "dynamiccontempt" => self.dynamic_contempt = flag(v)?,
+            "conversion" => self.conversion = flag(v)?,
+            "style" => self.style = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            // The standard UCI switch for the same thing
+            "uci_analysemode" => self.style = if flag(v)? { SearchStyle::Analysis } else { SearchStyle::Game },
+            "eval" => self.eval = v.parse().map_err(|e: String| TitanError::option(key, e))?,
+            "nnue" => self.nnue_file = file(v),
+            "nnue_quant" => self.nnue_quant_file = file(v),
+            "nnue_small" | "nnuesmallfile" => self.nnue_small_file = file(v),
+            "small_net_phase" | "smallnetphase" => self.small_net_phase = num(v)?.clamp(0, 24) as u32,
+            "small_net_time" | "smallnettime" => self.small_net_time_ms = num(v)?.clamp(0, 3_600_000) as u64,
+            "blend" => self.eval_blend = num(v)?.clamp(0, 100) as u8,
+            "policy" | "policyfile" => self.policy_file = file(v),
+            "policy_weight" | "policyweight" => self.policy_weight = num(v)?.clamp(0, 1000) as u32,
+            other => return Err(TitanError::UnknownOption(other.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Apply comma-separated `key=value` pairs, e.g. "lmr=off,hash=128".
+    pub fn apply_spec(&mut self, spec: &str) -> Result<()> {
+        for kv in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (k, v) = kv.split_once('=').ok_or_else(|| TitanError::option(kv, "expected key=value"))?;
+            self.set(k, v)?;
+        }
+        Ok(())
+    }
+
+    /// Every option in `apply_spec` syntax, so a run can be reproduced from its log.
+    pub fn describe(&self) -> String {
+        let on = |b: bool| if b { "on" } else { "off" };
+        let mut s = format!("depth={},threads={},hash={},tt={},captures={},history={},killers={},lmr={},nullmove={},aspiration={},window={},extensions={},deterministic={},style={},eval={}",
+            self.depth, self.threads, self.hash_mb, on(self.use_tt), on(self.order_captures), on(self.use_history), on(self.use_killers), on(self.use_lmr),
+            on(self.use_nullmove), on(self.use_aspiration), self.aspiration_window_cp, self.max_extensions, on(self.deterministic), self.style, self.eval);
+        if let Some(n) = &self.nnue_file { s.push_str(&format!(",nnue={}", n)); }
+        if let Some(n) = &self.nnue_quant_file { s.push_str(&format!(",nnue_quant={}", n)); }
+        if let Some(n) = &self.nnue_small_file { s.push_str(&format!(",nnue_small={},small_net_phase={},small_net_time={}", n, self.small_net_phase, self.small_net_time_ms)); }
+        if self.eval == EvalKind::Nnue { s.push_str(&format!(",blend={}", self.eval_blend)); }
+        if let Some(p) = &self.policy_file { s.push_str(&format!(",policy={}", p)); }
+        if self.policy_weight != DEFAULT_POLICY_WEIGHT { s.push_str(&format!(",policy_weight={}", self.policy_weight)); }
+        if let Some(mt) = self.movetime_ms { s.push_str(&format!(",movetime={}", mt)); }
+        if self.split_config() != SplitConfig::default() {
+            s.push_str(&format!(",split={},split_depth={},split_moves={}", self.split, self.split_depth, self.split_moves));
+        }
+        if self.contempt_config() != Contempt::default() {
+            s.push_str(&format!(",contempt={},dynamic_contempt={}", self.contempt, on(self.dynamic_contempt)));
+        }
+        if !self.king_danger { s.push_str(",king_danger=off"); }
+        if self.node_types { s.push_str(",node_types=on"); }
+        if !self.see_quiets { s.push_str(",see_quiets=off"); }
+        if self.lmr_late_extra != 0 { s.push_str(&format!(",lmr_late_extra={}", self.lmr_late_extra)); }
+        if self.lmr_history_div != 0 { s.push_str(&format!(",lmr_history_div={}", self.lmr_history_div)); }
+        if self.lmr_captures { s.push_str(",lmr_captures=on"); }
+        if self.see_prune_quiets { s.push_str(",see_prune_quiets=on"); }
+        if self.see_prune_captures { s.push_str(",see_prune_captures=on"); }
+        if !self.conversion { s.push_str(",conversion=off"); }
+        if !self.adaptive_window { s.push_str(",adaptive_window=off"); }
+        if self.smp_safe { s.push_str(",smp_safe=on"); }
+        if self.eval_hash_mb != 8 { s.push_str(&format!(",eval_hash={}", self.eval_hash_mb)); }
+        if self.smp_seed != 0 { s.push_str(&format!(",smp_seed={}", self.smp_seed)); }
+        if self.smp_helpers != HelperScheme::Rotate { s.push_str(&format!(",smp_helpers={}", self.smp_helpers)); }
+        if self.lmp != LmpTable::default() { s.push_str(&format!(",lmp={}", self.lmp)); }
+        if self.futility { s.push_str(",futility=on"); }
+        if self.lmp_pruning { s.push_str(",lmp_pruning=on"); }
+        if self.affinity != CorePinning::Off { s.push_str(&format!(",affinity={}", self.affinity)); }
+        if self.stack_mb != 16 { s.push_str(&format!(",stack={}", self.stack_mb)); }
+        if self.min_depth != 0 { s.push_str(&format!(",min_depth={}", self.min_depth)); }
+        if self.explosion_guard != EXPLOSION_GUARD { s.push_str(&format!(",explosion_guard={}", self.explosion_guard)); }
+        if self.move_overhead_ms != 10 { s.push_str(&format!(",move_overhead={}", self.move_overhead_ms)); }
+        s
+    }
+
+    /// UCI options for every shared knob, with the current values as defaults.
+    pub fn uci_options(&self) -> Vec<UciOption> {
+        use UciOption as O;
+        vec![
+            O::check("Search", "tt", self.use_tt, "Transposition table probes and stores"),
+            O::check("Search", "captures", self.order_captures, "Order captures first (MVV-LVA)"),
+            O::check("Search", "history", self.use_history, "History heuristic in move ordering"),
+            O::check("Search", "killers", self.use_killers, "Killer moves in move ordering"),
+            O::check("Search", "aspiration", self.use_aspiration, "Aspiration windows around the previous score"),
+            O::spin("Search", "window", self.aspiration_window_cp.into(), 1, 1000, "Aspiration half-window (cp)"),
+            O::check("Search", "AdaptiveWindow", self.adaptive_window, "Widen or narrow the window with the game's fail rate and score swings"),
+            O::spin("Search", "MinDepth", self.min_depth.into(), 0, 99, "Iterations completed even past the movetime (not the hard clock limit), 0 = none"),
+            O::spin("Search", "Move Overhead", self.move_overhead_ms as i64, 0, 10_000, "Clock time (ms) kept back per move for GUI latency"),
+            O::spin("Search", "ExplosionGuard", self.explosion_guard.into(), 0, 64, "Root move node cap under an aspiration window, x previous iteration, 0 = off"),
+            O::spin("Search", "EvalHash", self.eval_hash_mb as i64, 0, 1024, "NNUE eval hash (MB), 0 = off"),
+            O::string("Evaluation", "NNUESmallFile", self.nnue_small_file.as_deref().unwrap_or(""), "Small quantized network for endgames and low time (PIENNQ01)"),
+            O::spin("Evaluation", "SmallNetPhase", self.small_net_phase.into(), 0, 24, "Game phase at or below which the small network evaluates"),
+            O::spin("Evaluation", "SmallNetTime", self.small_net_time_ms as i64, 0, 3_600_000, "Clock (ms) below which the small network evaluates everything, 0 = off"),
+            O::string("Search", "PolicyFile", self.policy_file.as_deref().unwrap_or(""), "Policy net ordering quiet moves before history has data (PIEPOL01)"),
+            O::spin("Search", "PolicyWeight", self.policy_weight.into(), 0, 1000, "Ordering points of the policy's favourite move, 0 = off"),
+            O::check("Search", "Deterministic", self.deterministic, "Reproducible search: one thread, time limits as node budgets (cozy backend)"),
+            O::check("Search", "UCI_AnalyseMode", self.style == SearchStyle::Analysis, "Analysis style: no pruning along the PV"),
+            O::check("Pruning", "lmr", self.use_lmr, "Late move reductions"),
+            O::spin("Pruning", "LMRLateExtra", self.lmr_late_extra.into(), 0, 3, "Extra LMR plies for moves ordered 8th or later (game style)"),
+            O::spin("Pruning", "LMRHistoryDiv", self.lmr_history_div.into(), 0, 100_000, "History points per ply of LMR adjustment, 0 = fixed (cozy)"),
+            O::check("Pruning", "LMRCaptures", self.lmr_captures, "Reduce late captures that lose material (cozy)"),
+            O::check("Pruning", "nullmove", self.use_nullmove, "Null-move pruning"),
+            O::check("Pruning", "KingDanger", self.king_danger, "Relax null move and LMR under king attack"),
+            O::check("Pruning", "NodeTypes", self.node_types, "Expected cut/all nodes drive IIR, LMR and null move"),
+            O::check("Pruning", "Futility", self.futility, "Futility pruning of quiets at depth <= 2 off the PV (pleco)"),
+            O::check("Pruning", "LMPPruning", self.lmp_pruning, "Late-move pruning off the PV on every thread (pleco)"),
+            O::check("Pruning", "SEEQuiets", self.see_quiets, "No killer bonus for hanging quiets; reduce them more (cozy)"),
+            O::check("Pruning", "SEEPruneQuiets", self.see_prune_quiets, "Skip quiets losing material at low depth (cozy)"),
+            O::check("Pruning", "SEEPruneCaptures", self.see_prune_captures, "Skip captures losing material at low depth (cozy)"),
+            O::combo("Parallel", "Split", &self.split.to_string(), &["ybwc", "jamboree"], "In-tree split policy (threads > 1)"),
+            O::spin("Parallel", "SplitDepth", self.split_depth.into(), 1, 64, "Minimum remaining depth to split a node"),
+            O::spin("Parallel", "SplitMoves", self.split_moves as i64, 2, 256, "Minimum moves at a node to split it"),
+            O::check("Parallel", "SMPSafe", self.smp_safe, "Helpers keep the main thread's pruning (pleco)"),
+            O::spin("Parallel", "SMPSeed", self.smp_seed as i64, 0, i32::MAX.into(), "Seed for Lazy SMP helper diversification, 0 = fixed (pleco)"),
+            O::combo("Parallel", "SMPHelpers", &self.smp_helpers.to_string(), &["rotate", "skip"], "Lazy SMP helpers: rotated move order or staggered depths (pleco)"),
+            O::string("Parallel", "Affinity", &self.affinity.to_string(), "Pin search threads: off, auto or a core list"),
+            O::spin("Parallel", "ThreadStack", self.stack_mb as i64, 1, 1024, "Stack size of each search thread (MB)"),
+            O::spin("Draws", "Contempt", self.contempt.into(), -200, 200, "Draw value to the opponent (cp)"),
+            O::check("Draws", "DynamicContempt", self.dynamic_contempt, "Scale contempt with score and phase"),
+            O::check("Draws", "Conversion", self.conversion, "No contempt or pruning in won endgames (KQ, KR, KBN vs K)"),
+        ]
+    }
+
+    /// `uci_options` as `option` lines.
+    pub fn uci_option_lines(&self) -> Vec<String> {
+        self.uci_options().iter().map(ToString::to_string).collect()
+    }
+
+    pub fn search_params(&self) -> SearchParams {
+        SearchParams {
+            depth: self.depth,
+            min_depth: self.min_depth,
+            movetime: self.movetime_ms.map(Duration::from_millis),
+            threads: if self.deterministic { 1 } else { self.threads.max(1) },
+            use_tt: self.use_tt,
+            order_captures: self.order_captures,
+            use_history: self.use_history,
+            use_killers: self.use_killers,
+            use_lmr: self.use_lmr,
+            use_nullmove: self.use_nullmove,
+            use_aspiration: self.use_aspiration,
+            aspiration_window_cp: self.aspiration_window_cp,
+            adaptive_window: self.adaptive_window,
+            max_extensions: self.max_extensions,
+            deterministic: self.deterministic,
+            protect_pv: self.style == SearchStyle::Analysis,
+            lmr_late_extra: self.late_move_extra(),
+            lmr_history_div: self.lmr_history_div,
+            lmr_captures: self.lmr_captures,
+            king_danger: self.king_danger,
+            node_types: self.node_types,
+            see_quiets: self.see_quiets,
+            see_prune_quiets: self.see_prune_quiets,
+            see_prune_captures: self.see_prune_captures,
+            split: self.split_config(),
+            contempt: self.contempt_config(),
+            conversion: self.conversion,
+            explosion_guard: self.explosion_guard,
+            ..Default::default()
+        }
+    }
+
+    pub fn contempt_config(&self) -> Contempt {
+        Contempt { static_cp: self.contempt, dynamic: self.dynamic_contempt }
+    }
+
+    pub fn split_config(&self) -> SplitConfig {
+        SplitConfig { policy: self.split, min_depth: self.split_depth, min_moves: self.split_moves }
+    }
+
+    /// `lmr_late_extra` under the current style: the analysis style keeps it off.
+    pub fn late_move_extra(&self) -> u32 { if self.style == SearchStyle::Game { self.lmr_late_extra } else { 0 } }
+
+    /// Clock settings for the style and move overhead, calibrated to this machine if
+    /// a startup probe ran (`Calibration::set_global`). Analysis never stretches a
+    /// move past its soft budget; game play keeps the panic-time headroom.
+    pub fn time_config(&self) -> TimeConfig {
+        let base = TimeConfig { move_overhead: Duration::from_millis(self.move_overhead_ms), ..TimeConfig::default() };
+        let cfg = match self.style {
+            SearchStyle::Game => base,
+            SearchStyle::Analysis => TimeConfig { hard_factor: 1.0, ..base },
+        };
+        match Calibration::global() { Some(c) => cfg.calibrated(&c), None => cfg }
+    }
+
+    /// Load the configured network, if the eval needs one.
+    fn load_networks(&self) -> Result<(Option<QuantNnue>, Option<Nnue>)> {
+        if self.eval != EvalKind::Nnue { return Ok((None, None)); }
+        if let Some(q) = &self.nnue_quant_file {
+            let model = QuantNnue::load_quantized(q)?;
+            QuantNetwork::validate(&model)?;
+            return Ok((Some(model), None));
+        }
+        match &self.nnue_file {
+            Some(d) => Ok((None, Some(Nnue::load(d)?))),
+            None => Err(TitanError::option("eval", "nnue needs nnue=<file> or nnue_quant=<file>")),
+        }
+    }
+
+    /// Load the small network, if the eval is NNUE and one is configured.
+    fn load_small_network(&self) -> Result<Option<QuantNnue>> {
+        match &self.nnue_small_file {
+            Some(f) if self.eval == EvalKind::Nnue => {
+                let model = QuantNnue::load_quantized(f)?;
+                QuantNetwork::validate(&model)?;
+                Ok(Some(model))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Load the policy net, if one is configured (it works with every eval).
+    pub fn load_policy(&self) -> Result<Option<PolicyNet>> {
+        self.policy_file.as_ref().map(PolicyNet::load).transpose()
+    }
+
+    /// Whether a search with `remaining` on our clock should evaluate with the small
+    /// network only (`small_net_time`).
+    pub fn small_net_only(&self, remaining: Option<Duration>) -> bool {
+        self.small_net_time_ms > 0 && remaining.is_some_and(|r| r < Duration::from_millis(self.small_net_time_ms))
+    }
+
+    /// A cozy searcher with hash, eval and networks applied; search toggles travel
+    /// in `search_params`.
+    pub fn build_searcher(&self) -> Result<Searcher> {
+        let (quant, dense) = self.load_networks()?;
+        let small = self.load_small_network()?;
+        let policy = self.load_policy()?;
+        let nnue = self.eval == EvalKind::Nnue;
+        let mut b = Searcher::builder()
+            .hash_mb(self.hash_mb)
+            .policy_weight(self.policy_weight)
+            .eval_hash_mb(self.eval_hash_mb)
+            .material_eval(self.eval == EvalKind::Material)
+            .use_nnue(nnue)
+            .eval_blend_percent(if nnue { self.eval_blend } else { 100 });
+        if let Some(model) = quant { b = b.nnue_quant(model); }
+        if let Some(nn) = dense { b = b.nnue(nn); }
+        if let Some(model) = small { b = b.nnue_small(model, self.small_net_phase); }
+        if let Some(net) = policy { b = b.policy(net); }
+        b.build()
+    }
+
+    /// Apply hash and eval settings to an existing searcher (keeps its heuristics).
+    pub fn configure(&self, s: &mut Searcher) -> Result<()> {
+        let (quant, dense) = self.load_networks()?;
+        let small = self.load_small_network()?;
+        s.set_tt_capacity_mb(self.hash_mb);
+        s.set_eval_hash_mb(self.eval_hash_mb);
+        s.set_material_eval(self.eval == EvalKind::Material);
+        if let Some(model) = quant { s.set_nnue_quant_model(model); }
+        if let Some(nn) = dense { s.set_nnue_network(Some(nn)); }
+        s.set_nnue_small(small.map(|m| SmallNet::new(m, self.small_net_phase)));
+        s.set_use_nnue(self.eval == EvalKind::Nnue);
+        s.set_eval_blend_percent(self.eval_blend);
+        s.set_policy(self.load_policy()?);
+        s.set_policy_weight(self.policy_weight);
+        Ok(())
+    }
+
+    /// The pleco searcher equivalent. Pleco only has a material or quantized-NNUE
+    /// eval, so `pst` maps to material and a dense network is ignored.
+    #[cfg(feature = "board-pleco")]
+    pub fn build_pleco_searcher(&self) -> Result<crate::search::alphabeta_pleco::PlecoSearcher> {
+        use crate::search::alphabeta_pleco::PlecoSearcher;
+        let mut s = PlecoSearcher::default();
+        s.set_threads(self.threads);
+        s.set_tt_capacity_mb(self.hash_mb);
+        s.set_heuristics(self.use_killers, self.use_lmr, self.use_nullmove, self.use_aspiration, self.aspiration_window_cp);
+        s.set_lmr_late_extra(self.late_move_extra());
+        s.set_split(self.split_config());
+        s.set_contempt(self.contempt_config());
+        s.set_king_danger(self.king_danger);
+        s.set_node_types(self.node_types);
+        s.set_conversion(self.conversion);
+        s.set_smp_safe(self.smp_safe);
+        s.set_smp_seed(self.smp_seed);
+        s.set_helper_scheme(self.smp_helpers);
+        s.set_eval_hash_mb(self.eval_hash_mb);
+        s.set_lmp(self.lmp);
+        s.set_futility(self.futility);
+        s.set_lmp_pruning(self.lmp_pruning);
+        s.set_min_depth(self.min_depth);
+        s.set_explosion_guard(self.explosion_guard);
+        s.set_adaptive_window(self.adaptive_window);
+        s.set_policy(self.load_policy()?);
+        s.set_policy_weight(self.policy_weight);
+        self.configure_pleco_eval(&mut s)?;
+        Ok(s)
+    }
+
+    /// Apply `eval` (and with NNUE, the quantized and small networks) to a pleco searcher.
+    #[cfg(feature = "board-pleco")]
+    pub fn configure_pleco_eval(&self, s: &mut crate::search::alphabeta_pleco::PlecoSearcher) -> Result<()> {
+        use crate::search::alphabeta_pleco::PlecoEvalMode;
+        match self.eval {
+            EvalKind::Pst => { s.set_eval_mode(PlecoEvalMode::Pst); return Ok(()); }
+            EvalKind::Material => { s.set_eval_mode(PlecoEvalMode::Material); return Ok(()); }
+            EvalKind::Nnue => {}
+        }
+        if let Some(q) = &self.nnue_quant_file {
+            let model = QuantNnue::load_quantized(q)?;
+            QuantNetwork::validate(&model)?;
+            s.set_nnue_quant_model(model);
+        }
+        s.set_nnue_small(self.load_small_network()?.map(|m| SmallNet::new(m, self.small_net_phase)));
+        s.set_eval_mode(PlecoEvalMode::Nnue);
+        Ok(())
+    }
+}
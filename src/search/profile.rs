@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+/// Coarse per-phase counters collected by `Searcher` when profiling is enabled.
+/// Timings are inclusive wall-clock slices; they do not sum to total search time.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SearchProfile {
+    pub movegen: Duration,
+    pub ordering: Duration,
+    pub eval: Duration,
+    pub tt: Duration,
+    pub main_nodes: u64,
+    pub qsearch_nodes: u64,
+}
+
+impl SearchProfile {
+    pub fn merge(&mut self, other: &SearchProfile) {
+        self.movegen += other.movegen;
+        self.ordering += other.ordering;
+        self.eval += other.eval;
+        self.tt += other.tt;
+        self.main_nodes += other.main_nodes;
+        self.qsearch_nodes += other.qsearch_nodes;
+    }
+}
+
+/// A relaxed diagnostics counter shared across threads. Without the `search-stats`
+/// feature it is empty and `bump` compiles away, keeping atomics off the hot path.
+#[derive(Default, Debug)]
+pub struct StatCounter(#[cfg(feature = "search-stats")] std::sync::atomic::AtomicU64);
+
+impl StatCounter {
+    #[inline]
+    pub fn bump(&self) {
+        #[cfg(feature = "search-stats")]
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+    /// The count so far; always 0 without `search-stats`.
+    pub fn get(&self) -> u64 {
+        #[cfg(feature = "search-stats")]
+        return self.0.load(std::sync::atomic::Ordering::Relaxed);
+        #[cfg(not(feature = "search-stats"))]
+        0
+    }
+    pub fn reset(&self) {
+        #[cfg(feature = "search-stats")]
+        self.0.store(0, std::sync::atomic::Ordering::Relaxed);
+    }
+}
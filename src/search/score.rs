@@ -0,0 +1,81 @@
+//! Search scores: centipawns from the side to move's view, with mates encoded as
+//! `MATE_SCORE` minus the distance to mate in plies. The constants here are the
+//! one place the encoding is defined; search, TT and UCI output go through `Score`.
+
+use serde::{Deserialize, Serialize};
+
+/// Score of delivering mate on the board; a mate in `n` plies is `MATE_SCORE - n`.
+pub const MATE_SCORE: i32 = 30_000;
+/// Longest mate (in plies) the encoding can tell apart from an ordinary score.
+pub const MAX_MATE_PLY: i32 = 1_000;
+/// Deepest ply a search line may reach, qsearch included. A node there returns its
+/// static eval, which bounds the recursion (and stack use) whatever extensions add;
+/// per-ply tables are sized to it.
+pub const MAX_PLY: usize = 128;
+/// Scores beyond this are mates found within the search (`MATE_SCORE - plies`).
+pub const MATE_BOUND: i32 = MATE_SCORE - MAX_MATE_PLY;
+/// Score of a drawn position before contempt.
+pub const DRAW_SCORE: i32 = 0;
+
+/// A search score (see the module docs).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Score(i32);
+
+impl Score {
+    pub const DRAW: Score = Score(DRAW_SCORE);
+
+    pub const fn cp(cp: i32) -> Score { Score(cp) }
+    /// An already encoded value, as returned by `value`.
+    pub const fn from_value(value: i32) -> Score { Score(value) }
+    /// The side to move mates in `ply` plies.
+    pub const fn mate_in(ply: i32) -> Score { Score(MATE_SCORE - ply) }
+    /// The side to move is mated in `ply` plies.
+    pub const fn mated_in(ply: i32) -> Score { Score(-MATE_SCORE + ply) }
+
+    /// The encoded value: centipawns, or a mate as described above.
+    pub const fn value(self) -> i32 { self.0 }
+    pub const fn is_mate(self) -> bool { self.0 > MATE_BOUND || self.0 < -MATE_BOUND }
+
+    /// Moves to mate: positive when the side to move mates, negative when it is
+    /// mated; `None` for ordinary scores.
+    pub const fn mate_moves(self) -> Option<i32> {
+        if self.0 > MATE_BOUND { Some((MATE_SCORE - self.0 + 1) / 2) }
+        else if self.0 < -MATE_BOUND { Some(-(MATE_SCORE + self.0) / 2) }
+        else { None }
+    }
+
+    /// The score as stored in the TT: a mate counted from the node at `ply`, not
+    /// the root, so a probe from another ply reports the right distance.
+    pub const fn to_tt(self, ply: i32) -> Score {
+        if self.0 > MATE_BOUND { Score(self.0 + ply) } else if self.0 < -MATE_BOUND { Score(self.0 - ply) } else { self }
+    }
+
+    /// Inverse of `to_tt` for a probe at `ply`.
+    pub const fn from_tt(self, ply: i32) -> Score {
+        if self.0 > MATE_BOUND { Score(self.0 - ply) } else if self.0 < -MATE_BOUND { Score(self.0 + ply) } else { self }
+    }
+
+    /// The UCI `score` argument: `cp <n>` or `mate <moves>`.
+    pub fn to_uci(self) -> String {
+        match self.mate_moves() {
+            Some(m) => format!("mate {}", m),
+            None => format!("cp {}", self.0),
+        }
+    }
+}
+
+impl std::ops::Neg for Score {
+    type Output = Score;
+    fn neg(self) -> Score { Score(-self.0) }
+}
+
+impl std::fmt::Display for Score {
+    /// Centipawns with sign, or `#n` / `#-n` for mates.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mate_moves() {
+            Some(m) => f.pad(&format!("#{}", m)),
+            None => f.pad(&format!("{:+}", self.0)),
+        }
+    }
+}
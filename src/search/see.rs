@@ -80,3 +80,60 @@ pub fn see_gain_cp(board: &Board, mv: cozy_chess::Move) -> Option<i32> {
     }
     Some(gains[0])
 }
+
+/// Whether `mv` gains at least `threshold` cp once the exchange on its target
+/// square is played out, on bitboards with x-rays (no move generation, pins
+/// ignored). A quiet move captures nothing, so `see_ge(board, m, 0)` asks whether
+/// the moved piece can be won; castling is always safe.
+pub fn see_ge(board: &Board, mv: cozy_chess::Move, threshold: i32) -> bool {
+    use cozy_chess::{get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks, get_rook_moves, BitBoard};
+    let (from, to) = (mv.from, mv.to);
+    let stm = board.side_to_move();
+    if board.colors(stm).has(to) { return threshold <= 0; }
+    let Some(moving) = board.piece_on(from) else { return false };
+    let mut occ = board.occupied() ^ from.bitboard();
+    let mut gain = board.piece_on(to).map_or(0, piece_value);
+    if moving == Piece::Pawn && from.file() != to.file() && board.piece_on(to).is_none() {
+        // En passant: the captured pawn sits beside the target square
+        gain = piece_value(Piece::Pawn);
+        occ ^= cozy_chess::Square::new(to.file(), from.rank()).bitboard();
+    }
+    let mut at_risk = piece_value(moving);
+    if let Some(p) = mv.promotion { gain += piece_value(p) - piece_value(Piece::Pawn); at_risk = piece_value(p); }
+
+    let mut swap = gain - threshold;
+    if swap < 0 { return false; }
+    swap = at_risk - swap;
+    if swap <= 0 { return true; }
+
+    let diag = board.pieces(Piece::Bishop) | board.pieces(Piece::Queen);
+    let orth = board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    let mut attackers = (get_pawn_attacks(to, Color::Black) & board.colored_pieces(Color::White, Piece::Pawn))
+        | (get_pawn_attacks(to, Color::White) & board.colored_pieces(Color::Black, Piece::Pawn))
+        | (get_knight_moves(to) & board.pieces(Piece::Knight))
+        | (get_king_moves(to) & board.pieces(Piece::King))
+        | (get_bishop_moves(to, occ) & diag)
+        | (get_rook_moves(to, occ) & orth);
+    let mut side = stm;
+    // 1 while the side that moved last is winning the exchange
+    let mut res = 1;
+    loop {
+        side = !side;
+        attackers &= occ;
+        let ours = attackers & board.colors(side);
+        if ours.is_empty() { break; }
+        res ^= 1;
+        let Some((piece, bb)) = [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King].into_iter()
+            .map(|p| (p, ours & board.pieces(p))).find(|(_, bb)| !bb.is_empty()) else { break };
+        if piece == Piece::King {
+            // The king may only take last
+            return if (attackers & board.colors(!side)).is_empty() { res == 1 } else { res == 0 };
+        }
+        swap = piece_value(piece) - swap;
+        if swap < res { break; }
+        occ ^= bb.next_square().map_or(BitBoard::EMPTY, |s| s.bitboard());
+        if matches!(piece, Piece::Pawn | Piece::Bishop | Piece::Queen) { attackers |= get_bishop_moves(to, occ) & diag; }
+        if matches!(piece, Piece::Rook | Piece::Queen) { attackers |= get_rook_moves(to, occ) & orth; }
+    }
+    res == 1
+}
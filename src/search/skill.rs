@@ -0,0 +1,44 @@
+use rand::Rng;
+
+pub const MAX_SKILL_LEVEL: u8 = 20;
+
+/// Discrete playing strength (1..=20) for casual opponents.
+/// Lower levels cap search depth, blur root scores with noise, and
+/// sometimes play the k-th best root move instead of the best one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SkillLevel(u8);
+
+impl Default for SkillLevel {
+    fn default() -> Self { Self(MAX_SKILL_LEVEL) }
+}
+
+impl SkillLevel {
+    pub fn new(level: u8) -> Self { Self(level.clamp(1, MAX_SKILL_LEVEL)) }
+    pub fn level(&self) -> u8 { self.0 }
+    pub fn is_full_strength(&self) -> bool { self.0 >= MAX_SKILL_LEVEL }
+
+    /// Maximum search depth; `None` at full strength.
+    pub fn depth_cap(&self) -> Option<u32> {
+        if self.is_full_strength() { None } else { Some(1 + self.0 as u32 / 3) }
+    }
+
+    /// Half-width of the uniform noise added to each root score (centipawns).
+    pub fn eval_noise_cp(&self) -> i32 { (MAX_SKILL_LEVEL - self.0) as i32 * 15 }
+
+    /// Probability of stepping down one rank from the best move (applied repeatedly).
+    pub fn mistake_prob(&self) -> f32 { (MAX_SKILL_LEVEL - self.0) as f32 / 25.0 }
+
+    /// Pick a move from root scores (higher is better for the side to move).
+    pub fn pick<T: Copy, R: Rng>(&self, scored: &[(T, i32)], rng: &mut R) -> Option<T> {
+        if scored.is_empty() { return None; }
+        let noise = self.eval_noise_cp();
+        let mut noisy: Vec<(T, i32)> = scored.iter()
+            .map(|&(m, s)| (m, if noise > 0 { s + rng.gen_range(-noise..=noise) } else { s }))
+            .collect();
+        noisy.sort_by_key(|&(_, s)| -s);
+        let p = self.mistake_prob();
+        let mut k = 0usize;
+        while k + 1 < noisy.len() && rng.gen::<f32>() < p { k += 1; }
+        Some(noisy[k].0)
+    }
+}
@@ -0,0 +1,76 @@
+//! Running EPD suites (`io::epd`) through the cozy searcher. `dm` cases are also
+//! checked for the reported mate distance, and tallied by distance so a suite
+//! shows where mate finding breaks down rather than a single pass count.
+
+use crate::io::epd::EpdCase;
+use crate::search::alphabeta::{SearchParams, Searcher};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub struct CaseResult {
+    pub id: String,
+    pub bestmove: Option<String>,
+    pub score_cp: i32,
+    pub depth: u32,
+    /// The move is one of `bm` and none of `am` (true when neither is given)
+    pub move_ok: bool,
+    /// Mate distance from the score, in moves (negative: the engine is mated)
+    pub mate_found: Option<i32>,
+    /// The case's `dm`
+    pub mate_expected: Option<i32>,
+}
+
+impl CaseResult {
+    pub fn mate_ok(&self) -> bool { self.mate_expected.is_none() || self.mate_found == self.mate_expected }
+    pub fn solved(&self) -> bool { self.move_ok && self.mate_ok() }
+}
+
+/// Search one case with `params`. With `mate_margin`, a `dm` case is searched to
+/// exactly its mate depth plus that many plies instead of `params.depth`/movetime.
+pub fn run_case(searcher: &mut Searcher, case: &EpdCase, params: SearchParams, mate_margin: Option<u32>) -> CaseResult {
+    let params = match (case.mate_plies(), mate_margin) {
+        (Some(plies), Some(m)) => SearchParams { depth: plies + m, movetime: None, max_nodes: None, clock: None, ..params },
+        _ => params,
+    };
+    let res = searcher.search_with_params(&case.board, params);
+    let is = |list: &[cozy_chess::Move]| res.bestmove.as_ref().is_some_and(|b| list.iter().any(|m| format!("{}", m) == *b));
+    let move_ok = res.bestmove.is_some() && (case.best.is_empty() || is(&case.best)) && !is(&case.avoid);
+    CaseResult {
+        id: case.id.clone(),
+        bestmove: res.bestmove.clone(),
+        score_cp: res.score.value(),
+        depth: if res.depth > 0 { res.depth } else { params.depth },
+        move_ok,
+        mate_found: res.score.mate_moves(),
+        mate_expected: case.mate,
+    }
+}
+
+/// `dm` cases at one mate distance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MateBucket {
+    pub total: usize,
+    /// Right move and exact distance
+    pub solved: usize,
+    /// A mate score, but at another distance
+    pub wrong_distance: usize,
+    /// No mate score at all
+    pub missed: usize,
+}
+
+/// `dm` results by expected mate distance.
+pub fn mate_histogram(results: &[CaseResult]) -> BTreeMap<i32, MateBucket> {
+    let mut h: BTreeMap<i32, MateBucket> = BTreeMap::new();
+    for r in results {
+        let Some(n) = r.mate_expected else { continue };
+        let b = h.entry(n).or_default();
+        b.total += 1;
+        if r.solved() { b.solved += 1; }
+        match r.mate_found {
+            None => b.missed += 1,
+            Some(m) if m != n => b.wrong_distance += 1,
+            Some(_) => {}
+        }
+    }
+    h
+}
@@ -0,0 +1,82 @@
+//! Texel tuning of the classical eval's development weights: fit `DevelopmentWeights`
+//! to game results by minimising the squared error between each result and the
+//! eval's expected score, `1 / (1 + 10^(-k * eval / 400))`. The rest of the eval is
+//! held fixed, so a sample keeps it as one number next to the development features.
+
+use crate::search::eval::{eval_breakdown, DevelopmentFeatures, DevelopmentWeights, MaterialSig, SCALE_NORMAL};
+use cozy_chess::{Board, Color};
+
+/// One position: the other classical terms, each side's development features, the
+/// material phase and endgame scale, and the game result (White-relative 1, 0, -1).
+#[derive(Clone, Copy, Debug)]
+pub struct DevelopmentSample { pub rest: i32, pub white: DevelopmentFeatures, pub black: DevelopmentFeatures, pub phase: u32, pub scale: i32, pub result: i8 }
+
+impl DevelopmentSample {
+    pub fn new(board: &Board, result: i8) -> Self {
+        let e = eval_breakdown(board);
+        Self {
+            rest: e.material + e.pst + e.passers + e.castling,
+            white: DevelopmentFeatures::from_board(board, Color::White),
+            black: DevelopmentFeatures::from_board(board, Color::Black),
+            phase: MaterialSig::from_board(board).phase(),
+            scale: e.scale,
+            result: result.signum(),
+        }
+    }
+
+    /// The classical eval (White-relative) with `w` as the development weights; with
+    /// `DEVELOPMENT` it is `eval_breakdown(board).total`.
+    pub fn eval(&self, w: &DevelopmentWeights) -> i32 {
+        let dev = (self.white.score(w) - self.black.score(w)) * self.phase as i32 / crate::board::cozy::PHASE_MAX as i32;
+        let cp = self.rest + dev;
+        if self.scale == SCALE_NORMAL { cp } else { cp * self.scale / SCALE_NORMAL }
+    }
+}
+
+/// Mean squared error of the expected scores under `w` and `k` against the results.
+pub fn loss(samples: &[DevelopmentSample], w: &DevelopmentWeights, k: f64) -> f64 {
+    if samples.is_empty() { return 0.0; }
+    let err: f64 = samples.iter().map(|s| {
+        let expected = 1.0 / (1.0 + 10f64.powf(-k * f64::from(s.eval(w)) / 400.0));
+        let r = (f64::from(s.result) + 1.0) / 2.0;
+        (r - expected).powi(2)
+    }).sum();
+    err / samples.len() as f64
+}
+
+/// The scaling constant `k` that best fits the results with `w` fixed, by pattern
+/// search from 1. Fit it once with the current weights, then tune at that `k`.
+pub fn fit_k(samples: &[DevelopmentSample], w: &DevelopmentWeights) -> f64 {
+    let (mut k, mut best) = (1.0, loss(samples, w, 1.0));
+    let mut step = 0.5;
+    while step >= 0.001 {
+        let mut improved = false;
+        for cand in [k + step, k - step] {
+            if cand <= 0.0 { continue; }
+            let l = loss(samples, w, cand);
+            if l < best { (k, best, improved) = (cand, l, true); }
+        }
+        if !improved { step /= 2.0; }
+    }
+    k
+}
+
+/// Texel's local search from `start`: move each weight by one centipawn while that
+/// lowers the loss, until no single step does.
+pub fn tune_development(samples: &[DevelopmentSample], start: DevelopmentWeights, k: f64) -> DevelopmentWeights {
+    let mut best = start;
+    let mut best_loss = loss(samples, &best, k);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..3 {
+            for dir in [1, -1] {
+                let mut cand = best;
+                *[&mut cand.developed_minor, &mut cand.castled, &mut cand.repeat_move][i] += dir;
+                let l = loss(samples, &cand, k);
+                if l < best_loss { (best, best_loss, improved) = (cand, l, true); }
+            }
+        }
+    }
+    best
+}
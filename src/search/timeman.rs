@@ -0,0 +1,312 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Time source for the time manager. Searches use `WallClock`; tests drive a
+/// `ManualClock` so elapsed time is deterministic.
+pub trait Clock {
+    /// Time since the clock was started.
+    fn elapsed(&self) -> Duration;
+}
+
+pub struct WallClock(Instant);
+
+impl WallClock {
+    pub fn start() -> Self { Self(Instant::now()) }
+}
+
+impl Clock for WallClock {
+    fn elapsed(&self) -> Duration { self.0.elapsed() }
+}
+
+/// Clock that only moves when told to.
+#[derive(Default)]
+pub struct ManualClock(AtomicU64);
+
+impl ManualClock {
+    pub fn new() -> Self { Self::default() }
+    pub fn advance(&self, d: Duration) { self.0.fetch_add(d.as_nanos() as u64, Ordering::Relaxed); }
+    pub fn set(&self, d: Duration) { self.0.store(d.as_nanos() as u64, Ordering::Relaxed); }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration { Duration::from_nanos(self.0.load(Ordering::Relaxed)) }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn elapsed(&self) -> Duration { (**self).elapsed() }
+}
+
+/// What the GUI (or match runner) gave us for this move.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GoLimits {
+    /// Our remaining clock, if playing with one
+    pub remaining: Option<Duration>,
+    pub increment: Duration,
+    pub moves_to_go: Option<u32>,
+    /// Fixed time for this move; takes precedence over the clock
+    pub movetime: Option<Duration>,
+}
+
+impl GoLimits {
+    /// Limits from the arguments of a UCI `go` (`wtime btime winc binc movestogo
+    /// movetime`), seen from the side to move. Unknown tokens are skipped.
+    pub fn from_uci(args: &str, white_to_move: bool) -> Self {
+        let (ours, our_inc) = if white_to_move { ("wtime", "winc") } else { ("btime", "binc") };
+        let mut go = GoLimits::default();
+        let mut tokens = args.split_whitespace();
+        while let Some(tok) = tokens.next() {
+            let ms = |v: Option<&str>| v.and_then(|s| s.parse::<i64>().ok()).map(|ms| Duration::from_millis(ms.max(0) as u64));
+            match tok {
+                t if t == ours => go.remaining = ms(tokens.next()).or(go.remaining),
+                t if t == our_inc => go.increment = ms(tokens.next()).unwrap_or(go.increment),
+                "movestogo" => go.moves_to_go = tokens.next().and_then(|s| s.parse().ok()).or(go.moves_to_go),
+                "movetime" => go.movetime = ms(tokens.next()).or(go.movetime),
+                _ => {}
+            }
+        }
+        go
+    }
+}
+
+/// Movetime for a `go` that names no limit at all, when the configured ones are
+/// unlimited too. Only `go infinite` and `go ponder` wait for a `stop`.
+pub const DEFAULT_MOVETIME_MS: u64 = 1000;
+
+/// Depth and time limits of one search. `depth` 0 means no depth limit and
+/// `movetime_ms` `None` no time limit; with both, the search stops at whichever
+/// comes first. The first `min_depth` iterations always complete, whatever the
+/// movetime or soft budget; a clock's hard limit and a node limit still apply.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: u32,
+    pub movetime_ms: Option<u64>,
+    pub min_depth: u32,
+}
+
+impl SearchLimits {
+    /// Limits of a UCI `go`. `depth` and `movetime` given there replace the configured
+    /// ones (so `go movetime 500` is not capped at the configured depth); a `go` with
+    /// neither, nor a clock, searches with the configured limits. A clock's budget is
+    /// set by the caller. A search without any limit gets `DEFAULT_MOVETIME_MS`,
+    /// except `go infinite`, which keeps only a depth given with it.
+    pub fn from_uci(args: &str, white_to_move: bool, depth: u32, movetime_ms: Option<u64>, min_depth: u32) -> Self {
+        let clock = GoLimits::from_uci(args, white_to_move).remaining.is_some();
+        let (mut go_depth, mut go_movetime, mut infinite) = (None, None, false);
+        let mut tokens = args.split_whitespace();
+        while let Some(tok) = tokens.next() {
+            match tok {
+                "depth" => go_depth = tokens.next().and_then(|s| s.parse().ok()).or(go_depth),
+                "movetime" => go_movetime = tokens.next().and_then(|s| s.parse().ok()).or(go_movetime),
+                "infinite" => infinite = true,
+                _ => {}
+            }
+        }
+        if infinite { return Self { depth: go_depth.unwrap_or(0), movetime_ms: None, min_depth }; }
+        let mut limits = if go_depth.is_some() || go_movetime.is_some() || clock {
+            Self { depth: go_depth.unwrap_or(0), movetime_ms: go_movetime, min_depth }
+        } else {
+            Self { depth, movetime_ms, min_depth }
+        };
+        if limits.depth == 0 && limits.movetime_ms.is_none() && !clock { limits.movetime_ms = Some(DEFAULT_MOVETIME_MS); }
+        limits
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimeConfig {
+    /// Reserved per move for GUI/transport latency
+    pub move_overhead: Duration,
+    /// Floor for the soft budget while the clock allows it
+    pub min_per_move: Duration,
+    /// Moves the remaining clock is spread over when `moves_to_go` is unknown
+    pub horizon_moves: u32,
+    /// Share of the increment spent on top of the even share
+    pub inc_share: f32,
+    /// Hard limit as a multiple of the soft budget
+    pub hard_factor: f32,
+    /// Never plan to spend more than this share of the remaining clock on one move
+    pub max_share: f32,
+    /// Predicted cost of the next iteration as a multiple of the last one
+    pub growth: f32,
+    /// Search speed; when known, iteration costs are predicted from node counts
+    /// rather than from the (noisy, cold-cache) timings of the first iterations
+    pub nodes_per_ms: Option<f32>,
+    /// Soft budget added per recent root best-move change, as a share of the soft
+    /// budget (see `TimeManager::stretch_soft`)
+    pub instability: f32,
+    /// Weight of the best move's share of root nodes (see `TimeConfig::effort_scale`);
+    /// 0 ignores it
+    pub effort: f32,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self { move_overhead: Duration::from_millis(10), min_per_move: Duration::from_millis(5), horizon_moves: 30, inc_share: 0.75, hard_factor: 3.0, max_share: 0.8, growth: 2.5, nodes_per_ms: None, instability: 0.5, effort: 1.0 }
+    }
+}
+
+/// Best-move share of the root nodes at which `TimeConfig::effort_scale` is neutral.
+pub const EFFORT_PIVOT: f32 = 0.5;
+
+/// Share of the root nodes (`(move, nodes)` per root move) spent on `best`, or
+/// `None` when nothing was counted.
+pub fn best_move_share<M: PartialEq>(effort: &[(M, u64)], best: &M) -> Option<f32> {
+    let total: u64 = effort.iter().map(|(_, n)| n).sum();
+    if total == 0 { return None; }
+    let on_best: u64 = effort.iter().filter(|(m, _)| m == best).map(|(_, n)| n).sum();
+    Some(on_best as f32 / total as f32)
+}
+
+impl TimeConfig {
+    /// This config with the speed and iteration growth measured on this machine.
+    pub fn calibrated(self, c: &Calibration) -> Self { Self { growth: c.growth, nodes_per_ms: Some(c.nodes_per_ms), ..self } }
+
+    /// Soft-limit scale from `best_share`, the share of the last iteration's root
+    /// nodes spent on its best move. Nearly all of them (a clear choice) shrinks the
+    /// soft limit towards half; effort spread over several moves grows it by up to
+    /// half. A best move that changed in the last two iterations (`instability`
+    /// of 0.25 or more) is not trusted to stop early.
+    pub fn effort_scale(&self, best_share: f32, instability: f32) -> f32 {
+        let scale = (1.0 + self.effort * (EFFORT_PIVOT - best_share)).clamp(0.5, 1.5);
+        if instability >= 0.25 { scale.max(1.0) } else { scale }
+    }
+
+    /// Time the last iteration is taken to have cost when predicting the next one:
+    /// its nodes at the calibrated speed, else its measured time.
+    pub fn iteration_cost(&self, nodes: u64, time: Duration) -> Duration {
+        match self.nodes_per_ms {
+            Some(nps) if nps > 0.0 => Duration::from_secs_f64(nodes as f64 / nps as f64 / 1000.0),
+            _ => time,
+        }
+    }
+}
+
+/// The probe position of `Calibration::measure`: a quiet middlegame.
+pub const CALIBRATION_FEN: &str = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N2N2/PP2BPPP/R1BQK2R w KQ - 0 8";
+
+/// Machine speed from a short probe search, measured once at startup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    pub nodes_per_ms: f32,
+    /// Node ratio between consecutive iterations of the probe
+    pub growth: f32,
+}
+
+static CALIBRATION: RwLock<Option<Calibration>> = RwLock::new(None);
+
+impl Calibration {
+    /// Machine speed from a probe search: the cumulative node count at the end of each
+    /// iteration, and the search's time.
+    pub fn from_iterations(iters: &[u64], time_ms: u64) -> Self {
+        let nodes = iters.last().copied().unwrap_or(0);
+        let nodes_per_ms = (nodes as f32 / time_ms.max(1) as f32).max(1.0);
+        let per_iter: Vec<u64> = iters.iter().scan(0, |prev, &n| { let d = n - *prev; *prev = n; Some(d) }).collect();
+        // Ratios of the last few iterations big enough to be past the startup noise
+        let ratios: Vec<f32> = per_iter.windows(2).filter(|w| w[0] >= 1000).map(|w| w[1] as f32 / w[0] as f32).collect();
+        let tail = &ratios[ratios.len().saturating_sub(3)..];
+        let growth = if tail.is_empty() { TimeConfig::default().growth } else { (tail.iter().map(|r| r.ln()).sum::<f32>() / tail.len() as f32).exp().clamp(1.5, 6.0) };
+        Self { nodes_per_ms, growth }
+    }
+
+    /// Search `CALIBRATION_FEN` for about `probe` with the cozy searcher `opts`
+    /// configures (eval, network and pruning as in play, on one thread) and measure it.
+    pub fn measure(opts: &crate::search::options::EngineOptions, probe: Duration) -> Self {
+        use crate::search::alphabeta::{SearchParams, SearchResult, Searcher};
+        use std::sync::{Arc, Mutex};
+        let board: cozy_chess::Board = CALIBRATION_FEN.parse().unwrap();
+        let iters: Arc<Mutex<Vec<u64>>> = Arc::default();
+        let mut s = opts.build_searcher().unwrap_or_else(|e| { eprintln!("calibration: {}", e); Searcher::default() });
+        let seen = iters.clone();
+        s.set_info_hook(Some(Arc::new(move |r: &SearchResult| seen.lock().unwrap().push(r.nodes))));
+        let params = SearchParams { depth: 0, movetime: Some(probe), max_nodes: None, clock: None, min_depth: 0, threads: 1, ..opts.search_params() };
+        let res = s.search_with_params(&board, params);
+        let iters = iters.lock().unwrap();
+        Self::from_iterations(&iters, res.time_ms)
+    }
+
+    /// `measure` with the pleco searcher `opts` configures.
+    #[cfg(feature = "board-pleco")]
+    pub fn measure_pleco(opts: &crate::search::options::EngineOptions, probe: Duration) -> Self {
+        use crate::search::alphabeta::SearchResult;
+        use crate::search::alphabeta_pleco::PlecoSearcher;
+        use std::sync::{Arc, Mutex};
+        let mut board = pleco::Board::from_fen(CALIBRATION_FEN).unwrap();
+        let iters: Arc<Mutex<Vec<u64>>> = Arc::default();
+        let mut s = opts.build_pleco_searcher().unwrap_or_else(|e| { eprintln!("calibration: {}", e); PlecoSearcher::default() });
+        s.set_threads(1);
+        s.set_min_depth(0);
+        s.set_clock_plan(None);
+        let seen = iters.clone();
+        s.set_info_hook(Some(Arc::new(move |r: &SearchResult| seen.lock().unwrap().push(r.nodes))));
+        let res = s.search_result(&mut board, (probe.as_millis() as u64).max(1), 0);
+        let iters = iters.lock().unwrap();
+        Self::from_iterations(&iters, res.time_ms)
+    }
+
+    /// The calibration installed at startup, if any.
+    pub fn global() -> Option<Self> { *CALIBRATION.read().unwrap_or_else(|e| e.into_inner()) }
+
+    pub fn set_global(self) { *CALIBRATION.write().unwrap_or_else(|e| e.into_inner()) = Some(self); }
+}
+
+/// `soft`: don't start another iteration past this. `hard`: abort the search here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeBudget { pub soft: Duration, pub hard: Duration }
+
+/// Clock play for one search: `movetime` is set to the hard limit as the abort
+/// deadline, and iterative deepening consults this between iterations.
+#[derive(Clone, Copy, Debug)]
+pub struct ClockPlan { pub budget: TimeBudget, pub config: TimeConfig }
+
+/// Budget for one move, or `None` for untimed (depth/infinite) searches.
+/// With a clock, `hard` never exceeds the remaining time minus the overhead, and
+/// `soft` is at least `min_per_move` whenever that much is available.
+pub fn allocate(go: &GoLimits, cfg: &TimeConfig) -> Option<TimeBudget> {
+    if let Some(mt) = go.movetime {
+        let t = mt.saturating_sub(cfg.move_overhead).max(cfg.min_per_move.min(mt));
+        return Some(TimeBudget { soft: t, hard: t });
+    }
+    let remaining = go.remaining?;
+    let available = remaining.saturating_sub(cfg.move_overhead);
+    let moves = go.moves_to_go.unwrap_or(cfg.horizon_moves).clamp(1, cfg.horizon_moves.max(1));
+    let cap = available.mul_f32(cfg.max_share.clamp(0.0, 1.0));
+    let share = available / moves + go.increment.mul_f32(cfg.inc_share.max(0.0));
+    let soft = share.min(cap).max(cfg.min_per_move).min(available);
+    let hard = soft.mul_f32(cfg.hard_factor.max(1.0)).min(cap).max(soft).min(available);
+    Some(TimeBudget { soft, hard })
+}
+
+/// Tracks one search against its budget.
+pub struct TimeManager<C: Clock> {
+    clock: C,
+    budget: TimeBudget,
+    stretch: f32,
+    effort: f32,
+}
+
+impl<C: Clock> TimeManager<C> {
+    pub fn new(clock: C, budget: TimeBudget) -> Self { Self { clock, budget, stretch: 1.0, effort: 1.0 } }
+    pub fn budget(&self) -> TimeBudget { self.budget }
+    /// Scale the soft limit by `factor` (at least 1) while the root is unstable;
+    /// the hard limit still caps it.
+    pub fn stretch_soft(&mut self, factor: f32) { self.stretch = factor.max(1.0); }
+    /// Scale the soft limit by `TimeConfig::effort_scale` (0.5 to 1.5), on top of
+    /// `stretch_soft`; this one can also shorten it.
+    pub fn scale_soft_by_effort(&mut self, scale: f32) { self.effort = scale.clamp(0.5, 1.5); }
+    /// The soft limit after `stretch_soft` and `scale_soft_by_effort`.
+    pub fn soft_limit(&self) -> Duration {
+        self.budget.soft.mul_f64(f64::from(self.stretch * self.effort)).min(self.budget.hard.max(self.budget.soft))
+    }
+    pub fn elapsed(&self) -> Duration { self.clock.elapsed() }
+    /// Time left before the hard limit (use as the search deadline).
+    pub fn hard_remaining(&self) -> Duration { self.budget.hard.saturating_sub(self.elapsed()) }
+    pub fn should_stop(&self) -> bool { self.elapsed() >= self.budget.hard }
+
+    /// Start the next iteration only before the soft limit, and only if it is
+    /// predicted (`last_iter * growth`) to finish before the hard limit.
+    pub fn can_start_iteration(&self, last_iter: Duration, growth: f32) -> bool {
+        let e = self.elapsed();
+        e < self.soft_limit() && e + last_iter.mul_f32(growth.max(1.0)) <= self.budget.hard
+    }
+}
@@ -1,5 +1,6 @@
 use cozy_chess::Move;
-use std::sync::Mutex;
+use std::sync::{Mutex, PoisonError};
+use crate::search::profile::StatCounter;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Bound {
@@ -12,13 +13,61 @@ pub enum Bound {
 pub struct Entry {
     pub key: u64,
     pub depth: u32,
-    pub score: i32,
+    pub score: crate::search::score::Score,
     pub best: Option<Move>,
     pub bound: Bound,
     pub gen: u32,
 }
 
+/// Legality checks on moves taken from the search tables before they are used.
+/// A rejected TT move means a corrupted or aliased entry; rejected killers are
+/// routine, since killers are shared between sibling positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MoveChecks { pub tt_moves: u64, pub tt_rejected: u64, pub killers: u64, pub killers_rejected: u64 }
+
+/// Counters behind `MoveChecks`, shared by every thread using a table; only
+/// counted with the `search-stats` feature (see `StatCounter`).
+#[derive(Default)]
+pub(crate) struct MoveCheckCounters { tt_moves: StatCounter, tt_rejected: StatCounter, killers: StatCounter, killers_rejected: StatCounter }
+
+impl MoveCheckCounters {
+    /// Count a TT move check; returns `legal`.
+    pub(crate) fn tt_move(&self, legal: bool) -> bool {
+        self.tt_moves.bump();
+        if !legal { self.tt_rejected.bump(); }
+        legal
+    }
+    /// Count a killer check; returns `legal`.
+    pub(crate) fn killer(&self, legal: bool) -> bool {
+        self.killers.bump();
+        if !legal { self.killers_rejected.bump(); }
+        legal
+    }
+    pub(crate) fn snapshot(&self) -> MoveChecks {
+        let l = StatCounter::get;
+        MoveChecks { tt_moves: l(&self.tt_moves), tt_rejected: l(&self.tt_rejected), killers: l(&self.killers), killers_rejected: l(&self.killers_rejected) }
+    }
+}
+
 const DEFAULT_WAYS: usize = 4;
+/// Plies of depth one search of age is worth when choosing a victim slot.
+pub const AGE_WEIGHT: i64 = 8;
+
+/// Worth keeping an entry of `depth` stored at generation `gen` when the table is at
+/// `now`; a full bucket overwrites its lowest. An entry from a few searches back loses
+/// to a fresh one of much lower depth, so old lines do not crowd out the current tree.
+pub fn replace_value(depth: u32, gen: u32, now: u32) -> i64 {
+    depth as i64 - AGE_WEIGHT * i64::from(now.saturating_sub(gen))
+}
+/// Bytes per entry assumed when sizing the table in MB.
+pub const ENTRY_BYTES: usize = 64;
+
+/// Smallest power-of-two hash size (MB, within UCI's 1..=16384) with an entry for
+/// each of `nodes` searched nodes: the usual rule of thumb for a move's search.
+pub fn suggested_hash_mb(nodes: u64) -> usize {
+    let mb = (nodes as u128 * ENTRY_BYTES as u128).div_ceil(1 << 20);
+    (mb.min(16384) as usize).next_power_of_two().clamp(1, 16384)
+}
 
 #[derive(Default, Clone, Copy)]
 struct Slot(Option<Entry>);
@@ -30,23 +79,19 @@ struct Bucket {
 
 #[derive(Default)]
 pub struct Tt {
+    // A worker that panics while holding a bucket poisons it; entries are self-validating
+    // by key, so the lock is simply recovered.
     buckets: Vec<Mutex<Bucket>>,
     gen: std::sync::atomic::AtomicU32,
+    checks: MoveCheckCounters,
 }
 
 impl Tt {
-    pub fn new() -> Self { Self { buckets: Vec::new(), gen: std::sync::atomic::AtomicU32::new(0) } }
-
-    fn ensure_init(&mut self) {
-        if self.buckets.is_empty() {
-            // Use a larger default to reduce contention and collisions under SMP
-            self.set_capacity_entries(65_536);
-        }
-    }
+    pub fn new() -> Self { Self::default() }
 
-    pub fn clear(&mut self) {
-        self.ensure_init();
-        for b in &self.buckets { let mut g = b.lock().unwrap(); *g = Bucket::default(); }
+    /// Drop every entry, keeping the allocation; works through a shared handle.
+    pub fn clear(&self) {
+        for b in &self.buckets { let mut g = b.lock().unwrap_or_else(PoisonError::into_inner); *g = Bucket::default(); }
     }
 
     fn bucket_index(&self, key: u64) -> usize {
@@ -57,7 +102,7 @@ impl Tt {
     pub fn get(&self, key: u64) -> Option<Entry> {
         if self.buckets.is_empty() { return None; }
         let idx = self.bucket_index(key);
-        let g = self.buckets[idx].lock().unwrap();
+        let g = self.buckets[idx].lock().unwrap_or_else(PoisonError::into_inner);
         for slot in &g.slots {
             if let Some(e) = slot.0 { if e.key == key { return Some(e); } }
         }
@@ -68,7 +113,7 @@ impl Tt {
         if self.buckets.is_empty() { return 0; }
         let mut count = 0;
         for b in &self.buckets {
-            let g = b.lock().unwrap();
+            let g = b.lock().unwrap_or_else(PoisonError::into_inner);
             for s in &g.slots { if s.0.is_some() { count += 1; } }
         }
         count
@@ -82,34 +127,70 @@ impl Tt {
     }
 
     pub fn set_capacity_mb(&mut self, mb: usize) {
-        // Heuristic: ~64 bytes per entry
-        let entries = ((mb.saturating_mul(1024) * 1024) / 64).max(DEFAULT_WAYS);
-        self.set_capacity_entries(entries);
+        self.set_capacity_entries(Self::entries_for_mb(mb));
+    }
+
+    fn entries_for_mb(mb: usize) -> usize { ((mb.saturating_mul(1024) * 1024) / ENTRY_BYTES).max(DEFAULT_WAYS) }
+
+    /// Entries the table holds (0 until first sized).
+    pub fn capacity(&self) -> usize { self.buckets.len() * DEFAULT_WAYS }
+
+    /// Whether `set_capacity_mb(mb)` would give this table's size.
+    pub fn has_capacity_mb(&self, mb: usize) -> bool { self.buckets.len() == Self::entries_for_mb(mb).div_ceil(DEFAULT_WAYS) }
+
+    /// A table of `mb` MB holding this one's entries at their generation, so a
+    /// `Hash` change keeps the work done so far. Entries are rehashed bucket by
+    /// bucket, and a full bucket only gives up a less valuable entry, so a smaller
+    /// table keeps the deep, recent ones. Both tables are allocated until the caller
+    /// drops this one.
+    pub fn resized_mb(&self, mb: usize) -> Self {
+        let mut tt = Self::new();
+        tt.set_capacity_mb(mb);
+        tt.gen.store(self.generation(), std::sync::atomic::Ordering::Relaxed);
+        for b in &self.buckets {
+            let slots = b.lock().unwrap_or_else(PoisonError::into_inner).slots.map(|s| s.0);
+            for e in slots.into_iter().flatten() { tt.insert(e, false); }
+        }
+        tt
     }
 
     pub fn put(&self, e: Entry) {
+        let mut e = e; e.gen = self.gen.load(std::sync::atomic::Ordering::Relaxed);
+        self.insert(e, true);
+    }
+
+    // Store `e` as of its own generation. With `force` a full bucket gives up its
+    // least valuable entry even for a less valuable `e`, as a search's stores do
+    fn insert(&self, e: Entry, force: bool) {
         // Safety: we only mutate internal bucket; external API remains &self
         if self.buckets.is_empty() { return; }
         let idx = self.bucket_index(e.key);
-        let mut g = self.buckets[idx].lock().unwrap();
+        let mut g = self.buckets[idx].lock().unwrap_or_else(PoisonError::into_inner);
         let cur_gen = self.gen.load(std::sync::atomic::Ordering::Relaxed);
-        let mut e = e; e.gen = cur_gen;
-        // Replace same key if deeper
+        // Same key: the deeper result wins whatever its age, and is now in use by this search
         for slot in &mut g.slots {
-            if let Some(cur) = slot.0 { if cur.key == e.key { if e.depth >= cur.depth { slot.0 = Some(e); } return; } }
+            if let Some(cur) = slot.0 { if cur.key == e.key { slot.0 = Some(if e.depth >= cur.depth { e } else { Entry { gen: cur_gen, ..cur } }); return; } }
         }
         // Empty slot first
         for slot in &mut g.slots { if slot.0.is_none() { slot.0 = Some(e); return; } }
-        // Replace lowest depth
-        let mut victim = 0usize; let mut best_key = (u32::MAX, u32::MAX);
+        // Replace the least valuable: shallow and old first (ties go to the older entry)
+        let mut victim = 0usize; let mut best_key = (i64::MAX, u32::MAX);
         for (i, slot) in g.slots.iter().enumerate() {
             if let Some(cur) = slot.0 {
-                let key = (cur.depth, cur.gen); // lexicographic: prefer evicting lowest depth, then oldest gen
+                let key = (replace_value(cur.depth, cur.gen, cur_gen), cur.gen);
                 if key < best_key { best_key = key; victim = i; }
             }
         }
+        if !force && (replace_value(e.depth, e.gen, cur_gen), e.gen) <= best_key { return; }
         g.slots[victim].0 = Some(e);
     }
 
+    /// Start a new search generation; searchers call this once per `go` (`new_search`),
+    /// or at the start of a search that was not preceded by one.
     pub fn bump_generation(&self) { let _ = self.gen.fetch_add(1, std::sync::atomic::Ordering::Relaxed); }
+    pub fn generation(&self) -> u32 { self.gen.load(std::sync::atomic::Ordering::Relaxed) }
+
+    /// Cumulative TT-move and killer legality checks since creation.
+    pub fn move_checks(&self) -> MoveChecks { self.checks.snapshot() }
+    pub(crate) fn checks(&self) -> &MoveCheckCounters { &self.checks }
 }
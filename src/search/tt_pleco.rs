@@ -1,6 +1,9 @@
 #![cfg(feature = "board-pleco")]
 use pleco::BitMove;
-use std::sync::Mutex;
+use std::sync::{Mutex, PoisonError};
+use std::sync::atomic::Ordering;
+use crate::search::profile::StatCounter;
+use crate::search::tt::{replace_value, MoveCheckCounters, MoveChecks};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Bound { Exact, Lower, Upper }
@@ -9,7 +12,7 @@ pub enum Bound { Exact, Lower, Upper }
 pub struct Entry {
     pub key: u64,
     pub depth: u32,
-    pub score: i32,
+    pub score: crate::search::score::Score,
     pub best: Option<BitMove>,
     pub bound: Bound,
     pub gen: u32,
@@ -25,39 +28,72 @@ struct Bucket { slots: [Slot; WAYS] }
 
 #[derive(Default)]
 pub struct TtPleco {
+    // Poisoned buckets (a worker panicked mid-access) are recovered, not propagated
     buckets: Vec<Mutex<Bucket>>, gen: std::sync::atomic::AtomicU32,
+    // Probe accounting for SMP diagnostics; only counted with the `search-stats` feature
+    probes: StatCounter, hits: StatCounter,
+    checks: MoveCheckCounters,
 }
 
 impl TtPleco {
-    pub fn new() -> Self { Self { buckets: Vec::new(), gen: std::sync::atomic::AtomicU32::new(0) } }
+    pub fn new() -> Self { Self::default() }
     fn ensure(&mut self) { if self.buckets.is_empty() { self.set_capacity_entries(65_536); } }
     pub fn set_capacity_entries(&mut self, entries: usize) {
         let buckets = (entries + WAYS - 1) / WAYS;
         self.buckets.clear();
         self.buckets.resize_with(buckets, || Mutex::new(Bucket::default()));
     }
-    pub fn set_capacity_mb(&mut self, mb: usize) {
-        // Approximate entry size ~64 bytes
-        let entries = ((mb.saturating_mul(1024) * 1024) / 64).max(WAYS);
-        self.set_capacity_entries(entries);
+    /// Drop every entry, keeping the allocation; works through a shared handle.
+    pub fn clear(&self) {
+        for b in &self.buckets { *b.lock().unwrap_or_else(PoisonError::into_inner) = Bucket::default(); }
+    }
+    pub fn set_capacity_mb(&mut self, mb: usize) { self.set_capacity_entries(Self::entries_for_mb(mb)); }
+    // Approximate entry size ~64 bytes
+    fn entries_for_mb(mb: usize) -> usize { ((mb.saturating_mul(1024) * 1024) / 64).max(WAYS) }
+    /// Entries the table holds (0 until first sized).
+    pub fn capacity(&self) -> usize { self.buckets.len() * WAYS }
+    /// Whether `set_capacity_mb(mb)` would give this table's size.
+    pub fn has_capacity_mb(&self, mb: usize) -> bool { self.buckets.len() == Self::entries_for_mb(mb).div_ceil(WAYS) }
+    /// A table of `mb` MB holding this one's entries; see `Tt::resized_mb`.
+    pub fn resized_mb(&self, mb: usize) -> Self {
+        let mut tt = Self::new();
+        tt.set_capacity_mb(mb);
+        tt.gen.store(self.generation(), Ordering::Relaxed);
+        for b in &self.buckets {
+            let slots = b.lock().unwrap_or_else(PoisonError::into_inner).slots.map(|s| s.0);
+            for e in slots.into_iter().flatten() { tt.insert(e, false); }
+        }
+        tt
     }
     pub fn get(&self, key: u64) -> Option<Entry> {
         if self.buckets.is_empty() { return None; }
         let idx = self.bucket_index(key);
-        let g = self.buckets[idx].lock().unwrap();
-        for s in &g.slots { if let Some(e) = s.0 { if e.key == key { return Some(e); } } }
+        let g = self.buckets[idx].lock().unwrap_or_else(PoisonError::into_inner);
+        self.probes.bump();
+        for s in &g.slots { if let Some(e) = s.0 { if e.key == key { self.hits.bump(); return Some(e); } } }
         None
     }
-    pub fn put(&self, mut e: Entry) {
+    /// Cumulative (probes, hits) since creation; (0, 0) without the `search-stats` feature.
+    pub fn probe_stats(&self) -> (u64, u64) { (self.probes.get(), self.hits.get()) }
+    /// Cumulative TT-move and killer legality checks since creation.
+    pub fn move_checks(&self) -> MoveChecks { self.checks.snapshot() }
+    pub(crate) fn checks(&self) -> &MoveCheckCounters { &self.checks }
+    pub fn put(&self, mut e: Entry) { e.gen = self.gen.load(Ordering::Relaxed); self.insert(e, true); }
+    // Store `e` as of its own generation; see `Tt::insert` for `force`
+    fn insert(&self, e: Entry, force: bool) {
         if self.buckets.is_empty() { return; }
-        let idx = self.bucket_index(e.key); let mut g = self.buckets[idx].lock().unwrap();
-        let cur_gen = self.gen.load(std::sync::atomic::Ordering::Relaxed); e.gen = cur_gen;
-        for s in &mut g.slots { if let Some(cur) = s.0 { if cur.key == e.key { if e.depth >= cur.depth { s.0 = Some(e); } return; } } }
+        let idx = self.bucket_index(e.key); let mut g = self.buckets[idx].lock().unwrap_or_else(PoisonError::into_inner);
+        let cur_gen = self.gen.load(std::sync::atomic::Ordering::Relaxed);
+        for s in &mut g.slots { if let Some(cur) = s.0 { if cur.key == e.key { s.0 = Some(if e.depth >= cur.depth { e } else { Entry { gen: cur_gen, ..cur } }); return; } } }
         for s in &mut g.slots { if s.0.is_none() { s.0 = Some(e); return; } }
-        let mut victim = 0usize; let mut keymin = (u32::MAX, u32::MAX);
-        for (i, s) in g.slots.iter().enumerate() { if let Some(cur) = s.0 { let k = (cur.depth, cur.gen); if k < keymin { keymin = k; victim = i; } } }
+        // Same age-weighted policy as the cozy table
+        let mut victim = 0usize; let mut keymin = (i64::MAX, u32::MAX);
+        for (i, s) in g.slots.iter().enumerate() { if let Some(cur) = s.0 { let k = (replace_value(cur.depth, cur.gen, cur_gen), cur.gen); if k < keymin { keymin = k; victim = i; } } }
+        if !force && (replace_value(e.depth, e.gen, cur_gen), e.gen) <= keymin { return; }
         g.slots[victim].0 = Some(e);
     }
+    /// Start a new search generation (once per `go`, from `new_search`).
     pub fn bump_generation(&self) { let _ = self.gen.fetch_add(1, std::sync::atomic::Ordering::Relaxed); }
+    pub fn generation(&self) -> u32 { self.gen.load(std::sync::atomic::Ordering::Relaxed) }
     fn bucket_index(&self, key: u64) -> usize { let mixed = key ^ (key >> 32); (mixed as usize) % self.buckets.len().max(1) }
 }
@@ -0,0 +1,82 @@
+//! Time-to-depth and effective branching factor (EBF) of a search, from the info
+//! its iterations report. EBF is how much each extra ply multiplies the node count:
+//! the geometric mean of `nodes(d) / nodes(d - 1)` over the iterations, with
+//! cumulative node counts. Depth 1 is left out; its handful of nodes is noise.
+
+use crate::search::alphabeta::{InfoHook, SearchResult};
+use std::sync::{Arc, Mutex};
+
+/// One completed iteration: cumulative nodes and time when it finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IterationPoint {
+    pub depth: u32,
+    pub nodes: u64,
+    pub time_ms: u64,
+}
+
+/// The iterations of one search, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepthCurve {
+    pub points: Vec<IterationPoint>,
+}
+
+impl DepthCurve {
+    /// Record an iteration; a repeated or shallower depth replaces the tail.
+    pub fn push(&mut self, r: &SearchResult) {
+        while self.points.last().is_some_and(|p| p.depth >= r.depth) { self.points.pop(); }
+        self.points.push(IterationPoint { depth: r.depth, nodes: r.nodes, time_ms: r.time_ms });
+    }
+
+    /// Node ratio of the iteration at `index` to the one before it.
+    pub fn step_ebf(&self, index: usize) -> Option<f64> {
+        let (prev, cur) = (self.points.get(index.checked_sub(1)?)?, self.points.get(index)?);
+        if prev.depth < 2 || prev.nodes == 0 || cur.depth != prev.depth + 1 { return None; }
+        Some(cur.nodes as f64 / prev.nodes as f64)
+    }
+
+    /// Effective branching factor over the iterations from depth 2 on.
+    pub fn ebf(&self) -> Option<f64> {
+        let first = self.points.iter().find(|p| p.depth >= 2 && p.nodes > 0)?;
+        let last = self.points.last()?;
+        if last.depth <= first.depth { return None; }
+        Some((last.nodes as f64 / first.nodes as f64).powf(1.0 / f64::from(last.depth - first.depth)))
+    }
+
+    /// Time until `depth` was completed.
+    pub fn time_to(&self, depth: u32) -> Option<u64> {
+        self.points.iter().find(|p| p.depth >= depth).map(|p| p.time_ms)
+    }
+
+    pub fn depth(&self) -> u32 { self.points.last().map_or(0, |p| p.depth) }
+
+    /// An info hook that records into a shared curve, for `Searcher::set_info_hook`.
+    pub fn recorder() -> (Arc<Mutex<DepthCurve>>, InfoHook) {
+        let curve = Arc::new(Mutex::new(DepthCurve::default()));
+        let sink = curve.clone();
+        (curve, Arc::new(move |r: &SearchResult| sink.lock().unwrap().push(r)))
+    }
+}
+
+/// Per-run summary over the curves of a position set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TtdSummary {
+    pub positions: usize,
+    /// Geometric mean of the positions' EBF
+    pub ebf: Option<f64>,
+    /// Deepest depth every position completed
+    pub common_depth: u32,
+    /// Mean time (ms) to each depth from 1 to `common_depth`
+    pub mean_time_ms: Vec<f64>,
+}
+
+impl TtdSummary {
+    pub fn from_curves(curves: &[DepthCurve]) -> Self {
+        let ebfs: Vec<f64> = curves.iter().filter_map(DepthCurve::ebf).collect();
+        let ebf = (!ebfs.is_empty()).then(|| (ebfs.iter().map(|e| e.ln()).sum::<f64>() / ebfs.len() as f64).exp());
+        let common_depth = curves.iter().map(DepthCurve::depth).min().unwrap_or(0);
+        let mean_time_ms = (1..=common_depth).map(|d| {
+            curves.iter().filter_map(|c| c.time_to(d)).map(|t| t as f64).sum::<f64>() / curves.len() as f64
+        }).collect();
+        Self { positions: curves.len(), ebf, common_depth, mean_time_ms }
+    }
+}
@@ -0,0 +1,105 @@
+use cozy_chess::{Board, Piece};
+use serde::{Deserialize, Serialize};
+
+/// Material in pawn units (P=1, N=B=3, R=5, Q=9) for both sides; 78 at the start.
+pub fn material_units(board: &Board) -> u32 {
+    let n = |p: Piece| board.pieces(p).len();
+    n(Piece::Pawn) + 3 * (n(Piece::Knight) + n(Piece::Bishop)) + 5 * n(Piece::Rook) + 9 * n(Piece::Queen)
+}
+
+/// Same count for a pleco board.
+#[cfg(feature = "board-pleco")]
+pub fn material_units_pleco(board: &pleco::Board) -> u32 {
+    use pleco::PieceType;
+    let n = |p: PieceType| board.piece_bb_both_players(p).count_bits() as u32;
+    n(PieceType::P) + 3 * (n(PieceType::N) + n(PieceType::B)) + 5 * n(PieceType::R) + 9 * n(PieceType::Q)
+}
+
+/// Logistic win/draw/loss model in the style GUIs expect from `UCI_ShowWDL`:
+/// W = σ((cp - a) / b), L = σ((-cp - a) / b), D = 1 - W - L, where a and b are
+/// linear in the material fraction t = material / 78.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WdlModel {
+    pub a: [f64; 2],
+    pub b: [f64; 2],
+}
+
+impl Default for WdlModel {
+    // Rough starting point; refit with piebot-calibrate on real games
+    fn default() -> Self { Self { a: [100.0, 100.0], b: [60.0, 40.0] } }
+}
+
+/// One observed position: eval (cp, side to move), material, result for the side to move.
+#[derive(Clone, Copy, Debug)]
+pub struct WdlSample { pub cp: i32, pub material: u32, pub result: i8 }
+
+fn sigmoid(x: f64) -> f64 { 1.0 / (1.0 + (-x).exp()) }
+
+impl WdlModel {
+    fn params(&self, material: u32) -> (f64, f64) {
+        let t = (material.min(78) as f64) / 78.0;
+        (self.a[0] + self.a[1] * t, (self.b[0] + self.b[1] * t).max(1.0))
+    }
+
+    /// Win/draw/loss probabilities for the side to move.
+    pub fn probabilities(&self, cp: i32, material: u32) -> (f64, f64, f64) {
+        let (a, b) = self.params(material);
+        let x = cp as f64;
+        let w = sigmoid((x - a) / b);
+        let l = sigmoid((-x - a) / b);
+        (w, (1.0 - w - l).max(0.0), l)
+    }
+
+    /// Expected score for the side to move (W + D/2): a scale shared by evals whose
+    /// centipawns mean different things, given a model fitted to each.
+    pub fn expected_score(&self, cp: i32, material: u32) -> f64 {
+        let (w, d, _) = self.probabilities(cp, material);
+        w + 0.5 * d
+    }
+
+    /// Probabilities in permille, summing to exactly 1000 (the `info ... wdl W D L` format).
+    pub fn wdl_permille(&self, cp: i32, material: u32) -> (u32, u32, u32) {
+        let (w, _, l) = self.probabilities(cp, material);
+        let w = (w * 1000.0).round() as u32;
+        let l = ((l * 1000.0).round() as u32).min(1000 - w);
+        (w, 1000 - w - l, l)
+    }
+
+    /// Load a model written by piebot-calibrate (the JSON line it prints).
+    pub fn load_json(path: &str) -> crate::Result<WdlModel> {
+        crate::error::read_json(path)
+    }
+
+    /// Mean negative log-likelihood of the observed results.
+    pub fn loss(&self, samples: &[WdlSample]) -> f64 {
+        if samples.is_empty() { return 0.0; }
+        let nll: f64 = samples.iter().map(|s| {
+            let (w, d, l) = self.probabilities(s.cp, s.material);
+            let p = match s.result { 1 => w, -1 => l, _ => d };
+            -p.max(1e-9).ln()
+        }).sum();
+        nll / samples.len() as f64
+    }
+
+    /// Maximum-likelihood fit by pattern search from `self`.
+    pub fn fit(&self, samples: &[WdlSample]) -> WdlModel {
+        let mut best = *self;
+        let mut best_loss = best.loss(samples);
+        let mut step = 32.0;
+        while step >= 0.25 {
+            let mut improved = false;
+            for i in 0..4 {
+                for dir in [1.0, -1.0] {
+                    let mut cand = best;
+                    if i < 2 { cand.a[i] += dir * step; } else { cand.b[i - 2] += dir * step; }
+                    // Keep the scale positive over the whole material range
+                    if cand.b[0] < 1.0 || cand.b[0] + cand.b[1] < 1.0 { continue; }
+                    let l = cand.loss(samples);
+                    if l < best_loss { best = cand; best_loss = l; improved = true; }
+                }
+            }
+            if !improved { step /= 2.0; }
+        }
+        best
+    }
+}
@@ -0,0 +1,163 @@
+//! Quick in-engine A/B: paired games between two `SearchParams` configurations on
+//! the cozy searcher, with an SPRT that stops the run once the result is decided.
+//! No clocks, no reviewer and no timelines (that is `compare_play`), so it fits a
+//! CI strength gate: both sides search to a fixed depth or node budget and the
+//! verdict is reproducible from the seed.
+
+use super::game::{startpos, GameState};
+use super::openings::OpeningGenerator;
+use crate::board::cozy::has_legal_moves;
+use crate::search::alphabeta::{SearchParams, Searcher};
+use cozy_chess::Color;
+
+/// Sequential probability ratio test between two Elo hypotheses, using the
+/// normal approximation of the trinomial log-likelihood ratio (as fishtest does).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprt {
+    /// Elo difference of the null hypothesis (no gain)
+    pub elo0: f64,
+    /// Elo difference of the alternative (the gain worth detecting)
+    pub elo1: f64,
+    /// False-positive rate
+    pub alpha: f64,
+    /// False-negative rate
+    pub beta: f64,
+}
+
+impl Default for Sprt {
+    fn default() -> Self { Self { elo0: 0.0, elo1: 10.0, alpha: 0.05, beta: 0.05 } }
+}
+
+/// Where an SPRT stands after some games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtStatus {
+    Continue,
+    /// The experiment is not better by `elo1`
+    AcceptH0,
+    /// The experiment is better than `elo0`
+    AcceptH1,
+}
+
+// Expected score of an Elo difference
+fn score_of(elo: f64) -> f64 { 1.0 / (1.0 + 10f64.powf(-elo / 400.0)) }
+
+impl Sprt {
+    /// Lower and upper LLR bounds: `ln(beta / (1 - alpha))` and `ln((1 - beta) / alpha)`.
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    /// Log-likelihood ratio of H1 over H0 for a tally; 0 until the games have variance.
+    pub fn llr(&self, t: &Tally) -> f64 {
+        let n = t.games() as f64;
+        if n == 0.0 { return 0.0; }
+        let s = t.score();
+        let var = (t.wins as f64 * (1.0 - s).powi(2) + t.draws as f64 * (0.5 - s).powi(2) + t.losses as f64 * s.powi(2)) / n;
+        if var <= 0.0 { return 0.0; }
+        let (s0, s1) = (score_of(self.elo0), score_of(self.elo1));
+        (s1 - s0) * (2.0 * s - s0 - s1) / (2.0 * var) * n
+    }
+
+    pub fn status(&self, t: &Tally) -> SprtStatus {
+        let (lower, upper) = self.bounds();
+        let llr = self.llr(t);
+        if llr >= upper { SprtStatus::AcceptH1 } else if llr <= lower { SprtStatus::AcceptH0 } else { SprtStatus::Continue }
+    }
+}
+
+/// Results from the experiment's side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Tally {
+    pub fn games(&self) -> u32 { self.wins + self.draws + self.losses }
+
+    /// Points per game, 0.5 before any game.
+    pub fn score(&self) -> f64 {
+        let n = self.games();
+        if n == 0 { 0.5 } else { (self.wins as f64 + 0.5 * self.draws as f64) / n as f64 }
+    }
+
+    /// Elo difference of the score; `None` for a clean sweep either way.
+    pub fn elo(&self) -> Option<f64> {
+        let s = self.score();
+        (s > 0.0 && s < 1.0).then(|| 400.0 * (s / (1.0 - s)).log10())
+    }
+
+    /// Add one game; `exp_points` is 1, 0.5 or 0.
+    pub fn add(&mut self, exp_points: f64) {
+        if exp_points > 0.5 { self.wins += 1 } else if exp_points < 0.5 { self.losses += 1 } else { self.draws += 1 }
+    }
+}
+
+/// An A/B run: two configurations, its length and the stop rule.
+#[derive(Clone, Copy, Debug)]
+pub struct AbConfig {
+    pub base: SearchParams,
+    pub exp: SearchParams,
+    /// Game pairs at most; each opening is played once with each colour
+    pub max_pairs: usize,
+    /// Plies before a game is adjudicated a draw
+    pub max_plies: usize,
+    /// Random opening plies (0 = start position every pair)
+    pub random_plies: usize,
+    pub seed: u64,
+    /// Stop as soon as the SPRT accepts either hypothesis; `None` plays every pair
+    pub sprt: Option<Sprt>,
+}
+
+/// Outcome of `run_ab`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AbResult {
+    pub tally: Tally,
+    pub llr: Option<f64>,
+    pub status: SprtStatus,
+}
+
+// Points for White; the game is played from `game`'s start position
+fn play_game(game: &mut GameState, white: (&mut Searcher, SearchParams), black: (&mut Searcher, SearchParams), max_plies: usize) -> f64 {
+    white.0.new_game();
+    black.0.new_game();
+    game.reset();
+    for _ in 0..max_plies {
+        let board = game.board();
+        if !has_legal_moves(board) {
+            if board.checkers().is_empty() { return 0.5; }
+            return if board.side_to_move() == Color::White { 0.0 } else { 1.0 };
+        }
+        if game.adjudicate().is_some() { return 0.5; }
+        let (s, p) = if board.side_to_move() == Color::White { (&mut *white.0, white.1) } else { (&mut *black.0, black.1) };
+        s.new_search();
+        let res = s.search_with_params(board, p);
+        if !res.bestmove.as_deref().is_some_and(|u| game.play_uci(u)) { return 0.5; }
+    }
+    // Adjudicated a draw at the ply limit
+    0.5
+}
+
+/// Play the pairs of `cfg`, calling `on_pair` with the running result after each
+/// one (progress lines, or a caller's own stop rule: return `false` to stop).
+pub fn run_ab(cfg: &AbConfig, mut on_pair: impl FnMut(&AbResult) -> bool) -> AbResult {
+    let (mut base, mut exp) = (Searcher::default(), Searcher::default());
+    let mut openings = OpeningGenerator::new(cfg.seed, cfg.random_plies.div_ceil(2), cfg.random_plies);
+    let mut game = GameState::default();
+    let mut result = AbResult { tally: Tally::default(), llr: cfg.sprt.map(|_| 0.0), status: SprtStatus::Continue };
+    for _ in 0..cfg.max_pairs {
+        let start = if cfg.random_plies == 0 { startpos() } else { openings.generate(None).unwrap_or_else(startpos) };
+        game.reset_to(&start);
+        let exp_white = play_game(&mut game, (&mut exp, cfg.exp), (&mut base, cfg.base), cfg.max_plies);
+        let exp_black = 1.0 - play_game(&mut game, (&mut base, cfg.base), (&mut exp, cfg.exp), cfg.max_plies);
+        result.tally.add(exp_white);
+        result.tally.add(exp_black);
+        if let Some(sprt) = cfg.sprt {
+            result.llr = Some(sprt.llr(&result.tally));
+            result.status = sprt.status(&result.tally);
+        }
+        if !on_pair(&result) || result.status != SprtStatus::Continue { break; }
+    }
+    result
+}
@@ -0,0 +1,56 @@
+use cozy_chess::{Board, Color, Piece, BitBoard};
+
+/// Why a game was stopped before checkmate/stalemate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Adjudication {
+    Threefold,
+    FiftyMove,
+    InsufficientMaterial,
+    MaxPlies,
+}
+
+/// Position keys of a game in play order, for repetition detection.
+#[derive(Clone, Debug, Default)]
+pub struct GameHistory {
+    keys: Vec<u64>,
+}
+
+impl GameHistory {
+    pub fn new(start: &Board) -> Self { Self { keys: vec![start.hash()] } }
+    /// Start over from `start`, keeping the allocation.
+    pub fn reset(&mut self, start: &Board) { self.keys.clear(); self.keys.push(start.hash()); }
+    /// Record the position reached after a move.
+    pub fn push(&mut self, board: &Board) { self.keys.push(board.hash()); }
+    pub fn len(&self) -> usize { self.keys.len() }
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+
+    /// Occurrences of the current position since the last irreversible move.
+    pub fn repetitions(&self, board: &Board) -> usize {
+        let Some(&cur) = self.keys.last() else { return 0; };
+        let window = (board.halfmove_clock() as usize + 1).min(self.keys.len());
+        self.keys[self.keys.len() - window..].iter().filter(|&&k| k == cur).count()
+    }
+}
+
+/// Neither side can deliver mate: K vs K, K+minor vs K, or K+B vs K+B with same-coloured bishops.
+pub fn insufficient_material(board: &Board) -> bool {
+    let heavy = board.pieces(Piece::Pawn) | board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    if !heavy.is_empty() { return false; }
+    let knights = board.pieces(Piece::Knight);
+    let bishops = board.pieces(Piece::Bishop);
+    let minors = (knights | bishops).len();
+    if minors <= 1 { return true; }
+    if !knights.is_empty() { return false; }
+    // Bishops only: drawn when every bishop stands on the same square colour
+    let on_dark = (bishops & BitBoard::DARK_SQUARES).len();
+    let one_each = (board.colors(Color::White) & bishops).len() == 1 && (board.colors(Color::Black) & bishops).len() == 1;
+    one_each && (on_dark == 0 || on_dark == minors)
+}
+
+/// Draw adjudication for a position that still has legal moves.
+pub fn adjudicate(board: &Board, history: &GameHistory) -> Option<Adjudication> {
+    if history.repetitions(board) >= 3 { return Some(Adjudication::Threefold); }
+    if board.halfmove_clock() >= 100 { return Some(Adjudication::FiftyMove); }
+    if insufficient_material(board) { return Some(Adjudication::InsufficientMaterial); }
+    None
+}
@@ -0,0 +1,51 @@
+//! EPD export of self-play data for external tuners (Texel-style): one line per
+//! shard position with the game result as `c0` and a score for the side to move as
+//! `ce`. Shard records keep only a position's hash, so the export is written from
+//! the games themselves (or the `games.jsonl` saved beside the shards), position for
+//! position as `flatten_game_to_records` does.
+
+use super::{game_positions, GameRecord};
+use crate::search::label::{label_positions, LabelMode};
+use crate::search::options::EngineOptions;
+use crate::TitanError;
+use cozy_chess::Board;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// PGN result string of a White-relative result (1, 0, -1).
+pub fn result_str(result: i8) -> &'static str {
+    match result.signum() {
+        1 => "1-0",
+        -1 => "0-1",
+        _ => "1/2-1/2",
+    }
+}
+
+/// One EPD line: the four FEN fields (Shredder castling for Chess960 starts), `c0`
+/// with the result and `ce` with `score` (centipawns for the side to move).
+pub fn epd_line(board: &Board, result: i8, score: i32) -> String {
+    let fen = crate::io::fen::board_fen(board);
+    let fields: Vec<&str> = fen.split_whitespace().take(4).collect();
+    format!("{} c0 \"{}\"; ce {};", fields.join(" "), result_str(result), score)
+}
+
+/// Score every position of `games` (`mode` on `threads` workers, as the `score`
+/// binary does) and write them to `path` as EPD. Returns the number of lines.
+pub fn write_epd(games: &[GameRecord], path: impl AsRef<Path>, opts: &EngineOptions, mode: LabelMode, threads: usize) -> crate::Result<usize> {
+    let path = path.as_ref();
+    let mut boards = Vec::new();
+    let mut results = Vec::new();
+    for g in games {
+        let positions = game_positions(g);
+        results.resize(results.len() + positions.len(), g.result);
+        boards.extend(positions);
+    }
+    let labels = label_positions(&boards, opts, mode, threads)?;
+    let file = std::fs::File::create(path).map_err(|e| TitanError::io(path, e))?;
+    let mut out = BufWriter::new(file);
+    for ((board, &result), label) in boards.iter().zip(&results).zip(&labels) {
+        writeln!(out, "{}", epd_line(board, result, label.score)).map_err(|e| TitanError::io(path, e))?;
+    }
+    out.flush().map_err(|e| TitanError::io(path, e))?;
+    Ok(labels.len())
+}
@@ -0,0 +1,66 @@
+//! Chess960 (FRC) and double-FRC start positions. Back ranks are numbered 0..960
+//! by Scharnagl's scheme (518 is the standard setup); boards carry castling rights
+//! by rook file, so they print and parse as Shredder FEN (see `io::fen::board_fen`).
+//! The searcher plays castling as king-takes-rook, which covers every setup.
+
+use cozy_chess::{Board, Piece};
+use rand::Rng;
+
+/// Number of Chess960 back ranks.
+pub const FRC_POSITIONS: u16 = 960;
+/// Scharnagl number of the standard back rank (RNBQKBNR).
+pub const STANDARD_INDEX: u16 = 518;
+
+// Knight pairs on the five files left after bishops and queen, by the remaining digit
+const KNIGHTS: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+/// Back rank number `index` (taken mod 960), files a to h.
+pub fn back_rank(index: u16) -> [Piece; 8] {
+    let mut n = (index % FRC_POSITIONS) as usize;
+    let mut rank: [Option<Piece>; 8] = [None; 8];
+    rank[2 * (n % 4) + 1] = Some(Piece::Bishop);
+    n /= 4;
+    rank[2 * (n % 4)] = Some(Piece::Bishop);
+    n /= 4;
+    let empty = |rank: &[Option<Piece>; 8]| -> Vec<usize> { (0..8).filter(|&f| rank[f].is_none()).collect() };
+    rank[empty(&rank)[n % 6]] = Some(Piece::Queen);
+    n /= 6;
+    let free = empty(&rank);
+    let (a, b) = KNIGHTS[n];
+    rank[free[a]] = Some(Piece::Knight);
+    rank[free[b]] = Some(Piece::Knight);
+    // Rook, king, rook on the three files left
+    for (f, p) in empty(&rank).into_iter().zip([Piece::Rook, Piece::King, Piece::Rook]) { rank[f] = Some(p); }
+    rank.map(|p| p.expect("every file filled"))
+}
+
+/// Shredder FEN of the start position with White on back rank `white` and Black on
+/// `black`, all castling rights held.
+pub fn frc_fen(white: u16, black: u16) -> String {
+    fn row(rank: &[Piece; 8], upper: bool) -> String {
+        rank.iter().map(|&p| { let c: char = p.into(); if upper { c.to_ascii_uppercase() } else { c } }).collect()
+    }
+    // Short-side rook first, then long, as cozy-chess writes them
+    fn rights(rank: &[Piece; 8], upper: bool) -> String {
+        let files: Vec<usize> = (0..8).filter(|&f| rank[f] == Piece::Rook).collect();
+        files.iter().rev().map(|&f| { let c = (b'a' + f as u8) as char; if upper { c.to_ascii_uppercase() } else { c } }).collect()
+    }
+    let (w, b) = (back_rank(white), back_rank(black));
+    format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w {}{} - 0 1", row(&b, false), row(&w, true), rights(&w, true), rights(&b, false))
+}
+
+/// The start position for back ranks `white` and `black`.
+pub fn frc_board(white: u16, black: u16) -> Board {
+    Board::from_fen(&frc_fen(white, black), true).expect("Chess960 start positions are valid")
+}
+
+/// A random Chess960 position (both sides on the same back rank).
+pub fn random_frc(rng: &mut impl Rng) -> Board {
+    let n = rng.gen_range(0..FRC_POSITIONS);
+    frc_board(n, n)
+}
+
+/// A random double-FRC position (each side's back rank drawn independently).
+pub fn random_dfrc(rng: &mut impl Rng) -> Board {
+    frc_board(rng.gen_range(0..FRC_POSITIONS), rng.gen_range(0..FRC_POSITIONS))
+}
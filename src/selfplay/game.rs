@@ -0,0 +1,76 @@
+use cozy_chess::{Board, Move};
+use std::sync::OnceLock;
+use super::adjudicate::{adjudicate, Adjudication, GameHistory};
+
+/// The standard start position, built once: `Board::default()` goes through the
+/// board builder's validation every time.
+pub fn startpos() -> Board {
+    static START: OnceLock<Board> = OnceLock::new();
+    START.get_or_init(Board::default).clone()
+}
+
+/// A game in play: its start position, the current board, the moves played and
+/// the position keys for repetition checks. `reset`/`reset_to` start the next game
+/// in place, keeping the allocations, so game loops need no FEN parsing or
+/// per-game setup.
+#[derive(Clone, Debug)]
+pub struct GameState {
+    start: Board,
+    board: Board,
+    moves: Vec<Move>,
+    history: GameHistory,
+}
+
+impl Default for GameState {
+    fn default() -> Self { Self::new(startpos()) }
+}
+
+impl GameState {
+    pub fn new(start: Board) -> Self {
+        let history = GameHistory::new(&start);
+        Self { board: start.clone(), start, moves: Vec::with_capacity(256), history }
+    }
+
+    /// Back to this game's start position.
+    pub fn reset(&mut self) {
+        self.board.clone_from(&self.start);
+        self.moves.clear();
+        self.history.reset(&self.start);
+    }
+
+    /// Start a new game from `start`.
+    pub fn reset_to(&mut self, start: &Board) {
+        self.start.clone_from(start);
+        self.reset();
+    }
+
+    pub fn start(&self) -> &Board { &self.start }
+    pub fn board(&self) -> &Board { &self.board }
+    pub fn moves(&self) -> &[Move] { &self.moves }
+    pub fn history(&self) -> &GameHistory { &self.history }
+    pub fn ply(&self) -> usize { self.moves.len() }
+
+    /// Play a legal move.
+    pub fn play(&mut self, m: Move) {
+        self.board.play(m);
+        self.moves.push(m);
+        self.history.push(&self.board);
+    }
+
+    /// Parse a move in the searcher's notation (`Move`'s `Display`), if it is legal
+    /// here. Parses the string once instead of formatting every legal move.
+    pub fn find_move(&self, uci: &str) -> Option<Move> {
+        uci.parse::<Move>().ok().filter(|&m| self.board.is_legal(m))
+    }
+
+    /// Play a move given in the searcher's notation; `false` if it is not legal here.
+    pub fn play_uci(&mut self, uci: &str) -> bool {
+        match self.find_move(uci) {
+            Some(m) => { self.play(m); true }
+            None => false,
+        }
+    }
+
+    /// Draw adjudication for the current position (see `adjudicate`).
+    pub fn adjudicate(&self) -> Option<Adjudication> { adjudicate(&self.board, &self.history) }
+}
@@ -1,12 +1,23 @@
+pub mod ab;
+pub mod adjudicate;
+pub mod epd;
+pub mod frc;
+pub mod game;
+pub mod openings;
+pub mod review;
+pub mod stats;
+
 use cozy_chess::{Board, Move, Color};
 use rand::{SeedableRng, Rng};
 use rand::rngs::SmallRng;
 use rand_distr::{Gamma, Distribution};
 use crate::search::alphabeta::{Searcher, SearchParams};
 use crate::search::zobrist;
+use crate::board::cozy::{decode_move, encode_move, has_legal_moves};
 use std::fs::{File, create_dir_all};
 use std::io::{Write, Read, BufWriter, BufReader};
 use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
 pub struct SelfPlayParams {
@@ -25,51 +36,69 @@ pub struct SelfPlayParams {
     pub temperature_moves: usize,  // apply temperature for first N plies
     pub openings_path: Option<PathBuf>, // optional path to FEN list (one per line)
     pub temperature_tau_final: f32, // anneal temperature to this by temperature_moves
+    pub random_opening_plies: usize, // filtered random-walk openings of up to N plies when no openings file; 0 = off
+    pub opening_sources: Vec<openings::OpeningSource>, // weighted curriculum; empty = openings_path / random_opening_plies
+}
+
+impl SelfPlayParams {
+    /// The opening sources games draw from: `opening_sources` if given, else the
+    /// single source implied by `openings_path` / `random_opening_plies`.
+    pub fn curriculum(&self) -> Vec<openings::OpeningSource> {
+        use openings::{OpeningKind, OpeningSource};
+        if !self.opening_sources.is_empty() { return self.opening_sources.clone(); }
+        let kind = match &self.openings_path {
+            Some(p) => OpeningKind::Book(p.clone()),
+            None if self.random_opening_plies > 0 => OpeningKind::Random(self.random_opening_plies),
+            None => OpeningKind::Start,
+        };
+        vec![OpeningSource { kind, weight: 1.0, max_plies: None }]
+    }
 }
 
 pub struct GameRecord {
-    pub moves: Vec<String>,
+    pub start: Board,
+    /// Moves played, `encode_move`d
+    pub moves: Vec<u16>,
     pub result: i8, // 1 white win, 0 draw, -1 black win
 }
 
+impl GameRecord {
+    /// The moves as UCI strings (in cozy-chess's castling notation), for export.
+    pub fn uci_moves(&self) -> Vec<String> {
+        self.moves.iter().map_while(|&c| decode_move(c)).map(|m| m.to_string()).collect()
+    }
+}
+
 pub fn generate_games(params: &SelfPlayParams) -> Vec<GameRecord> {
     let mut rng = SmallRng::seed_from_u64(params.seed);
-    let openings = load_openings(params);
+    // Callers validate the sources up front; here a bad file is reported and the
+    // games fall back to the start position.
+    let mut mix = openings::OpeningMix::new(&params.curriculum(), params.seed).unwrap_or_else(|e| {
+        eprintln!("openings: {}", e);
+        openings::OpeningMix::new(&[], params.seed).expect("start position needs no files")
+    });
     let mut games = Vec::with_capacity(params.games);
+    let mut game = game::GameState::default();
     for gi in 0..params.games {
-        let mut board = if !openings.is_empty() {
-            let idx = (rng.gen::<u64>() ^ (gi as u64)) as usize % openings.len();
-            openings[idx].clone()
-        } else { Board::default() };
-        let mut record = GameRecord { moves: Vec::new(), result: 0 };
-        let mut plies = 0usize;
-        loop {
-            if plies >= params.max_plies { break; }
+        let (start, source_max_plies) = mix.sample(&mut rng, gi);
+        let max_plies = source_max_plies.unwrap_or(params.max_plies);
+        game.reset_to(&start);
+        let mut result = 0;
+        while game.ply() < max_plies {
+            let board = game.board();
             // Determine end conditions
-            let mut has_move = false;
-            board.generate_moves(|ml| { if !ml.is_empty() { has_move = true; } false });
-            if !has_move {
-                if (board.checkers()).is_empty() { record.result = 0; } else { record.result = if board.side_to_move() == Color::White { -1 } else { 1 }; }
+            if !has_legal_moves(board) {
+                if !board.checkers().is_empty() { result = if board.side_to_move() == Color::White { -1 } else { 1 }; }
                 break;
             }
-            {
-                // choose move
-                let mv = if params.use_engine {
-                    select_engine_move(&board, params, plies)
-                } else {
-                    select_random_move(&board, &mut rng)
-                };
-                if let Some(m) = mv {
-                    let mstr = format!("{}", m);
-                    record.moves.push(mstr);
-                    board.play(m);
-                    plies += 1;
-                } else {
-                    break;
-                }
-            }
+            let mv = if params.use_engine {
+                select_engine_move(&game, params)
+            } else {
+                select_random_move(board, &mut rng)
+            };
+            match mv { Some(m) => game.play(m), None => break }
         }
-        games.push(record);
+        games.push(GameRecord { start, moves: game.moves().iter().map(|&m| encode_move(m)).collect(), result });
     }
     games
 }
@@ -80,7 +109,8 @@ fn select_random_move(board: &Board, rng: &mut SmallRng) -> Option<Move> {
     if moves.is_empty() { None } else { Some(moves[rng.gen_range(0..moves.len())]) }
 }
 
-fn select_engine_move(board: &Board, params: &SelfPlayParams, ply_idx: usize) -> Option<Move> {
+fn select_engine_move(game: &game::GameState, params: &SelfPlayParams) -> Option<Move> {
+    let (board, ply_idx) = (game.board(), game.ply());
     // If temperature or Dirichlet requested, compute root policy and sample
     let use_temp = params.temperature_tau > 0.0 && ply_idx < params.temperature_moves;
     let use_dir = params.dirichlet_epsilon > 0.0 && ply_idx < params.dirichlet_plies;
@@ -102,7 +132,7 @@ fn select_engine_move(board: &Board, params: &SelfPlayParams, ply_idx: usize) ->
             p.max_nodes = Some(10_000);
             p.movetime = params.movetime_ms.map(|t| std::time::Duration::from_millis(t));
             let r = s.search_with_params(&child, p);
-            let score_from_parent = -(r.score_cp as f32);
+            let score_from_parent = -(r.score.value() as f32);
             scores.push(score_from_parent);
         }
         // Softmax with temperature
@@ -148,36 +178,7 @@ fn select_engine_move(board: &Board, params: &SelfPlayParams, ply_idx: usize) ->
     p.max_nodes = Some(20_000);
     p.movetime = params.movetime_ms.map(|t| std::time::Duration::from_millis(t));
     let res = s.search_with_params(board, p);
-    res.bestmove.and_then(|s| {
-        let mut choice = None;
-        board.generate_moves(|ml| { for m in ml { if format!("{}", m) == s { choice = Some(m); break; } } choice.is_some() });
-        choice
-    })
-}
-
-fn load_openings(params: &SelfPlayParams) -> Vec<Board> {
-    let mut out = Vec::new();
-    if let Some(ref p) = params.openings_path {
-        if let Ok(mut f) = std::fs::File::open(p) {
-            let mut s = String::new();
-            if f.read_to_string(&mut s).is_ok() {
-                for line in s.lines() {
-                    let raw = line.trim();
-                    if raw.is_empty() || raw.starts_with('#') { continue; }
-                    // Support EPD (4 fields) by padding halfmove/fullmove
-                    let parts: Vec<&str> = raw.split_whitespace().collect();
-                    let fen = if parts.len() >= 6 {
-                        parts[0..6].join(" ")
-                    } else if parts.len() >= 4 {
-                        let mut v = parts[0..4].to_vec();
-                        v.push("0"); v.push("1"); v.join(" ")
-                    } else { raw.to_string() };
-                    if let Ok(b) = Board::from_fen(&fen, false) { out.push(b); }
-                }
-            }
-        }
-    }
-    out
+    res.bestmove.and_then(|s| game.find_move(&s))
 }
 
 #[repr(C)]
@@ -189,25 +190,78 @@ pub struct RecordBin {
     pub _pad: u16,  // reserved
 }
 
-pub const SHARD_MAGIC: &[u8; 8] = b"PIESP001"; // Pie Self-Play v1
+pub const SHARD_MAGIC: &[u8; 8] = b"PIESP002"; // Pie Self-Play v2: JSON metadata header
+/// Headerless v1 shards, still readable
+pub const SHARD_MAGIC_V1: &[u8; 8] = b"PIESP001";
 pub const RECORD_SIZE: usize = 8 + 1 + 1 + 2;
+/// Upper bound on the metadata header; larger values mean a corrupt shard.
+const MAX_META_LEN: usize = 1 << 20;
+
+/// How a shard was produced, written as length-prefixed JSON after the magic so
+/// datasets stay traceable when generations of networks are mixed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShardMeta {
+    /// `piebot` crate version that generated the games
+    pub engine_version: String,
+    pub use_engine: bool,
+    pub depth: u32,
+    pub movetime_ms: Option<u64>,
+    pub threads: usize,
+    pub seed: u64,
+    pub games: usize,
+    pub max_plies: usize,
+    /// Seconds since the Unix epoch when generation finished
+    pub timestamp: u64,
+    /// Opening sources in `--source` syntax
+    pub openings: Vec<String>,
+}
+
+impl ShardMeta {
+    pub fn from_params(params: &SelfPlayParams) -> Self {
+        Self {
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            use_engine: params.use_engine,
+            depth: params.depth,
+            movetime_ms: params.movetime_ms,
+            threads: params.threads,
+            seed: params.seed,
+            games: params.games,
+            max_plies: params.max_plies,
+            timestamp: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            openings: params.curriculum().iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// The positions a game's shard records describe: each one before a move is played,
+/// up to the first undecodable or illegal move.
+pub fn game_positions(game: &GameRecord) -> Vec<Board> {
+    let mut boards = Vec::with_capacity(game.moves.len());
+    let mut state = game::GameState::new(game.start.clone());
+    for &code in &game.moves {
+        let board = state.board();
+        boards.push(board.clone());
+        match decode_move(code).filter(|&m| board.is_legal(m)) {
+            Some(m) => state.play(m),
+            None => break,
+        }
+    }
+    boards
+}
 
 pub fn flatten_game_to_records(game: &GameRecord) -> Vec<RecordBin> {
-    let mut recs = Vec::new();
-    let mut board = Board::default();
-    for mv_str in &game.moves {
-        let key = zobrist::compute(&board);
+    game_positions(game).iter().map(|board| {
+        let key = zobrist::compute(board);
         let stm = if board.side_to_move() == Color::White { 0u8 } else { 1u8 };
-        recs.push(RecordBin { key, result: game.result, stm, _pad: 0 });
-        // apply move
-        let mut chosen = None;
-        board.generate_moves(|ml| { for m in ml { if format!("{}", m) == *mv_str { chosen = Some(m); break; } } chosen.is_some() });
-        if let Some(m) = chosen { board.play(m); } else { break; }
-    }
-    recs
+        RecordBin { key, result: game.result, stm, _pad: 0 }
+    }).collect()
 }
 
-pub fn write_shards<P: AsRef<Path>>(games: &[GameRecord], out_dir: P, max_records_per_shard: usize) -> std::io::Result<Vec<PathBuf>> {
+/// Write games as fixed-size records, `max_records_per_shard` per file, each file
+/// starting with the magic and `meta`.
+pub fn write_shards<P: AsRef<Path>>(games: &[GameRecord], out_dir: P, max_records_per_shard: usize, meta: &ShardMeta) -> std::io::Result<Vec<PathBuf>> {
+    let meta_json = serde_json::to_vec(meta).map_err(std::io::Error::other)?;
     create_dir_all(&out_dir)?;
     let mut shard_index = 0usize;
     let mut rec_in_shard = 0usize;
@@ -218,6 +272,8 @@ pub fn write_shards<P: AsRef<Path>>(games: &[GameRecord], out_dir: P, max_record
         let path = out_dir.as_ref().join(format!("shard_{:06}.bin", idx));
         let mut f = BufWriter::new(File::create(&path)?);
         f.write_all(SHARD_MAGIC)?;
+        f.write_all(&(meta_json.len() as u32).to_le_bytes())?;
+        f.write_all(&meta_json)?;
         out_paths.push(path);
         Ok(f)
     };
@@ -245,25 +301,80 @@ pub fn write_shards<P: AsRef<Path>>(games: &[GameRecord], out_dir: P, max_record
     Ok(out_paths)
 }
 
-pub fn read_shard<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<RecordBin>> {
-    let mut f = BufReader::new(File::open(path)?);
+/// File beside the shards holding every game as its start and moves, from which
+/// the shards' positions can be rebuilt (see `read_games`).
+pub const GAMES_FILE: &str = "games.jsonl";
+
+// One line of `GAMES_FILE`
+#[derive(Serialize, Deserialize)]
+struct GameLine { start: String, moves: Vec<u16>, result: i8 }
+
+/// Write `games` to `GAMES_FILE` in `out_dir`, one JSON object per line.
+pub fn write_games<P: AsRef<Path>>(games: &[GameRecord], out_dir: P) -> std::io::Result<PathBuf> {
+    create_dir_all(&out_dir)?;
+    let path = out_dir.as_ref().join(GAMES_FILE);
+    let mut w = BufWriter::new(File::create(&path)?);
+    for g in games {
+        let line = GameLine { start: crate::io::fen::board_fen(&g.start), moves: g.moves.clone(), result: g.result };
+        serde_json::to_writer(&mut w, &line).map_err(std::io::Error::other)?;
+        w.write_all(b"\n")?;
+    }
+    w.flush()?;
+    Ok(path)
+}
+
+/// The games of a shard directory's `GAMES_FILE`.
+pub fn read_games<P: AsRef<Path>>(dir: P) -> std::io::Result<Vec<GameRecord>> {
+    let text = std::fs::read_to_string(dir.as_ref().join(GAMES_FILE))?;
+    text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()).map(|(i, l)| {
+        let g: GameLine = serde_json::from_str(l).map_err(|e| invalid(format!("game {}: {}", i + 1, e)))?;
+        let start = crate::io::fen::parse_fen_line(&g.start).map_err(|e| invalid(format!("game {}: {}", i + 1, e)))?;
+        Ok(GameRecord { start, moves: g.moves, result: g.result })
+    }).collect()
+}
+
+fn invalid(msg: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg.into())
+}
+
+// Consume the magic and header; `None` for v1 shards, which have no metadata
+fn read_shard_header<R: Read>(f: &mut R) -> std::io::Result<Option<ShardMeta>> {
     let mut magic = [0u8; 8];
     f.read_exact(&mut magic)?;
-    if &magic != SHARD_MAGIC { return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "bad magic")); }
-    let mut recs = Vec::new();
-    let mut buf = [0u8; RECORD_SIZE];
-    loop {
-        match f.read_exact(&mut buf) {
-            Ok(()) => {
-                let mut key_bytes = [0u8; 8]; key_bytes.copy_from_slice(&buf[0..8]);
-                let key = u64::from_le_bytes(key_bytes);
-                let result = buf[8] as i8;
-                let stm = buf[9];
-                recs.push(RecordBin { key, result, stm, _pad: 0 });
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
-            Err(e) => return Err(e),
-        }
+    if &magic == SHARD_MAGIC_V1 { return Ok(None); }
+    if &magic != SHARD_MAGIC { return Err(invalid("bad magic")); }
+    let mut len = [0u8; 4];
+    f.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_META_LEN { return Err(invalid("shard metadata too large")); }
+    let mut json = vec![0u8; len];
+    f.read_exact(&mut json)?;
+    serde_json::from_slice(&json).map(Some).map_err(|e| invalid(format!("shard metadata: {}", e)))
+}
+
+/// The metadata header of a shard; `None` for headerless v1 shards.
+pub fn read_shard_meta<P: AsRef<Path>>(path: P) -> std::io::Result<Option<ShardMeta>> {
+    read_shard_header(&mut BufReader::new(File::open(path)?))
+}
+
+/// All records of a shard. A partial trailing record or an out-of-range result or
+/// side to move is an `InvalidData` error rather than silently dropped or kept.
+pub fn read_shard<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<RecordBin>> {
+    let mut f = BufReader::new(File::open(path)?);
+    read_shard_header(&mut f)?;
+    let mut bytes = Vec::new();
+    f.read_to_end(&mut bytes)?;
+    if bytes.len() % RECORD_SIZE != 0 {
+        return Err(invalid(format!("truncated record {} ({} of {} bytes)", bytes.len() / RECORD_SIZE, bytes.len() % RECORD_SIZE, RECORD_SIZE)));
     }
-    Ok(recs)
+    bytes.chunks_exact(RECORD_SIZE).enumerate().map(|(i, buf)| {
+        let mut key_bytes = [0u8; 8]; key_bytes.copy_from_slice(&buf[0..8]);
+        let key = u64::from_le_bytes(key_bytes);
+        let result = buf[8] as i8;
+        let stm = buf[9];
+        if !(-1..=1).contains(&result) || stm > 1 {
+            return Err(invalid(format!("bad record {} (result {}, stm {})", i, result, stm)));
+        }
+        Ok(RecordBin { key, result, stm, _pad: 0 })
+    }).collect()
 }
@@ -0,0 +1,213 @@
+use cozy_chess::{Board, Color, Move};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use crate::board::cozy::has_legal_moves;
+use crate::error::TitanError;
+use crate::search::alphabeta::{Searcher, SearchParams};
+use std::path::PathBuf;
+
+/// Sanity checks a random-walk position must pass to be used as an opening.
+#[derive(Clone, Copy, Debug)]
+pub struct OpeningFilter {
+    /// Reject positions the engine scores beyond this (side to move)
+    pub max_eval_cp: i32,
+    /// Minimum pieces on the board, kings and pawns included
+    pub min_pieces: u32,
+    /// Search depth of the eval check; 0 uses quiescence only
+    pub eval_depth: u32,
+    /// Random walks tried per position before giving up
+    pub max_attempts: usize,
+}
+
+impl Default for OpeningFilter {
+    fn default() -> Self { Self { max_eval_cp: 150, min_pieces: 20, eval_depth: 2, max_attempts: 200 } }
+}
+
+/// Random-walk opening generator with an engine sanity filter. Walk lengths are
+/// drawn from `min_plies..=max_plies`; when a side to move is requested the length
+/// is nudged by one ply to match it, so callers can balance colours.
+pub struct OpeningGenerator {
+    rng: SmallRng,
+    start: Board,
+    min_plies: usize,
+    max_plies: usize,
+    filter: OpeningFilter,
+    searcher: Searcher,
+}
+
+impl OpeningGenerator {
+    pub fn new(seed: u64, min_plies: usize, max_plies: usize) -> Self {
+        Self { rng: SmallRng::seed_from_u64(seed), start: Board::default(), min_plies, max_plies: max_plies.max(min_plies), filter: OpeningFilter::default(), searcher: Searcher::default() }
+    }
+    pub fn with_start(mut self, start: Board) -> Self { self.start = start; self }
+    pub fn with_filter(mut self, filter: OpeningFilter) -> Self { self.filter = filter; self }
+
+    /// Whether `board` passes the filter: not in check, has legal moves, enough
+    /// material on the board and a roughly balanced engine eval.
+    pub fn acceptable(&mut self, board: &Board) -> bool {
+        if !board.checkers().is_empty() || board.occupied().len() < self.filter.min_pieces || !has_legal_moves(board) { return false; }
+        let cp = if self.filter.eval_depth == 0 { self.searcher.qsearch_eval_cp(board) } else {
+            self.searcher.new_game();
+            let p = SearchParams { depth: self.filter.eval_depth, use_tt: true, order_captures: true, ..Default::default() };
+            self.searcher.search_with_params(board, p).score.value()
+        };
+        cp.abs() < self.filter.max_eval_cp
+    }
+
+    /// One filtered position, optionally with the given side to move. `None` if no
+    /// walk passed the filter within `max_attempts`.
+    pub fn generate(&mut self, stm: Option<Color>) -> Option<Board> {
+        for _ in 0..self.filter.max_attempts.max(1) {
+            let mut plies = self.rng.gen_range(self.min_plies..=self.max_plies);
+            if let Some(c) = stm {
+                let flips = if self.start.side_to_move() == c { 0 } else { 1 };
+                if plies % 2 != flips { plies += 1; }
+            }
+            let mut b = self.start.clone();
+            let mut ok = true;
+            for _ in 0..plies {
+                let mut moves: Vec<Move> = Vec::new();
+                b.generate_moves(|ml| { moves.extend(ml); false });
+                if moves.is_empty() { ok = false; break; }
+                b.play(moves[self.rng.gen_range(0..moves.len())]);
+            }
+            if ok && self.acceptable(&b) { return Some(b); }
+        }
+        None
+    }
+
+    /// `n` positions alternating white/black to move; stops early if the filter
+    /// cannot be satisfied.
+    pub fn generate_n(&mut self, n: usize) -> Vec<Board> {
+        let mut out = Vec::with_capacity(n);
+        for i in 0..n {
+            let stm = if i % 2 == 0 { Color::White } else { Color::Black };
+            match self.generate(Some(stm)) { Some(b) => out.push(b), None => break }
+        }
+        out
+    }
+}
+
+/// Where a self-play game's start position comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OpeningKind {
+    /// The standard start position
+    Start,
+    /// Filtered random walk of up to this many plies (see `OpeningGenerator`)
+    Random(usize),
+    /// Quiet book positions, one FEN/EPD per line
+    Book(PathBuf),
+    /// Tactical suite (EPD; operations such as `bm` are ignored)
+    Tactical(PathBuf),
+    /// Random Chess960 start position (see `frc`)
+    Frc,
+    /// Random double-FRC start position: each side's back rank drawn independently
+    Dfrc,
+}
+
+/// One opening source in a self-play curriculum. Games draw a source with
+/// probability proportional to `weight`; `max_plies` caps games from that source
+/// (tactical starts are usually decided quickly), `None` keeps the global limit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningSource {
+    pub kind: OpeningKind,
+    pub weight: f32,
+    pub max_plies: Option<usize>,
+}
+
+impl std::str::FromStr for OpeningSource {
+    type Err = String;
+    /// `kind[:arg][,w=<weight>][,plies=<max>]`, e.g. `random:8,w=3`,
+    /// `book:openings.epd,w=5`, `tactical:wac.epd,w=1,plies=40` or `frc,w=2`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut parts = s.split(',').map(str::trim);
+        let head = parts.next().unwrap_or_default();
+        let (kind, arg) = head.split_once(':').map_or((head, None), |(k, a)| (k, Some(a)));
+        let path = || arg.filter(|a| !a.is_empty()).map(PathBuf::from).ok_or_else(|| format!("{} needs a file: {}:<path>", kind, kind));
+        let kind = match kind.to_lowercase().as_str() {
+            "start" => OpeningKind::Start,
+            "random" => OpeningKind::Random(arg.map_or(Ok(8), |a| a.parse().map_err(|_| format!("bad random plies '{}'", a)))?),
+            "book" => OpeningKind::Book(path()?),
+            "tactical" => OpeningKind::Tactical(path()?),
+            "frc" | "chess960" => OpeningKind::Frc,
+            "dfrc" => OpeningKind::Dfrc,
+            other => return Err(format!("unknown opening source '{}' (start|random|book|tactical|frc|dfrc)", other)),
+        };
+        let mut src = OpeningSource { kind, weight: 1.0, max_plies: None };
+        for kv in parts.filter(|p| !p.is_empty()) {
+            match kv.split_once('=') {
+                Some(("w" | "weight", v)) => src.weight = v.parse().map_err(|_| format!("bad weight '{}'", v))?,
+                Some(("plies", v)) => src.max_plies = Some(v.parse().map_err(|_| format!("bad plies '{}'", v))?),
+                _ => return Err(format!("expected w=<weight> or plies=<max>, got '{}'", kv)),
+            }
+        }
+        if !(src.weight.is_finite() && src.weight > 0.0) { return Err(format!("weight must be positive, got {}", src.weight)); }
+        Ok(src)
+    }
+}
+
+impl std::fmt::Display for OpeningSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            OpeningKind::Start => write!(f, "start")?,
+            OpeningKind::Random(plies) => write!(f, "random:{}", plies)?,
+            OpeningKind::Book(p) => write!(f, "book:{}", p.display())?,
+            OpeningKind::Tactical(p) => write!(f, "tactical:{}", p.display())?,
+            OpeningKind::Frc => write!(f, "frc")?,
+            OpeningKind::Dfrc => write!(f, "dfrc")?,
+        }
+        write!(f, ",w={}", self.weight)?;
+        if let Some(p) = self.max_plies { write!(f, ",plies={}", p)?; }
+        Ok(())
+    }
+}
+
+enum Positions { Start, Walk(Box<OpeningGenerator>), Fixed(Vec<Board>), Frc { double: bool } }
+
+/// Weighted sampler over loaded opening sources.
+pub struct OpeningMix {
+    sources: Vec<(OpeningSource, Positions)>,
+    total_weight: f32,
+}
+
+impl OpeningMix {
+    /// Load every source's files up front; an empty or unreadable file is an error.
+    pub fn new(sources: &[OpeningSource], seed: u64) -> crate::Result<Self> {
+        let mut loaded = Vec::with_capacity(sources.len());
+        for (i, src) in sources.iter().enumerate() {
+            let pos = match &src.kind {
+                OpeningKind::Start => Positions::Start,
+                OpeningKind::Frc => Positions::Frc { double: false },
+                OpeningKind::Dfrc => Positions::Frc { double: true },
+                OpeningKind::Random(plies) => Positions::Walk(Box::new(OpeningGenerator::new(seed.wrapping_add(i as u64), plies.div_ceil(2), *plies))),
+                OpeningKind::Book(p) | OpeningKind::Tactical(p) => {
+                    let boards = crate::io::fen::load_openings(p)?;
+                    if boards.is_empty() { return Err(TitanError::Openings { path: p.display().to_string(), line: 0, reason: "no positions".to_string() }); }
+                    Positions::Fixed(boards)
+                }
+            };
+            loaded.push((src.clone(), pos));
+        }
+        if loaded.is_empty() { loaded.push((OpeningSource { kind: OpeningKind::Start, weight: 1.0, max_plies: None }, Positions::Start)); }
+        let total_weight = loaded.iter().map(|(s, _)| s.weight).sum();
+        Ok(Self { sources: loaded, total_weight })
+    }
+
+    /// Start position for game `game` and the source's ply cap. Random walks
+    /// alternate the side to move by game index so both colours start equally often.
+    pub fn sample(&mut self, rng: &mut SmallRng, game: usize) -> (Board, Option<usize>) {
+        let idx = if self.sources.len() == 1 { 0 } else {
+            let mut x = rng.gen::<f32>() * self.total_weight;
+            self.sources.iter().position(|(s, _)| { x -= s.weight; x < 0.0 }).unwrap_or(self.sources.len() - 1)
+        };
+        let (src, pos) = &mut self.sources[idx];
+        let board = match pos {
+            Positions::Start => super::game::startpos(),
+            Positions::Walk(g) => g.generate(Some(if game.is_multiple_of(2) { Color::White } else { Color::Black })).unwrap_or_default(),
+            Positions::Fixed(boards) => boards[(rng.gen::<u64>() ^ (game as u64)) as usize % boards.len()].clone(),
+            Positions::Frc { double: false } => super::frc::random_frc(rng),
+            Positions::Frc { double: true } => super::frc::random_dfrc(rng),
+        };
+        (board, src.max_plies)
+    }
+}
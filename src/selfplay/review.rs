@@ -0,0 +1,114 @@
+//! Post-game review: the centipawn loss of every move, re-searched at a fixed
+//! depth (normally deeper than the game's), by side and game phase. A measure of
+//! play quality that does not need hundreds of game results to settle.
+
+use crate::board::cozy::game_phase;
+use crate::search::alphabeta::{SearchParams, Searcher};
+use cozy_chess::{Board, Color, Move};
+
+/// Most a single move is charged, so one blunder into a lost or mated position
+/// does not swamp a game's average.
+pub const MAX_LOSS_CP: i32 = 1000;
+/// Positions up to this move number count as the opening, unless already an endgame
+pub const OPENING_MOVES: u16 = 12;
+/// `game_phase` at or below which a position counts as an endgame
+pub const ENDGAME_PHASE: u32 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase { Opening, Middlegame, Endgame }
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Opening, Phase::Middlegame, Phase::Endgame];
+
+    /// Endgame by material first, then opening by move number.
+    pub fn of(board: &Board) -> Self {
+        if game_phase(board) <= ENDGAME_PHASE { Phase::Endgame }
+        else if board.fullmove_number() <= OPENING_MOVES { Phase::Opening }
+        else { Phase::Middlegame }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self { Phase::Opening => "opening", Phase::Middlegame => "middlegame", Phase::Endgame => "endgame" }
+    }
+}
+
+/// Centipawn loss totals of one side, by phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CplStats { pub moves: [u32; 3], pub loss: [u64; 3] }
+
+impl CplStats {
+    pub fn record(&mut self, phase: Phase, loss: u32) {
+        self.moves[phase as usize] += 1;
+        self.loss[phase as usize] += u64::from(loss);
+    }
+
+    pub fn add(&mut self, other: &CplStats) {
+        for i in 0..3 { self.moves[i] += other.moves[i]; self.loss[i] += other.loss[i]; }
+    }
+
+    /// Average loss per move in `phase`; `None` without moves there.
+    pub fn average(&self, phase: Phase) -> Option<f64> {
+        let (n, loss) = (self.moves[phase as usize], self.loss[phase as usize]);
+        (n > 0).then(|| loss as f64 / n as f64)
+    }
+
+    /// Average loss over every move.
+    pub fn overall(&self) -> Option<f64> {
+        let n: u32 = self.moves.iter().sum();
+        (n > 0).then(|| self.loss.iter().sum::<u64>() as f64 / n as f64)
+    }
+
+    /// `opening=N middlegame=N endgame=N all=N moves=N`, `-` for a phase without moves.
+    pub fn summary(&self) -> String {
+        let avg = |a: Option<f64>| a.map_or_else(|| "-".to_string(), |v| format!("{:.0}", v));
+        let phases: Vec<String> = Phase::ALL.iter().map(|&p| format!("{}={}", p.name(), avg(self.average(p)))).collect();
+        format!("{} all={} moves={}", phases.join(" "), avg(self.overall()), self.moves.iter().sum::<u32>())
+    }
+}
+
+/// Re-searches played positions at a fixed depth.
+pub struct Reviewer { searcher: Searcher, params: SearchParams }
+
+impl Reviewer {
+    /// Reviews at `params.depth` (at least 2). Time and node limits are dropped, so
+    /// every position is searched to the same depth.
+    pub fn new(searcher: Searcher, params: SearchParams) -> Self {
+        let params = SearchParams { depth: params.depth.max(2), min_depth: 0, movetime: None, max_nodes: None, clock: None, ..params };
+        Self { searcher, params }
+    }
+
+    pub fn depth(&self) -> u32 { self.params.depth }
+
+    // Scores past the loss cap (mates included) compare as the cap
+    fn score(&mut self, board: &Board, depth: u32) -> (Option<String>, i32) {
+        self.searcher.new_search();
+        let r = self.searcher.search_with_params(board, SearchParams { depth, ..self.params });
+        (r.bestmove, r.score.value().clamp(-MAX_LOSS_CP, MAX_LOSS_CP))
+    }
+
+    /// Loss of playing `m` in `board`: the best score at the review depth minus the
+    /// score of `m` (its reply searched a ply shallower, so both look as far ahead).
+    pub fn move_loss(&mut self, board: &Board, m: Move) -> u32 {
+        let (best_move, best) = self.score(board, self.params.depth);
+        if best_move.as_deref() == Some(m.to_string().as_str()) { return 0; }
+        let mut child = board.clone();
+        child.play(m);
+        let played = -self.score(&child, self.params.depth - 1).1;
+        (best - played).max(0) as u32
+    }
+
+    /// Losses of a game's moves from `start`, for White and Black. Stops at the
+    /// first illegal move.
+    pub fn review_game(&mut self, start: &Board, moves: &[Move]) -> [CplStats; 2] {
+        let mut stats = [CplStats::default(); 2];
+        let mut board = start.clone();
+        for &m in moves {
+            if !board.is_legal(m) { break; }
+            let side = if board.side_to_move() == Color::White { 0 } else { 1 };
+            let loss = self.move_loss(&board, m);
+            stats[side].record(Phase::of(&board), loss);
+            board.play(m);
+        }
+        stats
+    }
+}
@@ -0,0 +1,98 @@
+//! Sanity checks and statistics for a shard directory (`shards stats`), so bad
+//! data is caught before it reaches training.
+
+use super::{read_shard, read_shard_meta};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A shard that could not be used, and why (bad magic, truncated record, ...).
+#[derive(Clone, Debug, Serialize)]
+pub struct ShardProblem {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Totals over the readable shards of a directory; unreadable ones are listed in
+/// `bad_files` and left out of every count.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ShardStats {
+    pub files: usize,
+    pub positions: u64,
+    pub unique_keys: u64,
+    /// Records by game result from White's view: black wins, draws, white wins
+    pub results: [u64; 3],
+    /// Records by side to move: white, black
+    pub stm: [u64; 2],
+    /// Keys recorded with more than one game result
+    pub conflicting_keys: u64,
+    /// Mean game length in plies, from the `games` count in the shard headers;
+    /// `None` when a shard has no header (v1)
+    pub avg_game_plies: Option<f64>,
+    pub bad_files: Vec<ShardProblem>,
+}
+
+impl ShardStats {
+    pub fn unique_ratio(&self) -> f64 {
+        if self.positions == 0 { 0.0 } else { self.unique_keys as f64 / self.positions as f64 }
+    }
+
+    /// Expected number of distinct positions sharing a 64-bit key among the
+    /// unique keys (birthday bound); duplicates above this are real repeats.
+    pub fn expected_collisions(&self) -> f64 {
+        let n = self.unique_keys as f64;
+        n * (n - 1.0).max(0.0) / 2.0 / 2f64.powi(64)
+    }
+
+    /// Share of records in `results[i]`.
+    pub fn result_share(&self, i: usize) -> f64 {
+        if self.positions == 0 { 0.0 } else { self.results[i] as f64 / self.positions as f64 }
+    }
+
+    /// White-to-move share of records; far from 0.5 means skewed data.
+    pub fn white_to_move(&self) -> f64 {
+        if self.positions == 0 { 0.0 } else { self.stm[0] as f64 / self.positions as f64 }
+    }
+}
+
+/// Check every `*.bin` file in `dir`. Only listing the directory can fail; a bad
+/// shard is recorded in `bad_files`.
+pub fn shard_stats(dir: &Path) -> std::io::Result<ShardStats> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "bin"))
+        .collect();
+    paths.sort();
+    let mut st = ShardStats::default();
+    // Result bits seen per key (1 << (result + 1))
+    let mut keys: HashMap<u64, u8> = HashMap::new();
+    // Records per generation run, keyed by its header, with the run's game count
+    let mut runs: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut headerless = false;
+    for path in paths {
+        let read = read_shard_meta(&path).and_then(|meta| Ok((meta, read_shard(&path)?)));
+        let (meta, recs) = match read {
+            Ok(r) => r,
+            Err(e) => { st.bad_files.push(ShardProblem { path, reason: e.to_string() }); continue; }
+        };
+        st.files += 1;
+        st.positions += recs.len() as u64;
+        for r in &recs {
+            st.results[(r.result + 1) as usize] += 1;
+            st.stm[r.stm as usize] += 1;
+            *keys.entry(r.key).or_default() |= 1 << (r.result + 1);
+        }
+        match meta {
+            Some(m) => {
+                let run = runs.entry(serde_json::to_string(&m).unwrap_or_default()).or_insert((m.games as u64, 0));
+                run.1 += recs.len() as u64;
+            }
+            None => headerless = true,
+        }
+    }
+    st.unique_keys = keys.len() as u64;
+    st.conflicting_keys = keys.values().filter(|b| b.count_ones() > 1).count() as u64;
+    let (games, records) = runs.values().fold((0, 0), |(g, r), &(rg, rr)| (g + rg, r + rr));
+    if !headerless && games > 0 { st.avg_game_plies = Some(records as f64 / games as f64); }
+    Ok(st)
+}
@@ -1,176 +1,1064 @@
-use std::io::{self, BufRead};
-#[cfg(not(feature = "board-pleco"))]
+use std::io::{self, BufRead, Write};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use crate::search::skill::SkillLevel;
+use crate::search::humanize::{Humanizer, MoveContext};
 use crate::board::cozy::Position;
-#[cfg(not(feature = "board-pleco"))]
+use crate::error::TitanError;
 use crate::eval::nnue::Nnue;
-#[cfg(not(feature = "board-pleco"))]
 use crate::eval::nnue::loader::QuantNnue;
-#[cfg(not(feature = "board-pleco"))]
-use crate::search::alphabeta::{Searcher, SearchParams};
+use crate::eval::nnue::network::{QuantNetwork, SmallNet};
+use crate::eval::policy::PolicyNet;
+use crate::search::alphabeta::{AnalysisStyle, InfoHook, Searcher, SearchParams, SearchResult};
+use crate::search::wdl::{material_units, WdlModel};
+use crate::search::score::Score;
+use crate::search::timeman::{allocate, Calibration, ClockPlan, GoLimits, SearchLimits};
+use crate::io::engine_log::EngineLog;
+use crate::io::timeline::GameTimeline;
+use crate::search::options::{EngineOptions, EvalKind, UciOption};
+use crate::search::{affinity, guard};
+use crate::version;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+fn parse_check(value: &str) -> bool { matches!(value.to_lowercase().as_str(), "true" | "1" | "on" | "yes") }
+
+// Confirms a network swap, so a gauntlet log shows which net played each game
+fn loaded_line(option: &str, path: &str, model: &QuantNnue) -> String {
+    format!("info string {} {} loaded (hidden {}, hash {:016x})", option, path, model.meta.hidden_dim, model.fingerprint())
+}
+
+fn policy_line(path: &str, net: &PolicyNet) -> String {
+    format!("info string PolicyFile {} loaded (hidden {}, hash {:016x})", path, net.hidden, net.fingerprint())
+}
+
+// The installed small net with a new phase threshold, if that changes it
+fn rephased(small: Option<&SmallNet>, max_phase: u32) -> Option<SmallNet> {
+    small.filter(|sn| sn.max_phase != max_phase).map(|sn| SmallNet { max_phase, ..sn.clone() })
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: &str) -> crate::Result<T> {
+    value.trim().parse().map_err(|_| TitanError::option(name, format!("bad value '{}'", value)))
+}
+
+/// Reporting options shared by both backends: `UCI_ShowWDL`, `WDLModelFile`,
+/// `InfoMinDepth` and `InfoRate`.
+#[derive(Clone, Debug)]
+struct InfoOptions { show_wdl: bool, wdl: WdlModel, min_depth: u32, max_per_sec: u32 }
+
+impl Default for InfoOptions {
+    fn default() -> Self { Self { show_wdl: false, wdl: WdlModel::default(), min_depth: 1, max_per_sec: 0 } }
+}
+
+impl InfoOptions {
+    fn uci_options(&self) -> Vec<UciOption> {
+        vec![
+            UciOption::check("Reporting", "UCI_ShowWDL", self.show_wdl, "Append win/draw/loss per mille to info lines"),
+            UciOption::string("Reporting", "WDLModelFile", "", "JSON WDL model (empty: built-in)"),
+            UciOption::spin("Reporting", "InfoMinDepth", self.min_depth.into(), 1, 99, "Suppress info lines below this depth"),
+            UciOption::spin("Reporting", "InfoRate", self.max_per_sec.into(), 0, 1000, "Max info lines per second (0: unlimited)"),
+        ]
+    }
+
+    // `name` is already lowercased
+    fn set(&mut self, name: &str, value: &str) -> crate::Result<()> {
+        match name {
+            "uci_showwdl" => self.show_wdl = parse_check(value),
+            "wdlmodelfile" if value.trim().is_empty() => self.wdl = WdlModel::default(),
+            "wdlmodelfile" => self.wdl = WdlModel::load_json(value)?,
+            "infomindepth" => self.min_depth = parse_value::<u32>(name, value)?.max(1),
+            "inforate" => self.max_per_sec = parse_value(name, value)?,
+            _ => return Err(TitanError::UnknownOption(name.to_string())),
+        }
+        Ok(())
+    }
+
+    // The fields shared by iteration and MultiPV lines
+    fn fields(&self, res: &SearchResult, material: u32) -> String {
+        let wdl = if self.show_wdl { let (w, d, l) = self.wdl.wdl_permille(res.score.value(), material); format!(" wdl {} {} {}", w, d, l) } else { String::new() };
+        format!(" depth {} seldepth {} score {}{} nodes {} nps {} time {} tbhits {}", res.depth, res.seldepth, res.score.to_uci(), wdl, res.nodes, res.nps, res.time_ms, res.tbhits)
+    }
+
+    // The line's pv, or just its best move when the search kept no line
+    fn pv(res: &SearchResult) -> String {
+        if res.pv.is_empty() { res.bestmove.clone().unwrap_or_else(|| "0000".to_string()) } else { res.pv.join(" ") }
+    }
+
+    // Then, once the root's best move has changed, how often (`string` runs to the end of the line)
+    fn line(&self, res: &SearchResult, material: u32) -> String {
+        let pv = if res.bestmove.is_some() { format!(" pv {}", Self::pv(res)) } else { String::new() };
+        let changes = if res.bestmove_changes > 0 { format!(" string bmchanges {}", res.bestmove_changes) } else { String::new() };
+        format!("info{}{}{}", self.fields(res, material), pv, changes)
+    }
+
+    // MultiPV line k (1-based)
+    fn multipv_line(&self, k: usize, res: &SearchResult, material: u32) -> String {
+        format!("info multipv {}{} pv {}", k, self.fields(res, material), Self::pv(res))
+    }
+
+    // Per-iteration hook printing through a throttle; returns the throttle so the
+    // caller can decide whether the final line is still needed
+    fn hook(&self, material: u32, out: &UciOut) -> (InfoHook, Arc<Mutex<InfoThrottle>>) {
+        let throttle = Arc::new(Mutex::new(InfoThrottle::new(self.min_depth, self.max_per_sec)));
+        let (opts, t, out) = (self.clone(), throttle.clone(), out.clone());
+        let hook: InfoHook = Arc::new(move |res: &SearchResult| {
+            if t.lock().unwrap().admit(res.depth, res.score, Instant::now()) { out.info(opts.line(res, material)); }
+        });
+        (hook, throttle)
+    }
+}
+
+/// Queued lines waiting for the writer thread; beyond this `info` lines are merged
+const OUTPUT_QUEUE: usize = 64;
+/// Largest `Hash` either backend accepts, in MB.
+const MAX_HASH_MB: usize = 16384;
+
+// `Input`, `Warning` and `NewGame` only touch the engine log, in order with the output
+enum OutMsg { Line(String), Wake, Flush(mpsc::Sender<()>), Input(String), Warning(String), NewGame }
+
+/// Shared sink for everything the engine says to the GUI. A dedicated thread owns
+/// the stream, so a GUI that reads slowly never stalls the search: per-iteration
+/// `info` lines go through `info`, which never blocks and, once the queue is full,
+/// keeps only the newest line. Everything else goes through `line`, which is never
+/// dropped and stays in order behind the info lines already accepted. The thread
+/// starts with the first line, so an engine that never prints costs none. With an
+/// engine log set (`LogDir`), lines are also logged as they are written.
+#[derive(Clone)]
+pub struct UciOut { tx: Arc<OnceLock<mpsc::SyncSender<OutMsg>>>, stream: Arc<Mutex<Option<Box<dyn Write + Send>>>>, pending: Arc<Mutex<Option<String>>>, log: Arc<Mutex<Option<EngineLog>>> }
+
+impl UciOut {
+    pub fn new<W: Write + Send + 'static>(w: W) -> Self {
+        Self { tx: Arc::default(), stream: Arc::new(Mutex::new(Some(Box::new(w)))), pending: Arc::default(), log: Arc::default() }
+    }
+
+    // Shared by every clone: the first use takes the stream and starts the writer
+    fn tx(&self) -> &mpsc::SyncSender<OutMsg> {
+        self.tx.get_or_init(|| {
+            let mut w = self.stream.lock().unwrap().take().unwrap_or_else(|| Box::new(io::stdout()));
+            let (tx, rx) = mpsc::sync_channel(OUTPUT_QUEUE);
+            let (slot, sink) = (self.pending.clone(), self.log.clone());
+            std::thread::spawn(move || {
+                // A closed pipe means the GUI is gone; nothing useful to do with the error
+                let mut write = |text: &str| {
+                    let _ = writeln!(w, "{}", text).and_then(|_| w.flush());
+                    if let Some(l) = sink.lock().unwrap().as_mut() { l.output(text); }
+                };
+                loop {
+                    let msg = match rx.try_recv() {
+                        Ok(m) => m,
+                        Err(mpsc::TryRecvError::Empty) => {
+                            // The merged line is newer than anything queued, so it goes out once the queue drains.
+                            // A held slot means a sender is about to queue, maybe blocking on a full queue
+                            // with the lock held, so wait for its message instead of the lock.
+                            if let Some(text) = slot.try_lock().ok().and_then(|mut s| s.take()) { write(&text); continue; }
+                            match rx.recv() { Ok(m) => m, Err(_) => break }
+                        }
+                        Err(mpsc::TryRecvError::Disconnected) => break,
+                    };
+                    match msg {
+                        OutMsg::Line(text) => write(&text),
+                        OutMsg::Wake => {}
+                        OutMsg::Flush(ack) => { let _ = ack.send(()); }
+                        OutMsg::Input(text) => if let Some(l) = sink.lock().unwrap().as_mut() { l.input(&text); },
+                        OutMsg::Warning(text) => if let Some(l) = sink.lock().unwrap().as_mut() { l.warning(&text); },
+                        OutMsg::NewGame => if let Some(l) = sink.lock().unwrap().as_mut() { l.new_game(); },
+                    }
+                }
+                if let Some(text) = slot.lock().unwrap().take() { write(&text); }
+            });
+            tx
+        })
+    }
+
+    /// Log to per-game files in `dir` from now on; an empty value stops logging.
+    pub fn set_log_dir(&self, dir: &str) -> crate::Result<()> {
+        let log = if dir.trim().is_empty() { None } else { Some(EngineLog::new(dir.trim()).map_err(|e| TitanError::io(dir.trim(), e))?) };
+        *self.log.lock().unwrap() = log;
+        Ok(())
+    }
+
+    pub fn log_dir(&self) -> Option<PathBuf> { self.log.lock().unwrap().as_ref().map(|l| l.dir().to_path_buf()) }
+
+    /// Record a command received from the GUI, after the output already queued.
+    pub fn log_input(&self, line: &str) { self.send(OutMsg::Input(line.to_string())); }
+
+    /// Record an internal warning the GUI is not told about.
+    pub fn log_warning(&self, text: &str) { self.send(OutMsg::Warning(text.to_string())); }
+
+    /// Start the next game's log file (`ucinewgame`) once the queued output is logged.
+    pub fn log_new_game(&self) { self.send(OutMsg::NewGame); }
+
+    /// Queue a line that must reach the GUI (`bestmove`, `readyok`, ...); blocks
+    /// only while the queue is full.
+    pub fn line(&self, text: impl std::fmt::Display) { self.send(OutMsg::Line(text.to_string())); }
+
+    /// Queue a search `info` line without ever blocking; when the GUI lags it
+    /// replaces the previous unsent one.
+    pub fn info(&self, text: impl std::fmt::Display) {
+        let mut slot = self.pending.lock().unwrap();
+        if slot.is_none() {
+            match self.tx().try_send(OutMsg::Line(text.to_string())) {
+                Err(mpsc::TrySendError::Full(OutMsg::Line(text))) => *slot = Some(text),
+                _ => return,
+            }
+        } else {
+            *slot = Some(text.to_string());
+        }
+        // Nudge a writer that drained the queue just before the slot filled
+        let _ = self.tx().try_send(OutMsg::Wake);
+    }
+
+    /// Wait until everything queued so far has been written.
+    pub fn flush(&self) {
+        if self.tx.get().is_none() { return; }
+        let (ack, done) = mpsc::channel();
+        self.send(OutMsg::Flush(ack));
+        let _ = done.recv();
+    }
+
+    // In-order send: a merged info line still waiting goes out first
+    fn send(&self, msg: OutMsg) {
+        let mut slot = self.pending.lock().unwrap();
+        if let Some(info) = slot.take() { let _ = self.tx().send(OutMsg::Line(info)); }
+        let _ = self.tx().send(msg);
+    }
+}
+
+impl Default for UciOut {
+    fn default() -> Self { Self::new(io::stdout()) }
+}
+
+/// State of the running `go`, shared by the command reader and the backend that
+/// searches, so `stop`, `ponderhit` and `isready` are answered mid-search.
+#[derive(Default)]
+pub struct SearchControl {
+    state: Mutex<ControlState>,
+    // Signalled when a waiting search may hand out its `bestmove`
+    released: Condvar,
+}
+
+#[derive(Default)]
+struct ControlState {
+    // `go` commands read, begun and finished, and the last one a `stop` covers: a
+    // `stop` applies to every `go` read before it, one still queued included
+    queued: u64,
+    generation: u64,
+    finished: u64,
+    stopped: u64,
+    // The running search's flag; each `go` gets its own, so no later `go` clears it
+    stop: Arc<AtomicBool>,
+    pondering: bool,
+    infinite: bool,
+    quit: bool,
+    // Think time a ponder search gets once the ponder move is played
+    ponder_budget: Option<Duration>,
+    // Commands other than `go` queued for the handler and not applied yet, and the
+    // `isready`s read mid-search that wait for them
+    pending: usize,
+    ready_waiting: usize,
+}
+
+impl ControlState {
+    fn waits(&self) -> bool { !self.stop.load(Ordering::SeqCst) && (self.pondering || self.infinite) }
+}
+
+impl SearchControl {
+    /// The reader read a `go`: it counts as searching until that search finishes.
+    pub fn queue(&self) { self.state.lock().unwrap().queued += 1; }
+
+    /// A `go` starts: give it a fresh stop flag, already set if a `stop` came after
+    /// it was read, and note whether it waits for `stop`.
+    pub fn begin(&self, args: &str) {
+        let has = |t: &str| args.split_whitespace().any(|a| a == t);
+        let mut st = self.state.lock().unwrap();
+        st.generation += 1;
+        // Without the reader (`handle_line` called directly) nothing was queued
+        st.queued = st.queued.max(st.generation);
+        (st.pondering, st.infinite, st.ponder_budget) = (has("ponder"), has("infinite"), None);
+        // After `quit` nobody is left to send `stop`
+        let stopped = st.stopped >= st.generation || (st.quit && (st.pondering || st.infinite));
+        st.stop = Arc::new(AtomicBool::new(stopped));
+    }
+
+    /// A `go` has been read and its `bestmove` not sent yet.
+    pub fn searching(&self) -> bool { let st = self.state.lock().unwrap(); st.finished < st.queued }
+
+    /// The flag the current search polls (`set_stop_flag`).
+    pub fn stop_flag(&self) -> Arc<AtomicBool> { self.state.lock().unwrap().stop.clone() }
+
+    /// UCI `stop`: end the search and release its `bestmove`, and end any `go`
+    /// already read behind it as soon as it starts.
+    pub fn stop(&self) {
+        let mut st = self.state.lock().unwrap();
+        (st.pondering, st.infinite, st.stopped) = (false, false, st.queued);
+        st.stop.store(true, Ordering::SeqCst);
+        self.released.notify_all();
+    }
+
+    /// UCI `quit`: a search waiting for `stop` ends now, any other one runs to its
+    /// limits first, since scripts pipe `quit` right after `go`.
+    pub fn quit(&self) {
+        let waits = { let mut st = self.state.lock().unwrap(); st.quit = true; st.pondering || st.infinite };
+        if waits { self.stop(); }
+    }
+
+    /// Called by a ponder search before it starts with the think time of its `go`.
+    /// False when `ponderhit` already came, so the search runs normally instead.
+    pub fn arm_ponder(&self, budget: Option<Duration>) -> bool {
+        let mut st = self.state.lock().unwrap();
+        if !st.pondering { return false; }
+        st.ponder_budget = budget;
+        true
+    }
+
+    /// UCI `ponderhit`: the ponder move was played, so the search now runs on the
+    /// clock, with the whole budget counted from here.
+    pub fn ponderhit(self: &Arc<Self>) {
+        let mut st = self.state.lock().unwrap();
+        if !std::mem::take(&mut st.pondering) { return; }
+        self.released.notify_all();
+        let Some(budget) = st.ponder_budget else { return };
+        // The timer holds this search's own flag, so it can never stop a later one
+        let (control, flag) = (self.clone(), st.stop.clone());
+        std::thread::spawn(move || {
+            std::thread::sleep(budget);
+            let _st = control.state.lock().unwrap();
+            flag.store(true, Ordering::SeqCst);
+            control.released.notify_all();
+        });
+    }
+
+    /// The search is over: a `go infinite` or a ponder search holds its result
+    /// until `stop` or `ponderhit`, as UCI requires.
+    pub fn finish(&self) {
+        let mut st = self.released.wait_while(self.state.lock().unwrap(), |st| st.waits()).unwrap();
+        st.finished = st.generation;
+    }
+
+    // The reader queues a line for the handler. A `go` is not counted: it starts
+    // whatever the answer to `isready`.
+    fn enqueue(&self, line: &str) { if !is_go(line) { self.state.lock().unwrap().pending += 1; } }
+
+    // The handler applied a line: the `isready`s now due
+    fn applied(&self, line: &str) -> usize {
+        let mut st = self.state.lock().unwrap();
+        if is_go(line) { return 0; }
+        st.pending -= 1;
+        if st.pending == 0 { std::mem::take(&mut st.ready_waiting) } else { 0 }
+    }
+
+    // `isready` read mid-search: answered now unless commands read before it still
+    // have to be applied, then once they are
+    fn ready_now(&self) -> bool {
+        let mut st = self.state.lock().unwrap();
+        if st.pending > 0 { st.ready_waiting += 1; }
+        st.pending == 0
+    }
+}
+
+fn is_go(line: &str) -> bool { line == "go" || line.starts_with("go ") }
+
+// `bestmove` once the GUI may have it, with the expected reply when there is one
+fn send_bestmove(out: &UciOut, control: &SearchControl, best: Option<&str>, ponder: Option<String>) {
+    control.finish();
+    match (best, ponder) {
+        (Some(best), Some(reply)) => out.line(format_args!("bestmove {} ponder {}", best, reply)),
+        (Some(best), None) => out.line(format_args!("bestmove {}", best)),
+        (None, _) => out.line("bestmove 0000"),
+    }
+}
+
+/// Limits per-iteration `info` lines in fast games: iterations below `min_depth`
+/// are dropped, and at most `max_per_sec` lines are printed per second (0 = no limit).
+pub struct InfoThrottle { min_depth: u32, max_per_sec: u32, last: Option<Instant>, last_key: Option<(u32, Score)> }
+
+impl InfoThrottle {
+    pub fn new(min_depth: u32, max_per_sec: u32) -> Self { Self { min_depth, max_per_sec, last: None, last_key: None } }
+
+    /// Whether an iteration finishing at `now` gets a line; records it if so.
+    pub fn admit(&mut self, depth: u32, score: Score, now: Instant) -> bool {
+        if depth < self.min_depth { return false; }
+        if self.max_per_sec > 0 {
+            if let Some(last) = self.last {
+                if now.duration_since(last) < Duration::from_secs(1) / self.max_per_sec { return false; }
+            }
+        }
+        self.last = Some(now);
+        self.last_key = Some((depth, score));
+        true
+    }
+
+    /// The closing line before `bestmove` skips the rate limit, but not `min_depth`
+    /// (depth 0, a mated or stalemated root, is always reported), and is dropped
+    /// when it would repeat the last one.
+    pub fn admit_final(&self, depth: u32, score: Score) -> bool { (depth == 0 || depth >= self.min_depth) && self.last_key != Some((depth, score)) }
+}
+
+/// Resign and draw-offer signals for match runners (`MatchSignals`, off by default).
+/// Once the engine's own score has been at or below `-ResignScore` for `ResignMoves`
+/// searches in a row, `info string resign` precedes `bestmove`; once it has stayed
+/// within `DrawScore` of zero for `DrawMoves` searches from move `DrawMinMove` on,
+/// `info string offer draw` does. Mate scores never count as drawn. The line is
+/// repeated on every move while the streak lasts; `ucinewgame` resets it.
+#[derive(Clone, Debug)]
+pub struct MatchSignals {
+    pub enabled: bool,
+    pub resign_cp: i32,
+    pub resign_moves: u32,
+    pub draw_cp: i32,
+    pub draw_moves: u32,
+    pub draw_min_move: u32,
+    resign_streak: u32,
+    draw_streak: u32,
+}
+
+impl Default for MatchSignals {
+    fn default() -> Self {
+        Self { enabled: false, resign_cp: 800, resign_moves: 3, draw_cp: 10, draw_moves: 8, draw_min_move: 40, resign_streak: 0, draw_streak: 0 }
+    }
+}
+
+impl MatchSignals {
+    fn uci_options(&self) -> Vec<UciOption> {
+        vec![
+            UciOption::check("Play", "MatchSignals", self.enabled, "Say 'info string resign' / 'offer draw' for match adjudication"),
+            UciOption::spin("Play", "ResignScore", self.resign_cp.into(), 100, 10000, "Resign at or below minus this score (cp)"),
+            UciOption::spin("Play", "ResignMoves", self.resign_moves.into(), 1, 100, "Consecutive moves below ResignScore"),
+            UciOption::spin("Play", "DrawScore", self.draw_cp.into(), 0, 100, "Offer a draw within this score of zero (cp)"),
+            UciOption::spin("Play", "DrawMoves", self.draw_moves.into(), 1, 100, "Consecutive moves within DrawScore"),
+            UciOption::spin("Play", "DrawMinMove", self.draw_min_move.into(), 1, 500, "No draw offers before this move number"),
+        ]
+    }
+
+    // `name` is already lowercased
+    fn set(&mut self, name: &str, value: &str) -> crate::Result<()> {
+        match name {
+            "matchsignals" => self.enabled = parse_check(value),
+            "resignscore" => self.resign_cp = parse_value::<i32>(name, value)?.clamp(100, 10000),
+            "resignmoves" => self.resign_moves = parse_value::<u32>(name, value)?.clamp(1, 100),
+            "drawscore" => self.draw_cp = parse_value::<i32>(name, value)?.clamp(0, 100),
+            "drawmoves" => self.draw_moves = parse_value::<u32>(name, value)?.clamp(1, 100),
+            "drawminmove" => self.draw_min_move = parse_value::<u32>(name, value)?.clamp(1, 500),
+            _ => return Err(TitanError::UnknownOption(name.to_string())),
+        }
+        Ok(())
+    }
+
+    pub fn new_game(&mut self) { self.resign_streak = 0; self.draw_streak = 0; }
+
+    /// Record the score of a finished search (side to move) at full move `fullmove`
+    /// and return the signal to send, if any.
+    pub fn record(&mut self, score: Score, fullmove: u32) -> Option<&'static str> {
+        if !self.enabled { return None; }
+        let v = score.value();
+        self.resign_streak = if v <= -self.resign_cp { self.resign_streak + 1 } else { 0 };
+        self.draw_streak = if !score.is_mate() && v.abs() <= self.draw_cp && fullmove >= self.draw_min_move { self.draw_streak + 1 } else { 0 };
+        if self.resign_streak >= self.resign_moves { Some("info string resign") }
+        else if self.draw_streak >= self.draw_moves { Some("info string offer draw") }
+        else { None }
+    }
+}
+
+/// `TimelineDir`: when set, every search is appended to a per-game timeline JSON
+/// (`game_NNN.json`, see `io::timeline`); `ucinewgame` starts the next file.
+/// `start` is the root of the last `position` command, before its moves.
+#[derive(Default)]
+struct TimelineRecorder { dir: Option<PathBuf>, current: Option<GameTimeline>, games: usize, start: Option<cozy_chess::Board> }
+
+impl TimelineRecorder {
+    fn uci_options(&self) -> Vec<UciOption> {
+        let dir = self.dir.as_ref().map(|d| d.display().to_string()).unwrap_or_default();
+        vec![UciOption::string("Reporting", "TimelineDir", &dir, "Write a per-game search timeline JSON here")]
+    }
+
+    fn set_dir(&mut self, value: &str) {
+        let dir = PathBuf::from(value.trim());
+        self.dir = if value.trim().is_empty() { None } else { std::fs::create_dir_all(&dir).ok().map(|_| dir) };
+        self.current = None;
+    }
+
+    fn new_game(&mut self) { self.current = None; }
+
+    // Called with the arguments of a `position` command both backends accepted
+    fn set_start(&mut self, args: &str) {
+        self.start = match crate::board::split_position_args(args) {
+            Ok((Some(fen), _)) => cozy_chess::Board::from_fen(&fen, false).ok(),
+            Ok((None, _)) => Some(cozy_chess::Board::default()),
+            Err(_) => None,
+        };
+    }
+
+    // `board` is the searched position; the file is rewritten so it is always complete
+    fn record(&mut self, board: &cozy_chess::Board, res: &SearchResult) {
+        let Some(dir) = &self.dir else { return; };
+        let (games, start) = (&mut self.games, self.start.as_ref().unwrap_or(board));
+        let t = self.current.get_or_insert_with(|| {
+            *games += 1;
+            let (white, black) = if board.side_to_move() == cozy_chess::Color::White { ("uci", "opponent") } else { ("opponent", "uci") };
+            GameTimeline::new(*games, start, white, black)
+        });
+        t.push(board, "uci", res);
+        let _ = t.write(&dir.join(format!("game_{:03}.json", t.game)));
+    }
+}
+
+/// Board/search backend behind the UCI front-end, selectable at runtime via
+/// `setoption name Backend value cozy|pleco` or `piebot-uci --backend`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend { Cozy, Pleco }
+
+impl Default for Backend {
+    #[cfg(feature = "board-pleco")]
+    fn default() -> Self { Backend::Pleco }
+    #[cfg(not(feature = "board-pleco"))]
+    fn default() -> Self { Backend::Cozy }
+}
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cozy" => Ok(Backend::Cozy),
+            #[cfg(feature = "board-pleco")]
+            "pleco" => Ok(Backend::Pleco),
+            other => Err(format!("unsupported backend: {}", other)),
+        }
+    }
+}
+
+// `LogDir` lives in the `UciOut` both backends share
+fn log_option(out: &UciOut) -> UciOption {
+    let dir = out.log_dir().map(|d| d.display().to_string()).unwrap_or_default();
+    UciOption::string("Reporting", "LogDir", &dir, "Log commands, output and warnings to a per-game file here")
+}
+
+// Each backend reports itself as the current value: only the active one answers `uci`
+fn backend_option(current: Backend) -> UciOption {
+    let value = if current == Backend::Pleco { "pleco" } else { "cozy" };
+    let vars: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    UciOption::combo("Engine", "Backend", value, vars, "Board and search implementation")
+}
+
+/// `--dump-options`: every option grouped, with its current default and help.
+pub fn options_table(options: &[UciOption]) -> String {
+    let mut out = String::new();
+    let mut group = "";
+    for o in options {
+        if o.group != group {
+            group = o.group;
+            out.push_str(&format!("{}{}\n", if out.is_empty() { "" } else { "\n" }, group));
+        }
+        out.push_str(&format!("  {:<18} {:<44} {}\n", o.name, o.kind, o.help));
+    }
+    out
+}
+
+/// The board as printed by the `d` debug command: rank 8 first, White in capitals.
+fn board_diagram(board: &cozy_chess::Board) -> Vec<String> {
+    let rule = " +---+---+---+---+---+---+---+---+";
+    let mut lines = vec![rule.to_string()];
+    for &rank in cozy_chess::Rank::ALL.iter().rev() {
+        let mut row = String::from(" |");
+        for file in cozy_chess::File::ALL {
+            let sq = cozy_chess::Square::new(file, rank);
+            let c = match (board.piece_on(sq), board.color_on(sq)) {
+                (Some(p), Some(cozy_chess::Color::White)) => char::from(p).to_ascii_uppercase(),
+                (Some(p), _) => char::from(p),
+                _ => ' ',
+            };
+            row.push_str(&format!(" {} |", c));
+        }
+        lines.push(format!("{} {}", row, rank as usize + 1));
+        lines.push(rule.to_string());
+    }
+    lines.push("   a   b   c   d   e   f   g   h".to_string());
+    lines
+}
+
+/// Position lines shared by both backends' `d` output.
+fn position_lines(board: &cozy_chess::Board, key: u64) -> Vec<String> {
+    let mut lines = board_diagram(board);
+    let checkers: Vec<String> = board.checkers().into_iter().map(|s| s.to_string()).collect();
+    lines.push(format!("Fen: {}", board));
+    lines.push(format!("Key: {:016X}", key));
+    lines.push(format!("Checkers: {}", checkers.join(" ")));
+    let e = crate::search::eval::eval_breakdown(board);
+    lines.push(format!("Classical (white): material {} pst {} passers {} castling {} development {} scale {}/{} total {}",
+        e.material, e.pst, e.passers, e.castling, e.development, e.scale, crate::search::eval::SCALE_NORMAL, e.total));
+    lines
+}
+
+fn tt_line(entry: Option<(u32, impl std::fmt::Debug, Score, Option<String>)>) -> String {
+    match entry {
+        Some((depth, bound, score, best)) => format!("TT: depth {} bound {:?} score {} best {}", depth, bound, score, best.as_deref().unwrap_or("none")),
+        None => "TT: none".to_string(),
+    }
+}
+
+// Feed trimmed, non-empty lines to `handle` until it returns false or input ends.
+// A reader thread answers what cannot wait for a running search (`stop`,
+// `ponderhit`, `isready`) and queues the rest; it stops reading after `quit`.
+fn read_commands<R: BufRead + Send>(input: R, out: &UciOut, control: &Arc<SearchControl>, mut handle: impl FnMut(&str) -> bool) {
+    let (tx, rx) = mpsc::channel::<String>();
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            for line in input.lines() {
+                let line = match line { Ok(s) => s.trim().to_string(), Err(_) => break };
+                if line.is_empty() { continue; }
+                if control.searching() {
+                    let now = match line.as_str() {
+                        "stop" => { control.stop(); true }
+                        "ponderhit" => { control.ponderhit(); true }
+                        "isready" => { if control.ready_now() { out.line("readyok"); } true }
+                        _ => false,
+                    };
+                    if now { out.log_input(&line); continue; }
+                }
+                if is_go(&line) { control.queue(); }
+                let quit = line == "quit";
+                control.enqueue(&line);
+                if tx.send(line).is_err() || quit { break; }
+            }
+            // `quit` or end of input
+            control.quit();
+        });
+        for line in rx {
+            let more = handle(&line);
+            for _ in 0..control.applied(&line) { out.line("readyok"); }
+            if !more { break; }
+        }
+    });
+}
 
 #[cfg(feature = "board-pleco")]
 mod pleco_uci {
     use super::*;
-    use pleco::{Board as PBoard, BitMove as PMove};
-    use rayon::ThreadPoolBuilder;
-    use crate::search::alphabeta_pleco::PlecoSearcher;
+    use pleco::{Board as PBoard, BitMove as PMove, PieceType};
+    use crate::search::alphabeta_pleco::{PlecoSearcher, PlecoEvalMode};
+    use crate::search::wdl::material_units_pleco;
 
     fn move_to_uci(m: PMove) -> String { format!("{}", m) }
-    fn uci_to_move(board: &PBoard, uci: &str) -> Option<PMove> {
-        let ml = board.generate_moves();
-        for m in ml.iter() { if move_to_uci(*m) == uci { return Some(*m); } }
-        None
-    }
 
     pub struct UciEnginePleco {
         board: PBoard,
-        threads: usize,
-        hash_mb: usize,
+        history: Vec<u64>, // keys of the game's positions before `board`
+        options: EngineOptions,
         searcher: PlecoSearcher,
+        skill: SkillLevel,
+        skill_rng: SmallRng,
+        humanize: bool,
+        humanizer: Humanizer,
+        info: InfoOptions,
+        signals: MatchSignals,
+        multipv: usize,
+        timeline: TimelineRecorder,
+        ponder: bool,
+        control: Arc<SearchControl>,
+        out: UciOut,
+    }
+    impl Default for UciEnginePleco {
+        fn default() -> Self { Self::new() }
     }
     impl UciEnginePleco {
-        pub fn new() -> Self { Self { board: PBoard::start_pos(), threads: 1, hash_mb: 64, searcher: PlecoSearcher::default() } }
+        pub fn new() -> Self {
+            let options = EngineOptions::global();
+            let searcher = options.build_pleco_searcher().unwrap_or_else(|e| { eprintln!("engine options: {}", e); PlecoSearcher::default() });
+            Self { board: PBoard::start_pos(), history: Vec::new(), options, searcher, skill: SkillLevel::default(), skill_rng: SmallRng::from_entropy(), humanize: false, humanizer: Humanizer::new(rand::random()), info: InfoOptions::default(), signals: MatchSignals::default(), multipv: 1, timeline: TimelineRecorder::default(), ponder: false, control: Arc::default(), out: UciOut::default() }
+        }
+        pub fn set_skill_level(&mut self, level: u8) { self.skill = SkillLevel::new(level); }
+        pub(super) fn options(&self) -> &EngineOptions { &self.options }
+        pub fn set_output(&mut self, out: UciOut) { self.out = out; }
+        /// Share `stop` / `ponderhit` handling with the front-end's command reader.
+        pub fn set_control(&mut self, control: Arc<SearchControl>) { self.control = control; }
         fn cmd_uci(&self) {
-            println!("id name PieBot (Pleco)"); println!("id author PieBot Team");
-            println!("option name Threads type spin default 1 min 1 max 512");
-            println!("option name Hash type spin default 64 min 1 max 4096");
-            println!("uciok");
-        }
-        fn cmd_isready(&self) { println!("readyok"); }
-        fn cmd_ucinewgame(&mut self) { self.board = PBoard::start_pos(); self.searcher.clear(); }
-        fn apply_setoption(&mut self, name:&str, value:&str) {
+            self.out.line(format_args!("id name {}", version::id_name("Pleco"))); self.out.line("id author PieBot Team");
+            self.out.line(version::build_line());
+            self.out.line(version::network_line(self.searcher.nnue_quant_model().map(|m| m.fingerprint())));
+            for o in self.uci_options() { self.out.line(o); }
+            self.out.line("uciok");
+        }
+        /// Every option with its current value, grouped as printed by `uci`.
+        pub fn uci_options(&self) -> Vec<UciOption> {
+            let o = &self.options;
+            let mut v = vec![
+                UciOption::spin("Engine", "Threads", o.threads as i64, 1, 512, "Search threads"),
+                UciOption::spin("Engine", "Hash", o.hash_mb as i64, 1, MAX_HASH_MB as i64, "Transposition table size (MB)"),
+                backend_option(Backend::Pleco),
+                UciOption::check("Evaluation", "UseNNUE", self.searcher.eval_mode() == PlecoEvalMode::Nnue, "Evaluate with the loaded quantized network"),
+                UciOption::string("Evaluation", "NNUEQuantFile", "", "Quantized network file (PIENNQ01)"),
+                UciOption::spin("Play", "Skill Level", self.skill.level().into(), 1, 20, "Playing strength (20: full)"),
+                UciOption::check("Play", "Humanize", self.humanize, "Vary think time like a human player"),
+                UciOption::check("Play", "Ponder", self.ponder, "Name the expected reply after bestmove"),
+                UciOption::spin("Search", "MultiPV", self.multipv as i64, 1, 64, "Principal variations to report"),
+            ];
+            v.extend(o.uci_options());
+            v.extend(self.signals.uci_options());
+            v.extend(self.info.uci_options());
+            v.extend(self.timeline.uci_options());
+            v.push(log_option(&self.out));
+            UciOption::sort(&mut v);
+            v
+        }
+        fn cmd_isready(&self) { self.out.line("readyok"); }
+        fn cmd_ucinewgame(&mut self) { self.board = PBoard::start_pos(); self.history.clear(); self.searcher.clear(); self.humanizer.new_game(); self.signals.new_game(); self.timeline.new_game(); }
+        fn apply_setoption(&mut self, name:&str, value:&str) -> crate::Result<()> {
             match name.to_lowercase().as_str() {
-                "threads" => if let Ok(t)=value.parse::<usize>(){ self.threads=t.max(1);} ,
-                "hash" => if let Ok(mb)=value.parse::<usize>(){ self.hash_mb = mb.max(1); self.searcher.set_tt_capacity_mb(self.hash_mb); },
-                _=>{}
+                "threads" => self.options.threads = parse_value::<usize>(name, value)?.clamp(1, 512),
+                "hash" => { self.options.hash_mb = parse_value::<usize>(name, value)?.clamp(1, MAX_HASH_MB); self.searcher.set_tt_capacity_mb(self.options.hash_mb); },
+                "skill level" => self.set_skill_level(parse_value(name, value)?),
+                "humanize" => self.humanize = parse_check(value),
+                "ponder" => self.ponder = parse_check(value),
+                "multipv" => self.multipv = parse_value::<usize>(name, value)?.clamp(1, 64),
+                // Kept in `options.eval` too, so calibration follows the eval in use
+                "usennue" => {
+                    self.options.eval = if parse_check(value) { EvalKind::Nnue } else { EvalKind::Pst };
+                    self.searcher.set_eval_mode(if self.options.eval == EvalKind::Nnue { PlecoEvalMode::Nnue } else { PlecoEvalMode::Pst });
+                },
+                "nnuequantfile" if value.trim().is_empty() => {},
+                "nnuequantfile" => {
+                    let model = QuantNnue::load_quantized(value)?; QuantNetwork::validate(&model)?; self.out.line(loaded_line("NNUEQuantFile", value, &model));
+                    self.options.nnue_quant_file = Some(value.to_string());
+                    self.searcher.set_nnue_quant_model(model);
+                },
+                // An empty value removes the small network
+                "nnuesmallfile" | "nnue_small" if value.trim().is_empty() => { self.options.nnue_small_file = None; self.searcher.set_nnue_small(None); },
+                "nnuesmallfile" | "nnue_small" => {
+                    let model = QuantNnue::load_quantized(value)?; QuantNetwork::validate(&model)?; self.out.line(loaded_line("NNUESmallFile", value, &model));
+                    self.options.nnue_small_file = Some(value.to_string());
+                    self.searcher.set_nnue_small(Some(SmallNet::new(model, self.options.small_net_phase)));
+                },
+                "policyfile" | "policy" if value.trim().is_empty() => { self.options.policy_file = None; self.searcher.set_policy(None); },
+                "policyfile" | "policy" => {
+                    let net = PolicyNet::load(value)?; self.out.line(policy_line(value, &net));
+                    self.options.policy_file = Some(value.to_string());
+                    self.searcher.set_policy(Some(net));
+                },
+                "timelinedir" => self.timeline.set_dir(value),
+                "logdir" => self.out.set_log_dir(value)?,
+                // Node budgets and the serial root exist only in the cozy search
+                "deterministic" if parse_check(value) => return Err(TitanError::option(name, "not supported by the pleco backend; use Backend cozy")),
+                // Shared engine options (search toggles), then reporting options
+                n => match self.options.set(n, value) {
+                    Ok(()) if matches!(n, "eval" | "nnue_quant") => self.options.configure_pleco_eval(&mut self.searcher)?,
+                    Ok(()) => {
+                        let o = &self.options;
+                        self.searcher.set_heuristics(o.use_killers, o.use_lmr, o.use_nullmove, o.use_aspiration, o.aspiration_window_cp);
+                        self.searcher.set_lmr_late_extra(o.late_move_extra());
+                        self.searcher.set_split(o.split_config());
+                        self.searcher.set_contempt(o.contempt_config());
+                        self.searcher.set_king_danger(o.king_danger);
+                        self.searcher.set_node_types(o.node_types);
+                        self.searcher.set_conversion(o.conversion);
+                        self.searcher.set_smp_safe(o.smp_safe);
+                        self.searcher.set_smp_seed(o.smp_seed);
+                        self.searcher.set_helper_scheme(o.smp_helpers);
+                        self.searcher.set_eval_hash_mb(o.eval_hash_mb);
+                        self.searcher.set_lmp(o.lmp);
+                        self.searcher.set_futility(o.futility);
+                        self.searcher.set_lmp_pruning(o.lmp_pruning);
+                        self.searcher.set_min_depth(o.min_depth);
+                        self.searcher.set_explosion_guard(o.explosion_guard);
+                        self.searcher.set_adaptive_window(o.adaptive_window);
+                        self.searcher.set_policy_weight(o.policy_weight);
+                        if let Some(sn) = rephased(self.searcher.nnue_small(), o.small_net_phase) { self.searcher.set_nnue_small(Some(sn)); }
+                    },
+                    Err(TitanError::UnknownOption(_)) => match self.signals.set(n, value) {
+                        Err(TitanError::UnknownOption(_)) => self.info.set(n, value)?,
+                        r => r?,
+                    },
+                    Err(e) => return Err(e),
+                },
             }
+            Ok(())
+        }
+        fn cmd_setoption(&mut self, args:&str){ let mut it=args.split_whitespace(); if it.next()!=Some("name"){return;} let mut name=Vec::new(); let mut val=None; for tok in it{ if tok=="value"{ val=Some(String::new()); continue;} if let Some(v)=val.as_mut(){ if !v.is_empty(){v.push(' ');} v.push_str(tok);} else {name.push(tok.to_string());}}
+            if let Err(e)=self.apply_setoption(&name.join(" "), &val.unwrap_or_default()){ self.out.line(format_args!("info string {}", e)); }
         }
-        fn cmd_setoption(&mut self, args:&str){ let mut it=args.split_whitespace(); if it.next()!=Some("name"){return;} let mut name=Vec::new(); let mut val=None; for tok in it{ if tok=="value"{ val=Some(String::new()); continue;} if let Some(v)=val.as_mut(){ if !v.is_empty(){v.push(' ');} v.push_str(tok);} else {name.push(tok.to_string());}} self.apply_setoption(&name.join(" "), &val.unwrap_or_default()); }
-        fn cmd_position(&mut self, args:&str){ let mut it=args.split_whitespace(); match it.next(){ Some("startpos")=>{ self.board=PBoard::start_pos(); if let Some("moves")=it.next(){ for m in it { if let Some(bm)=uci_to_move(&self.board, m){ self.board.apply_move(bm);} } } }, Some("fen")=>{ let fen: Vec<&str>=it.by_ref().take(6).collect(); if fen.len()==6{ if let Ok(b)=PBoard::from_fen(&fen.join(" ")){ self.board=b; } } if let Some("moves")=it.next(){ for m in it { if let Some(bm)=uci_to_move(&self.board, m){ self.board.apply_move(bm);} } } }, _=>{} } }
+        // On error the previous position is kept
+        fn cmd_position(&mut self, args:&str){ match crate::board::pleco::parse_uci_position_history(args){ Ok((b, h))=>{ (self.board, self.history)=(b, h); self.timeline.set_start(args); } Err(e)=>self.out.line(format_args!("info string {}", e)) } }
         fn cmd_go(&mut self, args:&str){
-            let mut depth: u32=6; let mut movetime: Option<u64>=None; let mut it=args.split_whitespace();
-            while let Some(t)=it.next(){ match t{ "depth"=> if let Some(d)=it.next().and_then(|s|s.parse().ok()){ depth=d }, "movetime"=> if let Some(ms)=it.next().and_then(|s|s.parse().ok()){ movetime=Some(ms)}, _=>{} } }
+            self.control.begin(args);
+            self.searcher.set_stop_flag(Some(self.control.stop_flag()));
+            let white = self.board.turn() == pleco::Player::White;
+            let limits = SearchLimits::from_uci(args, white, self.options.depth, self.options.movetime_ms, self.options.min_depth);
+            let (depth, mut movetime) = (limits.depth, limits.movetime_ms);
+            let clock = GoLimits::from_uci(args, white);
+            let config = self.options.time_config();
+            let mut plan = None;
+            if clock.movetime.is_none() && clock.remaining.is_some() {
+                if let Some(budget) = allocate(&clock, &config) {
+                    movetime = Some((budget.soft.as_millis() as u64).max(1));
+                    plan = Some(ClockPlan { budget, config });
+                }
+            }
+            if self.humanize {
+                let ml = self.board.generate_moves();
+                let recapture = self.board.piece_captured_last_turn() != PieceType::None
+                    && self.board.last_move().map(|lm| ml.iter().any(|m| m.is_capture() && m.get_dest() == lm.get_dest())).unwrap_or(false);
+                let ctx = MoveContext { legal_moves: ml.len(), in_check: self.board.in_check(), recapture };
+                let budget = std::time::Duration::from_millis(movetime.unwrap_or(1000));
+                movetime = Some((self.humanizer.think_time(budget, ctx).as_millis() as u64).max(1));
+            }
+            // A humanized think time replaces the plan with a single deadline, which
+            // MinDepth may not pass on the clock
+            if self.humanize { plan = None; }
+            self.searcher.set_min_depth(if self.humanize && clock.remaining.is_some() { 0 } else { self.options.min_depth });
+            // A ponder search has no time limit until `ponderhit` starts the clock
+            if self.control.arm_ponder(movetime.map(std::time::Duration::from_millis)) { (movetime, plan) = (None, None); }
+            self.searcher.set_small_net_only(self.options.small_net_only(clock.remaining));
             // Ensure TT size
-            self.searcher.set_tt_capacity_mb(self.hash_mb);
-            self.searcher.set_threads(self.threads);
-            let pool=ThreadPoolBuilder::new().num_threads(self.threads).build().unwrap();
-            let (best,_sc,_nodes)=pool.install(||{
-                if let Some(ms)=movetime{ self.searcher.search_movetime(&mut self.board.clone(), ms, depth)} else { self.searcher.search_movetime(&mut self.board.clone(), 1000, depth)}
+            self.searcher.set_tt_capacity_mb(self.options.hash_mb);
+            self.searcher.set_threads(self.options.threads);
+            self.searcher.new_search();
+            // `searchmoves` runs until the next token that is not a legal move
+            let legal = self.board.generate_moves();
+            let mut tokens = args.split_whitespace().skip_while(|&t| t != "searchmoves").skip(1);
+            let search_moves: Vec<PMove> = std::iter::from_fn(|| tokens.next().and_then(|t| legal.iter().copied().find(|&m| move_to_uci(m) == t))).collect();
+            self.searcher.set_search_moves(if search_moves.is_empty() { None } else { Some(search_moves) });
+            self.searcher.set_game_history(self.history.clone());
+            let pool=affinity::thread_pool(self.options.threads, &self.options.affinity, self.options.stack_mb);
+            // A mated or stalemated root skips the skill pick: the search reports it
+            let terminal=self.board.generate_moves().is_empty();
+            if let Some(cap)=self.skill.depth_cap().filter(|_| !terminal){
+                let scored=pool.install(|| self.searcher.score_root_moves(&mut self.board.clone(), if depth == 0 { cap } else { depth.min(cap) }));
+                let best=self.skill.pick(&scored, &mut self.skill_rng).map(move_to_uci);
+                send_bestmove(&self.out, &self.control, best.as_deref(), self.ponder_reply(best.as_deref()));
+                return;
+            }
+            let material = material_units_pleco(&self.board);
+            if self.multipv > 1 && !terminal {
+                let lines = pool.install(|| self.searcher.search_multipv(&mut self.board.clone(), movetime.unwrap_or(0), depth, self.multipv));
+                for (k, res) in lines.iter().enumerate() { self.out.line(self.info.multipv_line(k + 1, res, material)); }
+                let board = cozy_chess::Board::from_fen(&self.board.fen(), false).ok();
+                if let (Some(b), Some(first)) = (&board, lines.first()) { self.timeline.record(b, first); }
+                if let Some(s) = lines.first().and_then(|r| self.signals.record(r.score, board.map_or(1, |b| b.fullmove_number().into()))) { self.out.line(s); }
+                let best = lines.first().and_then(|r| r.bestmove.as_deref());
+                send_bestmove(&self.out, &self.control, best, self.ponder_reply(best));
+                return;
+            }
+            let (hook, throttle) = self.info.hook(material, &self.out);
+            self.searcher.set_info_hook(Some(hook));
+            // Clock: abort at the hard limit, stop between iterations by the soft one
+            self.searcher.set_clock_plan(plan);
+            let millis = plan.map_or(movetime, |p| Some((p.budget.hard.as_millis() as u64).max(1)));
+            let res=pool.install(||{
+                // Movetime 0: the depth limit alone ends the search
+                self.searcher.search_result(&mut self.board.clone(), millis.unwrap_or(0), depth)
             });
-            if let Some(bm)=best{ println!("bestmove {}", move_to_uci(bm)); } else { println!("bestmove 0000"); }
+            self.searcher.set_clock_plan(None);
+            self.searcher.set_info_hook(None);
+            if throttle.lock().unwrap().admit_final(res.depth, res.score) { self.out.line(self.info.line(&res, material)); }
+            let board = cozy_chess::Board::from_fen(&self.board.fen(), false).ok();
+            if let Some(b) = &board { self.timeline.record(b, &res); }
+            if let Some(s) = self.signals.record(res.score, board.map_or(1, |b| b.fullmove_number().into())) { self.out.line(s); }
+            send_bestmove(&self.out, &self.control, res.bestmove.as_deref(), self.ponder_reply(res.bestmove.as_deref()));
+        }
+        // The reply `bestmove ... ponder` names when the Ponder option is on
+        fn ponder_reply(&self, best: Option<&str>) -> Option<String> {
+            let best = best.filter(|_| self.ponder).and_then(|b| self.board.generate_moves().iter().copied().find(|&m| move_to_uci(m) == b))?;
+            self.searcher.ponder_move(&mut self.board.clone(), best).map(move_to_uci)
         }
-        pub fn run_loop(&mut self){ let stdin=io::stdin(); for line in stdin.lock().lines(){ let line=match line{Ok(s)=>s.trim().to_string(),Err(_)=>break}; if line.is_empty(){continue;} if line=="uci"{ self.cmd_uci(); continue;} if line=="isready"{ self.cmd_isready(); continue;} if line=="ucinewgame"{ self.cmd_ucinewgame(); continue;} if let Some(rest)=line.strip_prefix("setoption "){ self.cmd_setoption(rest); continue;} if line=="quit"{ break;} if let Some(rest)=line.strip_prefix("position "){ self.cmd_position(rest); continue;} if let Some(rest)=line.strip_prefix("go "){ self.cmd_go(rest); continue;} if line=="stop"{ continue;} } }
+        // Non-standard `d`: board, key, eval, TT entry and the resolved options
+        fn cmd_d(&mut self) {
+            let Ok(board) = cozy_chess::Board::from_fen(&self.board.fen(), false) else { return };
+            for l in position_lines(&board, self.board.zobrist()) { self.out.line(l); }
+            self.out.line(format_args!("Static eval (side to move): {} ({:?})", self.searcher.static_eval(&self.board), self.searcher.eval_mode()));
+            self.out.line(tt_line(self.searcher.tt().get(self.board.zobrist()).map(|e| (e.depth, e.bound, e.score, e.best.map(move_to_uci)))));
+            self.out.line(format_args!("Options: {}", self.options.describe()));
+        }
+        /// Handle one UCI command; returns false on `quit`.
+        pub fn handle_line(&mut self, line:&str) -> bool { if line=="uci"{ self.cmd_uci(); } else if line=="isready"{ self.cmd_isready(); } else if line=="d" || line=="dump"{ self.cmd_d(); } else if line=="ucinewgame"{ self.cmd_ucinewgame(); } else if let Some(rest)=line.strip_prefix("setoption "){ self.cmd_setoption(rest); } else if line=="quit"{ return false; } else if let Some(rest)=line.strip_prefix("position "){ self.cmd_position(rest); } else if let Some(rest)=line.strip_prefix("go "){ self.cmd_go(rest); } true }
+        pub fn run_loop<R: BufRead + Send, W: Write + Send + 'static>(&mut self, input: R, output: W){ self.out = UciOut::new(output); let (out, control) = (self.out.clone(), self.control.clone()); read_commands(input, &out, &control, |line| self.handle_line(line)); self.out.flush(); }
     }
 }
 
 #[cfg(feature = "board-pleco")]
-pub use pleco_uci::UciEnginePleco as UciEngine;
+pub use pleco_uci::UciEnginePleco;
 
-#[cfg(not(feature = "board-pleco"))]
-pub struct UciEngine {
+pub struct UciEngineCozy {
     pos: Position,
     searcher: Searcher,
-    hash_mb: usize,
-    threads: usize,
+    options: EngineOptions,
     use_nnue: bool,
     nnue_loaded: bool,
+    skill: SkillLevel,
+    skill_rng: SmallRng,
+    humanize: bool,
+    humanizer: Humanizer,
+    info: InfoOptions,
+    signals: MatchSignals,
+    multipv: usize,
+    analysis_style: AnalysisStyle,
+    timeline: TimelineRecorder,
+    ponder: bool,
+    control: Arc<SearchControl>,
+    out: UciOut,
 }
 
-#[cfg(not(feature = "board-pleco"))]
-impl UciEngine {
-    pub fn new() -> Self { Self { pos: Position::startpos(), searcher: Searcher::default(), hash_mb: 64, threads: 1, use_nnue: false, nnue_loaded: false } }
+impl Default for UciEngineCozy {
+    fn default() -> Self { Self::new() }
+}
+
+impl UciEngineCozy {
+    pub fn new() -> Self {
+        let options = EngineOptions::global_or(EngineOptions::cozy_uci());
+        let (searcher, use_nnue) = match options.build_searcher() {
+            Ok(s) => (s, options.eval == EvalKind::Nnue),
+            Err(e) => { eprintln!("engine options: {}", e); (Searcher::default(), false) }
+        };
+        Self { pos: Position::startpos(), searcher, use_nnue, nnue_loaded: use_nnue, options, skill: SkillLevel::default(), skill_rng: SmallRng::from_entropy(), humanize: false, humanizer: Humanizer::new(rand::random()), info: InfoOptions::default(), signals: MatchSignals::default(), multipv: 1, analysis_style: AnalysisStyle::default(), timeline: TimelineRecorder::default(), ponder: false, control: Arc::default(), out: UciOut::default() }
+    }
+
+    pub fn set_skill_level(&mut self, level: u8) { self.skill = SkillLevel::new(level); }
+    pub fn set_output(&mut self, out: UciOut) { self.out = out; }
+    /// Share `stop` / `ponderhit` handling with the front-end's command reader.
+    pub fn set_control(&mut self, control: Arc<SearchControl>) { self.control = control; }
 
     fn cmd_uci(&self) {
-        println!("id name PieBot NNUE (skeleton)");
-        println!("id author PieBot Team");
-        println!("option name Threads type spin default 1 min 1 max 512");
-        println!("option name Hash type spin default 64 min 1 max 16384");
-        println!("option name UseNNUE type check default false");
-        println!("option name NNUEFile type string default ");
-        println!("option name NNUEQuantFile type string default ");
-        println!("option name EvalBlend type spin default 100 min 0 max 100");
-        println!("uciok");
+        self.out.line(format_args!("id name {}", version::id_name("Cozy")));
+        self.out.line("id author PieBot Team");
+        self.out.line(version::build_line());
+        self.out.line(version::network_line(self.network_fingerprint()));
+        for o in self.uci_options() { self.out.line(o); }
+        self.out.line("uciok");
+    }
+
+    /// Every option with its current value, grouped as printed by `uci`.
+    pub fn uci_options(&self) -> Vec<UciOption> {
+        let o = &self.options;
+        let style = if self.analysis_style == AnalysisStyle::Scored { "Scored" } else { "Exclusion" };
+        let mut v = vec![
+            UciOption::spin("Engine", "Threads", o.threads as i64, 1, 512, "Search threads"),
+            UciOption::spin("Engine", "Hash", o.hash_mb as i64, 1, MAX_HASH_MB as i64, "Transposition table size (MB)"),
+            backend_option(Backend::Cozy),
+            UciOption::spin("Search", "MultiPV", self.multipv as i64, 1, 64, "Principal variations to report"),
+            UciOption::combo("Search", "AnalysisStyle", style, &["Exclusion", "Scored"], "MultiPV by re-search (exact) or root scores (fast)"),
+            UciOption::spin("Search", "ExtensionBudget", o.max_extensions.into(), 0, 16, "One-ply extensions allowed along a line"),
+            UciOption::combo("Evaluation", "Eval", &o.eval.to_string(), &["pst", "material", "nnue"], "Static evaluation"),
+            UciOption::check("Evaluation", "UseNNUE", self.use_nnue, "Evaluate with the loaded network"),
+            UciOption::string("Evaluation", "NNUEFile", "", "Dense network file (PIENNUE1)"),
+            UciOption::string("Evaluation", "NNUEQuantFile", "", "Quantized network file (PIENNQ01)"),
+            UciOption::spin("Evaluation", "EvalBlend", o.eval_blend.into(), 0, 100, "Share of the NNUE score blended with PST (%)"),
+            UciOption::spin("Play", "Skill Level", self.skill.level().into(), 1, 20, "Playing strength (20: full)"),
+            UciOption::check("Play", "Humanize", self.humanize, "Vary think time like a human player"),
+            UciOption::check("Play", "Ponder", self.ponder, "Name the expected reply after bestmove"),
+        ];
+        v.extend(o.uci_options());
+        v.extend(self.signals.uci_options());
+        v.extend(self.info.uci_options());
+        v.extend(self.timeline.uci_options());
+        v.push(log_option(&self.out));
+        UciOption::sort(&mut v);
+        v
     }
 
-    fn cmd_isready(&self) { println!("readyok"); }
+    /// Hash of the network evaluation would use: the quantized net wins over the dense one.
+    fn network_fingerprint(&self) -> Option<u64> {
+        self.searcher.nnue_quant_model().map(|m| m.fingerprint())
+            .or_else(|| self.searcher.nnue_network().map(|n| n.fingerprint()))
+    }
 
-    fn cmd_ucinewgame(&mut self) { self.pos = Position::startpos(); }
+    fn cmd_isready(&self) { self.out.line("readyok"); }
 
-    pub(crate) fn apply_setoption(&mut self, name: &str, value: &str) {
+    fn cmd_ucinewgame(&mut self) { self.pos = Position::startpos(); self.searcher.new_game(); self.humanizer.new_game(); self.signals.new_game(); self.timeline.new_game(); }
+
+    pub(crate) fn apply_setoption(&mut self, name: &str, value: &str) -> crate::Result<()> {
         match name.to_lowercase().as_str() {
             "hash" => {
-                if let Ok(mb) = value.parse::<usize>() { self.hash_mb = mb; self.searcher.set_tt_capacity_mb(mb); }
-            }
-            "threads" => {
-                if let Ok(t) = value.parse::<usize>() { self.threads = t.max(1); }
+                self.options.hash_mb = parse_value::<usize>(name, value)?.clamp(1, MAX_HASH_MB);
+                self.searcher.set_tt_capacity_mb(self.options.hash_mb);
             }
+            "threads" => self.options.threads = parse_value::<usize>(name, value)?.clamp(1, 512),
             "usennue" => {
-                let on = matches!(value.to_lowercase().as_str(), "true" | "1" | "on" | "yes");
-                self.use_nnue = on;
-                self.searcher.set_use_nnue(on && self.nnue_loaded);
+                self.use_nnue = parse_check(value);
+                self.searcher.set_use_nnue(self.use_nnue && self.nnue_loaded);
             }
+            // GUIs send empty string options at startup; that means "no file"
+            "nnuefile" | "nnuequantfile" if value.trim().is_empty() => {}
             "nnuefile" => {
-                // Attempt to load the dense-f32 dev format (PIENNUE1)
-                match Nnue::load(value) {
-                    Ok(nn) => {
-                        self.searcher.set_nnue_network(Some(nn));
-                        self.nnue_loaded = true;
-                        self.searcher.set_use_nnue(self.use_nnue);
-                    }
-                    Err(_e) => {
-                        // Ignore errors silently for now
-                    }
-                }
+                // Dense-f32 dev format (PIENNUE1)
+                let nn = Nnue::load(value)?;
+                self.searcher.set_nnue_network(Some(nn));
+                self.nnue_loaded = true;
+                self.searcher.set_use_nnue(self.use_nnue);
             }
             "nnuequantfile" => {
-                match QuantNnue::load_quantized(value) {
-                    Ok(model) => {
-                        self.searcher.set_nnue_quant_model(model);
-                        self.nnue_loaded = true;
-                        self.searcher.set_use_nnue(self.use_nnue);
-                    }
-                    Err(_e) => {
-                        // Ignore errors silently for now
-                    }
-                }
+                // Swapped in for the next search (all workers); a bad file keeps the old one
+                let model = QuantNnue::load_quantized(value)?;
+                QuantNetwork::validate(&model)?;
+                self.out.line(loaded_line("NNUEQuantFile", value, &model));
+                self.searcher.set_nnue_quant_model(model);
+                self.nnue_loaded = true;
+                self.searcher.set_use_nnue(self.use_nnue);
+            }
+            // Next to the main network; an empty value removes it
+            "nnuesmallfile" | "nnue_small" if value.trim().is_empty() => {
+                self.options.nnue_small_file = None;
+                self.searcher.set_nnue_small(None);
+            }
+            "nnuesmallfile" | "nnue_small" => {
+                let model = QuantNnue::load_quantized(value)?;
+                QuantNetwork::validate(&model)?;
+                self.out.line(loaded_line("NNUESmallFile", value, &model));
+                self.options.nnue_small_file = Some(value.to_string());
+                self.searcher.set_nnue_small(Some(SmallNet::new(model, self.options.small_net_phase)));
+            }
+            // Works with every eval; an empty value removes it, a bad file keeps the old one
+            "policyfile" | "policy" if value.trim().is_empty() => {
+                self.options.policy_file = None;
+                self.searcher.set_policy(None);
+            }
+            "policyfile" | "policy" => {
+                let net = PolicyNet::load(value)?;
+                self.out.line(policy_line(value, &net));
+                self.options.policy_file = Some(value.to_string());
+                self.searcher.set_policy(Some(net));
             }
             "evalblend" => {
-                if let Ok(p) = value.parse::<u8>() {
-                    self.searcher.set_eval_blend_percent(p);
-                }
+                self.options.eval_blend = parse_value::<u8>(name, value)?.min(100);
+                self.searcher.set_eval_blend_percent(self.options.eval_blend);
+            }
+            "skill level" => self.set_skill_level(parse_value(name, value)?),
+            "humanize" => self.humanize = parse_check(value),
+            "ponder" => self.ponder = parse_check(value),
+            "multipv" => self.multipv = parse_value::<usize>(name, value)?.clamp(1, 64),
+            "analysisstyle" => {
+                self.analysis_style = value.parse().map_err(|e: String| TitanError::option(name, e))?;
             }
-            _ => {}
+            "timelinedir" => self.timeline.set_dir(value),
+            "logdir" => self.out.set_log_dir(value)?,
+            "extensionbudget" => self.options.max_extensions = parse_value::<u32>(name, value)?.min(16),
+            // Shared engine options, then reporting options
+            n => match self.options.set(n, value) {
+                Ok(()) if matches!(n, "eval" | "nnue" | "nnue_quant" | "blend") => {
+                    self.options.configure(&mut self.searcher)?;
+                    self.use_nnue = self.options.eval == EvalKind::Nnue;
+                    self.nnue_loaded = self.use_nnue;
+                }
+                Ok(()) if matches!(n, "eval_hash" | "evalhash") => self.searcher.set_eval_hash_mb(self.options.eval_hash_mb),
+                Ok(()) if matches!(n, "policy_weight" | "policyweight") => self.searcher.set_policy_weight(self.options.policy_weight),
+                Ok(()) if matches!(n, "small_net_phase" | "smallnetphase") => {
+                    if let Some(sn) = rephased(self.searcher.nnue_small(), self.options.small_net_phase) { self.searcher.set_nnue_small(Some(sn)); }
+                }
+                Ok(()) => {}
+                Err(TitanError::UnknownOption(_)) => match self.signals.set(n, value) {
+                    Err(TitanError::UnknownOption(_)) => self.info.set(n, value)?,
+                    r => r?,
+                },
+                Err(e) => return Err(e),
+            },
         }
+        Ok(())
     }
 
+    /// Replace the position; on error the previous one is kept and the reason is
+    /// reported as `info string`.
     fn cmd_position(&mut self, args: &str) {
-        // Supports: 'position startpos [moves ...]' and 'position fen <fen> [moves ...]'
-        let mut tokens = args.split_whitespace();
-        match tokens.next() {
-            Some("startpos") => {
-                self.pos = Position::startpos();
-                if let Some("moves") = tokens.next() {
-                    let moves: Vec<String> = tokens.map(|s| s.to_string()).collect();
-                    if let Ok(p) = Position::set_from_start_and_moves(&moves) {
-                        self.pos = p;
-                    }
-                }
-            }
-            Some("fen") => {
-                // FEN is 6 fields; collect them
-                let fen_fields: Vec<&str> = tokens.by_ref().take(6).collect();
-                if fen_fields.len() == 6 {
-                    let fen = fen_fields.join(" ");
-                    if let Ok(p) = Position::from_fen(&fen) { self.pos = p; }
-                }
-                if let Some("moves") = tokens.next() {
-                    let moves: Vec<String> = tokens.map(|s| s.to_string()).collect();
-                    if let Ok(p) = Position::set_from_start_and_moves(&moves) {
-                        self.pos = p;
-                    }
-                }
-            }
-            _ => {}
+        match Position::from_uci_position(args) {
+            Ok(p) => { self.pos = p; self.timeline.set_start(args); }
+            Err(e) => self.out.line(format_args!("info string {}", e)),
         }
     }
 
@@ -189,49 +1077,271 @@ impl UciEngine {
         }
         let name = name_parts.join(" ");
         let val = value.unwrap_or_else(|| "".to_string());
-        self.apply_setoption(&name, &val);
+        if let Err(e) = self.apply_setoption(&name, &val) { self.out.line(format_args!("info string {}", e)); }
     }
 
     fn cmd_go(&mut self, args: &str) {
-        // Support minimal: go depth N | go movetime T
-        let mut depth: u32 = 6;
-        let mut movetime_ms: Option<u64> = None;
-        let mut tokens = args.split_whitespace();
+        self.control.begin(args);
+        self.searcher.set_stop_flag(Some(self.control.stop_flag()));
+        // Run the whole search inside a pool whose workers have the configured stack
+        // and are pinned to cores
+        let pool = affinity::thread_pool(self.options.threads, &self.options.affinity, self.options.stack_mb);
+        pool.install(|| self.go(args));
+    }
+
+    fn go(&mut self, args: &str) {
+        // go [depth N] [movetime T] [wtime W btime B winc I binc J movestogo M] [searchmoves ...]
+        let white = self.pos.board().side_to_move() == cozy_chess::Color::White;
+        let limits = SearchLimits::from_uci(args, white, self.options.depth, self.options.movetime_ms, self.options.min_depth);
+        let (depth, mut movetime_ms) = (limits.depth, limits.movetime_ms);
+        let mut search_moves: Vec<cozy_chess::Move> = Vec::new();
+        let mut tokens = args.split_whitespace().peekable();
         while let Some(tok) = tokens.next() {
             match tok {
-                "depth" => {
-                    if let Some(d) = tokens.next().and_then(|s| s.parse::<u32>().ok()) { depth = d; }
-                }
-                "movetime" => {
-                    if let Some(t) = tokens.next().and_then(|s| s.parse::<u64>().ok()) { movetime_ms = Some(t); }
+                "searchmoves" => {
+                    // Moves run until the next non-move token
+                    while let Some(m) = tokens.peek().and_then(|s| self.pos.find_move_uci(s)) { search_moves.push(m); tokens.next(); }
                 }
                 _ => {}
             }
         }
-        let mut params = SearchParams::default();
-        params.depth = depth;
-        params.use_tt = true;
-        params.order_captures = true;
-        params.use_history = true;
-        params.movetime = movetime_ms.map(Duration::from_millis);
-        params.threads = self.threads;
+        // Clock: abort at the hard limit, stop between iterations by the soft one.
+        // A humanized think time replaces the plan with a single deadline.
+        let go = GoLimits::from_uci(args, white);
+        let config = self.options.time_config();
+        let mut clock = None;
+        if go.movetime.is_none() && go.remaining.is_some() {
+            if let Some(budget) = allocate(&go, &config) {
+                let deadline = if self.humanize { budget.soft } else { budget.hard };
+                movetime_ms = Some((deadline.as_millis() as u64).max(1));
+                if !self.humanize { clock = Some(ClockPlan { budget, config }); }
+            }
+        }
+        if self.humanize {
+            if let Some(ms) = movetime_ms {
+                let board = self.pos.board();
+                let ctx = MoveContext { legal_moves: self.pos.legal_move_count(), in_check: !board.checkers().is_empty(), recapture: self.pos.can_recapture() };
+                movetime_ms = Some((self.humanizer.think_time(Duration::from_millis(ms), ctx).as_millis() as u64).max(1));
+            }
+        }
+        let mut params = SearchParams { depth, movetime: movetime_ms.map(Duration::from_millis), clock, ..self.options.search_params() };
+        // A humanized think time on the clock is the only deadline: MinDepth may not pass it
+        if self.humanize && go.remaining.is_some() { params.min_depth = 0; }
+        // A ponder search has no time limit until `ponderhit` starts the clock
+        if self.control.arm_ponder(clock.map(|c| c.budget.soft).or(params.movetime)) { (params.movetime, params.clock) = (None, None); }
+        self.searcher.new_search();
+        self.searcher.set_small_net_only(self.options.small_net_only(go.remaining));
+        self.searcher.set_search_moves(if search_moves.is_empty() { None } else { Some(search_moves) });
+        self.searcher.set_game_history(self.pos.history().to_vec());
+        // A mated or stalemated root goes straight to the search, which reports it
+        let terminal = self.pos.legal_move_count() == 0;
+        if let Some(cap) = self.skill.depth_cap().filter(|_| !terminal) {
+            // Score every root move at the capped depth, then let the skill level choose
+            let board = self.pos.board().clone();
+            let scored = self.searcher.score_root_moves(&board, SearchParams { depth: if depth == 0 { cap } else { depth.min(cap) }, ..params });
+            let best = self.skill.pick(&scored, &mut self.skill_rng).map(|m| m.to_string());
+            send_bestmove(&self.out, &self.control, best.as_deref(), self.ponder_reply(best.as_deref()));
+            return;
+        }
+        let material = material_units(self.pos.board());
+        if self.multipv > 1 && !terminal {
+            let lines = self.searcher.search_multipv(self.pos.board(), params, self.multipv, self.analysis_style);
+            for (k, res) in lines.iter().enumerate() { self.out.line(self.info.multipv_line(k + 1, res, material)); }
+            if let Some(first) = lines.first() {
+                self.timeline.record(self.pos.board(), first);
+                if let Some(s) = self.signals.record(first.score, self.pos.board().fullmove_number().into()) { self.out.line(s); }
+            }
+            let best = lines.first().and_then(|r| r.bestmove.as_deref());
+            send_bestmove(&self.out, &self.control, best, self.ponder_reply(best));
+            return;
+        }
+        let (hook, throttle) = self.info.hook(material, &self.out);
+        self.searcher.set_info_hook(Some(hook));
         let res = self.searcher.search_with_params(self.pos.board(), params);
-        if let Some(best) = res.bestmove { println!("bestmove {}", best); } else { println!("bestmove 0000"); }
-    }
-
-    pub fn run_loop(&mut self) {
-        let stdin = io::stdin();
-        for line in stdin.lock().lines() {
-            let line = match line { Ok(s) => s.trim().to_string(), Err(_) => break };
-            if line.is_empty() { continue; }
-            if line == "uci" { self.cmd_uci(); continue; }
-            if line == "isready" { self.cmd_isready(); continue; }
-            if line == "ucinewgame" { self.cmd_ucinewgame(); continue; }
-            if let Some(rest) = line.strip_prefix("setoption ") { self.cmd_setoption(rest); continue; }
-            if line == "quit" { break; }
-            if let Some(rest) = line.strip_prefix("position ") { self.cmd_position(rest); continue; }
-            if let Some(rest) = line.strip_prefix("go ") { self.cmd_go(rest); continue; }
-            if line == "stop" { /* ignore in skeleton */ continue; }
+        self.searcher.set_info_hook(None);
+        if throttle.lock().unwrap().admit_final(res.depth, res.score) { self.out.line(self.info.line(&res, material)); }
+        self.timeline.record(self.pos.board(), &res);
+        if let Some(s) = self.signals.record(res.score, self.pos.board().fullmove_number().into()) { self.out.line(s); }
+        send_bestmove(&self.out, &self.control, res.bestmove.as_deref(), self.ponder_reply(res.bestmove.as_deref()));
+    }
+
+    // The reply `bestmove ... ponder` names when the Ponder option is on
+    fn ponder_reply(&self, best: Option<&str>) -> Option<String> {
+        let best = best.filter(|_| self.ponder).and_then(|b| self.pos.find_move_uci(b))?;
+        self.searcher.ponder_move(self.pos.board(), best).map(|m| m.to_string())
+    }
+
+    // Non-standard `d`: board, key, eval, TT entry and the resolved search parameters
+    fn cmd_d(&self) {
+        let board = self.pos.board();
+        let key = crate::search::zobrist::compute(board);
+        for l in position_lines(board, key) { self.out.line(l); }
+        let source = if self.use_nnue && self.network_fingerprint().is_some() { "nnue" } else { "classical" };
+        self.out.line(format_args!("Static eval (side to move): {} ({})", self.searcher.static_eval(board), source));
+        self.out.line(tt_line(self.searcher.tt().get(key).map(|e| (e.depth, e.bound, e.score, e.best.map(|m| m.to_string())))));
+        self.out.line(format_args!("Options: {}", self.options.describe()));
+        self.out.line(format_args!("Params: {:?}", self.options.search_params()));
+    }
+
+    /// Handle one UCI command; returns false on `quit`.
+    pub fn handle_line(&mut self, line: &str) -> bool {
+        if line == "uci" { self.cmd_uci(); }
+        else if line == "isready" { self.cmd_isready(); }
+        else if line == "d" || line == "dump" { self.cmd_d(); }
+        else if line == "ucinewgame" { self.cmd_ucinewgame(); }
+        else if let Some(rest) = line.strip_prefix("setoption ") { self.cmd_setoption(rest); }
+        else if line == "quit" { return false; }
+        else if let Some(rest) = line.strip_prefix("position ") { self.cmd_position(rest); }
+        else if let Some(rest) = line.strip_prefix("go ") { self.cmd_go(rest); }
+        // `stop` and `ponderhit` only matter mid-search, where the reader of `run_loop` takes them
+        true
+    }
+
+    pub fn run_loop<R: BufRead + Send, W: Write + Send + 'static>(&mut self, input: R, output: W) {
+        self.out = UciOut::new(output);
+        let (out, control) = (self.out.clone(), self.control.clone());
+        read_commands(input, &out, &control, |line| self.handle_line(line));
+        self.out.flush();
+    }
+}
+
+/// UCI front-end that dispatches to the selected backend. Positions are kept in
+/// sync across backends; other options apply to the active backend only, so
+/// GUIs should set Backend first.
+pub struct UciEngine {
+    backend: Backend,
+    cozy: UciEngineCozy,
+    #[cfg(feature = "board-pleco")]
+    pleco: UciEnginePleco,
+    control: Arc<SearchControl>,
+    out: UciOut,
+    // Probe length and what it measured (see `calibrate`)
+    calibration: Option<(Duration, CalibrationKey)>,
+}
+
+// What a calibration depends on: the backend, the eval and its networks
+type CalibrationKey = (Backend, EvalKind, Option<String>, Option<String>);
+
+impl Default for UciEngine {
+    fn default() -> Self { Self::new() }
+}
+
+impl UciEngine {
+    pub fn new() -> Self {
+        let mut e = Self {
+            backend: Backend::default(),
+            cozy: UciEngineCozy::new(),
+            #[cfg(feature = "board-pleco")]
+            pleco: UciEnginePleco::new(),
+            control: Arc::default(),
+            out: UciOut::default(),
+            calibration: None,
+        };
+        e.set_output(e.out.clone());
+        e.cozy.set_control(e.control.clone());
+        #[cfg(feature = "board-pleco")]
+        e.pleco.set_control(e.control.clone());
+        e
+    }
+
+    pub fn backend(&self) -> Backend { self.backend }
+    pub fn set_backend(&mut self, backend: Backend) { self.backend = backend; }
+
+    /// The active backend's options with their current values (what `uci` prints).
+    pub fn uci_options(&self) -> Vec<UciOption> {
+        match self.backend {
+            #[cfg(feature = "board-pleco")]
+            Backend::Pleco => self.pleco.uci_options(),
+            _ => self.cozy.uci_options(),
+        }
+    }
+
+    /// Measure this machine's speed with a `probe`-long search of the active backend
+    /// and eval (`Calibration::measure`) and install it for clock play. Switching the
+    /// backend or eval later measures again at the next `isready` or `ucinewgame`.
+    pub fn calibrate(&mut self, probe: Duration) {
+        let key = self.calibration_key();
+        let c = match self.backend {
+            #[cfg(feature = "board-pleco")]
+            Backend::Pleco => Calibration::measure_pleco(self.pleco.options(), probe),
+            _ => Calibration::measure(&self.cozy.options, probe),
+        };
+        c.set_global();
+        self.calibration = Some((probe, key));
+    }
+
+    fn calibration_key(&self) -> CalibrationKey {
+        let o = match self.backend {
+            #[cfg(feature = "board-pleco")]
+            Backend::Pleco => self.pleco.options(),
+            _ => &self.cozy.options,
+        };
+        (self.backend, o.eval, o.nnue_file.clone(), o.nnue_quant_file.clone())
+    }
+
+    pub fn set_skill_level(&mut self, level: u8) {
+        self.cozy.set_skill_level(level);
+        #[cfg(feature = "board-pleco")]
+        self.pleco.set_skill_level(level);
+    }
+
+    /// Handle one UCI command; returns false on `quit`. A `go infinite` or `go ponder`
+    /// waits for `stop` / `ponderhit`, which only `run_loop` reads while searching.
+    pub fn handle_line(&mut self, line: &str) -> bool {
+        if line == "ucinewgame" { self.out.log_new_game(); }
+        self.out.log_input(line);
+        let panics = guard::worker_panics();
+        let more = self.dispatch(line);
+        // Worker panics only reach stderr, which tournament GUIs rarely keep
+        let caught = guard::worker_panics() - panics;
+        if caught > 0 { self.out.log_warning(&format!("{} search worker panic(s) caught, details on stderr", caught)); }
+        more
+    }
+
+    fn dispatch(&mut self, line: &str) -> bool {
+        if line == "isready" || line == "ucinewgame" {
+            if let Some((probe, key)) = self.calibration.clone() { if key != self.calibration_key() { self.calibrate(probe); } }
+        }
+        if let Some(rest) = line.strip_prefix("setoption ") {
+            let mut tokens = rest.split_whitespace();
+            if tokens.next() == Some("name") && tokens.next().map(|t| t.eq_ignore_ascii_case("backend")).unwrap_or(false) && tokens.next() == Some("value") {
+                if let Some(Ok(b)) = tokens.next().map(|v| v.parse::<Backend>()) { self.backend = b; }
+                return true;
+            }
         }
+        #[cfg(feature = "board-pleco")]
+        if line.starts_with("position ") || line == "ucinewgame" {
+            // Keep the inactive backend's board in step so a switch mid-game is seamless
+            match self.backend {
+                Backend::Cozy => { self.pleco.handle_line(line); }
+                Backend::Pleco => { self.cozy.handle_line(line); }
+            }
+        }
+        match self.backend {
+            Backend::Cozy => self.cozy.handle_line(line),
+            #[cfg(feature = "board-pleco")]
+            Backend::Pleco => self.pleco.handle_line(line),
+            #[cfg(not(feature = "board-pleco"))]
+            Backend::Pleco => self.cozy.handle_line(line),
+        }
+    }
+
+    /// Read commands from `input` until `quit` or end of input, answering on
+    /// `output`. The binary passes stdin/stdout; embedders and tests pass their own.
+    /// Input is read on its own thread, so a search can be stopped.
+    pub fn run_loop<R: BufRead + Send, W: Write + Send + 'static>(&mut self, input: R, output: W) {
+        let out = UciOut::new(output);
+        self.set_output(out.clone());
+        let control = self.control.clone();
+        read_commands(input, &out, &control, |line| self.handle_line(line));
+        out.flush();
+    }
+
+    /// Send all further output (both backends) to `out`.
+    pub fn set_output(&mut self, out: UciOut) {
+        #[cfg(feature = "board-pleco")]
+        self.pleco.set_output(out.clone());
+        self.cozy.set_output(out.clone());
+        self.out = out;
     }
 }
@@ -0,0 +1,48 @@
+//! Build identity: crate version, git commit, build date and compiled-in features,
+//! reported by `uci` so results from distributed testing can be traced to a build.
+
+/// Crate version from Cargo.toml.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Short commit hash embedded by build.rs ("unknown" outside a git checkout).
+pub const GIT_HASH: &str = match option_env!("PIEBOT_GIT_HASH") { Some(h) => h, None => "unknown" };
+/// UTC build date (YYYY-MM-DD) embedded by build.rs.
+pub const BUILD_DATE: &str = match option_env!("PIEBOT_BUILD_DATE") { Some(d) => d, None => "unknown" };
+
+/// SIMD level the NNUE paths were built for: the `simd-*` feature if one is enabled,
+/// otherwise what the compile target guarantees.
+pub fn simd_level() -> &'static str {
+    if cfg!(feature = "simd-avx512") || cfg!(target_feature = "avx512f") { "avx512" }
+    else if cfg!(feature = "simd-avx2") || cfg!(target_feature = "avx2") { "avx2" }
+    else if cfg!(feature = "simd-neon") || cfg!(target_feature = "neon") { "neon" }
+    else if cfg!(target_feature = "sse4.1") { "sse4.1" }
+    else { "scalar" }
+}
+
+/// Cargo features compiled in (NNUE evaluation is always built).
+pub fn features() -> Vec<&'static str> {
+    let mut f = vec!["nnue"];
+    if cfg!(feature = "board-pleco") { f.push("pleco"); }
+    if cfg!(feature = "fuzzing") { f.push("fuzzing"); }
+    if cfg!(feature = "search-stats") { f.push("search-stats"); }
+    f
+}
+
+/// `id name` value, e.g. `PieBot 0.1.0 (Pleco) 1a2b3c4d5e6f`.
+pub fn id_name(backend: &str) -> String {
+    format!("PieBot {} ({}) {}", VERSION, backend, GIT_HASH)
+}
+
+/// One-line build summary for `info string`.
+pub fn build_line() -> String {
+    format!("info string build version {} commit {} date {} features {} simd {} profile {}",
+        VERSION, GIT_HASH, BUILD_DATE, features().join(","), simd_level(),
+        if cfg!(debug_assertions) { "debug" } else { "release" })
+}
+
+/// `info string` naming the active network by content hash, or `none`.
+pub fn network_line(fingerprint: Option<u64>) -> String {
+    match fingerprint {
+        Some(h) => format!("info string network {:016x}", h),
+        None => "info string network none".to_string(),
+    }
+}
@@ -0,0 +1,38 @@
+use cozy_chess::Board;
+use piebot::selfplay::adjudicate::{adjudicate, insufficient_material, Adjudication, GameHistory};
+
+fn play(board: &mut Board, hist: &mut GameHistory, uci: &str) {
+    let mut found = None;
+    board.generate_moves(|ml| { for m in ml { if format!("{}", m) == uci { found = Some(m); } } found.is_some() });
+    board.play(found.expect("legal move"));
+    hist.push(board);
+}
+
+#[test]
+fn knight_shuffle_is_threefold() {
+    let mut b = Board::default();
+    let mut h = GameHistory::new(&b);
+    for _ in 0..2 {
+        for m in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+            assert_eq!(adjudicate(&b, &h), None);
+            play(&mut b, &mut h, m);
+        }
+    }
+    assert_eq!(h.repetitions(&b), 3);
+    assert_eq!(adjudicate(&b, &h), Some(Adjudication::Threefold));
+}
+
+#[test]
+fn fifty_move_and_material_rules() {
+    let b = Board::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 100 80", false).unwrap();
+    assert_eq!(adjudicate(&b, &GameHistory::new(&b)), Some(Adjudication::FiftyMove));
+    let kk = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1", false).unwrap();
+    assert!(insufficient_material(&kk));
+    let kn = Board::from_fen("4k3/8/8/8/8/8/8/3NK3 w - - 0 1", false).unwrap();
+    assert!(insufficient_material(&kn));
+    let same_bishops = Board::from_fen("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1", false).unwrap();
+    assert!(insufficient_material(&same_bishops));
+    let opp_bishops = Board::from_fen("4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1", false).unwrap();
+    assert!(!insufficient_material(&opp_bishops));
+    assert!(!insufficient_material(&Board::default()));
+}
@@ -0,0 +1,51 @@
+use piebot::search::affinity::{physical_cores, CorePinning};
+use piebot::search::options::EngineOptions;
+
+#[test]
+fn pinning_parses_lists_and_round_trips() {
+    assert_eq!("off".parse::<CorePinning>().unwrap(), CorePinning::Off);
+    assert_eq!("Auto".parse::<CorePinning>().unwrap(), CorePinning::Auto);
+    assert_eq!("0,2,4-6".parse::<CorePinning>().unwrap(), CorePinning::Cores(vec![0, 2, 4, 5, 6]));
+    let p: CorePinning = "1+3-4".parse().unwrap();
+    assert_eq!(p.to_string().parse::<CorePinning>().unwrap(), p);
+    assert!("3-1".parse::<CorePinning>().is_err());
+    assert!("x".parse::<CorePinning>().is_err());
+    assert_eq!(serde_json::to_string(&p).unwrap(), "\"1+3+4\"");
+    assert_eq!(serde_json::from_str::<CorePinning>("\"auto\"").unwrap(), CorePinning::Auto);
+}
+
+#[test]
+fn affinity_option_survives_specs() {
+    let mut o = EngineOptions::default();
+    assert!(!o.describe().contains("affinity"));
+    o.apply_spec("threads=4,affinity=0+2+4-5").unwrap();
+    assert_eq!(o.affinity, CorePinning::Cores(vec![0, 2, 4, 5]));
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    assert!(o.set("affinity", "fast").is_err());
+}
+
+#[test]
+fn auto_uses_each_physical_core_once() {
+    let cores = physical_cores();
+    assert!(!cores.is_empty());
+    let mut dedup = cores.clone();
+    dedup.sort();
+    dedup.dedup();
+    assert_eq!(dedup.len(), cores.len());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn pool_workers_are_pinned() {
+    use piebot::search::affinity::{pin_current_thread, thread_pool};
+    // Skip where the sandbox refuses sched_setaffinity
+    if !std::thread::spawn(|| pin_current_thread(0)).join().unwrap() { return; }
+    let pool = thread_pool(2, &CorePinning::Cores(vec![0]), 16);
+    let allowed = pool.broadcast(|_| {
+        let status = std::fs::read_to_string("/proc/thread-self/status").unwrap();
+        status.lines().find_map(|l| l.strip_prefix("Cpus_allowed_list:")).map(|s| s.trim().to_string()).unwrap()
+    });
+    assert_eq!(allowed, vec!["0".to_string(), "0".to_string()]);
+}
@@ -0,0 +1,130 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::aspiration::{WindowStats, MAX_WINDOW, MIN_WINDOW};
+use piebot::search::options::EngineOptions;
+use piebot::search::score::MATE_SCORE;
+
+#[test]
+fn fresh_stats_keep_the_base_window() {
+    let w = WindowStats::default();
+    assert_eq!(w.window(30), 30);
+    assert_eq!(w.window(1), MIN_WINDOW);
+    assert_eq!(w.window(1000), MAX_WINDOW);
+}
+
+#[test]
+fn quiet_games_narrow_and_volatile_ones_widen() {
+    let mut quiet = WindowStats::default();
+    for _ in 0..50 { quiet.record(-30, 30, 5, 0, 5); }
+    assert_eq!(quiet.window(30), 15);
+    assert_eq!((quiet.iterations, quiet.fail_high, quiet.fail_low), (50, 0, 0));
+
+    let mut failing = WindowStats::default();
+    for i in 0..50 {
+        // Alternate fails high and low that settle close to the previous score
+        let score = if i % 2 == 0 { 30 } else { -30 };
+        failing.record(-30, 30, score, 0, score / 3);
+    }
+    assert_eq!((failing.fail_high, failing.fail_low), (25, 25));
+    assert_eq!(failing.window(30), 75, "fail rate near 1 caps the scale at 2.5");
+
+    let mut swinging = WindowStats::default();
+    for i in 0..50 { swinging.record(-1000, 1000, 0, 0, if i % 2 == 0 { 150 } else { -150 }); }
+    assert!(swinging.window(30) >= 140, "window follows the typical swing: {}", swinging.window(30));
+}
+
+#[test]
+fn mate_scores_do_not_count_as_swings() {
+    let mut w = WindowStats::default();
+    for _ in 0..20 { w.record(-1000, 1000, 0, 0, MATE_SCORE - 5); }
+    assert_eq!(w.volatility, 0.0);
+}
+
+fn params() -> SearchParams {
+    SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, use_aspiration: true, aspiration_window_cp: 30, adaptive_window: true, ..SearchParams::default() }
+}
+
+#[test]
+fn statistics_carry_across_moves_until_a_new_game() {
+    let mut s = Searcher::default();
+    let board = Board::default();
+    s.search_with_params(&board, params());
+    let first = s.window_stats().iterations;
+    assert_eq!(first, 4, "depths 2..=5 are aspirated");
+    s.new_search();
+    let mut next = board.clone();
+    next.play("e2e4".parse().unwrap());
+    s.search_with_params(&next, params());
+    assert_eq!(s.window_stats().iterations, 2 * first);
+    s.new_game();
+    assert_eq!(s.window_stats(), WindowStats::default());
+}
+
+#[test]
+fn adaptive_window_option() {
+    let mut o = EngineOptions::default();
+    assert!(o.adaptive_window && o.search_params().adaptive_window);
+    assert!(!o.describe().contains("adaptive_window"));
+    o.set("AdaptiveWindow", "false").unwrap();
+    assert!(!o.search_params().adaptive_window);
+    assert!(o.describe().contains(",adaptive_window=off"));
+    o.set("adaptive_window", "on").unwrap();
+    assert!(o.adaptive_window);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_statistics_carry_until_clear() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_adaptive_window(true);
+    let mut b = pleco::Board::start_pos();
+    s.search_movetime(&mut b, 60_000, 5);
+    let first = s.window_stats().iterations;
+    assert!(first > 0);
+    s.new_search();
+    s.search_movetime(&mut b, 60_000, 5);
+    assert_eq!(s.window_stats().iterations, 2 * first);
+    s.clear();
+    assert_eq!(s.window_stats(), WindowStats::default());
+}
+
+#[test]
+fn failed_side_widens_then_opens() {
+    use piebot::search::aspiration::{Window, WIDEN_TRIES};
+    let mut w = Window::new(20, 30);
+    assert_eq!((w.alpha, w.beta), (-10, 50));
+    assert!(!w.widen(25), "inside the window");
+    // Fail high: only beta moves, to the score plus the doubled margin
+    assert!(w.widen(50));
+    assert_eq!((w.alpha, w.beta, w.delta), (-10, 110, 60));
+    // Fail low
+    assert!(w.widen(-10));
+    assert_eq!((w.alpha, w.beta, w.delta), (-130, 110, 120));
+    assert_eq!(w.fails, WIDEN_TRIES - 1);
+    assert!(w.widen(110));
+    assert_eq!((w.alpha, w.beta), (-130, MATE_SCORE));
+    assert!(!w.widen(5000), "an open side never fails");
+    assert!(!w.is_full());
+}
+
+#[test]
+fn mate_scores_open_the_window() {
+    use piebot::search::aspiration::Window;
+    let mut w = Window::new(0, 30);
+    assert!(w.widen(-(MATE_SCORE - 10)));
+    assert_eq!(w.alpha, -MATE_SCORE);
+    assert!(w.widen(MATE_SCORE - 3));
+    assert!(w.is_full());
+}
+
+#[test]
+fn widening_matches_a_full_window_search() {
+    let board = Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4", false).unwrap();
+    let narrow = SearchParams { aspiration_window_cp: 1, adaptive_window: false, ..params() };
+    let full = SearchParams { use_aspiration: false, ..params() };
+    let a = Searcher::default().search_with_params(&board, narrow);
+    let b = Searcher::default().search_with_params(&board, full);
+    assert!(a.bestmove.is_some());
+    assert!((a.score.value() - b.score.value()).abs() <= 60, "{} vs {}", a.score.value(), b.score.value());
+}
@@ -0,0 +1,52 @@
+use cozy_chess::Board;
+use piebot::board::cozy::{game_phase, has_legal_moves, legal_move_count, Position, PHASE_MAX};
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::search::eval::MATE_SCORE;
+
+#[test]
+#[allow(deprecated)]
+fn old_move_count_name_still_works() {
+    let start = Position::startpos();
+    assert_eq!(start.legal_moves_count(), start.legal_move_count());
+}
+
+#[test]
+fn move_count_and_terminal_detection() {
+    let start = Position::startpos();
+    assert_eq!(start.legal_move_count(), 20);
+    assert!(start.has_legal_moves());
+    let kiwipete: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".parse().unwrap();
+    assert_eq!(legal_move_count(&kiwipete), 48);
+    let mated: Board = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3".parse().unwrap();
+    let stalemate: Board = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".parse().unwrap();
+    for b in [&mated, &stalemate] {
+        assert!(!has_legal_moves(b));
+        assert_eq!(legal_move_count(b), 0);
+    }
+    let mut s = Searcher::default();
+    let r = s.search_with_params(&mated, SearchParams { depth: 4, ..Default::default() });
+    assert!(r.bestmove.is_none());
+    assert_eq!(r.score.value(), -MATE_SCORE);
+    assert_eq!(s.search_with_params(&stalemate, SearchParams { depth: 4, ..Default::default() }).score.value(), 0);
+}
+
+#[test]
+fn game_phase_tracks_non_pawn_material() {
+    assert_eq!(Position::startpos().game_phase(), PHASE_MAX);
+    let pawns_only: Board = "4k3/pppp4/8/8/8/8/4PPPP/4K3 w - - 0 1".parse().unwrap();
+    assert_eq!(game_phase(&pawns_only), 0);
+    let rook_and_knight: Board = "4k3/8/8/8/8/8/8/RN2K3 w - - 0 1".parse().unwrap();
+    assert_eq!(game_phase(&rook_and_knight), 3);
+    let many_queens: Board = "qqqqk3/8/8/8/8/8/8/QQQQK3 w - - 0 1".parse().unwrap();
+    assert_eq!(game_phase(&many_queens), PHASE_MAX);
+}
+
+#[test]
+fn recapture_follows_the_last_capture() {
+    let pos = |moves: &str| Position::from_uci_position(&format!("startpos moves {}", moves)).unwrap();
+    // exd5 can be taken back by the queen
+    assert!(pos("e2e4 d7d5 e4d5").can_recapture());
+    // A quiet last move, and a capture nothing can answer
+    assert!(!pos("e2e4 d7d5").can_recapture());
+    assert!(!pos("g1f3 e7e5 f3e5").can_recapture());
+}
@@ -0,0 +1,85 @@
+use cozy_chess::{Board, Move};
+use piebot::board::cozy::{flip_colors, flip_move, mirror_files, mirror_move, Position};
+use piebot::search::alphabeta::Searcher;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn legal(b: &Board) -> Vec<Move> {
+    let mut v = Vec::new();
+    b.generate_moves(|ml| { v.extend(ml); false });
+    v
+}
+
+fn same_moves(mut a: Vec<Move>, mut b: Vec<Move>) -> bool {
+    a.sort_by_key(|m| format!("{}", m));
+    b.sort_by_key(|m| format!("{}", m));
+    a == b
+}
+
+// Random-walk positions, including some with castling rights and en passant squares
+fn positions(n: usize) -> Vec<Board> {
+    let mut rng = SmallRng::seed_from_u64(5);
+    let mut out = vec![Board::default(), "r3k2r/pp1n1ppp/8/2pP4/8/8/PPP2PPP/R3K2R w KQkq c6 0 12".parse().unwrap()];
+    while out.len() < n {
+        let mut b = Board::default();
+        for _ in 0..rng.gen_range(1..40) {
+            let ms = legal(&b);
+            if ms.is_empty() { break; }
+            b.play(ms[rng.gen_range(0..ms.len())]);
+        }
+        out.push(b);
+    }
+    out
+}
+
+#[test]
+fn colour_flip_is_an_involution_that_maps_moves() {
+    for b in positions(60) {
+        let f = flip_colors(&b);
+        assert_ne!(f.side_to_move(), b.side_to_move());
+        assert_eq!(format!("{}", flip_colors(&f)), format!("{}", b));
+        assert!(same_moves(legal(&b).into_iter().map(flip_move).collect(), legal(&f)), "moves differ for {}", b);
+    }
+}
+
+#[test]
+fn mirror_requires_no_castling_and_maps_moves() {
+    let start = Position::startpos();
+    assert!(start.mirrored().is_none());
+    let mut mirrored = 0;
+    for b in positions(200) {
+        let Some(m) = mirror_files(&b) else { continue; };
+        mirrored += 1;
+        assert_eq!(format!("{}", mirror_files(&m).unwrap()), format!("{}", b));
+        assert!(same_moves(legal(&b).into_iter().map(mirror_move).collect(), legal(&m)), "moves differ for {}", b);
+    }
+    let p = Position::from_fen("8/8/3k4/8/2P5/8/5K2/8 w - - 0 40").unwrap().mirrored().unwrap();
+    assert_eq!(format!("{}", p.board()), "8/8/4k3/8/5P2/8/2K5/8 w - - 0 40");
+    assert!(mirrored > 0);
+}
+
+#[test]
+fn eval_is_colour_symmetric() {
+    let mut s = Searcher::default();
+    for b in positions(40) {
+        if !b.checkers().is_empty() { continue; }
+        let f = flip_colors(&b);
+        assert_eq!(s.qsearch_eval_cp(&b), s.qsearch_eval_cp(&f), "asymmetric eval for {}", b);
+    }
+}
+
+#[test]
+fn move_encoding_round_trips_every_legal_move() {
+    use piebot::board::cozy::{decode_move, encode_move};
+    // Castling both ways, en passant and all four under/promotions with captures
+    for fen in ["r3k2r/1P6/8/3pP3/8/8/6p1/R3K2R w KQkq d6 0 1", "r3k2r/1P6/8/8/3pP3/8/6p1/R3K2R b KQkq e3 0 1"] {
+        let b = Board::from_fen(fen, false).unwrap();
+        let moves = legal(&b);
+        assert!(moves.iter().filter(|m| m.promotion.is_some()).count() >= 8);
+        let mut codes: Vec<u16> = moves.iter().map(|&m| encode_move(m)).collect();
+        for (&m, &c) in moves.iter().zip(&codes) { assert_eq!(decode_move(c), Some(m)); }
+        codes.sort(); codes.dedup();
+        assert_eq!(codes.len(), moves.len());
+    }
+    assert_eq!(decode_move(0x7000), None);
+}
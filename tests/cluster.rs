@@ -0,0 +1,91 @@
+use cozy_chess::Board;
+use piebot::search::cluster::{cluster_search, ping, run_job, serve, ClusterJob, ClusterLink};
+use piebot::search::options::EngineOptions;
+use std::net::TcpListener;
+
+// Without pruning or aspiration a restricted root search is exact, so splitting
+// the root cannot change the score
+fn exact_options() -> EngineOptions {
+    let mut o = EngineOptions::default();
+    o.apply_spec("depth=3,lmr=off,nullmove=off,aspiration=off,king_danger=off").unwrap();
+    o
+}
+
+fn spawn_worker_with(secret: Option<&str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let secret = secret.map(String::from);
+    std::thread::spawn(move || serve(listener, secret));
+    addr
+}
+
+fn spawn_worker() -> String { spawn_worker_with(None) }
+
+const FEN: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+
+#[test]
+fn split_root_matches_local_search() {
+    let board = Board::from_fen(FEN, false).unwrap();
+    let opts = exact_options();
+    let local = opts.build_searcher().unwrap().search_with_params(&board, opts.search_params());
+    let workers = vec![spawn_worker(), spawn_worker()];
+    assert!(workers.iter().all(|w| ping(w, &ClusterLink::default())));
+    let run = cluster_search(&board, &opts, &workers, &ClusterLink::default()).unwrap();
+    assert_eq!(run.result.score.value(), local.score.value());
+    assert_eq!(run.result.depth, 3);
+    assert_eq!(run.shares.len(), 2);
+    assert_eq!(run.shares.iter().map(|s| s.moves).sum::<usize>(), 33);
+    assert!(run.shares.iter().all(|s| s.failure.is_none()));
+}
+
+#[test]
+fn unreachable_worker_falls_back_to_local() {
+    let board = Board::from_fen(FEN, false).unwrap();
+    let opts = exact_options();
+    // Bind and drop to get a port nobody listens on
+    let dead = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    assert!(!ping(&dead, &ClusterLink::default()));
+    let run = cluster_search(&board, &opts, &[spawn_worker(), dead.clone()], &ClusterLink::default()).unwrap();
+    let local = opts.build_searcher().unwrap().search_with_params(&board, opts.search_params());
+    assert_eq!(run.result.score.value(), local.score.value());
+    let lost = run.shares.iter().find(|s| s.worker == dead).unwrap();
+    assert!(lost.failure.is_some() && lost.nodes > 0);
+}
+
+#[test]
+fn jobs_report_bad_input() {
+    let bad = |fen: &str, mv: &str, spec: &str| run_job(&ClusterJob { fen: fen.into(), moves: vec![mv.into()], spec: spec.into(), secret: None }).error;
+    let start = format!("{}", Board::default());
+    assert!(bad("not a fen", "e2e4", "depth=1").is_some());
+    assert!(bad(&start, "e2e5", "depth=1").unwrap().contains("e2e5"));
+    assert!(bad(&start, "e2e4", "bogus=1").is_some());
+    let ok = run_job(&ClusterJob { fen: start, moves: vec!["e2e4".into()], spec: "depth=2".into(), secret: None });
+    assert_eq!((ok.error, ok.bestmove.as_deref()), (None, Some("e2e4")));
+}
+
+#[test]
+fn workers_with_a_secret_refuse_other_masters() {
+    let worker = spawn_worker_with(Some("s3cret"));
+    let link = |secret: Option<&str>| ClusterLink { secret: secret.map(String::from), ..ClusterLink::default() };
+    assert!(ping(&worker, &link(Some("s3cret"))));
+    assert!(!ping(&worker, &link(None)) && !ping(&worker, &link(Some("guess"))));
+    let board = Board::from_fen(FEN, false).unwrap();
+    let run = cluster_search(&board, &exact_options(), &[worker], &link(Some("wrong"))).unwrap();
+    assert!(run.shares[0].failure.as_deref().unwrap().contains("bad secret"));
+    assert!(run.result.bestmove.is_some());
+}
+
+#[test]
+fn silent_worker_times_out_to_local() {
+    let board = Board::from_fen(FEN, false).unwrap();
+    let opts = exact_options();
+    // Connections are accepted by the OS but never answered
+    let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = silent.local_addr().unwrap().to_string();
+    let link = ClusterLink { timeout: std::time::Duration::from_millis(300), ..ClusterLink::default() };
+    let run = cluster_search(&board, &opts, &[addr], &link).unwrap();
+    assert!(run.shares[0].failure.as_deref().unwrap().contains("no reply"), "{:?}", run.shares[0].failure);
+    let local = opts.build_searcher().unwrap().search_with_params(&board, opts.search_params());
+    assert_eq!(run.result.score.value(), local.score.value());
+    drop(silent);
+}
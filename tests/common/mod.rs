@@ -0,0 +1,50 @@
+// Fixtures shared by the integration tests; each test binary uses only some of them
+#![allow(dead_code)]
+use piebot::eval::nnue::features::halfkp_dim;
+use piebot::eval::nnue::loader::{QuantMeta, QuantNnue};
+
+/// Version-1 quantized network (unit scales, zero `b1`) from its weights.
+pub fn quant_net(input_dim: usize, hidden_dim: usize, w1: Vec<i8>, w2: Vec<i8>, b2: i16) -> QuantNnue {
+    QuantNnue { meta: QuantMeta { version: 1, input_dim, hidden_dim, output_dim: 1, output_scale: 1.0 }, w1_scale: 1.0, w2_scale: 1.0, w1, b1: vec![0; hidden_dim], w2, b2: vec![b2] }
+}
+
+/// Quantized network with zero weights: every position scores `b2`, so the sign
+/// of a score shows which network evaluated it.
+pub fn bias_model(b2: i16) -> QuantNnue {
+    let (input_dim, hidden_dim) = (halfkp_dim(), 8usize);
+    quant_net(input_dim, hidden_dim, vec![0; hidden_dim * input_dim], vec![0; hidden_dim], b2)
+}
+
+/// HalfKP network with xorshift weights in [-10, 10] from `seed`: scores swing from
+/// ply to ply without the cost of a real net in debug builds.
+pub fn random_net(hidden_dim: usize, seed: u64) -> QuantNnue {
+    let input_dim = halfkp_dim();
+    let mut x = seed;
+    let mut rnd = || { x ^= x << 13; x ^= x >> 7; x ^= x << 17; ((x >> 32) % 21) as i8 - 10 };
+    let w1 = (0..hidden_dim * input_dim).map(|_| rnd()).collect();
+    let w2 = (0..hidden_dim).map(|_| rnd()).collect();
+    quant_net(input_dim, hidden_dim, w1, w2, 0)
+}
+
+/// Write a PIENNQ01 file with LCG weights in [-4, 4] from `seed` and zero biases.
+pub fn write_quant_file(path: &str, input_dim: usize, hidden_dim: usize, seed: u64) {
+    let mut seed = seed;
+    let mut next = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1); ((seed >> 32) as i32 % 9 - 4) as i8 };
+    let w1 = (0..input_dim * hidden_dim).map(|_| next()).collect();
+    let w2 = (0..hidden_dim).map(|_| next()).collect();
+    quant_net(input_dim, hidden_dim, w1, w2, 0).save_quantized(path).unwrap();
+}
+
+/// Clonable in-memory sink, so a test can read what the engine wrote.
+#[derive(Clone, Default)]
+pub struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().extend_from_slice(buf); Ok(buf.len()) }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+impl Captured {
+    pub fn text(&self) -> String { String::from_utf8(self.0.lock().unwrap().clone()).unwrap() }
+    pub fn lines(&self) -> Vec<String> { self.text().lines().map(str::to_string).collect() }
+}
@@ -0,0 +1,107 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::contempt::{Contempt, DYNAMIC_MAX_CP};
+use piebot::search::options::EngineOptions;
+
+// White (knight and pawn against a rook) is slightly worse; with the clock at 99
+// every quiet move is a fifty-move draw, while a pawn push keeps the game going.
+const FIFTY: &str = "4k2r/8/8/8/8/8/P7/4KN2 w - - 99 80";
+
+fn best(contempt: Contempt) -> String {
+    let b = Board::from_fen(FIFTY, false).unwrap();
+    let mut s = Searcher::default();
+    s.search_with_params(&b, SearchParams { depth: 3, use_tt: true, contempt, ..Default::default() }).bestmove.unwrap()
+}
+
+#[test]
+fn contempt_decides_whether_to_take_a_draw() {
+    // Without contempt the draw beats a worse position
+    assert!(!best(Contempt::default()).starts_with("a2"));
+    // A draw that costs more than the deficit is avoided
+    let pushed = best(Contempt { static_cp: 200, dynamic: false });
+    assert!(pushed.starts_with("a2"), "{}", pushed);
+}
+
+#[test]
+fn dynamic_contempt_follows_score_and_phase() {
+    let fixed = Contempt { static_cp: 10, dynamic: false };
+    assert_eq!(fixed.bias(500, 24).draw_cp, 10);
+    let dynamic = Contempt { dynamic: true, ..fixed };
+    let ahead = dynamic.bias(300, 24);
+    assert!(ahead.draw_cp > 10 && ahead.draw_cp <= 10 + DYNAMIC_MAX_CP && ahead.complexity_cp == 0);
+    // With the pieces off a draw matters less
+    assert!(dynamic.bias(300, 0).draw_cp < ahead.draw_cp);
+    let behind = dynamic.bias(-300, 24);
+    assert_eq!(behind.draw_cp, 10);
+    assert!(behind.complexity_cp > 0);
+    // The bonus favours keeping material, for the engine's side only
+    assert!(behind.complexity(true, || 24) > behind.complexity(true, || 6));
+    assert_eq!(behind.complexity(false, || 24), -behind.complexity(true, || 24));
+    assert_eq!(dynamic.bias(0, 24), fixed.bias(0, 24));
+}
+
+#[test]
+fn contempt_options_round_trip() {
+    let mut o = EngineOptions::default();
+    o.apply_spec("contempt=25,dynamic_contempt=on").unwrap();
+    assert_eq!(o.search_params().contempt, Contempt { static_cp: 25, dynamic: true });
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    // UCI spelling
+    o.set("DynamicContempt", "false").unwrap();
+    assert!(!o.dynamic_contempt);
+    assert!(o.uci_option_lines().iter().any(|l| l.starts_with("option name Contempt type spin")));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_contempt_decides_whether_to_take_a_draw() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let run = |c: Contempt| {
+        let mut s = PlecoSearcher::default();
+        s.set_threads(1);
+        s.set_contempt(c);
+        let mut b = pleco::Board::from_fen(FIFTY).unwrap();
+        format!("{}", s.search_movetime(&mut b, 60_000, 3).0.unwrap())
+    };
+    assert!(!run(Contempt::default()).starts_with("a2"));
+    assert!(run(Contempt { static_cp: 200, dynamic: false }).starts_with("a2"));
+}
+
+// Black, a queen down, can step back into the game's starting position
+const REPEAT: &str = "fen 4k3/8/8/8/8/8/8/Q3K3 w - - 0 1 moves e1d1 e8d8 d1e1";
+
+#[test]
+fn repetitions_of_the_game_history_are_draws() {
+    use piebot::board::cozy::Position;
+    let pos = Position::from_uci_position(REPEAT).unwrap();
+    assert_eq!(pos.history().len(), 3);
+    let back = pos.find_move_uci("d8e8").unwrap();
+    let score = |history: &[u64]| {
+        let mut s = Searcher::default();
+        s.set_game_history(history.to_vec());
+        s.set_search_moves(Some(vec![back]));
+        s.search_with_params(pos.board(), SearchParams { depth: 3, use_tt: true, ..Default::default() }).score.value()
+    };
+    assert_eq!(score(pos.history()), 0);
+    assert!(score(&[]) < -500);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_repetitions_of_the_game_history_are_draws() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let (board, history) = piebot::board::pleco::parse_uci_position_history(REPEAT).unwrap();
+    assert_eq!(history.len(), 3);
+    let back = board.generate_moves().iter().copied().find(|m| m.stringify() == "d8e8").unwrap();
+    let score = |history: &[u64]| {
+        let mut s = PlecoSearcher::default();
+        s.set_tt_capacity_mb(16);
+        s.set_game_history(history.to_vec());
+        s.set_search_moves(Some(vec![back]));
+        s.search_movetime(&mut board.clone(), 60_000, 3).1
+    };
+    assert_eq!(score(&history), 0);
+    assert!(score(&[]) < -500);
+}
@@ -0,0 +1,136 @@
+use cozy_chess::{Board, Color, GameStatus, Move};
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::contempt::Contempt;
+use piebot::search::conversion::{bonus, corner_bonus, eval, tb_win, tb_winner};
+use piebot::search::eval::MaterialSig;
+use piebot::search::options::EngineOptions;
+use std::collections::HashSet;
+
+const KRK: [&str; 3] = [
+    "8/8/8/4k3/8/8/8/R3K3 w - - 0 1",
+    "8/8/3k4/8/8/8/8/1R2K3 b - - 0 1",
+    "8/8/8/3k4/8/2K5/8/7R w - - 0 1",
+];
+
+fn sig(fen: &str) -> MaterialSig { MaterialSig::from_board(&Board::from_fen(fen, false).unwrap()) }
+
+fn params(depth: u32, conversion: bool) -> SearchParams {
+    SearchParams { depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, conversion, ..Default::default() }
+}
+
+#[test]
+fn tb_wins_are_recognised_from_material() {
+    assert_eq!(tb_win(&sig(KRK[0])), Some(0));
+    assert_eq!(tb_win(&sig("8/8/8/4k3/8/8/8/r3K3 w - - 0 1")), Some(1));
+    assert_eq!(tb_win(&sig("8/8/8/3k4/8/8/8/2BNK3 w - - 0 1")), Some(0));
+    assert_eq!(tb_win(&sig("7k/8/8/8/3K4/8/6P1/3Q4 w - - 0 1")), Some(0));
+    // The defender has something left, or the material cannot force mate
+    assert_eq!(tb_win(&sig("8/7p/8/4k3/8/8/8/R3K3 w - - 0 1")), None);
+    assert_eq!(tb_win(&sig("8/7r/8/4k3/8/8/8/R3K3 w - - 0 1")), None);
+    assert_eq!(tb_win(&sig("8/8/8/4k3/8/8/8/1N2K1N1 w - - 0 1")), None);
+    assert_eq!(tb_winner(&Board::default()), None);
+}
+
+#[test]
+fn bonus_drives_the_king_to_the_edge() {
+    // a1 = 0, d4 = 27, e5 = 36, h8 = 63
+    assert!(bonus(27, 63, 0) > bonus(27, 36, 0), "cornered beats central");
+    assert!(bonus(45, 63, 0) > bonus(27, 63, 0), "closer kings beat distant ones");
+    assert!(bonus(45, 63, 0) > bonus(45, 63, 40), "a fresh fifty-move count beats an old one");
+}
+
+// K+B+N v K with the defender in a1 or a8, the winning king equally far from both
+fn kbnk(bishop: &str, corner: &str) -> String {
+    let back = if corner == "a8" { "k7" } else { "8" };
+    let first = if corner == "a1" { "k" } else { "1" };
+    let rank1 = if bishop == "c1" { format!("{}1B1N3", first) } else { format!("{}2BN3", first) };
+    format!("{}/8/8/8/4K3/8/8/{} w - - 0 1", back, rank1)
+}
+
+#[test]
+fn kbnk_drives_the_king_to_a_corner_of_the_bishops_colour() {
+    // a1 = 0 and h8 = 63 are dark, a8 = 56 and h1 = 7 light
+    assert!(corner_bonus(0, false) > corner_bonus(56, false));
+    assert!(corner_bonus(63, false) > corner_bonus(7, false));
+    assert!(corner_bonus(56, true) > corner_bonus(0, true));
+    assert_eq!(corner_bonus(0, false), corner_bonus(7, true));
+    let score = |fen: &str| eval(&Board::from_fen(fen, false).unwrap(), Color::White);
+    // c1 is a dark square, d1 a light one
+    assert!(score(&kbnk("c1", "a1")) > score(&kbnk("c1", "a8")));
+    assert!(score(&kbnk("d1", "a8")) > score(&kbnk("d1", "a1")));
+    // Bishop and knight alongside a rook mate anywhere: no corner preference
+    let with_rook = |corner: &str| score(&kbnk("c1", corner).replacen("4K3", "4KR2", 1));
+    assert_eq!(with_rook("a1"), with_rook("a8"));
+}
+
+#[test]
+fn krk_is_mated_within_the_fifty_move_rule() {
+    for fen in KRK {
+        let mut b = Board::from_fen(fen, false).unwrap();
+        let mut s = Searcher::default();
+        // The searcher sees no game history, so keep the winner off repeated positions
+        let mut seen = HashSet::new();
+        for _ in 0..100 {
+            if b.status() != GameStatus::Ongoing { break; }
+            seen.insert(b.hash());
+            let mut fresh = Vec::new();
+            b.generate_moves(|ml| {
+                fresh.extend(ml.into_iter().filter(|&m| { let mut c = b.clone(); c.play(m); !seen.contains(&c.hash()) }));
+                false
+            });
+            s.set_search_moves((b.side_to_move() == Color::White && !fresh.is_empty()).then_some(fresh));
+            let r = s.search_with_params(&b, params(6, true));
+            b.play(r.bestmove.unwrap().parse::<Move>().unwrap());
+        }
+        assert_eq!(b.status(), GameStatus::Won, "{}: not mated, ended at {}", fen, b);
+    }
+}
+
+#[test]
+fn conversion_ignores_contempt_and_pruning_toggles() {
+    let b = Board::from_fen(KRK[2], false).unwrap();
+    let run = |p: SearchParams| { let r = Searcher::default().search_with_params(&b, p); (r.bestmove, r.score, r.nodes) };
+    let base = run(params(5, true));
+    let contempt = Contempt { static_cp: 150, dynamic: true };
+    assert_eq!(run(SearchParams { contempt, ..params(5, true) }), base);
+    assert_eq!(run(SearchParams { use_nullmove: false, use_lmr: false, ..params(5, true) }), base);
+}
+
+#[test]
+fn conversion_option() {
+    let mut o = EngineOptions::default();
+    assert!(o.conversion && o.search_params().conversion);
+    o.set("Conversion", "false").unwrap();
+    assert!(!o.search_params().conversion);
+    assert!(o.describe().contains("conversion=off"));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_converts_the_same_way() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let run = |contempt: Contempt| {
+        let mut s = PlecoSearcher::default();
+        s.set_conversion(true);
+        s.set_contempt(contempt);
+        s.set_tt_capacity_mb(16);
+        let mut b = pleco::Board::from_fen("7k/8/8/8/3K4/8/8/3Q4 w - - 0 1").unwrap();
+        let (bm, score, nodes) = s.search_movetime(&mut b, 60_000, 5);
+        (bm.map(|m| m.stringify()), score, nodes)
+    };
+    let base = run(Contempt::default());
+    assert!(base.0.is_some());
+    assert_eq!(run(Contempt { static_cp: 150, dynamic: true }), base);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_kbnk_eval_matches_cozy() {
+    use piebot::search::conversion::eval_pleco;
+    use piebot::search::eval::material_eval_cp;
+    for fen in [kbnk("c1", "a1"), kbnk("c1", "a8"), kbnk("d1", "a1"), kbnk("d1", "a8")] {
+        let cozy = Board::from_fen(&fen, false).unwrap();
+        let b = pleco::Board::from_fen(&fen).unwrap();
+        assert_eq!(eval_pleco(&b, pleco::Player::White, material_eval_cp(&cozy)), eval(&cozy, Color::White), "{}", fen);
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher, DETERMINISTIC_NODES_PER_MS};
+use piebot::search::options::EngineOptions;
+use piebot::search::timeman::{ClockPlan, TimeBudget, TimeConfig};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const MIDDLEGAME: &str = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R1BQKB1R w KQ - 0 9";
+
+#[test]
+fn time_limits_become_node_budgets() {
+    let p = SearchParams { threads: 4, movetime: Some(Duration::from_millis(20)), ..SearchParams::default() }.deterministic_limits();
+    assert_eq!((p.threads, p.movetime, p.max_nodes), (1, None, Some(20 * DETERMINISTIC_NODES_PER_MS)));
+    let p = SearchParams { movetime: Some(Duration::from_millis(20)), max_nodes: Some(1000), ..SearchParams::default() }.deterministic_limits();
+    assert_eq!(p.max_nodes, Some(1000), "a smaller node limit stays");
+    // With a clock the soft budget counts, and the hard limit stays as the abort deadline
+    let budget = TimeBudget { soft: Duration::from_millis(10), hard: Duration::from_millis(50) };
+    let p = SearchParams { movetime: Some(budget.hard), clock: Some(ClockPlan { budget, config: TimeConfig::default() }), ..SearchParams::default() }.deterministic_limits();
+    assert_eq!((p.max_nodes, p.clock.is_none(), p.movetime), (Some(10 * DETERMINISTIC_NODES_PER_MS), true, Some(budget.hard)));
+    assert_eq!(SearchParams::default().deterministic_limits().max_nodes, None);
+}
+
+#[test]
+fn repeated_searches_match_exactly() {
+    let board = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let o = EngineOptions { deterministic: true, threads: 4, depth: 0, movetime_ms: Some(40), ..EngineOptions::default() };
+    assert_eq!(o.search_params().threads, 1);
+    let run = || {
+        let mut s = o.build_searcher().unwrap();
+        let r = s.search_with_params(&board, o.search_params());
+        (r.bestmove, r.nodes, r.depth, r.score.value())
+    };
+    let first = run();
+    // The limit is checked as nodes are entered, so the count can pass it by a few
+    assert!(first.1 <= 40 * DETERMINISTIC_NODES_PER_MS + 16, "{:?}", first);
+    assert_eq!(run(), first);
+    // A default searcher configured from the same options agrees too
+    let mut s = Searcher::default();
+    o.configure(&mut s).unwrap();
+    let r = s.search_with_params(&board, o.search_params());
+    assert_eq!(r.nodes, first.1);
+}
+
+#[test]
+fn clock_play_stops_at_the_hard_limit_when_the_node_budget_is_too_big() {
+    let board = Board::from_fen(MIDDLEGAME, false).unwrap();
+    // A soft budget worth millions of nodes, but only 50ms before the flag falls
+    let budget = TimeBudget { soft: Duration::from_secs(60), hard: Duration::from_millis(50) };
+    let p = SearchParams { depth: 0, use_tt: true, order_captures: true, deterministic: true, movetime: Some(budget.hard), clock: Some(ClockPlan { budget, config: TimeConfig::default() }), ..SearchParams::default() };
+    let t0 = std::time::Instant::now();
+    let r = Searcher::default().search_with_params(&board, p);
+    assert!(r.bestmove.is_some());
+    assert!(t0.elapsed() < Duration::from_millis(1000), "took {:?}", t0.elapsed());
+}
+
+// Final `info` nodes and the bestmove of one `go` in a fresh engine process. The depth
+// limit keeps even a debug build well inside the clock's hard limit.
+fn uci_search() -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uci")).arg("--no-calibrate").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for cmd in ["setoption name Backend value cozy", "setoption name Deterministic value true", "setoption name Threads value 4", "position startpos moves e2e4 c7c5", "go wtime 10000 btime 10000 winc 20 binc 20 depth 3"] {
+        writeln!(stdin, "{}", cmd).unwrap();
+    }
+    stdin.flush().unwrap();
+    let (mut nodes, mut best) = (String::new(), String::new());
+    for line in BufReader::new(child.stdout.take().unwrap()).lines().map_while(Result::ok) {
+        if let Some(n) = line.split_whitespace().skip_while(|&w| w != "nodes").nth(1) { nodes = n.to_string(); }
+        if line.starts_with("bestmove") { best = line; break; }
+    }
+    writeln!(stdin, "quit").unwrap();
+    child.wait().unwrap();
+    (nodes, best)
+}
+
+#[test]
+fn uci_deterministic_option_reproduces_a_clocked_search() {
+    let first = uci_search();
+    assert!(first.1.starts_with("bestmove ") && !first.0.is_empty());
+    assert_eq!(uci_search(), first);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_backend_rejects_the_deterministic_option() {
+    let out = common::Captured::default();
+    piebot::uci::UciEngine::new().run_loop("setoption name Backend value pleco\nsetoption name Deterministic value true\nsetoption name Deterministic value false\nquit\n".as_bytes(), out.clone());
+    let text = out.text();
+    assert_eq!(text.lines().collect::<Vec<_>>(), ["info string option Deterministic: not supported by the pleco backend; use Backend cozy"], "{}", text);
+}
@@ -0,0 +1,44 @@
+use piebot::io::engine_log::EngineLog;
+use piebot::uci::UciEngine;
+use std::path::PathBuf;
+
+fn logs(dir: &PathBuf) -> Vec<String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+    files.sort_by_key(|p| p.file_name().unwrap().to_string_lossy().rsplit('_').next().unwrap().to_string());
+    files.iter().map(|p| std::fs::read_to_string(p).unwrap()).collect()
+}
+
+#[test]
+fn lines_are_tagged_and_each_game_gets_a_file() {
+    let dir = std::env::temp_dir().join(format!("piebot_engine_log_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut log = EngineLog::new(&dir).unwrap();
+    log.input("go depth 1");
+    log.output("bestmove e2e4");
+    log.new_game();
+    log.warning("something odd");
+    let files = logs(&dir);
+    assert_eq!(files.len(), 2);
+    let first: Vec<&str> = files[0].lines().map(|l| l.trim_start().split_once(' ').unwrap().1).collect();
+    assert_eq!(first, [">> go depth 1", "<< bestmove e2e4"]);
+    assert!(files[1].ends_with("!! something odd\n"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn uci_log_dir_records_commands_and_output_per_game() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let dir = std::env::temp_dir().join(format!("piebot_uci_log_{}_{}", backend, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let input = format!("setoption name Backend value {}\nsetoption name LogDir value {}\nposition startpos moves e2e4\ngo depth 2\nucinewgame\nposition startpos\ngo depth 1\nquit\n", backend, dir.display());
+        UciEngine::new().run_loop(input.as_bytes(), std::io::sink());
+        let files = logs(&dir);
+        assert_eq!(files.len(), 2, "{}", backend);
+        assert!(files[0].contains(">> position startpos moves e2e4\n") && files[0].contains(">> go depth 2\n"));
+        assert!(files[0].contains("<< bestmove "));
+        assert!(files[1].contains(">> ucinewgame\n") && files[1].contains(">> go depth 1\n") && files[1].contains("<< bestmove "));
+        assert!(!files[1].contains("go depth 2"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
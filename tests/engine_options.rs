@@ -0,0 +1,101 @@
+use piebot::search::alphabeta::SearchParams;
+use piebot::search::options::{EngineOptions, EvalKind, SearchStyle};
+
+#[test]
+fn describe_round_trips_through_apply_spec() {
+    let mut o = EngineOptions::default();
+    o.apply_spec("lmr=off,hash=128,extensions=0,eval=material,movetime=250,window=45").unwrap();
+    assert!(!o.use_lmr);
+    assert_eq!((o.hash_mb, o.max_extensions, o.eval, o.movetime_ms, o.aspiration_window_cp), (128, 0, EvalKind::Material, Some(250), 45));
+    let mut back = EngineOptions { use_lmr: true, hash_mb: 1, ..EngineOptions::default() };
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+}
+
+#[test]
+fn bad_specs_are_rejected() {
+    let mut o = EngineOptions::default();
+    assert!(o.apply_spec("lmr").is_err());
+    assert!(o.apply_spec("lmr=maybe").is_err());
+    assert!(o.apply_spec("eval=magic").is_err());
+    assert!(o.apply_spec("no_such_option=1").is_err());
+    assert_eq!(o, EngineOptions::default());
+}
+
+#[test]
+fn partial_json_keeps_defaults_and_feeds_search_params() {
+    let o: EngineOptions = serde_json::from_str(r#"{"use_nullmove": false, "threads": 3, "eval": "material"}"#).unwrap();
+    assert_eq!(o, EngineOptions { use_nullmove: false, threads: 3, eval: EvalKind::Material, ..EngineOptions::default() });
+    let p = o.search_params();
+    assert!(!p.use_nullmove && p.use_lmr && p.use_tt);
+    assert_eq!((p.threads, p.depth, p.max_extensions), (3, o.depth, o.max_extensions));
+}
+
+#[test]
+fn global_defaults_reach_new_searchers() {
+    EngineOptions::set_global(EngineOptions { eval: EvalKind::Material, depth: 3, ..EngineOptions::default() });
+    let o = EngineOptions::global();
+    assert_eq!((o.eval, o.depth), (EvalKind::Material, 3));
+    let mut s = o.build_searcher().unwrap();
+    let res = s.search_with_params(&cozy_chess::Board::default(), o.search_params());
+    assert_eq!(res.depth, 3);
+    // A network is required once the eval asks for NNUE
+    assert!(EngineOptions { eval: EvalKind::Nnue, ..o }.build_searcher().is_err());
+}
+
+#[test]
+fn analysis_style_spares_the_pv_and_game_style_prunes_harder() {
+    let mut o = EngineOptions::default();
+    assert_eq!(o.style, SearchStyle::Game);
+    assert_eq!(o.search_params().lmr_late_extra, 0, "no extra reduction unless asked for");
+    o.set("LMRLateExtra", "1").unwrap();
+    assert!(o.describe().contains("lmr_late_extra=1"));
+    let game = o.search_params();
+    assert!(!game.protect_pv && game.lmr_late_extra == 1);
+    o.set("UCI_AnalyseMode", "true").unwrap();
+    assert_eq!(o.style, SearchStyle::Analysis);
+    let analysis = o.search_params();
+    assert!(analysis.protect_pv && analysis.lmr_late_extra == 0);
+    assert!(o.time_config().hard_factor <= EngineOptions::default().time_config().hard_factor);
+    o.apply_spec("style=game").unwrap();
+    assert_eq!(o.style, SearchStyle::Game);
+
+    // Sparing the PV costs nodes at the same depth
+    let board = cozy_chess::Board::from_fen("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w KQ - 0 8", false).unwrap();
+    let mut s = o.build_searcher().unwrap();
+    let g = s.search_with_params(&board, SearchParams { depth: 5, ..game });
+    s.new_game();
+    let a = s.search_with_params(&board, SearchParams { depth: 5, ..analysis });
+    assert!(g.bestmove.is_some() && a.bestmove.is_some());
+    assert!(a.nodes > g.nodes, "analysis {} nodes vs game {}", a.nodes, g.nodes);
+}
+
+#[test]
+fn split_options_reach_search_params() {
+    use piebot::search::alphabeta::{SplitConfig, SplitPolicy};
+    let mut o = EngineOptions::default();
+    assert_eq!(o.search_params().split, SplitConfig::default());
+    o.apply_spec("split=jamboree,split_depth=3,split_moves=12").unwrap();
+    assert_eq!(o.search_params().split, SplitConfig::JAMBOREE);
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    assert!(o.set("split", "dynamic").is_err());
+    let j: EngineOptions = serde_json::from_str(r#"{"split": "ybwc", "split_moves": 8}"#).unwrap();
+    assert_eq!((j.split, j.split_moves), (SplitPolicy::Ybwc, 8));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_reduces_late_moves_more_in_the_game_style() {
+    let fen = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w KQ - 0 8";
+    let nodes = |spec: &str| {
+        let mut o = EngineOptions::default();
+        o.apply_spec(spec).unwrap();
+        let mut s = o.build_pleco_searcher().unwrap();
+        s.search_movetime(&mut pleco::Board::from_fen(fen).unwrap(), 0, 6).2
+    };
+    let plain = nodes("threads=1");
+    assert!(nodes("threads=1,lmr_late_extra=2") < plain);
+    assert_eq!(nodes("threads=1,lmr_late_extra=2,style=analysis"), plain);
+}
@@ -0,0 +1,57 @@
+use piebot::io::epd::{load_epd, parse_epd};
+use piebot::search::options::EngineOptions;
+use piebot::search::score::{Score, MATE_SCORE};
+use piebot::search::suite::{mate_histogram, run_case, MateBucket};
+use piebot::TitanError;
+
+const MATES: &str = "tests/data/mates.epd";
+
+#[test]
+fn epd_operations_parse() {
+    let c = parse_epd(r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - id "back; rank"; bm Ra8# a1a8; am Kf1; dm 1; c0 "ignored";"#, 7).unwrap();
+    assert_eq!(c.id, "back; rank");
+    assert_eq!(c.best.len(), 2);
+    assert_eq!(format!("{}", c.avoid[0]), "g1f1");
+    assert_eq!((c.mate, c.mate_plies()), (Some(1), Some(1)));
+    assert_eq!(parse_epd("6k1/8/6K1/8/8/8/8/R7 w - - dm 3;", 4).unwrap().id, "#4");
+    assert!(parse_epd("6k1/8/6K1/8/8/8/8/R7 w - - dm 0;", 1).is_err());
+    assert!(parse_epd("6k1/8/6K1/8/8/8/8/R7 w - - bm Qh7;", 1).unwrap_err().contains("Qh7"));
+}
+
+#[test]
+fn bad_epd_lines_name_the_line() {
+    let path = std::env::temp_dir().join(format!("piebot_epd_{}.epd", std::process::id()));
+    std::fs::write(&path, "# suite\n6k1/8/6K1/8/8/8/8/R7 w - - dm 1;\n6k1/8/6K1/8/8/8/8/R7 w - - bm Ra9;\n").unwrap();
+    match load_epd(&path) {
+        Err(TitanError::Epd { line, .. }) => assert_eq!(line, 3),
+        other => panic!("expected an EPD error, got {:?}", other.map(|c| c.len())),
+    }
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn mate_scores_convert_to_moves() {
+    assert_eq!(Score::from_value(MATE_SCORE - 1).mate_moves(), Some(1));
+    assert_eq!(Score::from_value(MATE_SCORE - 5).mate_moves(), Some(3));
+    assert_eq!(Score::from_value(-MATE_SCORE + 4).mate_moves(), Some(-2));
+    assert_eq!(Score::cp(350).mate_moves(), None);
+}
+
+#[test]
+fn mate_suite_reports_exact_distances() {
+    let cases = load_epd(MATES).unwrap();
+    let opts = EngineOptions::default();
+    let mut s = opts.build_searcher().unwrap();
+    let run = |s: &mut piebot::search::alphabeta::Searcher, margin, depth| {
+        let p = piebot::search::alphabeta::SearchParams { depth, ..opts.search_params() };
+        cases.iter().map(|c| { s.new_game(); run_case(s, c, p, margin) }).collect::<Vec<_>>()
+    };
+    let deep = run(&mut s, Some(3), 4);
+    assert!(deep.iter().all(|r| r.solved()), "{:?}", deep);
+    let h = mate_histogram(&deep);
+    assert_eq!(h[&1], MateBucket { total: 2, solved: 2, wrong_distance: 0, missed: 0 });
+    assert_eq!(h[&3], MateBucket { total: 2, solved: 2, wrong_distance: 0, missed: 0 });
+    // Too shallow for the mates in three
+    let shallow = mate_histogram(&run(&mut s, None, 3));
+    assert_eq!((shallow[&1].solved, shallow[&3].missed), (2, 2));
+}
@@ -0,0 +1,63 @@
+use cozy_chess::Color;
+use piebot::board::cozy::Position;
+use piebot::eval::nnue::Nnue;
+use piebot::io::fen::{load_openings, read_openings};
+use piebot::search::options::EngineOptions;
+use piebot::TitanError;
+
+const FEN: &str = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+
+#[test]
+fn position_command_applies_moves_after_fen() {
+    let p = Position::from_uci_position(&format!("fen {} moves e2e4 e8d7", FEN)).unwrap();
+    assert_eq!(p.side_to_move(), Color::White);
+    assert!(format!("{}", p.board()).starts_with("8/3k4/8/8/4P3/8/8/4K3"));
+    // Four-field FENs are accepted too
+    assert!(Position::from_uci_position("fen 4k3/8/8/8/8/8/4P3/4K3 w - - moves e2e3").is_ok());
+}
+
+#[test]
+fn position_command_errors_name_the_problem() {
+    assert!(matches!(Position::from_uci_position("fen 4k3/8/8 w - - 0 1"), Err(TitanError::Fen { .. })));
+    match Position::from_uci_position("startpos moves e2e4 e2e4") {
+        Err(TitanError::IllegalMove { mv, .. }) => assert_eq!(mv, "e2e4"),
+        other => panic!("expected illegal move, got {:?}", other),
+    }
+    assert!(matches!(Position::from_uci_position("startpos e2e4"), Err(TitanError::Position(_))));
+    assert!(matches!(Position::from_uci_position("nonsense"), Err(TitanError::Position(_))));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_position_command_matches_cozy() {
+    let args = format!("fen {} moves e2e4 e8d7", FEN);
+    let pleco = piebot::board::pleco::parse_uci_position(&args).unwrap();
+    assert_eq!(pleco.fen().split(' ').next(), format!("{}", Position::from_uci_position(&args).unwrap().board()).split(' ').next());
+    assert!(matches!(piebot::board::pleco::parse_uci_position("startpos moves e2e5"), Err(TitanError::IllegalMove { .. })));
+}
+
+#[test]
+fn openings_report_the_bad_line() {
+    let dir = std::env::temp_dir().join(format!("piebot_errors_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("openings.epd");
+    std::fs::write(&path, format!("# comment\n{}\n4k3/8/8/8/8/8/4P3/4K3 b - -\nnot a fen at all\n", FEN)).unwrap();
+    let (boards, bad) = read_openings(&path).unwrap();
+    assert_eq!(boards.len(), 2, "the good lines survive a bad one");
+    assert!(matches!(bad[..], [TitanError::Openings { line: 4, .. }]), "{:?}", bad);
+    assert_eq!(load_openings(&path).unwrap().len(), 2);
+    std::fs::write(&path, format!("{}\n4k3/8/8/8/8/8/4P3/4K3 b - - c0 \"epd op\";\n", FEN)).unwrap();
+    assert_eq!(load_openings(&path).unwrap().len(), 2);
+    assert!(matches!(load_openings(dir.join("missing.epd")), Err(TitanError::Io { .. })));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn nnue_and_option_errors_are_typed() {
+    assert!(matches!(Nnue::load("/nonexistent/net.nnue"), Err(TitanError::Io { .. })));
+    assert!(matches!(Nnue::from_reader(&b"NOTANNUE"[..]), Err(TitanError::Nnue(_))));
+    let mut o = EngineOptions::default();
+    assert!(matches!(o.set("no_such_option", "1"), Err(TitanError::UnknownOption(_))));
+    let e = o.set("hash", "lots").unwrap_err();
+    assert!(e.to_string().contains("hash"), "{}", e);
+}
@@ -1,15 +1,14 @@
+mod common;
+
+use common::bias_model;
 use cozy_chess::Board;
 
 #[test]
 fn eval_blend_quant_bias_only() {
-    use piebot::eval::nnue::features::halfkp_dim;
-    use piebot::eval::nnue::loader::{QuantNnue, QuantMeta};
     use piebot::search::alphabeta::Searcher;
 
-    // Quant model with constant 50 output: b2=50, others zero
-    let input_dim = halfkp_dim();
-    let hidden_dim = 8usize;
-    let model = QuantNnue { meta: QuantMeta { version: 1, input_dim, hidden_dim, output_dim: 1 }, w1_scale: 1.0, w2_scale: 1.0, w1: vec![0; hidden_dim * input_dim], b1: vec![0; hidden_dim], w2: vec![0; hidden_dim], b2: vec![50] };
+    // Quant model with constant 50 output
+    let model = bias_model(50);
 
     let b = Board::default();
     let pst = piebot::search::eval::eval_cp(&b);
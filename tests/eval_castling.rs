@@ -0,0 +1,35 @@
+// Unused castling rights are worth something early and nothing in the endgame.
+use cozy_chess::Board;
+use piebot::search::eval::{castling_rights_cp, eval_breakdown, eval_cp, CASTLING_RIGHT};
+
+fn board(fen: &str) -> Board { Board::from_fen(fen, false).unwrap() }
+
+#[test]
+fn early_king_walk_costs_both_rights() {
+    // 1.e4 e5 2.Ke2 against the same position with the king still at home
+    let home = board("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+    let walked = board("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 1 2");
+    assert_eq!(eval_breakdown(&home).castling, 0);
+    assert_eq!(eval_breakdown(&walked).castling, -2 * CASTLING_RIGHT);
+    // Black to move after Ke2, so a higher side-relative score is worse for White
+    assert!(-eval_cp(&walked) < eval_cp(&home), "Ke2 {} vs home {}", -eval_cp(&walked), eval_cp(&home));
+}
+
+#[test]
+fn castled_king_is_not_worse_than_keeping_the_rights() {
+    let uncastled = board("r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4");
+    let castled = board("r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4");
+    assert!(eval_breakdown(&castled).total >= eval_breakdown(&uncastled).total);
+}
+
+#[test]
+fn term_is_symmetric_and_fades_with_material() {
+    let b = board("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1");
+    assert_eq!(castling_rights_cp(&b, 24), 0);
+    let b = board("r3k2r/8/8/8/8/8/8/4K2R w Kkq - 0 1");
+    assert_eq!(castling_rights_cp(&b, 24), -CASTLING_RIGHT);
+    assert_eq!(castling_rights_cp(&b, 12), -CASTLING_RIGHT / 2);
+    assert_eq!(castling_rights_cp(&b, 0), 0);
+    // Rooks only: little phase left, so the term is small
+    assert!(eval_breakdown(&b).castling.abs() < CASTLING_RIGHT);
+}
@@ -0,0 +1,76 @@
+// Opening development: minors out, king castled, no second moves with the same minor.
+use cozy_chess::{Board, Color};
+use piebot::io::epd::epd_result;
+use piebot::search::eval::{development_cp, eval_breakdown, DevelopmentFeatures, DevelopmentWeights, DEVELOPMENT};
+use piebot::search::texel::{loss, tune_development, DevelopmentSample};
+
+fn board(fen: &str) -> Board { Board::from_fen(fen, false).unwrap() }
+
+#[test]
+fn features_count_developed_minors_castling_and_second_moves() {
+    let start = Board::default();
+    assert_eq!(DevelopmentFeatures::from_board(&start, Color::White), DevelopmentFeatures::default());
+    assert_eq!(eval_breakdown(&start).development, 0);
+
+    // Italian after O-O: Nf3, Bc4 out and castled; Black has Nc6, Bc5 out, not castled
+    let b = board("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4");
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::White), DevelopmentFeatures { developed_minors: 2, castled: 1, repeat_moves: 0 });
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::Black), DevelopmentFeatures { developed_minors: 2, castled: 0, repeat_moves: 0 });
+    assert_eq!(eval_breakdown(&b).development, DEVELOPMENT.castled);
+}
+
+#[test]
+fn a_knight_that_moved_twice_early_is_penalised() {
+    // 1.Nf3 Nf6 2.Ng5: g5 is two knight moves from g1
+    let early = board("rnbqkb1r/pppppppp/5n2/6N1/8/8/PPPPPPPP/RNBQKB1R b KQkq - 3 2");
+    let f = DevelopmentFeatures::from_board(&early, Color::White);
+    assert_eq!((f.developed_minors, f.repeat_moves), (1, 1));
+    assert_eq!(eval_breakdown(&early).development, DEVELOPMENT.repeat_move);
+
+    // The same placement after move 10 is no longer an opening tempo question
+    let late = board("rnbqkb1r/pppppppp/5n2/6N1/8/8/PPPPPPPP/RNBQKB1R b KQkq - 3 12");
+    assert_eq!(DevelopmentFeatures::from_board(&late, Color::White).repeat_moves, 0);
+
+    // A bishop off its home diagonals took two moves as well
+    let b = board("rnbqkbnr/pppp1ppp/8/4p3/2B1P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 2");
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::White).repeat_moves, 0, "Bc4 is one move from f1");
+    let b = board("rnbqkbnr/pppp1ppp/8/1B2p3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 2");
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::White).repeat_moves, 0, "Bb5 is one move from f1");
+    let b = board("rnbqkbnr/pppp1ppp/8/4p3/4P3/3B4/PPPP1PPP/RNBQK1NR b KQkq - 1 2");
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::White).repeat_moves, 0);
+    let b = board("rnbqkbnr/pppp1ppp/8/4p3/4P3/2B5/PPPP1PPP/RNBQK1NR b KQkq - 1 3");
+    assert_eq!(DevelopmentFeatures::from_board(&b, Color::White).repeat_moves, 1, "c3 is off both of f1's diagonals");
+}
+
+#[test]
+fn term_is_mirrored_linear_and_fades_with_material() {
+    let b = board("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4");
+    let mirrored = board("rnbq1rk1/pppp1ppp/5n2/2b1p3/2B1P3/2N5/PPPP1PPP/R1BQK1NR w KQ - 5 5");
+    assert_eq!(development_cp(&b, 24), -development_cp(&mirrored, 24));
+    assert_eq!(development_cp(&b, 12), development_cp(&b, 24) / 2);
+    assert_eq!(development_cp(&b, 0), 0);
+
+    let f = DevelopmentFeatures { developed_minors: 3, castled: 1, repeat_moves: 2 };
+    assert_eq!(f.score(&DevelopmentWeights { developed_minor: 1, castled: 10, repeat_move: 100 }), 213);
+}
+
+#[test]
+fn texel_samples_rebuild_the_eval_and_tuning_follows_the_results() {
+    let fens = [
+        "r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4",
+        "rnbqkb1r/pppppppp/5n2/6N1/8/8/PPPPPPPP/RNBQKB1R b KQkq - 3 2",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ];
+    for f in fens {
+        let b = board(f);
+        assert_eq!(DevelopmentSample::new(&b, 0).eval(&DEVELOPMENT), eval_breakdown(&b).total, "{}", f);
+    }
+    // White castled in the first position and won it every time: the castling weight
+    // rises and the loss falls
+    let line = format!("{} c0 \"1-0\";", &fens[0][..fens[0].len() - 4]);
+    assert_eq!(epd_result(&line), Some(1));
+    let samples = vec![DevelopmentSample::new(&board(fens[0]), epd_result(&line).unwrap()); 4];
+    let tuned = tune_development(&samples, DEVELOPMENT, 1.0);
+    assert!(tuned.castled > DEVELOPMENT.castled, "{:?}", tuned);
+    assert!(loss(&samples, &tuned, 1.0) < loss(&samples, &DEVELOPMENT, 1.0));
+}
@@ -0,0 +1,90 @@
+// NNUE eval hash: lock-free slots, and searches that score the same with it on.
+mod common;
+
+use common::random_net;
+use cozy_chess::Board;
+use piebot::eval::nnue::loader::QuantNnue;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::eval_hash::EvalHash;
+use piebot::search::options::EngineOptions;
+
+const FEN: &str = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N2N2/PP2BPPP/R1BQK2R w KQ - 0 8";
+
+// Small fixed pseudo-random network
+fn net() -> QuantNnue { random_net(4, 0x2545_F491_4F6C_DD1D) }
+
+#[test]
+fn slots_keep_the_latest_entry_and_check_the_key() {
+    let h = EvalHash::with_mb(1);
+    assert!(h.is_enabled());
+    assert_eq!(h.probe(0xDEAD_BEEF_0000_0001), None);
+    h.store(0xDEAD_BEEF_0000_0001, -37);
+    assert_eq!(h.probe(0xDEAD_BEEF_0000_0001), Some(-37));
+    // Same slot, other position: the newer entry wins and the old key misses
+    h.store(0xFEED_F00D_0000_0001, 12);
+    assert_eq!(h.probe(0xDEAD_BEEF_0000_0001), None);
+    assert_eq!(h.get_or_insert_with(0xFEED_F00D_0000_0001, || unreachable!()), 12);
+    // The counters are only kept with the search-stats feature
+    let st = h.stats();
+    assert_eq!((st.probes, st.hits), if cfg!(feature = "search-stats") { (4, 2) } else { (0, 0) });
+    h.clear();
+    assert_eq!((h.probe(0xFEED_F00D_0000_0001), h.stats().hits), (None, 0));
+    // 0 MB stores nothing
+    let off = EvalHash::with_mb(0);
+    off.store(1 << 40, 5);
+    assert!(!off.is_enabled() && off.probe(1 << 40).is_none() && off.stats().probes == 0);
+}
+
+fn params() -> SearchParams {
+    SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, threads: 1, ..Default::default() }
+}
+
+#[test]
+fn cozy_search_is_unchanged_and_reuses_network_outputs() {
+    let b = Board::from_fen(FEN, false).unwrap();
+    let run = |mb: usize| {
+        let mut s = Searcher::builder().hash_mb(4).eval_hash_mb(mb).use_nnue(true).nnue_quant(net()).build().unwrap();
+        let r = s.search_with_params(&b, params());
+        (r.bestmove, r.score, r.nodes, s.eval_hash_stats())
+    };
+    let (on, off) = (run(8), run(0));
+    assert_eq!((&on.0, on.1, on.2), (&off.0, off.1, off.2));
+    assert_eq!(off.3.probes, 0);
+    if cfg!(feature = "search-stats") { assert!(on.3.hits > 0 && on.3.hit_rate() > 0.05, "{:?}", on.3); }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_search_is_unchanged_and_shares_the_table_with_helpers() {
+    use piebot::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher, SmpMode};
+    let run = |mb: usize, threads: usize| {
+        let mut s = PlecoSearcher::default();
+        s.set_threads(threads);
+        s.set_smp_mode(if threads > 1 { SmpMode::LazyIndep } else { SmpMode::Off });
+        s.set_eval_hash_mb(mb);
+        s.set_nnue_quant_model(net());
+        s.set_eval_mode(PlecoEvalMode::Nnue);
+        let r = s.search_result(&mut pleco::Board::from_fen(FEN).unwrap(), 60_000, 5);
+        (r.bestmove, r.score, r.nodes, s.eval_hash_stats())
+    };
+    let (on, off) = (run(8, 1), run(0, 1));
+    assert_eq!((&on.0, on.1, on.2), (&off.0, off.1, off.2));
+    let lazy = run(8, 3);
+    if cfg!(feature = "search-stats") {
+        assert!(on.3.hits > 0, "{:?}", on.3);
+        assert!(lazy.3.hits > 0 && lazy.3.probes > on.3.probes, "{:?} vs {:?}", lazy.3, on.3);
+    }
+}
+
+#[test]
+fn eval_hash_option_reaches_both_searchers() {
+    let mut o = EngineOptions::default();
+    assert!(!o.describe().contains("eval_hash"));
+    o.apply_spec("eval_hash=0").unwrap();
+    assert!(o.describe().contains(",eval_hash=0"));
+    assert!(o.uci_option_lines().iter().any(|l| l.starts_with("option name EvalHash type spin default 0")));
+    o.apply_spec("eval_hash=2").unwrap();
+    let mut s = o.build_searcher().unwrap();
+    s.set_eval_hash_mb(2);
+    assert_eq!(s.eval_hash_stats().probes, 0);
+}
@@ -0,0 +1,19 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::Searcher;
+
+#[test]
+fn material_eval_ignores_piece_placement() {
+    // Same material, knight on the rim vs centralized: PST differs, material does not
+    let rim = Board::from_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1", false).unwrap();
+    let centre = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1", false).unwrap();
+    let mut s = Searcher::default();
+    assert_ne!(s.qsearch_eval_cp(&rim), s.qsearch_eval_cp(&centre));
+    s.set_material_eval(true);
+    assert_eq!(s.qsearch_eval_cp(&rim), s.qsearch_eval_cp(&centre));
+}
+
+#[test]
+fn bench_suite_is_valid_fen() {
+    use piebot::io::fen::BENCH_SUITE;
+    for fen in BENCH_SUITE { assert!(Board::from_fen(fen, false).is_ok(), "{}", fen); }
+}
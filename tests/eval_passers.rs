@@ -0,0 +1,43 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::search::eval::{passed_pawns_cp, UNSTOPPABLE_PASSER};
+
+fn b(fen: &str) -> Board { fen.parse().unwrap() }
+
+#[test]
+fn passers_are_rewarded_by_rank() {
+    // No enemy pawns in front or on adjacent files: passed; blocked by an adjacent-file pawn: not
+    let passed = passed_pawns_cp(&b("4k3/8/8/8/8/3P4/8/4K3 w - - 0 1"));
+    let further = passed_pawns_cp(&b("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1"));
+    let guarded = passed_pawns_cp(&b("4k3/2p5/8/8/8/3P4/8/4K3 w - - 0 1"));
+    assert!(passed > 0 && further > passed);
+    assert!(guarded < passed);
+}
+
+#[test]
+fn square_rule_decides_unstoppable_passers() {
+    // Kd6 reaches the square only with the move
+    let white_to_move = passed_pawns_cp(&b("8/8/8/P3k3/8/8/8/7K w - - 0 1"));
+    let black_to_move = passed_pawns_cp(&b("8/8/8/P3k3/8/8/8/7K b - - 0 1"));
+    assert!(white_to_move >= UNSTOPPABLE_PASSER);
+    assert!(black_to_move < UNSTOPPABLE_PASSER);
+    // A defending piece can stop the pawn, so no square-rule bonus
+    assert!(passed_pawns_cp(&b("8/8/8/P3k3/8/8/8/r6K w - - 0 1")) < UNSTOPPABLE_PASSER);
+    // Mirror for black
+    assert!(passed_pawns_cp(&b("7k/8/8/8/p3K3/8/8/8 b - - 0 1")) <= -UNSTOPPABLE_PASSER);
+}
+
+#[test]
+fn race_goes_to_the_faster_pawn() {
+    // White queens in 2, black in 4: White wins the race whoever moves
+    let fen = "8/1k5P/8/8/8/p7/8/7K b - - 0 1";
+    assert!(passed_pawns_cp(&b(fen)) > 0);
+}
+
+#[test]
+fn search_prefers_running_with_the_passer() {
+    let board = b("8/8/8/P3k3/8/8/8/7K w - - 0 1");
+    let mut s = Searcher::default();
+    let r = s.search_with_params(&board, SearchParams { depth: 3, use_tt: true, order_captures: true, ..Default::default() });
+    assert_eq!(r.bestmove.as_deref(), Some("a5a6"));
+}
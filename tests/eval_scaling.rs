@@ -0,0 +1,35 @@
+use cozy_chess::Board;
+use piebot::search::eval::{endgame_scale, eval_cp, material_eval_cp, MaterialSig, SCALE_NORMAL};
+
+fn sig(fen: &str) -> MaterialSig { MaterialSig::from_board(&fen.parse::<Board>().unwrap()) }
+
+#[test]
+fn drawish_material_is_scaled_down() {
+    // Opposite-coloured bishops, white a pawn up
+    let ocb = sig("4k3/5p2/8/3b4/8/8/4PP2/2B1K3 w - - 0 1");
+    assert!(ocb.opposite_bishops);
+    assert_eq!(endgame_scale(&ocb), SCALE_NORMAL / 2);
+    // Same-coloured bishops are not drawish by themselves
+    let scb = sig("4k3/5p2/8/4b3/8/8/4PP2/2B1K3 w - - 0 1");
+    assert!(!scb.opposite_bishops);
+    assert_eq!(endgame_scale(&scb), SCALE_NORMAL);
+    // Rook + minor vs rook, no pawns
+    assert!(endgame_scale(&sig("4k3/8/8/8/8/8/r7/RN2K3 w - - 0 1")) < SCALE_NORMAL);
+    // Rook vs bishop, black stronger and pawnless
+    assert!(endgame_scale(&sig("4k3/8/8/8/8/8/r7/2B1K3 w - - 0 1")) < SCALE_NORMAL);
+    // Winnable: rook vs nothing, queen vs rook, extra pawn for the stronger side
+    assert_eq!(endgame_scale(&sig("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")), SCALE_NORMAL);
+    assert_eq!(endgame_scale(&sig("4k3/8/8/8/8/8/r7/Q3K3 w - - 0 1")), SCALE_NORMAL);
+    assert_eq!(endgame_scale(&sig("4k3/8/8/8/8/8/r3P3/RN2K3 w - - 0 1")), SCALE_NORMAL);
+    assert_eq!(endgame_scale(&sig("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")), SCALE_NORMAL);
+}
+
+#[test]
+fn eval_shrinks_toward_zero_in_drawish_endings() {
+    let b: Board = "4k3/8/8/8/8/8/r7/RN2K3 w - - 0 1".parse().unwrap();
+    let e = eval_cp(&b);
+    assert!(e > 0 && e < material_eval_cp(&b) / 2, "eval {} not scaled", e);
+    // Symmetric for the side to move
+    let bb: Board = "4k3/8/8/8/8/8/r7/RN2K3 b - - 0 1".parse().unwrap();
+    assert_eq!(eval_cp(&bb), -e);
+}
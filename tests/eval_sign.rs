@@ -0,0 +1,135 @@
+mod common;
+
+use common::quant_net;
+use cozy_chess::Board;
+use piebot::board::cozy::flip_colors;
+use piebot::eval::nnue::features::{halfkp_dim, HALFKP_PIECE_ORDER};
+use piebot::eval::nnue::loader::QuantNnue;
+use piebot::eval::nnue::network::QuantNetwork;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::eval::{eval_cp, material_eval_cp, side_relative};
+
+// Quiet, lopsided positions (White-relative better side first); each is used
+// with both sides to move
+const POSITIONS: [(&str, bool); 4] = [
+    ("4k3/8/8/8/8/8/4P3/RN2K3", true),
+    ("rn2k3/4p3/8/8/8/8/8/4K3", false),
+    ("4k3/pp6/8/8/8/8/2P5/2B1K3", true),
+    ("2q1k3/8/8/8/8/8/PP6/3RK3", false),
+];
+
+fn boards() -> Vec<(Board, bool)> {
+    POSITIONS.iter().flat_map(|&(fen, white_better)| ["w", "b"].map(|stm| {
+        let b: Board = format!("{} {} - - 0 1", fen, stm).parse().unwrap();
+        let stm_better = white_better == (stm == "w");
+        (b, stm_better)
+    })).collect()
+}
+
+// Two hidden units summing each side's material in pawns; the head outputs
+// White minus Black in centipawns
+fn material_net() -> QuantNnue {
+    let (n, h) = (halfkp_dim(), 2);
+    let mut w1 = vec![0i8; h * n];
+    let per_side = n / 2;
+    for idx in 0..n {
+        let value = [1, 3, 3, 5, 9][(idx / 64) % HALFKP_PIECE_ORDER.len()];
+        w1[(idx / per_side) * n + idx] = value;
+    }
+    quant_net(n, h, w1, vec![100, -100], 0)
+}
+
+#[test]
+fn hand_evals_are_from_the_side_to_move() {
+    let mut s = Searcher::default();
+    for (b, stm_better) in boards() {
+        for (name, cp) in [("pst", eval_cp(&b)), ("material", material_eval_cp(&b)), ("static", s.static_eval(&b)), ("qsearch", s.qsearch_eval_cp(&b))] {
+            assert_eq!(cp > 0, stm_better, "{} eval {} on {}", name, cp, b);
+        }
+        // Swapping colours swaps the side to move too: the same eval
+        assert_eq!(eval_cp(&flip_colors(&b)), eval_cp(&b), "{}", b);
+    }
+    assert_eq!((side_relative(true, 40), side_relative(false, 40)), (40, -40));
+}
+
+#[test]
+fn nnue_is_white_relative_and_the_searcher_flips_it_once() {
+    let model = material_net();
+    let mut net = QuantNetwork::new(model.clone());
+    let mut s = Searcher::default();
+    s.set_use_nnue(true);
+    s.set_nnue_quant_model(model);
+    for (b, stm_better) in boards() {
+        let white = b.side_to_move() == cozy_chess::Color::White;
+        net.refresh(&b);
+        let raw = net.eval_full(&b);
+        assert_eq!(raw, net.eval_current());
+        assert_eq!(raw > 0, stm_better == white, "white-relative net output {} on {}", raw, b);
+        assert_eq!(net.eval_full(&flip_colors(&b)), -raw, "{}", b);
+        // Material-only net and positions without drawish material: no scaling
+        assert_eq!(s.static_eval(&b), side_relative(white, raw), "{}", b);
+        assert_eq!(s.qsearch_eval_cp(&b), side_relative(white, raw), "{}", b);
+    }
+}
+
+#[test]
+fn search_scores_are_from_the_side_to_move() {
+    let mut s = Searcher::default();
+    for (b, stm_better) in boards() {
+        s.new_game();
+        let r = s.search_with_params(&b, SearchParams { depth: 3, use_tt: true, order_captures: true, ..Default::default() });
+        assert_eq!(r.score.value() > 0, stm_better, "score {} on {}", r.score.value(), b);
+    }
+}
+
+#[cfg(feature = "board-pleco")]
+mod pleco_backend {
+    use super::*;
+    use piebot::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher};
+
+    fn pleco(b: &Board) -> pleco::Board { pleco::Board::from_fen(&format!("{}", b)).unwrap() }
+
+    #[test]
+    fn pleco_evals_match_cozy_signs() {
+        let mut cozy = Searcher::default();
+        cozy.set_material_eval(true);
+        let mut p = PlecoSearcher::default();
+        for (b, stm_better) in boards() {
+            let pe = p.static_eval(&pleco(&b));
+            assert_eq!(pe, cozy.static_eval(&b), "material on {}", b);
+            assert_eq!(pe > 0, stm_better, "{}", b);
+        }
+        let mut cozy = Searcher::default();
+        cozy.set_use_nnue(true);
+        cozy.set_nnue_quant_model(material_net());
+        p.set_eval_mode(PlecoEvalMode::Nnue);
+        p.set_nnue_quant_model(material_net());
+        for (b, stm_better) in boards() {
+            let pe = p.static_eval(&pleco(&b));
+            assert_eq!(pe, cozy.static_eval(&b), "nnue on {}", b);
+            assert_eq!(pe > 0, stm_better, "{}", b);
+        }
+    }
+
+    #[test]
+    fn pleco_pst_eval_is_material_plus_the_cozy_tables() {
+        use piebot::search::eval::{eval_breakdown, scale_eval, MaterialSig};
+        let mut p = PlecoSearcher::default();
+        p.set_eval_mode(PlecoEvalMode::Pst);
+        let opening: Board = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3".parse().unwrap();
+        for b in boards().into_iter().map(|(b, _)| b).chain([opening]) {
+            let bd = eval_breakdown(&b);
+            let white = scale_eval(&MaterialSig::from_board(&b), bd.material + bd.pst);
+            assert_eq!(p.static_eval(&pleco(&b)), side_relative(b.side_to_move() == cozy_chess::Color::White, white), "{}", b);
+        }
+    }
+
+    #[test]
+    fn pleco_search_scores_are_from_the_side_to_move() {
+        let mut p = PlecoSearcher::default();
+        for (b, stm_better) in boards() {
+            let r = p.search_result(&mut pleco(&b), 10_000, 3);
+            assert_eq!(r.score.value() > 0, stm_better, "score {} on {}", r.score.value(), b);
+        }
+    }
+}
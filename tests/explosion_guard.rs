@@ -0,0 +1,92 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher, EXPLOSION_GUARD};
+use piebot::search::options::EngineOptions;
+
+const MIDDLEGAME: &str = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R1BQKB1R w KQ - 0 9";
+
+fn params(guard: u32, aspiration: bool) -> SearchParams {
+    SearchParams { depth: 6, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, use_aspiration: aspiration, aspiration_window_cp: 30, explosion_guard: guard, ..SearchParams::default() }
+}
+
+fn legal(board: &Board, uci: &str) -> bool {
+    let mut found = false;
+    board.generate_moves(|ml| { found |= ml.into_iter().any(|m| m.to_string() == uci); found });
+    found
+}
+
+#[test]
+fn tight_cap_trips_and_still_returns_a_move() {
+    let board = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let mut s = Searcher::default();
+    // Every first move of an iteration takes more than the whole previous one
+    let r = s.search_with_params(&board, params(1, true));
+    assert!(s.guard_trips() > 0);
+    assert_eq!(r.depth, 6);
+    assert!(legal(&board, r.bestmove.as_deref().unwrap()));
+}
+
+#[test]
+fn a_tripped_fail_low_is_settled_with_a_full_window() {
+    use piebot::search::tt::Bound;
+    // The guard trips at depth 6 and the capped aspirated search fails low
+    let board = Board::from_fen("rnbqkbnr/1pppp1pp/p7/5p2/5P2/P1P5/RP1PPKPP/1NBQ1BNR b kq - 1 5", false).unwrap();
+    let mut s = Searcher::default();
+    s.set_track_root_scores(true);
+    let r = s.search_with_params(&board, SearchParams { aspiration_window_cp: 10, ..params(1, true) });
+    assert!(s.guard_trips() > 0);
+    // The best move of every iteration has a real score, not just a bound below the window
+    for it in s.root_score_table() {
+        let top = it.scores.iter().map(|x| x.score_cp).max().unwrap();
+        assert!(it.scores.iter().any(|x| x.score_cp == top && x.bound != Bound::Upper), "depth {}: {:?}", it.depth, r);
+    }
+}
+
+#[test]
+fn guard_is_off_at_zero_and_without_aspiration() {
+    let board = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let mut s = Searcher::default();
+    s.search_with_params(&board, params(0, true));
+    assert_eq!(s.guard_trips(), 0);
+    let mut s = Searcher::default();
+    s.search_with_params(&board, params(1, false));
+    assert_eq!(s.guard_trips(), 0, "only aspirated roots are guarded");
+}
+
+#[test]
+fn default_guard_leaves_normal_searches_alone() {
+    let board = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let (mut a, mut b) = (Searcher::default(), Searcher::default());
+    let guarded = a.search_with_params(&board, params(EXPLOSION_GUARD, true));
+    let plain = b.search_with_params(&board, params(0, true));
+    assert_eq!(a.guard_trips(), 0);
+    assert_eq!((guarded.nodes, guarded.bestmove), (plain.nodes, plain.bestmove));
+}
+
+#[test]
+fn explosion_guard_option() {
+    let mut o = EngineOptions::default();
+    assert_eq!(o.explosion_guard, EXPLOSION_GUARD);
+    assert_eq!(o.search_params().explosion_guard, EXPLOSION_GUARD);
+    assert!(!o.describe().contains("explosion_guard"));
+    o.set("ExplosionGuard", "100").unwrap();
+    assert_eq!(o.explosion_guard, 64);
+    o.set("explosion_guard", "0").unwrap();
+    assert!(o.describe().contains(",explosion_guard=0"));
+    assert_eq!(o.search_params().explosion_guard, 0);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_tight_cap_trips() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_explosion_guard(1);
+    let mut b = pleco::Board::from_fen(MIDDLEGAME).unwrap();
+    let (bm, _, _) = s.search_movetime(&mut b, 60_000, 7);
+    assert!(s.guard_trips() > 0);
+    assert!(bm.is_some());
+    let mut s = PlecoSearcher::default();
+    s.set_explosion_guard(0);
+    s.search_movetime(&mut b, 60_000, 7);
+    assert_eq!(s.guard_trips(), 0);
+}
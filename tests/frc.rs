@@ -0,0 +1,80 @@
+use cozy_chess::{Board, Color, File, Piece};
+use piebot::io::fen::{board_fen, parse_fen_line};
+use piebot::selfplay::frc::{back_rank, frc_board, frc_fen, random_dfrc, FRC_POSITIONS, STANDARD_INDEX};
+use piebot::selfplay::openings::{OpeningKind, OpeningMix, OpeningSource};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+#[test]
+fn back_ranks_are_the_960_legal_setups() {
+    use Piece::*;
+    assert_eq!(back_rank(STANDARD_INDEX), [Rook, Knight, Bishop, Queen, King, Bishop, Knight, Rook]);
+    assert_eq!(back_rank(0), [Bishop, Bishop, Queen, Knight, Knight, Rook, King, Rook]);
+    let mut seen = HashSet::new();
+    for n in 0..FRC_POSITIONS {
+        let r = back_rank(n);
+        assert!(seen.insert(r), "duplicate setup at {}", n);
+        let files = |p| (0..8).filter(|&f| r[f] == p).collect::<Vec<_>>();
+        let (b, k, rooks) = (files(Bishop), files(King), files(Rook));
+        assert_eq!(b[0] % 2 + b[1] % 2, 1, "{}: bishops on one colour", n);
+        assert!(rooks[0] < k[0] && k[0] < rooks[1], "{}: king not between the rooks", n);
+        assert_eq!((files(Queen).len(), files(Knight).len()), (1, 2));
+    }
+}
+
+#[test]
+fn frc_boards_castle_by_rook_file_and_round_trip() {
+    assert_eq!(frc_board(STANDARD_INDEX, STANDARD_INDEX), Board::default());
+    assert_eq!(board_fen(&Board::default()), format!("{}", Board::default()), "standard FENs keep KQkq");
+
+    let fen = frc_fen(0, 0);
+    assert_eq!(fen, "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1");
+    let b = frc_board(0, 0);
+    assert_eq!(b.castle_rights(Color::White).short, Some(File::H));
+    assert_eq!(b.castle_rights(Color::Black).long, Some(File::F));
+    assert_eq!(board_fen(&b), fen);
+    assert_eq!(parse_fen_line(&board_fen(&b)).unwrap(), b);
+
+    let mut rng = SmallRng::seed_from_u64(5);
+    for _ in 0..50 {
+        let b = random_dfrc(&mut rng);
+        assert_eq!(parse_fen_line(&board_fen(&b)).unwrap(), b);
+    }
+}
+
+#[test]
+fn castling_through_the_rook_works_in_frc() {
+    // King g1, rook h1: clear f1 and castle short as king-takes-rook
+    let mut b = parse_fen_line("bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNN1KR w H - 0 1").unwrap();
+    let castle = "g1h1".parse().unwrap();
+    assert!(b.is_legal(castle));
+    b.play(castle);
+    assert_eq!(b.piece_on(cozy_chess::Square::G1), Some(Piece::King));
+    assert_eq!(b.piece_on(cozy_chess::Square::F1), Some(Piece::Rook));
+}
+
+#[test]
+fn frc_opening_sources_sample_fresh_setups() {
+    let frc: OpeningSource = "frc,w=2".parse().unwrap();
+    assert_eq!(frc.kind, OpeningKind::Frc);
+    assert_eq!(frc.to_string(), "frc,w=2");
+    assert_eq!("chess960".parse::<OpeningSource>().unwrap().kind, OpeningKind::Frc);
+    assert_eq!("dfrc".parse::<OpeningSource>().unwrap().kind, OpeningKind::Dfrc);
+
+    let mut rng = SmallRng::seed_from_u64(9);
+    let mut mix = OpeningMix::new(&[frc], 1).unwrap();
+    let starts: HashSet<String> = (0..20).map(|g| board_fen(&mix.sample(&mut rng, g).0)).collect();
+    assert!(starts.len() > 15, "{:?}", starts);
+    for f in &starts {
+        let ranks: Vec<&str> = f.split(['/', ' ']).collect();
+        assert_eq!(ranks[0].to_uppercase(), ranks[7], "FRC mirrors the back ranks: {}", f);
+    }
+
+    let mut mix = OpeningMix::new(&["dfrc".parse().unwrap()], 1).unwrap();
+    let asymmetric = (0..20).map(|g| board_fen(&mix.sample(&mut rng, g).0)).filter(|f| {
+        let ranks: Vec<&str> = f.split(['/', ' ']).collect();
+        ranks[0].to_uppercase() != ranks[7]
+    }).count();
+    assert!(asymmetric > 15);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "fuzzing")]
+// Short deterministic runs of the fuzz harnesses: `cargo test --features fuzzing`.
+// Long runs use cargo-fuzz: `cargo +nightly fuzz run fen` (or uci, nnue).
+use piebot::fuzz;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+// Random byte edits of a valid seed, plus pure noise
+fn mutants(seed: &[u8], n: usize, rng_seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = SmallRng::seed_from_u64(rng_seed);
+    (0..n).map(|i| {
+        if i % 4 == 3 { return (0..rng.gen_range(0..64)).map(|_| rng.gen()).collect(); }
+        let mut v = seed.to_vec();
+        for _ in 0..rng.gen_range(1..4) {
+            match rng.gen_range(0..3) {
+                0 if !v.is_empty() => { let k = rng.gen_range(0..v.len()); v[k] = rng.gen(); }
+                1 if !v.is_empty() => { let k = rng.gen_range(0..v.len()); v.truncate(k); }
+                _ => { let k = rng.gen_range(0..=v.len()); v.insert(k, rng.gen()); }
+            }
+        }
+        v
+    }).collect()
+}
+
+#[test]
+fn fen_harness_survives_mutations() {
+    let seeds = ["rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1", "8/P7/8/8/8/8/7p/K6k b - - 5 60"];
+    for (i, s) in seeds.iter().enumerate() {
+        for m in mutants(s.as_bytes(), 300, i as u64) { fuzz::fen(&m); }
+    }
+}
+
+#[test]
+fn uci_harness_survives_mutations() {
+    let script = b"uci\nsetoption name Hash value 1\nsetoption name MultiPV value 2\nposition startpos moves e2e4 e7e5\ngo depth 2 searchmoves g1f3\nsetoption name Backend value pleco\nposition fen 8/8/8/8/8/8/k7/7K w - - 0 1 moves h1g1\ngo depth 1\nisready\n";
+    for m in mutants(script, 40, 9) { fuzz::uci(&m); }
+}
+
+#[test]
+fn nnue_harness_survives_mutations() {
+    let mut dense = b"PIENNUE1".to_vec();
+    for d in [1u32, 12, 4, 1] { dense.extend_from_slice(&d.to_le_bytes()); }
+    let mut quant = b"PIENNQ01".to_vec();
+    for d in [1u32, 12, 4, 1] { quant.extend_from_slice(&d.to_le_bytes()); }
+    quant.extend_from_slice(&1.0f32.to_le_bytes());
+    quant.extend_from_slice(&1.0f32.to_le_bytes());
+    for m in mutants(&dense, 300, 1).into_iter().chain(mutants(&quant, 300, 2)) { fuzz::nnue(&m); }
+    // Headers claiming enormous layers are rejected rather than allocated
+    let mut huge = b"PIENNUE1".to_vec();
+    for d in [1u32, u32::MAX, u32::MAX, 1] { huge.extend_from_slice(&d.to_le_bytes()); }
+    assert!(piebot::eval::nnue::Nnue::from_reader(&huge[..]).is_err());
+}
@@ -0,0 +1,58 @@
+use cozy_chess::Board;
+use piebot::selfplay::adjudicate::Adjudication;
+use piebot::selfplay::game::{startpos, GameState};
+use piebot::board::cozy::encode_move;
+use piebot::selfplay::{flatten_game_to_records, GameRecord};
+
+#[test]
+fn cached_startpos_is_the_standard_position() {
+    assert_eq!(startpos(), Board::default());
+    assert_eq!(GameState::default().board(), &Board::default());
+}
+
+#[test]
+fn moves_parse_once_and_illegal_ones_are_refused() {
+    let mut g = GameState::default();
+    assert!(g.play_uci("e2e4"));
+    assert!(!g.play_uci("e2e4"), "the pawn has left e2");
+    assert!(!g.play_uci("e7e4"));
+    assert!(!g.play_uci("not-a-move"));
+    assert!(g.play_uci("e7e5"));
+    assert_eq!(g.ply(), 2);
+    assert_eq!(g.moves().iter().map(|m| m.to_string()).collect::<Vec<_>>(), ["e2e4", "e7e5"]);
+    // Castling uses the searcher's king-takes-rook notation
+    let mut c = GameState::new(Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", false).unwrap());
+    assert!(c.play_uci("e1h1"));
+}
+
+#[test]
+fn reset_replays_the_start_and_clears_history() {
+    let mut g = GameState::default();
+    for _ in 0..2 {
+        for m in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+            assert_eq!(g.adjudicate(), None);
+            assert!(g.play_uci(m));
+        }
+    }
+    assert_eq!(g.adjudicate(), Some(Adjudication::Threefold));
+    g.reset();
+    assert_eq!((g.ply(), g.board(), g.history().len()), (0, &startpos(), 1));
+    assert_eq!(g.adjudicate(), None);
+
+    let kiwi = Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", false).unwrap();
+    g.reset_to(&kiwi);
+    assert!(g.play_uci("e2a6"));
+    g.reset();
+    assert_eq!((g.start(), g.board(), g.ply()), (&kiwi, &kiwi, 0));
+}
+
+#[test]
+fn records_replay_through_game_state() {
+    let code = |s: &str| encode_move(s.parse().unwrap());
+    let game = GameRecord { start: startpos(), moves: vec![code("e2e4"), code("e7e5"), code("g1f3"), code("e7e5"), code("b8c6")], result: 1 };
+    let recs = flatten_game_to_records(&game);
+    // Replay stops at the move that is not legal where it was recorded
+    assert_eq!(recs.len(), 4);
+    assert_eq!(game.uci_moves(), ["e2e4", "e7e5", "g1f3", "e7e5", "b8c6"]);
+    assert_eq!(recs.iter().map(|r| r.stm).collect::<Vec<_>>(), [0, 1, 0, 1]);
+}
@@ -14,7 +14,7 @@ fn aspiration_retains_score_and_can_reduce_nodes() {
     let mut s2 = Searcher::default();
     let mut p2 = p1; p2.use_aspiration = true;
     let r2 = s2.search_with_params(&b, p2);
-    assert_eq!(r2.score_cp, r1.score_cp, "aspiration changed score");
+    assert_eq!(r2.score.value(), r1.score.value(), "aspiration changed score");
     assert!(r2.nodes <= r1.nodes, "aspiration did not reduce nodes: {} vs {}", r2.nodes, r1.nodes);
 }
 
@@ -32,6 +32,6 @@ fn killers_lmr_reduce_nodes() {
     let mut s2 = Searcher::default();
     let mut p2 = p1; p2.use_lmr = true; p2.use_killers = true;
     let r2 = s2.search_with_params(&b, p2);
-    assert!((r2.score_cp - r1.score_cp).abs() <= 100, "heuristics changed score too much: {} vs {}", r2.score_cp, r1.score_cp);
+    assert!((r2.score.value() - r1.score.value()).abs() <= 100, "heuristics changed score too much: {} vs {}", r2.score.value(), r1.score.value());
     assert!(r2.nodes <= r1.nodes, "heuristics did not reduce nodes: {} vs {}", r2.nodes, r1.nodes);
 }
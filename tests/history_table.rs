@@ -18,7 +18,7 @@ fn history_table_reduces_nodes() {
     let mut p2 = p1; p2.use_history = true;
     let r2 = s2.search_with_params(&b, p2);
 
-    assert_eq!(r2.score_cp, r1.score_cp, "history should not change score");
+    assert_eq!(r2.score.value(), r1.score.value(), "history should not change score");
     assert!(r2.nodes <= r1.nodes, "history should reduce nodes: {} vs {}", r2.nodes, r1.nodes);
 }
 
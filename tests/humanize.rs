@@ -0,0 +1,32 @@
+use piebot::search::humanize::{Humanizer, MoveContext};
+use std::time::Duration;
+
+#[test]
+fn think_time_stays_within_budget_and_varies() {
+    let mut h = Humanizer::new(42);
+    let budget = Duration::from_millis(1000);
+    let ctx = MoveContext { legal_moves: 30, in_check: false, recapture: false };
+    let times: Vec<Duration> = (0..20).map(|_| h.think_time(budget, ctx)).collect();
+    assert!(times.iter().all(|&t| t <= budget));
+    assert!(times.iter().any(|&t| t != times[0]), "timing should not be flat");
+}
+
+#[test]
+fn forced_moves_and_recaptures_are_quick() {
+    let mut h = Humanizer::new(7);
+    let budget = Duration::from_millis(1000);
+    let forced = MoveContext { legal_moves: 1, in_check: true, recapture: false };
+    let recapture = MoveContext { legal_moves: 25, in_check: false, recapture: true };
+    assert!(h.think_time(budget, forced) < Duration::from_millis(100));
+    assert!(h.think_time(budget, recapture) < Duration::from_millis(100));
+}
+
+#[test]
+fn games_are_reproducible_from_seed() {
+    let ctx = MoveContext { legal_moves: 20, in_check: false, recapture: false };
+    let budget = Duration::from_millis(500);
+    let mut a = Humanizer::new(5);
+    let mut b = Humanizer::new(5);
+    a.new_game(); b.new_game();
+    assert_eq!(a.think_time(budget, ctx), b.think_time(budget, ctx));
+}
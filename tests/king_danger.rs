@@ -0,0 +1,60 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::eval::KingAttack;
+use piebot::search::options::EngineOptions;
+
+// Queen on h5 and bishop on d3 both bearing on the castled king
+const ATTACK: &str = "r1b2rk1/ppq2pp1/2n1p2p/3pP2Q/3P4/2PB1N2/P4PPP/R4RK1 w - - 0 1";
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+fn nodes(fen: &str, king_danger: bool) -> u64 {
+    let b = Board::from_fen(fen, false).unwrap();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, king_danger, ..Default::default() };
+    s.search_with_params(&b, p).nodes
+}
+
+#[test]
+fn king_attack_flags_pressure_on_the_castled_king() {
+    let attack = Board::from_fen(ATTACK, false).unwrap();
+    let a = KingAttack::from_board(&attack, cozy_chess::Color::White);
+    assert_eq!(a.attackers, 2);
+    assert!(a.is_dangerous());
+    // Black has nothing on White's king
+    assert!(!KingAttack::from_board(&attack, cozy_chess::Color::Black).is_dangerous());
+    assert_eq!(KingAttack::from_board(&Board::default(), cozy_chess::Color::White), KingAttack::default());
+    // A lone queen is not an attack, however many squares it hits
+    let lone = Board::from_fen("6k1/5p1p/6pQ/8/8/8/5PPP/6K1 w - - 0 1", false).unwrap();
+    assert!(!KingAttack::from_board(&lone, cozy_chess::Color::White).is_dangerous());
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_king_attack_matches_cozy() {
+    for fen in [ATTACK, START, "r4rk1/pp3ppp/2n5/3q4/8/2NB1Q2/PP3PPP/R4RK1 b - - 0 1"] {
+        let (b, p) = (Board::from_fen(fen, false).unwrap(), pleco::Board::from_fen(fen).unwrap());
+        for (c, pl) in [(cozy_chess::Color::White, pleco::Player::White), (cozy_chess::Color::Black, pleco::Player::Black)] {
+            assert_eq!(KingAttack::from_board(&b, c), KingAttack::from_pleco(&p, pl), "{}", fen);
+        }
+    }
+}
+
+#[test]
+fn dangerous_nodes_are_pruned_less() {
+    assert!(nodes(ATTACK, true) > nodes(ATTACK, false));
+    // Quiet positions search exactly as before
+    assert_eq!(nodes(START, true), nodes(START, false));
+}
+
+#[test]
+fn king_danger_option() {
+    let mut o = EngineOptions::default();
+    assert!(o.search_params().king_danger);
+    assert!(!o.describe().contains("king_danger"));
+    o.set("KingDanger", "false").unwrap();
+    assert!(!o.search_params().king_danger);
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    assert!(o.uci_option_lines().contains(&"option name KingDanger type check default false".to_string()));
+}
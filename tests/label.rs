@@ -0,0 +1,85 @@
+// Batch labelling: order, thread independence and the piebot-score CLI (text and shard input).
+use cozy_chess::Board;
+use piebot::search::label::{label_positions, LabelMode};
+use piebot::search::options::EngineOptions;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const FENS: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    // Black to move takes the hanging queen with the e6 pawn
+    "4k3/8/4p3/3Q4/8/8/8/4K3 b - - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+];
+
+fn boards() -> Vec<Board> { FENS.iter().map(|f| Board::from_fen(f, false).unwrap()).collect() }
+
+fn opts() -> EngineOptions { EngineOptions { hash_mb: 4, ..EngineOptions::default() } }
+
+#[test]
+fn qsearch_labels_settle_captures_for_the_side_to_move() {
+    let labels = label_positions(&boards(), &opts(), LabelMode::Qsearch, 2).unwrap();
+    assert_eq!(labels.len(), FENS.len());
+    for (l, f) in labels.iter().zip(FENS) { assert_eq!(&l.fen, f); assert_eq!((l.depth, l.bestmove.as_deref()), (0, None)); }
+    // A queen down on the board, a pawn up once it is taken
+    assert!(labels[1].score > 0, "{:?}", labels[1]);
+    assert!(!labels[1].white_to_move && labels[1].white_score() < 0);
+}
+
+#[test]
+fn depth_labels_do_not_depend_on_worker_count() {
+    let one = label_positions(&boards(), &opts(), LabelMode::Depth(4), 1).unwrap();
+    let three = label_positions(&boards(), &opts(), LabelMode::Depth(4), 3).unwrap();
+    assert_eq!(one, three);
+    assert!(one.iter().all(|l| l.depth == 4 && l.bestmove.is_some()), "{:?}", one);
+    assert_eq!(one[1].bestmove.as_deref(), Some("e6d5"));
+}
+
+#[test]
+fn cli_writes_csv_and_jsonl_and_skips_bad_lines() {
+    let run = |args: &[&str]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_score")).args(args).arg("--hash-mb").arg("4")
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+        let input = format!("{}\nnot a fen\n{{\"fen\": \"{}\"}}\n", FENS[0], FENS[1]);
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success());
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert!(stderr.contains("stdin:2:") && stderr.contains("(1 skipped)"), "{}", stderr);
+        String::from_utf8(out.stdout).unwrap()
+    };
+    let csv = run(&["--white"]);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 2, "{}", csv);
+    assert!(rows[0].starts_with(&format!("{},", FENS[0])));
+    let (_, score) = rows[1].rsplit_once(',').unwrap();
+    assert!(score.parse::<i32>().unwrap() < 0, "{}", rows[1]);
+
+    let jsonl = run(&["--depth", "2", "--format", "jsonl"]);
+    let v: Vec<serde_json::Value> = jsonl.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(v.len(), 2);
+    assert_eq!((v[1]["fen"].as_str(), v[1]["bestmove"].as_str(), v[1]["depth"].as_u64()), (Some(FENS[1]), Some("e6d5"), Some(2)));
+}
+
+#[test]
+fn cli_reads_selfplay_shards_from_their_games_file() {
+    use piebot::selfplay::{game_positions, generate_games, write_games, write_shards, SelfPlayParams, ShardMeta};
+    let params = SelfPlayParams {
+        games: 2, max_plies: 6, threads: 1, use_engine: false, depth: 1, movetime_ms: None, seed: 3,
+        temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0, opening_sources: Vec::new(),
+    };
+    let games = generate_games(&params);
+    let dir = std::env::temp_dir().join(format!("piebot_score_shards_{}", std::process::id()));
+    let shards = write_shards(&games, &dir, 4, &ShardMeta::from_params(&params)).unwrap();
+    write_games(&games, &dir).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_score")).args(&shards).args(["--hash-mb", "4"]).output().unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let fens: Vec<String> = String::from_utf8(out.stdout).unwrap().lines().map(|l| l.rsplit_once(',').unwrap().0.to_string()).collect();
+    let want: Vec<String> = games.iter().flat_map(game_positions).map(|b| b.to_string()).collect();
+    assert!(shards.len() > 1);
+    assert_eq!(fens, want);
+}
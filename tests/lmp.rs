@@ -0,0 +1,48 @@
+use piebot::search::alphabeta::LmpTable;
+use piebot::search::options::EngineOptions;
+
+#[test]
+fn table_prunes_late_quiets_at_shallow_depth_only() {
+    let t = LmpTable::default();
+    assert!(!t.prunes(1, false, 9) && t.prunes(1, false, 10));
+    // An improving node keeps more moves
+    assert!(!t.prunes(1, true, 10) && t.prunes(1, true, 15));
+    assert!(t.prunes(3, false, 6) && !t.prunes(3, true, 6));
+    assert!(!t.prunes(0, false, 100) && !t.prunes(LmpTable::DEPTH as u32 + 1, false, 100));
+}
+
+#[test]
+fn table_round_trips_through_text_json_and_options() {
+    let t: LmpTable = "4/7 : 5/9 : 6/12".parse().unwrap();
+    assert_eq!(t.moves, [[4, 7], [5, 9], [6, 12]]);
+    assert_eq!(t.to_string(), "4/7:5/9:6/12");
+    assert_eq!(LmpTable::default().to_string().parse::<LmpTable>().unwrap(), LmpTable::default());
+    for bad in ["", "4/7:5/9", "4/7:5/9:6/12:1/1", "4:5:6", "a/b:5/9:6/12"] { assert!(bad.parse::<LmpTable>().is_err(), "{}", bad); }
+    assert_eq!(serde_json::to_string(&t).unwrap(), "[[4,7],[5,9],[6,12]]");
+
+    let mut o = EngineOptions::default();
+    assert!(!o.describe().contains("lmp="));
+    o.apply_spec("lmp=4/7:5/9:6/12,hash=8").unwrap();
+    assert_eq!(o.lmp, t);
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    let j: EngineOptions = serde_json::from_str(r#"{"lmp": [[4,7],[5,9],[6,12]]}"#).unwrap();
+    assert_eq!(j.lmp, t);
+    assert!(o.apply_spec("lmp=1/2").is_err());
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn helpers_search_with_any_table() {
+    use piebot::search::alphabeta_pleco::SmpMode;
+    // Lazy helpers prune with the table: from none at all to nearly every quiet
+    for spec in ["99/99:99/99:99/99", "1/1:1/1:1/1"] {
+        let opts = EngineOptions { threads: 2, lmp: spec.parse().unwrap(), ..EngineOptions::default() };
+        let mut s = opts.build_pleco_searcher().unwrap();
+        s.set_smp_mode(SmpMode::LazyCoop);
+        let mut b = pleco::Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 2 3").unwrap();
+        let r = s.search_result(&mut b, 10_000, 4);
+        assert_eq!(r.bestmove.as_deref(), Some("h5f7"), "{}", spec);
+    }
+}
@@ -0,0 +1,43 @@
+// History-adjusted LMR and LMR of bad captures: both reshape the tree, neither
+// loses a simple tactic, and both are reachable from option specs.
+use cozy_chess::Board;
+use piebot::io::fen::BENCH_SUITE;
+use piebot::search::alphabeta::{SearchParams, Searcher, LMR_HISTORY_DIV};
+use piebot::search::options::EngineOptions;
+
+fn params(lmr_history_div: i32, lmr_captures: bool) -> SearchParams {
+    SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, lmr_history_div, lmr_captures, ..Default::default() }
+}
+
+fn search(fen: &str, p: SearchParams) -> (Option<String>, u64) {
+    let r = Searcher::default().search_with_params(&Board::from_fen(fen, false).unwrap(), p);
+    (r.bestmove, r.nodes)
+}
+
+#[test]
+fn history_and_capture_reductions_change_the_tree() {
+    let fen = BENCH_SUITE[1];
+    let (_, base) = search(fen, params(0, false));
+    let (best, nodes) = search(fen, params(LMR_HISTORY_DIV, false));
+    assert!(best.is_some() && nodes != base, "{} vs {}", nodes, base);
+    let (best, nodes) = search(fen, params(0, true));
+    assert!(best.is_some() && nodes != base, "{} vs {}", nodes, base);
+}
+
+#[test]
+fn adjusted_reductions_keep_a_back_rank_mate() {
+    let (best, _) = search("3q2k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", params(LMR_HISTORY_DIV, true));
+    assert_eq!(best.as_deref(), Some("d1d8"));
+}
+
+#[test]
+fn options_pass_the_lmr_settings_through() {
+    let mut o = EngineOptions::default();
+    assert!(!o.describe().contains("lmr_"));
+    o.apply_spec("lmr_history_div=300,lmr_captures=on").unwrap();
+    let p = o.search_params();
+    assert_eq!((p.lmr_history_div, p.lmr_captures), (300, true));
+    assert!(o.describe().contains(",lmr_history_div=300,lmr_captures=on"), "{}", o.describe());
+    o.set("LMRHistoryDiv", "0").unwrap();
+    assert_eq!(o.search_params().lmr_history_div, 0);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::Captured;
+use piebot::search::score::Score;
+use piebot::uci::{MatchSignals, UciEngine};
+
+#[test]
+fn streaks_trigger_resign_and_draw_offers() {
+    let mut s = MatchSignals::default();
+    (s.enabled, s.resign_moves, s.draw_moves, s.draw_min_move) = (true, 2, 3, 30);
+    assert_eq!(s.record(Score::cp(-900), 20), None);
+    assert_eq!(s.record(Score::cp(-300), 21), None, "a better score breaks the streak");
+    assert_eq!(s.record(Score::cp(-900), 22), None);
+    assert_eq!(s.record(Score::mated_in(5), 23), Some("info string resign"));
+    assert_eq!(s.record(Score::cp(-1200), 24), Some("info string resign"), "repeated while it lasts");
+
+    s.new_game();
+    assert_eq!(s.record(Score::cp(0), 10), None);
+    assert_eq!(s.record(Score::cp(0), 11), None, "before DrawMinMove");
+    for m in 30..32 { assert_eq!(s.record(Score::cp(5), m), None); }
+    assert_eq!(s.record(Score::cp(-10), 32), Some("info string offer draw"));
+    assert_eq!(s.record(Score::cp(11), 33), None);
+
+    let mut off = MatchSignals::default();
+    for _ in 0..10 { assert_eq!(off.record(Score::cp(-5000), 50), None); }
+}
+
+fn session(backend: &str, setup: &str, fen: &str) -> Vec<String> {
+    let out = Captured::default();
+    let mut e = UciEngine::new();
+    e.run_loop(format!("setoption name Backend value {}\n{}position fen {}\ngo depth 4\nquit\n", backend, setup, fen).as_bytes(), out.clone());
+    out.lines()
+}
+
+#[test]
+fn uci_says_resign_and_offer_draw_before_bestmove() {
+    let lost = "k7/8/8/8/8/8/1q6/3K4 w - - 0 50";
+    let dead = "8/8/4k3/8/8/4K3/8/8 w - - 0 60";
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let on = "setoption name MatchSignals value true\nsetoption name ResignMoves value 1\nsetoption name DrawMoves value 1\n";
+        let lines = session(backend, on, lost);
+        let n = lines.len();
+        assert_eq!(lines[n - 2], "info string resign", "{}: {:?}", backend, lines);
+        assert!(lines[n - 1].starts_with("bestmove "));
+
+        let lines = session(backend, on, dead);
+        assert_eq!(lines[lines.len() - 2], "info string offer draw", "{}: {:?}", backend, lines);
+
+        let lines = session(backend, "", lost);
+        assert!(!lines.iter().any(|l| l == "info string resign"), "off by default ({}): {:?}", backend, lines);
+    }
+}
+
+#[test]
+fn signal_options_are_listed() {
+    let names: Vec<String> = UciEngine::new().uci_options().iter().map(|o| o.name.to_string()).collect();
+    for n in ["MatchSignals", "ResignScore", "ResignMoves", "DrawScore", "DrawMoves", "DrawMinMove"] {
+        assert!(names.iter().any(|x| x == n), "{} missing: {:?}", n, names);
+    }
+}
@@ -0,0 +1,59 @@
+use cozy_chess::{Board, Move};
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::score::Score;
+use piebot::search::tt::{Bound, Entry};
+use piebot::search::zobrist;
+
+fn params(depth: u32) -> SearchParams {
+    SearchParams { depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, ..Default::default() }
+}
+
+#[test]
+fn clean_search_rejects_no_tt_moves() {
+    let b = Board::default();
+    let mut s = Searcher::default();
+    let r = s.search_with_params(&b, params(5));
+    assert!(r.bestmove.is_some());
+    // The counters are only kept with the search-stats feature
+    if !cfg!(feature = "search-stats") { return; }
+    let mc = s.move_checks();
+    assert!(mc.tt_moves > 0 && mc.killers > 0, "{:?}", mc);
+    assert_eq!(mc.tt_rejected, 0);
+    // Killers from sibling positions are often not legal here
+    assert!(mc.killers_rejected <= mc.killers);
+}
+
+#[test]
+fn aliased_tt_move_is_rejected_and_counted() {
+    let b = Board::default();
+    let mut after = b.clone();
+    after.play("e2e4".parse::<Move>().unwrap());
+    let mut s = Searcher::default();
+    // An entry for 1.e4 whose move belongs to another position (White's e2e4 again)
+    let bogus: Move = "e2e4".parse().unwrap();
+    assert!(!after.is_legal(bogus));
+    s.tt().put(Entry { key: zobrist::compute(&after), depth: 0, score: Score::cp(0), best: Some(bogus), bound: Bound::Upper, gen: 0 });
+    let r = s.search_with_params(&b, params(3));
+    let bm: Move = r.bestmove.unwrap().parse().unwrap();
+    assert!(b.is_legal(bm));
+    if cfg!(feature = "search-stats") { assert!(s.move_checks().tt_rejected >= 1, "{:?}", s.move_checks()); }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_rejects_aliased_tt_move() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    use piebot::search::tt_pleco::{Bound as PBound, Entry as PEntry};
+    let mut b = pleco::Board::start_pos();
+    // Black's e7e5 is no move for White
+    let mut black = b.clone();
+    black.apply_move(black.generate_moves().iter().copied().find(|m| m.stringify() == "e2e4").unwrap());
+    let bogus = black.generate_moves().iter().copied().find(|m| m.stringify() == "e7e5").unwrap();
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(16);
+    s.tt().put(PEntry { key: b.zobrist(), depth: 0, score: Score::cp(0), best: Some(bogus), bound: PBound::Upper, gen: 0 });
+    let (bm, _, _) = s.search_movetime(&mut b, 10_000, 3);
+    assert!(b.generate_moves().iter().any(|&m| Some(m) == bm));
+    let mc = s.move_checks();
+    if cfg!(feature = "search-stats") { assert!(mc.tt_rejected >= 1 && mc.tt_moves > mc.tt_rejected, "{:?}", mc); }
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::Captured;
+use cozy_chess::Board;
+use piebot::search::alphabeta::{AnalysisStyle, SearchParams, Searcher};
+
+fn params(depth: u32) -> SearchParams {
+    SearchParams { depth, use_tt: true, order_captures: true, use_history: true, ..Default::default() }
+}
+
+#[test]
+fn exclusion_multipv_returns_distinct_sorted_lines() {
+    let board = Board::default();
+    let mut s = Searcher::default();
+    let lines = s.search_multipv(&board, params(3), 4, AnalysisStyle::Exclusion);
+    assert_eq!(lines.len(), 4);
+    let mut moves: Vec<_> = lines.iter().map(|r| r.bestmove.clone().unwrap()).collect();
+    assert!(lines.windows(2).all(|w| w[0].score >= w[1].score));
+    moves.sort();
+    moves.dedup();
+    assert_eq!(moves.len(), 4, "each line must be a different root move");
+}
+
+#[test]
+fn searchmoves_restricts_the_root_and_multipv_caps_at_legal_moves() {
+    // Only two legal king moves in this position
+    let board = Board::from_fen("7k/8/6Q1/8/8/8/8/K7 b - - 0 1", false).unwrap();
+    let mut s = Searcher::default();
+    let lines = s.search_multipv(&board, params(2), 5, AnalysisStyle::Scored);
+    assert!(lines.len() <= 2);
+
+    let start = Board::default();
+    let h3 = {
+        let mut found = None;
+        start.generate_moves(|ml| { for m in ml { if format!("{}", m) == "h2h3" { found = Some(m); } } found.is_some() });
+        found.unwrap()
+    };
+    s.set_search_moves(Some(vec![h3]));
+    let r = s.search_with_params(&start, params(3));
+    assert_eq!(r.bestmove.as_deref(), Some("h2h3"));
+}
+
+#[test]
+fn scored_lines_count_each_child_search_once() {
+    // Kxb2 is the only move, so the lines' nodes are that one child search's
+    let board = Board::from_fen("k7/8/8/8/8/3p4/1q6/K7 w - - 0 1", false).unwrap();
+    let lines = Searcher::default().search_multipv(&board, params(4), 3, AnalysisStyle::Scored);
+    assert_eq!(lines.len(), 1);
+    let mut child = board.clone();
+    child.play(lines[0].bestmove.as_deref().unwrap().parse().unwrap());
+    assert_eq!(lines[0].nodes, Searcher::default().search_with_params(&child, params(3)).nodes);
+}
+
+#[test]
+fn scored_lines_search_children_without_the_root_restriction() {
+    let start = Board::default();
+    let mut s = Searcher::default();
+    let mut some = Vec::new();
+    start.generate_moves(|ml| { some.extend(ml.into_iter().filter(|m| ["e2e4", "d2d4"].contains(&format!("{}", m).as_str()))); false });
+    s.set_search_moves(Some(some));
+    let lines = s.search_multipv(&start, params(3), 5, AnalysisStyle::Scored);
+    assert_eq!(lines.len(), 2);
+    // Restricted to e2e4/d2d4, black would have no legal reply: the scores must be
+    // those of an unrestricted searcher
+    let all = Searcher::default().search_multipv(&start, params(3), 40, AnalysisStyle::Scored);
+    for r in &lines {
+        let free = all.iter().find(|a| a.bestmove == r.bestmove).unwrap();
+        assert_eq!(r.score, free.score, "{:?}", r.bestmove);
+    }
+    let r = s.search_with_params(&start, params(2));
+    assert!(["e2e4", "d2d4"].contains(&r.bestmove.as_deref().unwrap()), "the restriction is restored");
+}
+
+#[test]
+fn san_round_trips_through_pgn_parser() {
+    use piebot::io::pgn::{move_to_san, san_to_move};
+    let board = Board::from_fen("r3k2r/8/8/8/8/2N3N1/8/R3K2R w KQkq - 0 1", false).unwrap();
+    let mut all = Vec::new();
+    board.generate_moves(|ml| { all.extend(ml); false });
+    for m in all {
+        let san = move_to_san(&board, m);
+        assert_eq!(san_to_move(&board, &san), Some(m), "{}", san);
+    }
+}
+
+#[test]
+fn root_score_table_keeps_every_move_per_iteration() {
+    use piebot::search::tt::Bound;
+    let board = Board::default();
+    let mut s = Searcher::default();
+    s.set_track_root_scores(true);
+    let res = s.search_with_params(&board, params(3));
+    let table = s.root_score_table();
+    assert_eq!(table.iter().map(|it| it.depth).collect::<Vec<_>>(), vec![1, 2, 3]);
+    for it in table {
+        assert_eq!(it.scores.len(), 20);
+        assert!(it.scores.iter().any(|r| r.bound == Bound::Exact));
+    }
+    let last = table.last().unwrap();
+    let best = last.scores.iter().max_by_key(|r| r.score_cp).unwrap();
+    assert_eq!(best.score_cp, res.score.value());
+
+    // Tracking off: nothing is kept
+    s.set_track_root_scores(false);
+    s.search_with_params(&board, params(2));
+    assert!(s.root_score_table().is_empty());
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_exclusion_multipv_returns_distinct_sorted_lines() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    let mut b = pleco::Board::start_pos();
+    let lines = s.search_multipv(&mut b, 0, 3, 4);
+    assert_eq!(lines.len(), 4);
+    assert!(lines.windows(2).all(|w| w[0].score >= w[1].score));
+    let mut moves: Vec<_> = lines.iter().map(|r| r.bestmove.clone().unwrap()).collect();
+    moves.sort();
+    moves.dedup();
+    assert_eq!(moves.len(), 4, "each line must be a different root move");
+    // Kg8 and Kh7 only
+    assert_eq!(s.search_multipv(&mut pleco::Board::from_fen("7k/8/5Q2/8/8/8/8/K7 b - - 0 1").unwrap(), 0, 2, 5).len(), 2, "capped at the legal moves");
+    let h3 = b.generate_moves().iter().copied().find(|m| m.to_string() == "h2h3").unwrap();
+    s.set_search_moves(Some(vec![h3]));
+    assert_eq!(s.search_result(&mut b, 0, 3).bestmove.as_deref(), Some("h2h3"));
+}
+
+#[test]
+fn uci_reports_one_info_multipv_line_per_variation_on_both_backends() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let out = Captured::default();
+        let script = format!("setoption name Backend value {}\nsetoption name MultiPV value 3\nposition startpos moves e2e4\ngo depth 3\nquit\n", backend);
+        piebot::uci::UciEngine::new().run_loop(script.as_bytes(), out.clone());
+        let text = out.text();
+        let lines: Vec<&str> = text.lines().filter(|l| l.starts_with("info multipv ")).collect();
+        assert_eq!(lines.iter().map(|l| l.split_whitespace().nth(2).unwrap()).collect::<Vec<_>>(), ["1", "2", "3"], "{}: {}", backend, text);
+        // First moves: the rest of each pv is the line after it
+        let mut pvs: Vec<&str> = lines.iter().map(|l| l.split(" pv ").nth(1).unwrap().split_whitespace().next().unwrap()).collect();
+        let best = text.lines().find_map(|l| l.strip_prefix("bestmove ")).unwrap();
+        assert_eq!(pvs[0], best, "{}", backend);
+        pvs.sort();
+        pvs.dedup();
+        assert_eq!(pvs.len(), 3, "{}", backend);
+    }
+}
+
+#[test]
+fn uci_searchmoves_restricts_the_root_on_both_backends() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        for extra in ["", "setoption name MultiPV value 2\n", "setoption name Skill Level value 5\n"] {
+            let out = Captured::default();
+            let script = format!("setoption name Backend value {}\n{}position startpos\ngo depth 3 searchmoves h2h3 a2a4\nquit\n", backend, extra);
+            piebot::uci::UciEngine::new().run_loop(script.as_bytes(), out.clone());
+            let text = out.text();
+            let best = text.lines().find_map(|l| l.strip_prefix("bestmove ")).unwrap();
+            assert!(["h2h3", "a2a4"].contains(&best.split_whitespace().next().unwrap()), "{} {:?}: {}", backend, extra, text);
+        }
+    }
+}
@@ -1,33 +1,7 @@
-use cozy_chess::Board;
-use std::fs::File;
-use std::io::Write;
+mod common;
 
-fn write_quant_file(path: &str, input_dim: u32, hidden_dim: u32) {
-    let mut f = File::create(path).unwrap();
-    // magic PIENNQ01
-    f.write_all(b"PIENNQ01").unwrap();
-    // version
-    f.write_all(&1u32.to_le_bytes()).unwrap();
-    // dims
-    f.write_all(&input_dim.to_le_bytes()).unwrap();
-    f.write_all(&hidden_dim.to_le_bytes()).unwrap();
-    f.write_all(&1u32.to_le_bytes()).unwrap(); // out=1
-    // scales
-    f.write_all(&1.0f32.to_le_bytes()).unwrap();
-    f.write_all(&1.0f32.to_le_bytes()).unwrap();
-    // w1 random small values in [-4,4]
-    let mut seed = 20240601u64;
-    let mut next = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1); ((seed >> 32) as i32 % 9 - 4) as i8 };
-    for _ in 0..(input_dim as usize * hidden_dim as usize) {
-        f.write_all(&[next() as u8]).unwrap();
-    }
-    // b1 zeros
-    for _ in 0..hidden_dim { f.write_all(&0i16.to_le_bytes()).unwrap(); }
-    // w2 random small
-    for _ in 0..hidden_dim { f.write_all(&[next() as u8]).unwrap(); }
-    // b2 zero
-    f.write_all(&0i16.to_le_bytes()).unwrap();
-}
+use common::write_quant_file;
+use cozy_chess::Board;
 
 #[test]
 fn halfkp_active_count_startpos() {
@@ -47,9 +21,7 @@ fn halfkp_incremental_matches_full_over_sequence() {
     use cozy_chess::Move;
 
     let path = "target/halfkp_test.nnue";
-    let input_dim = halfkp_dim() as u32;
-    let hidden_dim = 8u32;
-    write_quant_file(path, input_dim, hidden_dim);
+    write_quant_file(path, halfkp_dim(), 8, 20240601);
     let model = QuantNnue::load_quantized(path).unwrap();
     let mut net = QuantNetwork::new(model);
     let mut b = Board::default();
@@ -72,3 +44,50 @@ fn halfkp_incremental_matches_full_over_sequence() {
         net.refresh(&b);
     }
 }
+
+// Depth-first walk pushing every child and checking the stack against a full
+// recompute on the way down and after each pop
+fn walk(net: &mut piebot::eval::nnue::network::QuantNetwork, b: &Board, depth: u32, visited: &mut u32) {
+    let here = net.eval_current();
+    assert_eq!(here, net.eval_full(b), "stack out of step at {}", b);
+    *visited += 1;
+    if depth == 0 { return; }
+    let mut moves = Vec::new();
+    b.generate_moves(|ml| { moves.extend(ml); false });
+    // A few moves per node keep the tree small; kings and captures show up along the line
+    for m in moves.into_iter().step_by(5) {
+        let mut child = b.clone();
+        child.play(m);
+        net.push(&child);
+        walk(net, &child, depth - 1, visited);
+        net.pop();
+        assert_eq!(net.eval_current(), here, "pop did not restore {} after {}", b, m);
+    }
+}
+
+#[test]
+fn halfkp_push_pop_stack_matches_full_in_a_tree() {
+    use piebot::eval::nnue::loader::QuantNnue;
+    use piebot::eval::nnue::network::QuantNetwork;
+    use piebot::eval::nnue::features::halfkp_dim;
+
+    let path = "target/halfkp_stack.nnue";
+    write_quant_file(path, halfkp_dim(), 8, 20240601);
+    let mut net = QuantNetwork::new(QuantNnue::load_quantized(path).unwrap());
+    // Castling rights, a capture and a king walk within reach
+    let b: Board = "r3k2r/pppq1ppp/2n2n2/3pp3/3PP3/2N2N2/PPPQ1PPP/R3K2R w KQkq - 0 8".parse().unwrap();
+    net.refresh(&b);
+    let mut visited = 0;
+    walk(&mut net, &b, 3, &mut visited);
+    assert!(visited > 100, "{}", visited);
+    assert_eq!(net.depth(), 0);
+
+    // A forked helper starts at our position and moves on without touching us
+    let root = net.eval_current();
+    let mut helper = net.fork();
+    let mut child = b.clone();
+    child.play("e1a1".parse().unwrap()); // O-O-O in cozy notation
+    helper.push(&child);
+    assert_eq!(helper.eval_current(), helper.eval_full(&child));
+    assert_eq!(net.eval_current(), root);
+}
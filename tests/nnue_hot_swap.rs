@@ -0,0 +1,54 @@
+mod common;
+
+use common::bias_model;
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use std::sync::Arc;
+
+#[test]
+fn swapped_network_is_used_by_the_next_search() {
+    let b = Board::default();
+    let mut s = Searcher::builder().use_nnue(true).nnue_quant(bias_model(50)).build().unwrap();
+    let params = SearchParams { depth: 3, use_tt: true, threads: 2, ..Default::default() };
+    let before = s.search_with_params(&b, params).score.value();
+    let old = s.nnue_quant_model().unwrap().clone();
+    let cp_old = s.qsearch_eval_cp(&b);
+
+    assert!(s.tt().len() > 0);
+    s.set_nnue_quant_model(bias_model(-80));
+    assert_eq!(s.tt().len(), 0);
+    assert!(!Arc::ptr_eq(&old, s.nnue_quant_model().unwrap()));
+    assert_ne!(s.qsearch_eval_cp(&b), cp_old);
+    // The TT was cleared, so the new search cannot return the old network's score
+    let after = s.search_with_params(&b, params).score.value();
+    assert_ne!(before, after);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_searcher_swaps_network() {
+    use piebot::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher};
+    use piebot::search::score::Score;
+    use piebot::search::tt_pleco::{Bound, Entry};
+    let mut s = PlecoSearcher::default();
+    s.set_eval_mode(PlecoEvalMode::Nnue);
+    let shared = Arc::new(bias_model(50));
+    s.set_nnue_quant_model(shared.clone());
+    assert!(Arc::ptr_eq(&shared, s.nnue_quant_model().unwrap()));
+    s.set_tt_capacity_mb(16);
+    let mut pb = pleco::Board::start_pos();
+    s.tt().put(Entry { key: pb.zobrist(), depth: 5, score: Score::cp(50), best: None, bound: Bound::Exact, gen: 0 });
+    s.set_nnue_quant_model(bias_model(-80));
+    assert_eq!(s.nnue_quant_model().unwrap().b2, vec![-80]);
+    assert!(s.tt().get(pb.zobrist()).is_none(), "the old network's entries are gone");
+    let (mv, _, _) = s.search_movetime(&mut pb, 50, 2);
+    assert!(mv.is_some());
+}
+
+#[test]
+fn bad_network_file_keeps_engine_usable() {
+    let mut e = piebot::uci::UciEngineCozy::new();
+    assert!(e.handle_line("setoption name NNUEQuantFile value /nonexistent/net.nnue"));
+    assert!(e.handle_line("position startpos"));
+    assert!(e.handle_line("go depth 1"));
+}
@@ -0,0 +1,98 @@
+use cozy_chess::Board;
+use piebot::eval::nnue::features::HalfKpA;
+use piebot::eval::nnue::loader::QuantNnue;
+use piebot::eval::nnue::network::QuantNetwork;
+use piebot::eval::nnue::{Nnue, MAX_NNUE_CP};
+
+const PIECE_PAWNS: [f32; 12] = [1.0, 3.0, 3.0, 5.0, 9.0, 0.0, -1.0, -3.0, -3.0, -5.0, -9.0, 0.0];
+
+// Dense 12-input net: identity hidden layer, material in pawns at the head
+fn dense_material(version: u32, scale: f32, bias: f32) -> Vec<u8> {
+    let mut f = b"PIENNUE1".to_vec();
+    for v in [version, 12, 12, 1] { f.extend_from_slice(&v.to_le_bytes()); }
+    if version >= 2 { f.extend_from_slice(&scale.to_le_bytes()); }
+    for j in 0..12 { for i in 0..12 { f.extend_from_slice(&(if i == j { 1f32 } else { 0f32 }).to_le_bytes()); } }
+    for _ in 0..12 { f.extend_from_slice(&0f32.to_le_bytes()); }
+    for w in PIECE_PAWNS { f.extend_from_slice(&w.to_le_bytes()); }
+    f.extend_from_slice(&bias.to_le_bytes());
+    f
+}
+
+// Quant HalfKP net with a single zero hidden unit: the output is the bias
+fn quant_bias(version: u32, scale: f32, b2: i16) -> Vec<u8> {
+    let mut f = b"PIENNQ01".to_vec();
+    for v in [version, HalfKpA.dim() as u32, 1, 1] { f.extend_from_slice(&v.to_le_bytes()); }
+    f.extend_from_slice(&1f32.to_le_bytes());
+    f.extend_from_slice(&1f32.to_le_bytes());
+    if version >= 2 { f.extend_from_slice(&scale.to_le_bytes()); }
+    f.extend(std::iter::repeat(0u8).take(HalfKpA.dim()));
+    f.extend_from_slice(&0i16.to_le_bytes());
+    f.push(0);
+    f.extend_from_slice(&b2.to_le_bytes());
+    f
+}
+
+#[test]
+fn scaled_dense_net_speaks_pst_centipawns() {
+    let nn = Nnue::from_reader(&dense_material(2, 100.0, 0.0)[..]).unwrap();
+    assert_eq!(nn.meta.output_scale, 100.0);
+    let start = Board::default();
+    let up_knight: Board = "r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".parse().unwrap();
+    assert_eq!(nn.evaluate(&start), 0);
+    assert_eq!(nn.evaluate(&up_knight), 300);
+    // Same units as the PST eval: within half a pawn for material-only differences
+    let pst = piebot::search::eval::eval_cp(&up_knight);
+    assert!((nn.evaluate(&up_knight) - pst).abs() < 50 + pst.abs() / 4, "nnue 300 vs pst {}", pst);
+    // Without the scale the same weights read in pawns
+    let raw = Nnue::from_reader(&dense_material(1, 100.0, 0.0)[..]).unwrap();
+    assert_eq!(raw.meta.output_scale, 1.0);
+    assert_eq!(raw.evaluate(&up_knight), 3);
+}
+
+#[test]
+fn outputs_never_reach_the_mate_range() {
+    let b = Board::default();
+    let huge = Nnue::from_reader(&dense_material(2, 100.0, 1e9)[..]).unwrap();
+    assert_eq!(huge.evaluate(&b), MAX_NNUE_CP);
+    let low = Nnue::from_reader(&dense_material(2, 100.0, -1e9)[..]).unwrap();
+    assert_eq!(low.evaluate(&b), -MAX_NNUE_CP);
+    let nan = Nnue::from_reader(&dense_material(2, f32::NAN, 0.0)[..]).unwrap();
+    assert_eq!(nan.evaluate(&b), 0);
+    let q = QuantNetwork::new(QuantNnue::from_reader(&quant_bias(2, 1000.0, i16::MAX)[..]).unwrap());
+    assert_eq!(q.eval_full(&b), MAX_NNUE_CP);
+}
+
+#[test]
+fn quant_scale_applies_to_full_and_incremental_eval() {
+    let b = Board::default();
+    let model = QuantNnue::from_reader(&quant_bias(2, 0.5, 50)[..]).unwrap();
+    let fp = model.fingerprint();
+    let mut q = QuantNetwork::new(model);
+    q.refresh(&b);
+    assert_eq!((q.eval_full(&b), q.eval_current()), (25, 25));
+    let v1 = QuantNnue::from_reader(&quant_bias(1, 0.5, 50)[..]).unwrap();
+    assert_eq!(v1.meta.output_scale, 1.0);
+    assert_eq!(QuantNetwork::new(v1).eval_full(&b), 50);
+    // The scale is part of the network's identity
+    assert_ne!(fp, QuantNnue::from_reader(&quant_bias(2, 1.0, 50)[..]).unwrap().fingerprint());
+}
+
+#[test]
+fn quant_writer_round_trips_both_versions() {
+    let b = Board::default();
+    for (version, bytes) in [(2, quant_bias(2, 0.5, 50)), (1, quant_bias(1, 0.5, 50))] {
+        let model = QuantNnue::from_reader(&bytes[..]).unwrap();
+        let mut out = Vec::new();
+        model.write_to(&mut out).unwrap();
+        assert_eq!(out, bytes, "v{} bytes", version);
+        let back = QuantNnue::from_reader(&out[..]).unwrap();
+        assert_eq!((back.meta.version, back.meta.output_scale, back.fingerprint()), (version, model.meta.output_scale, model.fingerprint()));
+    }
+    // Through a file, as the UCI loader reads it
+    let path = std::env::temp_dir().join(format!("piebot_quant_v2_{}.nnue", std::process::id()));
+    QuantNnue::from_reader(&quant_bias(2, 0.5, 50)[..]).unwrap().save_quantized(&path).unwrap();
+    let loaded = QuantNnue::load_quantized(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.meta.output_scale, 0.5);
+    assert_eq!(QuantNetwork::new(loaded).eval_full(&b), 25);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::bias_model;
+use cozy_chess::Board;
+use piebot::eval::nnue::network::SmallNet;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::options::EngineOptions;
+use std::time::Duration;
+
+// Kings and pawns only: phase 0
+const PAWN_ENDING: &str = "4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1";
+
+fn dual(max_phase: u32) -> Searcher {
+    Searcher::builder().use_nnue(true).nnue_quant(bias_model(50)).nnue_small(bias_model(-80), max_phase).build().unwrap()
+}
+
+#[test]
+fn small_net_covers_phases_up_to_its_threshold() {
+    let sn = SmallNet::new(bias_model(0), 6);
+    assert!(sn.covers(0) && sn.covers(6));
+    assert!(!sn.covers(7) && !sn.covers(24));
+}
+
+#[test]
+fn endgames_are_evaluated_by_the_small_net() {
+    let s = dual(6);
+    let start = Board::default();
+    let ending = Board::from_fen(PAWN_ENDING, false).unwrap();
+    assert!(s.static_eval(&start) > 0, "main net in the middlegame");
+    assert!(s.static_eval(&ending) < 0, "small net in the endgame");
+    // Below every position's phase: the main net everywhere
+    let s = Searcher::builder().use_nnue(true).nnue_quant(bias_model(50)).build().unwrap();
+    assert!(s.static_eval(&ending) > 0);
+}
+
+#[test]
+fn small_net_only_takes_every_position() {
+    let mut s = dual(0);
+    let start = Board::default();
+    assert!(s.static_eval(&start) > 0);
+    s.set_small_net_only(true);
+    assert!(s.static_eval(&start) < 0);
+    s.set_small_net_only(false);
+    assert!(s.static_eval(&start) > 0, "cached small-net scores were dropped");
+    // Search scores in the TT came from one network too
+    s.search_with_params(&start, SearchParams { depth: 2, use_tt: true, ..SearchParams::default() });
+    assert!(s.tt_probe(&start).is_some());
+    s.set_small_net_only(true);
+    assert!(s.tt_probe(&start).is_none(), "TT kept the main net's scores");
+}
+
+#[test]
+fn small_net_needs_nnue() {
+    assert!(Searcher::builder().nnue_small(bias_model(0), 6).build().is_err());
+}
+
+#[test]
+fn small_net_options() {
+    let mut o = EngineOptions::default();
+    assert_eq!(o.nnue_small_file, None);
+    assert!(!o.describe().contains("nnue_small"));
+    o.set("SmallNetPhase", "10").unwrap();
+    o.set("SmallNetTime", "5000").unwrap();
+    o.set("nnue_small", "small.nnue").unwrap();
+    assert_eq!((o.small_net_phase, o.small_net_time_ms), (10, 5000));
+    assert!(o.describe().contains(",nnue_small=small.nnue,small_net_phase=10,small_net_time=5000"));
+    assert!(o.small_net_only(Some(Duration::from_millis(4999))));
+    assert!(!o.small_net_only(Some(Duration::from_secs(60))));
+    assert!(!o.small_net_only(None), "no clock, no switch");
+    o.set("SmallNetTime", "0").unwrap();
+    assert!(!o.small_net_only(Some(Duration::from_millis(1))));
+    // The file only loads with the NNUE eval
+    o.set("eval", "nnue").unwrap();
+    o.set("nnue_quant", "/nonexistent/main.nnue").unwrap();
+    assert!(o.build_searcher().is_err());
+}
+
+#[test]
+fn bad_small_net_file_keeps_engine_usable() {
+    let mut e = piebot::uci::UciEngineCozy::new();
+    assert!(e.handle_line("setoption name NNUESmallFile value /nonexistent/small.nnue"));
+    assert!(e.handle_line("setoption name SmallNetPhase value 8"));
+    assert!(e.handle_line("position startpos"));
+    assert!(e.handle_line("go depth 1"));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_endgames_are_evaluated_by_the_small_net() {
+    use piebot::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher};
+    let mut s = PlecoSearcher::default();
+    s.set_eval_mode(PlecoEvalMode::Nnue);
+    s.set_nnue_quant_model(bias_model(50));
+    s.set_nnue_small(Some(SmallNet::new(bias_model(-80), 6)));
+    let mut start = pleco::Board::start_pos();
+    let mut ending = pleco::Board::from_fen(PAWN_ENDING).unwrap();
+    assert!(s.static_eval(&mut start) > 0);
+    assert!(s.static_eval(&mut ending) < 0);
+    s.set_small_net_only(true);
+    assert!(s.static_eval(&mut start) < 0);
+}
@@ -0,0 +1,64 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{NodeType, SearchParams, Searcher};
+use piebot::search::options::EngineOptions;
+use piebot::search::score::Score;
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const MIDDLE: &str = "r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 0 1";
+// Legal's mate finish: Nf6+ gxf6 Bxf7#
+const MATE2: &str = "r2qkb1r/pp2nppp/3p4/2pNN1B1/2BnP3/3P4/PPP2PPP/R2bK2R w KQkq - 1 1";
+
+fn search(fen: &str, depth: u32, node_types: bool) -> (Option<String>, Score, u64) {
+    let b = Board::from_fen(fen, false).unwrap();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, node_types, ..Default::default() };
+    let r = s.search_with_params(&b, p);
+    (r.bestmove, r.score, r.nodes)
+}
+
+#[test]
+fn children_follow_the_pv_cut_all_pattern() {
+    assert_eq!(NodeType::Pv.child(0), NodeType::Pv);
+    assert_eq!(NodeType::Pv.child(1), NodeType::Cut);
+    assert_eq!(NodeType::Cut.child(0), NodeType::All);
+    assert_eq!(NodeType::Cut.child(5), NodeType::Cut);
+    assert_eq!(NodeType::All.child(0), NodeType::Cut);
+    assert_eq!(NodeType::All.child(3), NodeType::Cut);
+}
+
+#[test]
+fn node_types_shrink_the_tree_and_keep_mates() {
+    for fen in [START, MIDDLE] {
+        let (on, off) = (search(fen, 6, true), search(fen, 6, false));
+        assert!(on.0.is_some());
+        assert!(on.2 < off.2, "{}: {} nodes with node types, {} without", fen, on.2, off.2);
+    }
+    let (bm, score, _) = search(MATE2, 4, true);
+    assert_eq!(bm.as_deref(), Some("d5f6"));
+    assert_eq!(score.mate_moves(), Some(2));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_finds_the_same_mate_with_node_types() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_node_types(true);
+    let mut b = pleco::Board::from_fen(MATE2).unwrap();
+    let (bm, score, _) = s.search_movetime(&mut b, 10_000, 4);
+    assert_eq!(bm.map(|m| m.stringify()).as_deref(), Some("d5f6"));
+    assert_eq!(Score::from_value(score).mate_moves(), Some(2));
+}
+
+#[test]
+fn node_types_option() {
+    let mut o = EngineOptions::default();
+    assert!(!o.search_params().node_types);
+    assert!(!o.describe().contains("node_types"));
+    o.set("NodeTypes", "true").unwrap();
+    assert!(o.search_params().node_types);
+    let mut back = EngineOptions::default();
+    back.apply_spec(&o.describe()).unwrap();
+    assert_eq!(back, o);
+    assert!(EngineOptions::default().uci_option_lines().contains(&"option name NodeTypes type check default false".to_string()));
+}
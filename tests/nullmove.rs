@@ -14,7 +14,7 @@ fn nullmove_reduces_nodes_midgame() {
     let mut s2 = Searcher::default();
     let mut p2 = p1; p2.use_nullmove = true;
     let r2 = s2.search_with_params(&b, p2);
-    assert!((r2.score_cp - r1.score_cp).abs() <= 100, "nullmove changed score too much: {} vs {}", r2.score_cp, r1.score_cp);
+    assert!((r2.score.value() - r1.score.value()).abs() <= 100, "nullmove changed score too much: {} vs {}", r2.score.value(), r1.score.value());
     assert!(r2.nodes <= r1.nodes, "nullmove did not reduce nodes: {} vs {}", r2.nodes, r1.nodes);
 }
 
@@ -33,5 +33,43 @@ fn nullmove_disabled_in_check() {
     let mut s2 = Searcher::default();
     let mut p2 = p1; p2.use_nullmove = true;
     let r2 = s2.search_with_params(&b, p2);
-    assert_eq!(r2.score_cp, r1.score_cp, "nullmove in check should not change score");
+    assert_eq!(r2.score.value(), r1.score.value(), "nullmove in check should not change score");
+}
+
+#[test]
+fn nullmove_searches_the_position_after_the_pass() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    // Nxe5 wins a pawn; a "pass" that left White to move again failed high on every
+    // reply and kept the first move searched
+    let b = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 1 2", false).unwrap();
+    let p = SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, threads: 1, use_nullmove: true, ..Default::default() };
+    let on = Searcher::default().search_with_params(&b, p);
+    let off = Searcher::default().search_with_params(&b, SearchParams { use_nullmove: false, ..p });
+    assert_eq!(on.bestmove.as_deref(), Some("f3e5"));
+    assert_eq!(on.score, off.score);
+}
+
+#[test]
+fn nullmove_mates_match_the_plain_search() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    // A pass that walks into a mate proves nothing about the position before it, so
+    // with null move on, mate scores must still be the plain search's
+    for fen in ["r5k1/5ppp/8/8/8/8/1R6/1R4K1 w - - 0 1", "8/8/8/8/8/2k5/8/K1q5 w - - 0 1", "6k1/5ppp/8/8/8/8/5PPP/3R2K1 b - - 0 1"] {
+        let b = Board::from_fen(fen, false).unwrap();
+        let p = SearchParams { depth: 7, use_tt: true, order_captures: true, use_history: true, threads: 1, use_nullmove: true, use_aspiration: true, aspiration_window_cp: 10, ..Default::default() };
+        let on = Searcher::default().search_with_params(&b, p);
+        let off = Searcher::default().search_with_params(&b, SearchParams { use_nullmove: false, ..p });
+        assert_eq!(on.score, off.score, "{}", fen);
+    }
+}
+
+#[test]
+fn nullmove_does_not_repeat_positions_across_the_pass() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    // With null move on, this search reaches no real repetition; the draw it used to
+    // report came from Black triangulating back to a position from before a pass
+    let b = Board::from_fen("8/3K4/8/5k2/4p3/8/3P4/8 w - - 0 1", false).unwrap();
+    let p = SearchParams { depth: 10, use_tt: true, order_captures: true, use_history: true, use_killers: true, threads: 1, use_nullmove: true, ..Default::default() };
+    let r = Searcher::default().search_with_params(&b, p);
+    assert_ne!(r.score.value(), 0);
 }
@@ -0,0 +1,89 @@
+use cozy_chess::{Board, Color};
+use piebot::search::alphabeta::Searcher;
+use piebot::selfplay::openings::{OpeningFilter, OpeningGenerator, OpeningKind, OpeningMix, OpeningSource};
+use piebot::selfplay::{flatten_game_to_records, generate_games, SelfPlayParams};
+
+#[test]
+fn generated_openings_pass_filter_and_alternate_colours() {
+    let mut gen = OpeningGenerator::new(7, 4, 10);
+    let boards = gen.generate_n(8);
+    assert_eq!(boards.len(), 8);
+    let mut s = Searcher::default();
+    for (i, b) in boards.iter().enumerate() {
+        let want = if i % 2 == 0 { Color::White } else { Color::Black };
+        assert_eq!(b.side_to_move(), want);
+        assert!(b.checkers().is_empty());
+        assert!(b.occupied().len() >= 20);
+        assert!(s.qsearch_eval_cp(b).abs() < 400, "wildly unbalanced opening {}", b);
+    }
+}
+
+#[test]
+fn generator_is_deterministic_per_seed() {
+    let a: Vec<String> = OpeningGenerator::new(3, 6, 6).generate_n(4).iter().map(|b| format!("{}", b)).collect();
+    let b: Vec<String> = OpeningGenerator::new(3, 6, 6).generate_n(4).iter().map(|b| format!("{}", b)).collect();
+    assert_eq!(a, b);
+    assert!(a.iter().all(|f| f != &format!("{}", Board::default())));
+}
+
+#[test]
+fn impossible_filter_gives_up() {
+    let f = OpeningFilter { min_pieces: 33, max_attempts: 5, ..Default::default() };
+    assert!(OpeningGenerator::new(1, 2, 4).with_filter(f).generate(None).is_none());
+}
+
+#[test]
+fn selfplay_records_replay_from_random_openings() {
+    let params = SelfPlayParams {
+        games: 2, max_plies: 4, threads: 1, use_engine: false, depth: 1, movetime_ms: None, seed: 11,
+        temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 6, opening_sources: Vec::new(),
+    };
+    let games = generate_games(&params);
+    assert_eq!(games[0].start.side_to_move(), Color::White);
+    assert_eq!(games[1].start.side_to_move(), Color::Black);
+    for g in &games {
+        assert_ne!(format!("{}", g.start), format!("{}", Board::default()));
+        // Every move must replay from the recorded start
+        assert_eq!(flatten_game_to_records(g).len(), g.moves.len());
+    }
+}
+
+#[test]
+fn opening_source_specs_parse_and_reject_nonsense() {
+    let s: OpeningSource = "tactical:wac.epd,w=2.5,plies=40".parse().unwrap();
+    assert_eq!(s, OpeningSource { kind: OpeningKind::Tactical("wac.epd".into()), weight: 2.5, max_plies: Some(40) });
+    assert_eq!("random:6".parse::<OpeningSource>().unwrap().kind, OpeningKind::Random(6));
+    assert_eq!("start".parse::<OpeningSource>().unwrap().weight, 1.0);
+    for bad in ["book", "random:x", "start,w=0", "start,w=-1", "start,depth=3", "nowhere:x"] {
+        assert!(bad.parse::<OpeningSource>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn curriculum_mixes_sources_by_weight_with_per_source_ply_caps() {
+    let dir = std::env::temp_dir().join(format!("piebot_curriculum_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let book = dir.join("book.epd");
+    let tactic = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+    std::fs::write(&book, format!("{} bm Qxf7#;\n", tactic)).unwrap();
+    let params = SelfPlayParams {
+        games: 40, max_plies: 6, threads: 1, use_engine: false, depth: 1, movetime_ms: None, seed: 5,
+        temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0,
+        opening_sources: vec![
+            "start,w=3".parse().unwrap(),
+            format!("tactical:{},w=1,plies=2", book.display()).parse().unwrap(),
+        ],
+    };
+    let games = generate_games(&params);
+    let (tactical, start): (Vec<_>, Vec<_>) = games.iter().partition(|g| format!("{}", g.start) == tactic);
+    assert_eq!(tactical.len() + start.len(), 40);
+    assert!(start.iter().all(|g| format!("{}", g.start) == format!("{}", Board::default()) && g.moves.len() <= 6));
+    assert!(tactical.iter().all(|g| g.moves.len() <= 2));
+    assert!((3..=20).contains(&tactical.len()), "{} tactical starts of 40", tactical.len());
+
+    let missing = SelfPlayParams { opening_sources: vec![format!("book:{}", dir.join("missing.epd").display()).parse().unwrap()], ..params };
+    assert!(OpeningMix::new(&missing.curriculum(), 1).is_err());
+    std::fs::remove_dir_all(&dir).ok();
+}
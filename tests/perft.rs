@@ -1,5 +1,5 @@
 use cozy_chess::Board;
-use piebot::perft::perft;
+use piebot::perft::{perft, perft_detail};
 
 #[test]
 fn perft_startpos_small_depths() {
@@ -10,3 +10,20 @@ fn perft_startpos_small_depths() {
     assert_eq!(perft(&b, 4), 197281);
 }
 
+
+// Chess Programming Wiki "Perft Results" tables
+#[test]
+fn perft_detail_matches_the_reference_tables() {
+    let stats = |fen: &str, depth| -> Vec<(u64, u64, u64, u64, u64, u64, u64)> {
+        let b = Board::from_fen(fen, false).unwrap();
+        perft_detail(&b, depth).iter().map(|s| (s.nodes, s.captures, s.en_passant, s.castles, s.promotions, s.checks, s.checkmates)).collect()
+    };
+    assert_eq!(stats("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 4)[2..], [(8902, 34, 0, 0, 0, 12, 0), (197281, 1576, 0, 0, 0, 469, 8)]);
+    assert_eq!(stats("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3),
+        [(48, 8, 0, 2, 0, 0, 0), (2039, 351, 1, 91, 0, 3, 0), (97862, 17102, 45, 3162, 0, 993, 1)]);
+    assert_eq!(stats("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4),
+        [(14, 1, 0, 0, 0, 2, 0), (191, 14, 0, 0, 0, 10, 0), (2812, 209, 2, 0, 0, 267, 0), (43238, 3348, 123, 0, 0, 1680, 17)]);
+    assert_eq!(stats("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 3),
+        [(6, 0, 0, 0, 0, 0, 0), (264, 87, 0, 6, 48, 10, 0), (9467, 1021, 4, 0, 120, 38, 22)]);
+    assert_eq!(perft_detail(&Board::default(), 0), []);
+}
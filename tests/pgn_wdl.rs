@@ -0,0 +1,105 @@
+use cozy_chess::Board;
+
+const PGN: &str = r#"[Event "Test"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 {develop} Nc6 3. Bb5 a6 (3... Nf6 4. O-O) 4. Ba4 Nf6 5. O-O Be7
+6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7 11. c4 c6 12. cxb5 axb5 1-0
+
+[Event "Promo"]
+[FEN "8/P6k/8/8/8/8/8/K7 w - - 0 1"]
+[Result "1/2-1/2"]
+
+1. a8=Q+ Kg6 1/2-1/2
+"#;
+
+#[test]
+fn pgn_parses_san_castling_disambiguation_and_promotion() {
+    use piebot::io::pgn::parse_pgn;
+    let games = parse_pgn(PGN);
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].result, Some(1));
+    assert_eq!(games[0].moves.len(), 24, "all SAN moves should resolve");
+    assert_eq!(games[1].tag("Event"), Some("Promo"));
+    assert_eq!(games[1].moves.len(), 2);
+    assert_eq!(games[1].result, Some(0));
+    let mut b = games[1].start.clone();
+    b.play(games[1].moves[0]);
+    assert_eq!(b.piece_on(cozy_chess::Square::A8), Some(cozy_chess::Piece::Queen));
+}
+
+#[test]
+fn wdl_model_is_monotone_and_fit_improves_likelihood() {
+    use piebot::search::wdl::{material_units, WdlModel, WdlSample};
+    assert_eq!(material_units(&Board::default()), 78);
+    let m = WdlModel::default();
+    let (w0, d0, l0) = m.wdl_permille(0, 78);
+    assert_eq!(w0 + d0 + l0, 1000);
+    assert_eq!(w0, l0, "equal position should be symmetric");
+    assert!(m.wdl_permille(300, 40).0 > m.wdl_permille(100, 40).0);
+    // Synthetic data: side ahead by 200cp wins far more often
+    let mut samples = Vec::new();
+    for i in 0..200 {
+        samples.push(WdlSample { cp: 200, material: 40, result: if i % 10 < 8 { 1 } else { 0 } });
+        samples.push(WdlSample { cp: 0, material: 40, result: if i % 10 < 8 { 0 } else if i % 2 == 0 { 1 } else { -1 } });
+    }
+    let fitted = m.fit(&samples);
+    assert!(fitted.loss(&samples) < m.loss(&samples));
+}
+
+#[test]
+fn wdl_model_json_round_trips_for_uci_loading() {
+    use piebot::search::wdl::WdlModel;
+    let m = WdlModel { a: [120.0, 80.0], b: [55.0, 30.0] };
+    let path = std::env::temp_dir().join("piebot_wdl_model_test.json");
+    std::fs::write(&path, serde_json::to_string(&m).unwrap()).unwrap();
+    assert_eq!(WdlModel::load_json(path.to_str().unwrap()).unwrap(), m);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_material_units_match_cozy() {
+    use piebot::search::wdl::{material_units, material_units_pleco};
+    let fen = "r1bq1rk1/pp3ppp/2n2n2/3p4/3P4/2NB1N2/PP3PPP/R2Q1RK1 w - - 0 10";
+    let cozy = Board::from_fen(fen, false).unwrap();
+    let pleco = pleco::Board::from_fen(fen).unwrap();
+    assert_eq!(material_units(&cozy), material_units_pleco(&pleco));
+}
+
+#[test]
+fn time_controls_classify_like_lichess() {
+    use piebot::io::pgn::TimeClass;
+    assert_eq!(TimeClass::from_time_control("15+0"), Some(TimeClass::UltraBullet));
+    assert_eq!(TimeClass::from_time_control("60+1"), Some(TimeClass::Bullet));
+    assert_eq!(TimeClass::from_time_control("180+0"), Some(TimeClass::Blitz));
+    assert_eq!(TimeClass::from_time_control("600+5"), Some(TimeClass::Rapid));
+    assert_eq!(TimeClass::from_time_control("1800+20"), Some(TimeClass::Classical));
+    assert_eq!(TimeClass::from_time_control("40/7200:3600"), Some(TimeClass::Classical));
+    assert_eq!(TimeClass::from_time_control("-"), Some(TimeClass::Correspondence));
+    assert_eq!(TimeClass::from_time_control("?"), None);
+}
+
+#[test]
+fn pgn_filter_applies_elo_speed_and_termination() {
+    use piebot::io::pgn::{parse_pgn, PgnFilter, TimeClass};
+    let game = |w: &str, b: &str, tc: &str, term: &str| format!(
+        "[Event \"Rated game\"]\n[WhiteElo \"{}\"]\n[BlackElo \"{}\"]\n[TimeControl \"{}\"]\n[Termination \"{}\"]\n[Result \"1-0\"]\n\n1. e4 e5 1-0\n\n", w, b, tc, term);
+    let text = [
+        game("2400", "2350", "600+5", "Normal"),
+        game("2400", "1800", "600+5", "Normal"),
+        game("2500", "2500", "60+0", "Normal"),
+        game("2500", "2500", "180+2", "Time forfeit"),
+        game("?", "2500", "180+2", "Normal"),
+    ].concat();
+    let games = parse_pgn(&text);
+    assert_eq!(games.len(), 5);
+    assert!(games.iter().all(|g| PgnFilter::default().accepts(g)));
+
+    let f = PgnFilter { min_elo: Some(2000), exclude_bullet: true, terminations: vec!["normal".into()], ..Default::default() };
+    let verdicts: Vec<_> = games.iter().map(|g| f.check(g)).collect();
+    assert_eq!(verdicts, vec![Ok(()), Err("elo"), Err("bullet"), Err("termination"), Err("missing elo")]);
+
+    let blitz_only = PgnFilter { time_classes: vec![TimeClass::Blitz], ..Default::default() };
+    assert_eq!(games.iter().filter(|g| blitz_only.accepts(g)).count(), 2);
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "board-pleco")]
+use pleco::Board as PBoard;
+use piebot::search::alphabeta_pleco::{PlecoSearcher, SmpMode};
+
+#[test]
+fn lazy_hybrid_reports_prewarm_and_tt_stats() {
+    let mut b = PBoard::from_fen("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w KQ - 0 8").unwrap();
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(16);
+    s.set_threads(2);
+    s.set_smp_mode(SmpMode::LazyHybrid);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let (bm, _sc, _n) = pool.install(|| s.search_movetime(&mut b, 1500, 6));
+    assert!(bm.is_some());
+    let h = s.hybrid_stats();
+    // probe counts are only kept with the search-stats feature
+    if cfg!(feature = "search-stats") { assert!(h.cold_probes + h.warm_probes > 0, "coop phase should probe the shared TT"); }
+    assert!(h.cold_hits <= h.cold_probes && h.warm_hits <= h.warm_probes);
+    if h.prewarm_runs == 0 { assert_eq!(h.warm_probes, 0); } else { assert!(h.prewarm_nodes > 0); }
+}
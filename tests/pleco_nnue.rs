@@ -0,0 +1,59 @@
+#![cfg(feature = "board-pleco")]
+mod common;
+
+use common::write_quant_file;
+use pleco::Board as PBoard;
+
+#[test]
+fn pleco_features_match_cozy_features() {
+    use piebot::eval::nnue::features::{HalfKpA, active_indices_pleco};
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let pb = PBoard::from_fen(fen).unwrap();
+    let cb = cozy_chess::Board::from_fen(fen, false).unwrap();
+    let mut a = active_indices_pleco(&pb); a.sort();
+    let mut b = HalfKpA.active_indices(&cb); b.sort();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn pleco_incremental_accumulator_matches_refresh() {
+    use piebot::eval::nnue::features::{halfkp_dim, active_indices_pleco};
+    use piebot::eval::nnue::loader::QuantNnue;
+    use piebot::eval::nnue::network::QuantNetwork;
+    let path = "target/pleco_nnue_test.nnue";
+    write_quant_file(path, halfkp_dim(), 8, 20240611);
+    let model = QuantNnue::load_quantized(path).unwrap();
+    let mut net = QuantNetwork::new(model.clone());
+    let mut fresh = QuantNetwork::new(model);
+    let mut b = PBoard::start_pos();
+    net.refresh_from_indices(&active_indices_pleco(&b));
+    let root = net.eval_current();
+    let mut changes = Vec::new();
+    // includes a capture and a king move
+    for uci in ["e2e4", "d7d5", "e4d5", "e8d7", "e1e2"] {
+        let m = b.generate_moves().iter().copied().find(|m| format!("{}", m) == uci).expect("legal");
+        b.apply_move(m);
+        changes.push(net.update_to_indices(&active_indices_pleco(&b)));
+        fresh.refresh_from_indices(&active_indices_pleco(&b));
+        assert_eq!(net.eval_current(), fresh.eval_current(), "mismatch after {}", uci);
+    }
+    while let Some(ch) = changes.pop() { net.revert(ch); }
+    assert_eq!(net.eval_current(), root);
+}
+
+#[test]
+fn pleco_search_runs_with_nnue_eval() {
+    use piebot::eval::nnue::features::halfkp_dim;
+    use piebot::eval::nnue::loader::QuantNnue;
+    use piebot::search::alphabeta_pleco::{PlecoSearcher, PlecoEvalMode};
+    let path = "target/pleco_nnue_search.nnue";
+    write_quant_file(path, halfkp_dim(), 8, 20240611);
+    let mut s = PlecoSearcher::default();
+    s.set_nnue_quant_model(QuantNnue::load_quantized(path).unwrap());
+    s.set_eval_mode(PlecoEvalMode::Nnue);
+    let mut b = PBoard::start_pos();
+    let fen_before = b.fen();
+    let (bm, _sc, nodes) = s.search_movetime(&mut b, 2000, 3);
+    assert!(bm.is_some() && nodes > 0);
+    assert_eq!(b.fen(), fen_before, "search must leave the board unchanged");
+}
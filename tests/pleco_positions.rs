@@ -61,3 +61,14 @@ fn pleco_root_smp_scales_nodes() {
     assert!(nodes4 > nodes1, "expected nodes to scale: 1T={} 4T={}", nodes1, nodes4);
 }
 
+
+#[test]
+fn pleco_first_move_extension_stays_bounded() {
+    // Extending the first move at every ply kept the PV at full depth (seldepth 45 here)
+    let mut b = PBoard::from_fen("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w KQ - 0 8").unwrap();
+    let mut s = piebot::search::alphabeta_pleco::PlecoSearcher::default();
+    s.set_threads(1);
+    let (bm, _sc, _nodes) = s.search_movetime(&mut b, 120_000, 6);
+    assert!(bm.is_some() && s.last_depth() == 6);
+    assert!(s.last_seldepth() < 36, "seldepth {}", s.last_seldepth());
+}
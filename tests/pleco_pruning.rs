@@ -0,0 +1,49 @@
+use piebot::search::options::EngineOptions;
+
+#[test]
+fn futility_and_lmp_options() {
+    let mut o = EngineOptions::default();
+    assert!(!o.futility && !o.lmp_pruning);
+    assert!(!o.describe().contains("futility") && !o.describe().contains("lmp_pruning"));
+    o.set("Futility", "true").unwrap();
+    o.set("LMPPruning", "on").unwrap();
+    assert!(o.futility && o.lmp_pruning);
+    assert!(o.describe().contains(",futility=on,lmp_pruning=on"));
+    o.set("lmp_pruning", "off").unwrap();
+    assert!(!o.lmp_pruning);
+}
+
+#[cfg(feature = "board-pleco")]
+mod pleco_side {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+
+    const MIDDLEGAME: &str = "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R1BQKB1R w KQ - 0 9";
+
+    fn search(futility: bool, lmp: bool, fen: &str, depth: u32) -> (Option<String>, i32, u64) {
+        let mut s = PlecoSearcher::default();
+        s.set_futility(futility);
+        s.set_lmp_pruning(lmp);
+        let mut b = pleco::Board::from_fen(fen).unwrap();
+        let (bm, sc, nodes) = s.search_movetime(&mut b, 60_000, depth);
+        (bm.map(|m| m.stringify()), sc, nodes)
+    }
+
+    #[test]
+    fn pruning_searches_fewer_nodes() {
+        let (_, _, plain) = search(false, false, MIDDLEGAME, 6);
+        let (fm, _, fut) = search(true, false, MIDDLEGAME, 6);
+        let (lm, _, lmp) = search(false, true, MIDDLEGAME, 6);
+        assert!(fm.is_some() && lm.is_some());
+        assert!(fut < plain, "futility {} vs {}", fut, plain);
+        assert!(lmp < plain, "lmp {} vs {}", lmp, plain);
+    }
+
+    #[test]
+    fn pruning_keeps_a_back_rank_mate() {
+        // Back-rank mate: pruning must not drop the mating move
+        let fen = "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1";
+        let (bm, sc, _) = search(true, true, fen, 5);
+        assert_eq!(bm.as_deref(), Some("d1d8"));
+        assert!(sc > piebot::search::score::MATE_BOUND);
+    }
+}
@@ -0,0 +1,126 @@
+use cozy_chess::{Board, Move};
+use piebot::eval::policy::{PolicyNet, DEFAULT_POLICY_WEIGHT};
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::options::EngineOptions;
+
+fn legal(board: &Board) -> Vec<Move> {
+    let mut moves = Vec::new();
+    board.generate_moves(|ml| { moves.extend(ml); false });
+    moves
+}
+
+fn uci(board: &Board, s: &str) -> Move {
+    legal(board).into_iter().find(|m| m.to_string() == s).expect("legal move")
+}
+
+fn tmp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("piebot_policy_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let net = PolicyNet::new(8, 3);
+    let path = tmp_path("round_trip.pol");
+    net.save(&path).unwrap();
+    let back = PolicyNet::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(back, net);
+    assert_eq!(back.fingerprint(), net.fingerprint());
+    assert_ne!(PolicyNet::new(8, 4).fingerprint(), net.fingerprint());
+}
+
+#[test]
+fn bad_or_short_files_are_rejected() {
+    assert!(PolicyNet::from_reader(&b"NOTAPOL!\x01\0\0\0\x08\0\0\0"[..]).is_err());
+    let mut bytes = Vec::new();
+    PolicyNet::new(4, 1).write_to(&mut bytes).unwrap();
+    assert!(PolicyNet::from_reader(&bytes[..]).is_ok());
+    assert!(PolicyNet::from_reader(&bytes[..bytes.len() - 1]).is_err(), "truncated");
+    assert!(PolicyNet::load("/nonexistent/net.pol").is_err());
+}
+
+#[test]
+fn training_makes_the_played_move_the_favourite() {
+    let board = Board::default();
+    let target = uci(&board, "g1f3");
+    let mut net = PolicyNet::new(16, 7);
+    let (first_loss, _) = net.train_position(&board, target, 0.05).unwrap();
+    let mut last = (first_loss, false);
+    for _ in 0..200 { last = net.train_position(&board, target, 0.05).unwrap(); }
+    assert!(last.1, "target is top-1 after training");
+    assert!(last.0 < first_loss);
+    // Illegal targets are not trained on
+    let illegal = Move { from: cozy_chess::Square::E1, to: cozy_chess::Square::E3, promotion: None };
+    assert!(net.train_position(&board, illegal, 0.05).is_none());
+}
+
+#[test]
+fn mirrored_positions_get_the_same_probabilities() {
+    let net = PolicyNet::new(16, 11);
+    let white = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", false).unwrap();
+    let black = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1", false).unwrap();
+    let sorted = |b: &Board| {
+        let mut p = net.probabilities(b, &legal(b));
+        p.sort_by(|a, b| a.total_cmp(b));
+        p
+    };
+    let (pw, pb) = (sorted(&white), sorted(&black));
+    assert_eq!(pw.len(), pb.len());
+    for (a, b) in pw.iter().zip(&pb) { assert!((a - b).abs() < 1e-6); }
+}
+
+#[test]
+fn zero_weight_searches_like_no_policy() {
+    let board = Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4", false).unwrap();
+    let params = SearchParams { depth: 5, movetime: None, max_nodes: None, ..SearchParams::default() };
+    let mut plain = Searcher::builder().build().unwrap();
+    let base = plain.search_with_params(&board, params);
+    let mut off = Searcher::builder().policy(PolicyNet::new(8, 1)).policy_weight(0).build().unwrap();
+    assert_eq!(off.search_with_params(&board, params).nodes, base.nodes);
+    let mut on = Searcher::builder().policy(PolicyNet::new(8, 1)).build().unwrap();
+    let r = on.search_with_params(&board, params);
+    assert!(r.bestmove.is_some_and(|m| legal(&board).iter().any(|l| l.to_string() == m)));
+}
+
+#[test]
+fn policy_options() {
+    let mut o = EngineOptions::default();
+    assert_eq!((o.policy_file.as_deref(), o.policy_weight), (None, DEFAULT_POLICY_WEIGHT));
+    assert!(!o.describe().contains("policy"));
+    assert!(o.load_policy().unwrap().is_none());
+    o.set("PolicyWeight", "5000").unwrap();
+    o.set("PolicyFile", "net.pol").unwrap();
+    assert_eq!(o.policy_weight, 1000);
+    assert!(o.describe().contains(",policy=net.pol,policy_weight=1000"));
+    assert!(o.build_searcher().is_err(), "missing policy file");
+    let path = tmp_path("options.pol");
+    PolicyNet::new(4, 2).save(&path).unwrap();
+    o.set("policy", path.to_str().unwrap()).unwrap();
+    let s = o.build_searcher().unwrap();
+    std::fs::remove_file(&path).ok();
+    assert!(s.policy().is_some());
+}
+
+#[test]
+fn uci_policy_file() {
+    let path = tmp_path("uci.pol");
+    PolicyNet::new(4, 5).save(&path).unwrap();
+    let mut e = piebot::uci::UciEngineCozy::new();
+    assert!(e.handle_line("setoption name PolicyFile value /nonexistent/net.pol"));
+    assert!(e.handle_line(&format!("setoption name PolicyFile value {}", path.display())));
+    assert!(e.handle_line("setoption name PolicyWeight value 60"));
+    assert!(e.handle_line("position startpos"));
+    assert!(e.handle_line("go depth 3"));
+    std::fs::remove_file(&path).ok();
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_searches_with_a_policy() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_policy(Some(PolicyNet::new(8, 9)));
+    let mut board = pleco::Board::start_pos();
+    let (bm, _, _) = s.search_movetime(&mut board, 60_000, 4);
+    assert!(bm.is_some());
+}
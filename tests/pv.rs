@@ -0,0 +1,56 @@
+// Principal variations: legal lines from the best move, in results and info lines.
+mod common;
+
+use common::Captured;
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+#[cfg(feature = "board-pleco")]
+use piebot::search::alphabeta_pleco::PlecoSearcher;
+use piebot::uci::UciEngine;
+
+// No castling rights, so both backends write every move the same way
+const FEN: &str = "r2q1rk1/pp2bppp/2n1pn2/3p4/3P4/2NBPN2/PP3PPP/R2Q1RK1 w - - 0 10";
+
+// Plays the line from FEN, failing on the first illegal move
+fn assert_legal(pv: &[String]) {
+    let mut b = Board::from_fen(FEN, false).unwrap();
+    for m in pv { let mv = m.parse().unwrap(); assert!(b.is_legal(mv), "{} in {:?}", m, pv); b.play(mv); }
+}
+
+#[test]
+fn cozy_and_pleco_results_carry_a_legal_pv() {
+    let b = Board::from_fen(FEN, false).unwrap();
+    let mut s = Searcher::default();
+    let res = s.search_with_params(&b, SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, ..SearchParams::default() });
+    assert!(res.pv.len() > 1 && res.pv.len() <= 5 && res.pv.first() == res.bestmove.as_ref(), "{:?}", res);
+    assert_legal(&res.pv);
+
+    #[cfg(feature = "board-pleco")]
+    {
+        let mut pb = pleco::Board::from_fen(FEN).unwrap();
+        let mut ps = PlecoSearcher::default();
+        ps.set_tt_capacity_mb(1);
+        let res = ps.search_result(&mut pb, 0, 5);
+        assert!(res.pv.len() > 1 && res.pv.len() <= 5 && res.pv.first() == res.bestmove.as_ref(), "{:?}", res);
+        assert_legal(&res.pv);
+    }
+}
+
+#[test]
+fn info_lines_print_the_pv() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let out = Captured::default();
+        UciEngine::new().run_loop(format!("setoption name Backend value {}\nposition fen {}\ngo depth 5\nquit\n", backend, FEN).as_bytes(), out.clone());
+        let text = out.text();
+        let info: Vec<&str> = text.lines().filter(|l| l.starts_with("info depth")).collect();
+        assert!(!info.is_empty(), "{}", text);
+        for l in &info {
+            let pv: Vec<String> = l.split(" pv ").nth(1).expect(l).split(" string").next().unwrap().split_whitespace().map(str::to_string).collect();
+            assert_legal(&pv);
+        }
+        let best = text.lines().last().unwrap().strip_prefix("bestmove ").unwrap();
+        let last = info.last().unwrap().split(" pv ").nth(1).unwrap();
+        assert!(last.starts_with(best) && last.split_whitespace().count() > 1, "{}: {}", backend, text);
+    }
+}
@@ -0,0 +1,58 @@
+// Post-game review: centipawn loss by side and phase at a fixed depth.
+use cozy_chess::{Board, Move};
+use piebot::search::options::EngineOptions;
+use piebot::selfplay::review::{CplStats, Phase, Reviewer, MAX_LOSS_CP};
+
+fn board(fen: &str) -> Board { Board::from_fen(fen, false).unwrap() }
+
+fn reviewer(depth: u32) -> Reviewer {
+    let o = EngineOptions { depth, ..EngineOptions::default() };
+    Reviewer::new(o.build_searcher().unwrap(), o.search_params())
+}
+
+#[test]
+fn phases_go_by_material_then_move_number() {
+    assert_eq!(Phase::of(&Board::default()), Phase::Opening);
+    assert_eq!(Phase::of(&board("r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 0 15")), Phase::Middlegame);
+    assert_eq!(Phase::of(&board("8/5pk1/6p1/8/3R4/6P1/r4PK1/8 w - - 0 5")), Phase::Endgame);
+}
+
+#[test]
+fn stats_average_by_phase() {
+    let mut s = CplStats::default();
+    assert_eq!(s.overall(), None);
+    s.record(Phase::Opening, 10);
+    s.record(Phase::Opening, 30);
+    s.record(Phase::Endgame, 200);
+    assert_eq!(s.average(Phase::Opening), Some(20.0));
+    assert_eq!(s.average(Phase::Middlegame), None);
+    assert_eq!(s.overall(), Some(80.0));
+    assert_eq!(s.summary(), "opening=20 middlegame=- endgame=200 all=80 moves=3");
+    let mut t = CplStats::default();
+    t.add(&s);
+    t.add(&s);
+    assert_eq!((t.moves, t.average(Phase::Endgame)), ([4, 0, 2], Some(200.0)));
+}
+
+#[test]
+fn blunders_cost_and_best_moves_do_not() {
+    let mut r = reviewer(4);
+    // White can take a free queen on d5
+    let b = board("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 30");
+    let take: Move = "d2d5".parse().unwrap();
+    assert_eq!(r.move_loss(&b, take), 0);
+    let idle: Move = "e1f1".parse().unwrap();
+    let loss = r.move_loss(&b, idle);
+    assert!((600..=2 * MAX_LOSS_CP as u32).contains(&loss), "loss {}", loss);
+}
+
+#[test]
+fn games_are_split_by_side() {
+    let mut r = reviewer(3);
+    let moves: Vec<Move> = ["e2e4", "e7e5", "g1f3", "b8c6", "f1c4"].iter().map(|m| m.parse().unwrap()).collect();
+    let [white, black] = r.review_game(&Board::default(), &moves);
+    assert_eq!((white.moves, black.moves), ([3, 0, 0], [2, 0, 0]));
+    // An illegal move ends the review
+    let [white, _] = r.review_game(&Board::default(), &["e2e5".parse().unwrap()]);
+    assert_eq!(white.moves, [0, 0, 0]);
+}
@@ -0,0 +1,107 @@
+// Per-root-move node accounting and the time manager's use of the best move's share.
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::timeman::{ClockPlan, ManualClock, TimeBudget, TimeConfig, TimeManager};
+use std::time::Duration;
+
+// White retakes the queen on f3; nothing else comes close
+const RECAPTURE: &str = "rnb1kbnr/pppp1ppp/8/4p3/4P3/5q2/PPPP1PPP/RNB1KB1R w KQkq - 0 4";
+// A quiet Giuoco Piano with several playable moves. (An earlier fixture had White's
+// dark-squared bishop on e2, which the development term reads as a second tempo.)
+const MIDDLEGAME: &str = "r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 0 7";
+// Busy enough that each pleco iteration costs several times the last
+#[cfg(feature = "board-pleco")]
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn params(depth: u32) -> SearchParams {
+    SearchParams { depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, threads: 1, ..Default::default() }
+}
+
+fn legal_moves(b: &Board) -> usize {
+    let mut n = 0;
+    b.generate_moves(|ml| { n += ml.len(); false });
+    n
+}
+
+#[test]
+fn cozy_counts_root_nodes_per_move() {
+    let mut shares = Vec::new();
+    for fen in [RECAPTURE, MIDDLEGAME] {
+        let b = Board::from_fen(fen, false).unwrap();
+        let mut s = Searcher::default();
+        let r = s.search_with_params(&b, params(7));
+        assert_eq!(s.root_effort().len(), legal_moves(&b));
+        assert!(s.root_effort().iter().map(|(_, n)| n).sum::<u64>() <= r.nodes);
+        shares.push(s.best_move_share(r.bestmove.as_deref()).unwrap());
+    }
+    // A forced recapture takes most of the effort, a quiet middlegame spreads it
+    assert!(shares[0] > 0.5 && shares[1] < 0.5, "{:?}", shares);
+}
+
+#[test]
+fn clear_best_move_spends_less_of_the_clock() {
+    let run = |fen: &str, effort: f32| {
+        let b = Board::from_fen(fen, false).unwrap();
+        let budget = TimeBudget { soft: Duration::from_millis(300), hard: Duration::from_millis(900) };
+        let plan = ClockPlan { budget, config: TimeConfig { effort, ..TimeConfig::default() } };
+        Searcher::default().search_with_params(&b, SearchParams { depth: 0, movetime: Some(budget.hard), clock: Some(plan), ..params(0) })
+    };
+    let (on, off) = (run(RECAPTURE, 2.0), run(RECAPTURE, 0.0));
+    assert!(on.nodes <= off.nodes && on.depth <= off.depth, "on {:?} off {:?}", on, off);
+    // Longer thinking is checked on the soft limit itself, not on a race against the clock
+    let soft = |fen: &str, effort: f32| {
+        let b = Board::from_fen(fen, false).unwrap();
+        let mut s = Searcher::default();
+        let r = s.search_with_params(&b, params(7));
+        let share = s.best_move_share(r.bestmove.as_deref()).unwrap();
+        let mut tm = TimeManager::new(ManualClock::new(), TimeBudget { soft: Duration::from_millis(300), hard: Duration::from_millis(900) });
+        tm.scale_soft_by_effort(TimeConfig { effort, ..TimeConfig::default() }.effort_scale(share, 0.0));
+        tm.soft_limit()
+    };
+    assert!(soft(RECAPTURE, 2.0) < soft(RECAPTURE, 0.0));
+    assert!(soft(MIDDLEGAME, 2.0) > soft(MIDDLEGAME, 0.0), "{:?}", soft(MIDDLEGAME, 2.0));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_counts_root_nodes_on_every_smp_path() {
+    use piebot::search::alphabeta_pleco::{PlecoSearcher, SmpMode};
+    for (threads, mode) in [(1, SmpMode::Off), (2, SmpMode::LazyIndep), (2, SmpMode::LazyCoop), (2, SmpMode::LazyHybrid)] {
+        let mut s = PlecoSearcher::default();
+        s.set_tt_capacity_mb(16);
+        s.set_threads(threads);
+        s.set_smp_mode(mode);
+        let mut b = pleco::Board::from_fen(RECAPTURE).unwrap();
+        let r = s.search_result(&mut b, 60_000, 6);
+        assert_eq!(r.bestmove.as_deref(), Some("g2f3"), "{:?}", mode);
+        assert!(!s.root_effort().is_empty() && s.root_effort().len() <= b.generate_moves().len(), "{:?}", mode);
+        assert!(s.root_effort().iter().any(|(m, _)| m.stringify() == "g2f3"), "{:?}", mode);
+        if threads == 1 {
+            assert_eq!(s.root_effort().len(), b.generate_moves().len());
+            assert!(s.root_effort().iter().map(|(_, n)| n).sum::<u64>() <= r.nodes);
+        }
+    }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_effort_stops_a_clear_move_early_and_extends_a_spread_one() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    // Single-threaded under a node limit, so the outcome does not depend on the machine
+    let run = |fen: &str, limit: u64, effort: f32| {
+        let mut s = PlecoSearcher::default();
+        s.set_tt_capacity_mb(16);
+        s.set_threads(1);
+        s.set_tm_effort(effort);
+        s.set_node_limit(Some(limit));
+        s.search_result(&mut pleco::Board::from_fen(fen).unwrap(), 0, 0)
+    };
+    let (on, off) = (run(RECAPTURE, 60_000, 2.0), run(RECAPTURE, 60_000, 0.0));
+    assert!(on.nodes < off.nodes && on.depth < off.depth, "on {:?} off {:?}", on, off);
+    assert_eq!(on.bestmove.as_deref(), Some("g2f3"));
+    // Finish-one keeps back the next iteration's predicted cost; spread effort shrinks
+    // that reserve enough to start (and finish) one more
+    let (on, off) = (run(KIWIPETE, 6_500, 2.0), run(KIWIPETE, 6_500, 0.0));
+    assert!(on.depth > off.depth && on.nodes > off.nodes, "on {:?} off {:?}", on, off);
+    assert!(on.nodes <= 6_500 + 1_000, "{:?}", on);
+}
@@ -0,0 +1,76 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{InfoHook, SearchParams, Searcher};
+use std::sync::{Arc, Mutex};
+
+fn params(depth: u32, threads: usize) -> SearchParams {
+    SearchParams { depth, threads, use_tt: true, order_captures: true, use_history: true, use_killers: true, ..Default::default() }
+}
+
+#[test]
+fn each_iteration_starts_with_the_previous_best_move() {
+    let b: Board = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4".parse().unwrap();
+    let mut s = Searcher::default();
+    s.set_track_root_scores(true);
+    s.search_with_params(&b, params(4, 1));
+    let table = s.root_score_table();
+    for w in table.windows(2) {
+        // Later moves can tie the best with a fail-low bound: the first to reach it is the best
+        let best = w[0].scores.iter().min_by_key(|r| -r.score_cp).unwrap().mv;
+        assert_eq!(w[1].scores[0].mv, best, "depth {}", w[1].depth);
+        // Every move is still searched
+        assert_eq!(w[1].scores.len(), w[0].scores.len());
+    }
+}
+
+#[test]
+fn bestmove_changes_are_counted_per_iteration() {
+    let b: Board = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4".parse().unwrap();
+    for threads in [1, 2] {
+        let seen: Arc<Mutex<Vec<(Option<String>, u32)>>> = Arc::default();
+        let log = seen.clone();
+        let hook: InfoHook = Arc::new(move |r| log.lock().unwrap().push((r.bestmove.clone(), r.bestmove_changes)));
+        let mut s = Searcher::default();
+        s.set_info_hook(Some(hook));
+        let r = s.search_with_params(&b, params(5, threads));
+        let seen = seen.lock().unwrap();
+        let mut expected = 0;
+        for (i, (mv, changes)) in seen.iter().enumerate() {
+            if i > 0 && *mv != seen[i - 1].0 { expected += 1; }
+            assert_eq!(*changes, expected, "threads {} iteration {}", threads, i + 1);
+        }
+        assert_eq!(r.bestmove_changes, expected);
+        assert!(r.bestmove_changes < r.depth);
+    }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_counts_bestmove_changes_and_starts_with_the_previous_best() {
+    use piebot::search::alphabeta_pleco::{PlecoSearcher, SmpMode};
+    const FEN: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+    for (threads, mode) in [(1, SmpMode::Off), (2, SmpMode::LazyIndep), (2, SmpMode::LazyCoop), (2, SmpMode::LazyHybrid)] {
+        let seen: Arc<Mutex<Vec<(Option<String>, u32)>>> = Arc::default();
+        let log = seen.clone();
+        let hook: InfoHook = Arc::new(move |r| log.lock().unwrap().push((r.bestmove.clone(), r.bestmove_changes)));
+        let mut s = PlecoSearcher::default();
+        s.set_tt_capacity_mb(16);
+        s.set_threads(threads);
+        s.set_smp_mode(mode);
+        s.set_info_hook(Some(hook));
+        let r = s.search_result(&mut pleco::Board::from_fen(FEN).unwrap(), 60_000, 5);
+        let seen = seen.lock().unwrap();
+        // Lazy helpers report nothing; the count comes from the worker whose move is played
+        if mode == SmpMode::LazyIndep { assert!(r.bestmove_changes < r.depth); continue; }
+        let mut expected = 0;
+        for (i, (mv, changes)) in seen.iter().enumerate() {
+            if i > 0 && *mv != seen[i - 1].0 { expected += 1; }
+            assert_eq!(*changes, expected, "{:?} iteration {}", mode, i + 1);
+        }
+        assert_eq!(r.bestmove_changes, expected, "{:?}", mode);
+        if threads == 1 {
+            // Serial roots search in order, so the last iteration began with the one before's best
+            let prev = seen[seen.len() - 2].0.clone();
+            assert_eq!(s.root_effort().first().map(|(m, _)| m.stringify()), prev);
+        }
+    }
+}
@@ -16,7 +16,7 @@ fn root_parallel_bestmove_equals_single_thread() {
     let mut p2 = p1; p2.threads = 4;
     let r2 = s2.search_with_params(&b, p2);
 
-    assert_eq!(r2.score_cp, r1.score_cp, "score differs between single and multi-thread at fixed depth");
+    assert_eq!(r2.score.value(), r1.score.value(), "score differs between single and multi-thread at fixed depth");
 }
 
 #[test]
@@ -31,3 +31,20 @@ fn root_parallel_returns_move_with_movetime() {
     let r = s.search_with_params(&b, p);
     assert!(r.bestmove.is_some(), "no move returned under movetime with threads");
 }
+
+#[test]
+fn root_parallel_node_count_covers_every_iteration() {
+    use piebot::search::alphabeta::{Searcher, SearchParams, SearchResult};
+    use std::sync::{Arc, Mutex};
+    let b = Board::default();
+    let mut s = Searcher::default();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    s.set_info_hook(Some(Arc::new(move |r: &SearchResult| sink.lock().unwrap().push(r.nodes))));
+    let mut p = SearchParams::default();
+    p.depth = 5; p.use_tt = true; p.order_captures = true; p.threads = 2;
+    let r = s.search_with_params(&b, p);
+    let seen = seen.lock().unwrap();
+    assert!(seen.windows(2).all(|w| w[1] > w[0]), "node count dropped between iterations: {:?}", seen);
+    assert_eq!(seen.last().copied(), Some(r.nodes));
+}
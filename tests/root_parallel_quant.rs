@@ -1,14 +1,13 @@
+mod common;
+
+use common::bias_model;
 use cozy_chess::Board;
 
 #[test]
 fn root_parallel_quant_bestmove_equals_single_thread() {
-    use piebot::eval::nnue::features::halfkp_dim;
-    use piebot::eval::nnue::loader::{QuantNnue, QuantMeta};
     use piebot::search::alphabeta::{Searcher, SearchParams};
     // Deterministic quant model (all zeros); eval always 0
-    let input_dim = halfkp_dim();
-    let hidden_dim = 8usize;
-    let model = QuantNnue { meta: QuantMeta { version: 1, input_dim, hidden_dim, output_dim: 1 }, w1_scale: 1.0, w2_scale: 1.0, w1: vec![0; hidden_dim * input_dim], b1: vec![0; hidden_dim], w2: vec![0; hidden_dim], b2: vec![0] };
+    let model = bias_model(0);
 
     let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 2 3";
     let b = Board::from_fen(fen, false).unwrap();
@@ -28,6 +27,6 @@ fn root_parallel_quant_bestmove_equals_single_thread() {
     let mut p2 = p1; p2.threads = 4;
     let r2 = s2.search_with_params(&b, p2);
 
-    assert_eq!(r2.score_cp, r1.score_cp, "score differs between single and multi-thread with quant NNUE at fixed depth");
+    assert_eq!(r2.score.value(), r1.score.value(), "score differs between single and multi-thread with quant NNUE at fixed depth");
 }
 
@@ -0,0 +1,88 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::score::{Score, MATE_BOUND, MATE_SCORE};
+
+// Mate in 2: 1.Rb8+ Rxb8 2.Rxb8#
+const MATE_IN_2: &str = "r5k1/5ppp/8/8/8/8/1R6/1R4K1 w - - 0 1";
+
+#[test]
+fn mate_encoding_round_trips() {
+    assert!(Score::mate_in(3).is_mate() && Score::mated_in(4).is_mate());
+    assert!(!Score::from_value(MATE_BOUND).is_mate() && !Score::DRAW.is_mate());
+    assert_eq!(Score::mate_in(1).mate_moves(), Some(1));
+    assert_eq!(Score::mate_in(3).mate_moves(), Some(2));
+    assert_eq!(Score::mated_in(2).mate_moves(), Some(-1));
+    assert_eq!(-Score::mate_in(5), Score::mated_in(5));
+    // A mate 3 plies below a node at ply 4 is stored relative to that node
+    let s = Score::mate_in(7);
+    assert_eq!(s.to_tt(4), Score::from_value(MATE_SCORE - 3));
+    assert_eq!(s.to_tt(4).from_tt(2), Score::mate_in(5));
+    assert_eq!(Score::cp(120).to_tt(9), Score::cp(120));
+}
+
+#[test]
+#[allow(deprecated)]
+fn old_integer_helpers_still_agree() {
+    use piebot::search::eval::{mate_in, score_from_tt, score_to_tt};
+    let s = Score::mate_in(7);
+    assert_eq!(mate_in(s.value()), s.mate_moves());
+    assert_eq!(score_to_tt(s.value(), 4), s.to_tt(4).value());
+    assert_eq!(score_from_tt(s.to_tt(4).value(), 2), Score::mate_in(5).value());
+    assert_eq!(mate_in(-120), None);
+}
+
+#[test]
+fn uci_and_display_formats() {
+    assert_eq!(Score::cp(35).to_uci(), "cp 35");
+    assert_eq!(Score::cp(-35).to_string(), "-35");
+    assert_eq!(Score::mate_in(3).to_uci(), "mate 2");
+    assert_eq!(Score::mated_in(4).to_uci(), "mate -2");
+    assert_eq!(Score::mate_in(1).to_string(), "#1");
+}
+
+#[test]
+fn search_reports_mate_distance_through_the_tt() {
+    let b = Board::from_fen(MATE_IN_2, false).unwrap();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth: 6, use_tt: true, order_captures: true, use_killers: true, ..Default::default() };
+    let r = s.search_with_params(&b, p);
+    assert_eq!(r.score.mate_moves(), Some(2), "{}", r.score);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_mates_count_plies_from_the_root() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    let mut b = pleco::Board::from_fen("6k1/8/6K1/8/8/8/8/R7 w - - 0 1").unwrap();
+    let (bm, score, _) = s.search_movetime(&mut b, 10_000, 3);
+    assert_eq!(bm.map(|m| m.stringify()).as_deref(), Some("a1a8"));
+    assert_eq!(Score::from_value(score), Score::mate_in(1));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_reports_the_same_mate_distance() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(16);
+    let mut b = pleco::Board::from_fen(MATE_IN_2).unwrap();
+    let (bm, score, _) = s.search_movetime(&mut b, 10_000, 6);
+    assert_eq!(bm.map(|m| m.stringify()).as_deref(), Some("b2b8"));
+    assert_eq!(Score::from_value(score).mate_moves(), Some(2));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_tt_mate_scores_keep_their_distance() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(16);
+    s.set_heuristics(false, false, false, false, 50);
+    // After the forced Kg8, Ra8# is stored one ply below this root
+    let mut before = pleco::Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1").unwrap();
+    assert_eq!(Score::from_value(s.search_movetime(&mut before, 10_000, 4).1), Score::mated_in(2));
+    // Kg6 Kg8 reaches it two plies below this one, a ply further from the mate
+    let mut earlier = pleco::Board::from_fen("7k/8/8/6K1/8/8/8/R7 w - - 0 1").unwrap();
+    assert_eq!(Score::from_value(s.search_movetime(&mut earlier, 10_000, 5).1), Score::mate_in(3));
+}
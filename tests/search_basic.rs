@@ -40,3 +40,16 @@ fn search_prefers_winning_queen_capture() {
     let bm = res.bestmove.expect("expected a best move");
     assert_eq!(bm, "e2d2", "expected Qe2xd2 as best move, got {bm}");
 }
+
+#[test]
+fn search_result_reports_depth_seldepth_and_timing() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    let b = Board::default();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth: 3, use_tt: true, order_captures: true, ..Default::default() };
+    let r = s.search_with_params(&b, p);
+    assert_eq!(r.depth, 3, "completed depth not reported");
+    assert!(r.seldepth >= r.depth, "seldepth {} below depth {}", r.seldepth, r.depth);
+    assert_eq!(r.tbhits, 0);
+    if r.time_ms > 0 { assert!(r.nps > 0, "nps missing with nonzero time"); }
+}
@@ -0,0 +1,43 @@
+// search_batch: every position answered once, results as in a fresh single search
+// with the worker's share of the table.
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+
+const FENS: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "4k3/8/4p3/3Q4/8/8/8/4K3 b - - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+];
+
+fn boards() -> Vec<Board> { FENS.iter().map(|f| Board::from_fen(f, false).unwrap()).collect() }
+
+fn params() -> SearchParams { SearchParams { depth: 4, use_tt: true, order_captures: true, use_history: true, ..SearchParams::default() } }
+
+#[test]
+fn batch_matches_fresh_single_searches() {
+    // Three workers share the 3 MB table, 1 MB each
+    let mut s = Searcher::default();
+    s.set_tt_capacity_mb(3);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    let mut got: Vec<_> = pool.install(|| s.search_batch(boards(), params())).into_iter().collect();
+    got.sort_by_key(|(i, _)| *i);
+    assert_eq!(got.iter().map(|(i, _)| *i).collect::<Vec<_>>(), (0..FENS.len()).collect::<Vec<_>>());
+    for ((_, r), b) in got.iter().zip(boards()) {
+        let mut single = Searcher::default();
+        single.set_tt_capacity_mb(1);
+        let want = single.search_with_params(&b, params());
+        assert_eq!((&r.bestmove, r.score, r.nodes, r.depth), (&want.bestmove, want.score, want.nodes, want.depth));
+    }
+}
+
+#[test]
+fn dropping_the_receiver_ends_the_batch() {
+    let s = Searcher::default();
+    let rx = s.search_batch(boards(), params());
+    let (i, r) = rx.recv().unwrap();
+    assert!(i < FENS.len() && r.bestmove.is_some());
+    drop(rx);
+    assert!(s.search_batch(Vec::new(), params()).recv().is_err(), "an empty batch closes at once");
+}
@@ -0,0 +1,56 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{Searcher, SearchParams, SplitConfig, SplitPolicy};
+
+fn search(fen: &str, depth: u32, max_extensions: u32, use_lmr: bool) -> piebot::search::alphabeta::SearchResult {
+    let b = Board::from_fen(fen, false).unwrap();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth, use_tt: true, order_captures: true, use_lmr, use_history: use_lmr, use_killers: use_lmr, max_extensions, ..Default::default() };
+    s.search_with_params(&b, p)
+}
+
+// White wins the rook; the b-pawn push to the 7th decides how much more
+const PUSH_FEN: &str = "6k1/8/1P6/8/8/8/5r2/6K1 w - - 0 1";
+
+#[test]
+fn zero_budget_matches_plain_search() {
+    let b = Board::from_fen(PUSH_FEN, false).unwrap();
+    let plain = Searcher::default().search_with_params(&b, SearchParams { depth: 4, use_tt: true, order_captures: true, ..Default::default() });
+    let zero = search(PUSH_FEN, 4, 0, false);
+    assert_eq!(plain.bestmove, zero.bestmove);
+    assert_eq!(plain.score.value(), zero.score.value());
+    assert_eq!(plain.nodes, zero.nodes);
+}
+
+#[test]
+fn seventh_rank_push_is_extended() {
+    for lmr in [false, true] {
+        let without = search(PUSH_FEN, 4, 0, lmr);
+        let with = search(PUSH_FEN, 4, 2, lmr);
+        let deeper = search(PUSH_FEN, 5, 0, lmr);
+        assert!(with.score.value() > without.score.value() + 100, "lmr {}: {} vs {}", lmr, with.score.value(), without.score.value());
+        // The extension buys roughly one ply on this line
+        assert!((with.score.value() - deeper.score.value()).abs() <= 20, "lmr {}: {} vs {}", lmr, with.score.value(), deeper.score.value());
+    }
+}
+
+#[test]
+fn large_budget_still_terminates_in_tactical_positions() {
+    let fen = "3r2k1/5ppp/8/3n4/8/3N4/5PPP/3R2K1 w - - 0 1";
+    let r = search(fen, 5, 16, true);
+    assert!(r.bestmove.is_some());
+    assert!(r.nodes > search(fen, 5, 0, true).nodes);
+}
+
+#[test]
+fn split_workers_extend_like_the_serial_search() {
+    // Black's b2 push is extended at the split nodes one ply below a serial root
+    let fen = "6K1/8/8/8/8/1p6/8/7k w - - 0 1";
+    let b = Board::from_fen(fen, false).unwrap();
+    let serial = search(fen, 3, 2, false);
+    for policy in [SplitPolicy::Ybwc, SplitPolicy::Jamboree] {
+        let split = SplitConfig { policy, min_depth: 1, min_moves: 1 };
+        let p = SearchParams { depth: 3, use_tt: true, order_captures: true, max_extensions: 2, threads: 4, serial_root: true, split, ..Default::default() };
+        let r = Searcher::default().search_with_params(&b, p);
+        assert_eq!(r.score.value(), serial.score.value(), "{}", policy);
+    }
+}
@@ -0,0 +1,17 @@
+use piebot::search::guard::{guarded, worker_panics};
+use rayon::prelude::*;
+
+#[test]
+fn guarded_passes_results_through() {
+    assert_eq!(guarded("test", 3, || "startpos".to_string(), || 42), Some(42));
+}
+
+#[test]
+fn panicking_workers_are_dropped_and_counted() {
+    let before = worker_panics();
+    let out: Vec<u32> = (0..8u32).into_par_iter()
+        .filter_map(|i| guarded("test", 5, || format!("worker {}", i), || { if i % 3 == 0 { panic!("boom {}", i); } i }))
+        .collect();
+    assert_eq!(out, vec![1, 2, 4, 5, 7]);
+    assert!(worker_panics() >= before + 3);
+}
@@ -0,0 +1,31 @@
+use cozy_chess::Board;
+
+#[test]
+fn new_game_clears_tt_but_new_search_keeps_it() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    let b = Board::default();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth: 3, use_tt: true, order_captures: true, use_history: true, use_killers: true, ..Default::default() };
+    let _ = s.search_with_params(&b, p);
+    assert!(s.tt_probe(&b).is_some(), "root not stored in TT");
+    s.new_search();
+    assert!(s.tt_probe(&b).is_some(), "new_search should not clear the TT");
+    s.new_game();
+    assert!(s.tt_probe(&b).is_none(), "new_game should clear the TT");
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_clear_empties_the_tt() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    use piebot::search::score::Score;
+    use piebot::search::tt_pleco::{Bound, Entry};
+    let b = pleco::Board::start_pos();
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(16);
+    s.tt().put(Entry { key: b.zobrist(), depth: 5, score: Score::cp(20), best: None, bound: Bound::Exact, gen: 0 });
+    s.new_search();
+    assert!(s.tt().get(b.zobrist()).is_some(), "new_search should not clear the TT");
+    s.clear();
+    assert!(s.tt().get(b.zobrist()).is_none(), "clear should empty the TT");
+}
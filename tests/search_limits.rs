@@ -0,0 +1,108 @@
+// Depth and movetime together: whichever comes first, with an optional min depth.
+mod common;
+
+use common::Captured;
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::timeman::{ClockPlan, SearchLimits, TimeBudget, TimeConfig, DEFAULT_MOVETIME_MS};
+use piebot::uci::UciEngine;
+use std::time::{Duration, Instant};
+
+const MIDDLEGAME: &str = "r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 0 7";
+
+fn limits(args: &str, depth: u32, movetime: Option<u64>) -> SearchLimits { SearchLimits::from_uci(args, true, depth, movetime, 0) }
+
+#[test]
+fn go_limits_replace_the_configured_ones() {
+    let l = |depth, movetime_ms| SearchLimits { depth, movetime_ms, min_depth: 0 };
+    assert_eq!(limits("", 6, None), l(6, None), "a bare go uses the configured limits");
+    assert_eq!(limits("depth 9", 6, Some(500)), l(9, None));
+    assert_eq!(limits("movetime 300", 6, None), l(0, Some(300)), "not capped at the configured depth");
+    assert_eq!(limits("depth 4 movetime 200", 6, None), l(4, Some(200)));
+    assert_eq!(limits("wtime 1000 btime 1000", 6, None), l(0, None), "the caller budgets the clock");
+    assert_eq!(limits("", 0, None), l(0, Some(DEFAULT_MOVETIME_MS)), "never unlimited");
+    assert_eq!(limits("btime 1000", 0, None), l(0, Some(DEFAULT_MOVETIME_MS)), "only the opponent's clock");
+    assert_eq!(SearchLimits::from_uci("depth 3", true, 6, None, 5).min_depth, 5);
+    assert_eq!(limits("infinite", 6, Some(500)), l(0, None), "until stop");
+    assert_eq!(limits("infinite depth 7", 0, None), l(7, None));
+}
+
+fn params(depth: u32, movetime_ms: u64, min_depth: u32) -> SearchParams {
+    SearchParams { depth, min_depth, movetime: Some(Duration::from_millis(movetime_ms)), use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, threads: 1, ..Default::default() }
+}
+
+#[test]
+fn whichever_limit_comes_first_stops_the_search() {
+    let b = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let t = Instant::now();
+    let r = Searcher::default().search_with_params(&b, params(3, 60_000, 0));
+    assert_eq!(r.depth, 3);
+    assert!(t.elapsed() < Duration::from_secs(30));
+
+    let t = Instant::now();
+    let r = Searcher::default().search_with_params(&b, params(60, 50, 0));
+    assert!(r.depth < 60 && r.bestmove.is_some());
+    assert!(t.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn min_depth_completes_even_past_the_movetime() {
+    let b = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let r = Searcher::default().search_with_params(&b, params(0, 1, 5));
+    assert!(r.depth >= 5, "depth {}", r.depth);
+    let r = Searcher::default().search_with_params(&b, params(4, 1, 6));
+    assert_eq!(r.depth, 4, "the depth limit still applies");
+}
+
+// A clock whose hard limit is 100ms
+fn tight_clock() -> ClockPlan {
+    ClockPlan { budget: TimeBudget { soft: Duration::from_millis(30), hard: Duration::from_millis(100) }, config: TimeConfig::default() }
+}
+
+#[test]
+fn min_depth_never_passes_the_clocks_hard_limit() {
+    let b = Board::from_fen(MIDDLEGAME, false).unwrap();
+    let t = Instant::now();
+    let r = Searcher::default().search_with_params(&b, SearchParams { clock: Some(tight_clock()), ..params(0, 100, 99) });
+    assert!(r.bestmove.is_some() && r.depth < 99, "{:?} depth {}", r.bestmove, r.depth);
+    assert!(t.elapsed() < Duration::from_secs(2), "{:?}", t.elapsed());
+    #[cfg(feature = "board-pleco")]
+    {
+        let mut s = piebot::search::alphabeta_pleco::PlecoSearcher::default();
+        s.set_min_depth(99);
+        s.set_clock_plan(Some(tight_clock()));
+        let t = Instant::now();
+        let r = s.search_result(&mut pleco::Board::from_fen(MIDDLEGAME).unwrap(), 100, 0);
+        assert!(r.bestmove.is_some() && r.depth < 99, "{:?} depth {}", r.bestmove, r.depth);
+        assert!(t.elapsed() < Duration::from_secs(2), "{:?}", t.elapsed());
+    }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_limits_match() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let board = || pleco::Board::from_fen(MIDDLEGAME).unwrap();
+    let mut s = PlecoSearcher::default();
+    assert_eq!(s.search_result(&mut board(), 0, 4).depth, 4, "movetime 0 is no time limit");
+    assert!(s.search_result(&mut board(), 50, 60).depth < 60);
+    s.set_min_depth(5);
+    assert!(s.search_result(&mut board(), 1, 0).depth >= 5);
+}
+
+fn final_depth(backend: &str, setup: &str, go: &str) -> u32 {
+    let out = Captured::default();
+    UciEngine::new().run_loop(format!("setoption name Backend value {}\n{}position fen {}\n{}\nquit\n", backend, setup, MIDDLEGAME, go).as_bytes(), out.clone());
+    let text = out.text();
+    assert!(text.contains("bestmove"), "{}", text);
+    text.lines().rev().find_map(|l| l.strip_prefix("info depth "))
+        .and_then(|l| l.split_whitespace().next()).and_then(|d| d.parse().ok()).unwrap()
+}
+
+#[test]
+fn uci_backends_honour_depth_movetime_and_min_depth() {
+    for backend in ["cozy", "pleco"] {
+        assert_eq!(final_depth(backend, "", "go depth 5 movetime 60000"), 5, "{}", backend);
+        assert!(final_depth(backend, "setoption name MinDepth value 5\n", "go movetime 1") >= 5, "{}", backend);
+    }
+}
@@ -0,0 +1,16 @@
+use cozy_chess::Board;
+
+#[test]
+fn profiling_collects_phase_counters_only_when_enabled() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    let b = Board::default();
+    let p = SearchParams { depth: 4, use_tt: true, order_captures: true, use_history: true, ..Default::default() };
+    let mut s = Searcher::default();
+    let _ = s.search_with_params(&b, p);
+    assert_eq!(s.profile().main_nodes, 0, "profile should stay empty when disabled");
+    s.set_profiling(true);
+    let _ = s.search_with_params(&b, p);
+    let prof = s.profile();
+    assert!(prof.main_nodes > 0 && prof.qsearch_nodes > 0);
+    assert!(!prof.movegen.is_zero(), "movegen time not recorded");
+}
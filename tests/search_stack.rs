@@ -0,0 +1,52 @@
+// Search workers get a configurable stack, and no line goes past MAX_PLY.
+use cozy_chess::Board;
+use piebot::search::affinity::{thread_pool, CorePinning};
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::options::EngineOptions;
+use piebot::search::score::MAX_PLY;
+use std::time::Duration;
+
+// Blocked pawns and kings alone: the tree stays narrow, so iterative deepening runs deep fast
+const SPARSE: &str = "8/8/3k4/3p4/3P4/3K4/8/8 w - - 0 1";
+
+fn params() -> SearchParams {
+    SearchParams { depth: 0, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, threads: 1, movetime: Some(Duration::from_millis(400)), ..Default::default() }
+}
+
+#[test]
+fn deep_searches_stop_at_max_ply() {
+    let b = Board::from_fen(SPARSE, false).unwrap();
+    let r = Searcher::default().search_with_params(&b, params());
+    assert!(r.bestmove.is_some());
+    assert!(r.seldepth as usize <= MAX_PLY, "seldepth {}", r.seldepth);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_deep_searches_stop_at_max_ply() {
+    let mut s = piebot::search::alphabeta_pleco::PlecoSearcher::default();
+    let r = s.search_result(&mut pleco::Board::from_fen(SPARSE).unwrap(), 400, 0);
+    assert!(r.bestmove.is_some());
+    assert!(r.seldepth as usize <= MAX_PLY, "seldepth {}", r.seldepth);
+}
+
+#[test]
+fn searches_run_inside_the_configured_pool() {
+    let b = Board::from_fen(SPARSE, false).unwrap();
+    let pool = thread_pool(2, &CorePinning::Off, 4);
+    let r = pool.install(|| Searcher::default().search_with_params(&b, SearchParams { threads: 2, ..params() }));
+    assert!(r.bestmove.is_some());
+}
+
+#[test]
+fn thread_stack_option_is_wired() {
+    let mut o = EngineOptions::default();
+    assert_eq!(o.stack_mb, 16);
+    assert!(!o.describe().contains("stack"));
+    o.set("stack", "64").unwrap();
+    assert_eq!(o.stack_mb, 64);
+    assert!(o.describe().contains(",stack=64"));
+    o.set("ThreadStack", "0").unwrap();
+    assert_eq!(o.stack_mb, 1);
+    assert!(o.uci_options().iter().any(|u| u.name == "ThreadStack"));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::bias_model;
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::TitanError;
+
+#[test]
+fn builder_rejects_contradictory_configs() {
+    let config_err = |r: piebot::Result<Searcher>| matches!(r, Err(TitanError::Config(_)));
+    assert!(config_err(Searcher::builder().hash_mb(0).build()));
+    assert!(config_err(Searcher::builder().killer_plies(0).build()));
+    assert!(config_err(Searcher::builder().use_nnue(true).build()));
+    assert!(config_err(Searcher::builder().use_nnue(true).material_eval(true).nnue_quant(bias_model(0)).build()));
+    assert!(config_err(Searcher::builder().eval_blend_percent(50).build()));
+    assert!(config_err(Searcher::builder().use_nnue(true).nnue_quant(bias_model(0)).eval_blend_percent(101).build()));
+    // A model that does not fit the network is caught before the first eval
+    let mut bad = bias_model(0);
+    bad.meta.input_dim = 10;
+    assert!(matches!(Searcher::builder().use_nnue(true).nnue_quant(bad).build(), Err(TitanError::Nnue(_))));
+}
+
+#[test]
+fn built_searcher_matches_setter_configuration() {
+    let b = Board::default();
+    let mut built = Searcher::builder().hash_mb(8).use_nnue(true).nnue_quant(bias_model(50)).build().unwrap();
+    let mut set = Searcher::default();
+    set.set_tt_capacity_mb(8);
+    set.set_use_nnue(true);
+    set.set_nnue_quant_model(bias_model(50));
+    assert_eq!(built.qsearch_eval_cp(&b), set.qsearch_eval_cp(&b));
+
+    let mut s = Searcher::builder().killer_plies(4).track_root_scores(true).build().unwrap();
+    let res = s.search_with_params(&b, SearchParams { depth: 6, use_tt: true, use_killers: true, ..Default::default() });
+    assert_eq!(res.depth, 6);
+    assert_eq!(s.root_score_table().len(), 6);
+}
@@ -0,0 +1,103 @@
+// Bitboard SEE threshold test, SEE-gated quiet ordering and cutoff-index stats.
+use cozy_chess::{Board, Move};
+use piebot::io::fen::BENCH_SUITE;
+use piebot::search::alphabeta::{CutoffStats, SearchParams, Searcher};
+use piebot::search::see::see_ge;
+
+fn mv(board: &Board, uci: &str) -> Move {
+    let mut found = None;
+    board.generate_moves(|ml| { for m in ml { if m.to_string() == uci { found = Some(m); } } false });
+    found.unwrap_or_else(|| panic!("{} not legal in {}", uci, board))
+}
+
+#[test]
+fn quiet_moves_that_hang_the_piece_fail() {
+    let b = Board::from_fen("4k3/8/3p4/8/8/2Q5/8/4K3 w - - 0 1", false).unwrap();
+    // The d6 pawn covers c5 and e5
+    assert!(!see_ge(&b, mv(&b, "c3c5"), 0));
+    assert!(!see_ge(&b, mv(&b, "c3e5"), 0));
+    assert!(see_ge(&b, mv(&b, "c3c4"), 0));
+    // A defended square is safe against a dearer attacker
+    let b = Board::from_fen("4k3/8/8/2r5/8/1P6/1N6/4K3 w - - 0 1", false).unwrap();
+    assert!(see_ge(&b, mv(&b, "b2c4"), 0));
+    let b = Board::from_fen("4k3/8/8/7r/8/8/1N6/R3K3 w Q - 0 1", false).unwrap();
+    assert!(!see_ge(&b, mv(&b, "a1a5"), 0));
+    // Castling never hangs anything
+    assert!(see_ge(&b, mv(&b, "e1a1"), 0));
+}
+
+#[test]
+fn x_rays_join_the_exchange() {
+    // Rxd5 is defended only through the other rook behind it
+    let b = Board::from_fen("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", false).unwrap();
+    assert!(see_ge(&b, mv(&b, "d2d5"), 0));
+    assert!(see_ge(&b, mv(&b, "d2d5"), 100));
+    assert!(!see_ge(&b, mv(&b, "d2d5"), 101));
+    let b = Board::from_fen("3rk3/3r4/8/3p4/8/8/3R4/4K3 w - - 0 1", false).unwrap();
+    assert!(!see_ge(&b, mv(&b, "d2d5"), 0));
+}
+
+#[test]
+fn captures_count_the_recapture() {
+    // Nxe5 wins a pawn and loses the knight to Nxe5
+    let b = Board::from_fen(BENCH_SUITE[1], false).unwrap();
+    assert!(see_ge(&b, mv(&b, "f3e5"), -220) && !see_ge(&b, mv(&b, "f3e5"), -219));
+    // Bxf4 takes an undefended queen
+    let b = Board::from_fen("4k3/8/8/8/5Q2/8/8/2b4K b - - 0 1", false).unwrap();
+    assert!(see_ge(&b, mv(&b, "c1f4"), 900) && !see_ge(&b, mv(&b, "c1f4"), 901));
+}
+
+fn params(see_quiets: bool) -> SearchParams {
+    SearchParams { depth: 6, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, see_quiets, ..Default::default() }
+}
+
+#[test]
+fn searches_report_beta_cutoffs_by_move_index() {
+    let b = Board::from_fen(BENCH_SUITE[3], false).unwrap();
+    let mut s = Searcher::default();
+    for on in [false, true] {
+        s.new_game();
+        let r = s.search_with_params(&b, params(on));
+        assert!(r.bestmove.is_some());
+        let c = s.cutoff_stats();
+        assert_eq!(c.by_index.len(), CutoffStats::BUCKETS);
+        assert!(c.total() > 100, "{:?}", c);
+        // Ordering puts the refutation first most of the time
+        assert!(c.first_move_rate() > 0.5 && c.mean_index() < 2.0, "{:?}", c);
+    }
+    // Stats belong to one search
+    s.search_with_params(&b, SearchParams { depth: 1, ..params(true) });
+    assert!(s.cutoff_stats().total() < 100);
+}
+
+#[test]
+fn cutoffs_by_depth_split_the_search_totals() {
+    let b = Board::from_fen(BENCH_SUITE[3], false).unwrap();
+    let mut s = Searcher::default();
+    s.search_with_params(&b, params(true));
+    let by_depth = s.cutoff_stats_by_depth();
+    assert_eq!(by_depth.len(), CutoffStats::DEPTHS);
+    let total = s.cutoff_stats();
+    for i in 0..CutoffStats::BUCKETS {
+        assert_eq!(by_depth.iter().map(|c| c.by_index[i]).sum::<u64>(), total.by_index[i]);
+    }
+    // Depth 1 nodes are the bulk of the tree
+    assert!(by_depth[1].total() > by_depth[3].total(), "{:?}", by_depth);
+    s.search_with_params(&b, SearchParams { depth: 1, ..params(true) });
+    assert!(s.cutoff_stats_by_depth()[2..].iter().all(|c| c.total() == 0), "reset per search");
+}
+
+fn pruned(quiets: bool, captures: bool) -> SearchParams { SearchParams { see_prune_quiets: quiets, see_prune_captures: captures, ..params(true) } }
+
+#[test]
+fn see_pruning_shrinks_the_tree_and_keeps_tactics() {
+    for fen in &BENCH_SUITE[1..3] {
+        let b = Board::from_fen(fen, false).unwrap();
+        let nodes = |p: SearchParams| { let mut s = Searcher::default(); s.search_with_params(&b, SearchParams { depth: 5, ..p }).nodes };
+        assert!(nodes(pruned(true, true)) < nodes(pruned(false, false)), "{}", fen);
+    }
+    // Rxd8 takes the queen and mates on the back rank
+    let b = Board::from_fen("3q2k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", false).unwrap();
+    let r = Searcher::default().search_with_params(&b, pruned(true, true));
+    assert_eq!(r.bestmove.as_deref(), Some("d1d8"));
+}
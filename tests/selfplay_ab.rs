@@ -0,0 +1,51 @@
+use piebot::search::alphabeta::SearchParams;
+use piebot::selfplay::ab::{run_ab, AbConfig, Sprt, SprtStatus, Tally};
+
+#[test]
+fn tally_score_and_elo() {
+    let mut t = Tally::default();
+    assert_eq!((t.score(), t.elo()), (0.5, Some(0.0)));
+    for p in [1.0, 0.5, 0.0, 1.0] { t.add(p); }
+    assert_eq!((t.wins, t.draws, t.losses, t.games()), (2, 1, 1, 4));
+    assert!((t.score() - 0.625).abs() < 1e-12);
+    assert!((t.elo().unwrap() - 88.7).abs() < 0.1);
+    assert_eq!(Tally { wins: 3, draws: 0, losses: 0 }.elo(), None, "a sweep has no finite Elo");
+}
+
+#[test]
+fn sprt_bounds_and_decisions() {
+    let sprt = Sprt::default();
+    let (lower, upper) = sprt.bounds();
+    assert!((lower + 2.944).abs() < 1e-3 && (upper - 2.944).abs() < 1e-3);
+    assert_eq!(sprt.llr(&Tally::default()), 0.0);
+    assert_eq!(sprt.llr(&Tally { wins: 0, draws: 10, losses: 0 }), 0.0, "no variance yet");
+    // A long run of even results favours H0, a clearly better score H1
+    assert_eq!(sprt.status(&Tally { wins: 3000, draws: 4000, losses: 3000 }), SprtStatus::AcceptH0);
+    assert_eq!(sprt.status(&Tally { wins: 700, draws: 400, losses: 400 }), SprtStatus::AcceptH1);
+    assert_eq!(sprt.status(&Tally { wins: 12, draws: 10, losses: 10 }), SprtStatus::Continue);
+}
+
+fn params(depth: u32) -> SearchParams {
+    SearchParams { depth, use_tt: true, order_captures: true, use_history: true, use_killers: true, ..SearchParams::default() }
+}
+
+#[test]
+fn deeper_search_wins_the_match_and_stops_early() {
+    let cfg = AbConfig {
+        base: params(1), exp: params(3), max_pairs: 20, max_plies: 120, random_plies: 4, seed: 5,
+        sprt: Some(Sprt { elo0: 0.0, elo1: 200.0, alpha: 0.1, beta: 0.1 }),
+    };
+    let mut pairs = 0;
+    let r = run_ab(&cfg, |_| { pairs += 1; true });
+    assert_eq!(r.tally.games(), 2 * pairs);
+    assert!(r.tally.score() > 0.5, "{:?}", r.tally);
+    assert_eq!(r.status, SprtStatus::AcceptH1);
+    assert!(pairs < 20, "stopped by the SPRT after {} pairs", pairs);
+}
+
+#[test]
+fn hook_can_stop_and_no_sprt_never_decides() {
+    let cfg = AbConfig { base: params(1), exp: params(1), max_pairs: 10, max_plies: 40, random_plies: 2, seed: 1, sprt: None };
+    let r = run_ab(&cfg, |r| r.tally.games() < 4);
+    assert_eq!((r.tally.games(), r.llr, r.status), (4, None, SprtStatus::Continue));
+}
@@ -0,0 +1,71 @@
+use cozy_chess::Board;
+use piebot::io::epd::parse_epd;
+use piebot::io::fen::parse_fen_line;
+use piebot::search::label::LabelMode;
+use piebot::search::options::EngineOptions;
+use piebot::selfplay::epd::{epd_line, result_str, write_epd};
+use piebot::selfplay::frc::frc_board;
+use piebot::selfplay::{flatten_game_to_records, game_positions, generate_games, read_games, write_games, GameRecord, SelfPlayParams};
+use piebot::search::zobrist;
+
+fn params() -> SelfPlayParams {
+    SelfPlayParams {
+        games: 2, max_plies: 10, threads: 1, use_engine: false, depth: 2, movetime_ms: None, seed: 7,
+        temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0, opening_sources: Vec::new(),
+    }
+}
+
+#[test]
+fn line_has_result_and_score_opcodes() {
+    let line = epd_line(&Board::default(), 1, 25);
+    assert_eq!(line, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - c0 \"1-0\"; ce 25;");
+    assert_eq!((result_str(0), result_str(-1)), ("1/2-1/2", "0-1"));
+    let case = parse_epd(&line, 0).unwrap();
+    assert_eq!(case.board, Board::default());
+}
+
+#[test]
+fn positions_match_the_shard_records() {
+    for g in generate_games(&params()) {
+        let keys: Vec<u64> = game_positions(&g).iter().map(zobrist::compute).collect();
+        let recs: Vec<u64> = flatten_game_to_records(&g).iter().map(|r| r.key).collect();
+        assert_eq!(keys, recs);
+    }
+}
+
+#[test]
+fn export_writes_one_line_per_position() {
+    let games = generate_games(&params());
+    let path = std::env::temp_dir().join(format!("piebot_selfplay_{}.epd", std::process::id()));
+    let n = write_epd(&games, &path, &EngineOptions::default(), LabelMode::Qsearch, 2).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    let total: usize = games.iter().map(|g| game_positions(g).len()).sum();
+    assert_eq!((n, text.lines().count()), (total, total));
+    let first = text.lines().next().unwrap();
+    assert!(first.starts_with("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - c0 \""));
+    assert!(text.lines().all(|l| l.contains(" ce ") && l.ends_with(';')));
+}
+
+#[test]
+fn chess960_starts_keep_their_castling_files() {
+    let start = frc_board(0, 959);
+    let line = epd_line(&start, 0, 0);
+    assert_eq!(parse_fen_line(&line).unwrap(), start, "{}", line);
+}
+
+#[test]
+fn shard_directory_exports_from_its_games_file() {
+    let mut games = generate_games(&params());
+    games.push(GameRecord { start: frc_board(0, 959), moves: Vec::new(), result: 0 });
+    let dir = std::env::temp_dir().join(format!("piebot_games_{}", std::process::id()));
+    write_games(&games, &dir).unwrap();
+    let back = read_games(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(back.len(), games.len());
+    for (a, b) in games.iter().zip(&back) {
+        assert_eq!((&a.start, &a.moves, a.result), (&b.start, &b.moves, b.result));
+        assert_eq!(game_positions(a), game_positions(b));
+    }
+}
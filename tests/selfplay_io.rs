@@ -1,4 +1,5 @@
-use piebot::selfplay::{SelfPlayParams, generate_games, write_shards, read_shard, RECORD_SIZE, SHARD_MAGIC};
+use piebot::selfplay::stats::shard_stats;
+use piebot::selfplay::{SelfPlayParams, ShardMeta, generate_games, write_shards, read_shard, read_shard_meta, RECORD_SIZE, SHARD_MAGIC, SHARD_MAGIC_V1};
 use std::fs::{read_dir, remove_file, create_dir_all};
 
 #[test]
@@ -6,13 +7,79 @@ fn write_and_read_shard() {
     let params = SelfPlayParams {
         games: 3, max_plies: 8, threads: 1, use_engine: false, depth: 2, movetime_ms: None, seed: 123,
         temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
-        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0, opening_sources: Vec::new(),
     };
     let games = generate_games(&params);
     let outdir = std::path::Path::new("target/selfplay_test");
     create_dir_all(outdir).unwrap();
-    let shards = write_shards(&games, outdir, 10).unwrap();
+    let meta = ShardMeta::from_params(&params);
+    let shards = write_shards(&games, outdir, 10, &meta).unwrap();
     assert!(!shards.is_empty());
     let recs = read_shard(&shards[0]).unwrap();
     assert!(!recs.is_empty());
+    let back = read_shard_meta(&shards[0]).unwrap().expect("v2 shards carry metadata");
+    assert_eq!(back, meta);
+    assert_eq!((back.seed, back.depth, back.openings.as_slice()), (123, 2, &["start,w=1".to_string()][..]));
+    assert_eq!(back.engine_version, env!("CARGO_PKG_VERSION"));
+    assert!(back.timestamp > 0);
+}
+
+#[test]
+fn v1_shards_without_metadata_still_read() {
+    let dir = std::path::Path::new("target/selfplay_test_v1");
+    create_dir_all(dir).unwrap();
+    let path = dir.join("shard_000000.bin");
+    let mut bytes = SHARD_MAGIC_V1.to_vec();
+    let mut rec = [0u8; RECORD_SIZE];
+    rec[0..8].copy_from_slice(&42u64.to_le_bytes());
+    rec[8] = 1;
+    bytes.extend_from_slice(&rec);
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(read_shard_meta(&path).unwrap().is_none());
+    let recs = read_shard(&path).unwrap();
+    assert_eq!((recs.len(), recs[0].key, recs[0].result), (1, 42, 1));
+
+    // A corrupt header length is rejected rather than allocated
+    let mut bad = SHARD_MAGIC.to_vec();
+    bad.extend_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(&path, &bad).unwrap();
+    assert!(read_shard(&path).is_err());
+    remove_file(&path).ok();
+}
+
+#[test]
+fn stats_report_balance_and_flag_bad_shards() {
+    let params = SelfPlayParams {
+        games: 4, max_plies: 12, threads: 1, use_engine: false, depth: 2, movetime_ms: None, seed: 7,
+        temperature_tau: 0.0, temp_cp_scale: 200.0, dirichlet_alpha: 0.3, dirichlet_epsilon: 0.0,
+        dirichlet_plies: 0, temperature_moves: 0, openings_path: None, temperature_tau_final: 0.1, random_opening_plies: 0, opening_sources: Vec::new(),
+    };
+    let games = generate_games(&params);
+    let plies: usize = games.iter().map(|g| g.moves.len()).sum();
+    let dir = std::path::Path::new("target/selfplay_test_stats");
+    std::fs::remove_dir_all(dir).ok();
+    let shards = write_shards(&games, dir, 10, &ShardMeta::from_params(&params)).unwrap();
+    let st = shard_stats(dir).unwrap();
+    assert_eq!((st.files, st.positions), (shards.len(), plies as u64));
+    assert!(st.bad_files.is_empty());
+    assert_eq!(st.results.iter().sum::<u64>(), st.positions);
+    assert_eq!(st.stm.iter().sum::<u64>(), st.positions);
+    // Every game starts from the start position
+    assert!(st.unique_keys < st.positions && st.unique_ratio() < 1.0);
+    assert_eq!(st.avg_game_plies, Some(plies as f64 / 4.0));
+    assert!(st.expected_collisions() < 1e-9);
+
+    // A torn write and a foreign file are reported, not counted
+    let mut torn = std::fs::read(&shards[0]).unwrap();
+    torn.truncate(torn.len() - 3);
+    std::fs::write(dir.join("torn.bin"), &torn).unwrap();
+    std::fs::write(dir.join("other.bin"), b"NOTASHARD").unwrap();
+    assert!(read_shard(dir.join("torn.bin")).unwrap_err().to_string().contains("truncated"));
+    let again = shard_stats(dir).unwrap();
+    assert_eq!((again.files, again.positions), (st.files, st.positions));
+    let mut bad: Vec<_> = again.bad_files.iter().map(|p| (p.path.file_name().unwrap().to_string_lossy().into_owned(), p.reason.clone())).collect();
+    bad.sort();
+    assert_eq!(bad[0], ("other.bin".to_string(), "bad magic".to_string()));
+    assert_eq!(bad[1].0, "torn.bin");
+    std::fs::remove_dir_all(dir).ok();
 }
@@ -0,0 +1,43 @@
+use piebot::search::skill::SkillLevel;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+#[test]
+fn full_strength_always_picks_best() {
+    let s = SkillLevel::new(20);
+    assert!(s.depth_cap().is_none());
+    let scored = [("a", 10), ("b", 300), ("c", -50)];
+    let mut rng = SmallRng::seed_from_u64(7);
+    for _ in 0..50 { assert_eq!(s.pick(&scored, &mut rng), Some("b")); }
+}
+
+#[test]
+fn low_levels_make_mistakes_and_cap_depth() {
+    let weak = SkillLevel::new(1);
+    let strong = SkillLevel::new(15);
+    assert!(weak.depth_cap().unwrap() < strong.depth_cap().unwrap());
+    assert!(weak.eval_noise_cp() > strong.eval_noise_cp());
+    let scored = [("a", 10), ("b", 30), ("c", 20), ("d", 0)];
+    let mut rng = SmallRng::seed_from_u64(1);
+    let non_best = (0..200).filter(|_| weak.pick(&scored, &mut rng) != Some("b")).count();
+    assert!(non_best > 50, "level 1 should often miss the best move: {non_best}/200");
+}
+
+#[test]
+fn levels_are_clamped() {
+    assert_eq!(SkillLevel::new(0).level(), 1);
+    assert_eq!(SkillLevel::new(99).level(), 20);
+}
+
+#[test]
+fn skill_search_keeps_to_the_movetime() {
+    use piebot::uci::UciEngine;
+    use std::time::{Duration, Instant};
+    let mut e = UciEngine::new();
+    e.set_backend(piebot::uci::Backend::Cozy);
+    assert!(e.handle_line("setoption name Skill Level value 19"));
+    assert!(e.handle_line("position fen r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N2N2/PP2BPPP/R1BQK2R w KQ - 0 8"));
+    let t0 = Instant::now();
+    assert!(e.handle_line("go movetime 200"));
+    assert!(t0.elapsed() < Duration::from_secs(3), "{:?}", t0.elapsed());
+}
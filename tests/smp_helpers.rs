@@ -0,0 +1,46 @@
+#![cfg(feature = "board-pleco")]
+use piebot::search::alphabeta::HelperScheme;
+use piebot::search::alphabeta_pleco::{helper_skips, PlecoSearcher, SmpMode};
+use piebot::search::options::EngineOptions;
+
+#[test]
+fn main_thread_and_depth_one_are_never_skipped() {
+    for d in 1..30 { assert!(!helper_skips(0, d)); }
+    for wid in 0..64 { assert!(!helper_skips(wid, 1)); }
+}
+
+#[test]
+fn first_helpers_take_alternate_depths() {
+    let searched = |wid| (2..=9).filter(|&d| !helper_skips(wid, d)).collect::<Vec<u32>>();
+    assert_eq!(searched(1), vec![2, 4, 6, 8]);
+    assert_eq!(searched(2), vec![3, 5, 7, 9]);
+    // Longer runs further out: two searched, two skipped
+    assert_eq!(searched(3), vec![4, 5, 8, 9]);
+    // Every depth is searched by one of the first few helpers
+    for d in 2..40 { assert!((1..=4).any(|w| !helper_skips(w, d)), "depth {}", d); }
+    // The patterns cycle after 20 helpers
+    for d in 1..30 { assert_eq!(helper_skips(3, d), helper_skips(23, d)); }
+}
+
+#[test]
+fn skipping_helpers_complete_the_search() {
+    let mut s = PlecoSearcher::default();
+    s.set_threads(4);
+    s.set_smp_mode(SmpMode::LazyIndep);
+    s.set_helper_scheme(HelperScheme::Skip);
+    let mut b = pleco::Board::from_fen("r4rk1/pp3ppp/2n5/3p4/3P4/2N5/PP3PPP/R4RK1 w - - 0 1").unwrap();
+    let r = s.search_result(&mut b, 60_000, 5);
+    assert!(r.bestmove.is_some() && r.depth == 5, "{:?}", r);
+}
+
+#[test]
+fn smp_helpers_option() {
+    let mut o = EngineOptions::default();
+    assert_eq!(o.smp_helpers, HelperScheme::Rotate);
+    assert!(!o.describe().contains("smp_helpers"));
+    o.set("SMPHelpers", "skip").unwrap();
+    assert!(o.describe().contains(",smp_helpers=skip"));
+    assert!(o.uci_option_lines().iter().any(|l| l.starts_with("option name SMPHelpers type combo default skip var rotate var skip")));
+    assert_eq!(o.build_pleco_searcher().unwrap().helper_scheme(), HelperScheme::Skip);
+    assert!(o.set("smp_helpers", "sideways").is_err());
+}
@@ -0,0 +1,79 @@
+// Safe SMP invariant: with `smp_safe` on, every multi-threaded mode returns a score
+// close to the single-threaded search at the same depth. Lazy helpers only change
+// move order (30cp worst case with the jumpy test net below, 0 for cozy); parallel
+// young brothers are not LMR-reduced, so in-tree splits differ more (108cp). A best
+// move kept from an earlier depth (268cp here without the safe profile) fails both.
+mod common;
+
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::options::EngineOptions;
+
+const LAZY_MARGIN_CP: i32 = 40;
+#[cfg(feature = "board-pleco")]
+const IN_TREE_MARGIN_CP: i32 = 120;
+const DEPTH: u32 = 4;
+
+const FENS: &[&str] = &[
+    "r4rk1/pp3ppp/2n5/3p4/3P4/2N5/PP3PPP/R4RK1 w - - 0 1",
+    "4k3/8/8/3pp3/8/2N2B2/PPP5/4K3 w - - 0 1",
+    "2r3k1/5pp1/p3p2p/1p1nP3/3P4/P4N2/1P3PPP/2R3K1 b - - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+];
+
+// Small fixed pseudo-random network: scores swing from ply to ply (material-only
+// pleco eval is mostly 0) without the cost of a real net in debug builds
+#[cfg(feature = "board-pleco")]
+fn net() -> piebot::eval::nnue::loader::QuantNnue { common::random_net(2, 0x9E37_79B9_7F4A_7C15) }
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_smp_modes_match_single_thread() {
+    use piebot::search::alphabeta_pleco::{PlecoEvalMode, PlecoSearcher, SmpMode};
+    let model = std::sync::Arc::new(net());
+    let run = |fen: &str, mode: SmpMode, threads: usize| {
+        let mut s = PlecoSearcher::default();
+        s.set_threads(threads);
+        s.set_smp_mode(mode);
+        s.set_smp_safe(true);
+        s.set_nnue_quant_model(model.clone());
+        s.set_eval_mode(PlecoEvalMode::Nnue);
+        let mut b = pleco::Board::from_fen(fen).unwrap();
+        s.search_result(&mut b, 60_000, DEPTH)
+    };
+    for fen in FENS {
+        let base = run(fen, SmpMode::Off, 1);
+        for mode in [SmpMode::InTree, SmpMode::LazyIndep, SmpMode::LazyCoop, SmpMode::LazyHybrid] {
+            let r = run(fen, mode, 4);
+            assert!(r.bestmove.is_some(), "{:?} no move for {}", mode, fen);
+            let margin = if mode == SmpMode::InTree { IN_TREE_MARGIN_CP } else { LAZY_MARGIN_CP };
+            assert!((r.score.value() - base.score.value()).abs() <= margin,
+                "{:?} {} cp vs single-threaded {} cp on {}", mode, r.score.value(), base.score.value(), fen);
+        }
+    }
+}
+
+#[test]
+fn cozy_threads_match_single_thread() {
+    let mut s = Searcher::builder().hash_mb(8).build().unwrap();
+    for fen in FENS {
+        let b = cozy_chess::Board::from_fen(fen, false).unwrap();
+        let params = SearchParams { depth: DEPTH, use_tt: true, threads: 1, ..EngineOptions::default().search_params() };
+        s.new_game();
+        let base = s.search_with_params(&b, params).score.value();
+        s.new_game();
+        let smp = s.search_with_params(&b, SearchParams { threads: 4, ..params }).score.value();
+        assert!((smp - base).abs() <= LAZY_MARGIN_CP, "{} cp vs single-threaded {} cp on {}", smp, base, fen);
+    }
+}
+
+#[test]
+fn smp_safe_option_reaches_the_searcher() {
+    let mut o = EngineOptions::default();
+    assert!(!o.smp_safe);
+    o.apply_spec("smp_safe=on").unwrap();
+    assert!(o.describe().contains(",smp_safe=on"));
+    assert!(o.uci_option_lines().contains(&"option name SMPSafe type check default true".to_string()));
+    #[cfg(feature = "board-pleco")]
+    assert!(o.build_pleco_searcher().unwrap().smp_safe());
+}
@@ -0,0 +1,48 @@
+// Lazy SMP helper profiles come from a master seed and the worker index.
+#![cfg(feature = "board-pleco")]
+use piebot::search::alphabeta_pleco::{HelperProfile, PlecoSearcher, SmpMode};
+use piebot::search::options::EngineOptions;
+
+#[test]
+fn seed_zero_keeps_the_fixed_scheme() {
+    assert_eq!(HelperProfile::derive(0, 0), HelperProfile { window_extra: 0, tt_first: true, order_offset: 0, lmr_aggr: 0 });
+    assert_eq!(HelperProfile::derive(0, 1), HelperProfile { window_extra: 20, tt_first: false, order_offset: 1, lmr_aggr: 2 });
+    assert_eq!(HelperProfile::derive(0, 5), HelperProfile { window_extra: 40, tt_first: false, order_offset: 5, lmr_aggr: 2 });
+}
+
+#[test]
+fn seeded_profiles_are_reproducible_and_vary_with_the_seed() {
+    let profiles = |seed| (0..8).map(|w| HelperProfile::derive(seed, w)).collect::<Vec<_>>();
+    assert_eq!(profiles(7), profiles(7));
+    assert_ne!(profiles(7), profiles(8));
+    // The main thread is never diversified
+    assert_eq!(profiles(7)[0], HelperProfile::derive(0, 0));
+    for p in &profiles(7)[1..] {
+        assert!([0, 20, 40].contains(&p.window_extra) && (1..=7).contains(&p.order_offset) && (1..=2).contains(&p.lmr_aggr), "{:?}", p);
+    }
+}
+
+#[test]
+fn smp_seed_option_reaches_the_searcher() {
+    let mut o = EngineOptions::default();
+    assert!(!o.describe().contains("smp_seed"));
+    o.apply_spec("smp_seed=42").unwrap();
+    assert!(o.describe().contains(",smp_seed=42"));
+    assert!(o.uci_option_lines().iter().any(|l| l.starts_with("option name SMPSeed type spin default 42")));
+    assert_eq!(o.build_pleco_searcher().unwrap().smp_seed(), 42);
+}
+
+#[test]
+fn seeded_lazy_search_completes() {
+    // LazyCoop root workers and the LazyHybrid pre-warm helper take seeded profiles too
+    for mode in [SmpMode::LazyIndep, SmpMode::LazyCoop, SmpMode::LazyHybrid] {
+        let mut s = PlecoSearcher::default();
+        s.set_tt_capacity_mb(16);
+        s.set_threads(3);
+        s.set_smp_mode(mode);
+        s.set_smp_seed(12345);
+        let mut b = pleco::Board::from_fen("r4rk1/pp3ppp/2n5/3p4/3P4/2N5/PP3PPP/R4RK1 w - - 0 1").unwrap();
+        let r = s.search_result(&mut b, 60_000, 4);
+        assert!(r.bestmove.is_some() && r.depth == 4, "{:?}: {:?}", mode, r);
+    }
+}
@@ -15,6 +15,66 @@ fn split_points_score_equal_multi_vs_single() {
     let mut p2 = p1; p2.threads = 4;
     let r2 = s2.search_with_params(&b, p2);
 
-    assert_eq!(r2.score_cp, r1.score_cp, "split points multi-thread score differs from single-thread");
+    assert_eq!(r2.score.value(), r1.score.value(), "split points multi-thread score differs from single-thread");
 }
 
+
+#[test]
+fn ybwc_splits_only_after_the_eldest_and_wastes_less() {
+    use piebot::search::alphabeta::{SearchParams, Searcher, SplitConfig};
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 2 3";
+    let b = Board::from_fen(fen, false).unwrap();
+    // A serial root makes every split an in-tree one
+    let base = SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, threads: 4, serial_root: true, ..Default::default() };
+    let run = |split: SplitConfig| {
+        let mut s = Searcher::default();
+        let r = s.search_with_params(&b, SearchParams { split, ..base });
+        (r, s.split_stats())
+    };
+    let (ybwc, y) = run(SplitConfig::default());
+    let (jam, j) = run(SplitConfig::JAMBOREE);
+    assert!(ybwc.bestmove.is_some() && jam.bestmove.is_some());
+    assert!(y.splits > 0 && j.splits > 0, "{:?} {:?}", y, j);
+    assert!(y.wasted_nodes <= y.split_nodes && j.wasted_nodes <= j.split_nodes);
+    assert!(y.wasted_fraction() <= j.wasted_fraction(), "ybwc {:?} vs jamboree {:?}", y, j);
+
+    // Thresholds gate splitting
+    let (_, none) = run(SplitConfig { min_depth: 99, ..SplitConfig::default() });
+    assert_eq!(none.splits, 0);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_in_tree_split_policies() {
+    use piebot::search::alphabeta::SplitConfig;
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 2 3";
+    for split in [SplitConfig::default(), SplitConfig::JAMBOREE] {
+        let mut s = PlecoSearcher::default();
+        s.set_threads(4);
+        s.set_split(split);
+        let mut b = pleco::Board::from_fen(fen).unwrap();
+        let (mv, _, _) = s.search_movetime(&mut b, 60_000, 5);
+        assert!(mv.is_some());
+        let st = s.split_stats();
+        assert!(st.splits > 0 && st.wasted_nodes <= st.split_nodes, "{:?}", st);
+    }
+}
+
+#[test]
+fn split_workers_prune_like_the_main_search() {
+    use piebot::search::alphabeta::{SearchParams, Searcher, SplitConfig};
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 2 3";
+    let b = Board::from_fen(fen, false).unwrap();
+    let base = SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, threads: 4, serial_root: true, ..Default::default() };
+    let split_nodes = |p: SearchParams| {
+        let mut s = Searcher::default();
+        assert!(s.search_with_params(&b, p).bestmove.is_some());
+        s.split_stats().split_nodes
+    };
+    for split in [SplitConfig::default(), SplitConfig::JAMBOREE] {
+        let full = split_nodes(SearchParams { split, ..base });
+        let pruned = split_nodes(SearchParams { split, use_lmr: true, use_nullmove: true, use_killers: true, ..base });
+        assert!(pruned < full, "{:?}: pruned {} vs full {}", split, pruned, full);
+    }
+}
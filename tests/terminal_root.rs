@@ -0,0 +1,56 @@
+// Mated and stalemated roots are reported at once, without iterating depths.
+mod common;
+
+use common::Captured;
+
+const MATED: &str = "7k/6Q1/6K1/8/8/8/8/8 b - - 0 1";
+const STALEMATE: &str = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1";
+
+// The info and bestmove lines answering one `go` on `fen`
+fn go(backend: &str, setup: &str, fen: &str, go: &str) -> Vec<String> {
+    let out = Captured::default();
+    let mut e = piebot::uci::UciEngine::new();
+    e.run_loop(format!("setoption name Backend value {}\n{}position fen {}\ngo {}\nquit\n", backend, setup, fen, go).as_bytes(), out.clone());
+    let text = out.text();
+    text.lines().filter(|l| l.starts_with("info depth") || l.starts_with("bestmove")).map(str::to_string).collect()
+}
+
+#[test]
+fn uci_reports_mate_and_draw_once_for_every_go_path() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    let setups = ["", "setoption name Skill Level value 5\n", "setoption name MultiPV value 3\n"];
+    for backend in backends {
+        for setup in setups {
+            for go_args in ["depth 5", "movetime 200"] {
+                let mated = go(backend, setup, MATED, go_args);
+                assert_eq!(mated.len(), 2, "{} {:?} {:?}", backend, setup, mated);
+                assert!(mated[0].starts_with("info depth 0 ") && mated[0].contains(" score mate 0 "), "{} {:?}", backend, mated);
+                assert_eq!(mated[1], "bestmove 0000");
+                let stale = go(backend, setup, STALEMATE, go_args);
+                assert_eq!(stale.len(), 2, "{} {:?} {:?}", backend, setup, stale);
+                assert!(stale[0].contains(" score cp 0 "), "{} {:?}", backend, stale);
+                assert_eq!(stale[1], "bestmove 0000");
+            }
+        }
+    }
+}
+
+#[test]
+fn cozy_search_returns_at_depth_zero() {
+    use piebot::search::alphabeta::{SearchParams, Searcher};
+    let b = cozy_chess::Board::from_fen(MATED, false).unwrap();
+    let r = Searcher::default().search_with_params(&b, SearchParams { depth: 8, ..Default::default() });
+    assert_eq!((r.bestmove, r.depth, r.nodes, r.score.mate_moves()), (None, 0, 0, Some(0)));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_search_returns_at_depth_zero() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    let mut b = pleco::Board::from_fen(MATED).unwrap();
+    let r = s.search_result(&mut b, 10_000, 0);
+    assert_eq!((r.bestmove, r.depth, r.nodes, r.score.mate_moves()), (None, 0, 0, Some(0)));
+    let mut b = pleco::Board::from_fen(STALEMATE).unwrap();
+    assert_eq!(s.search_movetime(&mut b, 10_000, 0), (None, 0, 0));
+}
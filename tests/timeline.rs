@@ -0,0 +1,92 @@
+use cozy_chess::Board;
+use piebot::io::fen::board_fen;
+use piebot::io::timeline::{GameTimeline, TIMELINE_VERSION};
+use piebot::search::alphabeta::{Searcher, SearchParams};
+use piebot::uci::UciEngine;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[test]
+fn timeline_records_plies_and_round_trips() {
+    let mut board = Board::default();
+    let mut t = GameTimeline::new(1, &board, "exp", "base");
+    let mut s = Searcher::default();
+    for engine in ["exp", "base", "exp"] {
+        let res = s.search_with_params(&board, SearchParams { depth: 2, ..Default::default() });
+        t.push(&board, engine, &res);
+        board.play(res.bestmove.as_deref().unwrap().parse().unwrap());
+    }
+    t.result = Some("1/2-1/2".to_string());
+    let plies: Vec<usize> = t.points.iter().map(|p| p.ply).collect();
+    let moves: Vec<u32> = t.points.iter().map(|p| p.move_number).collect();
+    assert_eq!(plies, vec![0, 1, 2]);
+    assert_eq!(moves, vec![1, 1, 2]);
+    assert_eq!(t.points[1].side, "b");
+    assert!(t.points.iter().all(|p| p.depth == 2 && p.nodes > 0));
+
+    let v: serde_json::Value = serde_json::from_str(&t.to_json()).unwrap();
+    assert_eq!(v["version"], TIMELINE_VERSION);
+    assert!(v["points"][0]["move"].is_string(), "move is serialized under its plotting name");
+    let back: GameTimeline = serde_json::from_value(v).unwrap();
+    assert_eq!(back, t);
+}
+
+#[test]
+fn uci_timeline_dir_writes_one_file_per_game() {
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let dir = std::env::temp_dir().join(format!("piebot_timeline_{}_{}", backend, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut e = UciEngine::new();
+        e.handle_line(&format!("setoption name Backend value {}", backend));
+        e.handle_line(&format!("setoption name TimelineDir value {}", dir.display()));
+        e.handle_line("position startpos moves e2e4");
+        e.handle_line("go depth 2");
+        e.handle_line("position startpos moves e2e4 e7e5 g1f3");
+        e.handle_line("go depth 2");
+        e.handle_line("ucinewgame");
+        e.handle_line("position startpos");
+        e.handle_line("go depth 1");
+        e.handle_line("ucinewgame");
+        e.handle_line(&format!("position fen {} moves e1f1", KIWIPETE));
+        e.handle_line("go depth 1");
+
+        let first = GameTimeline::load(&dir.join("game_001.json")).unwrap();
+        assert_eq!((first.white.as_str(), first.black.as_str()), ("opponent", "uci"));
+        assert_eq!(first.start_fen, board_fen(&Board::default()), "the game's start, not the first searched position");
+        assert_eq!(first.points.iter().map(|p| p.ply).collect::<Vec<_>>(), vec![1, 3]);
+        let second = GameTimeline::load(&dir.join("game_002.json")).unwrap();
+        assert_eq!(second.points.len(), 1);
+        assert_eq!(second.points[0].depth, 1);
+        let third = GameTimeline::load(&dir.join("game_003.json")).unwrap();
+        assert_eq!(third.start_fen, KIWIPETE);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[test]
+fn wdl_points_put_differently_scaled_evals_on_one_scale() {
+    use piebot::search::wdl::WdlModel;
+    let board = Board::default();
+    let mut s = Searcher::default();
+    let res = s.search_with_params(&board, SearchParams { depth: 2, ..Default::default() });
+    let mut t = GameTimeline::new(1, &board, "exp", "base");
+    t.push(&board, "base", &res);
+    t.push_wdl(&board, "exp", &res, &WdlModel::default());
+    assert_eq!(t.points[0].wdl, None);
+    let [w, d, l] = t.points[1].wdl.unwrap();
+    assert_eq!(w + d + l, 1000);
+
+    // An eval that reads twice as large needs a model twice as wide to mean the same
+    let narrow = WdlModel::default();
+    let wide = WdlModel { a: [200.0, 200.0], b: [120.0, 80.0] };
+    let (e1, e2) = (narrow.expected_score(150, 78), wide.expected_score(300, 78));
+    assert!((e1 - e2).abs() < 1e-9 && e1 > 0.5, "{} vs {}", e1, e2);
+    assert!((narrow.expected_score(0, 40) - 0.5).abs() < 1e-9);
+
+    // The field is optional: files written before it still load, and it is not written when absent
+    let json = t.to_json();
+    assert_eq!(json.matches("\"wdl\"").count(), 1);
+    let back: GameTimeline = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, t);
+}
@@ -0,0 +1,222 @@
+use piebot::search::timeman::{allocate, Calibration, ClockPlan, GoLimits, ManualClock, TimeBudget, TimeConfig, TimeManager};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+fn ms(x: u64) -> Duration { Duration::from_millis(x) }
+
+fn random_go(rng: &mut SmallRng) -> GoLimits {
+    GoLimits {
+        remaining: Some(ms(rng.gen_range(0..600_000))),
+        increment: ms(if rng.gen_bool(0.5) { rng.gen_range(0..5_000) } else { 0 }),
+        moves_to_go: if rng.gen_bool(0.3) { Some(rng.gen_range(0..60)) } else { None },
+        movetime: None,
+    }
+}
+
+#[test]
+fn allocation_respects_hard_limit_and_minimum_for_random_clocks() {
+    let mut rng = SmallRng::seed_from_u64(4424);
+    let cfg = TimeConfig::default();
+    for _ in 0..20_000 {
+        let go = random_go(&mut rng);
+        let b = allocate(&go, &cfg).unwrap();
+        let available = go.remaining.unwrap().saturating_sub(cfg.move_overhead);
+        assert!(b.hard <= available, "{:?} -> {:?}", go, b);
+        assert!(b.soft <= b.hard, "{:?} -> {:?}", go, b);
+        assert!(b.soft >= cfg.min_per_move.min(available), "{:?} -> {:?}", go, b);
+    }
+}
+
+#[test]
+fn movetime_and_untimed_searches() {
+    let cfg = TimeConfig::default();
+    let b = allocate(&GoLimits { movetime: Some(ms(1000)), ..Default::default() }, &cfg).unwrap();
+    assert_eq!(b, TimeBudget { soft: ms(990), hard: ms(990) });
+    let tiny = allocate(&GoLimits { movetime: Some(ms(3)), ..Default::default() }, &cfg).unwrap();
+    assert_eq!(tiny.hard, ms(3), "minimum is capped by the movetime itself");
+    assert!(allocate(&GoLimits::default(), &cfg).is_none());
+}
+
+// Simulated iterative deepening: iterations grow by a random factor, the search
+// aborts at the hard deadline, and a new iteration starts only when allowed
+fn simulate_search(rng: &mut SmallRng, budget: TimeBudget) -> Duration {
+    let clock = ManualClock::new();
+    let tm = TimeManager::new(&clock, budget);
+    let mut iter = ms(rng.gen_range(0..3));
+    let mut last = Duration::ZERO;
+    loop {
+        if last > Duration::ZERO && !tm.can_start_iteration(last, 2.0) { break; }
+        let left = tm.hard_remaining();
+        if iter >= left { clock.advance(left); break; }
+        clock.advance(iter);
+        last = iter;
+        iter = iter.mul_f32(rng.gen_range(1.5..5.0)).max(ms(1));
+        if tm.should_stop() { break; }
+    }
+    assert!(tm.elapsed() <= budget.hard);
+    tm.elapsed()
+}
+
+#[test]
+fn simulated_games_never_flag() {
+    let mut rng = SmallRng::seed_from_u64(7);
+    let cfg = TimeConfig::default();
+    for game in 0..200 {
+        let base = ms(rng.gen_range(1_000..120_000));
+        let inc = ms([0, 100, 1_000, 2_000][game % 4]);
+        let mut clock = base;
+        for _ in 0..150 {
+            let b = allocate(&GoLimits { remaining: Some(clock), increment: inc, ..Default::default() }, &cfg).unwrap();
+            // The search alone never eats into the overhead reserved for transport latency
+            let search = simulate_search(&mut rng, b);
+            assert!(search <= clock.saturating_sub(cfg.move_overhead), "clock {:?} search {:?} budget {:?}", clock, search, b);
+            let latency = ms(rng.gen_range(0..=cfg.move_overhead.as_millis() as u64)).min(clock - search);
+            clock = clock - search - latency + inc;
+            if clock.is_zero() { break; }
+        }
+    }
+}
+
+#[test]
+fn iteration_gate_uses_soft_and_predicted_hard() {
+    let clock = ManualClock::new();
+    let tm = TimeManager::new(&clock, TimeBudget { soft: ms(100), hard: ms(300) });
+    assert!(tm.can_start_iteration(ms(50), 2.0));
+    clock.set(ms(90));
+    assert!(!tm.can_start_iteration(ms(120), 2.0), "would overrun the hard limit");
+    clock.set(ms(100));
+    assert!(!tm.can_start_iteration(ms(1), 2.0), "past the soft limit");
+    clock.set(ms(300));
+    assert!(tm.should_stop());
+    assert_eq!(tm.hard_remaining(), Duration::ZERO);
+}
+
+#[test]
+fn unstable_root_stretches_only_the_soft_limit() {
+    let clock = ManualClock::new();
+    let mut tm = TimeManager::new(&clock, TimeBudget { soft: ms(100), hard: ms(300) });
+    clock.set(ms(120));
+    assert!(!tm.can_start_iteration(ms(10), 2.0));
+    tm.stretch_soft(1.5);
+    assert_eq!(tm.soft_limit(), ms(150));
+    assert!(tm.can_start_iteration(ms(10), 2.0));
+    // Never past the hard limit, and never below the plain soft limit
+    tm.stretch_soft(10.0);
+    assert_eq!(tm.soft_limit(), ms(300));
+    tm.stretch_soft(0.2);
+    assert_eq!(tm.soft_limit(), ms(100));
+}
+
+#[test]
+fn calibration_predicts_iterations_from_nodes() {
+    let c = Calibration::measure(&piebot::search::options::EngineOptions::default(), ms(50));
+    assert!(c.nodes_per_ms >= 1.0, "{:?}", c);
+    assert!((1.5..=6.0).contains(&c.growth), "{:?}", c);
+    let cfg = TimeConfig::default().calibrated(&Calibration { nodes_per_ms: 100.0, growth: 3.0 });
+    assert_eq!(cfg.growth, 3.0);
+    // 5000 nodes at 100 nodes/ms cost 50ms whatever the noisy wall time said
+    assert_eq!(cfg.iteration_cost(5_000, ms(7)), ms(50));
+    assert_eq!(TimeConfig::default().iteration_cost(5_000, ms(7)), ms(7), "uncalibrated: measured time");
+}
+
+#[test]
+fn clock_plan_stops_between_iterations() {
+    use piebot::search::alphabeta::{SearchParams, Searcher};
+    let board = cozy_chess::Board::default();
+    let base = SearchParams { depth: 0, movetime: Some(ms(5_000)), use_tt: true, order_captures: true, threads: 1, ..Default::default() };
+    // Past the soft limit after the first iteration: no second one, long before the deadline
+    let budget = TimeBudget { soft: Duration::ZERO, hard: ms(5_000) };
+    let mut s = Searcher::default();
+    let r = s.search_with_params(&board, SearchParams { clock: Some(ClockPlan { budget, config: TimeConfig::default() }), ..base });
+    assert_eq!(r.depth, 1);
+    // A calibrated speed far below the real one predicts every next iteration to overrun
+    let slow = TimeConfig { nodes_per_ms: Some(0.001), ..TimeConfig::default() };
+    let budget = TimeBudget { soft: ms(5_000), hard: ms(5_000) };
+    let r = s.search_with_params(&board, SearchParams { clock: Some(ClockPlan { budget, config: slow }), ..base });
+    assert_eq!(r.depth, 1);
+}
+
+#[test]
+fn move_overhead_is_kept_back_from_the_clock() {
+    use piebot::search::options::EngineOptions;
+    let mut o = EngineOptions::default();
+    assert_eq!(o.time_config().move_overhead, TimeConfig::default().move_overhead);
+    o.set("Move Overhead", "250").unwrap();
+    assert_eq!(o.time_config().move_overhead, ms(250));
+    assert!(o.describe().ends_with(",move_overhead=250"));
+    let go = GoLimits { remaining: Some(ms(1_000)), ..GoLimits::default() };
+    assert!(allocate(&go, &o.time_config()).unwrap().hard <= ms(750));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_clock_plan_stops_between_iterations() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut s = PlecoSearcher::default();
+    s.set_threads(1);
+    let budget = TimeBudget { soft: Duration::ZERO, hard: ms(5_000) };
+    s.set_clock_plan(Some(ClockPlan { budget, config: TimeConfig::default() }));
+    let r = s.search_result(&mut pleco::Board::start_pos(), 5_000, 0);
+    assert_eq!(r.depth, 1);
+    let slow = TimeConfig { nodes_per_ms: Some(0.001), ..TimeConfig::default() };
+    s.set_clock_plan(Some(ClockPlan { budget: TimeBudget { soft: ms(5_000), hard: ms(5_000) }, config: slow }));
+    assert_eq!(s.search_result(&mut pleco::Board::start_pos(), 5_000, 0).depth, 1);
+    // Without a plan the movetime is a plain deadline
+    s.set_clock_plan(None);
+    assert_eq!(s.search_result(&mut pleco::Board::start_pos(), 5_000, 3).depth, 3);
+}
+
+#[test]
+fn best_move_effort_scales_the_soft_limit() {
+    use piebot::search::timeman::best_move_share;
+    let cfg = TimeConfig::default();
+    assert_eq!(best_move_share(&[("e2e4", 900u64), ("d2d4", 100)], &"e2e4"), Some(0.9));
+    assert_eq!(best_move_share::<&str>(&[], &"e2e4"), None);
+    // A clear choice shrinks the budget, spread effort grows it, within 0.5..1.5
+    assert!(cfg.effort_scale(0.9, 0.0) < 1.0 && cfg.effort_scale(0.2, 0.0) > 1.0);
+    assert_eq!(cfg.effort_scale(0.5, 0.0), 1.0);
+    assert_eq!(TimeConfig { effort: 10.0, ..cfg }.effort_scale(1.0, 0.0), 0.5);
+    assert_eq!(TimeConfig { effort: 10.0, ..cfg }.effort_scale(0.0, 0.0), 1.5);
+    // A best move that just changed never stops early
+    assert_eq!(cfg.effort_scale(0.9, 1.0), 1.0);
+    assert_eq!(TimeConfig { effort: 0.0, ..cfg }.effort_scale(0.9, 0.0), 1.0);
+
+    let clock = ManualClock::new();
+    let mut tm = TimeManager::new(&clock, TimeBudget { soft: ms(100), hard: ms(300) });
+    tm.scale_soft_by_effort(0.5);
+    assert_eq!(tm.soft_limit(), ms(50));
+    clock.set(ms(70));
+    assert!(!tm.can_start_iteration(ms(1), 2.0));
+    tm.stretch_soft(3.0);
+    assert_eq!(tm.soft_limit(), ms(150));
+    tm.scale_soft_by_effort(3.0);
+    assert_eq!(tm.soft_limit(), ms(300));
+}
+
+#[test]
+fn calibration_follows_the_configured_backend_and_eval() {
+    use piebot::search::options::{EngineOptions, EvalKind};
+    use piebot::uci::UciEngine;
+    let material = EngineOptions { eval: EvalKind::Material, ..EngineOptions::default() };
+    let c = Calibration::measure(&material, ms(30));
+    assert!(c.nodes_per_ms >= 1.0 && (1.5..=6.0).contains(&c.growth), "{:?}", c);
+    #[cfg(feature = "board-pleco")]
+    {
+        let c = Calibration::measure_pleco(&material, ms(30));
+        assert!(c.nodes_per_ms >= 1.0 && (1.5..=6.0).contains(&c.growth), "{:?}", c);
+    }
+    // The engine measures again at `isready` once the backend or eval has changed
+    let sentinel = Calibration { nodes_per_ms: 12_345.0, growth: 2.0 };
+    let mut e = UciEngine::new();
+    e.calibrate(ms(20));
+    sentinel.set_global();
+    e.handle_line("isready");
+    assert_eq!(Calibration::global(), Some(sentinel), "nothing changed");
+    e.handle_line("setoption name Eval value material");
+    e.handle_line("isready");
+    assert_ne!(Calibration::global(), Some(sentinel));
+    // Iterations of 100, 1000 and 3000 nodes: only the last ratio is past the startup noise
+    let c = Calibration::from_iterations(&[100, 1_100, 4_100], 10);
+    assert!(c.nodes_per_ms == 410.0 && (c.growth - 3.0).abs() < 1e-4, "{:?}", c);
+}
@@ -1,3 +1,4 @@
+use piebot::search::score::Score;
 use piebot::search::tt::{Tt, Entry, Bound};
 
 #[test]
@@ -6,17 +7,101 @@ fn aging_eviction_prefers_oldest_when_depth_equal() {
     // Single bucket with 4 ways
     tt.set_capacity_entries(4);
     // Insert 4 entries at same depth, bump gen to create age spread
-    tt.put(Entry { key: 1, depth: 5, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 1, depth: 5, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     tt.bump_generation();
-    tt.put(Entry { key: 2, depth: 5, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 2, depth: 5, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     tt.bump_generation();
-    tt.put(Entry { key: 3, depth: 5, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 3, depth: 5, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     tt.bump_generation();
-    tt.put(Entry { key: 4, depth: 5, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 4, depth: 5, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     // New entry at same depth should evict the oldest (key=1)
     tt.bump_generation();
-    tt.put(Entry { key: 99, depth: 5, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 99, depth: 5, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     assert!(tt.get(1).is_none(), "oldest entry not evicted at equal depth");
     assert!(tt.get(99).is_some(), "new entry not inserted");
 }
 
+
+fn entry(key: u64, depth: u32) -> Entry { Entry { key, depth, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 } }
+
+#[test]
+fn old_entries_are_evicted_before_fresh_deep_ones() {
+    let mut tt = Tt::new();
+    tt.set_capacity_entries(4);
+    // Two deep entries from a search two moves back
+    tt.put(entry(1, 14));
+    tt.put(entry(2, 12));
+    tt.bump_generation();
+    tt.bump_generation();
+    tt.put(entry(3, 10));
+    tt.put(entry(4, 9));
+    // Shallow stores in the current search replace the stale deep entries first
+    tt.put(entry(5, 1));
+    assert!(tt.get(2).is_none(), "the shallower old entry goes first");
+    tt.put(entry(6, 1));
+    assert!(tt.get(1).is_none(), "then the other old entry");
+    // With only fresh entries left, depth decides again
+    tt.put(entry(7, 2));
+    for key in [3, 4, 7] { assert!(tt.get(key).is_some(), "fresh entry {} evicted", key); }
+    assert_eq!(tt.get(5).is_some() as u8 + tt.get(6).is_some() as u8, 1);
+}
+
+#[test]
+fn a_stale_deeper_entry_is_kept_and_refreshed() {
+    let mut tt = Tt::new();
+    tt.set_capacity_entries(4);
+    tt.put(entry(1, 12));
+    tt.put(entry(1, 3));
+    assert_eq!(tt.get(1).unwrap().depth, 12, "same search keeps the deeper result");
+    tt.bump_generation();
+    tt.put(entry(1, 3));
+    // A later search keeps it too, and no longer counts it as stale
+    let e = tt.get(1).unwrap();
+    assert_eq!((e.depth, e.gen), (12, tt.generation()));
+}
+
+#[test]
+fn generation_advances_once_per_go() {
+    use cozy_chess::Board;
+    use piebot::search::alphabeta::{SearchParams, Searcher};
+    use piebot::search::alphabeta::AnalysisStyle;
+    let mut s = Searcher::default();
+    let (b, params) = (Board::default(), SearchParams { depth: 5, use_tt: true, ..Default::default() });
+    let g0 = s.tt().generation();
+    s.new_search();
+    assert_eq!(s.tt().generation(), g0 + 1);
+    s.search_with_params(&b, params);
+    assert_eq!(s.tt().generation(), g0 + 1, "the search and its iterations use new_search's generation");
+    // A searcher reused without new_search still ages its entries, once per search
+    s.search_with_params(&b, params);
+    assert_eq!(s.tt().generation(), g0 + 2);
+    s.search_multipv(&b, SearchParams { depth: 3, ..params }, 3, AnalysisStyle::Exclusion);
+    assert_eq!(s.tt().generation(), g0 + 3, "MultiPV lines share one");
+    s.score_root_moves(&b, SearchParams { depth: 3, ..params });
+    assert_eq!(s.tt().generation(), g0 + 4);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_generation_advances_once_per_go() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    use piebot::search::tt_pleco::{Bound as PBound, Entry as PEntry, TtPleco};
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(1);
+    let g0 = s.tt().generation();
+    s.new_search();
+    s.search_result(&mut pleco::Board::start_pos(), 60_000, 5);
+    assert_eq!(s.tt().generation(), g0 + 1);
+    s.search_result(&mut pleco::Board::start_pos(), 60_000, 5);
+    assert_eq!(s.tt().generation(), g0 + 2, "a search without new_search starts its own");
+
+    let mut tt = TtPleco::new();
+    tt.set_capacity_entries(4);
+    let e = |key, depth| PEntry { key, depth, score: Score::cp(0), best: None, bound: PBound::Exact, gen: 0 };
+    tt.put(e(1, 14));
+    tt.bump_generation();
+    tt.bump_generation();
+    for k in 2..=4 { tt.put(e(k, 8)); }
+    tt.put(e(5, 1));
+    assert!(tt.get(1).is_none(), "stale deep entry kept over fresh ones");
+}
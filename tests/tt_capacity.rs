@@ -1,3 +1,4 @@
+use piebot::search::score::Score;
 use piebot::search::tt::{Tt, Entry, Bound};
 
 #[test]
@@ -5,7 +6,7 @@ fn tt_capacity_enforced() {
     let mut tt = Tt::new();
     tt.set_capacity_entries(8);
     for i in 0..64u64 {
-        let e = Entry { key: i, depth: (i % 4) as u32, score: i as i32, best: None, bound: Bound::Exact, gen: 0 };
+        let e = Entry { key: i, depth: (i % 4) as u32, score: Score::from_value(i as i32), best: None, bound: Bound::Exact, gen: 0 };
         tt.put(e);
     }
     // Capacity enforced
@@ -17,10 +18,21 @@ fn tt_depth_preferred_on_eviction() {
     let mut tt = Tt::new();
     tt.set_capacity_entries(2);
     // Insert a deep entry we want to keep
-    tt.put(Entry { key: 1, depth: 6, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 1, depth: 6, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     // Fill capacity
-    tt.put(Entry { key: 2, depth: 1, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 2, depth: 1, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     // Trigger eviction with a shallow entry; deep one should remain
-    tt.put(Entry { key: 3, depth: 1, score: 0, best: None, bound: Bound::Exact, gen: 0 });
+    tt.put(Entry { key: 3, depth: 1, score: Score::cp(0), best: None, bound: Bound::Exact, gen: 0 });
     assert!(tt.get(1).is_some(), "deep entry evicted unexpectedly");
 }
+
+#[test]
+fn suggested_hash_holds_the_searched_nodes() {
+    use piebot::search::tt::{suggested_hash_mb, ENTRY_BYTES};
+    assert_eq!(suggested_hash_mb(0), 1);
+    // 1M nodes at 64 B each is 64 MB; one node more rounds up to the next power of two
+    let per_mb = (1 << 20) / ENTRY_BYTES as u64;
+    assert_eq!(suggested_hash_mb(64 * per_mb), 64);
+    assert_eq!(suggested_hash_mb(64 * per_mb + 1), 128);
+    assert_eq!(suggested_hash_mb(u64::MAX), 16384);
+}
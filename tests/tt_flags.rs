@@ -15,6 +15,18 @@ fn tt_exact_after_search() {
     assert!(e.0 >= 3, "expected stored depth >= 3, got {}", e.0);
 }
 
+#[test]
+fn tt_root_stored_after_aspirated_search() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    use piebot::search::tt::Bound;
+    let b = Board::default();
+    let mut s = Searcher::default();
+    let p = SearchParams { depth: 4, use_tt: true, order_captures: true, use_history: true, use_aspiration: true, aspiration_window_cp: 50, ..SearchParams::default() };
+    let res = s.search_with_params(&b, p);
+    let e = s.tt_probe(&b).expect("aspirated root not stored");
+    assert_eq!(e, (4, Bound::Exact), "score {:?}", res.score);
+}
+
 #[test]
 fn tt_depth_preferred_not_replaced() {
     use piebot::search::alphabeta::{Searcher, SearchParams};
@@ -29,3 +41,17 @@ fn tt_depth_preferred_not_replaced() {
     assert!(d2 >= d1, "shallower search should not lower stored TT depth");
 }
 
+
+#[test]
+fn tt_mate_scores_keep_their_distance() {
+    use piebot::search::alphabeta::{Searcher, SearchParams};
+    use piebot::search::eval::MATE_SCORE;
+    let p = SearchParams { depth: 4, use_tt: true, order_captures: true, ..SearchParams::default() };
+    let mut s = Searcher::default();
+    // Ra8# is stored as a mate in one ply from the root it was found at
+    let mate = Board::from_fen("6k1/8/6K1/8/8/8/8/R7 w - - 0 1", false).unwrap();
+    assert_eq!(s.search_with_params(&mate, p).score.value(), MATE_SCORE - 1);
+    // Found again a ply below the root after the forced Kg8, it is two plies away
+    let before = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1", false).unwrap();
+    assert_eq!(s.search_with_params(&before, p).score.value(), -MATE_SCORE + 2);
+}
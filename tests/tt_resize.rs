@@ -0,0 +1,64 @@
+// Changing Hash keeps the table's entries: grown tables keep all of them, shrunk
+// ones the deepest.
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, Searcher};
+use piebot::search::score::Score;
+use piebot::search::tt::{Bound, Entry, Tt};
+
+fn entry(key: u64, depth: u32) -> Entry { Entry { key, depth, score: Score::from_value(key as i32), best: None, bound: Bound::Exact, gen: 0 } }
+
+#[test]
+fn resize_rehashes_entries() {
+    let mut tt = Tt::new();
+    tt.set_capacity_mb(1);
+    for k in 1..=1000 { tt.put(entry(k * 7919, (k % 20) as u32)); }
+    tt.bump_generation();
+    let grown = tt.resized_mb(4);
+    assert_eq!((grown.capacity(), grown.len(), grown.generation()), (4 * tt.capacity(), 1000, tt.generation()));
+    let e = grown.get(7919 * 5).unwrap();
+    assert_eq!((e.depth, e.score, e.gen), (5, Score::from_value(7919 * 5), 0), "entries keep their generation");
+
+    // One bucket: the four deepest survive
+    let mut small = Tt::new();
+    small.set_capacity_entries(16);
+    for (k, d) in [(1, 3), (2, 9), (3, 1), (4, 12), (5, 7), (6, 2), (7, 10)] { small.put(entry(k, d)); }
+    let shrunk = small.resized_mb(0);
+    assert_eq!(shrunk.capacity(), 4);
+    let mut kept: Vec<u64> = (1..=7).filter(|&k| shrunk.get(k).is_some()).collect();
+    kept.sort();
+    assert_eq!(kept, [2, 4, 5, 7]);
+}
+
+#[test]
+fn searcher_keeps_its_work_across_hash_changes() {
+    let b = Board::default();
+    let mut s = Searcher::default();
+    s.set_tt_capacity_mb(1);
+    s.search_with_params(&b, SearchParams { depth: 4, use_tt: true, ..Default::default() });
+    let before = s.tt_probe(&b).unwrap();
+    s.set_tt_capacity_mb(8);
+    assert_eq!((s.tt_probe(&b), s.tt().capacity()), (Some(before), 8 << 14));
+    // The same size leaves the table alone
+    let filled = s.tt().len();
+    s.set_tt_capacity_mb(8);
+    assert_eq!(s.tt().len(), filled);
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_searcher_keeps_its_work_across_hash_changes() {
+    use piebot::search::alphabeta_pleco::PlecoSearcher;
+    let mut b = pleco::Board::start_pos();
+    let mut s = PlecoSearcher::default();
+    s.set_tt_capacity_mb(1);
+    s.search_result(&mut b, 0, 4);
+    // The root's children as the table holds them
+    let children = |s: &PlecoSearcher| b.generate_moves().iter().map(|&m| {
+        let mut c = b.clone(); c.apply_move(m);
+        s.tt().get(c.zobrist()).map(|e| (e.depth, e.best))
+    }).collect::<Vec<_>>();
+    let before = children(&s);
+    assert!(before.iter().any(Option::is_some));
+    s.set_tt_capacity_mb(8);
+    assert_eq!(children(&s), before);
+}
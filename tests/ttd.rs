@@ -0,0 +1,54 @@
+use cozy_chess::Board;
+use piebot::search::alphabeta::{SearchParams, SearchResult, Searcher};
+use piebot::search::ttd::{DepthCurve, TtdSummary};
+
+fn iter(depth: u32, nodes: u64, time_ms: u64) -> SearchResult {
+    SearchResult { depth, nodes, time_ms, ..Default::default() }
+}
+
+fn curve(points: &[(u32, u64, u64)]) -> DepthCurve {
+    let mut c = DepthCurve::default();
+    for &(d, n, t) in points { c.push(&iter(d, n, t)); }
+    c
+}
+
+#[test]
+fn ebf_is_the_geometric_mean_from_depth_two() {
+    let c = curve(&[(1, 20, 0), (2, 100, 1), (3, 400, 3), (4, 1600, 9)]);
+    assert_eq!(c.step_ebf(1), None, "depth 1 is left out");
+    assert_eq!(c.step_ebf(2), Some(4.0));
+    assert!((c.ebf().unwrap() - 4.0).abs() < 1e-9);
+    assert_eq!((c.time_to(3), c.time_to(5), c.depth()), (Some(3), None, 4));
+    assert_eq!(curve(&[(1, 20, 0), (2, 100, 1)]).ebf(), None, "needs two iterations past depth 1");
+}
+
+#[test]
+fn repeated_depths_replace_the_tail() {
+    let c = curve(&[(1, 20, 0), (2, 100, 1), (3, 400, 3), (3, 500, 4)]);
+    assert_eq!(c.points.len(), 3);
+    assert_eq!(c.points[2].nodes, 500);
+}
+
+#[test]
+fn summary_averages_over_positions() {
+    let a = curve(&[(1, 10, 1), (2, 100, 2), (3, 400, 4)]);
+    let b = curve(&[(1, 10, 3), (2, 100, 4), (3, 900, 8), (4, 8100, 20)]);
+    let s = TtdSummary::from_curves(&[a, b]);
+    assert_eq!((s.positions, s.common_depth), (2, 3));
+    assert_eq!(s.mean_time_ms, vec![2.0, 3.0, 6.0]);
+    assert!((s.ebf.unwrap() - 6.0).abs() < 1e-9, "geometric mean of 4 and 9");
+    assert_eq!(TtdSummary::from_curves(&[]).ebf, None);
+}
+
+#[test]
+fn recorder_hook_sees_every_iteration() {
+    let mut s = Searcher::default();
+    let (curve, hook) = DepthCurve::recorder();
+    s.set_info_hook(Some(hook));
+    let p = SearchParams { depth: 5, use_tt: true, order_captures: true, use_history: true, use_killers: true, use_lmr: true, use_nullmove: true, ..SearchParams::default() };
+    let r = s.search_with_params(&Board::default(), p);
+    let c = curve.lock().unwrap().clone();
+    assert_eq!(c.points.iter().map(|p| p.depth).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(c.points.last().unwrap().nodes, r.nodes);
+    assert!(c.ebf().is_some_and(|e| e > 1.0));
+}
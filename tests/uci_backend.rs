@@ -0,0 +1,22 @@
+use piebot::uci::{Backend, UciEngine};
+
+#[test]
+fn backend_parses_and_switches_via_setoption() {
+    assert_eq!("cozy".parse::<Backend>(), Ok(Backend::Cozy));
+    assert!("stockfish".parse::<Backend>().is_err());
+    let mut e = UciEngine::new();
+    assert_eq!(e.backend(), Backend::default());
+    assert!(e.handle_line("setoption name Backend value cozy"));
+    assert_eq!(e.backend(), Backend::Cozy);
+    assert!(e.handle_line("isready"));
+    assert!(!e.handle_line("quit"));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_backend_available_with_feature() {
+    let mut e = UciEngine::new();
+    e.set_backend(Backend::Cozy);
+    assert!(e.handle_line("setoption name Backend value pleco"));
+    assert_eq!(e.backend(), Backend::Pleco);
+}
@@ -0,0 +1,34 @@
+use piebot::search::score::Score;
+use piebot::uci::InfoThrottle;
+use std::time::{Duration, Instant};
+
+#[test]
+fn info_throttle_applies_min_depth_and_rate() {
+    let t0 = Instant::now();
+    let mut t = InfoThrottle::new(3, 10);
+    assert!(!t.admit(2, Score::cp(10), t0), "below min depth");
+    assert!(t.admit(3, Score::cp(10), t0));
+    assert!(!t.admit(4, Score::cp(12), t0 + Duration::from_millis(50)), "within 100ms of the last line");
+    assert!(t.admit(5, Score::cp(15), t0 + Duration::from_millis(120)));
+    assert!(!t.admit_final(5, Score::cp(15)), "final line would repeat the last one");
+    assert!(t.admit_final(6, Score::cp(20)));
+    assert!(!t.admit_final(2, Score::cp(20)), "final line below min depth");
+    assert!(t.admit_final(0, Score::mated_in(0)), "a terminal root is still reported");
+
+    let mut unlimited = InfoThrottle::new(1, 0);
+    assert!(unlimited.admit(1, Score::cp(0), t0) && unlimited.admit(2, Score::cp(0), t0));
+}
+
+#[test]
+fn cozy_searcher_reports_each_iteration() {
+    use piebot::search::alphabeta::{InfoHook, SearchParams, Searcher};
+    use std::sync::{Arc, Mutex};
+    let depths = Arc::new(Mutex::new(Vec::new()));
+    let d = depths.clone();
+    let hook: InfoHook = Arc::new(move |r| d.lock().unwrap().push(r.depth));
+    let mut s = Searcher::default();
+    s.set_info_hook(Some(hook));
+    let res = s.search_with_params(&cozy_chess::Board::default(), SearchParams { depth: 4, ..Default::default() });
+    assert_eq!(*depths.lock().unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(res.depth, 4);
+}
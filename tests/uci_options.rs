@@ -0,0 +1,94 @@
+use piebot::search::options::{EngineOptions, UCI_GROUPS};
+use piebot::uci::{options_table, Backend, UciEngine};
+use std::collections::HashSet;
+
+fn kind_of(e: &UciEngine, name: &str) -> String {
+    e.uci_options().into_iter().find(|o| o.name == name).unwrap_or_else(|| panic!("no option {}", name)).kind
+}
+
+#[test]
+fn option_list_is_grouped_and_unique() {
+    for backend in [Backend::Cozy, Backend::default()] {
+        let mut e = UciEngine::new();
+        e.set_backend(backend);
+        let opts = e.uci_options();
+        let names: HashSet<_> = opts.iter().map(|o| o.name.to_lowercase()).collect();
+        assert_eq!(names.len(), opts.len(), "duplicate option names");
+        assert!(opts.windows(2).all(|w| w[0].group_rank() <= w[1].group_rank()));
+        assert!(opts.iter().all(|o| UCI_GROUPS.contains(&o.group) && !o.help.is_empty()));
+        for name in ["Threads", "Hash", "Backend", "Split", "SplitDepth", "SplitMoves", "Deterministic", "KingDanger", "Contempt"] {
+            assert!(names.contains(&name.to_lowercase()), "{:?} missing {}", backend, name);
+        }
+        let table = options_table(&opts);
+        assert!(table.starts_with("Engine\n"));
+        assert!(table.contains("\nParallel\n"));
+    }
+}
+
+#[test]
+fn set_values_survive_ucinewgame() {
+    let mut e = UciEngine::new();
+    e.set_backend(Backend::Cozy);
+    for cmd in ["Contempt value 25", "SplitDepth value 5", "Split value jamboree", "MultiPV value 3", "Skill Level value 12",
+                "UCI_ShowWDL value true", "EvalBlend value 60", "lmr value false", "Hash value 32"] {
+        assert!(e.handle_line(&format!("setoption name {}", cmd)));
+    }
+    assert!(e.handle_line("ucinewgame"));
+    assert!(e.handle_line("isready"));
+    assert_eq!(kind_of(&e, "Contempt"), "spin default 25 min -200 max 200");
+    assert_eq!(kind_of(&e, "SplitDepth"), "spin default 5 min 1 max 64");
+    assert!(kind_of(&e, "Split").starts_with("combo default jamboree"));
+    assert!(kind_of(&e, "MultiPV").starts_with("spin default 3 "));
+    assert!(kind_of(&e, "Skill Level").starts_with("spin default 12 "));
+    assert_eq!(kind_of(&e, "UCI_ShowWDL"), "check default true");
+    assert!(kind_of(&e, "EvalBlend").starts_with("spin default 60 "));
+    assert_eq!(kind_of(&e, "lmr"), "check default false");
+    assert!(kind_of(&e, "Hash").starts_with("spin default 32 "));
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_values_survive_ucinewgame() {
+    let mut e = UciEngine::new();
+    e.set_backend(Backend::Pleco);
+    assert!(e.handle_line("setoption name SplitMoves value 9"));
+    assert!(e.handle_line("setoption name Threads value 3"));
+    assert!(e.handle_line("ucinewgame"));
+    assert!(kind_of(&e, "SplitMoves").starts_with("spin default 9 "));
+    assert!(kind_of(&e, "Threads").starts_with("spin default 3 "));
+    assert!(kind_of(&e, "Backend").starts_with("combo default pleco"));
+}
+
+#[test]
+fn split_keys_accept_uci_names() {
+    let mut o = EngineOptions::default();
+    o.apply_spec("splitdepth=7,splitmoves=4").unwrap();
+    assert_eq!((o.split_depth, o.split_moves), (7, 4));
+}
+
+#[test]
+fn backends_share_the_hash_range_and_cozy_keeps_its_search_defaults() {
+    let mut e = UciEngine::new();
+    e.set_backend(Backend::Cozy);
+    assert!(kind_of(&e, "Hash").ends_with(" min 1 max 16384"));
+    for name in ["killers", "lmr", "nullmove", "aspiration"] { assert_eq!(kind_of(&e, name), "check default false", "{}", name); }
+    #[cfg(feature = "board-pleco")]
+    {
+        e.set_backend(Backend::Pleco);
+        assert!(kind_of(&e, "Hash").ends_with(" min 1 max 16384"));
+        assert!(e.handle_line("setoption name Hash value 8192"));
+        assert!(kind_of(&e, "Hash").starts_with("spin default 8192 "));
+        assert_eq!(kind_of(&e, "lmr"), "check default true");
+    }
+}
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pleco_eval_option_switches_the_search_eval() {
+    let mut e = UciEngine::new();
+    e.set_backend(Backend::Pleco);
+    assert!(e.handle_line("setoption name eval value nnue"));
+    assert_eq!(kind_of(&e, "UseNNUE"), "check default true");
+    assert!(e.handle_line("setoption name eval value material"));
+    assert_eq!(kind_of(&e, "UseNNUE"), "check default false");
+}
@@ -0,0 +1,271 @@
+// Drives the `uci` binary over pipes the way a GUI does and checks what comes back.
+mod common;
+
+use common::Captured;
+use piebot::search::timeman::GoLimits;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+struct Session { child: Child, stdin: ChildStdin, lines: Receiver<String> }
+
+impl Session {
+    fn start(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_uci")).args(args)
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (tx, lines) = mpsc::channel();
+        std::thread::spawn(move || for line in stdout.lines().map_while(Result::ok) { if tx.send(line).is_err() { break; } });
+        Self { child, stdin, lines }
+    }
+
+    fn send(&mut self, cmd: &str) { writeln!(self.stdin, "{}", cmd).unwrap(); self.stdin.flush().unwrap(); }
+
+    // Lines up to and including the first one starting with `prefix`
+    fn until(&mut self, prefix: &str, timeout: Duration) -> Vec<String> {
+        let deadline = Instant::now() + timeout;
+        let mut out = Vec::new();
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let line = self.lines.recv_timeout(left).unwrap_or_else(|_| panic!("no '{}' within {:?}, got {:?}", prefix, timeout, out));
+            let done = line.starts_with(prefix);
+            out.push(line);
+            if done { return out; }
+        }
+    }
+
+    fn quit(mut self) {
+        self.send("quit");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(status) = self.child.try_wait().unwrap() { assert!(status.success()); return; }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        panic!("engine did not exit on quit");
+    }
+}
+
+fn gui_session(args: &[&str]) {
+    let mut s = Session::start(args);
+    let t = Duration::from_secs(10);
+    s.send("uci");
+    let hello = s.until("uciok", t);
+    assert!(hello[0].starts_with("id name"), "{:?}", hello);
+    assert!(hello.iter().any(|l| l.starts_with("option name Hash")));
+    s.send("isready");
+    assert_eq!(s.until("readyok", t), ["readyok"]);
+
+    s.send("ucinewgame");
+    s.send("position startpos moves e2e4 e7e5");
+    s.send("go wtime 3000 btime 3000 winc 0 binc 0");
+    let t0 = Instant::now();
+    let out = s.until("bestmove", t);
+    let spent = t0.elapsed();
+    let (best, info) = out.split_last().unwrap();
+    assert!(info.iter().all(|l| l.starts_with("info")), "only info before bestmove: {:?}", out);
+    assert!(!info.is_empty(), "at least the final info line");
+    let mv = best.split_whitespace().nth(1).unwrap();
+    assert!(mv.len() >= 4 && mv != "0000", "{}", best);
+    // About a thirtieth of the clock, with plenty of room for a debug build
+    assert!(spent < Duration::from_millis(2000), "took {:?} on a 3s clock", spent);
+
+    // Black to move now reads btime: a nearly flagged clock still yields a move quickly
+    s.send(&format!("position startpos moves e2e4 e7e5 {}", mv));
+    s.send("go wtime 60000 btime 50 winc 0 binc 0");
+    let t0 = Instant::now();
+    assert!(s.until("bestmove", t).last().unwrap().starts_with("bestmove "));
+    assert!(t0.elapsed() < Duration::from_millis(1000), "took {:?} with 50ms left", t0.elapsed());
+
+    // Nothing is running: stop and ponderhit are no-ops and bring no second bestmove
+    s.send("stop");
+    s.send("ponderhit");
+    s.send("isready");
+    assert_eq!(s.until("readyok", t), ["readyok"]);
+    s.quit();
+}
+
+#[test]
+fn gui_session_with_clock_cozy() { gui_session(&["--backend", "cozy"]); }
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn gui_session_with_clock_pleco() { gui_session(&["--backend", "pleco"]); }
+
+#[test]
+fn go_limits_read_the_side_to_move_clock() {
+    let ms = Duration::from_millis;
+    let args = "wtime 60000 btime 30000 winc 1000 binc 500 movestogo 20";
+    let w = GoLimits::from_uci(args, true);
+    assert_eq!((w.remaining, w.increment, w.moves_to_go, w.movetime), (Some(ms(60000)), ms(1000), Some(20), None));
+    let b = GoLimits::from_uci(args, false);
+    assert_eq!((b.remaining, b.increment), (Some(ms(30000)), ms(500)));
+    // Flagged GUIs send negative times; searchmoves and depth are not clock tokens
+    assert_eq!(GoLimits::from_uci("depth 5 btime -20 searchmoves e2e4", false).remaining, Some(Duration::ZERO));
+    assert_eq!(GoLimits::from_uci("movetime 250", true).movetime, Some(ms(250)));
+    assert!(GoLimits::from_uci("infinite", true).remaining.is_none());
+}
+
+#[test]
+fn run_loop_on_in_memory_streams() {
+    use piebot::uci::UciEngine;
+    let out = Captured::default();
+    let mut e = UciEngine::new();
+    // Anything after quit is never read, so the deep search at the end would hang the test
+    e.run_loop("uci\n\n  isready\nposition startpos moves e2e4\ngo depth 2\nquit\ngo depth 99\n".as_bytes(), out.clone());
+    let lines = out.lines();
+    assert!(lines[0].starts_with("id name"), "{:?}", lines);
+    let ok = lines.iter().position(|l| l == "uciok").unwrap();
+    assert_eq!(lines[ok + 1], "readyok");
+    let (best, info) = lines[ok + 2..].split_last().unwrap();
+    assert!(!info.is_empty() && info.iter().all(|l| l.starts_with("info depth")), "{:?}", lines);
+    assert!(best.starts_with("bestmove ") && best != "bestmove 0000", "{}", best);
+
+    // End of input without quit also returns
+    let out = Captured::default();
+    e.run_loop("isready\n".as_bytes(), out.clone());
+    assert_eq!(out.lines(), ["readyok"]);
+}
+
+#[test]
+fn info_lines_report_root_bestmove_changes() {
+    use piebot::uci::UciEngine;
+    let out = Captured::default();
+    let mut e = UciEngine::new();
+    // Depth 1 takes on b7; depth 3 sees Rd1# in reply and changes its mind
+    let fen = "3r2k1/1p3ppp/8/8/8/1Q6/P4PPP/6K1 w - - 0 1";
+    e.run_loop(format!("setoption name Backend value cozy\nposition fen {}\ngo depth 3\nquit\n", fen).as_bytes(), out.clone());
+    let lines = out.lines();
+    let info: Vec<&String> = lines.iter().filter(|l| l.starts_with("info depth")).collect();
+    assert_eq!(info.len(), 3, "{:?}", lines);
+    assert!(!info[0].contains("string"), "{:?}", lines);
+    assert!(info[2].ends_with(" string bmchanges 1"), "{:?}", lines);
+    assert_ne!(lines.last().unwrap(), "bestmove b3b7");
+}
+
+#[test]
+fn d_prints_board_key_eval_tt_and_options() {
+    use piebot::uci::UciEngine;
+    let backends: &[&str] = if cfg!(feature = "board-pleco") { &["cozy", "pleco"] } else { &["cozy"] };
+    for backend in backends {
+        let out = Captured::default();
+        let mut e = UciEngine::new();
+        e.run_loop(format!("setoption name Backend value {}\nposition startpos moves e2e4\nd\ngo depth 3\nd\nquit\n", backend).as_bytes(), out.clone());
+        let lines = out.lines();
+        let fens: Vec<&String> = lines.iter().filter(|l| l.starts_with("Fen: ")).collect();
+        assert_eq!(fens.len(), 2, "{:?}", lines);
+        assert!(fens[0].starts_with("Fen: rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq"), "{:?}", fens);
+        assert!(lines.contains(&" | r | n | b | q | k | b | n | r | 8".to_string()), "{:?}", lines);
+        assert!(lines.contains(&"   a   b   c   d   e   f   g   h".to_string()));
+        let keys: Vec<&String> = lines.iter().filter(|l| l.starts_with("Key: ")).collect();
+        assert_eq!((keys.len(), keys[0] == keys[1]), (2, true));
+        assert!(lines.iter().any(|l| l.starts_with("Classical (white): material 0 ")), "{:?}", lines);
+        // The table is empty before the search; cozy stores the root afterwards
+        let tt: Vec<&String> = lines.iter().filter(|l| l.starts_with("TT: ")).collect();
+        assert_eq!((tt.len(), tt[0].as_str()), (2, "TT: none"));
+        if *backend == "cozy" { assert!(tt[1].starts_with("TT: depth 3 bound Exact "), "{:?}", tt); }
+        assert!(lines.iter().any(|l| l.starts_with("Options: depth=")), "{:?}", lines);
+    }
+}
+
+// Sink that takes `delay` per write, like a GUI that reads slowly
+#[derive(Clone, Default)]
+struct Slow(Captured, Duration);
+
+impl Write for Slow {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { std::thread::sleep(self.1); self.0.write(buf) }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+#[test]
+fn slow_reader_never_blocks_info_and_keeps_bestmove() {
+    use piebot::uci::UciOut;
+    let sink = Captured::default();
+    let out = UciOut::new(Slow(sink.clone(), Duration::from_millis(5)));
+    let t0 = Instant::now();
+    for d in 1..=500 { out.info(format!("info depth {}", d)); }
+    assert!(t0.elapsed() < Duration::from_millis(500), "info blocked for {:?}", t0.elapsed());
+    out.line("bestmove e2e4");
+    out.flush();
+    let lines = sink.lines();
+    assert!(lines.len() < 500, "excess info lines were merged");
+    // The newest info survives and bestmove comes last; kept lines stay in order
+    assert_eq!(&lines[lines.len() - 2..], ["info depth 500", "bestmove e2e4"]);
+    let depths: Vec<u32> = lines[..lines.len() - 1].iter().map(|l| l["info depth ".len()..].parse().unwrap()).collect();
+    assert!(depths.windows(2).all(|w| w[0] < w[1]), "{:?}", depths);
+}
+
+#[test]
+fn clones_share_one_writer_started_on_first_use() {
+    use piebot::uci::UciOut;
+    let sink = Captured::default();
+    let out = UciOut::new(sink.clone());
+    // Nothing printed yet: flushing has nothing to wait for
+    out.flush();
+    let other = out.clone();
+    other.line("id name piebot");
+    out.line("uciok");
+    other.flush();
+    assert_eq!(sink.lines(), ["id name piebot", "uciok"]);
+}
+
+fn pondering(args: &[&str]) {
+    let mut s = Session::start(args);
+    let t = Duration::from_secs(10);
+    let no_bestmove = |s: &Session| s.lines.try_iter().all(|l| !l.starts_with("bestmove"));
+    s.send("setoption name Ponder value true");
+    s.send("position startpos");
+    s.send("go infinite");
+    // Answered mid-search; only stop brings the bestmove
+    s.send("isready");
+    assert!(s.until("readyok", t).iter().all(|l| l.starts_with("info") || l == "readyok"));
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(no_bestmove(&s));
+    s.send("stop");
+    assert!(s.until("bestmove", t).last().unwrap().starts_with("bestmove "));
+
+    // Behind a running search, isready waits for the commands queued before it
+    s.send("go infinite");
+    s.send("setoption name Hash value 2");
+    s.send("isready");
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(s.lines.try_iter().all(|l| l.starts_with("info")), "readyok before the queued setoption");
+    s.send("stop");
+    let out = s.until("readyok", t);
+    assert!(out.iter().any(|l| l.starts_with("bestmove ")), "{:?}", out);
+
+    // Pondering ignores the clock until ponderhit, then thinks within its budget
+    s.send("position startpos moves e2e4 e7e5");
+    s.send("go ponder wtime 2000 btime 2000");
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(no_bestmove(&s));
+    s.send("ponderhit");
+    let t0 = Instant::now();
+    let best = s.until("bestmove", t).pop().unwrap();
+    assert!(t0.elapsed() < Duration::from_millis(1500), "took {:?} after ponderhit", t0.elapsed());
+    let tokens: Vec<&str> = best.split_whitespace().collect();
+    assert_eq!((tokens.len(), tokens[2]), (4, "ponder"), "{}", best);
+
+    // A ponder miss: stop, and the GUI starts the real search
+    s.send("go ponder wtime 2000 btime 2000");
+    s.send("stop");
+    assert!(s.until("bestmove", t).last().unwrap().starts_with("bestmove "));
+    // The same, sent back to back: the stop ends the ponder search, not the new one
+    s.send("go ponder wtime 2000 btime 2000");
+    std::thread::sleep(Duration::from_millis(100));
+    for cmd in ["stop", "position startpos moves e2e4 e7e5 g1f3", "go depth 3"] { s.send(cmd); }
+    s.until("bestmove", t);
+    assert!(s.until("bestmove", t).iter().any(|l| l.starts_with("info depth 3 ")));
+    // Quitting releases a search that waits for stop
+    s.send("go infinite");
+    s.quit();
+}
+
+#[test]
+fn pondering_cozy() { pondering(&["--backend", "cozy", "--no-calibrate"]); }
+
+#[cfg(feature = "board-pleco")]
+#[test]
+fn pondering_pleco() { pondering(&["--backend", "pleco", "--no-calibrate"]); }
@@ -0,0 +1,56 @@
+#![cfg(feature = "board-pleco")]
+use cozy_chess::Board;
+use piebot::board::verify::{standard_uci, Check, Verifier};
+use piebot::io::fen::BENCH_SUITE;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+// Checks where the backends are expected to agree exactly
+const EXACT: [Check; 5] = [Check::Moves, Check::Fen, Check::Material, Check::KingAttack, Check::Features];
+
+fn exact_mismatches(v: &Verifier) -> Vec<String> {
+    v.mismatches().iter().filter(|m| EXACT.contains(&m.check)).map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn castling_is_written_the_standard_way() {
+    let b = Board::from_fen(KIWIPETE, false).unwrap();
+    let mut castles = Vec::new();
+    b.generate_moves(|ml| { castles.extend(ml.into_iter().map(|m| standard_uci(&b, m)).filter(|u| u.starts_with("e1g1") || u.starts_with("e1c1"))); false });
+    castles.sort();
+    assert_eq!(castles, ["e1c1", "e1g1"]);
+}
+
+#[test]
+fn perft_trees_agree() {
+    let mut v = Verifier::new();
+    assert_eq!(v.perft(BENCH_SUITE[0], 3).unwrap(), 8902);
+    assert_eq!(v.perft(KIWIPETE, 2).unwrap(), 2039);
+    assert_eq!(v.perft("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3).unwrap(), 2812);
+    assert_eq!(exact_mismatches(&v), Vec::<String>::new());
+}
+
+#[test]
+fn random_games_agree_and_are_reproducible() {
+    let run = || {
+        let mut v = Verifier::new();
+        for (i, fen) in BENCH_SUITE.iter().enumerate() { v.random_games(fen, i as u64, 8, 60).unwrap(); }
+        v
+    };
+    let (a, b) = (run(), run());
+    assert!(a.positions() > 1000);
+    assert_eq!(exact_mismatches(&a), Vec::<String>::new());
+    assert_eq!((a.positions(), a.counts()), (b.positions(), b.counts()));
+}
+
+#[test]
+fn mismatches_carry_the_position() {
+    // Pawnless rook against bishop: pleco's material eval scales drawish endings down
+    let fen = "8/8/8/4k3/8/8/8/R3K2b w - - 0 1";
+    let mut v = Verifier::new();
+    v.perft(fen, 0).unwrap();
+    let eval = v.mismatches().iter().find(|m| m.check == Check::Eval).expect("eval drift");
+    assert_eq!(eval.fen, fen);
+    assert!(eval.to_string().starts_with("eval: material: cozy 170"), "{}", eval);
+    assert!(v.perft("not a fen", 1).is_err());
+}
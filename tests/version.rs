@@ -0,0 +1,29 @@
+mod common;
+
+use common::quant_net;
+use piebot::eval::nnue::loader::QuantNnue;
+use piebot::version;
+
+fn tiny(b2: i16) -> QuantNnue { quant_net(4, 2, vec![1; 8], vec![1; 2], b2) }
+
+#[test]
+fn build_identity_names_version_commit_and_features() {
+    let id = version::id_name("Cozy");
+    assert!(id.starts_with(&format!("PieBot {} (Cozy) ", version::VERSION)), "{}", id);
+    assert!(!version::GIT_HASH.is_empty());
+    let line = version::build_line();
+    assert!(line.starts_with("info string build version "));
+    for field in [" commit ", " date ", " features nnue", " simd ", " profile "] {
+        assert!(line.contains(field), "missing{} in {}", field, line);
+    }
+    assert_eq!(version::features().contains(&"pleco"), cfg!(feature = "board-pleco"));
+}
+
+#[test]
+fn network_fingerprint_tracks_weights() {
+    assert_eq!(tiny(5).fingerprint(), tiny(5).clone().fingerprint());
+    assert_ne!(tiny(5).fingerprint(), tiny(6).fingerprint());
+    let line = version::network_line(Some(tiny(5).fingerprint()));
+    assert_eq!(line.len(), "info string network ".len() + 16);
+    assert_eq!(version::network_line(None), "info string network none");
+}